@@ -5,10 +5,56 @@
 // Max number of pages to pull from the remote.
 pub const REST_API_MAX_PAGES: u32 = 10;
 
+// Largest page size accepted by both Github and Gitlab.
+pub const REST_API_MAX_PER_PAGE: u32 = 100;
+
+// Max number of list pages requested at the same time once the number of pages
+// is known. Bounded further by the remaining requests in the rate limit.
+pub const MAX_CONCURRENT_PAGE_REQUESTS: u32 = 8;
+
+// Max number of projects looked up at the same time when auditing a group.
+pub const MAX_CONCURRENT_PROJECT_REQUESTS: usize = 8;
+
+// Max number of steps run at the same time when gathering the data to open a
+// merge request.
+pub const MAX_CONCURRENT_STEPS: usize = 3;
+
+// Gitlab forks a template in the background. Its import status is checked
+// every interval until done, up to the max number of checks.
+pub const GITLAB_IMPORT_POLL_INTERVAL_SECONDS: u64 = 2;
+pub const GITLAB_IMPORT_MAX_POLLS: u32 = 150;
+
 // Number of requests remaining threshold. If we reach, we stop for precaution
 // before we reach 0.
 pub const RATE_LIMIT_REMAINING_THRESHOLD: u32 = 10;
 
+// Retries of GET requests failing with a 5xx, a 429 or a network error. The
+// wait doubles from the base on every retry, up to the max.
+pub const RETRY_MAX_RETRIES: u32 = 3;
+pub const RETRY_BACKOFF_BASE_SECONDS: u64 = 1;
+pub const RETRY_MAX_BACKOFF_SECONDS: u64 = 60;
+
 // most limiting Github 5000/60 = 83.33 requests per minute. Round
 // up to 80.
 pub const DEFAULT_NUMBER_REQUESTS_MINUTE: u32 = 80;
+
+// Warn after commands when the API token expires within this number of days.
+pub const TOKEN_EXPIRATION_WARNING_DAYS: u32 = 7;
+
+// Sent as User-Agent in all the requests. A suffix can be appended from the
+// configuration.
+pub const USER_AGENT: &str = concat!("gitar/", env!("CARGO_PKG_VERSION"));
+
+pub const GITLAB_API_PATH: &str = "api/v4";
+
+// Longest merge request title and description, in characters, accepted when
+// opening one. Github rejects longer ones with a 422, Gitlab with a 400.
+pub const GITLAB_MERGE_REQUEST_TITLE_MAX_CHARS: usize = 255;
+pub const GITLAB_MERGE_REQUEST_DESCRIPTION_MAX_CHARS: usize = 1_048_576;
+pub const GITHUB_PULL_REQUEST_TITLE_MAX_CHARS: usize = 256;
+pub const GITHUB_PULL_REQUEST_BODY_MAX_CHARS: usize = 65_536;
+// Gitea stores titles in a 255 characters column. Descriptions are not
+// limited by the API, keep them to Github's limit.
+pub const GITEA_PULL_REQUEST_TITLE_MAX_CHARS: usize = 255;
+pub const GITEA_PULL_REQUEST_BODY_MAX_CHARS: usize = 65_536;
+pub const GITHUB_API_VERSION: &str = "2022-11-28";
@@ -1,17 +1,45 @@
 use std::fmt::Display;
 
+#[cfg(feature = "admin")]
+use crate::cmds::admin::{
+    InstanceProject, InstanceProjectListBodyArgs, InstanceUser, InstanceUserListBodyArgs,
+};
+
 use crate::{
+    audit::{AuditEntry, Reversal},
     cli::browse::BrowseOptions,
     cmds::{
-        cicd::{Pipeline, PipelineBodyArgs, Runner, RunnerListBodyArgs, RunnerMetadata},
+        auth::TokenMetadata,
+        cicd::{
+            Artifact, ArtifactListBodyArgs, Job, JobRetryBodyArgs, LintBodyArgs, LintResponse,
+            Pipeline, PipelineBodyArgs, PipelineJobNode, Runner, RunnerListBodyArgs,
+            RunnerMetadata,
+        },
         docker::{DockerListBodyArgs, ImageMetadata, RegistryRepository, RepositoryTag},
-        merge_request::CommentMergeRequestBodyArgs,
-        project::ProjectListBodyArgs,
-        release::{Release, ReleaseBodyArgs},
+        environment::{
+            DeploymentApprovalListBodyArgs, DeploymentApproveBodyArgs, Environment,
+            EnvironmentListBodyArgs, PendingDeployment,
+        },
+        issue::{IssueBodyArgs, IssueListBodyArgs, IssueResponse},
+        merge_request::{
+            ApprovalStatus, CommentMergeRequestBodyArgs, CommentThread, MergeQueueEntry,
+            MergeQueueListBodyArgs, MergeRequestCheck, RebaseProgress, ReviewedHead,
+        },
+        my::dashboard::FailingPipeline,
+        project::{
+            AccessRequest, AccessRequestApproveBodyArgs, AccessRequestListBodyArgs,
+            ApprovalSettings, ApprovalSettingsBodyArgs, AuditedProject, BranchProtectionBodyArgs,
+            Commit, CommitListBodyArgs, CommitPickBodyArgs, MemberListBodyArgs, NewProject,
+            ProjectListBodyArgs, ProjectTemplateBodyArgs, ProjectVariable, ProjectVariableBodyArgs,
+            ProtectedBranch, PushRules, PushRulesBodyArgs, Ruleset,
+        },
+        release::{Release, ReleaseBodyArgs, ReleaseCreateBodyArgs},
+        status::{BroadcastMessage, RemoteHealth},
     },
-    io::CmdInfo,
+    io::{CmdInfo, PageCount},
     remote::{
-        Member, MergeRequestBodyArgs, MergeRequestListBodyArgs, MergeRequestResponse, Project,
+        Member, MergeRequestBodyArgs, MergeRequestLimits, MergeRequestListBodyArgs,
+        MergeRequestResponse, Milestone, Project,
     },
     Result,
 };
@@ -22,10 +50,77 @@ pub trait MergeRequest {
     fn merge(&self, id: i64) -> Result<MergeRequestResponse>;
     fn get(&self, id: i64) -> Result<MergeRequestResponse>;
     fn close(&self, id: i64) -> Result<MergeRequestResponse>;
-    fn approve(&self, id: i64) -> Result<MergeRequestResponse>;
+    fn reopen(&self, id: i64) -> Result<MergeRequestResponse>;
+    /// Approves the merge request. The `comment` is left as the body of the
+    /// approving review, or as a note right before approving on Gitlab.
+    fn approve(&self, id: i64, comment: Option<&str>) -> Result<MergeRequestResponse>;
+    /// Marks the merge request as draft, or as ready for review when `draft`
+    /// is false.
+    fn set_draft(&self, id: i64, draft: bool) -> Result<MergeRequestResponse>;
     /// Queries the remote API to get the number of pages available for a given
     /// resource based on list arguments.
-    fn num_pages(&self, args: MergeRequestListBodyArgs) -> Result<Option<u32>>;
+    fn num_pages(&self, args: MergeRequestListBodyArgs) -> Result<Option<PageCount>>;
+    fn limits(&self) -> MergeRequestLimits;
+}
+
+pub trait MergeRequestChecks {
+    /// Checks reported for the head commit of the merge request, so the ones
+    /// blocking the merge can be spotted.
+    fn list(&self, id: i64) -> Result<Vec<MergeRequestCheck>>;
+    /// Human readable requirements, like required checks or approvals, that
+    /// are not met yet and would make the merge fail. Empty when the merge
+    /// request can be merged.
+    fn unmet_requirements(&self, id: i64) -> Result<Vec<String>>;
+}
+
+pub trait MergeRequestApprovals {
+    /// Who approved the merge request and who is still expected to.
+    fn approvals(&self, id: i64) -> Result<ApprovalStatus>;
+}
+
+pub trait MergeRequestLabels {
+    /// Adds the labels to the merge request. Returns all its labels.
+    fn add(&self, id: i64, labels: &[String]) -> Result<Vec<String>>;
+    /// Removes the labels from the merge request. Returns the ones left.
+    fn remove(&self, id: i64, labels: &[String]) -> Result<Vec<String>>;
+}
+
+pub trait MergeRequestDiff {
+    /// Changes of the merge request as a unified diff.
+    fn diff(&self, id: i64) -> Result<String>;
+}
+
+pub trait MergeRequestReviewDiff {
+    /// Current head commit of the merge request and the one it had when the
+    /// user last approved or reviewed it.
+    fn reviewed_head(&self, id: i64, username: &str) -> Result<ReviewedHead>;
+    /// Changes between two commits as a unified diff.
+    fn compare(&self, from_sha: &str, to_sha: &str) -> Result<String>;
+}
+
+pub trait FailingPipelines {
+    /// Pipelines that did not pass on the head commit of the open merge
+    /// requests authored by the user, in any project.
+    fn failing(&self, user: &Member) -> Result<Vec<FailingPipeline>>;
+}
+
+pub trait MergeRequestRebase {
+    /// Start rebasing the source branch onto the target branch in the remote.
+    /// Returns the head commit of the merge request before the rebase.
+    fn rebase(&self, id: i64) -> Result<String>;
+    /// Progress of a rebase started when the head commit was `from_sha`.
+    fn rebase_progress(&self, id: i64, from_sha: &str) -> Result<RebaseProgress>;
+}
+
+/// Github merge queues and Gitlab merge trains. Both serialize merging into a
+/// target branch, running the pipeline of each merge request on top of the
+/// ones ahead of it.
+pub trait MergeQueue {
+    /// Entries queued for the branch in the order they will be merged.
+    fn list(&self, args: MergeQueueListBodyArgs) -> Result<Vec<MergeQueueEntry>>;
+    fn get(&self, id: i64) -> Result<MergeQueueEntry>;
+    fn add(&self, id: i64) -> Result<()>;
+    fn remove(&self, id: i64) -> Result<()>;
 }
 
 pub trait RemoteProject {
@@ -35,24 +130,180 @@ pub trait RemoteProject {
     // merge/pull requests, pipeline, issues, etc.
     fn get_url(&self, option: BrowseOptions) -> String;
     fn list(&self, args: ProjectListBodyArgs) -> Result<Vec<Project>>;
-    fn num_pages(&self, args: ProjectListBodyArgs) -> Result<Option<u32>>;
+    fn num_pages(&self, args: ProjectListBodyArgs) -> Result<Option<PageCount>>;
+}
+
+pub trait ProjectApprovals {
+    fn get(&self) -> Result<ApprovalSettings>;
+    fn set(&self, args: ApprovalSettingsBodyArgs) -> Result<ApprovalSettings>;
+}
+
+pub trait ProjectMembers {
+    /// Members of the project with their role and account state.
+    fn list(&self, args: MemberListBodyArgs) -> Result<Vec<Member>>;
+}
+
+pub trait ProjectMilestones {
+    /// Milestone with the given title, either open or closed.
+    fn get(&self, title: &str) -> Result<Milestone>;
+}
+
+/// Compliance audit of the projects of a group or organization.
+pub trait ProjectAudit {
+    /// Projects of the group with their visibility. Gitlab includes the
+    /// projects of its subgroups.
+    fn list(&self, group: &str) -> Result<Vec<AuditedProject>>;
+    /// Fills in what the list leaves out, the license in Gitlab and whether
+    /// the default branch is protected.
+    fn audit(&self, project: AuditedProject) -> Result<AuditedProject>;
+}
+
+/// Projects created from the template project the remote points to.
+pub trait ProjectTemplate {
+    fn create_from_template(&self, args: ProjectTemplateBodyArgs) -> Result<NewProject>;
+    /// Replace the topics of a project created from the template.
+    fn set_topics(&self, project: &NewProject, topics: &[String]) -> Result<()>;
+    fn create_label(&self, project: &NewProject, name: &str) -> Result<()>;
+}
+
+pub trait ProjectAccessRequests {
+    fn list(&self, args: AccessRequestListBodyArgs) -> Result<Vec<AccessRequest>>;
+    fn approve(&self, args: AccessRequestApproveBodyArgs) -> Result<()>;
+    fn deny(&self, user_id: i64) -> Result<()>;
+}
+
+pub trait ProjectBranchProtection {
+    fn list(&self) -> Result<Vec<ProtectedBranch>>;
+    /// Fails if the branch is not protected.
+    fn get(&self, branch: &str) -> Result<ProtectedBranch>;
+    fn set(&self, args: BranchProtectionBodyArgs) -> Result<ProtectedBranch>;
+}
+
+/// Operations across the whole instance. Gitlab administrators only.
+#[cfg(feature = "admin")]
+pub trait InstanceAdmin {
+    fn list_users(&self, args: InstanceUserListBodyArgs) -> Result<Vec<InstanceUser>>;
+    fn block_user(&self, id: i64) -> Result<()>;
+    fn list_projects(&self, args: InstanceProjectListBodyArgs) -> Result<Vec<InstanceProject>>;
+}
+
+/// CI/CD variables in Gitlab, Actions secrets in Github. An environment
+/// narrows them down to the ones scoped to it.
+pub trait ProjectVariables {
+    fn list(&self, environment: Option<&str>) -> Result<Vec<ProjectVariable>>;
+    fn set(&self, args: ProjectVariableBodyArgs) -> Result<()>;
+    fn delete(&self, key: &str, environment: Option<&str>) -> Result<()>;
+}
+
+/// Repository rulesets in Github. Push rules, the closest there is in
+/// Gitlab, come as a single ruleset.
+pub trait ProjectRulesets {
+    /// Includes the rulesets inherited from the organization.
+    fn list(&self) -> Result<Vec<Ruleset>>;
+    fn get(&self, id: i64) -> Result<Ruleset>;
+}
+
+/// History of the repository, with the CI status of each commit.
+pub trait CommitApi {
+    fn list(&self, args: CommitListBodyArgs) -> Result<Vec<Commit>>;
+    fn get(&self, sha: &str) -> Result<Commit>;
+    /// Applies the changes of the commit onto the branch as a new commit.
+    fn cherry_pick(&self, args: CommitPickBodyArgs) -> Result<Commit>;
+    /// Undoes the changes of the commit with a new commit on the branch.
+    fn revert(&self, args: CommitPickBodyArgs) -> Result<Commit>;
+}
+
+/// Push rules are a Gitlab feature. Github repositories set similar
+/// restrictions through rulesets.
+pub trait ProjectPushRules {
+    /// All the checks come disabled when the project has no push rules.
+    fn get(&self) -> Result<PushRules>;
+    fn set(&self, args: PushRulesBodyArgs) -> Result<PushRules>;
+}
+
+pub trait ProjectFile {
+    /// Contents of the file at the given path of the repository. The default
+    /// branch is used when no ref is given.
+    fn raw(&self, path: &str, ref_: Option<&str>) -> Result<Vec<u8>>;
 }
 
 pub trait Cicd {
     fn list(&self, args: PipelineBodyArgs) -> Result<Vec<Pipeline>>;
     fn get_pipeline(&self, id: i64) -> Result<Pipeline>;
-    fn num_pages(&self) -> Result<Option<u32>>;
+    fn num_pages(&self) -> Result<Option<PageCount>>;
+    /// Run the pipeline again. Gitlab retries its failed jobs, Github re-runs
+    /// the whole workflow.
+    fn retry(&self, id: i64) -> Result<Pipeline>;
+    fn cancel(&self, id: i64) -> Result<Pipeline>;
 }
 
 pub trait CicdRunner {
     fn list(&self, args: RunnerListBodyArgs) -> Result<Vec<Runner>>;
     fn get(&self, id: i64) -> Result<RunnerMetadata>;
-    fn num_pages(&self, args: RunnerListBodyArgs) -> Result<Option<u32>>;
+    fn num_pages(&self, args: RunnerListBodyArgs) -> Result<Option<PageCount>>;
+    /// Allow or disallow the project to pick jobs with shared runners.
+    fn set_shared_runners(&self, enabled: bool) -> Result<()>;
+}
+
+pub trait CicdArtifact {
+    /// List job artifacts that have not expired yet.
+    fn list(&self, args: ArtifactListBodyArgs) -> Result<Vec<Artifact>>;
+    /// Erase the artifact. In Gitlab, the id is the id of the job that holds
+    /// the artifacts.
+    fn delete(&self, id: i64) -> Result<()>;
+}
+
+pub trait CicdJob {
+    /// Run a single job again. Gitlab returns the newly created job. Github
+    /// does not return one, so the job being re-run is returned as queued.
+    fn retry(&self, args: JobRetryBodyArgs) -> Result<Job>;
+    /// Jobs of a pipeline. Only the latest attempt of retried jobs is listed.
+    fn list(&self, pipeline_id: i64) -> Result<Vec<Job>>;
+}
+
+pub trait CicdLinter {
+    /// Validate the contents of a CI configuration file.
+    fn lint(&self, args: LintBodyArgs) -> Result<LintResponse>;
+    /// Get the configuration at a given ref with all includes expanded.
+    fn merged_config(&self, ref_: &str) -> Result<LintResponse>;
+}
+
+pub trait CicdGraph {
+    /// Jobs of the pipeline in stage order, along with the jobs they need.
+    fn jobs(&self, pipeline_id: i64) -> Result<Vec<PipelineJobNode>>;
+}
+
+pub trait Issue {
+    fn open(&self, args: IssueBodyArgs) -> Result<IssueResponse>;
+    fn list(&self, args: IssueListBodyArgs) -> Result<Vec<IssueResponse>>;
+    fn get(&self, id: i64) -> Result<IssueResponse>;
+    fn close(&self, id: i64) -> Result<IssueResponse>;
+    fn comment(&self, id: i64, comment: &str) -> Result<()>;
+    fn num_pages(&self, args: IssueListBodyArgs) -> Result<Option<PageCount>>;
 }
 
 pub trait Deploy {
     fn list(&self, args: ReleaseBodyArgs) -> Result<Vec<Release>>;
-    fn num_pages(&self) -> Result<Option<u32>>;
+    fn num_pages(&self) -> Result<Option<PageCount>>;
+    /// Creates a release and uploads its assets. `progress` is called with
+    /// the name of the asset being uploaded, the bytes sent and its size.
+    fn create(
+        &self,
+        args: ReleaseCreateBodyArgs,
+        progress: &dyn Fn(&str, u64, u64),
+    ) -> Result<Release>;
+}
+
+pub trait Deployment {
+    /// Environments of the project along with their last deployment.
+    fn list(&self, args: EnvironmentListBodyArgs) -> Result<Vec<Environment>>;
+    fn get(&self, name: &str) -> Result<Environment>;
+}
+
+pub trait DeploymentApproval {
+    /// List deployments waiting for a reviewer to approve them.
+    fn list(&self, args: DeploymentApprovalListBodyArgs) -> Result<Vec<PendingDeployment>>;
+    fn approve(&self, args: DeploymentApproveBodyArgs) -> Result<()>;
 }
 
 pub trait UserInfo {
@@ -60,6 +311,13 @@ pub trait UserInfo {
     fn get(&self) -> Result<Member>;
 }
 
+pub trait Undo {
+    /// How the operation recorded in `entry` can be reversed.
+    fn reversal(&self, entry: &AuditEntry) -> Reversal;
+    /// Sends the request that reverses the operation recorded in `entry`.
+    fn undo(&self, entry: &AuditEntry) -> Result<()>;
+}
+
 pub trait Timestamp {
     fn created_at(&self) -> String;
 }
@@ -67,13 +325,33 @@ pub trait Timestamp {
 pub trait ContainerRegistry {
     fn list_repositories(&self, args: DockerListBodyArgs) -> Result<Vec<RegistryRepository>>;
     fn list_repository_tags(&self, args: DockerListBodyArgs) -> Result<Vec<RepositoryTag>>;
-    fn num_pages_repository_tags(&self, repository_id: i64) -> Result<Option<u32>>;
-    fn num_pages_repositories(&self) -> Result<Option<u32>>;
+    fn num_pages_repository_tags(&self, repository_id: i64) -> Result<Option<PageCount>>;
+    fn num_pages_repositories(&self) -> Result<Option<PageCount>>;
     fn get_image_metadata(&self, repository_id: i64, tag: &str) -> Result<ImageMetadata>;
 }
 
 pub trait CommentMergeRequest {
     fn create(&self, args: CommentMergeRequestBodyArgs) -> Result<()>;
+    /// Discussion of the merge request, oldest thread first. System notes
+    /// such as "added 1 commit" are left out.
+    fn list_comments(&self, id: i64) -> Result<Vec<CommentThread>>;
+}
+
+pub trait RemoteStatus {
+    fn health(&self) -> Result<RemoteHealth>;
+    /// Active broadcast messages set by the administrators of the remote.
+    fn broadcast_messages(&self) -> Result<Vec<BroadcastMessage>>;
+}
+
+pub trait TokenInfo {
+    /// Metadata of the API token used to authenticate against the remote.
+    fn token(&self) -> Result<TokenMetadata>;
+}
+
+/// Raw access to the remote API. Used to keep the contracts of the responses
+/// up to date.
+pub trait RemoteContract {
+    fn fetch(&self, endpoint: &str) -> Result<serde_json::Value>;
 }
 
 /// Types of API resources attached to a request. The request will carry this
@@ -90,6 +368,7 @@ pub enum ApiOperation {
     Project,
     ContainerRegistry,
     Release,
+    Issue,
 }
 
 impl Display for ApiOperation {
@@ -100,6 +379,7 @@ impl Display for ApiOperation {
             ApiOperation::Project => write!(f, "project"),
             ApiOperation::ContainerRegistry => write!(f, "container_registry"),
             ApiOperation::Release => write!(f, "release"),
+            ApiOperation::Issue => write!(f, "issue"),
         }
     }
 }
@@ -117,5 +397,6 @@ mod tests {
             format!("{}", ApiOperation::ContainerRegistry),
             "container_registry"
         );
+        assert_eq!(format!("{}", ApiOperation::Issue), "issue");
     }
 }
@@ -0,0 +1,336 @@
+//! Append-only local log of the mutating requests sent to the remotes. Each
+//! request is one JSON line in `audit.jsonl` under the cache location, so
+//! it can be traced back later with `gr history`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api_traits::Timestamp;
+use crate::config::ConfigProperties;
+use crate::display::{Column, DisplayBody};
+use crate::http::{Method, Request};
+use crate::time::{self, Seconds};
+use crate::Result;
+
+const AUDIT_FILE: &str = "audit.jsonl";
+/// Body fields carrying the value of CI/CD variables and secrets.
+const SECRET_FIELDS: [&str; 2] = ["value", "encrypted_value"];
+/// Body field carrying the inputs of a workflow run, keyed by input name.
+const INPUTS_FIELD: &str = "inputs";
+
+pub trait AuditLog {
+    fn record(&self, entry: &AuditEntry) -> Result<()>;
+    /// All the recorded entries, oldest first.
+    fn entries(&self) -> Result<Vec<AuditEntry>>;
+}
+
+#[derive(Builder, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Epoch seconds at which the request was sent.
+    pub timestamp: u64,
+    /// Local user that ran gitar.
+    pub user: String,
+    pub method: String,
+    pub url: String,
+    /// HTTP status code or the error if the request could not be sent.
+    pub result: String,
+    /// JSON body sent, if any. Needed to tell apart operations sent to the
+    /// same URL, such as closing and reopening a merge request.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+impl AuditEntry {
+    pub fn builder() -> AuditEntryBuilder {
+        AuditEntryBuilder::default()
+    }
+
+    pub fn new<T: Serialize>(request: &Request<T>, result: &Result<crate::io::Response>) -> Self {
+        let result = match result {
+            Ok(response) => response.status.to_string(),
+            Err(err) => format!("error: {}", err),
+        };
+        AuditEntry {
+            timestamp: *time::now_epoch_seconds(),
+            user: local_user(),
+            method: format!("{:?}", request.method),
+            url: request.url().to_string(),
+            result,
+            body: request
+                .body()
+                .as_ref()
+                .and_then(|body| serde_json::to_value(body).ok())
+                .map(redact_secrets),
+        }
+    }
+
+    /// Whether the remote accepted the request.
+    pub fn succeeded(&self) -> bool {
+        self.result.starts_with('2')
+    }
+
+    /// Value of `field` in the body sent, if it is a string.
+    pub fn body_field(&self, field: &str) -> Option<&str> {
+        self.body.as_ref().and_then(|body| body[field].as_str())
+    }
+
+    /// Path of the resource requested under `base`, ex. `/pulls/2`. Whole
+    /// path segments are compared, `repos/owner/repo-fork` is not under
+    /// `repos/owner/repo`.
+    pub fn resource(&self, base: &str) -> Option<&str> {
+        let resource = self.url.strip_prefix(base)?;
+        (resource.is_empty() || resource.starts_with(['/', '?'])).then_some(resource)
+    }
+}
+
+/// Secrets are not written to disk, the log only shows that they were sent.
+/// Variables can be nested, ex. `variables[*].value` when running a pipeline,
+/// so the whole body is walked. Workflow `inputs` are redacted as a whole.
+fn redact_secrets(mut body: serde_json::Value) -> serde_json::Value {
+    match &mut body {
+        serde_json::Value::Object(fields) => {
+            for (field, value) in fields.iter_mut() {
+                if SECRET_FIELDS.contains(&field.as_str()) {
+                    *value = serde_json::Value::from("[redacted]");
+                } else if field == INPUTS_FIELD && value.is_object() {
+                    for input in value.as_object_mut().unwrap().values_mut() {
+                        *input = serde_json::Value::from("[redacted]");
+                    }
+                } else {
+                    *value = redact_secrets(value.take());
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items.iter_mut() {
+                *item = redact_secrets(item.take());
+            }
+        }
+        _ => {}
+    }
+    body
+}
+
+/// How an operation in the audit log can be reversed in the current project.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reversal {
+    /// Description of the operation that reverses it.
+    Reversible(String),
+    Irreversible,
+    /// The operation was done in another project or remote.
+    Unrelated,
+}
+
+impl Timestamp for AuditEntry {
+    fn created_at(&self) -> String {
+        time::epoch_to_rfc3339(Seconds::new(self.timestamp))
+    }
+}
+
+impl From<AuditEntry> for DisplayBody {
+    fn from(entry: AuditEntry) -> Self {
+        DisplayBody::new(vec![
+            Column::new("Date", entry.created_at()),
+            Column::new("User", entry.user),
+            Column::new("Method", entry.method),
+            Column::new("URL", entry.url),
+            Column::new("Result", entry.result),
+        ])
+    }
+}
+
+fn local_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+/// Whether the request changes data in the remote. GraphQL queries are sent
+/// with POST, so only GraphQL mutations count.
+pub fn is_mutation<T: Serialize>(request: &Request<T>) -> bool {
+    match request.method {
+        Method::GET | Method::HEAD => false,
+        _ if request.url().ends_with("/graphql") => {
+            let body = serde_json::to_value(request.body()).unwrap_or_default();
+            body["query"]
+                .as_str()
+                .map(|query| query.trim_start().starts_with("mutation"))
+                .unwrap_or(false)
+        }
+        _ => true,
+    }
+}
+
+pub struct FileAuditLog {
+    path: PathBuf,
+}
+
+impl FileAuditLog {
+    pub fn new(config: &impl ConfigProperties) -> Self {
+        FileAuditLog {
+            path: PathBuf::from(config.cache_location()).join(AUDIT_FILE),
+        }
+    }
+}
+
+impl AuditLog for FileAuditLog {
+    fn record(&self, entry: &AuditEntry) -> Result<()> {
+        if let Some(cache_location) = self.path.parent() {
+            std::fs::create_dir_all(cache_location)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<AuditEntry>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            // A partially written line, ex. process killed while writing, is
+            // skipped instead of making the whole history unreadable.
+            if let Ok(entry) = serde_json::from_str(&line?) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::http::Body;
+
+    use super::*;
+
+    struct ConfigMock {
+        cache_location: String,
+    }
+
+    impl ConfigProperties for ConfigMock {
+        fn api_token(&self) -> &str {
+            "1234"
+        }
+        fn cache_location(&self) -> &str {
+            &self.cache_location
+        }
+    }
+
+    #[test]
+    fn test_record_and_read_entries_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ConfigMock {
+            cache_location: dir.path().to_str().unwrap().to_string(),
+        };
+        let audit = FileAuditLog::new(&config);
+        assert!(audit.entries().unwrap().is_empty());
+        let merged = AuditEntry::builder()
+            .timestamp(1)
+            .user("jdoe".to_string())
+            .method("PUT".to_string())
+            .url("https://gitlab.com/api/v4/projects/1/merge_requests/2/merge".to_string())
+            .result("200".to_string())
+            .build()
+            .unwrap();
+        let closed = AuditEntry {
+            timestamp: 2,
+            url: "https://gitlab.com/api/v4/projects/1/merge_requests/3".to_string(),
+            ..merged.clone()
+        };
+        audit.record(&merged).unwrap();
+        audit.record(&closed).unwrap();
+        assert_eq!(vec![merged, closed], audit.entries().unwrap());
+    }
+
+    #[test]
+    fn test_record_creates_the_cache_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ConfigMock {
+            cache_location: dir.path().join("gitar").to_str().unwrap().to_string(),
+        };
+        let audit = FileAuditLog::new(&config);
+        let entry = AuditEntry::builder()
+            .timestamp(1)
+            .user("jdoe".to_string())
+            .method("DELETE".to_string())
+            .url("https://gitlab.com/api/v4/projects/1/merge_requests/2".to_string())
+            .result("204".to_string())
+            .build()
+            .unwrap();
+        audit.record(&entry).unwrap();
+        assert_eq!(vec![entry], audit.entries().unwrap());
+    }
+
+    #[test]
+    fn test_new_entry_redacts_secret_values() {
+        let mut request = Request::new(
+            "https://gitlab.com/api/v4/projects/1/variables",
+            Method::POST,
+        );
+        let mut body = Body::new();
+        body.add("key", json!("DEPLOY_TOKEN"));
+        body.add("value", json!("s3cr3t"));
+        request.with_body(body);
+        let entry = AuditEntry::new(&request, &Err(crate::error::gen("timeout")));
+        assert_eq!(Some("DEPLOY_TOKEN"), entry.body_field("key"));
+        assert_eq!(Some("[redacted]"), entry.body_field("value"));
+    }
+
+    #[test]
+    fn test_new_entry_redacts_pipeline_variables_and_inputs() {
+        let mut request = Request::new(
+            "https://gitlab.com/api/v4/projects/1/pipeline",
+            Method::POST,
+        );
+        let mut body = Body::new();
+        body.add("ref", json!("main"));
+        body.add(
+            "variables",
+            json!([{"key": "DEPLOY_TOKEN", "value": "s3cr3t", "variable_type": "env_var"}]),
+        );
+        body.add("inputs", json!({"api_key": "s3cr3t"}));
+        request.with_body(body);
+        let entry = AuditEntry::new(&request, &Err(crate::error::gen("timeout")));
+        let body = entry.body.unwrap();
+        assert_eq!("main", body["ref"]);
+        assert_eq!("DEPLOY_TOKEN", body["variables"][0]["key"]);
+        assert_eq!("[redacted]", body["variables"][0]["value"]);
+        assert_eq!("[redacted]", body["inputs"]["api_key"]);
+    }
+
+    #[test]
+    fn test_is_mutation() {
+        let get: Request<()> = Request::new("https://gitlab.com/api/v4/projects", Method::GET);
+        assert!(!is_mutation(&get));
+        let post: Request<()> = Request::new("https://gitlab.com/api/v4/projects", Method::POST);
+        assert!(is_mutation(&post));
+
+        let mut query = Request::new("https://api.github.com/graphql", Method::POST);
+        let mut body = Body::new();
+        body.add("query", json!("query { viewer { login } }"));
+        query.with_body(body);
+        assert!(!is_mutation(&query));
+
+        let mut mutation = Request::new("https://api.github.com/graphql", Method::POST);
+        let mut body = Body::new();
+        body.add(
+            "query",
+            json!("mutation { enqueuePullRequest { clientMutationId } }"),
+        );
+        mutation.with_body(body);
+        assert!(is_mutation(&mutation));
+    }
+}
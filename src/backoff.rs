@@ -2,8 +2,12 @@ use std::sync::Arc;
 
 use serde::Serialize;
 
+use crate::api_defaults::RETRY_MAX_BACKOFF_SECONDS;
+use crate::cancel;
+use crate::config::ConfigProperties;
 use crate::error::{AddContext, GRError};
-use crate::io::{HttpRunner, RateLimitHeader};
+use crate::io::{HttpRunner, RateLimitHeader, RETRY_AFTER};
+use crate::time::Milliseconds;
 use crate::{error, log_info, Result};
 use crate::{http::Request, io::Response, time::Seconds};
 use crate::{log_error, Error};
@@ -56,11 +60,11 @@ impl<'a, R> ExponentialBackoff<'a, R> {
     /// can be retried if we are being rate limited or if there is a network
     /// outage.
     fn should_retry_on_error(&self, err: &Error) -> Option<RateLimitHeader> {
-        return match err.downcast_ref::<error::GRError>() {
+        match err.downcast_ref::<error::GRError>() {
             Some(error::GRError::RateLimitExceeded(headers)) => Some(headers.clone()),
             Some(error::GRError::HttpTransportError(_)) => Some(RateLimitHeader::default()),
             _ => None,
-        };
+        }
     }
 }
 
@@ -70,7 +74,7 @@ impl<'a, R: HttpRunner<Response = Response>> ExponentialBackoff<'a, R> {
             match self.runner.run(request) {
                 Ok(response) => return Ok(response),
                 Err(err) => {
-                    if self.max_retries == 0 {
+                    if self.max_retries == 0 || cancel::cancelled() {
                         return Err(err);
                     }
                     log_error!("Error: {}", err);
@@ -100,6 +104,68 @@ impl<'a, R: HttpRunner<Response = Response>> ExponentialBackoff<'a, R> {
     }
 }
 
+/// Retries of idempotent requests that failed with a transient error: a 5xx,
+/// a 429 or a network error. The wait doubles on every retry and, with
+/// jitter, is picked randomly from its upper half, so concurrent clients do
+/// not retry in lockstep. A Retry-After from the remote is the least it waits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base: Seconds,
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    pub fn new(config: &impl ConfigProperties) -> Self {
+        RetryPolicy {
+            max_retries: config.retry_max_retries(),
+            base: config.retry_backoff_base(),
+            jitter: config.retry_jitter(),
+        }
+    }
+
+    /// Time to wait before retrying the request for the `retry` time, given
+    /// the outcome of the last attempt. None if it must not be retried.
+    /// `random` picks the jitter.
+    pub fn delay(
+        &self,
+        result: &Result<Response>,
+        retry: u32,
+        random: u64,
+    ) -> Option<Milliseconds> {
+        if retry > self.max_retries {
+            return None;
+        }
+        let retry_after = match result {
+            Ok(response) if response.status == 429 || (500..600).contains(&response.status) => {
+                response
+                    .header(RETRY_AFTER)
+                    .and_then(|retry_after| retry_after.parse::<u64>().ok())
+                    .unwrap_or(0)
+            }
+            Ok(_) => return None,
+            Err(err) => match err.downcast_ref::<GRError>() {
+                Some(GRError::HttpTransportError(_)) => 0,
+                // A 429 with rate limit headers. Without a Retry-After it is
+                // our own threshold, which must not be retried.
+                Some(GRError::RateLimitExceeded(headers)) if *headers.retry_after > 0 => {
+                    *headers.retry_after
+                }
+                _ => return None,
+            },
+        };
+        let backoff = (*self.base * 1000)
+            .saturating_mul(2u64.saturating_pow(retry - 1))
+            .min(RETRY_MAX_BACKOFF_SECONDS * 1000);
+        let backoff = if self.jitter {
+            backoff / 2 + random % (backoff / 2 + 1)
+        } else {
+            backoff
+        };
+        Some(Milliseconds::new(backoff.max(retry_after * 1000)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -282,4 +348,78 @@ mod tests {
         // Total wait 187000
         assert_eq!(Milliseconds::new(187000), *client.milliseconds_throttled());
     }
+
+    fn response_with_status(status: i32, retry_after: Option<&str>) -> Result<Response> {
+        let mut headers = Headers::new();
+        if let Some(retry_after) = retry_after {
+            headers.set("retry-after".to_string(), retry_after.to_string());
+        }
+        Ok(Response::builder()
+            .status(status)
+            .headers(headers)
+            .build()
+            .unwrap())
+    }
+
+    fn retry_policy(jitter: bool) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            base: Seconds::new(1),
+            jitter,
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_doubles_wait_on_server_errors() {
+        let policy = retry_policy(false);
+        let result = response_with_status(502, None);
+        assert_eq!(Some(Milliseconds::new(1000)), policy.delay(&result, 1, 0));
+        assert_eq!(Some(Milliseconds::new(2000)), policy.delay(&result, 2, 0));
+        assert_eq!(Some(Milliseconds::new(4000)), policy.delay(&result, 3, 0));
+        assert_eq!(None, policy.delay(&result, 4, 0));
+    }
+
+    #[test]
+    fn test_retry_policy_jitter_picks_upper_half() {
+        let policy = retry_policy(true);
+        let result = response_with_status(503, None);
+        // 4 seconds on the third retry.
+        assert_eq!(Some(Milliseconds::new(2000)), policy.delay(&result, 3, 0));
+        assert_eq!(
+            Some(Milliseconds::new(3000)),
+            policy.delay(&result, 3, 1000)
+        );
+        assert_eq!(
+            Some(Milliseconds::new(4000)),
+            policy.delay(&result, 3, 2000)
+        );
+    }
+
+    #[test]
+    fn test_retry_policy_honors_retry_after() {
+        let policy = retry_policy(false);
+        let result = response_with_status(429, Some("30"));
+        assert_eq!(Some(Milliseconds::new(30000)), policy.delay(&result, 1, 0));
+        let result: Result<Response> = Err(GRError::RateLimitExceeded(RateLimitHeader::new(
+            0,
+            Seconds::new(0),
+            Seconds::new(20),
+        ))
+        .into());
+        assert_eq!(Some(Milliseconds::new(20000)), policy.delay(&result, 1, 0));
+    }
+
+    #[test]
+    fn test_retry_policy_does_not_retry_other_outcomes() {
+        let policy = retry_policy(false);
+        assert_eq!(None, policy.delay(&response_with_status(200, None), 1, 0));
+        assert_eq!(None, policy.delay(&response_with_status(404, None), 1, 0));
+        // Threshold reached, no Retry-After.
+        let result: Result<Response> =
+            Err(GRError::RateLimitExceeded(RateLimitHeader::default()).into());
+        assert_eq!(None, policy.delay(&result, 1, 0));
+        let result: Result<Response> =
+            Err(GRError::HttpTransportError("connection reset".to_string()).into());
+        assert_eq!(Some(Milliseconds::new(1000)), policy.delay(&result, 1, 0));
+    }
 }
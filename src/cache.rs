@@ -1,5 +1,6 @@
 use crate::io::{Response, ResponseField};
 
+pub mod cipher;
 pub mod filesystem;
 pub mod inmemory;
 pub mod nocache;
@@ -12,6 +13,17 @@ pub trait Cache<K = String> {
     fn get(&self, key: &K) -> Result<CacheState>;
     fn set(&self, key: &K, value: &Response) -> Result<()>;
     fn update(&self, key: &K, value: &Response, field: &ResponseField) -> Result<()>;
+    fn remove(&self, key: &K) -> Result<()>;
+    /// Drops the cached negative responses, see [`is_negative`], of the URLs
+    /// for which `related` is true.
+    fn remove_negative(&self, related: &dyn Fn(&str) -> bool) -> Result<()>;
+}
+
+/// Whether the response tells that the resource does not exist or that
+/// there is nothing to list. Scripts probing for them, ex. whether a project
+/// exists, get them over and over.
+pub fn is_negative(response: &Response) -> bool {
+    matches!(response.status, 404 | 410) || response.body.trim() == "[]"
 }
 
 pub enum CacheState {
@@ -0,0 +1,128 @@
+//! Encryption at rest of the cached responses, for caches of private projects
+//! kept on shared machines. Entries are sealed with XChaCha20-Poly1305 under
+//! a key derived from the cache passphrase with Argon2. The salt is random and
+//! kept in the cache location, so the same passphrase gives a different key on
+//! each machine.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+use crate::error;
+use crate::Result;
+
+/// Prefix of the encrypted entries, telling them apart from plain ones.
+const MAGIC: &[u8] = b"GRENC1";
+const SALT_FILE: &str = "cache.salt";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+pub struct CacheCipher {
+    cipher: XChaCha20Poly1305,
+}
+
+impl CacheCipher {
+    pub fn new(passphrase: &str, cache_location: &str) -> Result<Self> {
+        let salt = salt(&Path::new(cache_location).join(SALT_FILE))?;
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|err| error::gen(format!("Could not derive the cache key: {}", err)))?;
+        Ok(CacheCipher {
+            cipher: XChaCha20Poly1305::new(&key.into()),
+        })
+    }
+
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, data)
+            .map_err(|_| error::gen("Could not encrypt the cache entry"))?;
+        let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// None if `data` is not an encrypted entry or was encrypted with
+    /// another passphrase. Either way, it is a cache miss.
+    pub fn decrypt(&self, data: &[u8]) -> Option<Vec<u8>> {
+        let sealed = data.strip_prefix(MAGIC)?;
+        if sealed.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .ok()
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Salt of the cache key, created on first use. It is written aside and
+/// linked in place, so a concurrent invocation never reads it half written.
+fn salt(path: &Path) -> Result<Vec<u8>> {
+    match fs::read(path) {
+        Ok(salt) if salt.len() == SALT_LEN => return Ok(salt),
+        Ok(_) => {
+            return Err(error::gen(format!(
+                "Invalid cache salt in {}, remove it along with the cache",
+                path.display()
+            )))
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => (),
+        Err(err) => return Err(err.into()),
+    }
+    let mut salt = vec![0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    if let Some(cache_location) = path.parent() {
+        fs::create_dir_all(cache_location)?;
+    }
+    let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp_path, &salt)?;
+    let linked = fs::hard_link(&tmp_path, path);
+    let _ = fs::remove_file(&tmp_path);
+    match linked {
+        Ok(()) => Ok(salt),
+        // Created by a concurrent invocation in the meantime.
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => Ok(fs::read(path)?),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().to_str().unwrap();
+        let cipher = CacheCipher::new("s3cr3t", location).unwrap();
+        let sealed = cipher.encrypt(b"cached response").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(
+            b"cached response".to_vec(),
+            cipher.decrypt(&sealed).unwrap()
+        );
+        // Same passphrase and salt, ex. the next invocation.
+        let cipher = CacheCipher::new("s3cr3t", location).unwrap();
+        assert_eq!(
+            b"cached response".to_vec(),
+            cipher.decrypt(&sealed).unwrap()
+        );
+        let other = CacheCipher::new("other", location).unwrap();
+        assert!(other.decrypt(&sealed).is_none());
+        assert!(cipher.decrypt(b"plain entry").is_none());
+    }
+}
@@ -1,14 +1,15 @@
 use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 
 use flate2::bufread::GzDecoder;
 use sha2::{Digest, Sha256};
 
-use crate::cache::Cache;
+use crate::cache::{self, Cache};
 use crate::http::{Headers, Resource};
 use crate::io::{self, Response};
 use crate::time::Seconds;
 
+use super::cipher::{self, CacheCipher};
 use super::CacheState;
 
 use crate::config::ConfigProperties;
@@ -19,13 +20,48 @@ use crate::Result;
 use flate2::write::GzEncoder;
 use flate2::Compression;
 
+/// Lists the URLs with a negative response cached. Cache files are named
+/// after a hash of their URL, this is how they are found by URL.
+const NEGATIVE_INDEX_FILE: &str = "negative_lookups";
+
 pub struct FileCache<C> {
     config: C,
+    // Set when a cache passphrase is configured. Deriving the key can fail,
+    // ex. a read-only cache location, which is reported on first use.
+    cipher: Option<Result<CacheCipher>>,
 }
 
 impl<C: ConfigProperties> FileCache<C> {
     pub fn new(config: C) -> Self {
-        FileCache { config }
+        let passphrase = config.cache_passphrase();
+        let cipher =
+            (!passphrase.is_empty()).then(|| CacheCipher::new(passphrase, config.cache_location()));
+        FileCache { config, cipher }
+    }
+
+    fn cipher(&self) -> Result<Option<&CacheCipher>> {
+        match &self.cipher {
+            Some(Ok(cipher)) => Ok(Some(cipher)),
+            Some(Err(err)) => Err(error::gen(err.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Cached response in `path`. Entries encrypted with another passphrase,
+    /// or with none when one is configured, are misses.
+    fn read_cache_file(&self, path: &str) -> Result<Option<Response>> {
+        let Ok(data) = std::fs::read(path) else {
+            return Ok(None);
+        };
+        let data = match self.cipher()? {
+            Some(cipher) => match cipher.decrypt(&data) {
+                Some(data) => data,
+                None => return Ok(None),
+            },
+            None if cipher::is_encrypted(&data) => return Ok(None),
+            None => data,
+        };
+        self.get_cache_data(data.as_slice()).map(Some)
     }
 
     fn get_cache_file(&self, url: &str) -> String {
@@ -58,8 +94,9 @@ impl<C: ConfigProperties> FileCache<C> {
                 return Err(error::gen(trace));
             }
         };
+        // Bodies such as diffs span several lines, read up to the end.
         let mut body = String::new();
-        reader.read_line(&mut body)?;
+        reader.read_to_string(&mut body)?;
         let body = body.trim();
         let headers_map = serde_json::from_str::<Headers>(&headers)?;
         let response = Response::builder()
@@ -70,17 +107,73 @@ impl<C: ConfigProperties> FileCache<C> {
         Ok(response)
     }
 
-    fn persist_cache_data(&self, value: &Response, f: BufWriter<File>) -> Result<()> {
+    fn negative_index_file(&self) -> String {
+        let cache_location = self.config.cache_location();
+        let location = cache_location.strip_suffix('/').unwrap_or(cache_location);
+        format!("{}/{}", location, NEGATIVE_INDEX_FILE)
+    }
+
+    /// Encrypted as the cached responses are, URLs tell what is being worked
+    /// on.
+    fn read_negative_index(&self) -> Result<Vec<String>> {
+        let Ok(data) = std::fs::read(self.negative_index_file()) else {
+            return Ok(Vec::new());
+        };
+        let data = match self.cipher()? {
+            Some(cipher) => match cipher.decrypt(&data) {
+                Some(data) => data,
+                None => return Ok(Vec::new()),
+            },
+            None if cipher::is_encrypted(&data) => return Ok(Vec::new()),
+            None => data,
+        };
+        Ok(String::from_utf8_lossy(&data)
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    fn write_negative_index(&self, urls: &[String]) -> Result<()> {
+        let mut data = urls
+            .iter()
+            .map(|url| format!("{}\n", url))
+            .collect::<String>()
+            .into_bytes();
+        if let Some(cipher) = self.cipher()? {
+            data = cipher.encrypt(&data)?;
+        }
+        let path = self.negative_index_file();
+        let tmp_path = format!("{}.{}.tmp", path, std::process::id());
+        std::fs::write(&tmp_path, data)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn persist_cache_data(&self, value: &Response, mut f: BufWriter<File>) -> Result<()> {
         let headers_map = value.headers.as_ref().unwrap();
         let headers = serde_json::to_string(headers_map).unwrap();
         let status = value.status.to_string();
         let file_data = format!("{}\n{}\n{}", headers, status, value.body);
-        let mut encoder = GzEncoder::new(f, Compression::default());
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
         encoder.write_all(file_data.as_bytes())?;
+        let mut data = encoder.finish()?;
+        if let Some(cipher) = self.cipher()? {
+            data = cipher.encrypt(&data)?;
+        }
+        f.write_all(&data)?;
+        f.flush()?;
         Ok(())
     }
 
-    fn expired(&self, key: &Resource, path: String) -> Result<bool> {
+    fn expired(&self, key: &Resource, path: String, response: &Response) -> Result<bool> {
+        if let Some(negative_expiration) = self.config.cache_negative_expiration() {
+            if cache::is_negative(response) {
+                return expired(
+                    || get_file_mtime_elapsed(path.as_str()),
+                    negative_expiration,
+                );
+            }
+        }
         let cache_expiration = self
             .config
             .get_cache_expiration(key.api_operation.as_ref().unwrap())
@@ -98,10 +191,8 @@ impl<C: ConfigProperties> FileCache<C> {
 impl<C: ConfigProperties> Cache<Resource> for FileCache<C> {
     fn get(&self, key: &Resource) -> Result<CacheState> {
         let path = self.get_cache_file(&key.url);
-        if let Ok(f) = File::open(&path) {
-            let mut f = BufReader::new(f);
-            let response = self.get_cache_data(&mut f)?;
-            if self.expired(key, path)? {
+        if let Some(response) = self.read_cache_file(&path)? {
+            if self.expired(key, path, &response)? {
                 return Ok(CacheState::Stale(response));
             }
             Ok(CacheState::Fresh(response))
@@ -112,17 +203,31 @@ impl<C: ConfigProperties> Cache<Resource> for FileCache<C> {
 
     fn set(&self, key: &Resource, value: &Response) -> Result<()> {
         let path = self.get_cache_file(&key.url);
-        let f = File::create(path)?;
-        let f = BufWriter::new(f);
-        self.persist_cache_data(value, f)?;
+        // The default location under $XDG_CACHE_HOME is not there on a fresh
+        // machine.
+        std::fs::create_dir_all(self.config.cache_location())?;
+        // Written aside and renamed, so an interrupted write never leaves a
+        // truncated entry behind.
+        let tmp_path = format!("{}.{}.tmp", path, std::process::id());
+        let f = BufWriter::new(File::create(&tmp_path)?);
+        if let Err(err) = self.persist_cache_data(value, f) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(err);
+        }
+        std::fs::rename(&tmp_path, path)?;
+        if cache::is_negative(value) {
+            let mut urls = self.read_negative_index()?;
+            if !urls.contains(&key.url) {
+                urls.push(key.url.clone());
+                self.write_negative_index(&urls)?;
+            }
+        }
         Ok(())
     }
 
     fn update(&self, key: &Resource, value: &Response, field: &io::ResponseField) -> Result<()> {
         let path = self.get_cache_file(&key.url);
-        if let Ok(f) = File::open(path) {
-            let mut f = BufReader::new(f);
-            let mut response = self.get_cache_data(&mut f)?;
+        if let Some(mut response) = self.read_cache_file(&path)? {
             match field {
                 io::ResponseField::Body => response.body = value.body.clone(),
                 io::ResponseField::Headers => {
@@ -143,6 +248,31 @@ impl<C: ConfigProperties> Cache<Resource> for FileCache<C> {
         }
         Ok(())
     }
+
+    fn remove(&self, key: &Resource) -> Result<()> {
+        match std::fs::remove_file(self.get_cache_file(&key.url)) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn remove_negative(&self, related: &dyn Fn(&str) -> bool) -> Result<()> {
+        let urls = self.read_negative_index()?;
+        let (related_urls, kept): (Vec<_>, Vec<_>) = urls.into_iter().partition(|url| related(url));
+        if related_urls.is_empty() {
+            return Ok(());
+        }
+        for url in related_urls {
+            // Cached again since with a positive response, which stays.
+            let path = self.get_cache_file(&url);
+            if let Some(response) = self.read_cache_file(&path)? {
+                if cache::is_negative(&response) {
+                    self.remove(&Resource::new(&url, None))?;
+                }
+            }
+        }
+        self.write_negative_index(&kept)
+    }
 }
 
 fn expired<F: Fn() -> Result<Seconds>>(
@@ -167,11 +297,31 @@ fn get_file_mtime_elapsed(path: &str) -> Result<Seconds> {
 mod tests {
     use super::*;
 
-    struct ConfigMock;
+    use crate::api_traits::ApiOperation;
+
+    struct ConfigMock {
+        cache_location: String,
+        cache_passphrase: String,
+        cache_negative_expiration: Option<Seconds>,
+    }
 
     impl ConfigMock {
         fn new() -> Self {
-            ConfigMock {}
+            ConfigMock {
+                // TODO test with suffix /
+                // should probably be sanitized on the Config struct itself.
+                cache_location: "/home/user/.cache".to_string(),
+                cache_passphrase: "".to_string(),
+                cache_negative_expiration: None,
+            }
+        }
+
+        fn with_location(dir: &tempfile::TempDir, cache_passphrase: &str) -> Self {
+            ConfigMock {
+                cache_location: dir.path().to_str().unwrap().to_string(),
+                cache_passphrase: cache_passphrase.to_string(),
+                cache_negative_expiration: None,
+            }
         }
     }
 
@@ -180,9 +330,16 @@ mod tests {
             "1234"
         }
         fn cache_location(&self) -> &str {
-            // TODO test with suffix /
-            // should probably be sanitized on the Config struct itself.
-            "/home/user/.cache"
+            &self.cache_location
+        }
+        fn cache_passphrase(&self) -> &str {
+            &self.cache_passphrase
+        }
+        fn get_cache_expiration(&self, _api_operation: &ApiOperation) -> &str {
+            "1d"
+        }
+        fn cache_negative_expiration(&self) -> Option<Seconds> {
+            self.cache_negative_expiration
         }
     }
 
@@ -198,6 +355,172 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_replaces_cache_entry_without_leftovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_cache = FileCache::new(ConfigMock::with_location(&dir, ""));
+        let key = Resource::new("https://gitlab.org/api/v4/projects/jordilin%2Fmr", None);
+        for body in ["first", "second"] {
+            let response = Response::builder()
+                .status(200)
+                .body(body.to_string())
+                .headers(Headers::new())
+                .build()
+                .unwrap();
+            file_cache.set(&key, &response).unwrap();
+        }
+        let path = file_cache.get_cache_file(&key.url);
+        let mut reader = BufReader::new(File::open(&path).unwrap());
+        assert_eq!(
+            "second",
+            file_cache.get_cache_data(&mut reader).unwrap().body
+        );
+        assert_eq!(1, std::fs::read_dir(dir.path()).unwrap().count());
+    }
+
+    #[test]
+    fn test_set_creates_the_cache_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().join("gitar");
+        let file_cache = FileCache::new(ConfigMock {
+            cache_location: location.to_str().unwrap().to_string(),
+            ..ConfigMock::new()
+        });
+        let key = Resource::new("https://gitlab.org/api/v4/projects/jordilin%2Fmr", None);
+        let response = Response::builder()
+            .status(200)
+            .body("{}".to_string())
+            .headers(Headers::new())
+            .build()
+            .unwrap();
+        file_cache.set(&key, &response).unwrap();
+        assert!(std::path::Path::new(&file_cache.get_cache_file(&key.url)).exists());
+    }
+
+    #[test]
+    fn test_multi_line_body_is_read_back_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_cache = FileCache::new(ConfigMock::with_location(&dir, ""));
+        let key = Resource::new(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23",
+            None,
+        );
+        let diff = "diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1 +1 @@\n-fn main() {}\n+fn main() { run() }";
+        let response = Response::builder()
+            .status(200)
+            .body(diff.to_string())
+            .headers(Headers::new())
+            .build()
+            .unwrap();
+        file_cache.set(&key, &response).unwrap();
+        let path = file_cache.get_cache_file(&key.url);
+        let mut reader = BufReader::new(File::open(&path).unwrap());
+        assert_eq!(diff, file_cache.get_cache_data(&mut reader).unwrap().body);
+    }
+
+    #[test]
+    fn test_encrypted_entries_need_the_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = Resource::new("https://gitlab.org/api/v4/projects/jordilin%2Fmr", None);
+        let response = Response::builder()
+            .status(200)
+            .body("private".to_string())
+            .headers(Headers::new())
+            .build()
+            .unwrap();
+        let encrypted = FileCache::new(ConfigMock::with_location(&dir, "s3cr3t"));
+        encrypted.set(&key, &response).unwrap();
+        let path = encrypted.get_cache_file(&key.url);
+        let data = std::fs::read(&path).unwrap();
+        assert!(cipher::is_encrypted(&data));
+        assert_eq!(
+            "private",
+            encrypted.read_cache_file(&path).unwrap().unwrap().body
+        );
+        let plain = FileCache::new(ConfigMock::with_location(&dir, ""));
+        assert!(plain.read_cache_file(&path).unwrap().is_none());
+        let other = FileCache::new(ConfigMock::with_location(&dir, "other"));
+        assert!(other.read_cache_file(&path).unwrap().is_none());
+        // Entries cached before setting the passphrase are not trusted.
+        plain.set(&key, &response).unwrap();
+        assert!(encrypted.read_cache_file(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_negative_responses_expire_on_their_own() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_cache = FileCache::new(ConfigMock {
+            cache_negative_expiration: Some(Seconds::new(0)),
+            ..ConfigMock::with_location(&dir, "")
+        });
+        let key = Resource::new(
+            "https://gitlab.org/api/v4/projects/jordilin%2Fmr",
+            Some(ApiOperation::Project),
+        );
+        for (status, body, fresh) in [(404, "", false), (200, "[]", false), (200, "{}", true)] {
+            let response = Response::builder()
+                .status(status)
+                .body(body.to_string())
+                .headers(Headers::new())
+                .build()
+                .unwrap();
+            file_cache.set(&key, &response).unwrap();
+            match file_cache.get(&key).unwrap() {
+                CacheState::Fresh(_) => assert!(fresh),
+                CacheState::Stale(_) => assert!(!fresh),
+                CacheState::None => panic!("Expected a cached response"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_remove_negative_finds_cached_responses_by_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_cache = FileCache::new(ConfigMock::with_location(&dir, "secret"));
+        let response = |status: i32, body: &str| {
+            Response::builder()
+                .status(status)
+                .body(body.to_string())
+                .headers(Headers::new())
+                .build()
+                .unwrap()
+        };
+        let not_found = Resource::new(
+            "https://gitlab.org/api/v4/projects/jordilin%2Fnew",
+            Some(ApiOperation::Project),
+        );
+        let empty_page = Resource::new(
+            "https://gitlab.org/api/v4/projects?owned=true&page=1",
+            Some(ApiOperation::Project),
+        );
+        let other = Resource::new(
+            "https://gitlab.org/api/v4/groups/jordilin",
+            Some(ApiOperation::Project),
+        );
+        file_cache.set(&not_found, &response(404, "")).unwrap();
+        file_cache.set(&empty_page, &response(200, "[]")).unwrap();
+        file_cache.set(&other, &response(404, "")).unwrap();
+        file_cache
+            .remove_negative(&|url| url.contains("/projects"))
+            .unwrap();
+        assert!(matches!(
+            file_cache.get(&not_found).unwrap(),
+            CacheState::None
+        ));
+        assert!(matches!(
+            file_cache.get(&empty_page).unwrap(),
+            CacheState::None
+        ));
+        assert!(matches!(
+            file_cache.get(&other).unwrap(),
+            CacheState::Fresh(_)
+        ));
+        assert_eq!(
+            vec![other.url.clone()],
+            file_cache.read_negative_index().unwrap()
+        );
+    }
+
     #[test]
     fn test_get_cache_data() {
         let cached_data = r#"{"vary":"Accept-Encoding","cache-control":"max-age=0, private, must-revalidate","server":"nginx","transfer-encoding":"chunked","x-content-type-options":"nosniff","etag":"W/\"9ef5b79701ae0a753b6f08dc9229cdb6\"","x-per-page":"20","date":"Sat, 13 Jan 2024 19:50:23 GMT","connection":"keep-alive","x-next-page":"","x-runtime":"0.050489","content-type":"application/json","x-total-pages":"2","strict-transport-security":"max-age=63072000","referrer-policy":"strict-origin-when-cross-origin","x-prev-page":"1","x-request-id":"01HM260622PFEYAHAZQQWNT1WG","x-total":"22","x-page":"2","link":"<http://gitlab-web/api/v4/projects/tooling%2Fcli/members/all?id=tooling%2Fcli&page=1&per_page=20>; rel=\"prev\", <http://gitlab-web/api/v4/projects/tooling%2Fcli/members/all?id=tooling%2Fcli&page=1&per_page=20>; rel=\"first\", <http://gitlab-web/api/v4/projects/tooling%2Fcli/members/all?id=tooling%2Fcli&page=2&per_page=20>; rel=\"last\"","x-frame-options":"SAMEORIGIN"}
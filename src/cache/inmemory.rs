@@ -1,7 +1,7 @@
-use std::{cell::RefCell, collections::HashMap};
+use std::{collections::HashMap, sync::Mutex};
 
 use crate::{
-    cache::{Cache, CacheState},
+    cache::{self, Cache, CacheState},
     http::Resource,
     io::{Response, ResponseField},
 };
@@ -9,23 +9,23 @@ use crate::{
 use crate::Result;
 
 pub struct InMemoryCache {
-    cache: RefCell<HashMap<String, Response>>,
+    cache: Mutex<HashMap<String, Response>>,
     expired: bool,
-    pub updated: RefCell<bool>,
-    pub updated_field: RefCell<ResponseField>,
+    pub updated: Mutex<bool>,
+    pub updated_field: Mutex<ResponseField>,
 }
 
 impl Default for InMemoryCache {
     fn default() -> Self {
         Self {
-            cache: RefCell::new(HashMap::new()),
+            cache: Mutex::new(HashMap::new()),
             expired: false,
-            updated: RefCell::new(false),
+            updated: Mutex::new(false),
             // This is to verify we will get the headers updated in a 304
             // response. Set it to Body, so we can verify headers are actually
             // updated during tests. Might be better to have a builder pattern
             // for this (TODO)
-            updated_field: RefCell::new(ResponseField::Body),
+            updated_field: Mutex::new(ResponseField::Body),
         }
     }
 }
@@ -38,7 +38,7 @@ impl InMemoryCache {
 
 impl Cache<Resource> for &InMemoryCache {
     fn get(&self, key: &Resource) -> Result<CacheState> {
-        if let Some(response) = self.cache.borrow().get(&key.url) {
+        if let Some(response) = self.cache.lock().unwrap().get(&key.url) {
             if self.expired {
                 return Ok(CacheState::Stale(response.clone()));
             }
@@ -49,7 +49,8 @@ impl Cache<Resource> for &InMemoryCache {
 
     fn set(&self, key: &Resource, value: &Response) -> Result<()> {
         self.cache
-            .borrow_mut()
+            .lock()
+            .unwrap()
             .insert(key.url.to_string(), value.clone());
         Ok(())
     }
@@ -60,15 +61,24 @@ impl Cache<Resource> for &InMemoryCache {
         value: &Response,
         field: &crate::io::ResponseField,
     ) -> Result<()> {
-        *self.updated.borrow_mut() = true;
-        *self.updated_field.borrow_mut() = field.clone();
+        *self.updated.lock().unwrap() = true;
+        *self.updated_field.lock().unwrap() = field.clone();
         self.set(key, value)
     }
+
+    fn remove(&self, key: &Resource) -> Result<()> {
+        self.cache.lock().unwrap().remove(&key.url);
+        Ok(())
+    }
+
+    fn remove_negative(&self, related: &dyn Fn(&str) -> bool) -> Result<()> {
+        Cache::<String>::remove_negative(*self, related)
+    }
 }
 
 impl Cache<String> for InMemoryCache {
     fn get(&self, key: &String) -> Result<CacheState> {
-        if let Some(response) = self.cache.borrow().get(key) {
+        if let Some(response) = self.cache.lock().unwrap().get(key) {
             if self.expired {
                 return Ok(CacheState::Stale(response.clone()));
             }
@@ -79,7 +89,8 @@ impl Cache<String> for InMemoryCache {
 
     fn set(&self, key: &String, value: &Response) -> Result<()> {
         self.cache
-            .borrow_mut()
+            .lock()
+            .unwrap()
             .insert(key.to_string(), value.clone());
         Ok(())
     }
@@ -92,4 +103,17 @@ impl Cache<String> for InMemoryCache {
     ) -> Result<()> {
         self.set(key, value)
     }
+
+    fn remove(&self, key: &String) -> Result<()> {
+        self.cache.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn remove_negative(&self, related: &dyn Fn(&str) -> bool) -> Result<()> {
+        self.cache
+            .lock()
+            .unwrap()
+            .retain(|url, response| !(related(url) && cache::is_negative(response)));
+        Ok(())
+    }
 }
@@ -16,4 +16,12 @@ impl<K> Cache<K> for NoCache {
     fn update(&self, _key: &K, _value: &Response, _field: &ResponseField) -> Result<()> {
         Ok(())
     }
+
+    fn remove(&self, _key: &K) -> Result<()> {
+        Ok(())
+    }
+
+    fn remove_negative(&self, _related: &dyn Fn(&str) -> bool) -> Result<()> {
+        Ok(())
+    }
 }
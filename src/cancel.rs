@@ -0,0 +1,88 @@
+//! Cancellation with Ctrl-C. The first Ctrl-C asks the running command to
+//! stop at the next safe point: list requests stop fetching pages and print
+//! what was gathered so far, and watch loops and batch operations stop before
+//! their next step. Requests in flight are let finish, so their cache entries
+//! are written whole. A second Ctrl-C exits right away.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::error::GRError;
+use crate::Result;
+
+/// Exit code of a process interrupted with SIGINT.
+pub const EXIT_CODE: i32 = 130;
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(test)]
+thread_local! {
+    // Tests run in parallel in the same process. Each test cancels only
+    // itself.
+    static TEST_CANCELLED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+pub fn install_handler() -> Result<()> {
+    ctrlc::set_handler(|| {
+        if CANCELLED.swap(true, Ordering::SeqCst) {
+            std::process::exit(EXIT_CODE);
+        }
+        eprintln!("Cancelling... Press Ctrl-C again to exit now");
+    })
+    .map_err(|err| GRError::ApplicationError(format!("Cannot handle Ctrl-C: {}", err)).into())
+}
+
+/// Whether the user asked to stop the running command.
+pub fn cancelled() -> bool {
+    #[cfg(test)]
+    if TEST_CANCELLED.get() {
+        return true;
+    }
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Errors out if the user asked to stop the running command. `what` tells
+/// what was stopped, ex. "rebase of merge request 12".
+pub fn check(what: &str) -> Result<()> {
+    if cancelled() {
+        return Err(GRError::Cancelled(what.to_string()).into());
+    }
+    Ok(())
+}
+
+/// Sleeps for `duration`, waking up within a second to error out if the user
+/// asks to stop while waiting.
+pub fn sleep(duration: Duration, what: &str) -> Result<()> {
+    let until = Instant::now() + duration;
+    while let Some(left) = until.checked_duration_since(Instant::now()) {
+        check(what)?;
+        std::thread::sleep(left.min(Duration::from_secs(1)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub fn cancel() {
+    TEST_CANCELLED.set(true);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_errors_once_cancelled() {
+        assert!(check("sweep").is_ok());
+        cancel();
+        let err = check("sweep").unwrap_err();
+        assert_eq!("Cancelled: sweep", err.to_string());
+    }
+
+    #[test]
+    fn test_sleep_errors_once_cancelled() {
+        cancel();
+        let started = Instant::now();
+        assert!(sleep(Duration::from_secs(60), "retry").is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,166 @@
+//! Progress of batch operations such as `gr mr sweep`. Every item is recorded
+//! as soon as it is processed, so a run interrupted by Ctrl-C, an error or
+//! the rate limit can be re-run with `--resume` and skip the items already
+//! done. The checkpoint is removed once the whole batch completes.
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::Result;
+
+const CHECKPOINT_DIR: &str = "checkpoints";
+
+pub struct Checkpoint {
+    // None when progress is not recorded, ex. dry runs.
+    path: Option<PathBuf>,
+    done: HashSet<String>,
+}
+
+impl Checkpoint {
+    /// Checkpoint of the batch identified by `key` under the cache
+    /// `location`. The key must tell apart batches over different projects
+    /// or arguments. Unless `resume` is set, the progress of a previous run
+    /// is discarded.
+    pub fn new(location: &str, key: &str, resume: bool) -> Result<Self> {
+        let dir = PathBuf::from(location).join(CHECKPOINT_DIR);
+        fs::create_dir_all(&dir)?;
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        let path = dir.join(format!("{:x}", hasher.finalize()));
+        let mut done = HashSet::new();
+        if resume {
+            match File::open(&path) {
+                Ok(file) => {
+                    for line in BufReader::new(file).lines() {
+                        let line = line?;
+                        if !line.is_empty() {
+                            done.insert(line);
+                        }
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        } else {
+            remove(&path)?;
+        }
+        Ok(Checkpoint {
+            path: Some(path),
+            done,
+        })
+    }
+
+    /// Checkpoint that skips and records nothing.
+    pub fn disabled() -> Self {
+        Checkpoint {
+            path: None,
+            done: HashSet::new(),
+        }
+    }
+
+    /// Whether a previous run already processed the item.
+    pub fn is_done(&self, item: impl Display) -> bool {
+        self.done.contains(&item.to_string())
+    }
+
+    /// Number of items processed by previous runs.
+    pub fn num_done(&self) -> usize {
+        self.done.len()
+    }
+
+    pub fn record(&mut self, item: impl Display) -> Result<()> {
+        let item = item.to_string();
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", item)?;
+        }
+        self.done.insert(item);
+        Ok(())
+    }
+
+    /// Marks the batch as done, so the next run starts from scratch.
+    pub fn complete(self) -> Result<()> {
+        match &self.path {
+            Some(path) => remove(path),
+            None => Ok(()),
+        }
+    }
+}
+
+fn remove(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn location(dir: &tempfile::TempDir) -> &str {
+        dir.path().to_str().unwrap()
+    }
+
+    #[test]
+    fn test_resume_skips_recorded_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut checkpoint = Checkpoint::new(location(&dir), "sweep a/b stale", false).unwrap();
+        checkpoint.record(1).unwrap();
+        checkpoint.record(2).unwrap();
+        drop(checkpoint);
+
+        let checkpoint = Checkpoint::new(location(&dir), "sweep a/b stale", true).unwrap();
+        assert_eq!(2, checkpoint.num_done());
+        assert!(checkpoint.is_done(1));
+        assert!(checkpoint.is_done(2));
+        assert!(!checkpoint.is_done(3));
+    }
+
+    #[test]
+    fn test_checkpoints_are_per_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut checkpoint = Checkpoint::new(location(&dir), "sweep a/b stale", false).unwrap();
+        checkpoint.record(1).unwrap();
+
+        let checkpoint = Checkpoint::new(location(&dir), "sweep a/c stale", true).unwrap();
+        assert_eq!(0, checkpoint.num_done());
+    }
+
+    #[test]
+    fn test_run_without_resume_discards_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut checkpoint = Checkpoint::new(location(&dir), "prune a/b", false).unwrap();
+        checkpoint.record(1).unwrap();
+
+        let checkpoint = Checkpoint::new(location(&dir), "prune a/b", false).unwrap();
+        assert!(!checkpoint.is_done(1));
+        let checkpoint = Checkpoint::new(location(&dir), "prune a/b", true).unwrap();
+        assert!(!checkpoint.is_done(1));
+    }
+
+    #[test]
+    fn test_complete_removes_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut checkpoint = Checkpoint::new(location(&dir), "prune a/b", false).unwrap();
+        checkpoint.record(1).unwrap();
+        checkpoint.complete().unwrap();
+
+        let checkpoint = Checkpoint::new(location(&dir), "prune a/b", true).unwrap();
+        assert_eq!(0, checkpoint.num_done());
+    }
+
+    #[test]
+    fn test_disabled_checkpoint_writes_nothing() {
+        let mut checkpoint = Checkpoint::disabled();
+        checkpoint.record(1).unwrap();
+        assert!(checkpoint.is_done(1));
+        checkpoint.complete().unwrap();
+    }
+}
@@ -1,29 +1,59 @@
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod auth;
 pub mod browse;
+pub mod cache;
 pub mod cicd;
 pub mod common;
+pub mod config;
+pub mod contracts;
+pub mod cron;
 pub mod docker;
+pub mod environment;
+pub mod history;
 pub mod init;
+pub mod issue;
 pub mod merge_request;
+pub mod migrate;
 pub mod my;
 pub mod project;
 pub mod release;
 pub mod star;
+pub mod status;
+pub mod undo;
 
+#[cfg(feature = "admin")]
+use self::admin::{AdminCommand, AdminOptions};
+use self::auth::{AuthCommand, AuthOptions};
 use self::browse::BrowseCommand;
 use self::browse::BrowseOptions;
+use self::cache::{CacheCommand, CacheOptions};
 use self::cicd::{PipelineCommand, PipelineOptions};
+use self::config::{ConfigCommand, ConfigOptions};
+use self::contracts::{ContractsCommand, ContractsOptions};
+use self::cron::CronCommand;
 use self::docker::{DockerCommand, DockerOptions};
+use self::environment::{EnvironmentCommand, EnvironmentOptions};
+use self::history::HistoryCommand;
 use self::init::{InitCommand, InitCommandOptions};
+use self::issue::{IssueCommand, IssueOptions};
+use self::migrate::{MigrateCommand, MigrateOptions};
 use self::my::MyCommand;
 use self::my::MyOptions;
 use self::project::{ProjectCommand, ProjectOptions};
 use self::release::{ReleaseCommand, ReleaseOptions};
+use self::status::StatusCommand;
+use self::undo::UndoCommand;
+use crate::cmds::cron::CronCliArgs;
+use crate::cmds::history::HistoryCliArgs;
+use crate::cmds::status::StatusCliArgs;
+use crate::cmds::undo::UndoCliArgs;
 use merge_request::{MergeRequestCommand, MergeRequestOptions};
 
 use std::option::Option;
 
 use clap::builder::{styling::AnsiColor, Styles};
-use clap::Parser;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
 
 const CLI_STYLE: Styles = Styles::styled()
     .header(AnsiColor::Red.on_default().bold())
@@ -32,14 +62,30 @@ const CLI_STYLE: Styles = Styles::styled()
     .usage(AnsiColor::Red.on_default().bold());
 
 #[derive(Parser)]
-#[command(about = "A Github/Gitlab CLI tool", styles = CLI_STYLE)]
+#[command(
+    about = "A Github/Gitlab CLI tool",
+    styles = CLI_STYLE,
+    disable_version_flag = true
+)]
 #[clap(next_help_heading = "Global options")]
 struct Args {
     #[clap(subcommand)]
-    pub command: Command,
+    pub command: Option<Command>,
     /// Verbose mode. Enable gitar's logging
     #[clap(long, short, global = true)]
     verbose: bool,
+    /// Plain ASCII output, without emoji nor styling. Also enabled by
+    /// NO_COLOR or TERM=dumb
+    #[clap(long, global = true)]
+    ascii: bool,
+    /// Print the HTTP requests, cache hits, bytes transferred and time spent
+    /// per API operation to STDERR once done. Also printed with --verbose
+    #[clap(long, global = true)]
+    api_stats: bool,
+    /// Print version. Along with --verbose, print build information and the
+    /// config file in use
+    #[clap(long, short = 'V')]
+    version: bool,
 }
 
 #[derive(Parser)]
@@ -59,19 +105,84 @@ enum Command {
     Docker(DockerCommand),
     #[clap(name = "rl", about = "Release operations")]
     Release(ReleaseCommand),
+    #[clap(name = "is", about = "Issue operations")]
+    Issue(IssueCommand),
+    #[clap(name = "env", about = "Environment and deployment operations")]
+    Environment(EnvironmentCommand),
     #[clap(
         name = "my",
         about = "Your user information, such as assigned merge requests, etc..."
     )]
     My(MyCommand),
+    #[clap(
+        name = "migrate",
+        about = "Migrate merge requests metadata between remotes"
+    )]
+    Migrate(MigrateCommand),
+    #[clap(
+        name = "status",
+        about = "Check health, authentication, rate limit and latency of the remotes"
+    )]
+    Status(StatusCommand),
+    #[clap(name = "auth", about = "API token operations")]
+    Auth(AuthCommand),
+    #[clap(name = "cache", about = "Local cache operations")]
+    Cache(CacheCommand),
+    #[clap(name = "config", about = "Config file operations")]
+    Config(ConfigCommand),
+    #[clap(
+        name = "history",
+        about = "Operations that changed data in the remote, recorded locally"
+    )]
+    History(HistoryCommand),
+    #[clap(
+        name = "undo",
+        about = "Reverse the most recent operation that can be undone, such as closing a merge request"
+    )]
+    Undo(UndoCommand),
+    #[clap(
+        name = "cron",
+        about = "Run a command periodically, registered with cron, systemd timers or launchd"
+    )]
+    Cron(CronCommand),
+    #[cfg(feature = "admin")]
+    #[clap(
+        name = "admin",
+        about = "Instance administration of self-hosted Gitlab: users, projects and runners"
+    )]
+    Admin(AdminCommand),
+    // Development only. Keeps the contracts used by the unit tests current.
+    #[clap(name = "contracts", hide = true)]
+    Contracts(ContractsCommand),
     #[clap(name = "init", about = "Initialize the config file")]
     Init(InitCommand),
 }
 
+/// Checks that `args`, given without the program name, form a valid gr
+/// command. The error is the one gr would print when running it.
+pub fn validate_args(args: &[String]) -> std::result::Result<(), String> {
+    Args::try_parse_from(std::iter::once("gr").chain(args.iter().map(String::as_str)))
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
 // Parse cli and return CliOptions
 pub fn parse_cli() -> OptionArgs {
-    let args = Args::parse();
-    let options = match args.command {
+    let matches = Args::command().get_matches();
+    let command_name = command_name(&matches);
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    if args.version {
+        return OptionArgs::new(
+            Some(CliOptions::Version),
+            CliArgs::from(&args),
+            "version".to_string(),
+        );
+    }
+    let cli_args = CliArgs::from(&args);
+    let Some(command) = args.command else {
+        return OptionArgs::new(None, cli_args, command_name);
+    };
+    let options = match command {
         Command::MergeRequest(sub_matches) => Some(CliOptions::MergeRequest(sub_matches.into())),
         Command::Browse(sub_matches) => Some(CliOptions::Browse(sub_matches.into())),
         Command::Pipeline(sub_matches) => Some(CliOptions::Pipeline(sub_matches.into())),
@@ -79,9 +190,33 @@ pub fn parse_cli() -> OptionArgs {
         Command::Init(sub_matches) => Some(CliOptions::Init(sub_matches.into())),
         Command::Docker(sub_matches) => Some(CliOptions::Docker(sub_matches.into())),
         Command::Release(sub_matches) => Some(CliOptions::Release(sub_matches.into())),
+        Command::Issue(sub_matches) => Some(CliOptions::Issue(sub_matches.into())),
+        Command::Environment(sub_matches) => Some(CliOptions::Environment(sub_matches.into())),
         Command::My(sub_matches) => Some(CliOptions::My(sub_matches.into())),
+        Command::Migrate(sub_matches) => Some(CliOptions::Migrate(sub_matches.into())),
+        Command::Auth(sub_matches) => Some(CliOptions::Auth(sub_matches.into())),
+        Command::Contracts(sub_matches) => Some(CliOptions::Contracts(sub_matches.into())),
+        Command::Status(sub_matches) => Some(CliOptions::Status(sub_matches.into())),
+        Command::Cache(sub_matches) => Some(CliOptions::Cache(sub_matches.into())),
+        Command::Config(sub_matches) => Some(CliOptions::Config(sub_matches.into())),
+        Command::History(sub_matches) => Some(CliOptions::History(sub_matches.into())),
+        Command::Undo(sub_matches) => Some(CliOptions::Undo(sub_matches.into())),
+        Command::Cron(sub_matches) => Some(CliOptions::Cron(sub_matches.into())),
+        #[cfg(feature = "admin")]
+        Command::Admin(sub_matches) => Some(CliOptions::Admin(sub_matches.into())),
     };
-    OptionArgs::new(options, CliArgs::new(args.verbose))
+    OptionArgs::new(options, cli_args, command_name)
+}
+
+/// Subcommands given, ex. `mr list`, with aliases resolved to their name.
+fn command_name(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut matches = matches;
+    while let Some((name, sub_matches)) = matches.subcommand() {
+        names.push(name);
+        matches = sub_matches;
+    }
+    names.join(" ")
 }
 
 pub enum CliOptions {
@@ -92,30 +227,53 @@ pub enum CliOptions {
     Init(InitCommandOptions),
     Docker(DockerOptions),
     Release(ReleaseOptions),
+    Issue(IssueOptions),
+    Environment(EnvironmentOptions),
     My(MyOptions),
+    Migrate(MigrateOptions),
+    Status(StatusCliArgs),
+    Auth(AuthOptions),
+    Contracts(ContractsOptions),
+    Cache(CacheOptions),
+    Config(ConfigOptions),
+    History(HistoryCliArgs),
+    Undo(UndoCliArgs),
+    Cron(CronCliArgs),
+    #[cfg(feature = "admin")]
+    Admin(AdminOptions),
+    Version,
 }
 
 #[derive(Copy, Clone)]
 pub struct CliArgs {
     pub verbose: bool,
+    pub ascii: bool,
+    pub api_stats: bool,
 }
 
-impl CliArgs {
-    pub fn new(verbose: bool) -> Self {
-        CliArgs { verbose }
+impl From<&Args> for CliArgs {
+    fn from(args: &Args) -> Self {
+        CliArgs {
+            verbose: args.verbose,
+            ascii: args.ascii,
+            api_stats: args.api_stats,
+        }
     }
 }
 
 pub struct OptionArgs {
     pub cli_options: Option<CliOptions>,
     pub cli_args: CliArgs,
+    /// Ex. `mr list`, for the metrics of the run.
+    pub command_name: String,
 }
 
 impl OptionArgs {
-    pub fn new(cli_options: Option<CliOptions>, cli_args: CliArgs) -> Self {
+    pub fn new(cli_options: Option<CliOptions>, cli_args: CliArgs, command_name: String) -> Self {
         OptionArgs {
             cli_options,
             cli_args,
+            command_name,
         }
     }
 }
@@ -0,0 +1,203 @@
+use clap::Parser;
+
+use crate::cmds::admin::InstanceUserListCliArgs;
+use crate::cmds::cicd::{RunnerListCliArgs, RunnerScope};
+use crate::remote::ListRemoteCliArgs;
+
+use super::cicd::RunnerStatusCli;
+use super::common::ListArgs;
+
+#[derive(Parser)]
+pub struct AdminCommand {
+    /// Domain of the Gitlab instance, ex. gitlab.mycompany.com. Requires a
+    /// token of an administrator of the instance
+    #[clap(long, value_name = "DOMAIN")]
+    domain: String,
+    #[clap(subcommand)]
+    subcommand: AdminSubcommand,
+}
+
+#[derive(Parser)]
+enum AdminSubcommand {
+    #[clap(subcommand, about = "User account operations")]
+    Users(UserSubcommand),
+    #[clap(subcommand, about = "Projects across the instance")]
+    Projects(ProjectSubcommand),
+    #[clap(subcommand, about = "Runners of the instance")]
+    Runners(RunnerSubcommand),
+}
+
+#[derive(Parser)]
+enum UserSubcommand {
+    #[clap(about = "List all the users of the instance")]
+    List(Box<ListUsers>),
+    #[clap(about = "Block a user. The account keeps its data but cannot sign in")]
+    Block(BlockUser),
+}
+
+#[derive(Parser)]
+enum ProjectSubcommand {
+    #[clap(about = "List all the projects of the instance")]
+    List(ListArgs),
+}
+
+#[derive(Parser)]
+enum RunnerSubcommand {
+    #[clap(about = "List the shared runners of the instance")]
+    List(ListRunners),
+}
+
+#[derive(Parser)]
+struct ListUsers {
+    /// Only blocked users
+    #[clap(long)]
+    blocked: bool,
+    #[clap(flatten)]
+    list_args: ListArgs,
+}
+
+#[derive(Parser)]
+struct BlockUser {
+    /// User ID
+    #[clap()]
+    id: i64,
+}
+
+#[derive(Parser)]
+struct ListRunners {
+    /// Runner status
+    #[clap()]
+    status: RunnerStatusCli,
+    /// Comma separated list of tags
+    #[clap(long, value_delimiter = ',', help_heading = "Runner options")]
+    tags: Option<Vec<String>>,
+    /// List every runner of the instance, including group and project runners
+    #[clap(long, help_heading = "Runner options")]
+    all: bool,
+    #[clap(flatten)]
+    list_args: ListArgs,
+}
+
+pub struct AdminOptions {
+    pub domain: String,
+    pub operation: AdminOperation,
+}
+
+pub enum AdminOperation {
+    ListUsers(InstanceUserListCliArgs),
+    BlockUser(i64),
+    ListProjects(ListRemoteCliArgs),
+    ListRunners(RunnerListCliArgs),
+}
+
+impl From<AdminCommand> for AdminOptions {
+    fn from(options: AdminCommand) -> Self {
+        let operation = match options.subcommand {
+            AdminSubcommand::Users(UserSubcommand::List(options)) => AdminOperation::ListUsers(
+                InstanceUserListCliArgs::builder()
+                    .blocked(options.blocked)
+                    .list_args(options.list_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            AdminSubcommand::Users(UserSubcommand::Block(options)) => {
+                AdminOperation::BlockUser(options.id)
+            }
+            AdminSubcommand::Projects(ProjectSubcommand::List(list_args)) => {
+                AdminOperation::ListProjects(list_args.into())
+            }
+            AdminSubcommand::Runners(RunnerSubcommand::List(options)) => {
+                AdminOperation::ListRunners(
+                    RunnerListCliArgs::builder()
+                        .status(options.status.into())
+                        .tags(options.tags.map(|tags| tags.join(",")))
+                        .all(options.all)
+                        .scope(RunnerScope::Instance)
+                        .list_args(options.list_args.into())
+                        .build()
+                        .unwrap(),
+                )
+            }
+        };
+        AdminOptions {
+            domain: options.domain,
+            operation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+    use crate::cmds::cicd::RunnerStatus;
+
+    use super::*;
+
+    fn admin_options(args: Vec<&str>) -> AdminOptions {
+        let args = Args::parse_from(args);
+        match args.command.unwrap() {
+            Command::Admin(options) => options.into(),
+            _ => panic!("Expected AdminCommand"),
+        }
+    }
+
+    #[test]
+    fn test_admin_list_blocked_users() {
+        let options = admin_options(vec![
+            "gr",
+            "admin",
+            "--domain",
+            "gitlab.mycompany.com",
+            "users",
+            "list",
+            "--blocked",
+        ]);
+        assert_eq!("gitlab.mycompany.com", options.domain);
+        match options.operation {
+            AdminOperation::ListUsers(cli_args) => assert!(cli_args.blocked),
+            _ => panic!("Expected AdminOperation::ListUsers"),
+        }
+    }
+
+    #[test]
+    fn test_admin_block_user() {
+        let options = admin_options(vec![
+            "gr",
+            "admin",
+            "--domain",
+            "gitlab.mycompany.com",
+            "users",
+            "block",
+            "42",
+        ]);
+        match options.operation {
+            AdminOperation::BlockUser(id) => assert_eq!(42, id),
+            _ => panic!("Expected AdminOperation::BlockUser"),
+        }
+    }
+
+    #[test]
+    fn test_admin_list_runners_is_instance_scoped() {
+        let options = admin_options(vec![
+            "gr",
+            "admin",
+            "--domain",
+            "gitlab.mycompany.com",
+            "runners",
+            "list",
+            "online",
+        ]);
+        match options.operation {
+            AdminOperation::ListRunners(cli_args) => {
+                assert_eq!(RunnerScope::Instance, cli_args.scope);
+                assert_eq!(RunnerStatus::Online, cli_args.status);
+            }
+            _ => panic!("Expected AdminOperation::ListRunners"),
+        }
+    }
+
+    #[test]
+    fn test_admin_requires_domain() {
+        assert!(Args::try_parse_from(vec!["gr", "admin", "projects", "list"]).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use clap::Parser;
+
+use crate::cmds::auth::TokensCliArgs;
+
+#[derive(Parser)]
+pub struct AuthCommand {
+    #[clap(subcommand)]
+    subcommand: AuthSubcommand,
+}
+
+#[derive(Parser)]
+enum AuthSubcommand {
+    #[clap(about = "List the configured API tokens and their expiration dates")]
+    Tokens(ListTokens),
+}
+
+#[derive(Parser)]
+struct ListTokens {
+    /// Comma separated list of domains to query. Defaults to all the domains
+    /// in the configuration file.
+    #[clap(long, value_delimiter = ',', value_name = "DOMAINS")]
+    domains: Vec<String>,
+}
+
+pub enum AuthOptions {
+    Tokens(TokensCliArgs),
+}
+
+impl From<AuthCommand> for AuthOptions {
+    fn from(options: AuthCommand) -> Self {
+        match options.subcommand {
+            AuthSubcommand::Tokens(options) => AuthOptions::Tokens(
+                TokensCliArgs::builder()
+                    .domains(options.domains)
+                    .build()
+                    .unwrap(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    #[test]
+    fn test_auth_tokens_cli_args() {
+        let args = Args::parse_from(vec!["gr", "auth", "tokens", "--domains", "gitlab.com"]);
+        let options: AuthOptions = match args.command.unwrap() {
+            Command::Auth(options) => options.into(),
+            _ => panic!("Expected AuthCommand"),
+        };
+        match options {
+            AuthOptions::Tokens(cli_args) => assert_eq!(vec!["gitlab.com"], cli_args.domains),
+        }
+    }
+}
@@ -13,7 +13,13 @@ enum BrowseSubcommand {
     #[clap(name = "mr", about = "Open the merge requests using your browser")]
     MergeRequest(MergeRequestBrowse),
     #[clap(name = "pp", about = "Open the ci/cd pipelines using your browser")]
-    Pipelines,
+    Pipelines(PipelineBrowse),
+    #[clap(about = "Open a file of the repository using your browser")]
+    File(FileBrowse),
+    #[clap(name = "rl", about = "Open the releases using your browser")]
+    Releases,
+    #[clap(about = "Open the project settings using your browser")]
+    Settings,
 }
 
 impl From<MergeRequestBrowse> for BrowseOptions {
@@ -25,12 +31,27 @@ impl From<MergeRequestBrowse> for BrowseOptions {
     }
 }
 
+impl From<PipelineBrowse> for BrowseOptions {
+    fn from(options: PipelineBrowse) -> Self {
+        match options.id {
+            Some(id) => BrowseOptions::PipelineId(id),
+            None => BrowseOptions::Pipelines,
+        }
+    }
+}
+
 impl From<BrowseCommand> for BrowseOptions {
     fn from(options: BrowseCommand) -> Self {
         match options.subcommand {
             Some(BrowseSubcommand::Repo) => BrowseOptions::Repo,
             Some(BrowseSubcommand::MergeRequest(options)) => options.into(),
-            Some(BrowseSubcommand::Pipelines) => BrowseOptions::Pipelines,
+            Some(BrowseSubcommand::Pipelines(options)) => options.into(),
+            Some(BrowseSubcommand::File(options)) => BrowseOptions::File {
+                path: options.path,
+                ref_: options.ref_,
+            },
+            Some(BrowseSubcommand::Releases) => BrowseOptions::Releases,
+            Some(BrowseSubcommand::Settings) => BrowseOptions::Settings,
             // defaults to open repo in browser
             None => BrowseOptions::Repo,
         }
@@ -44,6 +65,15 @@ pub enum BrowseOptions {
     MergeRequests,
     MergeRequestId(i64),
     Pipelines,
+    PipelineId(i64),
+    /// A file at the given branch, tag or commit. The default branch if no
+    /// ref is given.
+    File {
+        path: String,
+        ref_: Option<String>,
+    },
+    Releases,
+    Settings,
 }
 
 #[derive(Parser)]
@@ -53,6 +83,23 @@ struct MergeRequestBrowse {
     pub id: Option<i64>,
 }
 
+#[derive(Parser)]
+struct PipelineBrowse {
+    /// Open pipeline id in the browser. A workflow run in Github
+    #[clap()]
+    pub id: Option<i64>,
+}
+
+#[derive(Parser)]
+struct FileBrowse {
+    /// Path of the file in the repository
+    #[clap()]
+    pub path: String,
+    /// Branch, tag or commit SHA. Defaults to the default branch
+    #[clap(long = "ref", value_name = "REF")]
+    pub ref_: Option<String>,
+}
+
 #[cfg(test)]
 mod test {
 
@@ -62,7 +109,7 @@ mod test {
     #[test]
     fn test_browse_command_repo() {
         let args = Args::parse_from(vec!["gr", "br", "repo"]);
-        match args.command {
+        match args.command.unwrap() {
             Command::Browse(BrowseCommand {
                 subcommand: Some(BrowseSubcommand::Repo),
             }) => {}
@@ -73,7 +120,7 @@ mod test {
     #[test]
     fn test_browse_command_mr() {
         let args = Args::parse_from(vec!["gr", "br", "mr"]);
-        let mr_browse = match args.command {
+        let mr_browse = match args.command.unwrap() {
             Command::Browse(BrowseCommand {
                 subcommand: Some(BrowseSubcommand::MergeRequest(options)),
             }) => {
@@ -89,7 +136,7 @@ mod test {
     #[test]
     fn test_browse_command_mr_id() {
         let args = Args::parse_from(vec!["gr", "br", "mr", "1"]);
-        let mr_browse = match args.command {
+        let mr_browse = match args.command.unwrap() {
             Command::Browse(BrowseCommand {
                 subcommand: Some(BrowseSubcommand::MergeRequest(options)),
             }) => {
@@ -102,14 +149,49 @@ mod test {
         assert_eq!(options, BrowseOptions::MergeRequestId(1));
     }
 
+    fn browse_options(args: Vec<&str>) -> BrowseOptions {
+        match Args::parse_from(args).command.unwrap() {
+            Command::Browse(options) => options.into(),
+            _ => panic!("Expected BrowseCommand"),
+        }
+    }
+
     #[test]
     fn test_browse_command_pipelines() {
-        let args = Args::parse_from(vec!["gr", "br", "pp"]);
-        match args.command {
-            Command::Browse(BrowseCommand {
-                subcommand: Some(BrowseSubcommand::Pipelines),
-            }) => {}
-            _ => panic!("Expected Pipelines BrowseCommand"),
-        }
+        assert_eq!(
+            BrowseOptions::Pipelines,
+            browse_options(vec!["gr", "br", "pp"])
+        );
+    }
+
+    #[test]
+    fn test_browse_command_pipeline_id() {
+        assert_eq!(
+            BrowseOptions::PipelineId(1234),
+            browse_options(vec!["gr", "br", "pp", "1234"])
+        );
+    }
+
+    #[test]
+    fn test_browse_command_file_at_ref() {
+        assert_eq!(
+            BrowseOptions::File {
+                path: "src/main.rs".to_string(),
+                ref_: Some("v1.0.0".to_string()),
+            },
+            browse_options(vec!["gr", "br", "file", "src/main.rs", "--ref", "v1.0.0"])
+        );
+    }
+
+    #[test]
+    fn test_browse_command_releases_and_settings() {
+        assert_eq!(
+            BrowseOptions::Releases,
+            browse_options(vec!["gr", "br", "rl"])
+        );
+        assert_eq!(
+            BrowseOptions::Settings,
+            browse_options(vec!["gr", "br", "settings"])
+        );
     }
 }
@@ -0,0 +1,72 @@
+use clap::Parser;
+
+use crate::cmds::cache::CacheWarmCliArgs;
+
+#[derive(Parser)]
+pub struct CacheCommand {
+    #[clap(subcommand)]
+    subcommand: CacheSubcommand,
+}
+
+#[derive(Parser)]
+enum CacheSubcommand {
+    #[clap(
+        about = "Prefetch project data, members, merge requests and pipelines of the current repository"
+    )]
+    Warm(WarmCache),
+}
+
+#[derive(Parser)]
+struct WarmCache {
+    /// Number of pages of merge requests and pipelines to prefetch
+    #[clap(long, default_value_t = 1, value_parser = clap::value_parser!(i64).range(1..))]
+    pages: i64,
+}
+
+pub enum CacheOptions {
+    Warm(CacheWarmCliArgs),
+}
+
+impl From<CacheCommand> for CacheOptions {
+    fn from(options: CacheCommand) -> Self {
+        match options.subcommand {
+            CacheSubcommand::Warm(options) => CacheOptions::Warm(
+                CacheWarmCliArgs::builder()
+                    .pages(options.pages)
+                    .build()
+                    .unwrap(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    #[test]
+    fn test_cache_warm_cli_args() {
+        let args = Args::parse_from(vec!["gr", "cache", "warm", "--pages", "3"]);
+        let options: CacheOptions = match args.command.unwrap() {
+            Command::Cache(options) => options.into(),
+            _ => panic!("Expected CacheCommand"),
+        };
+        match options {
+            CacheOptions::Warm(cli_args) => assert_eq!(3, cli_args.pages),
+        }
+    }
+
+    #[test]
+    fn test_cache_warm_defaults_to_one_page() {
+        let args = Args::parse_from(vec!["gr", "cache", "warm"]);
+        let options: CacheOptions = match args.command.unwrap() {
+            Command::Cache(options) => options.into(),
+            _ => panic!("Expected CacheCommand"),
+        };
+        match options {
+            CacheOptions::Warm(cli_args) => assert_eq!(1, cli_args.pages),
+        }
+    }
+}
@@ -1,7 +1,14 @@
+use std::path::PathBuf;
+
 use clap::{Parser, ValueEnum};
 
 use crate::{
-    cmds::cicd::{RunnerListCliArgs, RunnerMetadataGetCliArgs, RunnerStatus},
+    cmds::cicd::{
+        ArtifactPruneCliArgs, GraphFormat, JobRetryCliArgs, LintCliArgs, MergedConfigCliArgs,
+        PipelineActionCliArgs, PipelineDiffCliArgs, PipelineFlakyCliArgs, PipelineGraphCliArgs,
+        PipelineWaitCliArgs, RunnerListCliArgs, RunnerMetadataGetCliArgs, RunnerScope,
+        RunnerStatus,
+    },
     remote::ListRemoteCliArgs,
 };
 
@@ -17,8 +24,228 @@ pub struct PipelineCommand {
 enum PipelineSubcommand {
     #[clap(about = "List pipelines")]
     List(ListArgs),
+    #[clap(about = "Get the status of a pipeline and its jobs")]
+    Get(PipelineId),
+    #[clap(about = "Retry a pipeline. Gitlab retries its failed jobs")]
+    Retry(PipelineId),
+    #[clap(about = "Cancel a running pipeline")]
+    Cancel(PipelineId),
+    #[clap(about = "Wait for a pipeline to finish. Exits with an error if it does not pass")]
+    Wait(PipelineWait),
+    #[clap(about = "Print the graph of stages and jobs of a pipeline. Gitlab only")]
+    Graph(PipelineGraph),
+    #[clap(about = "Compare the job results and durations of two pipelines")]
+    Diff(PipelineDiff),
+    #[clap(about = "Report jobs that alternate between passing and failing")]
+    Flaky(PipelineFlaky),
     #[clap(subcommand, name = "rn", about = "Runner operations")]
     Runners(RunnerSubCommand),
+    #[clap(subcommand, name = "artifacts", about = "Job artifacts operations")]
+    Artifacts(ArtifactSubCommand),
+    #[clap(subcommand, name = "job", about = "Job operations")]
+    Jobs(JobSubCommand),
+    #[clap(about = "Validate the CI/CD pipeline configuration")]
+    Lint(LintFile),
+    #[clap(
+        name = "config",
+        about = "Show the merged CI configuration at a ref. Gitlab only"
+    )]
+    MergedConfig(MergedConfig),
+}
+
+#[derive(Parser)]
+struct PipelineId {
+    /// Pipeline ID. In Github, the workflow run ID
+    #[clap()]
+    id: i64,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+impl From<PipelineId> for PipelineActionCliArgs {
+    fn from(options: PipelineId) -> Self {
+        PipelineActionCliArgs::builder()
+            .id(options.id)
+            .get_args(options.get_args.into())
+            .build()
+            .unwrap()
+    }
+}
+
+#[derive(Parser)]
+struct PipelineWait {
+    /// Pipeline ID. In Github, the workflow run ID
+    #[clap(required_unless_present = "latest")]
+    id: Option<i64>,
+    /// Wait for the most recent pipeline of the commit checked out locally
+    #[clap(long, conflicts_with = "id")]
+    latest: bool,
+    /// Seconds to wait for before giving up
+    #[clap(long, value_name = "SECONDS", default_value = "3600")]
+    timeout: u64,
+}
+
+impl From<PipelineWait> for PipelineOptions {
+    fn from(options: PipelineWait) -> Self {
+        PipelineOptions::Wait(
+            PipelineWaitCliArgs::builder()
+                .id(options.id)
+                .timeout(options.timeout)
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[derive(Parser)]
+struct PipelineGraph {
+    /// Pipeline ID
+    #[clap()]
+    id: i64,
+    /// Diagram language of the graph
+    #[clap(long, value_enum, default_value_t = GraphFormatCli::Mermaid)]
+    format: GraphFormatCli,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum GraphFormatCli {
+    Mermaid,
+    Plantuml,
+}
+
+impl From<GraphFormatCli> for GraphFormat {
+    fn from(format: GraphFormatCli) -> Self {
+        match format {
+            GraphFormatCli::Mermaid => GraphFormat::Mermaid,
+            GraphFormatCli::Plantuml => GraphFormat::PlantUml,
+        }
+    }
+}
+
+impl From<PipelineGraph> for PipelineOptions {
+    fn from(options: PipelineGraph) -> Self {
+        PipelineOptions::Graph(
+            PipelineGraphCliArgs::builder()
+                .id(options.id)
+                .format(options.format.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[derive(Parser)]
+struct PipelineDiff {
+    /// Pipeline ID taken as the baseline. In Github, the workflow run ID
+    #[clap()]
+    from_id: i64,
+    /// Pipeline ID compared against the baseline
+    #[clap()]
+    to_id: i64,
+    /// Report jobs that got slower by at least this percentage
+    #[clap(long, value_name = "PERCENT", default_value = "50")]
+    threshold: u64,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+impl From<PipelineDiff> for PipelineOptions {
+    fn from(options: PipelineDiff) -> Self {
+        PipelineOptions::Diff(
+            PipelineDiffCliArgs::builder()
+                .from_id(options.from_id)
+                .to_id(options.to_id)
+                .threshold(options.threshold)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[derive(Parser)]
+struct PipelineFlaky {
+    /// Number of most recent pipelines to scan
+    #[clap(long, value_name = "N", default_value = "50")]
+    last: u32,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+impl From<PipelineFlaky> for PipelineOptions {
+    fn from(options: PipelineFlaky) -> Self {
+        PipelineOptions::Flaky(
+            PipelineFlakyCliArgs::builder()
+                .last(options.last)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[derive(Parser)]
+struct MergedConfig {
+    /// Branch, tag or commit SHA
+    #[clap(value_name = "REF")]
+    ref_: String,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+struct LintFile {
+    /// Configuration file. Defaults to .gitlab-ci.yml in Gitlab and all the
+    /// workflows in .github/workflows in Github
+    #[clap()]
+    file: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+enum JobSubCommand {
+    #[clap(about = "Retry a single job")]
+    Retry(RetryJob),
+}
+
+#[derive(Parser)]
+struct RetryJob {
+    /// Job ID
+    #[clap()]
+    id: i64,
+    /// Override a job variable. Can be given multiple times. Gitlab only
+    /// accepts variables when playing manual jobs.
+    #[clap(long = "var", value_name = "KEY=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+fn parse_variable(variable: &str) -> Result<(String, String), String> {
+    match variable.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("invalid variable {}, expected KEY=VALUE", variable)),
+    }
+}
+
+#[derive(Parser)]
+enum ArtifactSubCommand {
+    #[clap(about = "Erase job artifacts older than a given period")]
+    Prune(PruneArtifacts),
+}
+
+#[derive(Parser)]
+struct PruneArtifacts {
+    /// Erase artifacts created before this period of time. Ex: 30d, 12h
+    #[clap(long, value_name = "PERIOD")]
+    older_than: String,
+    /// List the artifacts that would be erased without erasing them
+    #[clap(long)]
+    dry_run: bool,
+    /// Skip the artifacts erased by a previous run that was interrupted
+    #[clap(long)]
+    resume: bool,
+    #[clap(flatten)]
+    get_args: GetArgs,
 }
 
 #[derive(Parser)]
@@ -27,10 +254,31 @@ enum RunnerSubCommand {
     List(ListRunner),
     #[clap(about = "Get runner metadata")]
     Get(RunnerMetadata),
+    #[clap(about = "Enable or disable shared runners for the project. Gitlab only")]
+    Shared(SharedRunners),
+}
+
+#[derive(Parser)]
+struct SharedRunners {
+    #[clap()]
+    state: SharedRunnersStateCli,
+}
+
+#[derive(ValueEnum, Clone, PartialEq, Debug)]
+enum SharedRunnersStateCli {
+    Enable,
+    Disable,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum RunnerScopeCli {
+    Project,
+    Group,
+    Instance,
 }
 
 #[derive(ValueEnum, Clone, PartialEq, Debug)]
-enum RunnerStatusCli {
+pub(super) enum RunnerStatusCli {
     Online,
     Offline,
     Stale,
@@ -47,8 +295,12 @@ struct ListRunner {
     #[clap(long, value_delimiter = ',', help_heading = "Runner options")]
     tags: Option<Vec<String>>,
     /// List all runners available across all projects. Gitlab admins only.
-    #[clap(long, help_heading = "Runner options")]
+    #[clap(long, help_heading = "Runner options", conflicts_with = "scope")]
     all: bool,
+    /// List the runners of the project, of its group or the shared runners of
+    /// the instance. Instance scope is for Gitlab admins only.
+    #[clap(long, help_heading = "Runner options")]
+    scope: Option<RunnerScopeCli>,
     #[command(flatten)]
     list_args: ListArgs,
 }
@@ -66,7 +318,18 @@ impl From<PipelineCommand> for PipelineOptions {
     fn from(options: PipelineCommand) -> Self {
         match options.subcommand {
             PipelineSubcommand::List(options) => options.into(),
+            PipelineSubcommand::Get(options) => PipelineOptions::Get(options.into()),
+            PipelineSubcommand::Retry(options) => PipelineOptions::Retry(options.into()),
+            PipelineSubcommand::Cancel(options) => PipelineOptions::Cancel(options.into()),
+            PipelineSubcommand::Wait(options) => options.into(),
+            PipelineSubcommand::Graph(options) => options.into(),
+            PipelineSubcommand::Diff(options) => options.into(),
+            PipelineSubcommand::Flaky(options) => options.into(),
             PipelineSubcommand::Runners(options) => options.into(),
+            PipelineSubcommand::Artifacts(options) => options.into(),
+            PipelineSubcommand::Jobs(options) => options.into(),
+            PipelineSubcommand::Lint(options) => options.into(),
+            PipelineSubcommand::MergedConfig(options) => options.into(),
         }
     }
 }
@@ -82,10 +345,74 @@ impl From<RunnerSubCommand> for PipelineOptions {
         match options {
             RunnerSubCommand::List(options) => PipelineOptions::Runners(options.into()),
             RunnerSubCommand::Get(options) => PipelineOptions::Runners(options.into()),
+            RunnerSubCommand::Shared(options) => PipelineOptions::Runners(RunnerOptions::Shared(
+                options.state == SharedRunnersStateCli::Enable,
+            )),
         }
     }
 }
 
+impl From<ArtifactSubCommand> for PipelineOptions {
+    fn from(options: ArtifactSubCommand) -> Self {
+        match options {
+            ArtifactSubCommand::Prune(options) => PipelineOptions::Artifacts(options.into()),
+        }
+    }
+}
+
+impl From<PruneArtifacts> for ArtifactOptions {
+    fn from(options: PruneArtifacts) -> Self {
+        ArtifactOptions::Prune(
+            ArtifactPruneCliArgs::builder()
+                .older_than(options.older_than)
+                .dry_run(options.dry_run)
+                .resume(options.resume)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+impl From<LintFile> for PipelineOptions {
+    fn from(options: LintFile) -> Self {
+        PipelineOptions::Lint(LintCliArgs::builder().file(options.file).build().unwrap())
+    }
+}
+
+impl From<MergedConfig> for PipelineOptions {
+    fn from(options: MergedConfig) -> Self {
+        PipelineOptions::MergedConfig(
+            MergedConfigCliArgs::builder()
+                .ref_(options.ref_)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+impl From<JobSubCommand> for PipelineOptions {
+    fn from(options: JobSubCommand) -> Self {
+        match options {
+            JobSubCommand::Retry(options) => PipelineOptions::Jobs(options.into()),
+        }
+    }
+}
+
+impl From<RetryJob> for JobOptions {
+    fn from(options: RetryJob) -> Self {
+        JobOptions::Retry(
+            JobRetryCliArgs::builder()
+                .id(options.id)
+                .variables(options.variables)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
 impl From<RunnerStatusCli> for RunnerStatus {
     fn from(status: RunnerStatusCli) -> Self {
         match status {
@@ -98,6 +425,16 @@ impl From<RunnerStatusCli> for RunnerStatus {
     }
 }
 
+impl From<RunnerScopeCli> for RunnerScope {
+    fn from(scope: RunnerScopeCli) -> Self {
+        match scope {
+            RunnerScopeCli::Project => RunnerScope::Project,
+            RunnerScopeCli::Group => RunnerScope::Group,
+            RunnerScopeCli::Instance => RunnerScope::Instance,
+        }
+    }
+}
+
 impl From<ListRunner> for RunnerOptions {
     fn from(options: ListRunner) -> Self {
         RunnerOptions::List(
@@ -105,6 +442,7 @@ impl From<ListRunner> for RunnerOptions {
                 .status(options.status.into())
                 .tags(options.tags.map(|tags| tags.join(",").to_string()))
                 .all(options.all)
+                .scope(options.scope.map(|scope| scope.into()).unwrap_or_default())
                 .list_args(options.list_args.into())
                 .build()
                 .unwrap(),
@@ -126,12 +464,33 @@ impl From<RunnerMetadata> for RunnerOptions {
 
 pub enum PipelineOptions {
     List(ListRemoteCliArgs),
+    Get(PipelineActionCliArgs),
+    Retry(PipelineActionCliArgs),
+    Cancel(PipelineActionCliArgs),
+    Wait(PipelineWaitCliArgs),
+    Graph(PipelineGraphCliArgs),
+    Diff(PipelineDiffCliArgs),
+    Flaky(PipelineFlakyCliArgs),
     Runners(RunnerOptions),
+    Artifacts(ArtifactOptions),
+    Jobs(JobOptions),
+    Lint(LintCliArgs),
+    MergedConfig(MergedConfigCliArgs),
+}
+
+pub enum JobOptions {
+    Retry(JobRetryCliArgs),
+}
+
+pub enum ArtifactOptions {
+    Prune(ArtifactPruneCliArgs),
 }
 
 pub enum RunnerOptions {
     List(RunnerListCliArgs),
     Get(RunnerMetadataGetCliArgs),
+    /// Enables (true) or disables (false) shared runners for the project.
+    Shared(bool),
 }
 
 #[cfg(test)]
@@ -151,7 +510,7 @@ mod test {
             "--to-page",
             "2",
         ]);
-        let list_args = match args.command {
+        let list_args = match args.command.unwrap() {
             Command::Pipeline(PipelineCommand {
                 subcommand: PipelineSubcommand::List(options),
             }) => {
@@ -171,6 +530,127 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_pipeline_cli_list_columns() {
+        let args = Args::parse_from(vec!["gr", "pp", "list", "--columns", "url,status,branch"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::List(args) => {
+                assert_eq!(
+                    Some(vec![
+                        "url".to_string(),
+                        "status".to_string(),
+                        "branch".to_string()
+                    ]),
+                    args.get_args.columns
+                );
+            }
+            _ => panic!("Expected PipelineOptions::List"),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_get() {
+        let args = Args::parse_from(vec!["gr", "pp", "get", "123"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Get(args) => assert_eq!(123, args.id),
+            _ => panic!("Expected PipelineOptions::Get"),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_wait() {
+        let args = Args::parse_from(vec!["gr", "pp", "wait", "--latest", "--timeout", "60"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Wait(args) => {
+                assert_eq!(None, args.id);
+                assert_eq!(60, args.timeout);
+            }
+            _ => panic!("Expected PipelineOptions::Wait"),
+        }
+        assert!(Args::try_parse_from(vec!["gr", "pp", "wait"]).is_err());
+        assert!(Args::try_parse_from(vec!["gr", "pp", "wait", "123", "--latest"]).is_err());
+    }
+
+    #[test]
+    fn test_pipeline_cli_retry_and_cancel() {
+        let args = Args::parse_from(vec!["gr", "pp", "retry", "123"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Retry(args) => assert_eq!(123, args.id),
+            _ => panic!("Expected PipelineOptions::Retry"),
+        }
+        let args = Args::parse_from(vec!["gr", "pp", "cancel", "123"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Cancel(args) => assert_eq!(123, args.id),
+            _ => panic!("Expected PipelineOptions::Cancel"),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_diff() {
+        let args = Args::parse_from(vec!["gr", "pp", "diff", "120", "123", "--threshold", "25"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Diff(args) => {
+                assert_eq!(120, args.from_id);
+                assert_eq!(123, args.to_id);
+                assert_eq!(25, args.threshold);
+            }
+            _ => panic!("Expected PipelineOptions::Diff"),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_flaky() {
+        let args = Args::parse_from(vec!["gr", "pp", "flaky", "--last", "20"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Flaky(args) => assert_eq!(20, args.last),
+            _ => panic!("Expected PipelineOptions::Flaky"),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_graph() {
+        let args = Args::parse_from(vec!["gr", "pp", "graph", "123", "--format", "plantuml"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Graph(args) => {
+                assert_eq!(123, args.id);
+                assert_eq!(GraphFormat::PlantUml, args.format);
+            }
+            _ => panic!("Expected PipelineOptions::Graph"),
+        }
+    }
+
     #[test]
     fn test_pipeline_cli_runners_list() {
         let args = Args::parse_from(vec![
@@ -187,7 +667,7 @@ mod test {
             "--to-page",
             "2",
         ]);
-        let list_args = match args.command {
+        let list_args = match args.command.unwrap() {
             Command::Pipeline(PipelineCommand {
                 subcommand: PipelineSubcommand::Runners(RunnerSubCommand::List(options)),
             }) => {
@@ -196,7 +676,7 @@ mod test {
                     options.tags,
                     Some(vec!["tag1".to_string(), "tag2".to_string()])
                 );
-                assert_eq!(options.all, true);
+                assert!(options.all);
                 assert_eq!(options.list_args.from_page, Some(1));
                 assert_eq!(options.list_args.to_page, Some(2));
                 options
@@ -208,7 +688,7 @@ mod test {
             RunnerOptions::List(args) => {
                 assert_eq!(args.status, RunnerStatus::Online);
                 assert_eq!(args.tags, Some("tag1,tag2".to_string()));
-                assert_eq!(args.all, true);
+                assert!(args.all);
                 assert_eq!(args.list_args.from_page, Some(1));
                 assert_eq!(args.list_args.to_page, Some(2));
             }
@@ -216,10 +696,134 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_pipeline_cli_artifacts_prune() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pp",
+            "artifacts",
+            "prune",
+            "--older-than",
+            "30d",
+            "--dry-run",
+            "--resume",
+        ]);
+        let prune_args = match args.command.unwrap() {
+            Command::Pipeline(PipelineCommand {
+                subcommand: PipelineSubcommand::Artifacts(ArtifactSubCommand::Prune(options)),
+            }) => {
+                assert_eq!(options.older_than, "30d");
+                assert!(options.dry_run);
+                options
+            }
+            _ => panic!("Expected PipelineCommand"),
+        };
+        let options: ArtifactOptions = prune_args.into();
+        match options {
+            ArtifactOptions::Prune(args) => {
+                assert_eq!(args.older_than, "30d");
+                assert!(args.dry_run);
+                assert!(args.resume);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_job_retry_with_variables() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pp",
+            "job",
+            "retry",
+            "123",
+            "--var",
+            "DEPLOY_ENV=staging",
+            "--var",
+            "DEBUG=",
+        ]);
+        let retry_args = match args.command.unwrap() {
+            Command::Pipeline(PipelineCommand {
+                subcommand: PipelineSubcommand::Jobs(JobSubCommand::Retry(options)),
+            }) => {
+                assert_eq!(options.id, 123);
+                options
+            }
+            _ => panic!("Expected PipelineCommand"),
+        };
+        let options: JobOptions = retry_args.into();
+        match options {
+            JobOptions::Retry(args) => {
+                assert_eq!(args.id, 123);
+                assert_eq!(
+                    vec![
+                        ("DEPLOY_ENV".to_string(), "staging".to_string()),
+                        ("DEBUG".to_string(), "".to_string())
+                    ],
+                    args.variables
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_job_retry_invalid_variable() {
+        let result =
+            Args::try_parse_from(vec!["gr", "pp", "job", "retry", "123", "--var", "DEBUG"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_pipeline_cli_lint_file() {
+        let args = Args::parse_from(vec!["gr", "pp", "lint", "ci/.gitlab-ci.yml"]);
+        let lint_args = match args.command.unwrap() {
+            Command::Pipeline(PipelineCommand {
+                subcommand: PipelineSubcommand::Lint(options),
+            }) => options,
+            _ => panic!("Expected PipelineCommand"),
+        };
+        let options: PipelineOptions = lint_args.into();
+        match options {
+            PipelineOptions::Lint(args) => {
+                assert_eq!(Some(PathBuf::from("ci/.gitlab-ci.yml")), args.file);
+            }
+            _ => panic!("Expected lint options"),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_lint_default_file() {
+        let args = Args::parse_from(vec!["gr", "pp", "lint"]);
+        match args.command.unwrap() {
+            Command::Pipeline(PipelineCommand {
+                subcommand: PipelineSubcommand::Lint(options),
+            }) => assert!(options.file.is_none()),
+            _ => panic!("Expected PipelineCommand"),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_merged_config() {
+        let args = Args::parse_from(vec!["gr", "pp", "config", "v1.0.0", "--refresh"]);
+        let config_args = match args.command.unwrap() {
+            Command::Pipeline(PipelineCommand {
+                subcommand: PipelineSubcommand::MergedConfig(options),
+            }) => options,
+            _ => panic!("Expected PipelineCommand"),
+        };
+        let options: PipelineOptions = config_args.into();
+        match options {
+            PipelineOptions::MergedConfig(args) => {
+                assert_eq!("v1.0.0", args.ref_);
+                assert!(args.get_args.refresh_cache);
+            }
+            _ => panic!("Expected merged config options"),
+        }
+    }
+
     #[test]
     fn test_get_gitlab_runner_metadata() {
         let args = Args::parse_from(vec!["gr", "pp", "rn", "get", "123"]);
-        let list_args = match args.command {
+        let list_args = match args.command.unwrap() {
             Command::Pipeline(PipelineCommand {
                 subcommand: PipelineSubcommand::Runners(RunnerSubCommand::Get(options)),
             }) => {
@@ -236,4 +840,41 @@ mod test {
             _ => panic!("Expected RunnerOptions::Get"),
         }
     }
+
+    #[test]
+    fn test_pipeline_cli_runners_list_group_scope() {
+        let args = Args::parse_from(vec!["gr", "pp", "rn", "list", "all", "--scope", "group"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Runners(RunnerOptions::List(args)) => {
+                assert_eq!(RunnerScope::Group, args.scope);
+                assert!(!args.all);
+            }
+            _ => panic!("Expected RunnerOptions::List"),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cli_runners_list_all_conflicts_with_scope() {
+        assert!(Args::try_parse_from(vec![
+            "gr", "pp", "rn", "list", "all", "--all", "--scope", "group"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_pipeline_cli_disable_shared_runners() {
+        let args = Args::parse_from(vec!["gr", "pp", "rn", "shared", "disable"]);
+        let options: PipelineOptions = match args.command.unwrap() {
+            Command::Pipeline(options) => options.into(),
+            _ => panic!("Expected PipelineCommand"),
+        };
+        match options {
+            PipelineOptions::Runners(RunnerOptions::Shared(enabled)) => assert!(!enabled),
+            _ => panic!("Expected RunnerOptions::Shared"),
+        }
+    }
 }
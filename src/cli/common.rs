@@ -23,6 +23,9 @@ pub struct ListArgs {
     /// How many pages are available
     #[clap(long)]
     num_pages: bool,
+    /// Number of results per page. Max 100. Larger pages mean fewer requests
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..=100))]
+    per_page: Option<u32>,
     /// Created after date (ISO 8601 YYYY-MM-DDTHH:MM:SSZ)
     #[clap(long)]
     created_after: Option<String>,
@@ -61,11 +64,19 @@ pub struct FormatArgs {
     #[clap(long)]
     pub no_headers: bool,
     /// Output format
-    #[clap(long, default_value_t=FormatCli::Pipe)]
+    #[clap(long, env = "GITAR_FORMAT", default_value_t=FormatCli::Pipe)]
     pub format: FormatCli,
     /// Display additional fields
     #[clap(visible_short_alias = 'o', long)]
     pub more_output: bool,
+    /// Comma separated columns to print, in that order, additional fields
+    /// included. Ex: url,status,branch
+    #[clap(long, value_name = "COLUMNS", value_delimiter = ',')]
+    pub columns: Option<Vec<String>>,
+    /// Write the output to the given file instead of STDOUT. The file is only
+    /// replaced once all the output has been gathered. "-" means STDOUT
+    #[clap(long, value_name = "PATH")]
+    pub output: Option<String>,
 }
 
 #[derive(Clone, Parser)]
@@ -122,6 +133,7 @@ impl From<ListArgs> for ListRemoteCliArgs {
             .to_page(args.to_page)
             .page_number(args.page)
             .num_pages(args.num_pages)
+            .per_page(args.per_page)
             .created_after(args.created_after)
             .created_before(args.created_before)
             .sort(args.sort.into())
@@ -142,6 +154,8 @@ impl From<GetArgs> for GetRemoteCliArgs {
             .refresh_cache(args.refresh)
             .backoff_max_retries(args.retry_args.max_retries)
             .backoff_retry_after(args.retry_args.retry_after)
+            .output(args.format_args.output)
+            .columns(args.format_args.columns)
             .build()
             .unwrap()
     }
@@ -0,0 +1,139 @@
+use clap::Parser;
+
+use crate::cmds::config::{ConfigGetCliArgs, ConfigSetCliArgs};
+
+#[derive(Parser)]
+pub struct ConfigCommand {
+    #[clap(subcommand)]
+    subcommand: ConfigSubcommand,
+}
+
+#[derive(Parser)]
+enum ConfigSubcommand {
+    #[clap(
+        about = "Validate the config file, reporting unknown keys, invalid values and missing keys"
+    )]
+    Check,
+    #[clap(about = "Print the value of a config key")]
+    Get(GetConfig),
+    #[clap(about = "Set a config key, keeping comments and the rest of the file as is")]
+    Set(SetConfig),
+}
+
+#[derive(Parser)]
+struct GetConfig {
+    /// Key to print, ex. per_page
+    #[clap()]
+    key: String,
+    /// Domain of the key, ex. gitlab.com. Falls back to the global value. If
+    /// not given, only the global value is printed
+    #[clap(long)]
+    domain: Option<String>,
+}
+
+#[derive(Parser)]
+struct SetConfig {
+    /// Key to set, ex. per_page
+    #[clap()]
+    key: String,
+    /// New value of the key
+    #[clap()]
+    value: String,
+    /// Domain of the key, ex. gitlab.com. If not given, the key is set
+    /// globally and applies to all domains that do not set it
+    #[clap(long)]
+    domain: Option<String>,
+}
+
+pub enum ConfigOptions {
+    Check,
+    Get(ConfigGetCliArgs),
+    Set(ConfigSetCliArgs),
+}
+
+impl From<ConfigCommand> for ConfigOptions {
+    fn from(options: ConfigCommand) -> Self {
+        match options.subcommand {
+            ConfigSubcommand::Check => ConfigOptions::Check,
+            ConfigSubcommand::Get(options) => options.into(),
+            ConfigSubcommand::Set(options) => options.into(),
+        }
+    }
+}
+
+impl From<GetConfig> for ConfigOptions {
+    fn from(options: GetConfig) -> Self {
+        ConfigOptions::Get(
+            ConfigGetCliArgs::builder()
+                .key(options.key)
+                .domain(options.domain)
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+impl From<SetConfig> for ConfigOptions {
+    fn from(options: SetConfig) -> Self {
+        ConfigOptions::Set(
+            ConfigSetCliArgs::builder()
+                .key(options.key)
+                .value(options.value)
+                .domain(options.domain)
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    fn config_options(args: Vec<&str>) -> ConfigOptions {
+        match Args::parse_from(args).command.unwrap() {
+            Command::Config(options) => options.into(),
+            _ => panic!("Expected ConfigCommand"),
+        }
+    }
+
+    #[test]
+    fn test_config_check_cli_args() {
+        let options = config_options(vec!["gr", "config", "check"]);
+        assert!(matches!(options, ConfigOptions::Check));
+    }
+
+    #[test]
+    fn test_config_get_cli_args() {
+        let options = config_options(vec![
+            "gr",
+            "config",
+            "get",
+            "per_page",
+            "--domain",
+            "gitlab.com",
+        ]);
+        match options {
+            ConfigOptions::Get(args) => {
+                assert_eq!("per_page", args.key);
+                assert_eq!(Some("gitlab.com".to_string()), args.domain);
+            }
+            _ => panic!("Expected ConfigOptions::Get"),
+        }
+    }
+
+    #[test]
+    fn test_config_set_global_cli_args() {
+        let options = config_options(vec!["gr", "config", "set", "ascii_output", "true"]);
+        match options {
+            ConfigOptions::Set(args) => {
+                assert_eq!("ascii_output", args.key);
+                assert_eq!("true", args.value);
+                assert_eq!(None, args.domain);
+            }
+            _ => panic!("Expected ConfigOptions::Set"),
+        }
+    }
+}
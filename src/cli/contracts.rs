@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::cmds::contracts::ContractRefreshCliArgs;
+
+#[derive(Parser)]
+pub struct ContractsCommand {
+    #[clap(subcommand)]
+    subcommand: ContractsSubcommand,
+}
+
+#[derive(Parser)]
+enum ContractsSubcommand {
+    #[clap(about = "Fetch a live API response and store it as a contract")]
+    Refresh(RefreshContract),
+}
+
+#[derive(Parser)]
+struct RefreshContract {
+    /// API endpoint relative to the API base path. Ex: projects/jordilin%2Fgitlapi
+    #[clap()]
+    endpoint: String,
+    /// Domain of the remote, its API token is read from the configuration
+    #[clap(long)]
+    domain: String,
+    /// File name of the contract. Ex: project.json
+    #[clap(long)]
+    name: String,
+    /// Keep only the first items when the response is a list
+    #[clap(long, value_name = "NUMBER")]
+    max_items: Option<usize>,
+    /// Directory holding the contracts of each remote
+    #[clap(long, default_value = "contracts", value_name = "DIR")]
+    contracts_dir: PathBuf,
+}
+
+pub enum ContractsOptions {
+    Refresh(ContractRefreshCliArgs),
+}
+
+impl From<ContractsCommand> for ContractsOptions {
+    fn from(options: ContractsCommand) -> Self {
+        match options.subcommand {
+            ContractsSubcommand::Refresh(options) => ContractsOptions::Refresh(
+                ContractRefreshCliArgs::builder()
+                    .endpoint(options.endpoint)
+                    .domain(options.domain)
+                    .name(options.name)
+                    .max_items(options.max_items)
+                    .contracts_dir(options.contracts_dir)
+                    .build()
+                    .unwrap(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    #[test]
+    fn test_contracts_refresh_cli_args() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "contracts",
+            "refresh",
+            "projects/jordilin%2Fgitlapi",
+            "--domain",
+            "gitlab.com",
+            "--name",
+            "project.json",
+        ]);
+        let options: ContractsOptions = match args.command.unwrap() {
+            Command::Contracts(options) => options.into(),
+            _ => panic!("Expected ContractsCommand"),
+        };
+        match options {
+            ContractsOptions::Refresh(cli_args) => {
+                assert_eq!("projects/jordilin%2Fgitlapi", cli_args.endpoint);
+                assert_eq!("gitlab.com", cli_args.domain);
+                assert_eq!("project.json", cli_args.name);
+                assert_eq!(None, cli_args.max_items);
+                assert_eq!(PathBuf::from("contracts"), cli_args.contracts_dir);
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use clap::{Parser, ValueEnum};
+
+use crate::cmds::cron::{CronCliArgs, Schedule, Scheduler};
+
+#[derive(Parser)]
+pub struct CronCommand {
+    /// Cron schedule: minute, hour, day of month, month and day of week,
+    /// ex. "0 9 * * 1-5"
+    #[clap(value_parser = parse_schedule)]
+    schedule: Schedule,
+    /// gr command to run on the schedule, ex. my mr opened. Options of gr
+    /// cron go before the schedule
+    #[clap(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+    /// Scheduler to register the command with. Defaults to launchd on macOS
+    /// and cron elsewhere
+    #[clap(long, value_name = "SCHEDULER")]
+    scheduler: Option<SchedulerOption>,
+    /// Print the crontab entry, timer units or launchd agent instead of
+    /// registering them
+    #[clap(long)]
+    dry_run: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum SchedulerOption {
+    Cron,
+    Systemd,
+    Launchd,
+}
+
+impl From<SchedulerOption> for Scheduler {
+    fn from(option: SchedulerOption) -> Self {
+        match option {
+            SchedulerOption::Cron => Scheduler::Cron,
+            SchedulerOption::Systemd => Scheduler::Systemd,
+            SchedulerOption::Launchd => Scheduler::Launchd,
+        }
+    }
+}
+
+fn parse_schedule(expr: &str) -> Result<Schedule, String> {
+    Schedule::parse(expr).map_err(|err| err.to_string())
+}
+
+impl From<CronCommand> for CronCliArgs {
+    fn from(options: CronCommand) -> Self {
+        CronCliArgs::builder()
+            .schedule(options.schedule)
+            .args(options.command)
+            .scheduler(options.scheduler.map(Into::into).unwrap_or_default())
+            .dry_run(options.dry_run)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    #[test]
+    fn test_cron_cli_args() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "cron",
+            "--scheduler",
+            "systemd",
+            "0 9 * * 1-5",
+            "my",
+            "mr",
+            "opened",
+            "--format",
+            "json",
+        ]);
+        let options: CronCliArgs = match args.command.unwrap() {
+            Command::Cron(options) => options.into(),
+            _ => panic!("Expected CronCommand"),
+        };
+        assert_eq!(Schedule::parse("0 9 * * 1-5").unwrap(), options.schedule);
+        assert_eq!(vec!["my", "mr", "opened", "--format", "json"], options.args);
+        assert_eq!(Scheduler::Systemd, options.scheduler);
+        assert!(!options.dry_run);
+    }
+
+    #[test]
+    fn test_cron_rejects_invalid_schedule() {
+        assert!(Args::try_parse_from(vec!["gr", "cron", "0 25 * * *", "my", "mr"]).is_err());
+        assert!(Args::try_parse_from(vec!["gr", "cron", "0 9 * * *"]).is_err());
+    }
+
+    #[test]
+    fn test_cron_command_is_validated_as_a_gr_command() {
+        let command = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert!(
+            crate::cli::validate_args(&command(&["my", "mr", "opened", "--format", "json"]))
+                .is_ok()
+        );
+        assert!(crate::cli::validate_args(&command(&["my", "dash", "--notify"])).is_err());
+        assert!(crate::cli::validate_args(&command(&["mr", "lst"])).is_err());
+    }
+}
@@ -96,7 +96,7 @@ mod test {
     #[test]
     fn test_docker_cli_repos() {
         let args = Args::parse_from(vec!["gr", "dk", "list", "--repos"]);
-        let list_images = match args.command {
+        let list_images = match args.command.unwrap() {
             Command::Docker(DockerCommand {
                 subcommand: DockerSubCommand::List(options),
             }) => {
@@ -119,7 +119,7 @@ mod test {
     #[test]
     fn test_docker_cli_tags() {
         let args = Args::parse_from(vec!["gr", "dk", "list", "--tags", "--repo-id", "12"]);
-        match args.command {
+        match args.command.unwrap() {
             Command::Docker(DockerCommand {
                 subcommand: DockerSubCommand::List(options),
             }) => {
@@ -143,7 +143,7 @@ mod test {
             "123",
             "v0.0.1",
         ]);
-        let image_metadata = match args.command {
+        let image_metadata = match args.command.unwrap() {
             Command::Docker(DockerCommand {
                 subcommand: DockerSubCommand::Image(options),
             }) => {
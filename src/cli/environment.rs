@@ -0,0 +1,201 @@
+use clap::Parser;
+
+use crate::{
+    cmds::environment::{DeploymentApproveCliArgs, EnvironmentGetCliArgs},
+    remote::ListRemoteCliArgs,
+};
+
+use super::common::{GetArgs, ListArgs};
+
+#[derive(Parser)]
+pub struct EnvironmentCommand {
+    #[clap(subcommand)]
+    subcommand: EnvironmentSubcommand,
+}
+
+#[derive(Parser)]
+enum EnvironmentSubcommand {
+    #[clap(
+        about = "List environments and their last deployment",
+        visible_alias = "ls"
+    )]
+    List(ListArgs),
+    #[clap(about = "Get the last deployment of an environment")]
+    Get(GetEnvironment),
+    #[clap(subcommand, about = "Deployment approval operations")]
+    Approvals(ApprovalSubcommand),
+}
+
+#[derive(Parser)]
+enum ApprovalSubcommand {
+    #[clap(about = "List deployments waiting for approval")]
+    List(ListArgs),
+    #[clap(about = "Approve a deployment waiting for approval")]
+    Approve(ApproveDeployment),
+}
+
+#[derive(Parser)]
+struct GetEnvironment {
+    /// Name of the environment, ex. production
+    #[clap()]
+    name: String,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+struct ApproveDeployment {
+    /// Deployment ID. In Github, the workflow run ID waiting for approval
+    #[clap()]
+    id: i64,
+    /// Comment attached to the approval
+    #[clap(long)]
+    comment: Option<String>,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+impl From<EnvironmentCommand> for EnvironmentOptions {
+    fn from(options: EnvironmentCommand) -> Self {
+        match options.subcommand {
+            EnvironmentSubcommand::List(options) => EnvironmentOptions::List(options.into()),
+            EnvironmentSubcommand::Get(options) => options.into(),
+            EnvironmentSubcommand::Approvals(options) => options.into(),
+        }
+    }
+}
+
+impl From<GetEnvironment> for EnvironmentOptions {
+    fn from(options: GetEnvironment) -> Self {
+        EnvironmentOptions::Get(
+            EnvironmentGetCliArgs::builder()
+                .name(options.name)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+impl From<ApprovalSubcommand> for EnvironmentOptions {
+    fn from(options: ApprovalSubcommand) -> Self {
+        match options {
+            ApprovalSubcommand::List(options) => {
+                EnvironmentOptions::Approvals(ApprovalOptions::List(options.into()))
+            }
+            ApprovalSubcommand::Approve(options) => EnvironmentOptions::Approvals(options.into()),
+        }
+    }
+}
+
+impl From<ApproveDeployment> for ApprovalOptions {
+    fn from(options: ApproveDeployment) -> Self {
+        ApprovalOptions::Approve(
+            DeploymentApproveCliArgs::builder()
+                .id(options.id)
+                .comment(options.comment)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+pub enum EnvironmentOptions {
+    List(ListRemoteCliArgs),
+    Get(EnvironmentGetCliArgs),
+    Approvals(ApprovalOptions),
+}
+
+pub enum ApprovalOptions {
+    List(ListRemoteCliArgs),
+    Approve(DeploymentApproveCliArgs),
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    fn environment_options(args: Vec<&str>) -> EnvironmentOptions {
+        match Args::parse_from(args).command.unwrap() {
+            Command::Environment(options) => options.into(),
+            _ => panic!("Expected EnvironmentCommand"),
+        }
+    }
+
+    #[test]
+    fn test_environment_list() {
+        match environment_options(vec!["gr", "env", "ls", "--from-page", "2"]) {
+            EnvironmentOptions::List(args) => assert_eq!(Some(2), args.from_page),
+            _ => panic!("Expected list environments"),
+        }
+    }
+
+    #[test]
+    fn test_environment_get() {
+        match environment_options(vec![
+            "gr",
+            "env",
+            "get",
+            "production",
+            "--output",
+            "env.json",
+        ]) {
+            EnvironmentOptions::Get(args) => {
+                assert_eq!("production", args.name);
+                assert_eq!(Some("env.json".to_string()), args.get_args.output);
+            }
+            _ => panic!("Expected get environment"),
+        }
+    }
+
+    #[test]
+    fn test_environment_approvals_list() {
+        let args = Args::parse_from(vec!["gr", "env", "approvals", "list"]);
+        let list_args = match args.command.unwrap() {
+            Command::Environment(EnvironmentCommand {
+                subcommand: EnvironmentSubcommand::Approvals(options),
+            }) => options,
+            _ => panic!("Expected EnvironmentCommand"),
+        };
+        let options: EnvironmentOptions = list_args.into();
+        match options {
+            EnvironmentOptions::Approvals(ApprovalOptions::List(args)) => {
+                assert!(!args.num_pages);
+            }
+            _ => panic!("Expected list approvals"),
+        }
+    }
+
+    #[test]
+    fn test_environment_approvals_approve() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "env",
+            "approvals",
+            "approve",
+            "591345821",
+            "--comment",
+            "LGTM",
+        ]);
+        let approve_args = match args.command.unwrap() {
+            Command::Environment(EnvironmentCommand {
+                subcommand: EnvironmentSubcommand::Approvals(ApprovalSubcommand::Approve(options)),
+            }) => {
+                assert_eq!(591345821, options.id);
+                options
+            }
+            _ => panic!("Expected EnvironmentCommand"),
+        };
+        let options: ApprovalOptions = approve_args.into();
+        match options {
+            ApprovalOptions::Approve(args) => {
+                assert_eq!(591345821, args.id);
+                assert_eq!(Some("LGTM".to_string()), args.comment);
+            }
+            _ => panic!("Expected approve deployment"),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use clap::Parser;
+
+use crate::cmds::history::HistoryCliArgs;
+use crate::remote::GetRemoteCliArgs;
+
+use super::common::FormatArgs;
+
+#[derive(Parser)]
+pub struct HistoryCommand {
+    /// Only show requests sent with this HTTP method. Ex: POST, PUT
+    #[clap(long)]
+    method: Option<String>,
+    /// Only show requests whose URL contains this text. Ex: merge_requests/12
+    #[clap(long, value_name = "TEXT")]
+    url: Option<String>,
+    /// Show at most this number of entries, most recent first
+    #[clap(long, default_value_t = 20, value_name = "NUMBER")]
+    limit: usize,
+    #[clap(flatten)]
+    format_args: FormatArgs,
+}
+
+impl From<HistoryCommand> for HistoryCliArgs {
+    fn from(options: HistoryCommand) -> Self {
+        HistoryCliArgs::builder()
+            .method(options.method)
+            .url(options.url)
+            .limit(options.limit)
+            .get_args(
+                GetRemoteCliArgs::builder()
+                    .no_headers(options.format_args.no_headers)
+                    .format(options.format_args.format.into())
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    #[test]
+    fn test_history_cli_args() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "history",
+            "--method",
+            "PUT",
+            "--url",
+            "merge_requests/12",
+        ]);
+        let options: HistoryCliArgs = match args.command.unwrap() {
+            Command::History(options) => options.into(),
+            _ => panic!("Expected HistoryCommand"),
+        };
+        assert_eq!(Some("PUT".to_string()), options.method);
+        assert_eq!(Some("merge_requests/12".to_string()), options.url);
+        assert_eq!(20, options.limit);
+    }
+}
@@ -0,0 +1,251 @@
+use clap::{ArgGroup, Parser, ValueEnum};
+
+use crate::cmds::issue::{
+    IssueCommentCliArgs, IssueCreateCliArgs, IssueGetCliArgs, IssueListCliArgs, IssueState,
+};
+
+use super::common::{GetArgs, ListArgs};
+
+#[derive(Parser)]
+pub struct IssueCommand {
+    #[clap(subcommand)]
+    subcommand: IssueSubcommand,
+}
+
+#[derive(Parser)]
+enum IssueSubcommand {
+    #[clap(about = "Open an issue")]
+    Create(CreateIssue),
+    #[clap(about = "List issues", visible_alias = "ls")]
+    List(ListIssue),
+    #[clap(about = "Get issue details")]
+    Get(GetIssue),
+    #[clap(about = "Close an issue")]
+    Close(CloseIssue),
+    #[clap(about = "Comment on an issue")]
+    Comment(CommentIssue),
+}
+
+#[derive(Parser)]
+struct CreateIssue {
+    /// Title of the issue
+    #[clap(long)]
+    title: String,
+    /// Description of the issue
+    #[clap(long, default_value = "")]
+    description: String,
+    /// Label to add to the issue. Can be repeated
+    #[clap(long = "label", value_name = "LABEL")]
+    labels: Vec<String>,
+}
+
+#[derive(ValueEnum, Clone, PartialEq, Debug)]
+enum IssueStateCli {
+    Opened,
+    Closed,
+}
+
+impl From<IssueStateCli> for IssueState {
+    fn from(state: IssueStateCli) -> Self {
+        match state {
+            IssueStateCli::Opened => IssueState::Opened,
+            IssueStateCli::Closed => IssueState::Closed,
+        }
+    }
+}
+
+#[derive(Parser)]
+struct ListIssue {
+    #[clap()]
+    state: IssueStateCli,
+    #[command(flatten)]
+    list_args: ListArgs,
+}
+
+#[derive(Parser)]
+struct GetIssue {
+    /// Id of the issue
+    #[clap()]
+    id: i64,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+struct CloseIssue {
+    /// Id of the issue
+    #[clap()]
+    id: i64,
+}
+
+#[derive(Parser)]
+#[clap(group(ArgGroup::new("comment_msg").required(true)))]
+struct CommentIssue {
+    /// Id of the issue
+    #[clap(long)]
+    id: i64,
+    /// Comment to add to the issue
+    #[clap(group = "comment_msg")]
+    comment: Option<String>,
+    /// Gather comment from the specified file. If "-" is provided, read from STDIN
+    #[clap(long, value_name = "FILE", group = "comment_msg")]
+    comment_from_file: Option<String>,
+}
+
+pub enum IssueOptions {
+    Create(IssueCreateCliArgs),
+    List(IssueListCliArgs),
+    Get(IssueGetCliArgs),
+    Close { id: i64 },
+    Comment(IssueCommentCliArgs),
+}
+
+impl From<IssueCommand> for IssueOptions {
+    fn from(options: IssueCommand) -> Self {
+        match options.subcommand {
+            IssueSubcommand::Create(options) => options.into(),
+            IssueSubcommand::List(options) => options.into(),
+            IssueSubcommand::Get(options) => options.into(),
+            IssueSubcommand::Close(options) => IssueOptions::Close { id: options.id },
+            IssueSubcommand::Comment(options) => options.into(),
+        }
+    }
+}
+
+impl From<CreateIssue> for IssueOptions {
+    fn from(options: CreateIssue) -> Self {
+        IssueOptions::Create(
+            IssueCreateCliArgs::builder()
+                .title(options.title)
+                .description(options.description)
+                .labels(options.labels)
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+impl From<ListIssue> for IssueOptions {
+    fn from(options: ListIssue) -> Self {
+        IssueOptions::List(
+            IssueListCliArgs::builder()
+                .state(options.state.into())
+                .list_args(options.list_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+impl From<GetIssue> for IssueOptions {
+    fn from(options: GetIssue) -> Self {
+        IssueOptions::Get(
+            IssueGetCliArgs::builder()
+                .id(options.id)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+impl From<CommentIssue> for IssueOptions {
+    fn from(options: CommentIssue) -> Self {
+        IssueOptions::Comment(
+            IssueCommentCliArgs::builder()
+                .id(options.id)
+                .comment(options.comment)
+                .comment_from_file(options.comment_from_file)
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    fn issue_options(args: Vec<&str>) -> IssueOptions {
+        match Args::parse_from(args).command.unwrap() {
+            Command::Issue(options) => options.into(),
+            _ => panic!("Expected IssueCommand"),
+        }
+    }
+
+    #[test]
+    fn test_create_issue_cli_args() {
+        let options = issue_options(vec![
+            "gr",
+            "is",
+            "create",
+            "--title",
+            "Crash on startup",
+            "--label",
+            "bug",
+            "--label",
+            "p1",
+        ]);
+        match options {
+            IssueOptions::Create(args) => {
+                assert_eq!("Crash on startup", args.title);
+                assert_eq!("", args.description);
+                assert_eq!(vec!["bug".to_string(), "p1".to_string()], args.labels);
+            }
+            _ => panic!("Expected IssueOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_list_issues_cli_args() {
+        let options = issue_options(vec!["gr", "is", "ls", "closed", "--from-page", "2"]);
+        match options {
+            IssueOptions::List(args) => {
+                assert_eq!(IssueState::Closed, args.state);
+                assert_eq!(Some(2), args.list_args.from_page);
+            }
+            _ => panic!("Expected IssueOptions::List"),
+        }
+    }
+
+    #[test]
+    fn test_get_issue_output_to_file() {
+        let options = issue_options(vec!["gr", "is", "get", "7", "--output", "issue.json"]);
+        match options {
+            IssueOptions::Get(args) => {
+                assert_eq!(7, args.id);
+                assert_eq!(Some("issue.json".to_string()), args.get_args.output);
+            }
+            _ => panic!("Expected IssueOptions::Get"),
+        }
+    }
+
+    #[test]
+    fn test_close_issue_cli_args() {
+        let options = issue_options(vec!["gr", "is", "close", "7"]);
+        match options {
+            IssueOptions::Close { id } => assert_eq!(7, id),
+            _ => panic!("Expected IssueOptions::Close"),
+        }
+    }
+
+    #[test]
+    fn test_comment_issue_requires_comment_or_file() {
+        let result = Args::try_parse_from(vec!["gr", "is", "comment", "--id", "7"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_comment_issue_cli_args() {
+        let options = issue_options(vec!["gr", "is", "comment", "--id", "7", "LGTM"]);
+        match options {
+            IssueOptions::Comment(args) => {
+                assert_eq!(7, args.id);
+                assert_eq!(Some("LGTM".to_string()), args.comment);
+            }
+            _ => panic!("Expected IssueOptions::Comment"),
+        }
+    }
+}
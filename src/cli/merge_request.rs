@@ -1,13 +1,16 @@
 use std::option::Option;
 
-use clap::{Parser, ValueEnum};
+use clap::{ArgGroup, Parser, ValueEnum};
 
 use crate::{
     cmds::merge_request::{
-        CommentMergeRequestCliArgs, MergeRequestCliArgs, MergeRequestGetCliArgs,
-        MergeRequestListCliArgs,
+        CommentMergeRequestCliArgs, MergeQueueGetCliArgs, MergeQueueListCliArgs,
+        MergeRequestCliArgs, MergeRequestGetCliArgs, MergeRequestListCliArgs,
+        MergeRequestRebaseCliArgs, MergeRequestSweepCliArgs,
     },
+    git::UpdateStrategy,
     remote::MergeRequestState,
+    time::Milliseconds,
 };
 
 use super::common::{GetArgs, ListArgs};
@@ -32,10 +35,129 @@ enum MergeRequestSubcommand {
     Comment(CommentMergeRequest),
     #[clap(about = "Close a merge request")]
     Close(CloseMergeRequest),
+    #[clap(about = "Reopen a closed merge request")]
+    Reopen(ReopenMergeRequest),
+    #[clap(about = "Mark a merge request as draft")]
+    Draft(DraftMergeRequest),
+    #[clap(about = "Mark a draft merge request as ready for review")]
+    Ready(ReadyMergeRequest),
+    #[clap(about = "Comment on and/or close all the open merge requests with a label")]
+    Sweep(SweepMergeRequests),
     /// Get a merge request
     Get(GetMergeRequest),
     #[clap(about = "List merge requests", visible_alias = "ls")]
     List(ListMergeRequest),
+    #[clap(about = "List the checks and statuses of a merge request head commit")]
+    Checks(MergeRequestChecks),
+    #[clap(about = "Show who approved a merge request and who still has to")]
+    Approvers(GetMergeRequest),
+    #[clap(about = "Show the changes of a merge request as a unified diff")]
+    Diff(DiffMergeRequest),
+    #[clap(about = "Show the changes of a merge request since you last approved or reviewed it")]
+    ChangedSinceReview(DiffMergeRequest),
+    #[clap(about = "Rebase a merge request onto its target branch in the remote")]
+    Rebase(RebaseMergeRequest),
+    #[clap(
+        subcommand,
+        about = "Merge queue (Github) and merge train (Gitlab) operations"
+    )]
+    Queue(MergeQueueSubcommand),
+    #[clap(subcommand, about = "Add or remove labels of a merge request")]
+    Label(MergeRequestLabelSubcommand),
+}
+
+#[derive(Parser)]
+enum MergeRequestLabelSubcommand {
+    #[clap(about = "Add labels to a merge request")]
+    Add(LabelMergeRequest),
+    #[clap(about = "Remove labels from a merge request")]
+    Remove(LabelMergeRequest),
+}
+
+#[derive(Parser)]
+struct LabelMergeRequest {
+    /// Id of the merge request
+    #[clap()]
+    id: i64,
+    /// Labels to add or remove
+    #[clap(required = true, value_name = "LABEL")]
+    labels: Vec<String>,
+}
+
+#[derive(Parser)]
+struct DiffMergeRequest {
+    /// Id of the merge request
+    #[clap()]
+    id: i64,
+    /// Print to STDOUT instead of going through the pager set in PAGER
+    #[clap(long)]
+    no_pager: bool,
+}
+
+#[derive(Parser)]
+struct RebaseMergeRequest {
+    /// Id of the merge request
+    #[clap()]
+    id: i64,
+    /// Seconds to wait for the rebase to finish
+    #[clap(long, value_name = "SECONDS", default_value = "300")]
+    timeout: u64,
+}
+
+#[derive(Parser)]
+struct MergeRequestChecks {
+    /// Id of the merge request
+    #[clap()]
+    id: i64,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+enum MergeQueueSubcommand {
+    #[clap(
+        about = "List the merge requests queued for a branch",
+        visible_alias = "ls"
+    )]
+    List(ListMergeQueue),
+    #[clap(about = "Get the queue position and status of a merge request")]
+    Get(GetMergeQueueEntry),
+    #[clap(about = "Add a merge request to the merge queue")]
+    Add(EnqueueMergeRequest),
+    #[clap(about = "Remove a merge request from the merge queue")]
+    Remove(DequeueMergeRequest),
+}
+
+#[derive(Parser)]
+struct ListMergeQueue {
+    /// Target branch of the queue. Defaults to the project's default branch
+    #[clap(long)]
+    branch: Option<String>,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+struct GetMergeQueueEntry {
+    /// Id of the merge request
+    #[clap()]
+    id: i64,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+struct EnqueueMergeRequest {
+    /// Id of the merge request
+    #[clap()]
+    id: i64,
+}
+
+#[derive(Parser)]
+struct DequeueMergeRequest {
+    /// Id of the merge request
+    #[clap()]
+    id: i64,
 }
 
 #[derive(Parser)]
@@ -50,14 +172,18 @@ struct GetMergeRequest {
 #[derive(Parser)]
 struct CommentMergeRequest {
     /// Id of the merge request
-    #[clap(long)]
-    pub id: i64,
+    #[clap(long, required_unless_present = "list")]
+    pub id: Option<i64>,
     /// Comment to add to the merge request
     #[clap(group = "comment_msg")]
     pub comment: Option<String>,
     /// Gather comment from the specified file. If "-" is provided, read from STDIN
     #[clap(long, value_name = "FILE", group = "comment_msg")]
     pub comment_from_file: Option<String>,
+    /// List the comments of the merge request with the given id, grouped in
+    /// threads
+    #[clap(long, value_name = "ID", conflicts_with_all = ["id", "comment_msg"])]
+    pub list: Option<i64>,
 }
 
 #[derive(Parser)]
@@ -75,6 +201,16 @@ struct CreateMergeRequest {
     /// provided, read from STDIN
     #[clap(long, value_name = "FILE")]
     pub description_from_file: Option<String>,
+    /// Prefill the description with a template of the repository, found in
+    /// .gitlab/merge_request_templates/NAME.md or
+    /// .github/PULL_REQUEST_TEMPLATE/NAME.md. The single
+    /// .github/PULL_REQUEST_TEMPLATE.md is named "default"
+    #[clap(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = ["description", "description_from_file"]
+    )]
+    pub template: Option<String>,
     /// Accept the default title, description, and target branch
     #[clap(long, short)]
     pub auto: bool,
@@ -93,9 +229,71 @@ struct CreateMergeRequest {
     /// Adds and commits all changes before creating the merge request
     #[clap(long)]
     pub commit: Option<String>,
+    /// GPG sign the commit created with --commit. Signing is also done when
+    /// commit.gpgsign is enabled in the git configuration
+    #[clap(long, short = 'S', requires = "commit")]
+    pub gpg_sign: bool,
     /// Set up the merge request as draft
     #[clap(long, visible_alias = "wip")]
     pub draft: bool,
+    /// How to bring in new commits from the target branch before pushing.
+    /// Overrides the merge_request_update_strategy configuration. Defaults to
+    /// rebase
+    #[clap(long, value_name = "STRATEGY", group = "update")]
+    pub update_strategy: Option<UpdateStrategyCli>,
+    /// Push the branch without rebasing it. Same as --update-strategy none
+    #[clap(long, group = "update")]
+    pub no_rebase: bool,
+    /// Push option sent to the remote when pushing the branch. Ex: ci.skip,
+    /// merge_request.label=bug. Can be given multiple times
+    #[clap(long = "push-option", short = 'o', value_name = "OPTION")]
+    pub push_options: Vec<String>,
+    /// Overwrite the remote branch if it has not changed since it was last
+    /// fetched. Needed after rewriting the history of a pushed branch
+    #[clap(long)]
+    pub force_with_lease: bool,
+    /// Wait for the pipeline of the pushed commit to finish. Exits with an
+    /// error if it does not pass
+    #[clap(long)]
+    pub wait: bool,
+    /// Username to assign the merge request to. Can be given multiple times.
+    /// Multiple assignees in Gitlab require a premium tier
+    #[clap(long = "assignee", value_name = "USERNAME")]
+    pub assignees: Vec<String>,
+    /// Assign the merge request to the users in the group. Groups are
+    /// configured with assignee_group_<NAME>=user1,user2
+    #[clap(long = "assignee-group", value_name = "NAME")]
+    pub assignee_groups: Vec<String>,
+    /// Username to request a review from. Can be given multiple times
+    #[clap(long = "reviewer", value_name = "USERNAME")]
+    pub reviewers: Vec<String>,
+    /// Open the merge request against this project when working from a
+    /// fork. Defaults to the project of the `upstream` git remote, if any
+    #[clap(long, value_name = "OWNER/REPO")]
+    pub upstream: Option<String>,
+    /// Title of the milestone to add the merge request to
+    #[clap(long, value_name = "TITLE")]
+    pub milestone: Option<String>,
+    /// Label to add to the merge request. Can be given multiple times
+    #[clap(long = "label", value_name = "LABEL")]
+    pub labels: Vec<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum UpdateStrategyCli {
+    Rebase,
+    Merge,
+    None,
+}
+
+impl From<UpdateStrategyCli> for UpdateStrategy {
+    fn from(strategy: UpdateStrategyCli) -> Self {
+        match strategy {
+            UpdateStrategyCli::Rebase => UpdateStrategy::Rebase,
+            UpdateStrategyCli::Merge => UpdateStrategy::Merge,
+            UpdateStrategyCli::None => UpdateStrategy::None,
+        }
+    }
 }
 
 #[derive(ValueEnum, Clone, PartialEq, Debug)]
@@ -119,44 +317,112 @@ impl From<MergeRequestStateStateCli> for MergeRequestState {
 pub struct ListMergeRequest {
     #[clap()]
     pub state: MergeRequestStateStateCli,
+    /// Only merge requests opened by this username
+    #[clap(long, value_name = "USERNAME")]
+    pub author: Option<String>,
+    /// Only merge requests in the milestone with this title
+    #[clap(long, value_name = "TITLE")]
+    pub milestone: Option<String>,
+    /// Only merge requests opened by the authenticated user
+    #[clap(long, conflicts_with_all = ["author", "needs_my_review"])]
+    pub mine: bool,
+    /// Only merge requests waiting for the authenticated user: review
+    /// requested, not a draft and with CI passing
+    #[clap(long)]
+    pub needs_my_review: bool,
     #[command(flatten)]
     pub list_args: ListArgs,
 }
 
 #[derive(Parser)]
 struct MergeMergeRequest {
-    /// Id of the merge request
+    /// Id of the merge request. Picked among the open merge requests if not
+    /// given
     #[clap()]
-    pub id: i64,
+    pub id: Option<i64>,
 }
 
 #[derive(Parser)]
 struct CheckoutMergeRequest {
+    /// Id of the merge request. Picked among the open merge requests if not
+    /// given
+    #[clap()]
+    pub id: Option<i64>,
+}
+
+#[derive(Parser)]
+struct CloseMergeRequest {
+    /// Id of the merge request. Picked among the open merge requests if not
+    /// given
+    #[clap()]
+    pub id: Option<i64>,
+}
+
+#[derive(Parser)]
+struct ReopenMergeRequest {
     /// Id of the merge request
     #[clap()]
     pub id: i64,
 }
 
 #[derive(Parser)]
-struct CloseMergeRequest {
+struct DraftMergeRequest {
     /// Id of the merge request
     #[clap()]
     pub id: i64,
 }
 
 #[derive(Parser)]
-struct ApproveMergeRequest {
+struct ReadyMergeRequest {
     /// Id of the merge request
     #[clap()]
     pub id: i64,
 }
 
+#[derive(Parser)]
+#[clap(group(ArgGroup::new("sweep_action").required(true).multiple(true)))]
+struct SweepMergeRequests {
+    /// Sweep open merge requests with this label. Ex: stale
+    #[clap(long)]
+    pub label: String,
+    /// Comment to add to each merge request
+    #[clap(long, group = "sweep_action")]
+    pub comment: Option<String>,
+    /// Close each merge request
+    #[clap(long, group = "sweep_action")]
+    pub close: bool,
+    /// Show the merge requests that would be swept without changing them
+    #[clap(long)]
+    pub dry_run: bool,
+    /// Time to wait in milliseconds between merge requests
+    #[clap(long, value_name = "MILLISECONDS")]
+    pub throttle: Option<u64>,
+    /// Skip the merge requests swept by a previous run that was interrupted
+    #[clap(long)]
+    pub resume: bool,
+}
+
+#[derive(Parser)]
+struct ApproveMergeRequest {
+    /// Id of the merge request. Picked among the open merge requests if not
+    /// given
+    #[clap()]
+    pub id: Option<i64>,
+    /// Confirm all the items of the review checklist in the configuration
+    /// without being asked
+    #[clap(long)]
+    pub checklist_ok: bool,
+}
+
 impl From<ListMergeRequest> for MergeRequestOptions {
     fn from(options: ListMergeRequest) -> Self {
-        MergeRequestOptions::List(MergeRequestListCliArgs::new(
-            options.state.into(),
-            options.list_args.into(),
-        ))
+        MergeRequestOptions::List(
+            MergeRequestListCliArgs::new(options.state.into(), options.list_args.into())
+                .with_author(options.author)
+                .with_milestone(options.milestone)
+                .with_mine(options.mine)
+                .with_needs_my_review(options.needs_my_review),
+        )
     }
 }
 
@@ -178,9 +444,46 @@ impl From<CloseMergeRequest> for MergeRequestOptions {
     }
 }
 
+impl From<ReopenMergeRequest> for MergeRequestOptions {
+    fn from(options: ReopenMergeRequest) -> Self {
+        MergeRequestOptions::Reopen { id: options.id }
+    }
+}
+
+impl From<DraftMergeRequest> for MergeRequestOptions {
+    fn from(options: DraftMergeRequest) -> Self {
+        MergeRequestOptions::Draft { id: options.id }
+    }
+}
+
+impl From<ReadyMergeRequest> for MergeRequestOptions {
+    fn from(options: ReadyMergeRequest) -> Self {
+        MergeRequestOptions::Ready { id: options.id }
+    }
+}
+
+impl From<SweepMergeRequests> for MergeRequestOptions {
+    fn from(options: SweepMergeRequests) -> Self {
+        MergeRequestOptions::Sweep(
+            MergeRequestSweepCliArgs::builder()
+                .label(options.label)
+                .comment(options.comment)
+                .close(options.close)
+                .dry_run(options.dry_run)
+                .throttle_time(options.throttle.map(Milliseconds::from))
+                .resume(options.resume)
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
 impl From<ApproveMergeRequest> for MergeRequestOptions {
     fn from(options: ApproveMergeRequest) -> Self {
-        MergeRequestOptions::Approve { id: options.id }
+        MergeRequestOptions::Approve {
+            id: options.id,
+            checklist_ok: options.checklist_ok,
+        }
     }
 }
 
@@ -192,9 +495,84 @@ impl From<MergeRequestCommand> for MergeRequestOptions {
             MergeRequestSubcommand::Merge(options) => options.into(),
             MergeRequestSubcommand::Checkout(options) => options.into(),
             MergeRequestSubcommand::Close(options) => options.into(),
+            MergeRequestSubcommand::Reopen(options) => options.into(),
+            MergeRequestSubcommand::Draft(options) => options.into(),
+            MergeRequestSubcommand::Ready(options) => options.into(),
+            MergeRequestSubcommand::Sweep(options) => options.into(),
             MergeRequestSubcommand::Comment(options) => options.into(),
             MergeRequestSubcommand::Get(options) => options.into(),
             MergeRequestSubcommand::Approve(options) => options.into(),
+            MergeRequestSubcommand::Checks(options) => MergeRequestOptions::Checks(
+                MergeRequestGetCliArgs::builder()
+                    .id(options.id)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            MergeRequestSubcommand::Approvers(options) => MergeRequestOptions::Approvers(
+                MergeRequestGetCliArgs::builder()
+                    .id(options.id)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            MergeRequestSubcommand::Diff(options) => MergeRequestOptions::Diff {
+                id: options.id,
+                no_pager: options.no_pager,
+            },
+            MergeRequestSubcommand::ChangedSinceReview(options) => {
+                MergeRequestOptions::ChangedSinceReview {
+                    id: options.id,
+                    no_pager: options.no_pager,
+                }
+            }
+            MergeRequestSubcommand::Rebase(options) => MergeRequestOptions::Rebase(
+                MergeRequestRebaseCliArgs::builder()
+                    .id(options.id)
+                    .timeout(options.timeout)
+                    .build()
+                    .unwrap(),
+            ),
+            MergeRequestSubcommand::Queue(options) => MergeRequestOptions::Queue(options.into()),
+            MergeRequestSubcommand::Label(options) => MergeRequestOptions::Label(options.into()),
+        }
+    }
+}
+
+impl From<MergeRequestLabelSubcommand> for MergeRequestLabelOptions {
+    fn from(options: MergeRequestLabelSubcommand) -> Self {
+        match options {
+            MergeRequestLabelSubcommand::Add(options) => MergeRequestLabelOptions::Add {
+                id: options.id,
+                labels: options.labels,
+            },
+            MergeRequestLabelSubcommand::Remove(options) => MergeRequestLabelOptions::Remove {
+                id: options.id,
+                labels: options.labels,
+            },
+        }
+    }
+}
+
+impl From<MergeQueueSubcommand> for MergeQueueOptions {
+    fn from(options: MergeQueueSubcommand) -> Self {
+        match options {
+            MergeQueueSubcommand::List(options) => MergeQueueOptions::List(
+                MergeQueueListCliArgs::builder()
+                    .branch(options.branch)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            MergeQueueSubcommand::Get(options) => MergeQueueOptions::Get(
+                MergeQueueGetCliArgs::builder()
+                    .id(options.id)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            MergeQueueSubcommand::Add(options) => MergeQueueOptions::Add { id: options.id },
+            MergeQueueSubcommand::Remove(options) => MergeQueueOptions::Remove { id: options.id },
         }
     }
 }
@@ -207,6 +585,7 @@ impl From<CreateMergeRequest> for MergeRequestOptions {
                 .title_from_commit(options.title_from_commit)
                 .description(options.description)
                 .description_from_file(options.description_from_file)
+                .template(options.template)
                 .target_branch(options.target_branch)
                 .auto(options.auto)
                 .refresh_cache(options.refresh)
@@ -214,6 +593,21 @@ impl From<CreateMergeRequest> for MergeRequestOptions {
                 .accept_summary(options.yes)
                 .commit(options.commit)
                 .draft(options.draft)
+                .update_strategy(if options.no_rebase {
+                    Some(UpdateStrategy::None)
+                } else {
+                    options.update_strategy.map(|strategy| strategy.into())
+                })
+                .push_options(options.push_options)
+                .force_with_lease(options.force_with_lease)
+                .wait(options.wait)
+                .gpg_sign(options.gpg_sign)
+                .assignees(options.assignees)
+                .assignee_groups(options.assignee_groups)
+                .reviewers(options.reviewers)
+                .upstream(options.upstream)
+                .milestone(options.milestone)
+                .labels(options.labels)
                 .build()
                 .unwrap(),
         )
@@ -222,9 +616,13 @@ impl From<CreateMergeRequest> for MergeRequestOptions {
 
 impl From<CommentMergeRequest> for MergeRequestOptions {
     fn from(options: CommentMergeRequest) -> Self {
+        if let Some(id) = options.list {
+            return MergeRequestOptions::ListComments { id };
+        }
         MergeRequestOptions::Comment(
             CommentMergeRequestCliArgs::builder()
-                .id(options.id)
+                // The id is required at the CLI unless listing.
+                .id(options.id.unwrap())
                 .comment(options.comment)
                 .comment_from_file(options.comment_from_file)
                 .build()
@@ -250,10 +648,35 @@ pub enum MergeRequestOptions {
     Get(MergeRequestGetCliArgs),
     List(MergeRequestListCliArgs),
     Comment(CommentMergeRequestCliArgs),
-    Approve { id: i64 },
-    Merge { id: i64 },
-    Checkout { id: i64 },
-    Close { id: i64 },
+    ListComments { id: i64 },
+    // Without an id, the merge request is picked interactively.
+    Approve { id: Option<i64>, checklist_ok: bool },
+    Merge { id: Option<i64> },
+    Checkout { id: Option<i64> },
+    Close { id: Option<i64> },
+    Reopen { id: i64 },
+    Draft { id: i64 },
+    Ready { id: i64 },
+    Sweep(MergeRequestSweepCliArgs),
+    Checks(MergeRequestGetCliArgs),
+    Approvers(MergeRequestGetCliArgs),
+    Diff { id: i64, no_pager: bool },
+    ChangedSinceReview { id: i64, no_pager: bool },
+    Rebase(MergeRequestRebaseCliArgs),
+    Queue(MergeQueueOptions),
+    Label(MergeRequestLabelOptions),
+}
+
+pub enum MergeRequestLabelOptions {
+    Add { id: i64, labels: Vec<String> },
+    Remove { id: i64, labels: Vec<String> },
+}
+
+pub enum MergeQueueOptions {
+    List(MergeQueueListCliArgs),
+    Get(MergeQueueGetCliArgs),
+    Add { id: i64 },
+    Remove { id: i64 },
 }
 
 #[cfg(test)]
@@ -265,7 +688,7 @@ mod test {
     #[test]
     fn test_list_merge_requests_cli_args() {
         let args = Args::parse_from(vec!["gr", "mr", "list", "opened"]);
-        let list_merge_request = match args.command {
+        let list_merge_request = match args.command.unwrap() {
             Command::MergeRequest(MergeRequestCommand {
                 subcommand: MergeRequestSubcommand::List(options),
             }) => {
@@ -284,14 +707,82 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_list_merge_requests_by_author_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "list", "opened", "--author", "tsmith"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(MergeRequestCommand {
+                subcommand: MergeRequestSubcommand::List(options),
+            }) => options.into(),
+            _ => panic!("Expected MergeRequestCommand::List"),
+        };
+        match options {
+            MergeRequestOptions::List(args) => {
+                assert_eq!(Some("tsmith".to_string()), args.author);
+            }
+            _ => panic!("Expected MergeRequestOptions::List"),
+        }
+    }
+
+    #[test]
+    fn test_list_merge_requests_needing_my_review_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "list", "opened", "--needs-my-review"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(MergeRequestCommand {
+                subcommand: MergeRequestSubcommand::List(options),
+            }) => options.into(),
+            _ => panic!("Expected MergeRequestCommand::List"),
+        };
+        match options {
+            MergeRequestOptions::List(args) => {
+                assert!(args.needs_my_review);
+                assert!(!args.mine);
+            }
+            _ => panic!("Expected MergeRequestOptions::List"),
+        }
+        let result = Args::try_parse_from(vec![
+            "gr",
+            "mr",
+            "list",
+            "opened",
+            "--mine",
+            "--needs-my-review",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_list_merge_requests_by_milestone_cli_args() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "mr",
+            "list",
+            "opened",
+            "--milestone",
+            "Release 1.0",
+        ]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(MergeRequestCommand {
+                subcommand: MergeRequestSubcommand::List(options),
+            }) => options.into(),
+            _ => panic!("Expected MergeRequestCommand::List"),
+        };
+        match options {
+            MergeRequestOptions::List(args) => {
+                assert_eq!(Some("Release 1.0".to_string()), args.milestone);
+            }
+            _ => panic!("Expected MergeRequestOptions::List"),
+        }
+    }
+
     #[test]
     fn test_merge_merge_request_cli_args() {
         let args = Args::parse_from(vec!["gr", "mr", "merge", "123"]);
-        let merge_merge_request = match args.command {
+        let merge_merge_request = match args.command.unwrap() {
             Command::MergeRequest(MergeRequestCommand {
                 subcommand: MergeRequestSubcommand::Merge(options),
             }) => {
-                assert_eq!(options.id, 123);
+                assert_eq!(options.id, Some(123));
                 options
             }
             _ => panic!("Expected MergeRequestCommand::Merge"),
@@ -300,7 +791,7 @@ mod test {
         let options: MergeRequestOptions = merge_merge_request.into();
         match options {
             MergeRequestOptions::Merge { id } => {
-                assert_eq!(id, 123);
+                assert_eq!(id, Some(123));
             }
             _ => panic!("Expected MergeRequestOptions::Merge"),
         }
@@ -309,11 +800,11 @@ mod test {
     #[test]
     fn test_checkout_merge_request_cli_args() {
         let args = Args::parse_from(vec!["gr", "mr", "checkout", "123"]);
-        let checkout_merge_request = match args.command {
+        let checkout_merge_request = match args.command.unwrap() {
             Command::MergeRequest(MergeRequestCommand {
                 subcommand: MergeRequestSubcommand::Checkout(options),
             }) => {
-                assert_eq!(options.id, 123);
+                assert_eq!(options.id, Some(123));
                 options
             }
             _ => panic!("Expected MergeRequestCommand::Checkout"),
@@ -322,7 +813,7 @@ mod test {
         let options: MergeRequestOptions = checkout_merge_request.into();
         match options {
             MergeRequestOptions::Checkout { id } => {
-                assert_eq!(id, 123);
+                assert_eq!(id, Some(123));
             }
             _ => panic!("Expected MergeRequestOptions::Checkout"),
         }
@@ -331,11 +822,11 @@ mod test {
     #[test]
     fn test_close_merge_request_cli_args() {
         let args = Args::parse_from(vec!["gr", "mr", "close", "123"]);
-        let close_merge_request = match args.command {
+        let close_merge_request = match args.command.unwrap() {
             Command::MergeRequest(MergeRequestCommand {
                 subcommand: MergeRequestSubcommand::Close(options),
             }) => {
-                assert_eq!(options.id, 123);
+                assert_eq!(options.id, Some(123));
                 options
             }
             _ => panic!("Expected MergeRequestCommand::Close"),
@@ -344,20 +835,85 @@ mod test {
         let options: MergeRequestOptions = close_merge_request.into();
         match options {
             MergeRequestOptions::Close { id } => {
-                assert_eq!(id, 123);
+                assert_eq!(id, Some(123));
             }
             _ => panic!("Expected MergeRequestOptions::Close"),
         }
     }
 
+    #[test]
+    fn test_reopen_merge_request_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "reopen", "123"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Reopen { id } => assert_eq!(123, id),
+            _ => panic!("Expected MergeRequestOptions::Reopen"),
+        }
+    }
+
+    #[test]
+    fn test_draft_and_ready_merge_request_cli_args() {
+        let options = |args: Vec<&str>| -> MergeRequestOptions {
+            match Args::parse_from(args).command.unwrap() {
+                Command::MergeRequest(options) => options.into(),
+                _ => panic!("Expected MergeRequestCommand"),
+            }
+        };
+        match options(vec!["gr", "mr", "draft", "123"]) {
+            MergeRequestOptions::Draft { id } => assert_eq!(123, id),
+            _ => panic!("Expected MergeRequestOptions::Draft"),
+        }
+        match options(vec!["gr", "mr", "ready", "123"]) {
+            MergeRequestOptions::Ready { id } => assert_eq!(123, id),
+            _ => panic!("Expected MergeRequestOptions::Ready"),
+        }
+    }
+
+    #[test]
+    fn test_sweep_merge_requests_cli_args() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "mr",
+            "sweep",
+            "--label",
+            "stale",
+            "--close",
+            "--comment",
+            "Closing due to inactivity",
+            "--dry-run",
+        ]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Sweep(args) => {
+                assert_eq!("stale", args.label);
+                assert_eq!(Some("Closing due to inactivity".to_string()), args.comment);
+                assert!(args.close);
+                assert!(args.dry_run);
+                assert!(!args.resume);
+            }
+            _ => panic!("Expected MergeRequestOptions::Sweep"),
+        }
+    }
+
+    #[test]
+    fn test_sweep_merge_requests_requires_an_action() {
+        assert!(Args::try_parse_from(vec!["gr", "mr", "sweep", "--label", "stale"]).is_err());
+    }
+
     #[test]
     fn test_comment_merge_request_cli_args() {
         let args = Args::parse_from(vec!["gr", "mr", "comment", "--id", "123", "LGTM"]);
-        let comment_merge_request = match args.command {
+        let comment_merge_request = match args.command.unwrap() {
             Command::MergeRequest(MergeRequestCommand {
                 subcommand: MergeRequestSubcommand::Comment(options),
             }) => {
-                assert_eq!(options.id, 123);
+                assert_eq!(options.id, Some(123));
                 assert_eq!(options.comment, Some("LGTM".to_string()));
                 options
             }
@@ -374,10 +930,29 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_list_merge_request_comments_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "comment", "--list", "123"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(MergeRequestCommand {
+                subcommand: MergeRequestSubcommand::Comment(options),
+            }) => options.into(),
+            _ => panic!("Expected MergeRequestCommand::Comment"),
+        };
+        match options {
+            MergeRequestOptions::ListComments { id } => assert_eq!(123, id),
+            _ => panic!("Expected MergeRequestOptions::ListComments"),
+        }
+        assert!(
+            Args::try_parse_from(vec!["gr", "mr", "comment", "--list", "123", "LGTM"]).is_err()
+        );
+        assert!(Args::try_parse_from(vec!["gr", "mr", "comment", "LGTM"]).is_err());
+    }
+
     #[test]
     fn test_create_merge_request_cli_args() {
         let args = Args::parse_from(vec!["gr", "mr", "create", "--auto", "-y", "--browse"]);
-        let create_merge_request = match args.command {
+        let create_merge_request = match args.command.unwrap() {
             Command::MergeRequest(MergeRequestCommand {
                 subcommand: MergeRequestSubcommand::Create(options),
             }) => {
@@ -400,10 +975,36 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_create_merge_request_upstream_and_milestone_cli_args() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "mr",
+            "create",
+            "--upstream",
+            "jordilin/gitar",
+            "--milestone",
+            "Release 1.0",
+        ]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(MergeRequestCommand {
+                subcommand: MergeRequestSubcommand::Create(options),
+            }) => options.into(),
+            _ => panic!("Expected MergeRequestCommand::Create"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => {
+                assert_eq!(Some("jordilin/gitar".to_string()), args.upstream);
+                assert_eq!(Some("Release 1.0".to_string()), args.milestone);
+            }
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
     #[test]
     fn test_get_merge_request_details_cli_args() {
         let args = Args::parse_from(vec!["gr", "mr", "get", "123"]);
-        let get_merge_request = match args.command {
+        let get_merge_request = match args.command.unwrap() {
             Command::MergeRequest(MergeRequestCommand {
                 subcommand: MergeRequestSubcommand::Get(options),
             }) => {
@@ -425,7 +1026,7 @@ mod test {
     #[test]
     fn test_wip_alias_as_draft() {
         let args = Args::parse_from(vec!["gr", "mr", "create", "--auto", "--wip"]);
-        let create_merge_request = match args.command {
+        let create_merge_request = match args.command.unwrap() {
             Command::MergeRequest(MergeRequestCommand {
                 subcommand: MergeRequestSubcommand::Create(options),
             }) => {
@@ -443,4 +1044,361 @@ mod test {
             _ => panic!("Expected MergeRequestOptions::Create"),
         }
     }
+
+    #[test]
+    fn test_create_merge_request_update_strategy() {
+        let args = Args::parse_from(vec!["gr", "mr", "create", "--update-strategy", "merge"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => {
+                assert_eq!(Some(UpdateStrategy::Merge), args.update_strategy)
+            }
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_create_merge_request_multiple_assignees() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "mr",
+            "create",
+            "--assignee",
+            "jdoe",
+            "--assignee",
+            "tsmith",
+            "--assignee-group",
+            "backend",
+        ]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => {
+                assert_eq!(
+                    vec!["jdoe".to_string(), "tsmith".to_string()],
+                    args.assignees
+                );
+                assert_eq!(vec!["backend".to_string()], args.assignee_groups);
+            }
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_create_merge_request_with_reviewers() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "mr",
+            "create",
+            "--reviewer",
+            "jdoe",
+            "--reviewer",
+            "tsmith",
+        ]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => {
+                assert_eq!(
+                    vec!["jdoe".to_string(), "tsmith".to_string()],
+                    args.reviewers
+                );
+                assert!(args.assignees.is_empty());
+            }
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_create_merge_request_push_options() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "mr",
+            "create",
+            "--push-option",
+            "ci.skip",
+            "-o",
+            "merge_request.label=bug",
+        ]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => assert_eq!(
+                vec!["ci.skip".to_string(), "merge_request.label=bug".to_string()],
+                args.push_options
+            ),
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_create_merge_request_force_with_lease() {
+        let args = Args::parse_from(vec!["gr", "mr", "create", "--force-with-lease"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => assert!(args.force_with_lease),
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_create_merge_request_wait_for_pipeline() {
+        let args = Args::parse_from(vec!["gr", "mr", "create", "--auto", "--wait"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => assert!(args.wait),
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_create_merge_request_gpg_sign_commit() {
+        let args = Args::parse_from(vec!["gr", "mr", "create", "--commit", "Fix", "--gpg-sign"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => assert!(args.gpg_sign),
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_create_merge_request_gpg_sign_requires_commit() {
+        assert!(Args::try_parse_from(vec!["gr", "mr", "create", "--gpg-sign"]).is_err());
+    }
+
+    #[test]
+    fn test_create_merge_request_with_template() {
+        let args = Args::parse_from(vec!["gr", "mr", "create", "--template", "bugfix"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => {
+                assert_eq!(Some("bugfix".to_string()), args.template)
+            }
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+        assert!(Args::try_parse_from(vec![
+            "gr",
+            "mr",
+            "create",
+            "--template",
+            "bugfix",
+            "--description",
+            "Fix"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_create_merge_request_no_rebase() {
+        let args = Args::parse_from(vec!["gr", "mr", "create", "--no-rebase"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Create(args) => {
+                assert_eq!(Some(UpdateStrategy::None), args.update_strategy)
+            }
+            _ => panic!("Expected MergeRequestOptions::Create"),
+        }
+    }
+
+    #[test]
+    fn test_create_merge_request_no_rebase_conflicts_with_update_strategy() {
+        let result = Args::try_parse_from(vec![
+            "gr",
+            "mr",
+            "create",
+            "--no-rebase",
+            "--update-strategy",
+            "merge",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_merge_request_checks_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "checks", "42"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Checks(args) => assert_eq!(42, args.id),
+            _ => panic!("Expected MergeRequestOptions::Checks"),
+        }
+    }
+
+    #[test]
+    fn test_merge_request_approve_checklist_ok_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "approve", "42", "--checklist-ok"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Approve { id, checklist_ok } => {
+                assert_eq!(Some(42), id);
+                assert!(checklist_ok);
+            }
+            _ => panic!("Expected MergeRequestOptions::Approve"),
+        }
+    }
+
+    #[test]
+    fn test_merge_request_approvers_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "approvers", "42", "--format", "json"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Approvers(args) => assert_eq!(42, args.id),
+            _ => panic!("Expected MergeRequestOptions::Approvers"),
+        }
+    }
+
+    #[test]
+    fn test_merge_queue_list_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "queue", "list", "--branch", "release"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Queue(MergeQueueOptions::List(args)) => {
+                assert_eq!(Some("release".to_string()), args.branch);
+            }
+            _ => panic!("Expected MergeQueueOptions::List"),
+        }
+    }
+
+    #[test]
+    fn test_merge_queue_get_add_remove_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "queue", "get", "12"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Queue(MergeQueueOptions::Get(args)) => assert_eq!(12, args.id),
+            _ => panic!("Expected MergeQueueOptions::Get"),
+        }
+        let args = Args::parse_from(vec!["gr", "mr", "queue", "add", "12"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Queue(MergeQueueOptions::Add { id }) => assert_eq!(12, id),
+            _ => panic!("Expected MergeQueueOptions::Add"),
+        }
+        let args = Args::parse_from(vec!["gr", "mr", "queue", "remove", "12"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Queue(MergeQueueOptions::Remove { id }) => assert_eq!(12, id),
+            _ => panic!("Expected MergeQueueOptions::Remove"),
+        }
+    }
+
+    #[test]
+    fn test_merge_request_label_add_remove_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "label", "add", "12", "bug", "backend"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Label(MergeRequestLabelOptions::Add { id, labels }) => {
+                assert_eq!(12, id);
+                assert_eq!(vec!["bug", "backend"], labels);
+            }
+            _ => panic!("Expected MergeRequestLabelOptions::Add"),
+        }
+        let args = Args::parse_from(vec!["gr", "mr", "label", "remove", "12", "bug"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Label(MergeRequestLabelOptions::Remove { id, labels }) => {
+                assert_eq!(12, id);
+                assert_eq!(vec!["bug"], labels);
+            }
+            _ => panic!("Expected MergeRequestLabelOptions::Remove"),
+        }
+        assert!(Args::try_parse_from(vec!["gr", "mr", "label", "add", "12"]).is_err());
+    }
+
+    #[test]
+    fn test_diff_merge_request_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "diff", "7", "--no-pager"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Diff { id, no_pager } => {
+                assert_eq!(7, id);
+                assert!(no_pager);
+            }
+            _ => panic!("Expected MergeRequestOptions::Diff"),
+        }
+    }
+
+    #[test]
+    fn test_changed_since_review_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "changed-since-review", "7"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::ChangedSinceReview { id, no_pager } => {
+                assert_eq!(7, id);
+                assert!(!no_pager);
+            }
+            _ => panic!("Expected MergeRequestOptions::ChangedSinceReview"),
+        }
+    }
+
+    #[test]
+    fn test_rebase_merge_request_cli_args() {
+        let args = Args::parse_from(vec!["gr", "mr", "rebase", "7", "--timeout", "60"]);
+        let options: MergeRequestOptions = match args.command.unwrap() {
+            Command::MergeRequest(options) => options.into(),
+            _ => panic!("Expected MergeRequestCommand"),
+        };
+        match options {
+            MergeRequestOptions::Rebase(args) => {
+                assert_eq!(7, args.id);
+                assert_eq!(60, args.timeout);
+            }
+            _ => panic!("Expected MergeRequestOptions::Rebase"),
+        }
+    }
 }
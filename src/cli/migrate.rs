@@ -0,0 +1,107 @@
+use clap::Parser;
+
+use crate::cmds::migrate::MigrateMergeRequestCliArgs;
+
+#[derive(Parser)]
+pub struct MigrateCommand {
+    #[clap(subcommand)]
+    subcommand: MigrateSubcommand,
+}
+
+#[derive(Parser)]
+enum MigrateSubcommand {
+    #[clap(
+        about = "Replays open merge requests from one remote into another",
+        name = "mrs"
+    )]
+    MergeRequests(MigrateMergeRequests),
+}
+
+#[derive(Parser)]
+struct MigrateMergeRequests {
+    /// Source remote as <domain>/<owner>/<repo>. Ex: gitlab.com/org/repo
+    #[clap(long, value_name = "REMOTE")]
+    from: String,
+    /// Target remote as <domain>/<owner>/<repo>. Ex: github.com/org/repo
+    #[clap(long, value_name = "REMOTE")]
+    to: String,
+    /// Show the merge requests that would be migrated without opening them
+    #[clap(long)]
+    dry_run: bool,
+    /// Refresh the cache when reading the source merge requests
+    #[clap(long, short)]
+    refresh: bool,
+    /// Skip the merge requests migrated by a previous run that was
+    /// interrupted or had failures
+    #[clap(long)]
+    resume: bool,
+}
+
+pub enum MigrateOptions {
+    MergeRequests(MigrateMergeRequestCliArgs),
+}
+
+impl From<MigrateCommand> for MigrateOptions {
+    fn from(options: MigrateCommand) -> Self {
+        match options.subcommand {
+            MigrateSubcommand::MergeRequests(options) => options.into(),
+        }
+    }
+}
+
+impl From<MigrateMergeRequests> for MigrateOptions {
+    fn from(options: MigrateMergeRequests) -> Self {
+        MigrateOptions::MergeRequests(
+            MigrateMergeRequestCliArgs::builder()
+                .from(options.from)
+                .to(options.to)
+                .dry_run(options.dry_run)
+                .refresh_cache(options.refresh)
+                .resume(options.resume)
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    #[test]
+    fn test_migrate_merge_requests_cli_args() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "migrate",
+            "mrs",
+            "--from",
+            "gitlab.com/org/repo",
+            "--to",
+            "github.com/org/repo",
+            "--dry-run",
+        ]);
+        let migrate_command = match args.command.unwrap() {
+            Command::Migrate(MigrateCommand {
+                subcommand: MigrateSubcommand::MergeRequests(options),
+            }) => {
+                assert_eq!(options.from, "gitlab.com/org/repo");
+                assert_eq!(options.to, "github.com/org/repo");
+                assert!(options.dry_run);
+                options
+            }
+            _ => panic!("Expected MigrateCommand"),
+        };
+        let options: MigrateOptions = migrate_command.into();
+        match options {
+            MigrateOptions::MergeRequests(options) => {
+                assert_eq!(options.from, "gitlab.com/org/repo");
+                assert_eq!(options.to, "github.com/org/repo");
+                assert!(options.dry_run);
+                assert!(!options.refresh_cache);
+                assert!(!options.resume);
+            }
+        }
+    }
+}
@@ -1,8 +1,15 @@
 use clap::Parser;
 
-use crate::cmds::{merge_request::MergeRequestListCliArgs, project::ProjectListCliArgs};
+use crate::cmds::{
+    merge_request::MergeRequestListCliArgs,
+    my::{dashboard::DashboardCliArgs, MyAllCliArgs},
+    project::{ProjectListCliArgs, ProjectListFilters},
+};
 
-use super::{common::ListArgs, merge_request::ListMergeRequest, project::ListProject};
+use super::{
+    common::{GetArgs, ListArgs},
+    merge_request::ListMergeRequest,
+};
 
 #[derive(Parser)]
 pub struct MyCommand {
@@ -15,14 +22,26 @@ enum MySubcommand {
     #[clap(about = "Lists your assigned merge requests", name = "mr")]
     MergeRequest(ListMergeRequest),
     #[clap(about = "Lists your projects", name = "pj")]
-    Project(ListProject),
+    Project(ListMyProject),
     #[clap(about = "Lists your starred projects", name = "st")]
     Star(ListStar),
+    #[clap(
+        about = "Lists your assigned and review requested merge requests across remotes",
+        name = "all"
+    )]
+    All(ListAll),
+    #[clap(
+        about = "Summary of your assigned merge requests, the ones awaiting your review and your failing pipelines across remotes",
+        name = "dash"
+    )]
+    Dashboard(ListAll),
 }
 
 pub enum MyOptions {
     MergeRequest(MergeRequestListCliArgs),
     Project(ProjectListCliArgs),
+    All(MyAllCliArgs),
+    Dashboard(DashboardCliArgs),
 }
 
 impl From<MyCommand> for MyOptions {
@@ -31,24 +50,64 @@ impl From<MyCommand> for MyOptions {
             MySubcommand::MergeRequest(options) => options.into(),
             MySubcommand::Project(options) => options.into(),
             MySubcommand::Star(options) => options.into(),
+            MySubcommand::All(options) => options.into(),
+            MySubcommand::Dashboard(options) => MyOptions::Dashboard(
+                DashboardCliArgs::builder()
+                    .domains(options.domains)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
         }
     }
 }
 
 impl From<ListMergeRequest> for MyOptions {
     fn from(options: ListMergeRequest) -> Self {
-        MyOptions::MergeRequest(MergeRequestListCliArgs::new(
-            options.state.into(),
-            options.list_args.into(),
-        ))
+        MyOptions::MergeRequest(
+            MergeRequestListCliArgs::new(options.state.into(), options.list_args.into())
+                .with_author(options.author),
+        )
     }
 }
 
-impl From<ListProject> for MyOptions {
-    fn from(options: ListProject) -> Self {
+#[derive(Parser)]
+pub struct ListMyProject {
+    /// Projects owned by you
+    #[clap(long, help_heading = "Project filters")]
+    pub owned: bool,
+    /// Projects you are a member or collaborator of
+    #[clap(long, help_heading = "Project filters")]
+    pub member: bool,
+    /// Your starred projects
+    #[clap(long, help_heading = "Project filters")]
+    pub starred: bool,
+    /// Archived projects only. In Github, only repositories you own
+    #[clap(long, help_heading = "Project filters")]
+    pub archived: bool,
+    /// Projects whose name matches the term. In Github, only repositories you
+    /// own
+    #[clap(long, value_name = "TERM", help_heading = "Project filters")]
+    pub search: Option<String>,
+    #[clap(flatten)]
+    pub list_args: ListArgs,
+}
+
+impl From<ListMyProject> for MyOptions {
+    fn from(options: ListMyProject) -> Self {
         MyOptions::Project(
             ProjectListCliArgs::builder()
                 .list_args(options.list_args.into())
+                .stars(options.starred)
+                .filters(
+                    ProjectListFilters::builder()
+                        .owned(options.owned)
+                        .member(options.member)
+                        .archived(options.archived)
+                        .search(options.search)
+                        .build()
+                        .unwrap(),
+                )
                 .build()
                 .unwrap(),
         )
@@ -73,6 +132,28 @@ impl From<ListStar> for MyOptions {
     }
 }
 
+#[derive(Parser)]
+pub struct ListAll {
+    /// Comma separated list of domains to query. Defaults to all the domains
+    /// in the configuration file.
+    #[clap(long, value_delimiter = ',', value_name = "DOMAINS")]
+    pub domains: Vec<String>,
+    #[clap(flatten)]
+    pub get_args: GetArgs,
+}
+
+impl From<ListAll> for MyOptions {
+    fn from(options: ListAll) -> Self {
+        MyOptions::All(
+            MyAllCliArgs::builder()
+                .domains(options.domains)
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,7 +165,7 @@ mod tests {
     #[test]
     fn test_my_merge_request_cli_args() {
         let args = Args::parse_from(vec!["gr", "my", "mr", "opened"]);
-        let my_command = match args.command {
+        let my_command = match args.command.unwrap() {
             Command::My(MyCommand {
                 subcommand: MySubcommand::MergeRequest(options),
             }) => {
@@ -105,7 +186,7 @@ mod tests {
     #[test]
     fn test_my_projects_cli_args() {
         let args = Args::parse_from(vec!["gr", "my", "pj"]);
-        let my_command = match args.command {
+        let my_command = match args.command.unwrap() {
             Command::My(MyCommand {
                 subcommand: MySubcommand::Project(options),
             }) => options,
@@ -118,10 +199,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_my_projects_cli_filters() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "my",
+            "pj",
+            "--owned",
+            "--member",
+            "--archived",
+            "--search",
+            "gitar",
+        ]);
+        let my_command = match args.command.unwrap() {
+            Command::My(MyCommand {
+                subcommand: MySubcommand::Project(options),
+            }) => options,
+            _ => panic!("Expected MyCommand"),
+        };
+        let options: MyOptions = my_command.into();
+        match options {
+            MyOptions::Project(options) => {
+                assert!(!options.stars);
+                assert!(options.filters.owned);
+                assert!(options.filters.member);
+                assert!(options.filters.archived);
+                assert_eq!(Some("gitar".to_string()), options.filters.search);
+            }
+            _ => panic!("Expected MyOptions::Project"),
+        }
+    }
+
+    #[test]
+    fn test_my_projects_cli_starred() {
+        let args = Args::parse_from(vec!["gr", "my", "pj", "--starred"]);
+        let my_command = match args.command.unwrap() {
+            Command::My(MyCommand {
+                subcommand: MySubcommand::Project(options),
+            }) => options,
+            _ => panic!("Expected MyCommand"),
+        };
+        let options: MyOptions = my_command.into();
+        match options {
+            MyOptions::Project(options) => assert!(options.stars),
+            _ => panic!("Expected MyOptions::Project"),
+        }
+    }
+
+    #[test]
+    fn test_my_all_cli_args() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "my",
+            "all",
+            "--domains",
+            "gitlab.com,github.com",
+        ]);
+        let my_command = match args.command.unwrap() {
+            Command::My(MyCommand {
+                subcommand: MySubcommand::All(options),
+            }) => options,
+            _ => panic!("Expected MyCommand"),
+        };
+        let options: MyOptions = my_command.into();
+        match options {
+            MyOptions::All(options) => {
+                assert_eq!(vec!["gitlab.com", "github.com"], options.domains);
+            }
+            _ => panic!("Expected MyOptions::All"),
+        }
+    }
+
+    #[test]
+    fn test_my_dashboard_cli_args() {
+        let args = Args::parse_from(vec!["gr", "my", "dash", "--domains", "gitlab.com"]);
+        let options: MyOptions = match args.command.unwrap() {
+            Command::My(options) => options.into(),
+            _ => panic!("Expected MyCommand"),
+        };
+        match options {
+            MyOptions::Dashboard(options) => {
+                assert_eq!(vec!["gitlab.com"], options.domains);
+            }
+            _ => panic!("Expected MyOptions::Dashboard"),
+        }
+    }
+
     #[test]
     fn test_my_stars_cli_args() {
         let args = Args::parse_from(vec!["gr", "my", "st"]);
-        let my_command = match args.command {
+        let my_command = match args.command.unwrap() {
             Command::My(MyCommand {
                 subcommand: MySubcommand::Star(options),
             }) => options,
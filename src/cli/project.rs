@@ -1,6 +1,18 @@
-use clap::Parser;
+use clap::{ArgGroup, Parser, ValueEnum};
 
-use crate::cmds::project::ProjectMetadataGetCliArgs;
+use crate::{
+    cmds::project::{
+        AccessLevel, AccessRequestApproveBodyArgs, ApprovalSettingsBodyArgs,
+        ApprovalSettingsSetCliArgs, BranchAccess, BranchProtectionBodyArgs,
+        BranchProtectionGetCliArgs, BranchProtectionSetCliArgs, CommitGetCliArgs,
+        CommitListCliArgs, CommitPickCliArgs, FileCatCliArgs, ProjectAuditCliArgs,
+        ProjectMetadataGetCliArgs, ProjectNewCliArgs, ProjectTemplateBodyArgs, PushRulesBodyArgs,
+        PushRulesSetCliArgs, RulesetGetCliArgs, VariableDeleteCliArgs, VariableListCliArgs,
+        VariableSetCliArgs,
+    },
+    display::Format,
+    remote::{GetRemoteCliArgs, ListRemoteCliArgs},
+};
 
 use super::common::{GetArgs, ListArgs};
 
@@ -14,6 +26,596 @@ pub struct ProjectCommand {
 enum ProjectSubcommand {
     #[clap(about = "Gather project information metadata")]
     Info(ProjectInfo),
+    #[clap(subcommand, about = "Merge request approval settings")]
+    Approvals(ApprovalsSubcommand),
+    #[clap(subcommand, about = "Project members and their roles")]
+    Members(MembersSubcommand),
+    #[clap(
+        subcommand,
+        name = "access-requests",
+        about = "Handle requests to join the project. Gitlab only"
+    )]
+    AccessRequests(AccessRequestsSubcommand),
+    #[clap(about = "Create a project from a template")]
+    New(NewProject),
+    #[clap(
+        name = "audit-visibility",
+        about = "Report visibility, license and default branch protection of the projects of a group as CSV"
+    )]
+    AuditVisibility(AuditVisibility),
+    #[clap(
+        subcommand,
+        name = "var",
+        about = "CI/CD variables in Gitlab, Actions secrets in Github"
+    )]
+    Variables(VariablesSubcommand),
+    #[clap(subcommand, about = "Branch protection rules")]
+    Protect(ProtectSubcommand),
+    #[clap(about = "Print the contents of a file of the repository without cloning it")]
+    Cat(CatFile),
+    #[clap(
+        subcommand,
+        about = "Repository rulesets in Github, push rules in Gitlab"
+    )]
+    Rulesets(RulesetsSubcommand),
+    #[clap(
+        subcommand,
+        name = "push-rules",
+        about = "Checks Gitlab runs on every push. Gitlab only"
+    )]
+    PushRules(PushRulesSubcommand),
+    #[clap(about = "List commits of a branch with their CI status")]
+    Commits(ListCommits),
+    #[clap(about = "Get a commit with its CI status, or cherry-pick or revert it")]
+    Commit(CommitCommand),
+}
+
+#[derive(Parser)]
+struct ListCommits {
+    /// Branch, tag or commit to list the history of. Defaults to the default
+    /// branch
+    #[clap(long = "ref", value_name = "REF")]
+    ref_: Option<String>,
+    /// Only commits after this date, YYYY-MM-DD or ISO 8601
+    #[clap(long, value_name = "DATE")]
+    since: Option<String>,
+    #[clap(flatten)]
+    list_args: ListArgs,
+}
+
+#[derive(Parser)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct CommitCommand {
+    #[clap(subcommand)]
+    subcommand: Option<CommitSubcommand>,
+    /// Commit SHA
+    #[clap(required = true)]
+    sha: Option<String>,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+enum CommitSubcommand {
+    #[clap(
+        name = "cherry-pick",
+        about = "Cherry-pick a commit onto a branch, ex. to backport a fix"
+    )]
+    CherryPick(CherryPickCommit),
+    #[clap(about = "Revert a commit with a new commit on a branch")]
+    Revert(RevertCommit),
+}
+
+#[derive(Parser)]
+struct CherryPickCommit {
+    /// Commit SHA
+    #[clap()]
+    sha: String,
+    /// Branch to add the commit to
+    #[clap(long, value_name = "BRANCH")]
+    branch: String,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+struct RevertCommit {
+    /// Commit SHA
+    #[clap()]
+    sha: String,
+    /// Branch to add the revert commit to. Defaults to the default branch
+    #[clap(long, value_name = "BRANCH")]
+    branch: Option<String>,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+impl From<CommitCommand> for CommitOptions {
+    fn from(options: CommitCommand) -> Self {
+        match options.subcommand {
+            Some(CommitSubcommand::CherryPick(options)) => CommitOptions::CherryPick(
+                CommitPickCliArgs::builder()
+                    .sha(options.sha)
+                    .branch(Some(options.branch))
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            Some(CommitSubcommand::Revert(options)) => CommitOptions::Revert(
+                CommitPickCliArgs::builder()
+                    .sha(options.sha)
+                    .branch(options.branch)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            // The sha is required unless a subcommand is given.
+            None => CommitOptions::Get(
+                CommitGetCliArgs::builder()
+                    .sha(options.sha.unwrap_or_default())
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+        }
+    }
+}
+
+#[derive(Parser)]
+enum PushRulesSubcommand {
+    #[clap(about = "Get the push rules of the project")]
+    Get(GetArgs),
+    #[clap(about = "Update the push rules. Values are validated before submitting")]
+    Set(SetPushRules),
+}
+
+#[derive(Parser)]
+#[clap(group(
+    ArgGroup::new("rules")
+        .required(true)
+        .multiple(true)
+        .args([
+            "commit_message_regex",
+            "commit_message_negative_regex",
+            "max_file_size",
+            "deny_secrets",
+            "no_deny_secrets",
+        ])
+))]
+struct SetPushRules {
+    /// Commit messages must match this regular expression. Empty to disable
+    #[clap(long, value_name = "REGEX")]
+    commit_message_regex: Option<String>,
+    /// Commit messages must not match this regular expression. Empty to
+    /// disable
+    #[clap(long, value_name = "REGEX")]
+    commit_message_negative_regex: Option<String>,
+    /// Largest file that can be pushed, in MB. 0 to disable
+    #[clap(long, value_name = "MB")]
+    max_file_size: Option<u64>,
+    /// Reject files likely to contain secrets, such as private keys
+    #[clap(long, conflicts_with = "no_deny_secrets")]
+    deny_secrets: bool,
+    /// Allow pushing files likely to contain secrets
+    #[clap(long)]
+    no_deny_secrets: bool,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+impl From<PushRulesSubcommand> for PushRulesOptions {
+    fn from(options: PushRulesSubcommand) -> Self {
+        match options {
+            PushRulesSubcommand::Get(get_args) => PushRulesOptions::Get(get_args.into()),
+            PushRulesSubcommand::Set(options) => {
+                let deny_secrets = if options.deny_secrets {
+                    Some(true)
+                } else if options.no_deny_secrets {
+                    Some(false)
+                } else {
+                    None
+                };
+                PushRulesOptions::Set(
+                    PushRulesSetCliArgs::builder()
+                        .body_args(
+                            PushRulesBodyArgs::builder()
+                                .commit_message_regex(options.commit_message_regex)
+                                .commit_message_negative_regex(
+                                    options.commit_message_negative_regex,
+                                )
+                                .max_file_size(options.max_file_size)
+                                .deny_secrets(deny_secrets)
+                                .build()
+                                .unwrap(),
+                        )
+                        .get_args(options.get_args.into())
+                        .build()
+                        .unwrap(),
+                )
+            }
+        }
+    }
+}
+
+#[derive(Parser)]
+enum RulesetsSubcommand {
+    #[clap(about = "List the rulesets that apply to the repository")]
+    List(GetArgs),
+    #[clap(about = "Get a ruleset with its refs and rules")]
+    Get(GetRuleset),
+}
+
+#[derive(Parser)]
+struct GetRuleset {
+    /// Ruleset ID
+    #[clap()]
+    id: i64,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+impl From<RulesetsSubcommand> for RulesetOptions {
+    fn from(options: RulesetsSubcommand) -> Self {
+        match options {
+            RulesetsSubcommand::List(get_args) => RulesetOptions::List(get_args.into()),
+            RulesetsSubcommand::Get(options) => RulesetOptions::Get(
+                RulesetGetCliArgs::builder()
+                    .id(options.id)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+        }
+    }
+}
+
+#[derive(Parser)]
+struct CatFile {
+    /// Path of the file in the repository, ex. .gitlab-ci.yml
+    #[clap()]
+    path: String,
+    /// Branch, tag or commit SHA. Defaults to the default branch
+    #[clap(long = "ref", value_name = "REF")]
+    ref_: Option<String>,
+    /// Read the file from another project, as <domain>/<owner>/<repo>. No
+    /// local repository is required then
+    #[clap(long, value_name = "REPO")]
+    repo: Option<String>,
+    /// Refresh the cache
+    #[clap(long, short, help_heading = "Cache options")]
+    refresh: bool,
+}
+
+#[derive(Parser)]
+enum ProtectSubcommand {
+    #[clap(about = "List protected branches")]
+    List(ListProtectedBranches),
+    #[clap(about = "Get the protection rules of a branch")]
+    Get(GetProtectedBranch),
+    #[clap(about = "Protect a branch or update its protection rules")]
+    Set(SetProtectedBranch),
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum BranchAccessCli {
+    NoOne,
+    Maintainers,
+    Developers,
+}
+
+#[derive(Parser)]
+struct ListProtectedBranches {
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+struct GetProtectedBranch {
+    /// Name of the branch
+    #[clap()]
+    branch: String,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+#[clap(group(
+    ArgGroup::new("rules")
+        .required(true)
+        .multiple(true)
+        .args(["required_approvals", "push", "merge", "allow_force_push", "no_force_push"])
+))]
+struct SetProtectedBranch {
+    /// Name of the branch
+    #[clap()]
+    branch: String,
+    /// Number of approvals required before merging
+    #[clap(long)]
+    required_approvals: Option<u32>,
+    /// Who can push. Github restricts pushes to users and teams, so it only
+    /// takes no-one or developers, anyone with write access
+    #[clap(long, value_name = "ACCESS")]
+    push: Option<BranchAccessCli>,
+    /// Who can merge. Gitlab only
+    #[clap(long, value_name = "ACCESS")]
+    merge: Option<BranchAccessCli>,
+    /// Allow force pushes
+    #[clap(long, conflicts_with = "no_force_push")]
+    allow_force_push: bool,
+    /// Refuse force pushes
+    #[clap(long)]
+    no_force_push: bool,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+impl From<BranchAccessCli> for BranchAccess {
+    fn from(access: BranchAccessCli) -> Self {
+        match access {
+            BranchAccessCli::NoOne => BranchAccess::NoOne,
+            BranchAccessCli::Maintainers => BranchAccess::Maintainers,
+            BranchAccessCli::Developers => BranchAccess::Developers,
+        }
+    }
+}
+
+impl From<ProtectSubcommand> for BranchProtectionOptions {
+    fn from(options: ProtectSubcommand) -> Self {
+        match options {
+            ProtectSubcommand::List(options) => {
+                BranchProtectionOptions::List(options.get_args.into())
+            }
+            ProtectSubcommand::Get(options) => BranchProtectionOptions::Get(
+                BranchProtectionGetCliArgs::builder()
+                    .branch(options.branch)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            ProtectSubcommand::Set(options) => options.into(),
+        }
+    }
+}
+
+impl From<SetProtectedBranch> for BranchProtectionOptions {
+    fn from(options: SetProtectedBranch) -> Self {
+        let allow_force_push = if options.allow_force_push {
+            Some(true)
+        } else if options.no_force_push {
+            Some(false)
+        } else {
+            None
+        };
+        BranchProtectionOptions::Set(
+            BranchProtectionSetCliArgs::builder()
+                .body_args(
+                    BranchProtectionBodyArgs::builder()
+                        .branch(options.branch)
+                        .required_approvals(options.required_approvals)
+                        .push(options.push.map(Into::into))
+                        .merge(options.merge.map(Into::into))
+                        .allow_force_push(allow_force_push)
+                        .build()
+                        .unwrap(),
+                )
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[derive(Parser)]
+enum VariablesSubcommand {
+    #[clap(about = "List variables. Masked values are not shown")]
+    List(ListVariables),
+    #[clap(about = "Create or update a variable")]
+    Set(SetVariable),
+    #[clap(about = "Delete a variable")]
+    Delete(DeleteVariable),
+}
+
+#[derive(Parser)]
+struct ListVariables {
+    /// Only the variables scoped to the environment
+    #[clap(long)]
+    environment: Option<String>,
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+struct SetVariable {
+    /// Name of the variable
+    #[clap()]
+    key: String,
+    /// Value of the variable. Read from STDIN, or prompted for in a terminal,
+    /// if not given
+    #[clap()]
+    value: Option<String>,
+    /// Hide the value in job logs. Gitlab only
+    #[clap(long)]
+    masked: bool,
+    /// Expose the variable to protected branches and tags only. Gitlab only
+    #[clap(long)]
+    protected: bool,
+    /// Environment the variable is scoped to
+    #[clap(long)]
+    environment: Option<String>,
+}
+
+#[derive(Parser)]
+struct DeleteVariable {
+    /// Name of the variable
+    #[clap()]
+    key: String,
+    /// Environment the variable is scoped to
+    #[clap(long)]
+    environment: Option<String>,
+}
+
+impl From<VariablesSubcommand> for VariableOptions {
+    fn from(options: VariablesSubcommand) -> Self {
+        match options {
+            VariablesSubcommand::List(options) => VariableOptions::List(
+                VariableListCliArgs::builder()
+                    .environment(options.environment)
+                    .get_args(options.get_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            VariablesSubcommand::Set(options) => VariableOptions::Set(
+                VariableSetCliArgs::builder()
+                    .key(options.key)
+                    .value(options.value)
+                    .masked(options.masked)
+                    .protected(options.protected)
+                    .environment(options.environment)
+                    .build()
+                    .unwrap(),
+            ),
+            VariablesSubcommand::Delete(options) => VariableOptions::Delete(
+                VariableDeleteCliArgs::builder()
+                    .key(options.key)
+                    .environment(options.environment)
+                    .build()
+                    .unwrap(),
+            ),
+        }
+    }
+}
+
+#[derive(Parser)]
+struct AuditVisibility {
+    /// Group, or organization in Github. Defaults to the namespace of the
+    /// current project
+    #[clap(long)]
+    group: Option<String>,
+    /// Do not print headers
+    #[clap(long)]
+    no_headers: bool,
+    /// Write the report to the given file instead of STDOUT
+    #[clap(long, value_name = "PATH")]
+    output: Option<String>,
+    /// Refresh the cache
+    #[clap(long, short)]
+    refresh: bool,
+}
+
+impl From<AuditVisibility> for ProjectOptions {
+    fn from(options: AuditVisibility) -> Self {
+        ProjectOptions::AuditVisibility(
+            ProjectAuditCliArgs::builder()
+                .group(options.group)
+                .get_args(
+                    GetRemoteCliArgs::builder()
+                        .format(Format::CSV)
+                        .no_headers(options.no_headers)
+                        .output(options.output)
+                        .refresh_cache(options.refresh)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+#[derive(Parser)]
+struct NewProject {
+    /// Name of the new project
+    #[clap()]
+    name: String,
+    /// Template to create the project from, as <domain>/<owner>/<repo>
+    #[clap(long, value_name = "TEMPLATE")]
+    template: String,
+    /// User, organization or group owning the new project. Defaults to the
+    /// authenticated user
+    #[clap(long)]
+    owner: Option<String>,
+    /// Description of the new project
+    #[clap(long)]
+    description: Option<String>,
+    /// Make the new project private
+    #[clap(long)]
+    private: bool,
+}
+
+#[derive(Parser)]
+enum MembersSubcommand {
+    #[clap(about = "List members with their role and state")]
+    List(ListArgs),
+}
+
+#[derive(Parser)]
+enum AccessRequestsSubcommand {
+    #[clap(about = "List pending access requests")]
+    List(ListArgs),
+    #[clap(about = "Approve an access request")]
+    Approve(ApproveAccessRequest),
+    #[clap(about = "Deny an access request")]
+    Deny(DenyAccessRequest),
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum AccessLevelCli {
+    Guest,
+    Reporter,
+    Developer,
+    Maintainer,
+}
+
+#[derive(Parser)]
+struct ApproveAccessRequest {
+    /// ID of the user requesting access
+    #[clap()]
+    user_id: i64,
+    /// Role given to the new member
+    #[clap(long, default_value = "developer")]
+    access_level: AccessLevelCli,
+}
+
+#[derive(Parser)]
+struct DenyAccessRequest {
+    /// ID of the user requesting access
+    #[clap()]
+    user_id: i64,
+}
+
+#[derive(Parser)]
+enum ApprovalsSubcommand {
+    #[clap(about = "Get the merge request approval settings")]
+    Get(GetApprovals),
+    #[clap(about = "Update the merge request approval settings")]
+    Set(SetApprovals),
+}
+
+#[derive(Parser)]
+struct GetApprovals {
+    #[clap(flatten)]
+    get_args: GetArgs,
+}
+
+#[derive(Parser)]
+#[clap(group(
+    ArgGroup::new("settings")
+        .required(true)
+        .multiple(true)
+        .args(["required", "reset_on_push", "no_reset_on_push"])
+))]
+struct SetApprovals {
+    /// Number of approvals required before merging
+    #[clap(long)]
+    required: Option<u32>,
+    /// Remove all approvals when new commits are pushed
+    #[clap(long, conflicts_with = "no_reset_on_push")]
+    reset_on_push: bool,
+    /// Keep approvals when new commits are pushed
+    #[clap(long)]
+    no_reset_on_push: bool,
+    #[clap(flatten)]
+    get_args: GetArgs,
 }
 
 #[derive(Parser)]
@@ -35,10 +637,61 @@ impl From<ProjectCommand> for ProjectOptions {
     fn from(options: ProjectCommand) -> Self {
         match options.subcommand {
             ProjectSubcommand::Info(options) => options.into(),
+            ProjectSubcommand::Approvals(options) => ProjectOptions::Approvals(options.into()),
+            ProjectSubcommand::Members(MembersSubcommand::List(options)) => {
+                ProjectOptions::Members(MemberOptions::List(options.into()))
+            }
+            ProjectSubcommand::AccessRequests(options) => {
+                ProjectOptions::AccessRequests(options.into())
+            }
+            ProjectSubcommand::New(options) => options.into(),
+            ProjectSubcommand::AuditVisibility(options) => options.into(),
+            ProjectSubcommand::Variables(options) => ProjectOptions::Variables(options.into()),
+            ProjectSubcommand::Protect(options) => ProjectOptions::Protect(options.into()),
+            ProjectSubcommand::Cat(options) => ProjectOptions::Cat(
+                FileCatCliArgs::builder()
+                    .path(options.path)
+                    .ref_(options.ref_)
+                    .repo(options.repo)
+                    .refresh_cache(options.refresh)
+                    .build()
+                    .unwrap(),
+            ),
+            ProjectSubcommand::Rulesets(options) => ProjectOptions::Rulesets(options.into()),
+            ProjectSubcommand::PushRules(options) => ProjectOptions::PushRules(options.into()),
+            ProjectSubcommand::Commits(options) => ProjectOptions::Commits(
+                CommitListCliArgs::builder()
+                    .ref_(options.ref_)
+                    .since(options.since)
+                    .list_args(options.list_args.into())
+                    .build()
+                    .unwrap(),
+            ),
+            ProjectSubcommand::Commit(options) => ProjectOptions::Commit(options.into()),
         }
     }
 }
 
+impl From<NewProject> for ProjectOptions {
+    fn from(options: NewProject) -> Self {
+        ProjectOptions::New(
+            ProjectNewCliArgs::builder()
+                .template(options.template)
+                .body_args(
+                    ProjectTemplateBodyArgs::builder()
+                        .name(options.name)
+                        .owner(options.owner)
+                        .description(options.description)
+                        .private(options.private)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
 impl From<ProjectInfo> for ProjectOptions {
     fn from(options: ProjectInfo) -> Self {
         ProjectOptions::Info(
@@ -51,8 +704,126 @@ impl From<ProjectInfo> for ProjectOptions {
     }
 }
 
+impl From<AccessLevelCli> for AccessLevel {
+    fn from(access_level: AccessLevelCli) -> Self {
+        match access_level {
+            AccessLevelCli::Guest => AccessLevel::Guest,
+            AccessLevelCli::Reporter => AccessLevel::Reporter,
+            AccessLevelCli::Developer => AccessLevel::Developer,
+            AccessLevelCli::Maintainer => AccessLevel::Maintainer,
+        }
+    }
+}
+
+impl From<AccessRequestsSubcommand> for AccessRequestOptions {
+    fn from(options: AccessRequestsSubcommand) -> Self {
+        match options {
+            AccessRequestsSubcommand::List(options) => AccessRequestOptions::List(options.into()),
+            AccessRequestsSubcommand::Approve(options) => AccessRequestOptions::Approve(
+                AccessRequestApproveBodyArgs::builder()
+                    .user_id(options.user_id)
+                    .access_level(options.access_level.into())
+                    .build()
+                    .unwrap(),
+            ),
+            AccessRequestsSubcommand::Deny(options) => AccessRequestOptions::Deny(options.user_id),
+        }
+    }
+}
+
+impl From<ApprovalsSubcommand> for ApprovalSettingsOptions {
+    fn from(options: ApprovalsSubcommand) -> Self {
+        match options {
+            ApprovalsSubcommand::Get(options) => {
+                ApprovalSettingsOptions::Get(options.get_args.into())
+            }
+            ApprovalsSubcommand::Set(options) => options.into(),
+        }
+    }
+}
+
+impl From<SetApprovals> for ApprovalSettingsOptions {
+    fn from(options: SetApprovals) -> Self {
+        let reset_on_push = if options.reset_on_push {
+            Some(true)
+        } else if options.no_reset_on_push {
+            Some(false)
+        } else {
+            None
+        };
+        ApprovalSettingsOptions::Set(
+            ApprovalSettingsSetCliArgs::builder()
+                .body_args(
+                    ApprovalSettingsBodyArgs::builder()
+                        .required(options.required)
+                        .reset_on_push(reset_on_push)
+                        .build()
+                        .unwrap(),
+                )
+                .get_args(options.get_args.into())
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
 pub enum ProjectOptions {
     Info(ProjectMetadataGetCliArgs),
+    Approvals(ApprovalSettingsOptions),
+    Members(MemberOptions),
+    AccessRequests(AccessRequestOptions),
+    New(ProjectNewCliArgs),
+    AuditVisibility(ProjectAuditCliArgs),
+    Variables(VariableOptions),
+    Protect(BranchProtectionOptions),
+    Cat(FileCatCliArgs),
+    Rulesets(RulesetOptions),
+    PushRules(PushRulesOptions),
+    Commits(CommitListCliArgs),
+    Commit(CommitOptions),
+}
+
+pub enum CommitOptions {
+    Get(CommitGetCliArgs),
+    CherryPick(CommitPickCliArgs),
+    Revert(CommitPickCliArgs),
+}
+
+pub enum RulesetOptions {
+    List(GetRemoteCliArgs),
+    Get(RulesetGetCliArgs),
+}
+
+pub enum PushRulesOptions {
+    Get(GetRemoteCliArgs),
+    Set(PushRulesSetCliArgs),
+}
+
+pub enum BranchProtectionOptions {
+    List(GetRemoteCliArgs),
+    Get(BranchProtectionGetCliArgs),
+    Set(BranchProtectionSetCliArgs),
+}
+
+pub enum VariableOptions {
+    List(VariableListCliArgs),
+    Set(VariableSetCliArgs),
+    Delete(VariableDeleteCliArgs),
+}
+
+pub enum MemberOptions {
+    List(ListRemoteCliArgs),
+}
+
+pub enum AccessRequestOptions {
+    List(ListRemoteCliArgs),
+    Approve(AccessRequestApproveBodyArgs),
+    Deny(i64),
+}
+
+pub enum ApprovalSettingsOptions {
+    Get(GetRemoteCliArgs),
+    Set(ApprovalSettingsSetCliArgs),
 }
 
 #[cfg(test)]
@@ -64,7 +835,7 @@ mod test {
     #[test]
     fn test_project_cli_info() {
         let args = Args::parse_from(vec!["gr", "pj", "info", "--id", "1"]);
-        let project_info = match args.command {
+        let project_info = match args.command.unwrap() {
             Command::Project(ProjectCommand {
                 subcommand: ProjectSubcommand::Info(options),
             }) => {
@@ -78,6 +849,419 @@ mod test {
             ProjectOptions::Info(options) => {
                 assert_eq!(options.id, Some(1));
             }
+            _ => panic!("Expected ProjectOptions::Info"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_audit_visibility_outputs_csv() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "audit-visibility",
+            "--group",
+            "my-org",
+            "--output",
+            "audit.csv",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::AuditVisibility(options) => {
+                assert_eq!(Some("my-org".to_string()), options.group);
+                assert!(matches!(options.get_args.format, Format::CSV));
+                assert_eq!(Some("audit.csv".to_string()), options.get_args.output);
+            }
+            _ => panic!("Expected ProjectOptions::AuditVisibility"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_new_from_template() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "new",
+            "my-service",
+            "--template",
+            "github.com/my-org/service-template",
+            "--owner",
+            "my-org",
+            "--private",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::New(options) => {
+                assert_eq!("github.com/my-org/service-template", options.template);
+                assert_eq!("my-service", options.body_args.name);
+                assert_eq!(Some("my-org".to_string()), options.body_args.owner);
+                assert!(options.body_args.private);
+            }
+            _ => panic!("Expected ProjectOptions::New"),
+        }
+        assert!(Args::try_parse_from(vec!["gr", "pj", "new", "my-service"]).is_err());
+    }
+
+    #[test]
+    fn test_project_cli_approvals_set() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "approvals",
+            "set",
+            "--required",
+            "2",
+            "--reset-on-push",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Approvals(ApprovalSettingsOptions::Set(options)) => {
+                assert_eq!(Some(2), options.body_args.required);
+                assert_eq!(Some(true), options.body_args.reset_on_push);
+            }
+            _ => panic!("Expected ApprovalSettingsOptions::Set"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_access_requests_approve() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "access-requests",
+            "approve",
+            "42",
+            "--access-level",
+            "maintainer",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::AccessRequests(AccessRequestOptions::Approve(args)) => {
+                assert_eq!(42, args.user_id);
+                assert_eq!(AccessLevel::Maintainer, args.access_level);
+            }
+            _ => panic!("Expected AccessRequestOptions::Approve"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_access_requests_approve_defaults_to_developer() {
+        let args = Args::parse_from(vec!["gr", "pj", "access-requests", "approve", "42"]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::AccessRequests(AccessRequestOptions::Approve(args)) => {
+                assert_eq!(AccessLevel::Developer, args.access_level);
+            }
+            _ => panic!("Expected AccessRequestOptions::Approve"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_access_requests_deny() {
+        let args = Args::parse_from(vec!["gr", "pj", "access-requests", "deny", "42"]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::AccessRequests(AccessRequestOptions::Deny(user_id)) => {
+                assert_eq!(42, user_id);
+            }
+            _ => panic!("Expected AccessRequestOptions::Deny"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_members_list() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "members",
+            "list",
+            "--from-page",
+            "2",
+            "--per-page",
+            "100",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Members(MemberOptions::List(args)) => {
+                assert_eq!(Some(2), args.from_page);
+                assert_eq!(Some(100), args.per_page);
+            }
+            _ => panic!("Expected ProjectOptions::Members"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_members_list_per_page_over_max_is_error() {
+        assert!(
+            Args::try_parse_from(vec!["gr", "pj", "members", "list", "--per-page", "101"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_project_cli_var_set() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "var",
+            "set",
+            "DEPLOY_TOKEN",
+            "--masked",
+            "--environment",
+            "production",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Variables(VariableOptions::Set(args)) => {
+                assert_eq!("DEPLOY_TOKEN", args.key);
+                assert_eq!(None, args.value);
+                assert!(args.masked);
+                assert!(!args.protected);
+                assert_eq!(Some("production".to_string()), args.environment);
+            }
+            _ => panic!("Expected VariableOptions::Set"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_var_delete() {
+        let args = Args::parse_from(vec!["gr", "pj", "var", "delete", "DEPLOY_TOKEN"]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Variables(VariableOptions::Delete(args)) => {
+                assert_eq!("DEPLOY_TOKEN", args.key);
+                assert_eq!(None, args.environment);
+            }
+            _ => panic!("Expected VariableOptions::Delete"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_protect_set() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "protect",
+            "set",
+            "main",
+            "--required-approvals",
+            "2",
+            "--push",
+            "no-one",
+            "--no-force-push",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Protect(BranchProtectionOptions::Set(options)) => {
+                assert_eq!("main", options.body_args.branch);
+                assert_eq!(Some(2), options.body_args.required_approvals);
+                assert_eq!(Some(BranchAccess::NoOne), options.body_args.push);
+                assert_eq!(None, options.body_args.merge);
+                assert_eq!(Some(false), options.body_args.allow_force_push);
+            }
+            _ => panic!("Expected BranchProtectionOptions::Set"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_protect_set_requires_a_rule() {
+        assert!(Args::try_parse_from(vec!["gr", "pj", "protect", "set", "main"]).is_err());
+    }
+
+    #[test]
+    fn test_project_cli_approvals_set_requires_a_setting() {
+        assert!(Args::try_parse_from(vec!["gr", "pj", "approvals", "set"]).is_err());
+        assert!(Args::try_parse_from(vec![
+            "gr",
+            "pj",
+            "approvals",
+            "set",
+            "--reset-on-push",
+            "--no-reset-on-push"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_project_cli_cat_from_another_repo() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "cat",
+            ".gitlab-ci.yml",
+            "--ref",
+            "v1.0.0",
+            "--repo",
+            "gitlab.com/platform/templates",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Cat(options) => {
+                assert_eq!(".gitlab-ci.yml", options.path);
+                assert_eq!(Some("v1.0.0".to_string()), options.ref_);
+                assert_eq!(
+                    Some("gitlab.com/platform/templates".to_string()),
+                    options.repo
+                );
+                assert!(!options.refresh_cache);
+            }
+            _ => panic!("Expected ProjectOptions::Cat"),
         }
     }
+
+    #[test]
+    fn test_project_cli_rulesets_get() {
+        let args = Args::parse_from(vec!["gr", "pj", "rulesets", "get", "42"]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Rulesets(RulesetOptions::Get(options)) => assert_eq!(42, options.id),
+            _ => panic!("Expected RulesetOptions::Get"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_commits() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "commits",
+            "--ref",
+            "release-1.2",
+            "--since",
+            "2024-03-01",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Commits(options) => {
+                assert_eq!(Some("release-1.2".to_string()), options.ref_);
+                assert_eq!(Some("2024-03-01".to_string()), options.since);
+            }
+            _ => panic!("Expected ProjectOptions::Commits"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_commit_get_and_cherry_pick() {
+        let args = Args::parse_from(vec!["gr", "pj", "commit", "ed899a2f"]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Commit(CommitOptions::Get(options)) => {
+                assert_eq!("ed899a2f", options.sha);
+            }
+            _ => panic!("Expected CommitOptions::Get"),
+        }
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "commit",
+            "cherry-pick",
+            "ed899a2f",
+            "--branch",
+            "release-1.2",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::Commit(CommitOptions::CherryPick(options)) => {
+                assert_eq!("ed899a2f", options.sha);
+                assert_eq!(Some("release-1.2".to_string()), options.branch);
+            }
+            _ => panic!("Expected CommitOptions::CherryPick"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_commit_cherry_pick_requires_branch() {
+        let result = Args::try_parse_from(vec!["gr", "pj", "commit", "cherry-pick", "ed899a2f"]);
+        assert!(result.is_err());
+        assert!(Args::try_parse_from(vec!["gr", "pj", "commit"]).is_err());
+    }
+
+    #[test]
+    fn test_project_cli_push_rules_set() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "pj",
+            "push-rules",
+            "set",
+            "--commit-message-regex",
+            "^(feat|fix): ",
+            "--max-file-size",
+            "50",
+            "--no-deny-secrets",
+        ]);
+        let options: ProjectOptions = match args.command.unwrap() {
+            Command::Project(options) => options.into(),
+            _ => panic!("Expected ProjectCommand"),
+        };
+        match options {
+            ProjectOptions::PushRules(PushRulesOptions::Set(options)) => {
+                let body_args = options.body_args;
+                assert_eq!(
+                    Some("^(feat|fix): ".to_string()),
+                    body_args.commit_message_regex
+                );
+                assert_eq!(None, body_args.commit_message_negative_regex);
+                assert_eq!(Some(50), body_args.max_file_size);
+                assert_eq!(Some(false), body_args.deny_secrets);
+            }
+            _ => panic!("Expected PushRulesOptions::Set"),
+        }
+    }
+
+    #[test]
+    fn test_project_cli_push_rules_set_requires_a_rule() {
+        assert!(Args::try_parse_from(vec!["gr", "pj", "push-rules", "set"]).is_err());
+        assert!(Args::try_parse_from(vec![
+            "gr",
+            "pj",
+            "push-rules",
+            "set",
+            "--deny-secrets",
+            "--no-deny-secrets"
+        ])
+        .is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use clap::Parser;
 
+use crate::cmds::release::ReleaseCreateCliArgs;
 use crate::remote::ListRemoteCliArgs;
 
 use super::common::ListArgs;
@@ -14,12 +15,34 @@ pub struct ReleaseCommand {
 pub enum ReleaseSubcommand {
     #[clap(about = "List releases")]
     List(ListArgs),
+    #[clap(about = "Create a release and upload its assets")]
+    Create(CreateRelease),
+}
+
+#[derive(Parser)]
+pub struct CreateRelease {
+    /// Tag of the release, ex. v1.2.0
+    #[clap(long)]
+    tag: String,
+    /// Title of the release. Defaults to the tag
+    #[clap(long)]
+    title: Option<String>,
+    /// File with the release notes. If "-" is provided, read from STDIN
+    #[clap(long, value_name = "FILE")]
+    notes_file: Option<String>,
+    /// File to upload along with the release. Can be repeated
+    #[clap(long = "asset", value_name = "PATH")]
+    assets: Vec<String>,
+    /// Branch or commit to create the tag from if it does not exist yet
+    #[clap(long = "ref", value_name = "REF")]
+    target: Option<String>,
 }
 
 impl From<ReleaseCommand> for ReleaseOptions {
     fn from(options: ReleaseCommand) -> Self {
         match options.subcommand {
             ReleaseSubcommand::List(options) => options.into(),
+            ReleaseSubcommand::Create(options) => options.into(),
         }
     }
 }
@@ -30,8 +53,24 @@ impl From<ListArgs> for ReleaseOptions {
     }
 }
 
+impl From<CreateRelease> for ReleaseOptions {
+    fn from(options: CreateRelease) -> Self {
+        ReleaseOptions::Create(
+            ReleaseCreateCliArgs::builder()
+                .tag(options.tag)
+                .title(options.title)
+                .notes_file(options.notes_file)
+                .assets(options.assets)
+                .target(options.target)
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
 pub enum ReleaseOptions {
     List(ListRemoteCliArgs),
+    Create(ReleaseCreateCliArgs),
 }
 
 #[cfg(test)]
@@ -51,7 +90,7 @@ mod test {
             "--to-page",
             "2",
         ]);
-        let list_args = match args.command {
+        let list_args = match args.command.unwrap() {
             Command::Release(ReleaseCommand {
                 subcommand: ReleaseSubcommand::List(options),
             }) => {
@@ -67,6 +106,41 @@ mod test {
                 assert_eq!(args.from_page, Some(1));
                 assert_eq!(args.to_page, Some(2));
             }
+            _ => panic!("Expected ReleaseOptions::List"),
+        }
+    }
+
+    #[test]
+    fn test_release_cli_create() {
+        let args = Args::parse_from(vec![
+            "gr",
+            "rl",
+            "create",
+            "--tag",
+            "v1.2.0",
+            "--notes-file",
+            "NOTES.md",
+            "--asset",
+            "gr-linux.tar.gz",
+            "--asset",
+            "gr-macos.tar.gz",
+        ]);
+        let options: ReleaseOptions = match args.command.unwrap() {
+            Command::Release(options) => options.into(),
+            _ => panic!("Expected ReleaseCommand"),
+        };
+        match options {
+            ReleaseOptions::Create(args) => {
+                assert_eq!("v1.2.0", args.tag);
+                assert_eq!(None, args.title);
+                assert_eq!(Some("NOTES.md".to_string()), args.notes_file);
+                assert_eq!(
+                    vec!["gr-linux.tar.gz".to_string(), "gr-macos.tar.gz".to_string()],
+                    args.assets
+                );
+                assert_eq!(None, args.target);
+            }
+            _ => panic!("Expected ReleaseOptions::Create"),
         }
     }
 }
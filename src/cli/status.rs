@@ -0,0 +1,42 @@
+use clap::Parser;
+
+use crate::cmds::status::StatusCliArgs;
+
+#[derive(Parser)]
+pub struct StatusCommand {
+    /// Comma separated list of domains to check. Defaults to all the domains
+    /// in the configuration file.
+    #[clap(long, value_delimiter = ',', value_name = "DOMAINS")]
+    domains: Vec<String>,
+    /// Number of requests sent to each remote to compute the average latency
+    #[clap(long, default_value_t = 3, value_name = "NUMBER")]
+    samples: u32,
+}
+
+impl From<StatusCommand> for StatusCliArgs {
+    fn from(options: StatusCommand) -> Self {
+        StatusCliArgs::builder()
+            .domains(options.domains)
+            .samples(options.samples)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    #[test]
+    fn test_status_cli_args() {
+        let args = Args::parse_from(vec!["gr", "status", "--domains", "gitlab.com,github.com"]);
+        let options: StatusCliArgs = match args.command.unwrap() {
+            Command::Status(options) => options.into(),
+            _ => panic!("Expected StatusCommand"),
+        };
+        assert_eq!(vec!["gitlab.com", "github.com"], options.domains);
+        assert_eq!(3, options.samples);
+    }
+}
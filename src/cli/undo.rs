@@ -0,0 +1,33 @@
+use clap::Parser;
+
+use crate::cmds::undo::UndoCliArgs;
+
+#[derive(Parser)]
+pub struct UndoCommand {
+    /// Undo without asking for confirmation
+    #[clap(long, short)]
+    yes: bool,
+}
+
+impl From<UndoCommand> for UndoCliArgs {
+    fn from(options: UndoCommand) -> Self {
+        UndoCliArgs::builder().accept(options.yes).build().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Args, Command};
+
+    use super::*;
+
+    #[test]
+    fn test_undo_cli_args() {
+        let args = Args::parse_from(vec!["gr", "undo", "-y"]);
+        let options: UndoCliArgs = match args.command.unwrap() {
+            Command::Undo(options) => options.into(),
+            _ => panic!("Expected UndoCommand"),
+        };
+        assert!(options.accept);
+    }
+}
@@ -1,8 +1,22 @@
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod auth;
 pub mod browse;
+pub mod cache;
 pub mod cicd;
 pub mod common;
+pub mod config;
+pub mod contracts;
+pub mod cron;
 pub mod docker;
+pub mod environment;
+pub mod history;
+pub mod issue;
 pub mod merge_request;
+pub mod migrate;
 pub mod my;
 pub mod project;
 pub mod release;
+pub mod status;
+pub mod undo;
+pub mod version;
@@ -0,0 +1,352 @@
+//! Instance wide operations for administrators of self-hosted Gitlab
+//! instances. Only built with the `admin` feature.
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::api_traits::{CicdRunner, InstanceAdmin, Timestamp};
+use crate::cli::admin::{AdminOperation, AdminOptions};
+use crate::display::{self, Column, DisplayBody};
+use crate::i18n::{self, Message};
+use crate::remote::{self, ListBodyArgs, ListRemoteCliArgs};
+use crate::Result;
+
+use super::cicd::{RunnerListBodyArgs, RunnerListCliArgs, RunnerScope};
+use super::common;
+
+/// A user account of the instance as seen by an administrator.
+#[derive(Builder, Clone, Debug)]
+pub struct InstanceUser {
+    pub id: i64,
+    pub username: String,
+    pub name: String,
+    #[builder(default)]
+    pub email: String,
+    pub state: String,
+    #[builder(default)]
+    pub is_admin: bool,
+    #[builder(default)]
+    pub last_activity_on: String,
+    pub created_at: String,
+}
+
+impl InstanceUser {
+    pub fn builder() -> InstanceUserBuilder {
+        InstanceUserBuilder::default()
+    }
+}
+
+impl Timestamp for InstanceUser {
+    fn created_at(&self) -> String {
+        self.created_at.clone()
+    }
+}
+
+impl From<InstanceUser> for DisplayBody {
+    fn from(user: InstanceUser) -> Self {
+        let admin = if user.is_admin { "yes" } else { "no" };
+        DisplayBody::new(vec![
+            Column::new("ID", user.id.to_string()),
+            Column::new("Username", user.username),
+            Column::new("Name", user.name),
+            Column::new("Email", user.email),
+            Column::new("State", user.state),
+            Column::new("Admin", admin),
+            Column::new("Last activity", user.last_activity_on),
+        ])
+    }
+}
+
+/// Any project hosted in the instance, regardless of membership.
+#[derive(Builder, Clone, Debug)]
+pub struct InstanceProject {
+    pub id: i64,
+    pub path: String,
+    pub visibility: String,
+    #[builder(default)]
+    pub archived: bool,
+    #[builder(default)]
+    pub last_activity_at: String,
+    pub created_at: String,
+}
+
+impl InstanceProject {
+    pub fn builder() -> InstanceProjectBuilder {
+        InstanceProjectBuilder::default()
+    }
+}
+
+impl Timestamp for InstanceProject {
+    fn created_at(&self) -> String {
+        self.created_at.clone()
+    }
+}
+
+impl From<InstanceProject> for DisplayBody {
+    fn from(project: InstanceProject) -> Self {
+        let archived = if project.archived { "yes" } else { "no" };
+        DisplayBody::new(vec![
+            Column::new("ID", project.id.to_string()),
+            Column::new("Path", project.path),
+            Column::new("Visibility", project.visibility),
+            Column::new("Archived", archived),
+            Column::new("Last activity", project.last_activity_at),
+        ])
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct InstanceUserListCliArgs {
+    /// Only blocked users.
+    #[builder(default)]
+    pub blocked: bool,
+    pub list_args: ListRemoteCliArgs,
+}
+
+impl InstanceUserListCliArgs {
+    pub fn builder() -> InstanceUserListCliArgsBuilder {
+        InstanceUserListCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct InstanceUserListBodyArgs {
+    pub list_args: Option<ListBodyArgs>,
+    #[builder(default)]
+    pub blocked: bool,
+}
+
+impl InstanceUserListBodyArgs {
+    pub fn builder() -> InstanceUserListBodyArgsBuilder {
+        InstanceUserListBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct InstanceProjectListBodyArgs {
+    pub list_args: Option<ListBodyArgs>,
+}
+
+impl InstanceProjectListBodyArgs {
+    pub fn builder() -> InstanceProjectListBodyArgsBuilder {
+        InstanceProjectListBodyArgsBuilder::default()
+    }
+}
+
+/// Admin operations run against the instance given in the command line, no
+/// local repository is involved.
+pub fn execute<P: AsRef<Path>>(options: AdminOptions, config_file: P) -> Result<()> {
+    let config = common::read_config(config_file.as_ref(), &options.domain)?;
+    let domain = options.domain;
+    match options.operation {
+        AdminOperation::ListUsers(cli_args) => {
+            let remote = remote::get_instance_admin(
+                domain,
+                "".to_string(),
+                config,
+                cli_args.list_args.get_args.refresh_cache,
+            )?;
+            let from_to_args = remote::validate_from_to_page(&cli_args.list_args)?;
+            let body_args = InstanceUserListBodyArgs::builder()
+                .list_args(from_to_args)
+                .blocked(cli_args.blocked)
+                .build()?;
+            let output = cli_args.list_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_users(remote, body_args, cli_args.list_args, writer)
+            })
+        }
+        AdminOperation::BlockUser(id) => {
+            let remote = remote::get_instance_admin(domain, "".to_string(), config, true)?;
+            block_user(remote, id, std::io::stdout())
+        }
+        AdminOperation::ListProjects(cli_args) => {
+            let remote = remote::get_instance_admin(
+                domain,
+                "".to_string(),
+                config,
+                cli_args.get_args.refresh_cache,
+            )?;
+            let from_to_args = remote::validate_from_to_page(&cli_args)?;
+            let body_args = InstanceProjectListBodyArgs::builder()
+                .list_args(from_to_args)
+                .build()?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_projects(remote, body_args, cli_args, writer)
+            })
+        }
+        AdminOperation::ListRunners(cli_args) => {
+            let remote = remote::get_cicd_runner(
+                domain,
+                "".to_string(),
+                config,
+                cli_args.list_args.get_args.refresh_cache,
+            )?;
+            let from_to_args = remote::validate_from_to_page(&cli_args.list_args)?;
+            let body_args = RunnerListBodyArgs::builder()
+                .list_args(from_to_args)
+                .status(cli_args.status)
+                .tags(cli_args.tags.clone())
+                .all(cli_args.all)
+                .scope(RunnerScope::Instance)
+                .build()?;
+            let output = cli_args.list_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_runners(remote, body_args, cli_args, writer)
+            })
+        }
+    }
+}
+
+fn list_users<W: Write>(
+    remote: Arc<dyn InstanceAdmin>,
+    body_args: InstanceUserListBodyArgs,
+    cli_args: ListRemoteCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let users = remote.list_users(body_args)?;
+    if cli_args.flush {
+        return Ok(());
+    }
+    if users.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoResourcesFound))?;
+        return Ok(());
+    }
+    display::print(&mut writer, users, cli_args.get_args)
+}
+
+fn block_user<W: Write>(remote: Arc<dyn InstanceAdmin>, id: i64, mut writer: W) -> Result<()> {
+    remote.block_user(id)?;
+    writeln!(writer, "{}", i18n::format(Message::UserBlocked, &[&id]))?;
+    Ok(())
+}
+
+fn list_projects<W: Write>(
+    remote: Arc<dyn InstanceAdmin>,
+    body_args: InstanceProjectListBodyArgs,
+    cli_args: ListRemoteCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let projects = remote.list_projects(body_args)?;
+    if cli_args.flush {
+        return Ok(());
+    }
+    if projects.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoResourcesFound))?;
+        return Ok(());
+    }
+    display::print(&mut writer, projects, cli_args.get_args)
+}
+
+fn list_runners<W: Write>(
+    remote: Arc<dyn CicdRunner>,
+    body_args: RunnerListBodyArgs,
+    cli_args: RunnerListCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    common::list_runners(remote, body_args, cli_args, &mut writer)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InstanceAdminMock {
+        users: Vec<InstanceUser>,
+        projects: Vec<InstanceProject>,
+        blocked: Mutex<Vec<i64>>,
+        blocked_filter: Mutex<bool>,
+    }
+
+    impl InstanceAdmin for InstanceAdminMock {
+        fn list_users(&self, args: InstanceUserListBodyArgs) -> Result<Vec<InstanceUser>> {
+            *self.blocked_filter.lock().unwrap() = args.blocked;
+            Ok(self.users.clone())
+        }
+
+        fn block_user(&self, id: i64) -> Result<()> {
+            self.blocked.lock().unwrap().push(id);
+            Ok(())
+        }
+
+        fn list_projects(
+            &self,
+            _args: InstanceProjectListBodyArgs,
+        ) -> Result<Vec<InstanceProject>> {
+            Ok(self.projects.clone())
+        }
+    }
+
+    fn user() -> InstanceUser {
+        InstanceUser::builder()
+            .id(7)
+            .username("tanuki".to_string())
+            .name("Tanuki".to_string())
+            .email("tanuki@example.com".to_string())
+            .state("active".to_string())
+            .is_admin(true)
+            .last_activity_on("2024-03-01".to_string())
+            .created_at("2020-01-01T00:00:00Z".to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_list_users() {
+        let remote = Arc::new(InstanceAdminMock {
+            users: vec![user()],
+            ..Default::default()
+        });
+        let body_args = InstanceUserListBodyArgs::builder()
+            .list_args(None)
+            .blocked(true)
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        list_users(
+            remote.clone(),
+            body_args,
+            ListRemoteCliArgs::builder().build().unwrap(),
+            &mut buf,
+        )
+        .unwrap();
+        assert!(*remote.blocked_filter.lock().unwrap());
+        assert_eq!(
+            "ID|Username|Name|Email|State|Admin|Last activity\n\
+             7|tanuki|Tanuki|tanuki@example.com|active|yes|2024-03-01\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list_projects_empty() {
+        let remote = Arc::new(InstanceAdminMock::default());
+        let body_args = InstanceProjectListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        list_projects(
+            remote,
+            body_args,
+            ListRemoteCliArgs::builder().build().unwrap(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!("No resources found.\n", String::from_utf8(buf).unwrap());
+    }
+
+    #[test]
+    fn test_block_user() {
+        let remote = Arc::new(InstanceAdminMock::default());
+        let mut buf = Vec::new();
+        block_user(remote.clone(), 7, &mut buf).unwrap();
+        assert_eq!(vec![7], *remote.blocked.lock().unwrap());
+        assert_eq!("User 7 blocked\n", String::from_utf8(buf).unwrap());
+    }
+}
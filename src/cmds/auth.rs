@@ -0,0 +1,278 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::api_traits::TokenInfo;
+use crate::cli::auth::AuthOptions;
+use crate::config::{Config, ConfigProperties};
+use crate::display::{self, Column, DisplayBody};
+use crate::error::{AddContext, GRError};
+use crate::i18n::{self, Message};
+use crate::remote::{self, GetRemoteCliArgs};
+use crate::{log_debug, Result};
+
+use super::common;
+
+#[derive(Builder)]
+pub struct TokensCliArgs {
+    pub domains: Vec<String>,
+}
+
+impl TokensCliArgs {
+    pub fn builder() -> TokensCliArgsBuilder {
+        TokensCliArgsBuilder::default()
+    }
+}
+
+/// Metadata of the API token configured for a domain. The expiration date is
+/// in YYYY-MM-DD format and None when the token does not expire or the remote
+/// does not disclose it.
+#[derive(Builder, Clone, Debug)]
+pub struct TokenMetadata {
+    #[builder(default)]
+    pub domain: String,
+    #[builder(default)]
+    pub name: String,
+    #[builder(default)]
+    pub scopes: String,
+    #[builder(default)]
+    pub expires_at: Option<String>,
+}
+
+impl TokenMetadata {
+    pub fn builder() -> TokenMetadataBuilder {
+        TokenMetadataBuilder::default()
+    }
+
+    /// Days left until expiration. Negative if the token has already
+    /// expired.
+    pub fn days_to_expiration(&self, today: NaiveDate) -> Option<i64> {
+        let expires_at = self.expires_at.as_ref()?;
+        let expires_at = NaiveDate::parse_from_str(expires_at, "%Y-%m-%d").ok()?;
+        Some((expires_at - today).num_days())
+    }
+}
+
+/// A token metadata together with the day it is displayed, so days left can
+/// be computed.
+#[derive(Clone)]
+struct TokenRow(TokenMetadata, NaiveDate);
+
+impl From<TokenRow> for DisplayBody {
+    fn from(row: TokenRow) -> Self {
+        let TokenRow(token, today) = row;
+        let days_left = token
+            .days_to_expiration(today)
+            .map(|days| days.to_string())
+            .unwrap_or("never".to_string());
+        DisplayBody::new(vec![
+            Column::new("Domain", token.domain),
+            Column::new("Name", token.name),
+            Column::new("Scopes", token.scopes),
+            Column::new("Expires at", token.expires_at.unwrap_or_default()),
+            Column::new("Days left", days_left),
+        ])
+    }
+}
+
+pub fn execute<P: AsRef<Path>>(options: AuthOptions, config_file: P) -> Result<()> {
+    match options {
+        AuthOptions::Tokens(cli_args) => {
+            let config_file = config_file.as_ref();
+            let domains = if cli_args.domains.is_empty() {
+                let f = File::open(config_file).err_context(GRError::ConfigurationError(
+                    format!("Cannot open config file {}", config_file.display()),
+                ))?;
+                Config::domains(f)?
+            } else {
+                cli_args.domains
+            };
+            let mut remotes = Vec::new();
+            for domain in domains {
+                let config = common::read_config(config_file, &domain)?;
+                remotes.push(remote::get_token_info(
+                    domain,
+                    "".to_string(),
+                    config,
+                    true,
+                )?);
+            }
+            list_tokens(remotes, today(), std::io::stdout())
+        }
+    }
+}
+
+fn today() -> NaiveDate {
+    chrono::Utc::now().date_naive()
+}
+
+fn list_tokens<W: Write>(
+    remotes: Vec<Arc<dyn TokenInfo + Send + Sync>>,
+    today: NaiveDate,
+    mut writer: W,
+) -> Result<()> {
+    let mut tokens = Vec::new();
+    for remote in remotes {
+        tokens.push(TokenRow(remote.token()?, today));
+    }
+    if tokens.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoResourcesFound))?;
+        return Ok(());
+    }
+    display::print(&mut writer, tokens, GetRemoteCliArgs::default())?;
+    Ok(())
+}
+
+/// Warns when the API token of the domain expires within the number of days
+/// configured. Run after a command succeeds, errors are only logged so they
+/// never hide the command's outcome.
+pub fn warn_token_expiration(config: Arc<Config>, domain: String, path: String) {
+    let warning_days = config.token_expiration_warning_days();
+    if warning_days == 0 {
+        return;
+    }
+    // Token metadata is cached as any other project data.
+    let token = remote::get_token_info(domain, path, config, false).and_then(|r| r.token());
+    match token {
+        Ok(token) => {
+            if let Err(err) =
+                write_expiration_warning(&token, warning_days, today(), std::io::stderr())
+            {
+                log_debug!("Cannot write token expiration warning: {}", err);
+            }
+        }
+        Err(err) => log_debug!("Cannot check token expiration: {}", err),
+    }
+}
+
+fn write_expiration_warning<W: Write>(
+    token: &TokenMetadata,
+    warning_days: u32,
+    today: NaiveDate,
+    mut writer: W,
+) -> Result<()> {
+    let Some(days) = token.days_to_expiration(today) else {
+        return Ok(());
+    };
+    if days > warning_days as i64 {
+        return Ok(());
+    }
+    let expires_at = token.expires_at.as_deref().unwrap_or_default();
+    let message = if days < 0 {
+        format!(
+            "Warning: API token for {} expired on {}",
+            token.domain, expires_at
+        )
+    } else {
+        format!(
+            "Warning: API token for {} expires on {} ({} days left)",
+            token.domain, expires_at, days
+        )
+    };
+    writer.write_all(
+        format!(
+            "{}. Set {}.token_expiration_warning_days=0 to silence this warning.\n",
+            message, token.domain
+        )
+        .as_bytes(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TokenInfoMock {
+        token: TokenMetadata,
+    }
+
+    impl TokenInfo for TokenInfoMock {
+        fn token(&self) -> Result<TokenMetadata> {
+            Ok(self.token.clone())
+        }
+    }
+
+    fn token(domain: &str, expires_at: Option<&str>) -> TokenMetadata {
+        TokenMetadata::builder()
+            .domain(domain.to_string())
+            .name("gitar".to_string())
+            .scopes("api".to_string())
+            .expires_at(expires_at.map(|s| s.to_string()))
+            .build()
+            .unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn test_list_tokens_with_days_left() {
+        let remotes: Vec<Arc<dyn TokenInfo + Send + Sync>> = vec![
+            Arc::new(TokenInfoMock {
+                token: token("gitlab.com", Some("2024-03-11")),
+            }),
+            Arc::new(TokenInfoMock {
+                token: token("github.com", None),
+            }),
+        ];
+        let mut buf = Vec::new();
+        list_tokens(remotes, today(), &mut buf).unwrap();
+        assert_eq!(
+            "Domain|Name|Scopes|Expires at|Days left\n\
+             gitlab.com|gitar|api|2024-03-11|10\n\
+             github.com|gitar|api||never\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_warn_token_expires_within_warning_days() {
+        let mut buf = Vec::new();
+        write_expiration_warning(
+            &token("gitlab.com", Some("2024-03-04")),
+            7,
+            today(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            "Warning: API token for gitlab.com expires on 2024-03-04 (3 days left). \
+             Set gitlab.com.token_expiration_warning_days=0 to silence this warning.\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_warn_token_already_expired() {
+        let mut buf = Vec::new();
+        write_expiration_warning(
+            &token("gitlab.com", Some("2024-02-28")),
+            7,
+            today(),
+            &mut buf,
+        )
+        .unwrap();
+        assert!(String::from_utf8(buf)
+            .unwrap()
+            .starts_with("Warning: API token for gitlab.com expired on 2024-02-28."));
+    }
+
+    #[test]
+    fn test_no_warning_if_expiration_is_far_or_unknown() {
+        let mut buf = Vec::new();
+        write_expiration_warning(
+            &token("gitlab.com", Some("2024-04-01")),
+            7,
+            today(),
+            &mut buf,
+        )
+        .unwrap();
+        write_expiration_warning(&token("gitlab.com", None), 7, today(), &mut buf).unwrap();
+        assert!(buf.is_empty());
+    }
+}
@@ -18,19 +18,9 @@ pub fn execute(
             let remote_url = format!("https://{}/{}", domain, path);
             Ok(open::that(remote_url)?)
         }
-        BrowseOptions::MergeRequests => {
+        options => {
             let remote = remote::get_project(domain, path, config, false)?;
-            Ok(open::that(remote.get_url(BrowseOptions::MergeRequests))?)
-        }
-        BrowseOptions::MergeRequestId(id) => {
-            let remote = remote::get_project(domain, path, config, false)?;
-            Ok(open::that(
-                remote.get_url(BrowseOptions::MergeRequestId(id)),
-            )?)
-        }
-        BrowseOptions::Pipelines => {
-            let remote = remote::get_project(domain, path, config, false)?;
-            Ok(open::that(remote.get_url(BrowseOptions::Pipelines))?)
+            Ok(open::that(remote.get_url(options))?)
         }
     }
 }
@@ -0,0 +1,258 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use crate::api_traits::{Cicd, MergeRequest, RemoteProject};
+use crate::cli::cache::CacheOptions;
+use crate::cmds::cicd::PipelineBodyArgs;
+use crate::config::Config;
+use crate::display::{self, Column, DisplayBody};
+use crate::io::CmdInfo;
+use crate::remote::{
+    self, GetRemoteCliArgs, ListBodyArgs, MergeRequestListBodyArgs, MergeRequestState,
+};
+use crate::{exec, Cmd, Result};
+
+#[derive(Builder)]
+pub struct CacheWarmCliArgs {
+    /// Number of pages of merge requests and pipelines to prefetch.
+    pub pages: i64,
+}
+
+impl CacheWarmCliArgs {
+    pub fn builder() -> CacheWarmCliArgsBuilder {
+        CacheWarmCliArgsBuilder::default()
+    }
+}
+
+/// Outcome of prefetching one resource. A failure is reported, but does not
+/// stop the rest of the resources from being warmed.
+#[derive(Clone, Debug)]
+pub struct WarmedResource {
+    pub resource: String,
+    pub items: usize,
+    pub status: String,
+}
+
+impl WarmedResource {
+    fn new(resource: &str, result: Result<usize>) -> Self {
+        let (items, status) = match result {
+            Ok(items) => (items, "ok".to_string()),
+            Err(err) => (0, format!("failed: {}", err)),
+        };
+        WarmedResource {
+            resource: resource.to_string(),
+            items,
+            status,
+        }
+    }
+}
+
+impl From<WarmedResource> for DisplayBody {
+    fn from(warmed: WarmedResource) -> Self {
+        DisplayBody::new(vec![
+            Column::new("Resource", warmed.resource),
+            Column::new("Items", warmed.items.to_string()),
+            Column::new("Status", warmed.status),
+        ])
+    }
+}
+
+pub fn execute(
+    options: CacheOptions,
+    config: Arc<Config>,
+    domain: String,
+    path: String,
+) -> Result<()> {
+    match options {
+        CacheOptions::Warm(cli_args) => {
+            // Always hit the remote, so the cache is fresh when the review
+            // session starts.
+            let project = remote::get_project(domain.clone(), path.clone(), config.clone(), true)?;
+            let mr = remote::get_mr(domain.clone(), path.clone(), config.clone(), true)?;
+            let cicd = remote::get_cicd(domain, path, config, true)?;
+            warm_cache(project, mr, cicd, &cli_args, std::io::stdout())
+        }
+    }
+}
+
+fn warm_cache<W: Write>(
+    project: Arc<dyn RemoteProject + Send + Sync>,
+    mr: Arc<dyn MergeRequest + Send + Sync>,
+    cicd: Arc<dyn Cicd + Send + Sync>,
+    cli_args: &CacheWarmCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let list_args = ListBodyArgs::builder()
+        .page(1)
+        .max_pages(cli_args.pages)
+        .build()?;
+    let mr_args = MergeRequestListBodyArgs::builder()
+        .state(MergeRequestState::Opened)
+        .list_args(Some(list_args.clone()))
+        .assignee_id(None)
+        .build()?;
+    let pipeline_args = PipelineBodyArgs::builder()
+        .from_to_page(Some(list_args))
+        .build()?;
+    let members = project.clone();
+    let cmds: Vec<Cmd<(usize, WarmedResource)>> = vec![
+        Box::new(move || {
+            let result = project.get_project_data(None).map(|_| 1);
+            Ok((0, WarmedResource::new("project", result)))
+        }),
+        Box::new(move || {
+            let result = members
+                .get_project_members()
+                .map(|cmd_info| match cmd_info {
+                    CmdInfo::Members(members) => members.len(),
+                    _ => 0,
+                });
+            Ok((1, WarmedResource::new("members", result)))
+        }),
+        Box::new(move || {
+            let result = mr.list(mr_args).map(|mrs| mrs.len());
+            Ok((2, WarmedResource::new("merge requests", result)))
+        }),
+        Box::new(move || {
+            let result = cicd.list(pipeline_args).map(|pipelines| pipelines.len());
+            Ok((3, WarmedResource::new("pipelines", result)))
+        }),
+    ];
+    let mut warmed = Vec::new();
+    for result in exec::parallel_stream(cmds) {
+        warmed.push(result?);
+    }
+    // Results arrive in completion order. Keep a stable order for display.
+    warmed.sort_by_key(|(position, _)| *position);
+    let warmed = warmed
+        .into_iter()
+        .map(|(_, resource)| resource)
+        .collect::<Vec<_>>();
+    display::print(&mut writer, warmed, GetRemoteCliArgs::default())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::browse::BrowseOptions;
+    use crate::cmds::cicd::Pipeline;
+    use crate::cmds::project::ProjectListBodyArgs;
+    use crate::error;
+    use crate::io::PageCount;
+    use crate::remote::{
+        Member, MergeRequestBodyArgs, MergeRequestLimits, MergeRequestResponse, Project,
+    };
+
+    use super::*;
+
+    struct ProjectMock;
+
+    impl RemoteProject for ProjectMock {
+        fn get_project_data(&self, _id: Option<i64>) -> Result<CmdInfo> {
+            Ok(CmdInfo::Project(Project::default()))
+        }
+        fn get_project_members(&self) -> Result<CmdInfo> {
+            Ok(CmdInfo::Members(vec![
+                Member::builder()
+                    .id(1)
+                    .username("jdoe".to_string())
+                    .name("John Doe".to_string())
+                    .build()
+                    .unwrap(),
+                Member::builder()
+                    .id(2)
+                    .username("tsmith".to_string())
+                    .name("Tom Smith".to_string())
+                    .build()
+                    .unwrap(),
+            ]))
+        }
+        fn get_url(&self, _option: BrowseOptions) -> String {
+            todo!()
+        }
+        fn list(&self, _args: ProjectListBodyArgs) -> Result<Vec<Project>> {
+            todo!()
+        }
+        fn num_pages(&self, _args: ProjectListBodyArgs) -> Result<Option<PageCount>> {
+            todo!()
+        }
+    }
+
+    struct MergeRequestMock;
+
+    impl MergeRequest for MergeRequestMock {
+        fn open(&self, _args: MergeRequestBodyArgs) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn list(&self, args: MergeRequestListBodyArgs) -> Result<Vec<MergeRequestResponse>> {
+            assert_eq!(Some(2), args.list_args.unwrap().max_pages);
+            Ok(vec![MergeRequestResponse::default()])
+        }
+        fn merge(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn get(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn close(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn reopen(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn num_pages(&self, _args: MergeRequestListBodyArgs) -> Result<Option<PageCount>> {
+            todo!()
+        }
+        fn limits(&self) -> MergeRequestLimits {
+            todo!()
+        }
+        fn set_draft(&self, _id: i64, _draft: bool) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn approve(&self, _id: i64, _comment: Option<&str>) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+    }
+
+    struct CicdMock;
+
+    impl Cicd for CicdMock {
+        fn list(&self, _args: PipelineBodyArgs) -> Result<Vec<Pipeline>> {
+            Err(error::gen("server error"))
+        }
+        fn get_pipeline(&self, _id: i64) -> Result<Pipeline> {
+            todo!()
+        }
+        fn num_pages(&self) -> Result<Option<PageCount>> {
+            todo!()
+        }
+        fn retry(&self, _id: i64) -> Result<Pipeline> {
+            todo!()
+        }
+        fn cancel(&self, _id: i64) -> Result<Pipeline> {
+            todo!()
+        }
+    }
+
+    #[test]
+    fn test_warm_cache_reports_each_resource_and_keeps_going_on_failure() {
+        let cli_args = CacheWarmCliArgs::builder().pages(2).build().unwrap();
+        let mut buf = Vec::new();
+        warm_cache(
+            Arc::new(ProjectMock),
+            Arc::new(MergeRequestMock),
+            Arc::new(CicdMock),
+            &cli_args,
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            "Resource|Items|Status\n\
+             project|1|ok\n\
+             members|2|ok\n\
+             merge requests|1|ok\n\
+             pipelines|0|failed: server error\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+}
@@ -1,17 +1,29 @@
-use crate::api_traits::{Cicd, CicdRunner, Timestamp};
-use crate::cli::cicd::{PipelineOptions, RunnerOptions};
-use crate::config::Config;
+use crate::api_traits::{
+    Cicd, CicdArtifact, CicdGraph, CicdJob, CicdLinter, CicdRunner, Timestamp,
+};
+use crate::checkpoint::Checkpoint;
+use crate::cli::cicd::{ArtifactOptions, JobOptions, PipelineOptions, RunnerOptions};
+use crate::config::{Config, ConfigProperties};
 use crate::display::{Column, DisplayBody};
+use crate::error::{AddContext, GRError};
+use crate::i18n::{self, Message};
 use crate::remote::{GetRemoteCliArgs, ListBodyArgs, ListRemoteCliArgs};
-use crate::{display, remote, Result};
+use crate::shell::Shell;
+use crate::time::{self, Milliseconds, Seconds};
+use crate::{api_defaults, cancel, display, git, remote, Result};
 use std::fmt::Display;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 
 use super::common::{self, num_cicd_pages, process_num_pages};
 
 #[derive(Builder, Clone, Debug)]
 pub struct Pipeline {
+    /// In Github, the workflow run ID.
+    #[builder(default)]
+    pub id: i64,
     pub status: String,
     web_url: String,
     branch: String,
@@ -25,6 +37,10 @@ impl Pipeline {
     pub fn builder() -> PipelineBuilder {
         PipelineBuilder::default()
     }
+
+    pub fn sha(&self) -> &str {
+        &self.sha
+    }
 }
 
 impl Timestamp for Pipeline {
@@ -149,6 +165,8 @@ pub struct RunnerListCliArgs {
     pub tags: Option<String>,
     #[builder(default)]
     pub all: bool,
+    #[builder(default)]
+    pub scope: RunnerScope,
     pub list_args: ListRemoteCliArgs,
 }
 
@@ -166,6 +184,8 @@ pub struct RunnerListBodyArgs {
     pub tags: Option<String>,
     #[builder(default)]
     pub all: bool,
+    #[builder(default)]
+    pub scope: RunnerScope,
 }
 
 impl RunnerListBodyArgs {
@@ -186,6 +206,408 @@ impl RunnerMetadataGetCliArgs {
     }
 }
 
+#[derive(Builder, Clone, Debug)]
+pub struct Artifact {
+    pub id: i64,
+    pub name: String,
+    pub size: u64,
+    pub created_at: String,
+    pub web_url: String,
+    #[builder(default)]
+    pub expired: bool,
+}
+
+impl Artifact {
+    pub fn builder() -> ArtifactBuilder {
+        ArtifactBuilder::default()
+    }
+}
+
+impl Timestamp for Artifact {
+    fn created_at(&self) -> String {
+        self.created_at.clone()
+    }
+}
+
+impl From<Artifact> for DisplayBody {
+    fn from(a: Artifact) -> DisplayBody {
+        DisplayBody {
+            columns: vec![
+                Column::new("ID", a.id.to_string()),
+                Column::new("Name", a.name),
+                Column::new("Size", common::human_size(a.size)),
+                Column::new("Created at", a.created_at),
+                Column::new("URL", a.web_url),
+            ],
+        }
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct ArtifactListBodyArgs {
+    pub list_args: Option<ListBodyArgs>,
+}
+
+impl ArtifactListBodyArgs {
+    pub fn builder() -> ArtifactListBodyArgsBuilder {
+        ArtifactListBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct ArtifactPruneCliArgs {
+    pub older_than: String,
+    #[builder(default)]
+    pub dry_run: bool,
+    /// Skip the artifacts erased by a previous interrupted run.
+    #[builder(default)]
+    pub resume: bool,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl ArtifactPruneCliArgs {
+    pub fn builder() -> ArtifactPruneCliArgsBuilder {
+        ArtifactPruneCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug)]
+pub struct Job {
+    pub id: i64,
+    pub name: String,
+    /// Empty in Github, where jobs are not grouped in stages.
+    #[builder(default)]
+    pub stage: String,
+    pub status: String,
+    pub web_url: String,
+    pub created_at: String,
+    /// Seconds the job ran for. `None` if it has not finished.
+    #[builder(default)]
+    pub duration: Option<u64>,
+}
+
+impl Job {
+    pub fn builder() -> JobBuilder {
+        JobBuilder::default()
+    }
+}
+
+impl Timestamp for Job {
+    fn created_at(&self) -> String {
+        self.created_at.clone()
+    }
+}
+
+impl From<Job> for DisplayBody {
+    fn from(j: Job) -> DisplayBody {
+        DisplayBody {
+            columns: vec![
+                Column::new("ID", j.id.to_string()),
+                Column::new("Name", j.name),
+                Column::new("Status", j.status),
+                Column::new("Created at", j.created_at),
+                Column::new("URL", j.web_url),
+            ],
+        }
+    }
+}
+
+/// Row of the job breakdown of `gr pp get`.
+#[derive(Clone)]
+struct JobBreakdown(Job);
+
+impl From<JobBreakdown> for DisplayBody {
+    fn from(JobBreakdown(job): JobBreakdown) -> DisplayBody {
+        DisplayBody::new(vec![
+            Column::new("Name", job.name),
+            Column::new("Stage", job.stage),
+            Column::new("Status", job.status),
+            Column::new(
+                "Duration",
+                job.duration
+                    .map(|duration| duration.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ])
+    }
+}
+
+/// A job in the graph of a pipeline.
+#[derive(Builder, Clone, Debug)]
+pub struct PipelineJobNode {
+    pub name: String,
+    pub stage: String,
+    /// Jobs that have to finish before this one starts. None when the job
+    /// waits for all the jobs in the previous stage instead.
+    #[builder(default)]
+    pub needs: Option<Vec<String>>,
+}
+
+impl PipelineJobNode {
+    pub fn builder() -> PipelineJobNodeBuilder {
+        PipelineJobNodeBuilder::default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphFormat {
+    Mermaid,
+    PlantUml,
+}
+
+#[derive(Builder, Clone)]
+pub struct PipelineGraphCliArgs {
+    pub id: i64,
+    #[builder(default = "GraphFormat::Mermaid")]
+    pub format: GraphFormat,
+}
+
+impl PipelineGraphCliArgs {
+    pub fn builder() -> PipelineGraphCliArgsBuilder {
+        PipelineGraphCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct PipelineDiffCliArgs {
+    /// Pipeline taken as the baseline.
+    pub from_id: i64,
+    pub to_id: i64,
+    /// Percentage a job has to be slower by to be reported as a regression.
+    #[builder(default = "50")]
+    pub threshold: u64,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl PipelineDiffCliArgs {
+    pub fn builder() -> PipelineDiffCliArgsBuilder {
+        PipelineDiffCliArgsBuilder::default()
+    }
+}
+
+/// A job compared between two pipelines. Jobs are matched by name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobDiff {
+    pub name: String,
+    pub from_status: Option<String>,
+    pub to_status: Option<String>,
+    pub from_duration: Option<u64>,
+    pub to_duration: Option<u64>,
+    pub change: JobChange,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobChange {
+    NewlyFailing,
+    Regression,
+    Fixed,
+    Added,
+    Removed,
+    Unchanged,
+}
+
+impl Display for JobChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobChange::NewlyFailing => write!(f, "newly failing"),
+            JobChange::Regression => write!(f, "slower"),
+            JobChange::Fixed => write!(f, "fixed"),
+            JobChange::Added => write!(f, "added"),
+            JobChange::Removed => write!(f, "removed"),
+            JobChange::Unchanged => write!(f, ""),
+        }
+    }
+}
+
+impl From<JobDiff> for DisplayBody {
+    fn from(diff: JobDiff) -> DisplayBody {
+        let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        DisplayBody::new(vec![
+            Column::new("Name", diff.name),
+            Column::new("From status", or_dash(diff.from_status)),
+            Column::new("To status", or_dash(diff.to_status)),
+            Column::new(
+                "From duration",
+                or_dash(diff.from_duration.map(|d| d.to_string())),
+            ),
+            Column::new(
+                "To duration",
+                or_dash(diff.to_duration.map(|d| d.to_string())),
+            ),
+            Column::new("Change", diff.change.to_string()),
+        ])
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct PipelineFlakyCliArgs {
+    /// Number of most recent pipelines to scan.
+    #[builder(default = "50")]
+    pub last: u32,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl PipelineFlakyCliArgs {
+    pub fn builder() -> PipelineFlakyCliArgsBuilder {
+        PipelineFlakyCliArgsBuilder::default()
+    }
+}
+
+/// A job that went from passing to failing and back on the same branch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlakyJob {
+    pub name: String,
+    /// Finished runs, across all branches.
+    pub runs: u32,
+    pub failures: u32,
+    /// Times the outcome changed between consecutive runs on a branch.
+    pub flips: u32,
+    /// Percentage of consecutive runs on a branch with a different outcome.
+    pub flake_rate: u32,
+    pub branches: Vec<String>,
+}
+
+impl From<FlakyJob> for DisplayBody {
+    fn from(job: FlakyJob) -> DisplayBody {
+        DisplayBody::new(vec![
+            Column::new("Name", job.name),
+            Column::new("Runs", job.runs.to_string()),
+            Column::new("Failures", job.failures.to_string()),
+            Column::new("Flips", job.flips.to_string()),
+            Column::new("Flake rate", format!("{}%", job.flake_rate)),
+            Column::new("Branches", job.branches.join(", ")),
+        ])
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct JobRetryCliArgs {
+    pub id: i64,
+    /// Variables overriding the ones defined for the job as (key, value)
+    #[builder(default)]
+    pub variables: Vec<(String, String)>,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl JobRetryCliArgs {
+    pub fn builder() -> JobRetryCliArgsBuilder {
+        JobRetryCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct JobRetryBodyArgs {
+    pub id: i64,
+    #[builder(default)]
+    pub variables: Vec<(String, String)>,
+}
+
+impl JobRetryBodyArgs {
+    pub fn builder() -> JobRetryBodyArgsBuilder {
+        JobRetryBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct LintCliArgs {
+    /// CI configuration file. Defaults to the remote's standard location.
+    #[builder(default)]
+    pub file: Option<PathBuf>,
+}
+
+impl LintCliArgs {
+    pub fn builder() -> LintCliArgsBuilder {
+        LintCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct LintBodyArgs {
+    pub content: String,
+}
+
+impl LintBodyArgs {
+    pub fn builder() -> LintBodyArgsBuilder {
+        LintBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl LintError {
+    pub fn new(line: Option<usize>, message: impl Into<String>) -> Self {
+        LintError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LintResponse {
+    pub valid: bool,
+    pub errors: Vec<LintError>,
+    /// Configuration after expanding includes, extends and anchors.
+    pub merged_yaml: String,
+}
+
+#[derive(Builder, Clone)]
+pub struct PipelineActionCliArgs {
+    pub id: i64,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl PipelineActionCliArgs {
+    pub fn builder() -> PipelineActionCliArgsBuilder {
+        PipelineActionCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct PipelineWaitCliArgs {
+    /// Pipeline to wait for. `None` waits for the most recent pipeline of
+    /// the commit checked out locally.
+    #[builder(default)]
+    pub id: Option<i64>,
+    /// Seconds to wait for the pipeline to finish.
+    #[builder(default = "3600")]
+    pub timeout: u64,
+    /// Delay after the first poll. It doubles after every poll up to
+    /// `max_poll_interval`.
+    #[builder(default = "Milliseconds::new(5000)")]
+    pub poll_interval: Milliseconds,
+    #[builder(default = "Milliseconds::new(60000)")]
+    pub max_poll_interval: Milliseconds,
+}
+
+impl PipelineWaitCliArgs {
+    pub fn builder() -> PipelineWaitCliArgsBuilder {
+        PipelineWaitCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct MergedConfigCliArgs {
+    pub ref_: String,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl MergedConfigCliArgs {
+    pub fn builder() -> MergedConfigCliArgsBuilder {
+        MergedConfigCliArgsBuilder::default()
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum RunnerStatus {
     Online,
@@ -207,6 +629,18 @@ impl Display for RunnerStatus {
     }
 }
 
+/// Where the runners to list are registered.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RunnerScope {
+    /// Runners available to the current project.
+    #[default]
+    Project,
+    /// Runners of the group the project belongs to.
+    Group,
+    /// Shared runners of the instance. Gitlab admins only.
+    Instance,
+}
+
 pub fn execute(
     options: PipelineOptions,
     config: Arc<Config>,
@@ -214,7 +648,10 @@ pub fn execute(
     path: String,
 ) -> Result<()> {
     match options {
-        PipelineOptions::List(cli_args) => {
+        PipelineOptions::List(mut cli_args) => {
+            cli_args.get_args = cli_args
+                .get_args
+                .with_default_columns(config.columns("pipeline"));
             let remote = remote::get_cicd(domain, path, config, cli_args.get_args.refresh_cache)?;
             if cli_args.num_pages {
                 return num_cicd_pages(remote, std::io::stdout());
@@ -223,10 +660,52 @@ pub fn execute(
             let body_args = PipelineBodyArgs::builder()
                 .from_to_page(from_to_args)
                 .build()?;
-            list_pipelines(remote, body_args, cli_args, std::io::stdout())
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_pipelines(remote, body_args, cli_args, writer)
+            })
+        }
+        PipelineOptions::Get(cli_args) => {
+            let remote = remote::get_cicd(
+                domain.clone(),
+                path.clone(),
+                config.clone(),
+                cli_args.get_args.refresh_cache,
+            )?;
+            let jobs_remote =
+                remote::get_cicd_job(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                get_pipeline(remote, jobs_remote, cli_args, writer)
+            })
+        }
+        PipelineOptions::Retry(cli_args) => {
+            let remote = remote::get_cicd(domain, path, config, cli_args.get_args.refresh_cache)?;
+            retry_pipeline(remote, cli_args, std::io::stdout())
+        }
+        PipelineOptions::Cancel(cli_args) => {
+            let remote = remote::get_cicd(domain, path, config, cli_args.get_args.refresh_cache)?;
+            cancel_pipeline(remote, cli_args, std::io::stdout())
+        }
+        PipelineOptions::Wait(cli_args) => {
+            // Always fresh, the status is what is being waited on.
+            let remote = remote::get_cicd(domain, path, config, true)?;
+            let head_sha = match cli_args.id {
+                Some(_) => None,
+                None => Some(git::head_sha(&Shell)?),
+            };
+            wait_pipeline(remote, cli_args, head_sha, std::io::stdout())
+        }
+        PipelineOptions::Graph(cli_args) => {
+            let remote = remote::get_cicd_graph(domain, path, config, false)?;
+            pipeline_graph(remote, cli_args, std::io::stdout())
         }
         PipelineOptions::Runners(options) => match options {
-            RunnerOptions::List(cli_args) => {
+            RunnerOptions::List(mut cli_args) => {
+                cli_args.list_args.get_args = cli_args
+                    .list_args
+                    .get_args
+                    .with_default_columns(config.columns("runner"));
                 let remote = remote::get_cicd_runner(
                     domain,
                     path,
@@ -240,21 +719,708 @@ pub fn execute(
                     .status(cli_args.status)
                     .tags(tags)
                     .all(cli_args.all)
+                    .scope(cli_args.scope)
                     .build()?;
                 if cli_args.list_args.num_pages {
                     return process_num_pages(remote.num_pages(body_args), std::io::stdout());
                 }
-                list_runners(remote, body_args, cli_args, std::io::stdout())
+                let output = cli_args.list_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    list_runners(remote, body_args, cli_args, writer)
+                })
             }
             RunnerOptions::Get(cli_args) => {
                 let remote =
                     remote::get_cicd_runner(domain, path, config, cli_args.get_args.refresh_cache)?;
-                get_runner_details(remote, cli_args, std::io::stdout())
+                let output = cli_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    get_runner_details(remote, cli_args, writer)
+                })
+            }
+            RunnerOptions::Shared(enabled) => {
+                let remote = remote::get_cicd_runner(domain, path, config, false)?;
+                set_shared_runners(remote, enabled, std::io::stdout())
+            }
+        },
+        PipelineOptions::Artifacts(options) => match options {
+            ArtifactOptions::Prune(cli_args) => {
+                let checkpoint = if cli_args.dry_run {
+                    Checkpoint::disabled()
+                } else {
+                    Checkpoint::new(
+                        config.cache_location(),
+                        &format!(
+                            "artifacts prune {}/{} {}",
+                            domain, path, cli_args.older_than
+                        ),
+                        cli_args.resume,
+                    )?
+                };
+                let remote = remote::get_cicd_artifact(
+                    domain,
+                    path,
+                    config,
+                    cli_args.get_args.refresh_cache,
+                )?;
+                prune_artifacts(
+                    remote,
+                    cli_args,
+                    time::now_epoch_seconds(),
+                    checkpoint,
+                    std::io::stdout(),
+                )
             }
         },
+        PipelineOptions::Jobs(options) => match options {
+            JobOptions::Retry(cli_args) => {
+                let remote =
+                    remote::get_cicd_job(domain, path, config, cli_args.get_args.refresh_cache)?;
+                retry_job(remote, cli_args, std::io::stdout())
+            }
+        },
+        PipelineOptions::Diff(cli_args) => {
+            let remote =
+                remote::get_cicd_job(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                pipeline_diff(remote, cli_args, writer)
+            })
+        }
+        PipelineOptions::Flaky(cli_args) => {
+            let pipelines_remote = remote::get_cicd(
+                domain.clone(),
+                path.clone(),
+                config.clone(),
+                cli_args.get_args.refresh_cache,
+            )?;
+            let jobs_remote =
+                remote::get_cicd_job(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                pipeline_flaky(pipelines_remote, jobs_remote, cli_args, writer)
+            })
+        }
+        PipelineOptions::Lint(cli_args) => {
+            let files = match &cli_args.file {
+                Some(file) => vec![file.clone()],
+                None => {
+                    let root = git::toplevel(&Shell)?;
+                    default_ci_files(&domain, Path::new(&root))?
+                }
+            };
+            let remote = remote::get_cicd_linter(domain, path, config, false)?;
+            lint_ci_files(remote, files, std::io::stdout())
+        }
+        PipelineOptions::MergedConfig(cli_args) => {
+            let remote =
+                remote::get_cicd_linter(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                merged_ci_config(remote, cli_args, writer)
+            })
+        }
+    }
+}
+
+/// Gitlab keeps its configuration in `.gitlab-ci.yml` whereas Github can
+/// have several workflows under `.github/workflows`.
+fn default_ci_files(domain: &str, repo_root: &Path) -> Result<Vec<PathBuf>> {
+    if !domain.starts_with("github") {
+        return Ok(vec![repo_root.join(".gitlab-ci.yml")]);
+    }
+    let workflows_dir = repo_root.join(".github").join("workflows");
+    let mut files = std::fs::read_dir(&workflows_dir)
+        .err_context(GRError::PreconditionNotMet(format!(
+            "Cannot read workflows directory {}",
+            workflows_dir.display()
+        )))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .map(|ext| ext == "yml" || ext == "yaml")
+                .unwrap_or(false)
+        })
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+    Ok(files)
+}
+
+fn merged_ci_config<W: Write>(
+    remote: Arc<dyn CicdLinter>,
+    cli_args: MergedConfigCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let response = remote.merged_config(&cli_args.ref_)?;
+    if !response.valid {
+        let errors = response
+            .errors
+            .into_iter()
+            .map(|error| error.message)
+            .collect::<Vec<String>>()
+            .join("\n");
+        return Err(GRError::PreconditionNotMet(format!(
+            "CI configuration at {} is invalid:\n{}",
+            cli_args.ref_, errors
+        ))
+        .into());
+    }
+    writer.write_all(response.merged_yaml.as_bytes())?;
+    Ok(())
+}
+
+fn lint_ci_files<W: Write>(
+    remote: Arc<dyn CicdLinter>,
+    files: Vec<PathBuf>,
+    mut writer: W,
+) -> Result<()> {
+    let mut invalid = 0;
+    for file in files.iter() {
+        let content = std::fs::read_to_string(file).err_context(GRError::PreconditionNotMet(
+            i18n::format(Message::CannotReadCiFile, &[&file.display()]),
+        ))?;
+        let body_args = LintBodyArgs::builder().content(content).build()?;
+        let response = remote.lint(body_args)?;
+        if response.valid {
+            writeln!(
+                writer,
+                "{}",
+                i18n::format(Message::CiConfigValid, &[&file.display()])
+            )?;
+            continue;
+        }
+        invalid += 1;
+        for error in response.errors {
+            let location = match error.line {
+                Some(line) => format!("{}:{}", file.display(), line),
+                None => file.display().to_string(),
+            };
+            writer.write_all(format!("{}: {}\n", location, error.message).as_bytes())?;
+        }
+    }
+    if invalid > 0 {
+        return Err(GRError::PreconditionNotMet(i18n::format(
+            Message::CiConfigInvalid,
+            &[&invalid, &files.len()],
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Prints the pipeline followed by the status and duration of each of its
+/// jobs.
+fn get_pipeline<W: Write>(
+    remote: Arc<dyn Cicd>,
+    jobs_remote: Arc<dyn CicdJob>,
+    cli_args: PipelineActionCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let pipeline = remote.get_pipeline(cli_args.id)?;
+    let jobs = jobs_remote.list(cli_args.id)?;
+    display::print(&mut writer, vec![pipeline], cli_args.get_args.clone())?;
+    if jobs.is_empty() {
+        return Ok(());
+    }
+    writeln!(writer)?;
+    let jobs = jobs.into_iter().map(JobBreakdown).collect::<Vec<_>>();
+    display::print(&mut writer, jobs, cli_args.get_args)?;
+    Ok(())
+}
+
+fn retry_pipeline<W: Write>(
+    remote: Arc<dyn Cicd>,
+    cli_args: PipelineActionCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let pipeline = remote.retry(cli_args.id)?;
+    display::print(&mut writer, vec![pipeline], cli_args.get_args)?;
+    Ok(())
+}
+
+fn cancel_pipeline<W: Write>(
+    remote: Arc<dyn Cicd>,
+    cli_args: PipelineActionCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let pipeline = remote.cancel(cli_args.id)?;
+    display::print(&mut writer, vec![pipeline], cli_args.get_args)?;
+    Ok(())
+}
+
+/// Polls the pipeline until it is done. Errors out if it does not pass, so
+/// scripts can chain on its exit code. Without a pipeline id, waits for the
+/// most recent pipeline of `head_sha`, which may not have been created yet.
+pub fn wait_pipeline<W: Write>(
+    remote: Arc<dyn Cicd>,
+    cli_args: PipelineWaitCliArgs,
+    head_sha: Option<String>,
+    mut writer: W,
+) -> Result<()> {
+    let head_sha = head_sha.unwrap_or_default();
+    let started = Instant::now();
+    let mut id = cli_args.id;
+    let mut interval = cli_args.poll_interval;
+    let mut last_status = None;
+    if id.is_none() {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::WaitingForPipeline, &[&head_sha])
+        )?;
+    }
+    loop {
+        let pipeline = match id {
+            Some(id) => Some(remote.get_pipeline(id)?),
+            None => latest_pipeline(remote.as_ref(), &head_sha)?,
+        };
+        if let Some(pipeline) = pipeline {
+            id = Some(pipeline.id);
+            if last_status.as_ref() != Some(&pipeline.status) {
+                writeln!(
+                    writer,
+                    "{}",
+                    i18n::format(Message::PipelineStatus, &[&pipeline.id, &pipeline.status])
+                )?;
+                last_status = Some(pipeline.status.clone());
+            }
+            match pipeline_passed(&pipeline.status) {
+                Some(true) => {
+                    writeln!(writer, "{}", pipeline.web_url)?;
+                    return Ok(());
+                }
+                Some(false) => {
+                    return Err(GRError::ApplicationError(format!(
+                        "Pipeline {} finished with status {}: {}",
+                        pipeline.id, pipeline.status, pipeline.web_url
+                    ))
+                    .into());
+                }
+                None => {}
+            }
+        }
+        let what = match id {
+            Some(id) => format!("waiting for pipeline {}", id),
+            None => format!("waiting for a pipeline of commit {}", head_sha),
+        };
+        if started.elapsed().as_secs() >= cli_args.timeout {
+            return Err(GRError::ApplicationError(format!(
+                "Stopped {} after {} seconds",
+                what, cli_args.timeout
+            ))
+            .into());
+        }
+        cancel::check(&what)?;
+        cancel::sleep(interval.into(), &what)?;
+        interval = Milliseconds::new((*interval * 2).min(*cli_args.max_poll_interval));
     }
 }
 
+/// Most recent pipeline of the commit `sha`. Only the first page is looked
+/// at, a pushed commit gets its pipeline right away.
+fn latest_pipeline(remote: &dyn Cicd, sha: &str) -> Result<Option<Pipeline>> {
+    let list_args = ListBodyArgs::builder().page(1).max_pages(1).build()?;
+    let body_args = PipelineBodyArgs::builder()
+        .from_to_page(Some(list_args))
+        .build()?;
+    let mut pipelines = remote.list(body_args)?;
+    pipelines.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(pipelines.into_iter().find(|pipeline| pipeline.sha == sha))
+}
+
+/// Whether a pipeline that is done passed, `None` while it is not done. A
+/// Gitlab pipeline waiting on a manual job is done, nothing else runs on its
+/// own.
+fn pipeline_passed(status: &str) -> Option<bool> {
+    match status {
+        "success" | "skipped" | "manual" | "neutral" => Some(true),
+        "canceled" | "cancelled" | "action_required" | "startup_failure" | "stale" => Some(false),
+        status if is_failure(status) => Some(false),
+        _ => None,
+    }
+}
+
+fn pipeline_graph<W: Write>(
+    remote: Arc<dyn CicdGraph>,
+    cli_args: PipelineGraphCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let jobs = remote.jobs(cli_args.id)?;
+    if jobs.is_empty() {
+        return Err(
+            GRError::PreconditionNotMet(format!("Pipeline {} has no jobs", cli_args.id)).into(),
+        );
+    }
+    writer.write_all(render_graph(&jobs, cli_args.format).as_bytes())?;
+    Ok(())
+}
+
+/// Draws the jobs grouped by stage with an edge from each job to the ones
+/// depending on it. Jobs are given ids by position as their names can have
+/// characters that are not valid in ids.
+fn render_graph(jobs: &[PipelineJobNode], format: GraphFormat) -> String {
+    let mut stages: Vec<&str> = Vec::new();
+    for job in jobs {
+        if !stages.contains(&job.stage.as_str()) {
+            stages.push(&job.stage);
+        }
+    }
+    let position = |name: &str| jobs.iter().position(|job| job.name == name);
+    let mut edges = Vec::new();
+    for (index, job) in jobs.iter().enumerate() {
+        match &job.needs {
+            Some(needs) => {
+                for need in needs.iter().filter_map(|need| position(need)) {
+                    edges.push((need, index));
+                }
+            }
+            None => {
+                let stage = stages.iter().position(|s| *s == job.stage).unwrap();
+                if stage == 0 {
+                    continue;
+                }
+                for (previous, _) in jobs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| other.stage == stages[stage - 1])
+                {
+                    edges.push((previous, index));
+                }
+            }
+        }
+    }
+    let mut graph = String::new();
+    match format {
+        GraphFormat::Mermaid => {
+            graph.push_str("flowchart LR\n");
+            for (stage_index, stage) in stages.iter().enumerate() {
+                graph.push_str(&format!(
+                    "    subgraph stage{}[\"{}\"]\n",
+                    stage_index,
+                    stage.replace('"', "#quot;")
+                ));
+                for (index, job) in jobs.iter().enumerate().filter(|(_, j)| j.stage == *stage) {
+                    graph.push_str(&format!(
+                        "        job{}[\"{}\"]\n",
+                        index,
+                        job.name.replace('"', "#quot;")
+                    ));
+                }
+                graph.push_str("    end\n");
+            }
+            for (from, to) in edges {
+                graph.push_str(&format!("    job{} --> job{}\n", from, to));
+            }
+        }
+        GraphFormat::PlantUml => {
+            graph.push_str("@startuml\n");
+            for stage in &stages {
+                graph.push_str(&format!("rectangle \"{}\" {{\n", stage.replace('"', "'")));
+                for (index, job) in jobs.iter().enumerate().filter(|(_, j)| j.stage == *stage) {
+                    graph.push_str(&format!(
+                        "  rectangle \"{}\" as job{}\n",
+                        job.name.replace('"', "'"),
+                        index
+                    ));
+                }
+                graph.push_str("}\n");
+            }
+            for (from, to) in edges {
+                graph.push_str(&format!("job{} --> job{}\n", from, to));
+            }
+            graph.push_str("@enduml\n");
+        }
+    }
+    graph
+}
+
+fn retry_job<W: Write>(
+    remote: Arc<dyn CicdJob>,
+    cli_args: JobRetryCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let body_args = JobRetryBodyArgs::builder()
+        .id(cli_args.id)
+        .variables(cli_args.variables)
+        .build()?;
+    let job = remote.retry(body_args)?;
+    display::print(&mut writer, vec![job], cli_args.get_args)?;
+    Ok(())
+}
+
+fn pipeline_diff<W: Write>(
+    remote: Arc<dyn CicdJob>,
+    cli_args: PipelineDiffCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let from_jobs = remote.list(cli_args.from_id)?;
+    let to_jobs = remote.list(cli_args.to_id)?;
+    let diffs = diff_jobs(from_jobs, to_jobs, cli_args.threshold);
+    let newly_failing = diffs
+        .iter()
+        .filter(|diff| diff.change == JobChange::NewlyFailing)
+        .count();
+    let regressions = diffs
+        .iter()
+        .filter(|diff| diff.change == JobChange::Regression)
+        .count();
+    display::print(&mut writer, diffs, cli_args.get_args.clone())?;
+    if matches!(cli_args.get_args.format, display::Format::PIPE) {
+        writeln!(
+            writer,
+            "{} newly failing, {} slower by {}% or more",
+            newly_failing, regressions, cli_args.threshold
+        )?;
+    }
+    Ok(())
+}
+
+/// Jobs that ran for less than this are not reported as regressions, as small
+/// absolute differences are mostly noise from runner scheduling.
+const MIN_REGRESSION_SECONDS: u64 = 30;
+
+/// Matches the jobs of two pipelines by name, in the order of the `to`
+/// pipeline followed by the jobs that were removed. Newly failing jobs, which
+/// include new jobs that fail, and duration regressions are listed first.
+fn diff_jobs(from_jobs: Vec<Job>, to_jobs: Vec<Job>, threshold: u64) -> Vec<JobDiff> {
+    let mut from_jobs = from_jobs;
+    let mut diffs = Vec::new();
+    for to_job in to_jobs {
+        if diffs.iter().any(|diff: &JobDiff| diff.name == to_job.name) {
+            continue;
+        }
+        let from_job = from_jobs
+            .iter()
+            .position(|job| job.name == to_job.name)
+            .map(|pos| from_jobs.remove(pos));
+        let change = match &from_job {
+            None if is_failure(&to_job.status) => JobChange::NewlyFailing,
+            None => JobChange::Added,
+            Some(from_job) => job_change(from_job, &to_job, threshold),
+        };
+        diffs.push(JobDiff {
+            name: to_job.name,
+            from_status: from_job.as_ref().map(|job| job.status.clone()),
+            to_status: Some(to_job.status),
+            from_duration: from_job.and_then(|job| job.duration),
+            to_duration: to_job.duration,
+            change,
+        });
+    }
+    for from_job in from_jobs {
+        if diffs.iter().any(|diff| diff.name == from_job.name) {
+            continue;
+        }
+        diffs.push(JobDiff {
+            name: from_job.name,
+            from_status: Some(from_job.status),
+            to_status: None,
+            from_duration: from_job.duration,
+            to_duration: None,
+            change: JobChange::Removed,
+        });
+    }
+    diffs.sort_by_key(|diff| match diff.change {
+        JobChange::NewlyFailing => 0,
+        JobChange::Regression => 1,
+        _ => 2,
+    });
+    diffs
+}
+
+fn job_change(from_job: &Job, to_job: &Job, threshold: u64) -> JobChange {
+    match (is_failure(&from_job.status), is_failure(&to_job.status)) {
+        (false, true) => return JobChange::NewlyFailing,
+        (true, false) => return JobChange::Fixed,
+        _ => {}
+    }
+    if let (Some(from), Some(to)) = (from_job.duration, to_job.duration) {
+        if to >= from + MIN_REGRESSION_SECONDS && (to - from) * 100 >= from * threshold {
+            return JobChange::Regression;
+        }
+    }
+    JobChange::Unchanged
+}
+
+/// Gitlab reports `failed`, Github the `failure` and `timed_out` conclusions.
+fn is_failure(status: &str) -> bool {
+    matches!(status, "failed" | "failure" | "timed_out")
+}
+
+fn pipeline_flaky<W: Write>(
+    pipelines_remote: Arc<dyn Cicd>,
+    jobs_remote: Arc<dyn CicdJob>,
+    cli_args: PipelineFlakyCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let last = cli_args.last.max(1);
+    let per_page = last.min(api_defaults::REST_API_MAX_PER_PAGE);
+    let list_args = ListBodyArgs::builder()
+        .page(1)
+        .max_pages(last.div_ceil(per_page) as i64)
+        .per_page(Some(per_page))
+        .build()?;
+    let body_args = PipelineBodyArgs::builder()
+        .from_to_page(Some(list_args))
+        .build()?;
+    // Pages start from the most recent pipeline, but the list comes back
+    // sorted by creation date, oldest first.
+    let mut pipelines = pipelines_remote.list(body_args)?;
+    pipelines.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    pipelines.truncate(last as usize);
+    let history = pipelines
+        .into_iter()
+        .map(|pipeline| {
+            let jobs = jobs_remote.list(pipeline.id)?;
+            Ok((pipeline, jobs))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let flaky = flaky_jobs(history);
+    if flaky.is_empty() {
+        writeln!(writer, "{}", i18n::format(Message::NoFlakyJobs, &[&last]))?;
+        return Ok(());
+    }
+    display::print(&mut writer, flaky, cli_args.get_args)?;
+    Ok(())
+}
+
+/// Whether each run of a job on a branch passed, oldest first.
+type BranchOutcomes = (String, Vec<bool>);
+
+/// Walks the outcome of each job on each branch from the oldest pipeline to
+/// the newest. A job is flaky when its outcome changes at least twice on a
+/// branch, i.e. it failed and passed again or the other way around. A job
+/// that broke once and got fixed is not. Only finished jobs that passed or
+/// failed are taken into account. Flakiest jobs first.
+fn flaky_jobs(history: Vec<(Pipeline, Vec<Job>)>) -> Vec<FlakyJob> {
+    let mut history = history;
+    history.sort_by(|(a, _), (b, _)| a.created_at.cmp(&b.created_at));
+    // Outcomes of each job name per branch, in the order first seen.
+    let mut outcomes: Vec<(String, Vec<BranchOutcomes>)> = Vec::new();
+    for (pipeline, jobs) in history {
+        for job in jobs {
+            let passed = match job.status.as_str() {
+                "success" => true,
+                status if is_failure(status) => false,
+                _ => continue,
+            };
+            let branches = match outcomes.iter().position(|(name, _)| *name == job.name) {
+                Some(pos) => &mut outcomes[pos].1,
+                None => {
+                    outcomes.push((job.name, Vec::new()));
+                    &mut outcomes.last_mut().unwrap().1
+                }
+            };
+            match branches
+                .iter_mut()
+                .find(|(branch, _)| *branch == pipeline.branch)
+            {
+                Some((_, runs)) => runs.push(passed),
+                None => branches.push((pipeline.branch.clone(), vec![passed])),
+            }
+        }
+    }
+    let mut flaky = outcomes
+        .into_iter()
+        .filter_map(|(name, branches)| {
+            let mut job = FlakyJob {
+                name,
+                runs: 0,
+                failures: 0,
+                flips: 0,
+                flake_rate: 0,
+                branches: Vec::new(),
+            };
+            let mut transitions = 0;
+            for (branch, runs) in branches {
+                let flips = runs.windows(2).filter(|pair| pair[0] != pair[1]).count() as u32;
+                job.runs += runs.len() as u32;
+                job.failures += runs.iter().filter(|passed| !**passed).count() as u32;
+                job.flips += flips;
+                transitions += runs.len() as u32 - 1;
+                if flips >= 2 {
+                    job.branches.push(branch);
+                }
+            }
+            if job.branches.is_empty() {
+                return None;
+            }
+            job.flake_rate = job.flips * 100 / transitions;
+            Some(job)
+        })
+        .collect::<Vec<_>>();
+    flaky.sort_by(|a, b| b.flake_rate.cmp(&a.flake_rate).then(a.name.cmp(&b.name)));
+    flaky
+}
+
+/// Erases artifacts created before `now - older_than` and reports the space
+/// reclaimed. Artifacts recorded in the checkpoint are skipped.
+fn prune_artifacts<W: Write>(
+    remote: Arc<dyn CicdArtifact>,
+    cli_args: ArtifactPruneCliArgs,
+    now: Seconds,
+    mut checkpoint: Checkpoint,
+    mut writer: W,
+) -> Result<()> {
+    let older_than = Seconds::try_from(cli_args.older_than.as_str())?;
+    let created_before = time::epoch_to_rfc3339(now - older_than);
+    let list_args = ListBodyArgs::builder()
+        .created_before(Some(created_before))
+        .build()?;
+    let body_args = ArtifactListBodyArgs::builder()
+        .list_args(Some(list_args))
+        .build()?;
+    let (skipped, artifacts): (Vec<_>, Vec<_>) = remote
+        .list(body_args)?
+        .into_iter()
+        .partition(|artifact| checkpoint.is_done(artifact.id));
+    if !skipped.is_empty() {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::SkippingErasedArtifacts, &[&skipped.len()])
+        )?;
+    }
+    if artifacts.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoArtifactsToPrune))?;
+        return checkpoint.complete();
+    }
+    let mut reclaimed = 0;
+    let total = artifacts.len();
+    for (done, artifact) in artifacts.iter().enumerate() {
+        if cancel::cancelled() {
+            writeln!(
+                writer,
+                "{}",
+                i18n::format(Message::PruneCancelled, &[&done, &total])
+            )?;
+            return cancel::check("artifacts prune");
+        }
+        if !cli_args.dry_run {
+            remote.delete(artifact.id)?;
+            checkpoint.record(artifact.id)?;
+        }
+        reclaimed += artifact.size;
+    }
+    checkpoint.complete()?;
+    let num_artifacts = artifacts.len();
+    display::print(&mut writer, artifacts, cli_args.get_args)?;
+    let action = if cli_args.dry_run {
+        "Would reclaim"
+    } else {
+        "Reclaimed"
+    };
+    writer.write_all(
+        format!(
+            "{} {} from {} artifacts\n",
+            action,
+            common::human_size(reclaimed),
+            num_artifacts
+        )
+        .as_bytes(),
+    )?;
+    Ok(())
+}
+
 fn get_runner_details<W: Write>(
     remote: Arc<dyn CicdRunner>,
     cli_args: RunnerMetadataGetCliArgs,
@@ -265,6 +1431,21 @@ fn get_runner_details<W: Write>(
     Ok(())
 }
 
+fn set_shared_runners<W: Write>(
+    remote: Arc<dyn CicdRunner>,
+    enabled: bool,
+    mut writer: W,
+) -> Result<()> {
+    remote.set_shared_runners(enabled)?;
+    let message = if enabled {
+        Message::SharedRunnersEnabled
+    } else {
+        Message::SharedRunnersDisabled
+    };
+    writeln!(writer, "{}", i18n::text(message))?;
+    Ok(())
+}
+
 fn list_runners<W: Write>(
     remote: Arc<dyn CicdRunner>,
     body_args: RunnerListBodyArgs,
@@ -285,6 +1466,9 @@ fn list_pipelines<W: Write>(
 
 #[cfg(test)]
 mod test {
+    use crate::io::PageCount;
+    use std::sync::Mutex;
+
     use super::*;
     use crate::error;
 
@@ -298,32 +1482,213 @@ mod test {
         num_pages: Option<u32>,
     }
 
-    impl PipelineListMock {
-        pub fn builder() -> PipelineListMockBuilder {
-            PipelineListMockBuilder::default()
-        }
+    impl PipelineListMock {
+        pub fn builder() -> PipelineListMockBuilder {
+            PipelineListMockBuilder::default()
+        }
+    }
+
+    impl Cicd for PipelineListMock {
+        fn list(&self, _args: PipelineBodyArgs) -> Result<Vec<Pipeline>> {
+            if self.error {
+                return Err(error::gen("Error"));
+            }
+            let pp = self.pipelines.clone();
+            Ok(pp)
+        }
+
+        fn get_pipeline(&self, _id: i64) -> Result<Pipeline> {
+            let pp = self.pipelines.clone();
+            Ok(pp[0].clone())
+        }
+
+        fn num_pages(&self) -> Result<Option<PageCount>> {
+            if self.error {
+                return Err(error::gen("Error"));
+            }
+            Ok(self.num_pages.map(PageCount::from))
+        }
+
+        fn retry(&self, _id: i64) -> Result<Pipeline> {
+            let mut pipeline = self.pipelines[0].clone();
+            pipeline.status = "running".to_string();
+            Ok(pipeline)
+        }
+
+        fn cancel(&self, _id: i64) -> Result<Pipeline> {
+            let mut pipeline = self.pipelines[0].clone();
+            pipeline.status = "canceled".to_string();
+            Ok(pipeline)
+        }
+    }
+
+    /// Reports the next status on every poll of a pipeline, sticking to the
+    /// last one.
+    struct PipelineWaitMock {
+        statuses: Mutex<Vec<&'static str>>,
+        listed: Mutex<Vec<Vec<Pipeline>>>,
+    }
+
+    impl PipelineWaitMock {
+        fn new(statuses: Vec<&'static str>) -> Self {
+            PipelineWaitMock {
+                statuses: Mutex::new(statuses),
+                listed: Mutex::new(vec![]),
+            }
+        }
+
+        fn listing(self, listed: Vec<Vec<Pipeline>>) -> Self {
+            *self.listed.lock().unwrap() = listed;
+            self
+        }
+    }
+
+    fn wait_pipeline_with(id: i64, sha: &str, status: &str) -> Pipeline {
+        Pipeline::builder()
+            .id(id)
+            .status(status.to_string())
+            .web_url(format!("https://gitlab.com/owner/repo/-/pipelines/{}", id))
+            .branch("feature".to_string())
+            .sha(sha.to_string())
+            .created_at(format!("2024-01-01T00:00:{:02}Z", id % 60))
+            .updated_at("2024-01-01T00:01:00Z".to_string())
+            .duration(60)
+            .build()
+            .unwrap()
+    }
+
+    impl Cicd for PipelineWaitMock {
+        fn list(&self, _args: PipelineBodyArgs) -> Result<Vec<Pipeline>> {
+            let mut listed = self.listed.lock().unwrap();
+            if listed.len() > 1 {
+                return Ok(listed.remove(0));
+            }
+            Ok(listed.first().cloned().unwrap_or_default())
+        }
+
+        fn get_pipeline(&self, id: i64) -> Result<Pipeline> {
+            let mut statuses = self.statuses.lock().unwrap();
+            let status = if statuses.len() > 1 {
+                statuses.remove(0)
+            } else {
+                statuses[0]
+            };
+            Ok(wait_pipeline_with(id, "abc123", status))
+        }
+
+        fn num_pages(&self) -> Result<Option<PageCount>> {
+            Ok(None)
+        }
+
+        fn retry(&self, _id: i64) -> Result<Pipeline> {
+            unimplemented!()
+        }
+
+        fn cancel(&self, _id: i64) -> Result<Pipeline> {
+            unimplemented!()
+        }
+    }
+
+    fn wait_args(id: Option<i64>) -> PipelineWaitCliArgs {
+        PipelineWaitCliArgs::builder()
+            .id(id)
+            .poll_interval(Milliseconds::new(0))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_wait_pipeline_until_it_passes() {
+        let remote = Arc::new(PipelineWaitMock::new(vec![
+            "pending", "running", "running", "success",
+        ]));
+        let mut buf = Vec::new();
+        wait_pipeline(remote, wait_args(Some(12)), None, &mut buf).unwrap();
+        assert_eq!(
+            "Pipeline 12 is pending\n\
+             Pipeline 12 is running\n\
+             Pipeline 12 is success\n\
+             https://gitlab.com/owner/repo/-/pipelines/12\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wait_pipeline_errors_if_it_fails() {
+        let remote = Arc::new(PipelineWaitMock::new(vec!["running", "failed"]));
+        let mut buf = Vec::new();
+        let err = wait_pipeline(remote, wait_args(Some(12)), None, &mut buf).unwrap_err();
+        assert_eq!(
+            "Application error: Pipeline 12 finished with status failed: \
+             https://gitlab.com/owner/repo/-/pipelines/12",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_wait_pipeline_github_cancelled_conclusion_is_a_failure() {
+        let remote = Arc::new(PipelineWaitMock::new(vec!["in_progress", "cancelled"]));
+        let mut buf = Vec::new();
+        assert!(wait_pipeline(remote, wait_args(Some(12)), None, &mut buf).is_err());
     }
 
-    impl Cicd for PipelineListMock {
-        fn list(&self, _args: PipelineBodyArgs) -> Result<Vec<Pipeline>> {
-            if self.error {
-                return Err(error::gen("Error"));
-            }
-            let pp = self.pipelines.clone();
-            Ok(pp)
-        }
+    #[test]
+    fn test_wait_pipeline_times_out() {
+        let remote = Arc::new(PipelineWaitMock::new(vec!["running"]));
+        let cli_args = PipelineWaitCliArgs::builder()
+            .id(Some(12))
+            .timeout(0)
+            .poll_interval(Milliseconds::new(0))
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        let err = wait_pipeline(remote, cli_args, None, &mut buf).unwrap_err();
+        assert_eq!(
+            "Application error: Stopped waiting for pipeline 12 after 0 seconds",
+            err.to_string()
+        );
+    }
 
-        fn get_pipeline(&self, _id: i64) -> Result<Pipeline> {
-            let pp = self.pipelines.clone();
-            Ok(pp[0].clone())
-        }
+    #[test]
+    fn test_wait_pipeline_stops_when_cancelled() {
+        let remote = Arc::new(PipelineWaitMock::new(vec!["running"]));
+        cancel::cancel();
+        let mut buf = Vec::new();
+        let err = wait_pipeline(remote, wait_args(Some(12)), None, &mut buf).unwrap_err();
+        assert_eq!("Cancelled: waiting for pipeline 12", err.to_string());
+    }
 
-        fn num_pages(&self) -> Result<Option<u32>> {
-            if self.error {
-                return Err(error::gen("Error"));
-            }
-            return Ok(self.num_pages);
-        }
+    #[test]
+    fn test_wait_latest_pipeline_of_commit_once_created() {
+        // The new pipeline shows up on the second poll, next to an older one
+        // of another commit.
+        let older = wait_pipeline_with(10, "old", "success");
+        let remote = Arc::new(
+            PipelineWaitMock::new(vec!["running", "success"]).listing(vec![
+                vec![older.clone()],
+                vec![
+                    wait_pipeline_with(11, "abc123", "pending"),
+                    wait_pipeline_with(12, "abc123", "pending"),
+                    older,
+                ],
+            ]),
+        );
+        let mut buf = Vec::new();
+        wait_pipeline(
+            remote,
+            wait_args(None),
+            Some("abc123".to_string()),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            "Waiting for a pipeline of commit abc123\n\
+             Pipeline 12 is pending\n\
+             Pipeline 12 is running\n\
+             Pipeline 12 is success\n\
+             https://gitlab.com/owner/repo/-/pipelines/12\n",
+            String::from_utf8(buf).unwrap()
+        );
     }
 
     #[test]
@@ -367,6 +1732,83 @@ mod test {
              https://gitlab.com/owner/repo/-/pipelines/456|master|1234567890abcdef|2020-01-01T00:00:00Z|2020-01-01T00:01:01Z|61|failed\n")
     }
 
+    fn graph_jobs() -> Vec<PipelineJobNode> {
+        let job = |name: &str, stage: &str, needs: Option<Vec<&str>>| {
+            PipelineJobNode::builder()
+                .name(name.to_string())
+                .stage(stage.to_string())
+                .needs(needs.map(|needs| needs.into_iter().map(String::from).collect()))
+                .build()
+                .unwrap()
+        };
+        vec![
+            job("build", "build", None),
+            job("docs", "build", None),
+            job("unit \"fast\"", "test", Some(vec!["build"])),
+            job("pages", "deploy", None),
+        ]
+    }
+
+    #[test]
+    fn test_render_mermaid_graph() {
+        assert_eq!(
+            "flowchart LR\n    \
+             subgraph stage0[\"build\"]\n        \
+             job0[\"build\"]\n        \
+             job1[\"docs\"]\n    \
+             end\n    \
+             subgraph stage1[\"test\"]\n        \
+             job2[\"unit #quot;fast#quot;\"]\n    \
+             end\n    \
+             subgraph stage2[\"deploy\"]\n        \
+             job3[\"pages\"]\n    \
+             end\n    \
+             job0 --> job2\n    \
+             job2 --> job3\n",
+            render_graph(&graph_jobs(), GraphFormat::Mermaid)
+        );
+    }
+
+    #[test]
+    fn test_render_plantuml_graph() {
+        let graph = render_graph(&graph_jobs(), GraphFormat::PlantUml);
+        assert!(
+            graph.starts_with("@startuml\nrectangle \"build\" {\n  rectangle \"build\" as job0\n")
+        );
+        assert!(graph.contains("  rectangle \"unit 'fast'\" as job2\n"));
+        assert!(graph.ends_with("job0 --> job2\njob2 --> job3\n@enduml\n"));
+    }
+
+    #[test]
+    fn test_retry_and_cancel_pipeline() {
+        let pp_remote = Arc::new(
+            PipelineListMock::builder()
+                .pipelines(vec![Pipeline::builder()
+                    .status("failed".to_string())
+                    .web_url("https://gitlab.com/owner/repo/-/pipelines/123".to_string())
+                    .branch("master".to_string())
+                    .sha("1234567890abcdef".to_string())
+                    .created_at("2020-01-01T00:00:00Z".to_string())
+                    .updated_at("2020-01-01T00:01:00Z".to_string())
+                    .duration(60)
+                    .build()
+                    .unwrap()])
+                .build()
+                .unwrap(),
+        );
+        let cli_args = PipelineActionCliArgs::builder().id(123).build().unwrap();
+        let mut buf = Vec::new();
+        retry_pipeline(pp_remote.clone(), cli_args.clone(), &mut buf).unwrap();
+        assert_eq!(
+            "URL|Branch|SHA|Created at|Updated at|Duration|Status\n\
+             https://gitlab.com/owner/repo/-/pipelines/123|master|1234567890abcdef|2020-01-01T00:00:00Z|2020-01-01T00:01:00Z|60|running\n",
+            String::from_utf8(buf).unwrap()
+        );
+        let mut buf = Vec::new();
+        cancel_pipeline(pp_remote, cli_args, &mut buf).unwrap();
+        assert!(String::from_utf8(buf).unwrap().ends_with("|60|canceled\n"));
+    }
+
     #[test]
     fn test_list_pipelines_empty_warns_message() {
         let pp_remote = PipelineListMock::builder().build().unwrap();
@@ -409,7 +1851,7 @@ mod test {
     #[test]
     fn test_list_number_of_pipelines_pages() {
         let pp_remote = PipelineListMock::builder()
-            .num_pages(3 as u32)
+            .num_pages(3_u32)
             .build()
             .unwrap();
         let mut buf = Vec::new();
@@ -514,12 +1956,38 @@ mod test {
             Ok(rr.clone())
         }
 
-        fn num_pages(&self, _args: RunnerListBodyArgs) -> Result<Option<u32>> {
+        fn num_pages(&self, _args: RunnerListBodyArgs) -> Result<Option<PageCount>> {
             if self.error {
                 return Err(error::gen("Error"));
             }
             Ok(None)
         }
+
+        fn set_shared_runners(&self, _enabled: bool) -> Result<()> {
+            if self.error {
+                return Err(error::gen("Error"));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_disable_shared_runners() {
+        let remote = Arc::new(RunnerMock::builder().build().unwrap());
+        let mut buf = Vec::new();
+        set_shared_runners(remote, false, &mut buf).unwrap();
+        assert_eq!(
+            "Shared runners disabled for the project\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_shared_runners_error_is_propagated() {
+        let remote = Arc::new(RunnerMock::builder().error(true).build().unwrap());
+        let mut buf = Vec::new();
+        assert!(set_shared_runners(remote, true, &mut buf).is_err());
+        assert!(buf.is_empty());
     }
 
     #[test]
@@ -639,4 +2107,575 @@ mod test {
             String::from_utf8(buf).unwrap()
         )
     }
+
+    #[derive(Default)]
+    struct ArtifactMock {
+        artifacts: Vec<Artifact>,
+        created_before: Mutex<Option<String>>,
+        deleted: Mutex<Vec<i64>>,
+    }
+
+    impl CicdArtifact for ArtifactMock {
+        fn list(&self, args: ArtifactListBodyArgs) -> Result<Vec<Artifact>> {
+            *self.created_before.lock().unwrap() = args.list_args.unwrap().created_before;
+            Ok(self.artifacts.clone())
+        }
+
+        fn delete(&self, id: i64) -> Result<()> {
+            self.deleted.lock().unwrap().push(id);
+            Ok(())
+        }
+    }
+
+    fn artifacts() -> Vec<Artifact> {
+        vec![
+            Artifact::builder()
+                .id(1)
+                .name("build".to_string())
+                .size(2 * 1024 * 1024)
+                .created_at("2024-01-01T00:00:00Z".to_string())
+                .web_url("https://gitlab.com/owner/repo/-/jobs/1".to_string())
+                .build()
+                .unwrap(),
+            Artifact::builder()
+                .id(2)
+                .name("test".to_string())
+                .size(512 * 1024)
+                .created_at("2024-01-02T00:00:00Z".to_string())
+                .web_url("https://gitlab.com/owner/repo/-/jobs/2".to_string())
+                .build()
+                .unwrap(),
+        ]
+    }
+
+    fn prune_cli_args(dry_run: bool) -> ArtifactPruneCliArgs {
+        ArtifactPruneCliArgs::builder()
+            .older_than("30d".to_string())
+            .dry_run(dry_run)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_prune_artifacts_deletes_and_reports_reclaimed_space() {
+        let remote = Arc::new(ArtifactMock {
+            artifacts: artifacts(),
+            ..Default::default()
+        });
+        let now = Seconds::new(31 * 24 * 3600);
+        let mut buf = Vec::new();
+        prune_artifacts(
+            remote.clone(),
+            prune_cli_args(false),
+            now,
+            Checkpoint::disabled(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(vec![1, 2], *remote.deleted.lock().unwrap());
+        assert_eq!(
+            Some(time::epoch_to_rfc3339(Seconds::new(24 * 3600))),
+            *remote.created_before.lock().unwrap()
+        );
+        assert_eq!(
+            "ID|Name|Size|Created at|URL\n\
+             1|build|2.0 MB|2024-01-01T00:00:00Z|https://gitlab.com/owner/repo/-/jobs/1\n\
+             2|test|512.0 KB|2024-01-02T00:00:00Z|https://gitlab.com/owner/repo/-/jobs/2\n\
+             Reclaimed 2.5 MB from 2 artifacts\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prune_artifacts_dry_run_does_not_delete() {
+        let remote = Arc::new(ArtifactMock {
+            artifacts: artifacts(),
+            ..Default::default()
+        });
+        let mut buf = Vec::new();
+        prune_artifacts(
+            remote.clone(),
+            prune_cli_args(true),
+            Seconds::new(31 * 24 * 3600),
+            Checkpoint::disabled(),
+            &mut buf,
+        )
+        .unwrap();
+        assert!(remote.deleted.lock().unwrap().is_empty());
+        assert!(String::from_utf8(buf)
+            .unwrap()
+            .ends_with("Would reclaim 2.5 MB from 2 artifacts\n"));
+    }
+
+    #[test]
+    fn test_prune_artifacts_resume_skips_erased_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().to_str().unwrap();
+        let mut checkpoint = Checkpoint::new(location, "artifacts prune 30d", false).unwrap();
+        checkpoint.record(1).unwrap();
+        let remote = Arc::new(ArtifactMock {
+            artifacts: artifacts(),
+            ..Default::default()
+        });
+        let mut buf = Vec::new();
+        prune_artifacts(
+            remote.clone(),
+            prune_cli_args(false),
+            Seconds::new(31 * 24 * 3600),
+            Checkpoint::new(location, "artifacts prune 30d", true).unwrap(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(vec![2], *remote.deleted.lock().unwrap());
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.starts_with("Skipping 1 artifacts erased in a previous run\n"));
+        assert!(output.ends_with("Reclaimed 512.0 KB from 1 artifacts\n"));
+    }
+
+    #[test]
+    fn test_prune_artifacts_stops_when_cancelled() {
+        let remote = Arc::new(ArtifactMock {
+            artifacts: artifacts(),
+            ..Default::default()
+        });
+        cancel::cancel();
+        let mut buf = Vec::new();
+        let err = prune_artifacts(
+            remote.clone(),
+            prune_cli_args(false),
+            Seconds::new(31 * 24 * 3600),
+            Checkpoint::disabled(),
+            &mut buf,
+        )
+        .unwrap_err();
+        assert_eq!("Cancelled: artifacts prune", err.to_string());
+        assert!(remote.deleted.lock().unwrap().is_empty());
+        assert_eq!(
+            "Cancelled after erasing 0 of 2 artifacts. Run again with --resume to continue\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prune_artifacts_nothing_to_prune() {
+        let remote = Arc::new(ArtifactMock::default());
+        let mut buf = Vec::new();
+        prune_artifacts(
+            remote,
+            prune_cli_args(false),
+            Seconds::new(31 * 24 * 3600),
+            Checkpoint::disabled(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            "No artifacts found to prune.\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prune_artifacts_invalid_older_than_is_error() {
+        let remote = Arc::new(ArtifactMock::default());
+        let cli_args = ArtifactPruneCliArgs::builder()
+            .older_than("thirty days".to_string())
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        assert!(prune_artifacts(
+            remote,
+            cli_args,
+            Seconds::new(0),
+            Checkpoint::disabled(),
+            &mut buf
+        )
+        .is_err());
+    }
+
+    struct JobMock;
+
+    impl CicdJob for JobMock {
+        fn retry(&self, args: JobRetryBodyArgs) -> Result<Job> {
+            let status = if args.variables.is_empty() {
+                "pending"
+            } else {
+                "manual"
+            };
+            Ok(Job::builder()
+                .id(args.id + 1)
+                .name("deploy".to_string())
+                .status(status.to_string())
+                .web_url(format!(
+                    "https://gitlab.com/owner/repo/-/jobs/{}",
+                    args.id + 1
+                ))
+                .created_at("2024-03-02T09:12:03Z".to_string())
+                .build()
+                .unwrap())
+        }
+
+        fn list(&self, pipeline_id: i64) -> Result<Vec<Job>> {
+            let job = |name: &str, status: &str, duration: Option<u64>| {
+                Job::builder()
+                    .id(pipeline_id)
+                    .name(name.to_string())
+                    .status(status.to_string())
+                    .web_url(format!(
+                        "https://gitlab.com/owner/repo/-/jobs/{}",
+                        pipeline_id
+                    ))
+                    .created_at("2024-03-02T09:12:03Z".to_string())
+                    .duration(duration)
+                    .build()
+                    .unwrap()
+            };
+            if pipeline_id == 1 {
+                return Ok(vec![
+                    job("build", "success", Some(60)),
+                    job("test", "success", Some(100)),
+                    job("lint", "failed", Some(10)),
+                    job("docs", "success", Some(5)),
+                ]);
+            }
+            Ok(vec![
+                job("build", "success", Some(80)),
+                job("test", "success", Some(200)),
+                job("lint", "success", Some(10)),
+                job("deploy", "failed", None),
+                job("e2e", "failed", Some(300)),
+            ])
+        }
+    }
+
+    #[test]
+    fn test_get_pipeline_with_job_breakdown() {
+        let pp_remote = Arc::new(
+            PipelineListMock::builder()
+                .pipelines(vec![Pipeline::builder()
+                    .id(1)
+                    .status("failed".to_string())
+                    .web_url("https://gitlab.com/owner/repo/-/pipelines/1".to_string())
+                    .branch("master".to_string())
+                    .sha("1234567890abcdef".to_string())
+                    .created_at("2020-01-01T00:00:00Z".to_string())
+                    .updated_at("2020-01-01T00:01:00Z".to_string())
+                    .duration(175)
+                    .build()
+                    .unwrap()])
+                .build()
+                .unwrap(),
+        );
+        let cli_args = PipelineActionCliArgs::builder().id(1).build().unwrap();
+        let mut buf = Vec::new();
+        get_pipeline(pp_remote, Arc::new(JobMock), cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "URL|Branch|SHA|Created at|Updated at|Duration|Status\n\
+             https://gitlab.com/owner/repo/-/pipelines/1|master|1234567890abcdef|2020-01-01T00:00:00Z|2020-01-01T00:01:00Z|175|failed\n\
+             \n\
+             Name|Stage|Status|Duration\n\
+             build||success|60\n\
+             test||success|100\n\
+             lint||failed|10\n\
+             docs||success|5\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pipeline_diff_highlights_failures_and_regressions() {
+        let cli_args = PipelineDiffCliArgs::builder()
+            .from_id(1)
+            .to_id(2)
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        pipeline_diff(Arc::new(JobMock), cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "Name|From status|To status|From duration|To duration|Change\n\
+             deploy|-|failed|-|-|newly failing\n\
+             e2e|-|failed|-|300|newly failing\n\
+             test|success|success|100|200|slower\n\
+             build|success|success|60|80|\n\
+             lint|failed|success|10|10|fixed\n\
+             docs|success|-|5|-|removed\n\
+             2 newly failing, 1 slower by 50% or more\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_job_change() {
+        let job = |status: &str, duration: u64| {
+            Job::builder()
+                .id(1)
+                .name("test".to_string())
+                .status(status.to_string())
+                .web_url("".to_string())
+                .created_at("".to_string())
+                .duration(Some(duration))
+                .build()
+                .unwrap()
+        };
+        assert_eq!(
+            JobChange::NewlyFailing,
+            job_change(&job("success", 60), &job("failed", 60), 50)
+        );
+        assert_eq!(
+            JobChange::NewlyFailing,
+            job_change(&job("success", 60), &job("timed_out", 60), 50)
+        );
+        assert_eq!(
+            JobChange::Regression,
+            job_change(&job("success", 60), &job("success", 90), 50)
+        );
+        // Below the threshold.
+        assert_eq!(
+            JobChange::Unchanged,
+            job_change(&job("success", 100), &job("success", 140), 50)
+        );
+        // Slower by a lot in relative terms but only a few seconds.
+        assert_eq!(
+            JobChange::Unchanged,
+            job_change(&job("success", 2), &job("success", 20), 50)
+        );
+    }
+
+    struct JobHistoryMock {
+        jobs: Vec<(i64, &'static str, &'static str)>,
+    }
+
+    impl CicdJob for JobHistoryMock {
+        fn retry(&self, _args: JobRetryBodyArgs) -> Result<Job> {
+            todo!()
+        }
+
+        fn list(&self, pipeline_id: i64) -> Result<Vec<Job>> {
+            Ok(self
+                .jobs
+                .iter()
+                .filter(|(id, _, _)| *id == pipeline_id)
+                .map(|(id, name, status)| {
+                    Job::builder()
+                        .id(*id)
+                        .name(name.to_string())
+                        .status(status.to_string())
+                        .web_url("".to_string())
+                        .created_at("".to_string())
+                        .build()
+                        .unwrap()
+                })
+                .collect())
+        }
+    }
+
+    fn pipeline_history() -> (PipelineListMock, JobHistoryMock) {
+        let pipeline = |id: i64, branch: &str| {
+            Pipeline::builder()
+                .id(id)
+                .status("success".to_string())
+                .web_url(format!("https://gitlab.com/owner/repo/-/pipelines/{}", id))
+                .branch(branch.to_string())
+                .sha("1234567890abcdef".to_string())
+                .created_at(format!("2024-03-0{}T00:00:00Z", id))
+                .updated_at(format!("2024-03-0{}T00:10:00Z", id))
+                .duration(600)
+                .build()
+                .unwrap()
+        };
+        let pipelines = PipelineListMock::builder()
+            .pipelines(vec![
+                pipeline(6, "feature"),
+                pipeline(5, "main"),
+                pipeline(4, "main"),
+                pipeline(3, "main"),
+                pipeline(2, "main"),
+                pipeline(1, "main"),
+            ])
+            .build()
+            .unwrap();
+        let mut jobs = Vec::new();
+        let statuses = [
+            ("build", ["success"; 5]),
+            (
+                "test",
+                ["success", "failed", "success", "success", "success"],
+            ),
+            (
+                "lint",
+                ["failed", "failed", "success", "success", "success"],
+            ),
+            ("e2e", ["success", "failed", "failed", "success", "failed"]),
+            (
+                "deploy",
+                ["manual", "skipped", "canceled", "manual", "success"],
+            ),
+        ];
+        for (name, statuses) in statuses {
+            for (pipeline_id, status) in (1..=5).zip(statuses) {
+                jobs.push((pipeline_id, name, status));
+            }
+        }
+        jobs.push((6, "e2e", "failed"));
+        (pipelines, JobHistoryMock { jobs })
+    }
+
+    #[test]
+    fn test_pipeline_flaky_reports_jobs_alternating_on_a_branch() {
+        let (pipelines, jobs) = pipeline_history();
+        let cli_args = PipelineFlakyCliArgs::builder().build().unwrap();
+        let mut buf = Vec::new();
+        pipeline_flaky(Arc::new(pipelines), Arc::new(jobs), cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "Name|Runs|Failures|Flips|Flake rate|Branches\n\
+             e2e|6|4|3|75%|main\n\
+             test|5|1|2|50%|main\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pipeline_flaky_scans_last_pipelines_only() {
+        let (pipelines, jobs) = pipeline_history();
+        let cli_args = PipelineFlakyCliArgs::builder().last(3).build().unwrap();
+        let mut buf = Vec::new();
+        pipeline_flaky(Arc::new(pipelines), Arc::new(jobs), cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "No flaky jobs found in the last 3 pipelines\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_retry_job_displays_new_job() {
+        let cli_args = JobRetryCliArgs::builder()
+            .id(10)
+            .variables(vec![("DEPLOY_ENV".to_string(), "staging".to_string())])
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        retry_job(Arc::new(JobMock), cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "ID|Name|Status|Created at|URL\n\
+             11|deploy|manual|2024-03-02T09:12:03Z|https://gitlab.com/owner/repo/-/jobs/11\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    struct LinterMock;
+
+    impl CicdLinter for LinterMock {
+        fn lint(&self, args: LintBodyArgs) -> Result<LintResponse> {
+            if args.content.contains("script") {
+                return Ok(LintResponse {
+                    valid: true,
+                    ..Default::default()
+                });
+            }
+            Ok(LintResponse {
+                valid: false,
+                errors: vec![
+                    LintError::new(Some(2), "unknown keyword"),
+                    LintError::new(None, "build config should implement a script"),
+                ],
+                ..Default::default()
+            })
+        }
+
+        fn merged_config(&self, ref_: &str) -> Result<LintResponse> {
+            if ref_ == "broken" {
+                return Ok(LintResponse {
+                    valid: false,
+                    errors: vec![LintError::new(None, "jobs config should contain a job")],
+                    ..Default::default()
+                });
+            }
+            Ok(LintResponse {
+                valid: true,
+                errors: vec![],
+                merged_yaml: "---\nbuild:\n  script:\n  - make\n".to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_merged_ci_config_prints_expanded_yaml() {
+        let cli_args = MergedConfigCliArgs::builder()
+            .ref_("main".to_string())
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        merged_ci_config(Arc::new(LinterMock), cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "---\nbuild:\n  script:\n  - make\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_merged_ci_config_invalid_is_error() {
+        let cli_args = MergedConfigCliArgs::builder()
+            .ref_("broken".to_string())
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        let err = merged_ci_config(Arc::new(LinterMock), cli_args, &mut buf).unwrap_err();
+        assert!(err.to_string().contains("jobs config should contain a job"));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_lint_ci_files_reports_errors_with_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let valid = dir.path().join("valid.yml");
+        let invalid = dir.path().join("invalid.yml");
+        std::fs::write(&valid, "build:\n  script: make\n").unwrap();
+        std::fs::write(&invalid, "build:\n  scrpt: make\n").unwrap();
+        let mut buf = Vec::new();
+        let result = lint_ci_files(
+            Arc::new(LinterMock),
+            vec![valid.clone(), invalid.clone()],
+            &mut buf,
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            format!(
+                "{valid}: configuration is valid\n\
+                 {invalid}:2: unknown keyword\n\
+                 {invalid}: build config should implement a script\n",
+                valid = valid.display(),
+                invalid = invalid.display()
+            ),
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_lint_ci_files_missing_file_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut buf = Vec::new();
+        let result = lint_ci_files(
+            Arc::new(LinterMock),
+            vec![dir.path().join(".gitlab-ci.yml")],
+            &mut buf,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_default_ci_files() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            vec![dir.path().join(".gitlab-ci.yml")],
+            default_ci_files("gitlab.com", dir.path()).unwrap()
+        );
+        let workflows = dir.path().join(".github").join("workflows");
+        std::fs::create_dir_all(&workflows).unwrap();
+        std::fs::write(workflows.join("release.yaml"), "").unwrap();
+        std::fs::write(workflows.join("ci.yml"), "").unwrap();
+        std::fs::write(workflows.join("README.md"), "").unwrap();
+        assert_eq!(
+            vec![workflows.join("ci.yml"), workflows.join("release.yaml")],
+            default_ci_files("github.com", dir.path()).unwrap()
+        );
+    }
 }
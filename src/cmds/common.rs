@@ -1,15 +1,28 @@
+use crate::config::Config;
 use crate::display;
+use crate::error::{AddContext, GRError};
+use crate::i18n::{self, Message};
+use crate::io::PageCount;
 use crate::remote::MergeRequestListBodyArgs;
 /// Common functions and macros that are used by multiple commands
 use crate::Result;
 use crate::{api_traits::MergeRequest, remote::ListRemoteCliArgs};
-use std::io::Write;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use std::sync::Arc;
 
-use crate::api_traits::{Cicd, CicdRunner, Deploy, RemoteProject};
+use crate::api_traits::{
+    Cicd, CicdRunner, Deploy, Deployment, DeploymentApproval, Issue, ProjectAccessRequests,
+    ProjectMembers, RemoteProject,
+};
 
 use super::cicd::{RunnerListBodyArgs, RunnerListCliArgs};
-use super::project::{ProjectListBodyArgs, ProjectListCliArgs};
+use super::environment::{DeploymentApprovalListBodyArgs, EnvironmentListBodyArgs};
+use super::issue::{IssueListBodyArgs, IssueListCliArgs};
+use super::project::{
+    AccessRequestListBodyArgs, MemberListBodyArgs, ProjectListBodyArgs, ProjectListCliArgs,
+};
 use super::release::ReleaseBodyArgs;
 use super::{cicd::PipelineBodyArgs, merge_request::MergeRequestListCliArgs};
 
@@ -30,11 +43,20 @@ macro_rules! query_pages {
     };
 }
 
-pub fn process_num_pages<W: Write>(num_pages: Result<Option<u32>>, mut writer: W) -> Result<()> {
+pub fn process_num_pages<W: Write>(
+    num_pages: Result<Option<PageCount>>,
+    mut writer: W,
+) -> Result<()> {
     match num_pages {
-        Ok(Some(pages)) => writer.write_all(format!("{pages}\n", pages = pages).as_bytes())?,
+        Ok(Some(count)) => {
+            writer.write_all(format!("{pages}\n", pages = count.pages).as_bytes())?;
+            // The page count stays on its own line for scripts.
+            if let Some(total) = count.total {
+                writeln!(writer, "{}", i18n::format(Message::Total, &[&total]))?;
+            }
+        }
         Ok(None) => {
-            writer.write_all(b"Number of pages not available.\n")?;
+            writeln!(writer, "{}", i18n::text(Message::NumPagesNotAvailable))?;
         }
         Err(e) => {
             return Err(e);
@@ -51,6 +73,7 @@ query_pages!(
     MergeRequestListBodyArgs
 );
 query_pages!(num_project_pages, RemoteProject, ProjectListBodyArgs);
+query_pages!(num_issue_pages, Issue, IssueListBodyArgs);
 
 macro_rules! list_resource {
     ($func_name:ident, $trait_name:ident, $body_args:ident, $cli_args:ident, $embeds_list_args: literal) => {
@@ -65,7 +88,7 @@ macro_rules! list_resource {
                 return Ok(());
             }
             if objs.is_empty() {
-                writer.write_all(b"No resources found.\n")?;
+                writeln!(writer, "{}", i18n::text(Message::NoResourcesFound))?;
                 return Ok(());
             }
             display::print(&mut writer, objs, cli_args.list_args.get_args)?;
@@ -84,7 +107,7 @@ macro_rules! list_resource {
                 return Ok(());
             }
             if objs.is_empty() {
-                writer.write_all(b"No resources found.\n")?;
+                writeln!(writer, "{}", i18n::text(Message::NoResourcesFound))?;
                 return Ok(());
             }
             display::print(&mut writer, objs, cli_args.get_args)?;
@@ -112,6 +135,42 @@ list_resource!(
 
 list_resource!(list_releases, Deploy, ReleaseBodyArgs, ListRemoteCliArgs);
 
+list_resource!(
+    list_issues,
+    Issue,
+    IssueListBodyArgs,
+    IssueListCliArgs,
+    true
+);
+
+list_resource!(
+    list_members,
+    ProjectMembers,
+    MemberListBodyArgs,
+    ListRemoteCliArgs
+);
+
+list_resource!(
+    list_access_requests,
+    ProjectAccessRequests,
+    AccessRequestListBodyArgs,
+    ListRemoteCliArgs
+);
+
+list_resource!(
+    list_environments,
+    Deployment,
+    EnvironmentListBodyArgs,
+    ListRemoteCliArgs
+);
+
+list_resource!(
+    list_pending_deployments,
+    DeploymentApproval,
+    DeploymentApprovalListBodyArgs,
+    ListRemoteCliArgs
+);
+
 list_resource!(
     list_user_projects,
     RemoteProject,
@@ -119,3 +178,143 @@ list_resource!(
     ProjectListCliArgs,
     true
 );
+
+/// Reads the configuration for a domain that is not necessarily the one of
+/// the current git repository. Used by commands that operate across remotes.
+pub fn read_config(config_file: &Path, domain: &str) -> Result<Arc<Config>> {
+    let f = File::open(config_file).err_context(GRError::ConfigurationError(format!(
+        "Cannot open config file {}",
+        config_file.display()
+    )))?;
+    Ok(Arc::new(Config::new(f, domain)?))
+}
+
+/// Splits a remote in the form <domain>/<owner>/<repo> into its domain and
+/// project path.
+pub fn parse_remote(remote: &str) -> Result<(String, String)> {
+    let remote = remote
+        .trim_start_matches("https://")
+        .trim_end_matches('/')
+        .trim_end_matches(".git");
+    match remote.split_once('/') {
+        Some((domain, path)) if !domain.is_empty() && path.contains('/') => {
+            Ok((domain.to_string(), path.to_string()))
+        }
+        _ => Err(GRError::PreconditionNotMet(format!(
+            "Invalid remote {}. Expected <domain>/<owner>/<repo>",
+            remote
+        ))
+        .into()),
+    }
+}
+
+/// Runs `f` with a writer for the `--output` destination. No destination or
+/// "-" writes to STDOUT. A file is written to a temporary sibling first and
+/// renamed into place once `f` succeeds, so it is never left half written.
+pub fn write_output<F>(output: Option<&str>, f: F) -> Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> Result<()>,
+{
+    let path = match output {
+        None | Some("-") => return f(&mut std::io::stdout()),
+        Some(path) => Path::new(path),
+    };
+    let file_name = path.file_name().ok_or_else(|| {
+        GRError::PreconditionNotMet(format!("Output path {} is not a file", path.display()))
+    })?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+    let mut file = BufWriter::new(File::create(&tmp_path).err_context(
+        GRError::PreconditionNotMet(format!("Cannot create output file {}", path.display())),
+    )?);
+    let result = f(&mut file).and_then(|_| Ok(file.flush()?));
+    drop(file);
+    match result {
+        Ok(()) => Ok(fs::rename(&tmp_path, path)?),
+        Err(err) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(err)
+        }
+    }
+}
+
+pub fn human_size(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        return format!("{} {}", bytes, units[unit]);
+    }
+    format!("{:.1} {}", size, units[unit])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote() {
+        let (domain, path) = parse_remote("gitlab.com/org/repo").unwrap();
+        assert_eq!("gitlab.com", domain);
+        assert_eq!("org/repo", path);
+        let (domain, path) = parse_remote("https://github.com/org/repo.git").unwrap();
+        assert_eq!("github.com", domain);
+        assert_eq!("org/repo", path);
+    }
+
+    #[test]
+    fn test_parse_remote_without_path_is_error() {
+        assert!(parse_remote("gitlab.com").is_err());
+        assert!(parse_remote("gitlab.com/org").is_err());
+    }
+
+    #[test]
+    fn test_num_pages_reports_total_if_known() {
+        let mut writer = Vec::new();
+        process_num_pages(Ok(Some(PageCount::from(3))), &mut writer).unwrap();
+        assert_eq!("3\n", String::from_utf8(writer).unwrap());
+        let mut writer = Vec::new();
+        let count = PageCount {
+            pages: 62,
+            total: Some(1234),
+        };
+        process_num_pages(Ok(Some(count)), &mut writer).unwrap();
+        assert_eq!("62\nTotal: 1234\n", String::from_utf8(writer).unwrap());
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!("0 B", human_size(0));
+        assert_eq!("1023 B", human_size(1023));
+        assert_eq!("1.0 KB", human_size(1024));
+        assert_eq!("1.5 MB", human_size(1024 * 1024 + 512 * 1024));
+        assert_eq!("3.0 GB", human_size(3 * 1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_write_output_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mrs.json");
+        let output = path.to_str().unwrap();
+        write_output(Some(output), |writer| Ok(writer.write_all(b"[]\n")?)).unwrap();
+        assert_eq!("[]\n", fs::read_to_string(&path).unwrap());
+        assert_eq!(1, fs::read_dir(dir.path()).unwrap().count());
+    }
+
+    #[test]
+    fn test_write_output_keeps_previous_file_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mrs.json");
+        fs::write(&path, "previous").unwrap();
+        let result = write_output(Some(path.to_str().unwrap()), |writer| {
+            writer.write_all(b"partial")?;
+            Err(GRError::RemoteServerError("boom".to_string()).into())
+        });
+        assert!(result.is_err());
+        assert_eq!("previous", fs::read_to_string(&path).unwrap());
+        assert_eq!(1, fs::read_dir(dir.path()).unwrap().count());
+    }
+}
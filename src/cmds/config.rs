@@ -0,0 +1,272 @@
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read, Write};
+use std::path::Path;
+
+use crate::cli::config::ConfigOptions;
+use crate::config::{self, Config, Severity};
+use crate::error::{AddContext, GRError};
+use crate::Result;
+
+#[derive(Builder)]
+pub struct ConfigGetCliArgs {
+    pub key: String,
+    #[builder(default)]
+    pub domain: Option<String>,
+}
+
+impl ConfigGetCliArgs {
+    pub fn builder() -> ConfigGetCliArgsBuilder {
+        ConfigGetCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct ConfigSetCliArgs {
+    pub key: String,
+    pub value: String,
+    /// Global key if None.
+    #[builder(default)]
+    pub domain: Option<String>,
+}
+
+impl ConfigSetCliArgs {
+    pub fn builder() -> ConfigSetCliArgsBuilder {
+        ConfigSetCliArgsBuilder::default()
+    }
+}
+
+pub fn execute<P: AsRef<Path>>(options: ConfigOptions, config_file: P) -> Result<()> {
+    let config_file = config_file.as_ref();
+    match options {
+        ConfigOptions::Get(cli_args) => {
+            let config_data =
+                fs::read_to_string(config_file).err_context(GRError::ConfigurationError(
+                    format!("Cannot open config file {}", config_file.display()),
+                ))?;
+            get(&config_data, &cli_args, std::io::stdout())
+        }
+        ConfigOptions::Set(cli_args) => set(config_file, &cli_args),
+        ConfigOptions::Check => {
+            let f = File::open(config_file).err_context(GRError::ConfigurationError(format!(
+                "Cannot open config file {}",
+                config_file.display()
+            )))?;
+            check(f, config_file, std::io::stdout())
+        }
+    }
+}
+
+fn get<W: Write>(config_data: &str, cli_args: &ConfigGetCliArgs, mut writer: W) -> Result<()> {
+    match config::get_value(config_data, cli_args.domain.as_deref(), &cli_args.key) {
+        Some(value) => {
+            writeln!(writer, "{}", value)?;
+            Ok(())
+        }
+        None => {
+            let scope = match &cli_args.domain {
+                Some(domain) => format!("for domain {} nor globally", domain),
+                None => "globally".to_string(),
+            };
+            Err(
+                GRError::ConfigurationError(format!("{} is not set {}", cli_args.key, scope))
+                    .into(),
+            )
+        }
+    }
+}
+
+fn set(config_file: &Path, cli_args: &ConfigSetCliArgs) -> Result<()> {
+    set_values(
+        config_file,
+        cli_args.domain.as_deref(),
+        &[(&cli_args.key, &cli_args.value)],
+    )
+}
+
+/// Sets the keys of `domain`, or global ones if None, in the config file,
+/// creating it if needed. The new contents are written to a temporary file
+/// that replaces the config file once complete, so an interrupted write never
+/// leaves it truncated.
+pub fn set_values(config_file: &Path, domain: Option<&str>, values: &[(&str, &str)]) -> Result<()> {
+    let (mut config_data, permissions) = match fs::read_to_string(config_file) {
+        Ok(config_data) => (config_data, Some(fs::metadata(config_file)?.permissions())),
+        Err(err) if err.kind() == ErrorKind::NotFound => (String::new(), None),
+        Err(err) => return Err(err.into()),
+    };
+    for (key, value) in values {
+        config_data = config::set_value(&config_data, domain, key, value)?;
+    }
+    if let Some(parent) = config_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file_name = config_file
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let tmp_path = config_file.with_file_name(format!(".{}.tmp", file_name));
+    fs::write(&tmp_path, config_data)?;
+    // The file holds API tokens, keep it as private as it was. New ones are
+    // only readable by the user.
+    match permissions {
+        Some(permissions) => fs::set_permissions(&tmp_path, permissions)?,
+        #[cfg(unix)]
+        None => {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))?
+        }
+        #[cfg(not(unix))]
+        None => {}
+    }
+    fs::rename(&tmp_path, config_file)?;
+    Ok(())
+}
+
+/// Prints every issue found in the config file. Fails if there are errors, so
+/// it can gate scripts that change the configuration.
+fn check<R: Read, W: Write>(reader: R, config_file: &Path, mut writer: W) -> Result<()> {
+    let issues = Config::check(reader)?;
+    if issues.is_empty() {
+        writeln!(writer, "{}: no issues found", config_file.display())?;
+        return Ok(());
+    }
+    for issue in &issues {
+        writeln!(writer, "{}: {}", config_file.display(), issue)?;
+    }
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    let warnings = issues.len() - errors;
+    writeln!(writer, "{} errors, {} warnings", errors, warnings)?;
+    if errors > 0 {
+        return Err(GRError::ConfigurationError(format!(
+            "{} has {} errors",
+            config_file.display(),
+            errors
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_check_reports_issues_with_line_numbers() {
+        let config_data = "\
+# gitar config
+gitlab.com.api_token=1234
+gitlab.com.cache_location=/tmp/cache
+gitlab.com.max_pages_api_pipelin=10
+gitlab.com.per_page=ten
+github.com.cache_location=/tmp/cache
+";
+        let mut writer = Vec::new();
+        let result = check(
+            Cursor::new(config_data),
+            Path::new("/home/user/.config/gitar/api"),
+            &mut writer,
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            "/home/user/.config/gitar/api: line 4: warning: unknown key max_pages_api_pipelin, it is ignored. Did you mean gitlab.com.max_pages_api_pipeline?\n\
+             /home/user/.config/gitar/api: line 5: error: invalid value ten for per_page. Expected a number between 1 and 100\n\
+             /home/user/.config/gitar/api: line 6: error: missing required key api_token for domain github.com. Add github.com.api_token=<value>\n\
+             2 errors, 1 warnings\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_valid_config() {
+        let config_data = "\
+gitlab.com.api_token=1234
+gitlab.com.cache_location=/tmp/cache
+";
+        let mut writer = Vec::new();
+        check(Cursor::new(config_data), Path::new("api"), &mut writer).unwrap();
+        assert_eq!("api: no issues found\n", String::from_utf8(writer).unwrap());
+    }
+
+    #[test]
+    fn test_get_falls_back_to_global_value() {
+        let config_data = "per_page=50\ngitlab.com.api_token=1234\n";
+        let cli_args = ConfigGetCliArgs::builder()
+            .key("per_page".to_string())
+            .domain(Some("gitlab.com".to_string()))
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        get(config_data, &cli_args, &mut writer).unwrap();
+        assert_eq!("50\n", String::from_utf8(writer).unwrap());
+    }
+
+    #[test]
+    fn test_get_unset_key_is_error() {
+        let cli_args = ConfigGetCliArgs::builder()
+            .key("api_token".to_string())
+            .build()
+            .unwrap();
+        let Err(err) = get("gitlab.com.api_token=1234\n", &cli_args, Vec::new()) else {
+            panic!("Expected an error");
+        };
+        assert_eq!(
+            "Configuration error: api_token is not set globally",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_set_keeps_comments_and_creates_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("gitar").join("api");
+        let cli_args = ConfigSetCliArgs::builder()
+            .key("api_token".to_string())
+            .value("1234".to_string())
+            .domain(Some("gitlab.com".to_string()))
+            .build()
+            .unwrap();
+        set(&config_file, &cli_args).unwrap();
+        assert_eq!(
+            "gitlab.com.api_token=1234\n",
+            fs::read_to_string(&config_file).unwrap()
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&config_file).unwrap().permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+
+        fs::write(&config_file, "# token\ngitlab.com.api_token=1234\n").unwrap();
+        let cli_args = ConfigSetCliArgs::builder()
+            .key("api_token".to_string())
+            .value("5678".to_string())
+            .domain(Some("gitlab.com".to_string()))
+            .build()
+            .unwrap();
+        set(&config_file, &cli_args).unwrap();
+        assert_eq!(
+            "# token\ngitlab.com.api_token=5678\n",
+            fs::read_to_string(&config_file).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_only_warnings_is_ok() {
+        let config_data = "\
+gitlab.com.api_token=1234
+gitlab.com.cache_location=/tmp/cache
+gitlab.com.per_page=500
+";
+        let mut writer = Vec::new();
+        check(Cursor::new(config_data), Path::new("api"), &mut writer).unwrap();
+        assert!(String::from_utf8(writer)
+            .unwrap()
+            .ends_with("0 errors, 1 warnings\n"));
+    }
+}
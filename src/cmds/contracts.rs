@@ -0,0 +1,180 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::api_traits::RemoteContract;
+use crate::cli::contracts::ContractsOptions;
+use crate::config::ConfigProperties;
+use crate::error::AddContext;
+use crate::i18n::{self, Message};
+use crate::remote;
+use crate::Result;
+
+use super::common;
+
+const REDACTED: &str = "REDACTED";
+const FAKE_URL: &str = "https://any_url_test.test";
+// Keys whose values are never stored in a contract.
+const SECRET_KEYS: [&str; 5] = ["token", "secret", "password", "email", "private"];
+
+#[derive(Builder)]
+pub struct ContractRefreshCliArgs {
+    pub endpoint: String,
+    pub domain: String,
+    pub name: String,
+    #[builder(default)]
+    pub max_items: Option<usize>,
+    #[builder(default = "PathBuf::from(\"contracts\")")]
+    pub contracts_dir: PathBuf,
+}
+
+impl ContractRefreshCliArgs {
+    pub fn builder() -> ContractRefreshCliArgsBuilder {
+        ContractRefreshCliArgsBuilder::default()
+    }
+}
+
+pub fn execute<P: AsRef<Path>>(options: ContractsOptions, config_file: P) -> Result<()> {
+    match options {
+        ContractsOptions::Refresh(cli_args) => {
+            let config = common::read_config(config_file.as_ref(), &cli_args.domain)?;
+            let api_token = config.api_token().to_string();
+            let remote =
+                remote::get_remote_contract(cli_args.domain.clone(), "".to_string(), config, true)?;
+            refresh_contract(remote, &cli_args, &api_token, std::io::stdout())
+        }
+    }
+}
+
+/// Fetches a live response and stores it scrubbed in the contracts directory
+/// of the remote, as read by the unit tests.
+fn refresh_contract<W: Write>(
+    remote: Arc<dyn RemoteContract>,
+    cli_args: &ContractRefreshCliArgs,
+    api_token: &str,
+    mut writer: W,
+) -> Result<()> {
+    let mut data = remote.fetch(&cli_args.endpoint)?;
+    if let (Some(max_items), Some(items)) = (cli_args.max_items, data.as_array_mut()) {
+        items.truncate(max_items);
+    }
+    scrub(&mut data, api_token);
+    let remote_dir = if cli_args.domain.starts_with("github") {
+        "github"
+    } else {
+        "gitlab"
+    };
+    let path = cli_args.contracts_dir.join(remote_dir).join(&cli_args.name);
+    let contents = format!("{}\n", serde_json::to_string_pretty(&data)?);
+    fs::write(&path, contents).err_context(format!("Cannot write {}", path.display()))?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::ContractStored, &[&path.display()])
+    )?;
+    Ok(())
+}
+
+/// Replaces secrets, personal data and avatars with fixed values. Any value
+/// containing the API token in use is redacted too.
+fn scrub(data: &mut serde_json::Value, api_token: &str) {
+    match data {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if value.is_string() && SECRET_KEYS.iter().any(|secret| key.contains(secret)) {
+                    *value = serde_json::Value::from(REDACTED);
+                } else if value.is_string() && key.contains("avatar") {
+                    *value = serde_json::Value::from(FAKE_URL);
+                } else {
+                    scrub(value, api_token);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items.iter_mut() {
+                scrub(item, api_token);
+            }
+        }
+        serde_json::Value::String(value) if !api_token.is_empty() && value.contains(api_token) => {
+            *value = REDACTED.to_string();
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use serde_json::json;
+
+    use super::*;
+
+    struct RemoteContractMock {
+        data: serde_json::Value,
+        endpoint: Mutex<String>,
+    }
+
+    impl RemoteContract for RemoteContractMock {
+        fn fetch(&self, endpoint: &str) -> Result<serde_json::Value> {
+            *self.endpoint.lock().unwrap() = endpoint.to_string();
+            Ok(self.data.clone())
+        }
+    }
+
+    #[test]
+    fn test_scrub_secrets_avatars_and_token() {
+        let mut data = json!({
+            "id": 1,
+            "runners_token": "GR1348941abc",
+            "owner": {"avatar_url": "https://gitlab.com/avatar.png", "public_email": "a@b.c"},
+            "web_url": "https://gitlab.com/jdoe?private_token=s3cr3t",
+            "members": [{"name": "jdoe", "commit_email": "jdoe@example.com"}],
+            "token_count": 3
+        });
+        scrub(&mut data, "s3cr3t");
+        assert_eq!(
+            json!({
+                "id": 1,
+                "runners_token": "REDACTED",
+                "owner": {"avatar_url": "https://any_url_test.test", "public_email": "REDACTED"},
+                "web_url": "REDACTED",
+                "members": [{"name": "jdoe", "commit_email": "REDACTED"}],
+                "token_count": 3
+            }),
+            data
+        );
+    }
+
+    #[test]
+    fn test_refresh_contract_stores_scrubbed_response() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("gitlab")).unwrap();
+        let remote = Arc::new(RemoteContractMock {
+            data: json!([{"id": 1, "secret": "x"}, {"id": 2}, {"id": 3}]),
+            endpoint: Mutex::new(String::new()),
+        });
+        let cli_args = ContractRefreshCliArgs::builder()
+            .endpoint("projects/jordilin%2Fgitlapi/members".to_string())
+            .domain("gitlab.com".to_string())
+            .name("project_members.json".to_string())
+            .max_items(Some(2))
+            .contracts_dir(dir.path().to_path_buf())
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        refresh_contract(remote.clone(), &cli_args, "1234", &mut buf).unwrap();
+        assert_eq!(
+            "projects/jordilin%2Fgitlapi/members",
+            *remote.endpoint.lock().unwrap()
+        );
+        let contents =
+            fs::read_to_string(dir.path().join("gitlab").join("project_members.json")).unwrap();
+        assert_eq!(
+            "[\n  {\n    \"id\": 1,\n    \"secret\": \"REDACTED\"\n  },\n  {\n    \"id\": 2\n  }\n]\n",
+            contents
+        );
+    }
+}
@@ -0,0 +1,748 @@
+//! Registers gr commands with the scheduler of the OS, so periodic reports
+//! need no glue. Schedules are given in cron format and translated into
+//! systemd timers and launchd agents.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{self, GRError};
+use crate::i18n::{self, Message};
+use crate::io::{Response, TaskRunner};
+use crate::shell::{self, Shell};
+use crate::Result;
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scheduler {
+    Cron,
+    Systemd,
+    Launchd,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            Scheduler::Launchd
+        } else {
+            Scheduler::Cron
+        }
+    }
+}
+
+/// Values a field of a schedule matches. `None` matches any value.
+type Field = Option<Vec<u32>>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schedule {
+    expr: String,
+    minute: Field,
+    hour: Field,
+    day: Field,
+    month: Field,
+    /// 0 is Sunday.
+    weekday: Field,
+}
+
+impl Schedule {
+    /// Parses the five fields of a cron schedule: minute, hour, day of month,
+    /// month and day of week. Fields take values, ranges, lists and steps,
+    /// ex. `*/15 9-17 * * 1,3,5`.
+    pub fn parse(expr: &str) -> Result<Schedule> {
+        let fields = expr.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != 5 {
+            return Err(GRError::PreconditionNotMet(format!(
+                "Invalid schedule {}, expected minute, hour, day of month, month and day of week",
+                expr
+            ))
+            .into());
+        }
+        // Both 0 and 7 are Sunday.
+        let weekday = parse_field(fields[4], 0, 7)?.map(|days| {
+            let mut days = days.into_iter().map(|day| day % 7).collect::<Vec<_>>();
+            days.sort_unstable();
+            days.dedup();
+            days
+        });
+        Ok(Schedule {
+            expr: fields.join(" "),
+            minute: parse_field(fields[0], 0, 59)?,
+            hour: parse_field(fields[1], 0, 23)?,
+            day: parse_field(fields[2], 1, 31)?,
+            month: parse_field(fields[3], 1, 12)?,
+            weekday,
+        })
+    }
+
+    /// Day of month and day of week pairs, any of which triggers a run. Cron
+    /// runs when either of them matches if both are restricted, while timers
+    /// and agents have to match all their fields.
+    fn days(&self) -> Vec<(&Field, &Field)> {
+        match (&self.day, &self.weekday) {
+            (Some(_), Some(_)) => vec![(&self.day, &None), (&None, &self.weekday)],
+            (day, weekday) => vec![(day, weekday)],
+        }
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Field> {
+    let invalid = || -> anyhow::Error {
+        GRError::PreconditionNotMet(format!(
+            "Invalid schedule field {}, expected values from {} to {}",
+            field, min, max
+        ))
+        .into()
+    };
+    if field == "*" {
+        return Ok(None);
+    }
+    let value = |value: &str| value.parse::<u32>().map_err(|_| invalid());
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, value(step)?),
+            None => (part, 1),
+        };
+        let (from, to) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((from, to)) => (value(from)?, value(to)?),
+            // 5/10 starts at 5 and goes on up to the max.
+            None if part.contains('/') => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if step == 0 || from < min || to > max || from > to {
+            return Err(invalid());
+        }
+        values.extend((from..=to).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(Some(values))
+}
+
+/// A gr command run on a schedule from a directory, usually the repository
+/// it works on.
+pub struct CronJob {
+    pub schedule: Schedule,
+    pub workdir: PathBuf,
+    /// Path to the gr executable.
+    pub program: PathBuf,
+    pub args: Vec<String>,
+}
+
+impl CronJob {
+    /// Name of the entry in the scheduler. Derived from what it runs, so the
+    /// same command is not scheduled twice.
+    pub fn name(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.schedule.expr);
+        hasher.update(self.workdir.to_string_lossy().as_bytes());
+        for arg in &self.args {
+            hasher.update([0]);
+            hasher.update(arg);
+        }
+        let digest = format!("{:x}", hasher.finalize());
+        format!("gitar-cron-{}", &digest[..8])
+    }
+
+    fn description(&self) -> String {
+        format!("gr {}", self.args.join(" "))
+    }
+}
+
+#[derive(Builder)]
+pub struct CronCliArgs {
+    pub schedule: Schedule,
+    /// gr arguments, without the program name.
+    pub args: Vec<String>,
+    #[builder(default)]
+    pub scheduler: Scheduler,
+    /// Print the entry instead of registering it.
+    #[builder(default)]
+    pub dry_run: bool,
+}
+
+impl CronCliArgs {
+    pub fn builder() -> CronCliArgsBuilder {
+        CronCliArgsBuilder::default()
+    }
+}
+
+/// Schedules the command to run from the current directory. Systemd units go
+/// next to the config directory, `~/.config/systemd/user` by default, and
+/// launchd agents to `~/Library/LaunchAgents`.
+pub fn execute<P: AsRef<Path>>(cli_args: CronCliArgs, config_file: P) -> Result<()> {
+    // A mistyped command would otherwise be found failing on every run.
+    crate::cli::validate_args(&cli_args.args).map_err(|err| {
+        GRError::PreconditionNotMet(format!("Invalid gr command to schedule: {}", err))
+    })?;
+    let job = CronJob {
+        schedule: cli_args.schedule,
+        workdir: std::env::current_dir()?,
+        program: std::env::current_exe()?,
+        args: cli_args.args,
+    };
+    let writer = std::io::stdout();
+    match cli_args.scheduler {
+        Scheduler::Cron => install_crontab(
+            &Shell,
+            &job,
+            |crontab| shell::run_with_input(&["crontab", "-"], crontab).map(|_| ()),
+            cli_args.dry_run,
+            writer,
+        ),
+        Scheduler::Systemd => {
+            let config_home = config_file
+                .as_ref()
+                .parent()
+                .and_then(Path::parent)
+                .ok_or_else(|| error::gen("Cannot find the config directory"))?;
+            let dir = config_home.join("systemd").join("user");
+            install_systemd(&Shell, &job, &dir, cli_args.dry_run, writer)
+        }
+        Scheduler::Launchd => {
+            let home = std::env::var("HOME")
+                .map_err(|_| error::gen("HOME is not set, cannot find ~/Library/LaunchAgents"))?;
+            let dir = Path::new(&home).join("Library").join("LaunchAgents");
+            install_launchd(&Shell, &job, &dir, cli_args.dry_run, writer)
+        }
+    }
+}
+
+/// The new crontab is handed to `write_crontab`, which replaces the current
+/// one. Piped to `crontab -`, never written to a shared temporary file.
+fn install_crontab<W: Write>(
+    runner: &impl TaskRunner<Response = Response>,
+    job: &CronJob,
+    write_crontab: impl FnOnce(&str) -> Result<()>,
+    dry_run: bool,
+    mut writer: W,
+) -> Result<()> {
+    let entry = crontab_entry(job);
+    if dry_run {
+        writeln!(writer, "{}", entry)?;
+        return Ok(());
+    }
+    // Fails when the user has no crontab yet.
+    let mut crontab = match runner.run(["crontab", "-l"]) {
+        Ok(response) => response.body,
+        Err(err) if err.to_string().contains("no crontab") => String::new(),
+        Err(err) => return Err(err),
+    };
+    if crontab.lines().any(|line| line == entry) {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::AlreadyScheduledCron, &[&entry])
+        )?;
+        return Ok(());
+    }
+    if !crontab.is_empty() && !crontab.ends_with('\n') {
+        crontab.push('\n');
+    }
+    crontab.push_str(&entry);
+    crontab.push('\n');
+    write_crontab(&crontab)?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::ScheduledCron, &[&entry])
+    )?;
+    Ok(())
+}
+
+/// Line of the crontab running the job, tagged with its name.
+fn crontab_entry(job: &CronJob) -> String {
+    let mut command = format!("cd {} &&", shell_quote(&job.workdir.to_string_lossy()));
+    command.push(' ');
+    command.push_str(&shell_quote(&job.program.to_string_lossy()));
+    for arg in &job.args {
+        command.push(' ');
+        command.push_str(&shell_quote(arg));
+    }
+    // Cron turns unescaped % into newlines.
+    format!(
+        "{} {} # {}",
+        job.schedule.expr,
+        command.replace('%', "\\%"),
+        job.name()
+    )
+}
+
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn install_systemd<W: Write>(
+    runner: &impl TaskRunner<Response = Response>,
+    job: &CronJob,
+    dir: &Path,
+    dry_run: bool,
+    writer: W,
+) -> Result<()> {
+    let name = job.name();
+    let files = [
+        (dir.join(format!("{}.service", name)), systemd_service(job)),
+        (dir.join(format!("{}.timer", name)), systemd_timer(job)),
+    ];
+    let timer = format!("{}.timer", name);
+    let activate: [&[&str]; 2] = [
+        &["systemctl", "--user", "daemon-reload"],
+        &["systemctl", "--user", "enable", "--now", &timer],
+    ];
+    install_files(runner, &files, &activate, dry_run, writer)
+}
+
+fn systemd_service(job: &CronJob) -> String {
+    let mut exec_start = systemd_quote(&job.program.to_string_lossy());
+    for arg in &job.args {
+        exec_start.push(' ');
+        exec_start.push_str(&systemd_quote(arg));
+    }
+    format!(
+        "[Unit]\n\
+         Description={}\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         WorkingDirectory={}\n\
+         ExecStart={}\n",
+        job.description(),
+        job.workdir.display(),
+        exec_start
+    )
+}
+
+fn systemd_timer(job: &CronJob) -> String {
+    let on_calendar = on_calendar(&job.schedule)
+        .into_iter()
+        .map(|spec| format!("OnCalendar={}\n", spec))
+        .collect::<String>();
+    format!(
+        "[Unit]\n\
+         Description=Schedule of {}\n\
+         \n\
+         [Timer]\n\
+         {}\
+         \n\
+         [Install]\n\
+         WantedBy=timers.target\n",
+        job.description(),
+        on_calendar
+    )
+}
+
+/// Systemd expands % specifiers and $ variables in command lines.
+fn systemd_quote(arg: &str) -> String {
+    let arg = arg
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    format!("\"{}\"", arg)
+}
+
+/// Calendar events of a systemd timer, ex. `Mon,Tue *-*-* 09:00:00`.
+fn on_calendar(schedule: &Schedule) -> Vec<String> {
+    let join = |field: &Field| match field {
+        Some(values) => values
+            .iter()
+            .map(|value| format!("{:02}", value))
+            .collect::<Vec<_>>()
+            .join(","),
+        None => "*".to_string(),
+    };
+    schedule
+        .days()
+        .into_iter()
+        .map(|(day, weekday)| {
+            let weekdays = match weekday {
+                Some(days) => {
+                    let names = days
+                        .iter()
+                        .map(|day| WEEKDAYS[*day as usize])
+                        .collect::<Vec<_>>();
+                    format!("{} ", names.join(","))
+                }
+                None => String::new(),
+            };
+            format!(
+                "{}*-{}-{} {}:{}:00",
+                weekdays,
+                join(&schedule.month),
+                join(day),
+                join(&schedule.hour),
+                join(&schedule.minute)
+            )
+        })
+        .collect()
+}
+
+fn install_launchd<W: Write>(
+    runner: &impl TaskRunner<Response = Response>,
+    job: &CronJob,
+    dir: &Path,
+    dry_run: bool,
+    writer: W,
+) -> Result<()> {
+    let plist = dir.join(format!("{}.plist", job.name()));
+    let path = plist.to_string_lossy().to_string();
+    let activate: [&[&str]; 1] = [&["launchctl", "load", "-w", &path]];
+    install_files(
+        runner,
+        &[(plist.clone(), launchd_plist(job))],
+        &activate,
+        dry_run,
+        writer,
+    )
+}
+
+fn launchd_plist(job: &CronJob) -> String {
+    let mut plist = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+         \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n",
+    );
+    plist.push_str(&format!(
+        "    <key>Label</key>\n    <string>{}</string>\n",
+        job.name()
+    ));
+    plist.push_str(&format!(
+        "    <key>WorkingDirectory</key>\n    <string>{}</string>\n",
+        xml_escape(&job.workdir.to_string_lossy())
+    ));
+    plist.push_str("    <key>ProgramArguments</key>\n    <array>\n");
+    plist.push_str(&format!(
+        "        <string>{}</string>\n",
+        xml_escape(&job.program.to_string_lossy())
+    ));
+    for arg in &job.args {
+        plist.push_str(&format!("        <string>{}</string>\n", xml_escape(arg)));
+    }
+    plist.push_str("    </array>\n    <key>StartCalendarInterval</key>\n    <array>\n");
+    for interval in calendar_intervals(&job.schedule) {
+        plist.push_str("        <dict>\n");
+        for (key, value) in interval {
+            plist.push_str(&format!(
+                "            <key>{}</key>\n            <integer>{}</integer>\n",
+                key, value
+            ));
+        }
+        plist.push_str("        </dict>\n");
+    }
+    plist.push_str("    </array>\n</dict>\n</plist>\n");
+    plist
+}
+
+/// Launchd matches one value per key, so every combination of the values of
+/// the restricted fields gets its own interval.
+fn calendar_intervals(schedule: &Schedule) -> Vec<Vec<(&'static str, u32)>> {
+    let mut intervals = Vec::new();
+    for (day, weekday) in schedule.days() {
+        let fields = [
+            ("Month", &schedule.month),
+            ("Day", day),
+            ("Weekday", weekday),
+            ("Hour", &schedule.hour),
+            ("Minute", &schedule.minute),
+        ];
+        let mut combinations = vec![Vec::new()];
+        for (key, field) in fields {
+            let Some(values) = field else {
+                continue;
+            };
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination: Vec<(&'static str, u32)>| {
+                    values.iter().map(move |value| {
+                        let mut combination = combination.clone();
+                        combination.push((key, *value));
+                        combination
+                    })
+                })
+                .collect();
+        }
+        intervals.extend(combinations);
+    }
+    intervals
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Writes the files and runs the commands that activate them. Existing files
+/// mean the job is scheduled already.
+fn install_files<W: Write>(
+    runner: &impl TaskRunner<Response = Response>,
+    files: &[(PathBuf, String)],
+    activate: &[&[&str]],
+    dry_run: bool,
+    mut writer: W,
+) -> Result<()> {
+    if dry_run {
+        for (path, content) in files {
+            writeln!(writer, "# {}\n{}", path.display(), content)?;
+        }
+        return Ok(());
+    }
+    if files.iter().all(|(path, _)| path.exists()) {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::AlreadyScheduled, &[&files[0].0.display()])
+        )?;
+        return Ok(());
+    }
+    for (path, content) in files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::Created, &[&path.display()])
+        )?;
+    }
+    for cmd in activate {
+        runner.run(*cmd)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::utils::MockRunner;
+
+    fn job(schedule: &str) -> CronJob {
+        CronJob {
+            schedule: Schedule::parse(schedule).unwrap(),
+            workdir: PathBuf::from("/home/jdoe/project"),
+            program: PathBuf::from("/usr/local/bin/gr"),
+            args: vec!["my".to_string(), "mr".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_schedule_fields() {
+        let schedule = Schedule::parse("*/15 9-17 1,15 * 1-5").unwrap();
+        assert_eq!(Some(vec![0, 15, 30, 45]), schedule.minute);
+        assert_eq!(Some((9..=17).collect()), schedule.hour);
+        assert_eq!(Some(vec![1, 15]), schedule.day);
+        assert_eq!(None, schedule.month);
+        assert_eq!(Some(vec![1, 2, 3, 4, 5]), schedule.weekday);
+    }
+
+    #[test]
+    fn test_parse_schedule_sunday_is_0_and_7() {
+        let schedule = Schedule::parse("0 0 * * 0,7").unwrap();
+        assert_eq!(Some(vec![0]), schedule.weekday);
+    }
+
+    #[test]
+    fn test_parse_invalid_schedules() {
+        for expr in [
+            "0 9 * *",
+            "60 9 * * *",
+            "0 9 0 * *",
+            "0 9 * * 1-8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "@daily * * * *",
+        ] {
+            assert!(Schedule::parse(expr).is_err(), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_crontab_entry_quotes_and_escapes() {
+        let mut job = job("0 9 * * 1-5");
+        job.args = vec!["pp".to_string(), "list".to_string(), "50%".to_string()];
+        let entry = crontab_entry(&job);
+        assert_eq!(
+            format!(
+                "0 9 * * 1-5 cd '/home/jdoe/project' && '/usr/local/bin/gr' 'pp' 'list' '50\\%' # {}",
+                job.name()
+            ),
+            entry
+        );
+    }
+
+    #[test]
+    fn test_job_name_depends_on_what_it_runs() {
+        let name = job("0 9 * * 1-5").name();
+        assert!(name.starts_with("gitar-cron-"));
+        assert_eq!(name, job("0  9 * * 1-5").name());
+        assert_ne!(name, job("0 10 * * 1-5").name());
+    }
+
+    #[test]
+    fn test_install_crontab_appends_to_existing_entries() {
+        let runner = MockRunner::new(vec![Response::builder()
+            .body("0 0 * * * backup.sh".to_string())
+            .build()
+            .unwrap()]);
+        let job = job("0 9 * * 1-5");
+        let mut buf = Vec::new();
+        let mut written = String::new();
+        install_crontab(
+            &runner,
+            &job,
+            |crontab| {
+                written = crontab.to_string();
+                Ok(())
+            },
+            false,
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            format!("0 0 * * * backup.sh\n{}\n", crontab_entry(&job)),
+            written
+        );
+        assert_eq!(
+            format!("Scheduled with cron: {}\n", crontab_entry(&job)),
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_install_crontab_skips_jobs_already_scheduled() {
+        let job = job("0 9 * * 1-5");
+        let runner = MockRunner::new(vec![Response::builder()
+            .body(format!("0 0 * * * backup.sh\n{}", crontab_entry(&job)))
+            .build()
+            .unwrap()]);
+        let mut buf = Vec::new();
+        install_crontab(
+            &runner,
+            &job,
+            |_| panic!("crontab must not be written"),
+            false,
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!("crontab -l", *runner.cmd());
+        assert!(String::from_utf8(buf)
+            .unwrap()
+            .starts_with("Already scheduled with cron"));
+    }
+
+    #[test]
+    fn test_install_crontab_when_user_has_none() {
+        let runner = MockRunner::new(vec![Response::builder()
+            .status(1)
+            .body("no crontab for jdoe".to_string())
+            .build()
+            .unwrap()]);
+        let job = job("0 9 * * 1-5");
+        let mut buf = Vec::new();
+        let mut written = String::new();
+        install_crontab(
+            &runner,
+            &job,
+            |crontab| {
+                written = crontab.to_string();
+                Ok(())
+            },
+            false,
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(format!("{}\n", crontab_entry(&job)), written);
+    }
+
+    #[test]
+    fn test_systemd_timer_calendar() {
+        assert_eq!(
+            vec!["Mon,Tue,Wed,Thu,Fri *-*-* 09:00:00"],
+            on_calendar(&Schedule::parse("0 9 * * 1-5").unwrap())
+        );
+        assert_eq!(
+            vec!["*-*-* *:00,30:00"],
+            on_calendar(&Schedule::parse("*/30 * * * *").unwrap())
+        );
+        // Cron runs on the 1st and on every Monday.
+        assert_eq!(
+            vec!["*-*-01 08:00:00", "Mon *-*-* 08:00:00"],
+            on_calendar(&Schedule::parse("0 8 1 * 1").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_systemd_units() {
+        let job = job("0 9 * * 1-5");
+        assert_eq!(
+            "[Unit]\n\
+             Description=gr my mr\n\
+             \n\
+             [Service]\n\
+             Type=oneshot\n\
+             WorkingDirectory=/home/jdoe/project\n\
+             ExecStart=\"/usr/local/bin/gr\" \"my\" \"mr\"\n",
+            systemd_service(&job)
+        );
+        assert!(systemd_timer(&job).contains("OnCalendar=Mon,Tue,Wed,Thu,Fri *-*-* 09:00:00\n"));
+    }
+
+    #[test]
+    fn test_launchd_calendar_intervals() {
+        let schedule = Schedule::parse("0,30 9 * * 1,5").unwrap();
+        assert_eq!(
+            vec![
+                vec![("Weekday", 1), ("Hour", 9), ("Minute", 0)],
+                vec![("Weekday", 1), ("Hour", 9), ("Minute", 30)],
+                vec![("Weekday", 5), ("Hour", 9), ("Minute", 0)],
+                vec![("Weekday", 5), ("Hour", 9), ("Minute", 30)],
+            ],
+            calendar_intervals(&schedule)
+        );
+    }
+
+    #[test]
+    fn test_launchd_plist_lists_program_arguments() {
+        let plist = launchd_plist(&job("0 9 * * *"));
+        assert!(plist.contains(
+            "    <array>\n        <string>/usr/local/bin/gr</string>\n        \
+             <string>my</string>\n        <string>mr</string>\n    </array>\n"
+        ));
+        assert!(plist.contains(
+            "        <dict>\n            <key>Hour</key>\n            <integer>9</integer>\n"
+        ));
+    }
+
+    #[test]
+    fn test_install_systemd_writes_units_and_enables_timer() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = MockRunner::new(vec![
+            Response::builder().build().unwrap(),
+            Response::builder().build().unwrap(),
+        ]);
+        let job = job("0 9 * * 1-5");
+        let mut buf = Vec::new();
+        install_systemd(&runner, &job, dir.path(), false, &mut buf).unwrap();
+        let timer = dir.path().join(format!("{}.timer", job.name()));
+        assert!(timer.exists());
+        assert!(dir.path().join(format!("{}.service", job.name())).exists());
+        assert_eq!(
+            format!("systemctl --user enable --now {}.timer", job.name()),
+            *runner.cmd()
+        );
+        // Second time around there is nothing to do.
+        let mut buf = Vec::new();
+        install_systemd(&runner, &job, dir.path(), false, &mut buf).unwrap();
+        assert!(String::from_utf8(buf)
+            .unwrap()
+            .starts_with("Already scheduled"));
+    }
+}
@@ -9,7 +9,7 @@ use crate::{
     Result,
 };
 
-use super::common::process_num_pages;
+use super::common::{self, process_num_pages};
 
 #[derive(Builder)]
 pub struct DockerListCliArgs {
@@ -160,11 +160,17 @@ pub fn execute(
                 config,
                 cli_args.list_args.get_args.refresh_cache,
             )?;
-            validate_and_list(remote, cli_args, std::io::stdout())
+            let output = cli_args.list_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                validate_and_list(remote, cli_args, writer)
+            })
         }
         DockerOptions::Get(cli_args) => {
             let remote = get_registry(domain, path, config, cli_args.get_args.refresh_cache)?;
-            get_image_metadata(remote, cli_args, std::io::stdout())
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                get_image_metadata(remote, cli_args, writer)
+            })
         }
     }
 }
@@ -219,6 +225,7 @@ fn get_num_pages<W: Write>(
 #[cfg(test)]
 mod tests {
     use crate::error;
+    use crate::io::PageCount;
 
     use super::*;
 
@@ -262,18 +269,18 @@ mod tests {
             Ok(vec![tag])
         }
 
-        fn num_pages_repository_tags(&self, _repository_id: i64) -> Result<Option<u32>> {
-            Ok(Some(3))
+        fn num_pages_repository_tags(&self, _repository_id: i64) -> Result<Option<PageCount>> {
+            Ok(Some(PageCount::from(3)))
         }
 
-        fn num_pages_repositories(&self) -> Result<Option<u32>> {
+        fn num_pages_repositories(&self) -> Result<Option<PageCount>> {
             if self.num_pages_repos_ok_none {
                 return Ok(None);
             }
             if self.num_pages_repos_err {
                 return Err(error::gen("Error"));
             }
-            Ok(Some(1))
+            Ok(Some(PageCount::from(1)))
         }
 
         fn get_image_metadata(&self, _repository_id: i64, tag: &str) -> Result<ImageMetadata> {
@@ -0,0 +1,389 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use crate::api_traits::{Deployment, DeploymentApproval, Timestamp};
+use crate::cli::environment::{ApprovalOptions, EnvironmentOptions};
+use crate::config::Config;
+use crate::display::{self, Column, DisplayBody};
+use crate::i18n::{self, Message};
+use crate::remote::{self, GetRemoteCliArgs, ListBodyArgs};
+use crate::Result;
+
+use super::common;
+
+/// An environment of the project along with its last deployment. The
+/// deployment fields are empty when nothing has been deployed to it yet.
+#[derive(Builder, Clone, Debug)]
+pub struct Environment {
+    pub id: i64,
+    pub name: String,
+    /// Commit of the last deployment.
+    #[builder(default)]
+    pub sha: String,
+    /// Status of the last deployment, ex. success, failed, running.
+    #[builder(default)]
+    pub status: String,
+    /// Where the environment can be reached.
+    #[builder(default)]
+    pub url: String,
+    #[builder(default)]
+    pub deployed_at: String,
+    pub created_at: String,
+}
+
+impl Environment {
+    pub fn builder() -> EnvironmentBuilder {
+        EnvironmentBuilder::default()
+    }
+}
+
+impl Timestamp for Environment {
+    fn created_at(&self) -> String {
+        self.created_at.clone()
+    }
+}
+
+impl From<Environment> for DisplayBody {
+    fn from(environment: Environment) -> Self {
+        DisplayBody::new(vec![
+            Column::new("Name", environment.name),
+            Column::new("SHA", environment.sha),
+            Column::new("Status", environment.status),
+            Column::new("URL", environment.url),
+            Column::new("Deployed at", environment.deployed_at),
+        ])
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct EnvironmentListBodyArgs {
+    pub list_args: Option<ListBodyArgs>,
+}
+
+impl EnvironmentListBodyArgs {
+    pub fn builder() -> EnvironmentListBodyArgsBuilder {
+        EnvironmentListBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct EnvironmentGetCliArgs {
+    pub name: String,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl EnvironmentGetCliArgs {
+    pub fn builder() -> EnvironmentGetCliArgsBuilder {
+        EnvironmentGetCliArgsBuilder::default()
+    }
+}
+
+/// A deployment blocked until a reviewer approves it. In Github, the id is
+/// the id of the workflow run waiting on its environments.
+#[derive(Builder, Clone, Debug)]
+pub struct PendingDeployment {
+    pub id: i64,
+    pub environment: String,
+    pub branch: String,
+    pub created_at: String,
+    pub web_url: String,
+}
+
+impl PendingDeployment {
+    pub fn builder() -> PendingDeploymentBuilder {
+        PendingDeploymentBuilder::default()
+    }
+}
+
+impl Timestamp for PendingDeployment {
+    fn created_at(&self) -> String {
+        self.created_at.clone()
+    }
+}
+
+impl From<PendingDeployment> for DisplayBody {
+    fn from(deployment: PendingDeployment) -> Self {
+        DisplayBody::new(vec![
+            Column::new("ID", deployment.id.to_string()),
+            Column::new("Environment", deployment.environment),
+            Column::new("Branch", deployment.branch),
+            Column::new("Created at", deployment.created_at),
+            Column::new("URL", deployment.web_url),
+        ])
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct DeploymentApprovalListBodyArgs {
+    pub list_args: Option<ListBodyArgs>,
+}
+
+impl DeploymentApprovalListBodyArgs {
+    pub fn builder() -> DeploymentApprovalListBodyArgsBuilder {
+        DeploymentApprovalListBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct DeploymentApproveCliArgs {
+    pub id: i64,
+    #[builder(default)]
+    pub comment: Option<String>,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl DeploymentApproveCliArgs {
+    pub fn builder() -> DeploymentApproveCliArgsBuilder {
+        DeploymentApproveCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct DeploymentApproveBodyArgs {
+    pub id: i64,
+    #[builder(default)]
+    pub comment: String,
+}
+
+impl DeploymentApproveBodyArgs {
+    pub fn builder() -> DeploymentApproveBodyArgsBuilder {
+        DeploymentApproveBodyArgsBuilder::default()
+    }
+}
+
+pub fn execute(
+    options: EnvironmentOptions,
+    config: Arc<Config>,
+    domain: String,
+    path: String,
+) -> Result<()> {
+    match options {
+        EnvironmentOptions::List(cli_args) => {
+            let remote =
+                remote::get_deployment(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let from_to_args = remote::validate_from_to_page(&cli_args)?;
+            let body_args = EnvironmentListBodyArgs::builder()
+                .list_args(from_to_args)
+                .build()?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                common::list_environments(remote, body_args, cli_args, writer)
+            })
+        }
+        EnvironmentOptions::Get(cli_args) => {
+            let remote =
+                remote::get_deployment(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                get_environment(remote, cli_args, writer)
+            })
+        }
+        EnvironmentOptions::Approvals(options) => match options {
+            ApprovalOptions::List(cli_args) => {
+                let remote = remote::get_deployment_approval(
+                    domain,
+                    path,
+                    config,
+                    cli_args.get_args.refresh_cache,
+                )?;
+                let from_to_args = remote::validate_from_to_page(&cli_args)?;
+                let body_args = DeploymentApprovalListBodyArgs::builder()
+                    .list_args(from_to_args)
+                    .build()?;
+                let output = cli_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    common::list_pending_deployments(remote, body_args, cli_args, writer)
+                })
+            }
+            ApprovalOptions::Approve(cli_args) => {
+                let remote = remote::get_deployment_approval(
+                    domain,
+                    path,
+                    config,
+                    cli_args.get_args.refresh_cache,
+                )?;
+                approve_deployment(remote, cli_args, std::io::stdout())
+            }
+        },
+    }
+}
+
+fn get_environment<W: Write>(
+    remote: Arc<dyn Deployment>,
+    cli_args: EnvironmentGetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let environment = remote.get(&cli_args.name)?;
+    display::print(&mut writer, vec![environment], cli_args.get_args)?;
+    Ok(())
+}
+
+fn approve_deployment<W: Write>(
+    remote: Arc<dyn DeploymentApproval>,
+    cli_args: DeploymentApproveCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let body_args = DeploymentApproveBodyArgs::builder()
+        .id(cli_args.id)
+        .comment(cli_args.comment.unwrap_or_default())
+        .build()?;
+    remote.approve(body_args)?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::DeploymentApproved, &[&cli_args.id])
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use crate::remote::ListRemoteCliArgs;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct DeploymentApprovalMock {
+        deployments: Vec<PendingDeployment>,
+        approved: Mutex<Vec<DeploymentApproveBodyArgs>>,
+    }
+
+    impl DeploymentApproval for DeploymentApprovalMock {
+        fn list(&self, _args: DeploymentApprovalListBodyArgs) -> Result<Vec<PendingDeployment>> {
+            Ok(self.deployments.clone())
+        }
+
+        fn approve(&self, args: DeploymentApproveBodyArgs) -> Result<()> {
+            self.approved.lock().unwrap().push(args);
+            Ok(())
+        }
+    }
+
+    struct DeploymentMock {
+        environments: Vec<Environment>,
+    }
+
+    impl Deployment for DeploymentMock {
+        fn list(&self, _args: EnvironmentListBodyArgs) -> Result<Vec<Environment>> {
+            Ok(self.environments.clone())
+        }
+
+        fn get(&self, name: &str) -> Result<Environment> {
+            Ok(self
+                .environments
+                .iter()
+                .find(|environment| environment.name == name)
+                .unwrap()
+                .clone())
+        }
+    }
+
+    fn environments() -> Vec<Environment> {
+        vec![
+            Environment::builder()
+                .id(15987451)
+                .name("production".to_string())
+                .sha("aa8b3b2c".to_string())
+                .status("success".to_string())
+                .url("https://example.com".to_string())
+                .deployed_at("2024-03-04T08:15:10Z".to_string())
+                .created_at("2024-01-10T08:00:00Z".to_string())
+                .build()
+                .unwrap(),
+            Environment::builder()
+                .id(15987452)
+                .name("staging".to_string())
+                .created_at("2024-01-10T08:00:00Z".to_string())
+                .build()
+                .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_list_environments() {
+        let remote = Arc::new(DeploymentMock {
+            environments: environments(),
+        });
+        let body_args = EnvironmentListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let cli_args = ListRemoteCliArgs::builder().build().unwrap();
+        let mut buf = Vec::new();
+        common::list_environments(remote, body_args, cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "Name|SHA|Status|URL|Deployed at\n\
+             production|aa8b3b2c|success|https://example.com|2024-03-04T08:15:10Z\n\
+             staging||||\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_environment() {
+        let remote = Arc::new(DeploymentMock {
+            environments: environments(),
+        });
+        let cli_args = EnvironmentGetCliArgs::builder()
+            .name("production".to_string())
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        get_environment(remote, cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "Name|SHA|Status|URL|Deployed at\n\
+             production|aa8b3b2c|success|https://example.com|2024-03-04T08:15:10Z\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list_pending_deployments() {
+        let remote = Arc::new(DeploymentApprovalMock {
+            deployments: vec![PendingDeployment::builder()
+                .id(591345821)
+                .environment("production".to_string())
+                .branch("main".to_string())
+                .created_at("2024-03-04T08:15:10Z".to_string())
+                .web_url("https://gitlab.com/owner/repo/-/jobs/1".to_string())
+                .build()
+                .unwrap()],
+            ..Default::default()
+        });
+        let body_args = DeploymentApprovalListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let cli_args = ListRemoteCliArgs::builder().build().unwrap();
+        let mut buf = Vec::new();
+        common::list_pending_deployments(remote, body_args, cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "ID|Environment|Branch|Created at|URL\n\
+             591345821|production|main|2024-03-04T08:15:10Z|https://gitlab.com/owner/repo/-/jobs/1\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_approve_deployment_with_comment() {
+        let remote = Arc::new(DeploymentApprovalMock::default());
+        let cli_args = DeploymentApproveCliArgs::builder()
+            .id(591345821)
+            .comment(Some("Release window open".to_string()))
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        approve_deployment(remote.clone(), cli_args, &mut buf).unwrap();
+        let approved = remote.approved.lock().unwrap();
+        assert_eq!(591345821, approved[0].id);
+        assert_eq!("Release window open", approved[0].comment);
+        assert_eq!(
+            "Deployment 591345821 approved\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+}
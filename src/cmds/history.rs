@@ -0,0 +1,150 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use crate::audit::{AuditLog, FileAuditLog};
+use crate::config::Config;
+use crate::display;
+use crate::i18n::{self, Message};
+use crate::remote::GetRemoteCliArgs;
+use crate::Result;
+
+#[derive(Builder)]
+pub struct HistoryCliArgs {
+    pub method: Option<String>,
+    pub url: Option<String>,
+    pub limit: usize,
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl HistoryCliArgs {
+    pub fn builder() -> HistoryCliArgsBuilder {
+        HistoryCliArgsBuilder::default()
+    }
+}
+
+pub fn execute(cli_args: HistoryCliArgs, config: Arc<Config>) -> Result<()> {
+    let audit_log = FileAuditLog::new(&config);
+    history(&audit_log, cli_args, std::io::stdout())
+}
+
+fn history<W: Write>(
+    audit_log: &impl AuditLog,
+    cli_args: HistoryCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let entries = audit_log
+        .entries()?
+        .into_iter()
+        .rev()
+        .filter(|entry| match &cli_args.method {
+            Some(method) => entry.method.eq_ignore_ascii_case(method),
+            None => true,
+        })
+        .filter(|entry| match &cli_args.url {
+            Some(url) => entry.url.contains(url.as_str()),
+            None => true,
+        })
+        .take(cli_args.limit)
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoOperationsRecorded))?;
+        return Ok(());
+    }
+    display::print(&mut writer, entries, cli_args.get_args)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audit::AuditEntry;
+
+    use super::*;
+
+    struct AuditLogMock {
+        entries: Vec<AuditEntry>,
+    }
+
+    impl AuditLog for AuditLogMock {
+        fn record(&self, _entry: &AuditEntry) -> Result<()> {
+            Ok(())
+        }
+        fn entries(&self) -> Result<Vec<AuditEntry>> {
+            Ok(self.entries.clone())
+        }
+    }
+
+    fn entry(method: &str, url: &str) -> AuditEntry {
+        AuditEntry::builder()
+            .timestamp(0)
+            .user("jdoe".to_string())
+            .method(method.to_string())
+            .url(url.to_string())
+            .result("200".to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn cli_args(method: Option<&str>, url: Option<&str>, limit: usize) -> HistoryCliArgs {
+        HistoryCliArgs::builder()
+            .method(method.map(|m| m.to_string()))
+            .url(url.map(|u| u.to_string()))
+            .limit(limit)
+            .get_args(
+                GetRemoteCliArgs::builder()
+                    .no_headers(true)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_history_filters_most_recent_first() {
+        let audit_log = AuditLogMock {
+            entries: vec![
+                entry(
+                    "PUT",
+                    "https://gitlab.com/api/v4/projects/1/merge_requests/12/merge",
+                ),
+                entry(
+                    "POST",
+                    "https://gitlab.com/api/v4/projects/1/merge_requests",
+                ),
+                entry(
+                    "PUT",
+                    "https://gitlab.com/api/v4/projects/1/merge_requests/13/merge",
+                ),
+            ],
+        };
+        let mut writer = Vec::new();
+        history(
+            &audit_log,
+            cli_args(Some("put"), Some("/merge"), 20),
+            &mut writer,
+        )
+        .unwrap();
+        let output = String::from_utf8(writer).unwrap();
+        let urls = output
+            .lines()
+            .map(|line| line.split('|').nth(3).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "https://gitlab.com/api/v4/projects/1/merge_requests/13/merge",
+                "https://gitlab.com/api/v4/projects/1/merge_requests/12/merge",
+            ],
+            urls
+        );
+    }
+
+    #[test]
+    fn test_history_no_entries() {
+        let audit_log = AuditLogMock { entries: vec![] };
+        let mut writer = Vec::new();
+        history(&audit_log, cli_args(None, None, 20), &mut writer).unwrap();
+        assert_eq!(
+            "No operations recorded\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+}
@@ -0,0 +1,414 @@
+use std::fmt::Display;
+use std::io::{BufRead, Cursor, Write};
+use std::sync::Arc;
+
+use crate::api_traits::{Issue, Timestamp};
+use crate::cli::issue::IssueOptions;
+use crate::config::Config;
+use crate::display::{self, Column, DisplayBody};
+use crate::i18n::{self, Message};
+use crate::remote::{self, GetRemoteCliArgs, ListBodyArgs, ListRemoteCliArgs};
+use crate::Result;
+
+use super::common;
+use super::merge_request::get_reader_file_cli;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum IssueState {
+    Opened,
+    Closed,
+}
+
+// Gitlab's naming. Github's is mapped in its implementation.
+impl Display for IssueState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueState::Opened => write!(f, "opened"),
+            IssueState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct IssueBodyArgs {
+    pub title: String,
+    #[builder(default)]
+    pub description: String,
+    #[builder(default)]
+    pub labels: Vec<String>,
+}
+
+impl IssueBodyArgs {
+    pub fn builder() -> IssueBodyArgsBuilder {
+        IssueBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct IssueListBodyArgs {
+    pub state: IssueState,
+    pub list_args: Option<ListBodyArgs>,
+}
+
+impl IssueListBodyArgs {
+    pub fn builder() -> IssueListBodyArgsBuilder {
+        IssueListBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug, Default, PartialEq)]
+pub struct IssueResponse {
+    pub id: i64,
+    pub title: String,
+    #[builder(default)]
+    pub description: String,
+    pub author: String,
+    pub state: String,
+    pub web_url: String,
+    #[builder(default)]
+    pub labels: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Github lists pull requests as issues too.
+    #[builder(default)]
+    pub pull_request: bool,
+}
+
+impl IssueResponse {
+    pub fn builder() -> IssueResponseBuilder {
+        IssueResponseBuilder::default()
+    }
+}
+
+impl From<IssueResponse> for DisplayBody {
+    fn from(issue: IssueResponse) -> Self {
+        DisplayBody::new(vec![
+            Column::new("ID", issue.id.to_string()),
+            Column::new("Title", issue.title),
+            Column::builder()
+                .name("Description".to_string())
+                .value(issue.description)
+                .optional(true)
+                .build()
+                .unwrap(),
+            Column::new("Author", issue.author),
+            Column::new("State", issue.state),
+            Column::builder()
+                .name("Labels".to_string())
+                .value(issue.labels.join(","))
+                .optional(true)
+                .build()
+                .unwrap(),
+            Column::new("URL", issue.web_url),
+            Column::new("Updated at", issue.updated_at),
+        ])
+    }
+}
+
+impl Timestamp for IssueResponse {
+    fn created_at(&self) -> String {
+        self.created_at.clone()
+    }
+}
+
+#[derive(Builder)]
+pub struct IssueCreateCliArgs {
+    pub title: String,
+    #[builder(default)]
+    pub description: String,
+    #[builder(default)]
+    pub labels: Vec<String>,
+}
+
+impl IssueCreateCliArgs {
+    pub fn builder() -> IssueCreateCliArgsBuilder {
+        IssueCreateCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct IssueListCliArgs {
+    pub state: IssueState,
+    pub list_args: ListRemoteCliArgs,
+}
+
+impl IssueListCliArgs {
+    pub fn builder() -> IssueListCliArgsBuilder {
+        IssueListCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct IssueGetCliArgs {
+    pub id: i64,
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl IssueGetCliArgs {
+    pub fn builder() -> IssueGetCliArgsBuilder {
+        IssueGetCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct IssueCommentCliArgs {
+    pub id: i64,
+    #[builder(default)]
+    pub comment: Option<String>,
+    #[builder(default)]
+    pub comment_from_file: Option<String>,
+}
+
+impl IssueCommentCliArgs {
+    pub fn builder() -> IssueCommentCliArgsBuilder {
+        IssueCommentCliArgsBuilder::default()
+    }
+}
+
+pub fn execute(
+    options: IssueOptions,
+    config: Arc<Config>,
+    domain: String,
+    path: String,
+) -> Result<()> {
+    match options {
+        IssueOptions::Create(cli_args) => {
+            let remote = remote::get_issue(domain, path, config, false)?;
+            create(remote, cli_args, std::io::stdout())
+        }
+        IssueOptions::List(cli_args) => {
+            let remote = remote::get_issue(
+                domain,
+                path,
+                config,
+                cli_args.list_args.get_args.refresh_cache,
+            )?;
+            let from_to_args = remote::validate_from_to_page(&cli_args.list_args)?;
+            let body_args = IssueListBodyArgs::builder()
+                .state(cli_args.state)
+                .list_args(from_to_args)
+                .build()?;
+            if cli_args.list_args.num_pages {
+                return common::num_issue_pages(remote, body_args, std::io::stdout());
+            }
+            let output = cli_args.list_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                common::list_issues(remote, body_args, cli_args, writer)
+            })
+        }
+        IssueOptions::Get(cli_args) => {
+            let remote = remote::get_issue(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| get(remote, cli_args, writer))
+        }
+        IssueOptions::Close { id } => {
+            let remote = remote::get_issue(domain, path, config, false)?;
+            close(remote, id, std::io::stdout())
+        }
+        IssueOptions::Comment(cli_args) => {
+            let remote = remote::get_issue(domain, path, config, false)?;
+            if let Some(comment_file) = &cli_args.comment_from_file {
+                let reader = get_reader_file_cli(comment_file)?;
+                comment(remote, cli_args, Some(reader))
+            } else {
+                comment(remote, cli_args, None::<Cursor<&str>>)
+            }
+        }
+    }
+}
+
+fn create<W: Write>(
+    remote: Arc<dyn Issue>,
+    cli_args: IssueCreateCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let body_args = IssueBodyArgs::builder()
+        .title(cli_args.title)
+        .description(cli_args.description)
+        .labels(cli_args.labels)
+        .build()?;
+    let issue = remote.open(body_args)?;
+    writer.write_all(
+        format!(
+            "{}\n",
+            i18n::format(Message::IssueOpened, &[&issue.web_url])
+        )
+        .as_bytes(),
+    )?;
+    Ok(())
+}
+
+fn get<W: Write>(remote: Arc<dyn Issue>, cli_args: IssueGetCliArgs, mut writer: W) -> Result<()> {
+    let issue = remote.get(cli_args.id)?;
+    display::print(&mut writer, vec![issue], cli_args.get_args)?;
+    Ok(())
+}
+
+fn close<W: Write>(remote: Arc<dyn Issue>, id: i64, mut writer: W) -> Result<()> {
+    let issue = remote.close(id)?;
+    writer.write_all(
+        format!(
+            "{}\n",
+            i18n::format(Message::IssueClosed, &[&issue.web_url])
+        )
+        .as_bytes(),
+    )?;
+    Ok(())
+}
+
+fn comment<R: BufRead>(
+    remote: Arc<dyn Issue>,
+    cli_args: IssueCommentCliArgs,
+    reader: Option<R>,
+) -> Result<()> {
+    let comment = if let Some(comment) = cli_args.comment {
+        comment
+    } else {
+        let mut comment = String::new();
+        // Either a comment or a file is enforced at the CLI interface.
+        reader.unwrap().read_to_string(&mut comment)?;
+        comment
+    };
+    remote.comment(cli_args.id, &comment)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::PageCount;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct IssueMock {
+        opened: Mutex<Option<IssueBodyArgs>>,
+        comment: Mutex<Option<(i64, String)>>,
+    }
+
+    fn issue(id: i64, state: &str) -> IssueResponse {
+        IssueResponse::builder()
+            .id(id)
+            .title("Crash on startup".to_string())
+            .author("jdoe".to_string())
+            .state(state.to_string())
+            .web_url(format!("https://gitlab.com/jdoe/gitlapi/-/issues/{}", id))
+            .labels(vec!["bug".to_string()])
+            .created_at("2024-03-01T10:00:00Z".to_string())
+            .updated_at("2024-03-02T10:00:00Z".to_string())
+            .build()
+            .unwrap()
+    }
+
+    impl Issue for IssueMock {
+        fn open(&self, args: IssueBodyArgs) -> Result<IssueResponse> {
+            *self.opened.lock().unwrap() = Some(args);
+            Ok(issue(7, "opened"))
+        }
+        fn list(&self, _args: IssueListBodyArgs) -> Result<Vec<IssueResponse>> {
+            Ok(vec![issue(7, "opened")])
+        }
+        fn get(&self, id: i64) -> Result<IssueResponse> {
+            Ok(issue(id, "opened"))
+        }
+        fn close(&self, id: i64) -> Result<IssueResponse> {
+            Ok(issue(id, "closed"))
+        }
+        fn comment(&self, id: i64, comment: &str) -> Result<()> {
+            *self.comment.lock().unwrap() = Some((id, comment.to_string()));
+            Ok(())
+        }
+        fn num_pages(&self, _args: IssueListBodyArgs) -> Result<Option<PageCount>> {
+            Ok(Some(PageCount::from(1)))
+        }
+    }
+
+    #[test]
+    fn test_create_issue() {
+        let remote = Arc::new(IssueMock::default());
+        let cli_args = IssueCreateCliArgs::builder()
+            .title("Crash on startup".to_string())
+            .labels(vec!["bug".to_string()])
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        create(remote.clone(), cli_args, &mut writer).unwrap();
+        let opened = remote.opened.lock().unwrap().clone().unwrap();
+        assert_eq!("Crash on startup", opened.title);
+        assert_eq!(vec!["bug".to_string()], opened.labels);
+        assert_eq!(
+            "Issue opened: https://gitlab.com/jdoe/gitlapi/-/issues/7\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list_issues() {
+        let remote = Arc::new(IssueMock::default());
+        let body_args = IssueListBodyArgs::builder()
+            .state(IssueState::Opened)
+            .list_args(None)
+            .build()
+            .unwrap();
+        let cli_args = IssueListCliArgs::builder()
+            .state(IssueState::Opened)
+            .list_args(ListRemoteCliArgs::builder().build().unwrap())
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        common::list_issues(remote, body_args, cli_args, &mut writer).unwrap();
+        assert_eq!(
+            "ID|Title|Author|State|URL|Updated at\n\
+             7|Crash on startup|jdoe|opened|https://gitlab.com/jdoe/gitlapi/-/issues/7|2024-03-02T10:00:00Z\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_issue_displays_optional_fields() {
+        let remote = Arc::new(IssueMock::default());
+        let cli_args = IssueGetCliArgs::builder()
+            .id(7)
+            .get_args(
+                GetRemoteCliArgs::builder()
+                    .display_optional(true)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        get(remote, cli_args, &mut writer).unwrap();
+        assert_eq!(
+            "ID|Title|Description|Author|State|Labels|URL|Updated at\n\
+             7|Crash on startup||jdoe|opened|bug|https://gitlab.com/jdoe/gitlapi/-/issues/7|2024-03-02T10:00:00Z\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_close_issue() {
+        let remote = Arc::new(IssueMock::default());
+        let mut writer = Vec::new();
+        close(remote, 7, &mut writer).unwrap();
+        assert_eq!(
+            "Issue closed: https://gitlab.com/jdoe/gitlapi/-/issues/7\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_comment_issue_from_reader() {
+        let remote = Arc::new(IssueMock::default());
+        let cli_args = IssueCommentCliArgs::builder()
+            .id(7)
+            .comment_from_file(Some("-".to_string()))
+            .build()
+            .unwrap();
+        let reader = Cursor::new("Cannot reproduce on main");
+        comment(remote.clone(), cli_args, Some(reader)).unwrap();
+        assert_eq!(
+            Some((7, "Cannot reproduce on main".to_string())),
+            *remote.comment.lock().unwrap()
+        );
+    }
+}
@@ -1,21 +1,38 @@
-use crate::api_traits::{CommentMergeRequest, MergeRequest, RemoteProject};
-use crate::cli::merge_request::MergeRequestOptions;
+use crate::api_traits::{
+    CommentMergeRequest, MergeQueue, MergeRequest, MergeRequestApprovals, MergeRequestChecks,
+    MergeRequestDiff, MergeRequestLabels, MergeRequestRebase, MergeRequestReviewDiff,
+    RemoteProject, Timestamp, UserInfo,
+};
+use crate::checkpoint::Checkpoint;
+use crate::cli::merge_request::{MergeQueueOptions, MergeRequestLabelOptions, MergeRequestOptions};
 use crate::config::{Config, ConfigProperties};
+use crate::display::{Column, DisplayBody};
 use crate::error::{AddContext, GRError};
-use crate::git::Repo;
+use crate::exec::{Step, StepProgress};
+use crate::git::{Divergence, Repo, UpdateStrategy};
+use crate::i18n::{self, Message};
 use crate::io::{CmdInfo, Response, TaskRunner};
+use crate::recent::RecentMembers;
 use crate::remote::{
-    GetRemoteCliArgs, ListRemoteCliArgs, Member, MergeRequestBodyArgs, MergeRequestListBodyArgs,
-    MergeRequestState, Project,
+    GetRemoteCliArgs, ListRemoteCliArgs, Member, MergeRequestBodyArgs, MergeRequestLimits,
+    MergeRequestListBodyArgs, MergeRequestListBodyArgsBuilder, MergeRequestState, Milestone,
+    Project,
 };
-use crate::shell::Shell;
-use crate::{dialog, display, exec, git, remote, Cmd, Result};
+use crate::shell::{self, Shell};
+use crate::time::Milliseconds;
+use crate::{api_defaults, cancel, dialog, display, exec, git, remote, Result};
 use std::{
+    fmt::Display,
     fs::File,
-    io::{BufRead, BufReader, Cursor, Write},
-    sync::Arc,
+    io::{BufRead, BufReader, Cursor, IsTerminal, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
+use super::cicd::{self, PipelineWaitCliArgs};
 use super::common;
 
 #[derive(Builder, Clone)]
@@ -24,6 +41,9 @@ pub struct MergeRequestCliArgs {
     pub title_from_commit: Option<String>,
     pub description: Option<String>,
     pub description_from_file: Option<String>,
+    /// Name of the repository template to prefill the description with.
+    #[builder(default)]
+    pub template: Option<String>,
     pub target_branch: Option<String>,
     pub auto: bool,
     pub refresh_cache: bool,
@@ -31,6 +51,39 @@ pub struct MergeRequestCliArgs {
     pub accept_summary: bool,
     pub commit: Option<String>,
     pub draft: bool,
+    /// Overrides the update strategy set in the configuration.
+    #[builder(default)]
+    pub update_strategy: Option<UpdateStrategy>,
+    /// Sent along with the ones in the configuration.
+    #[builder(default)]
+    pub push_options: Vec<String>,
+    #[builder(default)]
+    pub force_with_lease: bool,
+    /// Wait for the pipeline of the pushed commit once the merge request is
+    /// open.
+    #[builder(default)]
+    pub wait: bool,
+    /// GPG sign the commit created with `commit`. It is also signed when
+    /// commit.gpgsign is set in the git configuration.
+    #[builder(default)]
+    pub gpg_sign: bool,
+    /// Usernames to assign, on top of the members of `assignee_groups`.
+    #[builder(default)]
+    pub assignees: Vec<String>,
+    #[builder(default)]
+    pub assignee_groups: Vec<String>,
+    /// Usernames to request a review from.
+    #[builder(default)]
+    pub reviewers: Vec<String>,
+    /// Project to open the merge request against, as owner/repo, when the
+    /// branch is pushed to a fork.
+    #[builder(default)]
+    pub upstream: Option<String>,
+    /// Title of the milestone to add the merge request to.
+    #[builder(default)]
+    pub milestone: Option<String>,
+    #[builder(default)]
+    pub labels: Vec<String>,
 }
 
 impl MergeRequestCliArgs {
@@ -42,6 +95,13 @@ impl MergeRequestCliArgs {
 pub struct MergeRequestListCliArgs {
     pub state: MergeRequestState,
     pub list_args: ListRemoteCliArgs,
+    pub author: Option<String>,
+    /// Title of the milestone the merge requests belong to.
+    pub milestone: Option<String>,
+    /// Opened by the authenticated user.
+    pub mine: bool,
+    /// Ready for the review of the authenticated user.
+    pub needs_my_review: bool,
 }
 
 impl MergeRequestListCliArgs {
@@ -49,8 +109,32 @@ impl MergeRequestListCliArgs {
         MergeRequestListCliArgs {
             state,
             list_args: args,
+            author: None,
+            milestone: None,
+            mine: false,
+            needs_my_review: false,
         }
     }
+
+    pub fn with_author(mut self, author: Option<String>) -> Self {
+        self.author = author;
+        self
+    }
+
+    pub fn with_milestone(mut self, milestone: Option<String>) -> Self {
+        self.milestone = milestone;
+        self
+    }
+
+    pub fn with_mine(mut self, mine: bool) -> Self {
+        self.mine = mine;
+        self
+    }
+
+    pub fn with_needs_my_review(mut self, needs_my_review: bool) -> Self {
+        self.needs_my_review = needs_my_review;
+        self
+    }
 }
 
 #[derive(Builder)]
@@ -78,6 +162,28 @@ impl CommentMergeRequestCliArgs {
     }
 }
 
+#[derive(Builder)]
+pub struct MergeRequestSweepCliArgs {
+    /// Open merge requests with this label are swept.
+    pub label: String,
+    pub comment: Option<String>,
+    pub close: bool,
+    /// Report what would be done without doing it.
+    pub dry_run: bool,
+    /// Time to wait between merge requests.
+    #[builder(default)]
+    pub throttle_time: Option<Milliseconds>,
+    /// Skip the merge requests swept by a previous interrupted run.
+    #[builder(default)]
+    pub resume: bool,
+}
+
+impl MergeRequestSweepCliArgs {
+    pub fn builder() -> MergeRequestSweepCliArgsBuilder {
+        MergeRequestSweepCliArgsBuilder::default()
+    }
+}
+
 #[derive(Builder)]
 pub struct CommentMergeRequestBodyArgs {
     pub id: i64,
@@ -90,6 +196,292 @@ impl CommentMergeRequestBodyArgs {
     }
 }
 
+#[derive(Builder)]
+pub struct MergeQueueListCliArgs {
+    /// Target branch of the queue. Defaults to the project's default branch.
+    pub branch: Option<String>,
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl MergeQueueListCliArgs {
+    pub fn builder() -> MergeQueueListCliArgsBuilder {
+        MergeQueueListCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct MergeQueueGetCliArgs {
+    pub id: i64,
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl MergeQueueGetCliArgs {
+    pub fn builder() -> MergeQueueGetCliArgsBuilder {
+        MergeQueueGetCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct MergeQueueListBodyArgs {
+    pub branch: String,
+}
+
+impl MergeQueueListBodyArgs {
+    pub fn builder() -> MergeQueueListBodyArgsBuilder {
+        MergeQueueListBodyArgsBuilder::default()
+    }
+}
+
+/// A merge request waiting in a Github merge queue or a Gitlab merge train.
+#[derive(Builder, Clone, Debug)]
+pub struct MergeQueueEntry {
+    /// Position in the queue, starting at 1 for the next one to be merged. It
+    /// is 0 when the merge request has already left the queue.
+    pub position: i64,
+    pub id: i64,
+    pub title: String,
+    pub target_branch: String,
+    pub status: String,
+    pub enqueued_by: String,
+    pub enqueued_at: String,
+    pub web_url: String,
+}
+
+impl MergeQueueEntry {
+    pub fn builder() -> MergeQueueEntryBuilder {
+        MergeQueueEntryBuilder::default()
+    }
+}
+
+impl From<MergeQueueEntry> for DisplayBody {
+    fn from(entry: MergeQueueEntry) -> Self {
+        DisplayBody::new(vec![
+            Column::new("Position", entry.position.to_string()),
+            Column::new("ID", entry.id.to_string()),
+            Column::new("Title", entry.title),
+            Column::new("Branch", entry.target_branch),
+            Column::new("Status", entry.status),
+            Column::new("Enqueued by", entry.enqueued_by),
+            Column::new("Enqueued at", entry.enqueued_at),
+            Column::new("URL", entry.web_url),
+        ])
+    }
+}
+
+impl Timestamp for MergeQueueEntry {
+    fn created_at(&self) -> String {
+        self.enqueued_at.clone()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CheckState {
+    Pass,
+    Fail,
+    Pending,
+    /// Finished without blocking the merge. Skipped, neutral or allowed to
+    /// fail.
+    Neutral,
+}
+
+impl Display for CheckState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckState::Pass => write!(f, "pass"),
+            CheckState::Fail => write!(f, "fail"),
+            CheckState::Pending => write!(f, "pending"),
+            CheckState::Neutral => write!(f, "neutral"),
+        }
+    }
+}
+
+/// A gate reported on the head commit of a merge request. Github check runs
+/// and commit statuses, Gitlab pipeline jobs and external commit statuses.
+#[derive(Builder, Clone, Debug)]
+pub struct MergeRequestCheck {
+    pub id: i64,
+    pub name: String,
+    pub kind: String,
+    pub state: CheckState,
+    pub web_url: String,
+}
+
+impl MergeRequestCheck {
+    pub fn builder() -> MergeRequestCheckBuilder {
+        MergeRequestCheckBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct MergeRequestComment {
+    pub id: i64,
+    pub author: String,
+    pub created_at: String,
+    pub body: String,
+}
+
+impl MergeRequestComment {
+    pub fn builder() -> MergeRequestCommentBuilder {
+        MergeRequestCommentBuilder::default()
+    }
+}
+
+/// A comment and its replies. Gitlab discussions, Github review comments
+/// grouped by the comment they reply to and Github issue comments, which
+/// have no replies.
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct CommentThread {
+    pub id: String,
+    /// File and line the thread is attached to in the diff, ex.
+    /// `src/main.rs:12`.
+    #[builder(default)]
+    pub position: Option<String>,
+    /// None if the thread cannot be resolved. Github only tracks resolution
+    /// in its GraphQL API.
+    #[builder(default)]
+    pub resolved: Option<bool>,
+    pub comments: Vec<MergeRequestComment>,
+}
+
+impl CommentThread {
+    pub fn builder() -> CommentThreadBuilder {
+        CommentThreadBuilder::default()
+    }
+}
+
+impl From<CommentThread> for DisplayBody {
+    fn from(thread: CommentThread) -> Self {
+        let resolved = match thread.resolved {
+            Some(resolved) => resolved.to_string(),
+            None => "-".to_string(),
+        };
+        let author = thread
+            .comments
+            .first()
+            .map(|comment| comment.author.clone())
+            .unwrap_or_default();
+        DisplayBody::new(vec![
+            Column::new("ID", thread.id.clone()),
+            Column::new("Author", author),
+            Column::new("Comments", thread.comments.len().to_string()),
+            Column::new("Resolved", resolved),
+            Column::new("Position", thread.position.clone().unwrap_or_default()),
+            Column::new("Created at", thread.created_at()),
+        ])
+    }
+}
+
+impl Timestamp for CommentThread {
+    fn created_at(&self) -> String {
+        self.comments
+            .first()
+            .map(|comment| comment.created_at.clone())
+            .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
+    }
+}
+
+impl From<MergeRequestCheck> for DisplayBody {
+    fn from(check: MergeRequestCheck) -> Self {
+        DisplayBody::new(vec![
+            Column::new("Name", check.name),
+            Column::new("Kind", check.kind),
+            Column::new("State", check.state.to_string()),
+            Column::new("URL", check.web_url),
+        ])
+    }
+}
+
+impl Timestamp for MergeRequestCheck {
+    fn created_at(&self) -> String {
+        // Checks are listed in the order reported by the remote. Set it to
+        // UNIX epoch.
+        "1970-01-01T00:00:00Z".to_string()
+    }
+}
+
+/// Approvals given to a merge request and the ones still expected.
+#[derive(Builder, Clone, Debug, Default)]
+pub struct ApprovalStatus {
+    pub approved: bool,
+    /// Approvals still needed to merge. `None` when the remote does not tell,
+    /// as Github requires admin access to read the branch protection rules.
+    #[builder(default)]
+    pub approvals_left: Option<i64>,
+    #[builder(default)]
+    pub approved_by: Vec<String>,
+    /// Eligible approvers or requested reviewers that have not approved yet.
+    #[builder(default)]
+    pub pending: Vec<String>,
+}
+
+impl ApprovalStatus {
+    pub fn builder() -> ApprovalStatusBuilder {
+        ApprovalStatusBuilder::default()
+    }
+}
+
+impl From<ApprovalStatus> for DisplayBody {
+    fn from(status: ApprovalStatus) -> Self {
+        DisplayBody::new(vec![
+            Column::new("Approved", status.approved.to_string()),
+            Column::new(
+                "Approvals left",
+                status
+                    .approvals_left
+                    .map(|left| left.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Column::new("Approved by", status.approved_by.join(", ")),
+            Column::new("Pending", status.pending.join(", ")),
+        ])
+    }
+}
+
+/// Head commit of a merge request and the one it had when a user last
+/// reviewed it.
+#[derive(Builder, Clone, Debug, Default)]
+pub struct ReviewedHead {
+    pub head_sha: String,
+    /// `None` if the user has not reviewed the merge request yet.
+    #[builder(default)]
+    pub reviewed_sha: Option<String>,
+}
+
+impl ReviewedHead {
+    pub fn builder() -> ReviewedHeadBuilder {
+        ReviewedHeadBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct MergeRequestRebaseCliArgs {
+    pub id: i64,
+    /// Seconds to wait for the remote to finish the rebase.
+    #[builder(default = "300")]
+    pub timeout: u64,
+    #[builder(default = "Milliseconds::new(5000)")]
+    pub poll_interval: Milliseconds,
+}
+
+impl MergeRequestRebaseCliArgs {
+    pub fn builder() -> MergeRequestRebaseCliArgsBuilder {
+        MergeRequestRebaseCliArgsBuilder::default()
+    }
+}
+
+/// State of a rebase carried out by the remote.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RebaseProgress {
+    InProgress,
+    /// The source branch has been rebased onto `sha`. The pipeline is the one
+    /// for the new head commit, if it has been created already.
+    Done {
+        sha: String,
+        pipeline_url: Option<String>,
+    },
+    Failed(String),
+}
+
 pub fn execute(
     options: MergeRequestOptions,
     config: Arc<Config>,
@@ -97,48 +489,165 @@ pub fn execute(
     path: String,
 ) -> Result<()> {
     match options {
-        MergeRequestOptions::Create(cli_args) => {
+        MergeRequestOptions::Create(mut cli_args) => {
             let mr_remote = remote::get_mr(
                 domain.clone(),
                 path.clone(),
                 config.clone(),
                 cli_args.refresh_cache,
             )?;
+            if cli_args.upstream.is_none() {
+                cli_args.upstream = upstream_remote_path(&Shell, &domain, &path);
+            }
+            if let Some(name) = &cli_args.template {
+                let root = git::toplevel(&Shell)?;
+                cli_args.description = Some(description_template(Path::new(&root), name)?);
+            }
+            // Pipelines run for the pushed branch in origin.
+            let cicd_remote = cli_args
+                .wait
+                .then(|| remote::get_cicd(domain.clone(), path.clone(), config.clone(), true))
+                .transpose()?;
+            // Target branch, members and project id come from the project the
+            // merge request is opened against.
+            let project_path = cli_args.upstream.clone().unwrap_or(path);
+            // Resolved before pushing, so a mistyped title fails early.
+            let milestone = match &cli_args.milestone {
+                Some(title) => Some(
+                    remote::get_project_milestones(
+                        domain.clone(),
+                        project_path.clone(),
+                        config.clone(),
+                        cli_args.refresh_cache,
+                    )?
+                    .get(title)?,
+                ),
+                None => None,
+            };
+            let recent = RecentMembers::new(
+                config.cache_location(),
+                &format!("{}/{}", domain, project_path),
+            );
+            let members_remote = remote::get_cached_project(
+                domain.clone(),
+                project_path.clone(),
+                config.clone(),
+                cli_args.refresh_cache,
+            )?;
             let project_remote =
-                remote::get_project(domain, path, config.clone(), cli_args.refresh_cache)?;
+                remote::get_project(domain, project_path, config.clone(), cli_args.refresh_cache)?;
+            let refresh_remote = project_remote.clone();
             if let Some(commit_message) = &cli_args.commit {
                 git::add(&Shell)?;
-                git::commit(&Shell, commit_message)?;
+                if cli_args.gpg_sign || git::gpg_sign_enabled(&Shell) {
+                    git::signed_commit(&Shell, commit_message)?;
+                } else {
+                    git::commit(&Shell, commit_message)?;
+                }
             }
             let cmds = if let Some(description_file) = &cli_args.description_from_file {
                 let reader = get_reader_file_cli(description_file)?;
-                cmds(project_remote, &cli_args, Arc::new(Shell), Some(reader))
+                cmds(
+                    project_remote,
+                    members_remote,
+                    &cli_args,
+                    Arc::new(Shell),
+                    Some(reader),
+                )
             } else {
                 cmds(
                     project_remote,
+                    members_remote,
                     &cli_args,
                     Arc::new(Shell),
                     None::<Cursor<&str>>,
                 )
             };
-            let mr_body = get_repo_project_info(cmds)?;
-            open(mr_remote, config, mr_body, &cli_args)
+            let progress = std::io::stderr();
+            let terminal = progress.is_terminal();
+            let mut mr_body = get_repo_project_info(cmds, Some(progress), terminal)?;
+            mr_body.milestone = milestone;
+            // Members may have come from an expired cache. Bring it up to
+            // date for the next run while the user goes through the prompts.
+            let refresh_members = (!cli_args.refresh_cache)
+                .then(|| std::thread::spawn(move || refresh_remote.get_project_members()));
+            let result = open(mr_remote, config, mr_body, &cli_args, recent);
+            if let Some(refresh_members) = refresh_members {
+                let _ = refresh_members.join();
+            }
+            match (result?, cicd_remote) {
+                (true, Some(cicd_remote)) => {
+                    let cli_args = PipelineWaitCliArgs::builder().build()?;
+                    let head_sha = git::head_sha(&Shell)?;
+                    cicd::wait_pipeline(cicd_remote, cli_args, Some(head_sha), std::io::stdout())
+                }
+                _ => Ok(()),
+            }
         }
         MergeRequestOptions::List(cli_args) => {
             list_merge_requests(domain, path, config, cli_args, None)
         }
+        MergeRequestOptions::Sweep(cli_args) => {
+            // Fresh list, so merge requests closed in a previous run are not
+            // swept again.
+            let remote = remote::get_mr(domain.clone(), path.clone(), config.clone(), true)?;
+            let checkpoint = if cli_args.dry_run {
+                Checkpoint::disabled()
+            } else {
+                Checkpoint::new(
+                    config.cache_location(),
+                    &format!("mr sweep {}/{} {}", domain, path, cli_args.label),
+                    cli_args.resume,
+                )?
+            };
+            let comment_remote = remote::get_comment_mr(domain, path, config, false)?;
+            sweep(
+                remote,
+                comment_remote,
+                cli_args,
+                checkpoint,
+                std::io::stdout(),
+            )
+        }
         MergeRequestOptions::Merge { id } => {
-            let remote = remote::get_mr(domain, path, config, false)?;
-            merge(remote, id)
+            let remote = remote::get_mr(domain.clone(), path.clone(), config.clone(), false)?;
+            let id = merge_request_id(remote.clone(), id)?;
+            // Always fetch fresh requirements. Stale ones could let a merge
+            // through or block it for no reason.
+            let checks = remote::get_mr_checks(domain.clone(), path.clone(), config.clone(), true)?;
+            let user = remote::get_auth_user(domain, path, config.clone(), false)?;
+            self_review_guard(
+                remote.clone(),
+                user,
+                id,
+                "merge",
+                config.block_self_review(),
+                std::io::stdout(),
+            )?;
+            merge(remote, checks, id, std::io::stdout())
         }
         MergeRequestOptions::Checkout { id } => {
             let remote = remote::get_mr(domain, path, config, false)?;
+            let id = merge_request_id(remote.clone(), id)?;
             checkout(remote, id)
         }
         MergeRequestOptions::Close { id } => {
             let remote = remote::get_mr(domain, path, config, false)?;
+            let id = merge_request_id(remote.clone(), id)?;
             close(remote, id)
         }
+        MergeRequestOptions::Reopen { id } => {
+            let remote = remote::get_mr(domain, path, config, false)?;
+            reopen(remote, id, std::io::stdout())
+        }
+        MergeRequestOptions::Draft { id } => {
+            let remote = remote::get_mr(domain, path, config, false)?;
+            set_draft(remote, id, true, std::io::stdout())
+        }
+        MergeRequestOptions::Ready { id } => {
+            let remote = remote::get_mr(domain, path, config, false)?;
+            set_draft(remote, id, false, std::io::stdout())
+        }
         MergeRequestOptions::Comment(cli_args) => {
             let remote = remote::get_comment_mr(domain, path, config, false)?;
             if let Some(comment_file) = &cli_args.comment_from_file {
@@ -148,17 +657,145 @@ pub fn execute(
                 create_comment(remote, cli_args, None::<Cursor<&str>>)
             }
         }
+        MergeRequestOptions::ListComments { id } => {
+            // Always fetched, so comments added since the last run show up.
+            let remote = remote::get_comment_mr(domain, path, config, true)?;
+            list_comments(remote, id, std::io::stdout())
+        }
         MergeRequestOptions::Get(cli_args) => {
             let remote = remote::get_mr(domain, path, config, cli_args.get_args.refresh_cache)?;
-            get_merge_request_details(remote, cli_args, std::io::stdout())
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                get_merge_request_details(remote, cli_args, writer)
+            })
         }
-        MergeRequestOptions::Approve { id } => {
-            let remote = remote::get_mr(domain, path, config, false)?;
-            approve(remote, id, std::io::stdout())
+        MergeRequestOptions::Approve { id, checklist_ok } => {
+            let remote = remote::get_mr(domain.clone(), path.clone(), config.clone(), false)?;
+            let id = merge_request_id(remote.clone(), id)?;
+            let user = remote::get_auth_user(domain, path, config.clone(), false)?;
+            self_review_guard(
+                remote.clone(),
+                user,
+                id,
+                "approve",
+                config.block_self_review(),
+                std::io::stdout(),
+            )?;
+            let checklist = config.review_checklist();
+            if checklist.is_empty() {
+                return approve(remote, id, None, std::io::stdout());
+            }
+            confirm_checklist(checklist, checklist_ok, std::io::stdin().is_terminal())?;
+            // The confirmed checklist goes along with the approval, so the
+            // review can be audited later.
+            let comment = checklist_comment(checklist);
+            approve(remote, id, Some(&comment), std::io::stdout())
+        }
+        MergeRequestOptions::Checks(cli_args) => {
+            let remote =
+                remote::get_mr_checks(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_checks(remote, cli_args, writer)
+            })
+        }
+        MergeRequestOptions::Approvers(cli_args) => {
+            let remote =
+                remote::get_mr_approvals(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_approvers(remote, cli_args, writer)
+            })
+        }
+        MergeRequestOptions::Diff { id, no_pager } => {
+            let remote = remote::get_mr_diff(domain, path, config, false)?;
+            if no_pager || !std::io::stdout().is_terminal() {
+                return show_diff(remote, id, std::io::stdout());
+            }
+            let mut diff = Vec::new();
+            show_diff(remote, id, &mut diff)?;
+            shell::page(&String::from_utf8_lossy(&diff))
+        }
+        MergeRequestOptions::ChangedSinceReview { id, no_pager } => {
+            // Reviews and pushes since the last run have to show up.
+            let remote =
+                remote::get_mr_review_diff(domain.clone(), path.clone(), config.clone(), true)?;
+            let user = remote::get_auth_user(domain, path, config, false)?;
+            if no_pager || !std::io::stdout().is_terminal() {
+                return show_changed_since_review(remote, user, id, std::io::stdout());
+            }
+            let mut diff = Vec::new();
+            show_changed_since_review(remote, user, id, &mut diff)?;
+            shell::page(&String::from_utf8_lossy(&diff))
+        }
+        MergeRequestOptions::Rebase(cli_args) => {
+            // Polling has to reach the remote every time.
+            let remote = remote::get_mr_rebase(domain, path, config, true)?;
+            rebase(remote, cli_args, std::io::stdout())
+        }
+        MergeRequestOptions::Queue(options) => match options {
+            MergeQueueOptions::List(cli_args) => {
+                let branch = match cli_args.branch.clone() {
+                    Some(branch) => branch,
+                    None => {
+                        let project = remote::get_project(
+                            domain.clone(),
+                            path.clone(),
+                            config.clone(),
+                            cli_args.get_args.refresh_cache,
+                        )?;
+                        default_branch(project)?
+                    }
+                };
+                let remote =
+                    remote::get_merge_queue(domain, path, config, cli_args.get_args.refresh_cache)?;
+                let body_args = MergeQueueListBodyArgs::builder().branch(branch).build()?;
+                let output = cli_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    list_merge_queue(remote, body_args, cli_args, writer)
+                })
+            }
+            MergeQueueOptions::Get(cli_args) => {
+                let remote =
+                    remote::get_merge_queue(domain, path, config, cli_args.get_args.refresh_cache)?;
+                let output = cli_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    get_merge_queue_entry(remote, cli_args, writer)
+                })
+            }
+            MergeQueueOptions::Add { id } => {
+                let remote = remote::get_merge_queue(domain, path, config, false)?;
+                enqueue(remote, id, std::io::stdout())
+            }
+            MergeQueueOptions::Remove { id } => {
+                let remote = remote::get_merge_queue(domain, path, config, false)?;
+                dequeue(remote, id, std::io::stdout())
+            }
+        },
+        MergeRequestOptions::Label(options) => {
+            let remote = remote::get_mr_labels(domain, path, config, false)?;
+            update_labels(remote, options, std::io::stdout())
         }
     }
 }
 
+/// Turns `--mine` and `--needs-my-review` into the filters they stand for.
+fn smart_filters(
+    body_args: &mut MergeRequestListBodyArgsBuilder,
+    cli_args: &MergeRequestListCliArgs,
+    user: Member,
+) {
+    if cli_args.mine {
+        body_args.author(Some(user.username));
+    } else if cli_args.needs_my_review {
+        body_args
+            .reviewer(Some(user))
+            .in_project(true)
+            .draft(Some(false))
+            .passing_ci(true);
+    }
+}
+
 pub fn get_reader_file_cli(file_path: &str) -> Result<Box<dyn BufRead + Send + Sync>> {
     if file_path == "-" {
         Ok(Box::new(BufReader::new(std::io::stdin())))
@@ -175,25 +812,51 @@ pub fn list_merge_requests(
     domain: String,
     path: String,
     config: Arc<Config>,
-    cli_args: MergeRequestListCliArgs,
+    mut cli_args: MergeRequestListCliArgs,
     assignee_id: Option<i64>,
 ) -> Result<()> {
-    let remote = remote::get_mr(
-        domain,
-        path,
-        config,
-        cli_args.list_args.get_args.refresh_cache,
-    )?;
+    cli_args.list_args.get_args = cli_args
+        .list_args
+        .get_args
+        .with_default_columns(config.columns("merge_request"));
+    let refresh_cache = cli_args.list_args.get_args.refresh_cache;
+    let milestone = match &cli_args.milestone {
+        Some(title) => Some(
+            remote::get_project_milestones(
+                domain.clone(),
+                path.clone(),
+                config.clone(),
+                refresh_cache,
+            )?
+            .get(title)?,
+        ),
+        None => None,
+    };
+    let user = if cli_args.mine || cli_args.needs_my_review {
+        Some(remote::get_auth_user(domain.clone(), path.clone(), config.clone(), false)?.get()?)
+    } else {
+        None
+    };
+    let remote = remote::get_mr(domain, path, config, refresh_cache)?;
     let from_to_args = remote::validate_from_to_page(&cli_args.list_args)?;
-    let body_args = MergeRequestListBodyArgs::builder()
+    let mut body_args = MergeRequestListBodyArgs::builder();
+    body_args
         .list_args(from_to_args)
         .state(cli_args.state)
         .assignee_id(assignee_id)
-        .build()?;
+        .author(cli_args.author.clone())
+        .milestone(milestone);
+    if let Some(user) = user {
+        smart_filters(&mut body_args, &cli_args, user);
+    }
+    let body_args = body_args.build()?;
     if cli_args.list_args.num_pages {
         return common::num_merge_request_pages(remote, body_args, std::io::stdout());
     }
-    list(remote, body_args, cli_args, std::io::stdout())
+    let output = cli_args.list_args.get_args.output.clone();
+    common::write_output(output.as_deref(), |writer| {
+        list(remote, body_args, cli_args, writer)
+    })
 }
 
 fn user_prompt_confirmation(
@@ -202,53 +865,216 @@ fn user_prompt_confirmation(
     description: String,
     target_branch: &String,
     cli_args: &MergeRequestCliArgs,
+    recent: &mut RecentMembers,
 ) -> Result<MergeRequestBodyArgs> {
     let mut title = mr_body.repo.title().to_string();
     if cli_args.draft {
-        title = format!("DRAFT: {}", title);
+        title = draft_title(&title);
+    }
+    let mut assignees = requested_assignees(config.as_ref(), cli_args)?;
+    if assignees.is_empty() {
+        assignees.push(config.preferred_assignee_username().to_string());
     }
+    let reviewers = resolve_members(&mr_body.members, &cli_args.reviewers, "reviewer")?;
     let user_input = if cli_args.auto {
-        let preferred_assignee_members = mr_body
-            .members
-            .iter()
-            .filter(|member| member.username == config.preferred_assignee_username())
-            .collect::<Vec<&Member>>();
-        if preferred_assignee_members.len() != 1 {
-            return Err(GRError::PreconditionNotMet(
-                "Cannot get preferred assignee user id".to_string(),
-            )
-            .into());
-        }
-        dialog::MergeRequestUserInput::new(
+        let assignees = resolve_members(&mr_body.members, &assignees, "assignee")?;
+        dialog::MergeRequestUserInput::new(&title, &description, assignees)
+            .with_reviewers(reviewers)
+    } else {
+        dialog::prompt_user_merge_request_info(
             &title,
             &description,
-            preferred_assignee_members[0].id,
-            &preferred_assignee_members[0].username,
-        )
-    } else {
-        dialog::prompt_user_merge_request_info(&title, &description, &mr_body.members, config)?
+            &recent.rank(&mr_body.members),
+            &assignees,
+            &cli_args.reviewers,
+        )?
     };
+    let picked = user_input
+        .assignees
+        .iter()
+        .chain(user_input.reviewers.iter())
+        .map(|member| member.username.clone())
+        .collect::<Vec<String>>();
+    // Losing track of recent members is no reason to stop.
+    let _ = recent.record(&picked);
+    let reviewers = user_input.reviewers;
 
     Ok(MergeRequestBodyArgs::builder()
         .title(user_input.title)
         .description(user_input.description)
         .source_branch(mr_body.repo.current_branch().to_string())
         .target_branch(target_branch.to_string())
-        .assignee_id(user_input.user_id.to_string())
-        .username(user_input.username)
+        .assignee_ids(user_input.assignees.iter().map(|a| a.id).collect())
+        .assignee_usernames(
+            user_input
+                .assignees
+                .into_iter()
+                .map(|a| a.username)
+                .collect(),
+        )
+        .reviewer_ids(reviewers.iter().map(|r| r.id).collect())
+        .reviewer_usernames(reviewers.into_iter().map(|r| r.username).collect())
         // TODO make this configurable
         .remove_source_branch("true".to_string())
         .draft(cli_args.draft)
+        // The project data was gathered from upstream when opening from a fork.
+        .target_repo(cli_args.upstream.clone())
+        .target_project_id(cli_args.upstream.as_ref().map(|_| mr_body.project.id()))
+        .milestone_id(mr_body.milestone.as_ref().map(|milestone| milestone.id))
+        .labels(cli_args.labels.clone())
         .build()?)
 }
 
-/// Open a merge request.
+/// Gitlab marks a merge request as draft when its title starts with any of
+/// these prefixes, regardless of case.
+const DRAFT_PREFIXES: [&str; 3] = ["draft:", "[draft]", "(draft)"];
+
+fn is_draft_title(title: &str) -> bool {
+    draft_prefix_len(title).is_some()
+}
+
+fn draft_prefix_len(title: &str) -> Option<usize> {
+    let lowercase = title.to_lowercase();
+    DRAFT_PREFIXES
+        .iter()
+        .find(|prefix| lowercase.starts_with(*prefix))
+        .map(|prefix| prefix.len())
+}
+
+/// Title with the draft prefix, unless it has one already.
+pub fn draft_title(title: &str) -> String {
+    if is_draft_title(title) {
+        return title.to_string();
+    }
+    format!("DRAFT: {}", title)
+}
+
+/// Title without its draft prefix.
+pub fn ready_title(title: &str) -> String {
+    match draft_prefix_len(title) {
+        Some(len) => title[len..].trim_start().to_string(),
+        None => title.to_string(),
+    }
+}
+
+/// Checks the title and description against the remote's limits, so a merge
+/// request that would be rejected is caught before pushing the branch. Titles
+/// over the limit are an error, as only the user can decide how to shorten
+/// them. Descriptions are truncated. Returns whether the description was
+/// truncated.
+fn apply_limits(args: &mut MergeRequestBodyArgs, limits: MergeRequestLimits) -> Result<bool> {
+    let title_chars = args.title.chars().count();
+    if title_chars > limits.title {
+        return Err(GRError::PreconditionNotMet(format!(
+            "The title is {} characters long and the remote accepts up to {}. \
+             Shorten it in the prompt or amend the last commit's subject",
+            title_chars, limits.title
+        ))
+        .into());
+    }
+    if args.description.chars().count() <= limits.description {
+        return Ok(false);
+    }
+    args.description = args.description.chars().take(limits.description).collect();
+    Ok(true)
+}
+
+/// Usernames given with `--assignee` followed by the members of each
+/// `--assignee-group`, without duplicates.
+fn requested_assignees(
+    config: &impl ConfigProperties,
+    cli_args: &MergeRequestCliArgs,
+) -> Result<Vec<String>> {
+    let mut usernames: Vec<String> = Vec::new();
+    let mut add = |username: &String| {
+        if !usernames.contains(username) {
+            usernames.push(username.clone());
+        }
+    };
+    cli_args.assignees.iter().for_each(&mut add);
+    for group in &cli_args.assignee_groups {
+        let members = config.assignee_group(group).ok_or_else(|| {
+            GRError::PreconditionNotMet(format!(
+                "Assignee group {} is not configured. Set assignee_group_{} in the configuration",
+                group, group
+            ))
+        })?;
+        members.iter().for_each(&mut add);
+    }
+    Ok(usernames)
+}
+
+/// Project members matching `usernames`, in the same order. `role` names what
+/// the users are for in the error given when one of them is not a member.
+fn resolve_members(members: &[Member], usernames: &[String], role: &str) -> Result<Vec<Member>> {
+    usernames
+        .iter()
+        .map(|username| {
+            members
+                .iter()
+                .find(|member| &member.username == username)
+                .cloned()
+                .ok_or_else(|| {
+                    GRError::PreconditionNotMet(format!(
+                        "Cannot find {} {} among the project members",
+                        role, username
+                    ))
+                    .into()
+                })
+        })
+        .collect()
+}
+
+/// Past this number of commits behind the target branch, rebasing is not done
+/// without asking first.
+const BEHIND_COMMITS_WARN_THRESHOLD: u32 = 50;
+
+fn divergence_report(source_branch: &str, target_branch: &str, divergence: &Divergence) -> String {
+    let mut report = format!(
+        "Branch {} is {} commits ahead and {} commits behind origin/{}\n",
+        source_branch, divergence.ahead, divergence.behind, target_branch
+    );
+    if divergence.behind >= BEHIND_COMMITS_WARN_THRESHOLD {
+        report.push_str(&format!(
+            "Warning: {} is far behind origin/{}. Rebasing may run into conflicts.\n",
+            source_branch, target_branch
+        ));
+    }
+    report
+}
+
+/// Push options in the configuration are sent first, followed by the ones
+/// given in the command line.
+fn push_options(config_options: &[String], cli_options: &[String]) -> Vec<String> {
+    config_options
+        .iter()
+        .chain(cli_options.iter())
+        .cloned()
+        .collect()
+}
+
+fn update_branch(
+    runner: &impl TaskRunner,
+    strategy: UpdateStrategy,
+    target_branch: &str,
+) -> Result<()> {
+    match strategy {
+        UpdateStrategy::Rebase => git::rebase(runner, "origin", target_branch)?,
+        UpdateStrategy::Merge => git::merge(runner, "origin", target_branch)?,
+        UpdateStrategy::None => CmdInfo::Ignore,
+    };
+    Ok(())
+}
+
+/// Open a merge request. Returns whether it was opened, which it is not if
+/// the user turns down the summary.
 fn open(
     remote: Arc<dyn MergeRequest>,
     config: Arc<impl ConfigProperties>,
     mr_body: MergeRequestBody,
     cli_args: &MergeRequestCliArgs,
-) -> Result<()> {
+    mut recent: RecentMembers,
+) -> Result<bool> {
     let source_branch = &mr_body.repo.current_branch();
     let target_branch = cli_args.target_branch.clone();
     let target_branch = target_branch.unwrap_or(mr_body.project.default_branch().to_string());
@@ -261,10 +1087,47 @@ fn open(
     // make sure we are in a feature branch or bail
     in_feature_branch(source_branch, &target_branch)?;
 
+    let strategy = cli_args
+        .update_strategy
+        .unwrap_or(config.merge_request_update_strategy());
+    let push_options = push_options(config.merge_request_push_options(), &cli_args.push_options);
+
     // confirm title, description and assignee
-    let args = user_prompt_confirmation(&mr_body, config, description, &target_branch, cli_args)?;
+    let mut args = user_prompt_confirmation(
+        &mr_body,
+        config,
+        description,
+        &target_branch,
+        cli_args,
+        &mut recent,
+    )?;
+    let limits = remote.limits();
+    if apply_limits(&mut args, limits)? {
+        println!(
+            "{}",
+            i18n::format(Message::DescriptionTruncated, &[&limits.description])
+        );
+    }
 
-    git::rebase(&Shell, "origin", &target_branch)?;
+    let divergence = git::divergence(&Shell, "origin", &target_branch)?;
+    print!(
+        "{}",
+        divergence_report(source_branch, &target_branch, &divergence)
+    );
+    if divergence.behind > 0 && strategy != UpdateStrategy::None {
+        if divergence.behind < BEHIND_COMMITS_WARN_THRESHOLD
+            || dialog::confirm_branch_update(
+                &target_branch,
+                divergence.behind,
+                strategy,
+                cli_args.accept_summary,
+            )
+        {
+            update_branch(&Shell, strategy, &target_branch)?;
+        } else {
+            println!("{}", i18n::text(Message::SkipBranchUpdate));
+        }
+    }
 
     let outgoing_commits = git::outgoing_commits(&Shell, "origin", &target_branch)?;
 
@@ -279,27 +1142,100 @@ fn open(
     if let Ok(()) =
         dialog::show_summary_merge_request(&outgoing_commits, &args, cli_args.accept_summary)
     {
-        println!("\nTaking off... 🚀\n");
-        git::push(&Shell, "origin", &mr_body.repo)?;
+        let rocket = if display::ascii_output() { "" } else { " 🚀" };
+        println!("\n{}{}\n", i18n::text(Message::TakingOff), rocket);
+        git::push(
+            &Shell,
+            "origin",
+            &mr_body.repo,
+            &push_options,
+            cli_args.force_with_lease,
+        )?;
         let merge_request_response = remote.open(args)?;
-        println!("Merge request opened: {}", merge_request_response.web_url);
+        println!(
+            "{}",
+            i18n::format(
+                Message::MergeRequestOpened,
+                &[&merge_request_response.web_url]
+            )
+        );
         if cli_args.open_browser {
             open::that(merge_request_response.web_url)?;
         }
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Id of the merge request given or, without one, picked by the user among
+/// the open merge requests.
+fn merge_request_id(remote: Arc<dyn MergeRequest>, id: Option<i64>) -> Result<i64> {
+    if let Some(id) = id {
+        return Ok(id);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(GRError::PreconditionNotMet(
+            "Merge request id required, it can only be picked in a terminal".to_string(),
+        )
+        .into());
+    }
+    pick_merge_request(remote, dialog::fuzzy_select)
+}
+
+fn pick_merge_request(
+    remote: Arc<dyn MergeRequest>,
+    pick: impl FnOnce(&str, &[String]) -> Result<Option<usize>>,
+) -> Result<i64> {
+    let body_args = MergeRequestListBodyArgs::builder()
+        .state(MergeRequestState::Opened)
+        .list_args(None)
+        .assignee_id(None)
+        .build()?;
+    let merge_requests = remote.list(body_args)?;
+    if merge_requests.is_empty() {
+        return Err(GRError::PreconditionNotMet("No open merge requests".to_string()).into());
+    }
+    let items = merge_requests
+        .iter()
+        .map(|mr| {
+            format!(
+                "{} {} ({}, {})",
+                mr.id, mr.title, mr.author, mr.source_branch
+            )
+        })
+        .collect::<Vec<_>>();
+    match pick(&i18n::text(Message::PickMergeRequest), &items)? {
+        Some(index) => Ok(merge_requests[index].id),
+        None => Err(GRError::PreconditionNotMet(i18n::text(Message::UserCancelled)).into()),
+    }
+}
+
+/// Project path of the `upstream` git remote, if there is one on the same
+/// domain as origin and it is not origin itself.
+fn upstream_remote_path(
+    runner: &impl TaskRunner<Response = Response>,
+    domain: &str,
+    path: &str,
+) -> Option<String> {
+    match git::named_remote_url(runner, "upstream") {
+        Ok(CmdInfo::RemoteUrl {
+            domain: upstream_domain,
+            path: upstream_path,
+        }) if upstream_domain == domain && upstream_path != path => Some(upstream_path),
+        _ => None,
     }
-    Ok(())
 }
 
 /// Required commands to build a Project and a Repository
 fn cmds<R: BufRead + Send + Sync + 'static>(
     remote: Arc<dyn RemoteProject + Send + Sync + 'static>,
+    members_remote: Arc<dyn RemoteProject + Send + Sync + 'static>,
     cli_args: &MergeRequestCliArgs,
     task_runner: Arc<impl TaskRunner<Response = Response> + Send + Sync + 'static>,
     reader: Option<R>,
-) -> Vec<Cmd<CmdInfo>> {
-    let remote_cl = remote.clone();
-    let remote_project_cmd = move || -> Result<CmdInfo> { remote_cl.get_project_data(None) };
-    let remote_members_cmd = move || -> Result<CmdInfo> { remote.get_project_members() };
+) -> Vec<Step<CmdInfo>> {
+    let remote_project_cmd = move || -> Result<CmdInfo> { remote.get_project_data(None) };
+    let remote_members_cmd = move || -> Result<CmdInfo> { members_remote.get_project_members() };
     let status_runner = task_runner.clone();
     let git_status_cmd = || -> Result<CmdInfo> { git::status(status_runner) };
     let fetch_runner = task_runner.clone();
@@ -341,27 +1277,81 @@ fn cmds<R: BufRead + Send + Sync + 'static>(
             Ok(CmdInfo::CommitMessage(description.clone()))
         }
     };
-    let cmds: Vec<Cmd<CmdInfo>> = vec![
-        Box::new(remote_project_cmd),
-        Box::new(remote_members_cmd),
-        Box::new(git_status_cmd),
-        Box::new(git_fetch_cmd),
-        Box::new(git_title_cmd),
-        Box::new(git_current_branch),
-        Box::new(git_last_commit_message),
-    ];
-    cmds
+    vec![
+        Step::new("project", Box::new(remote_project_cmd)),
+        Step::new("members", Box::new(remote_members_cmd)),
+        Step::new("status", Box::new(git_status_cmd)),
+        Step::new("fetch", Box::new(git_fetch_cmd)),
+        Step::new("title", Box::new(git_title_cmd)),
+        Step::new("branch", Box::new(git_current_branch)),
+        Step::new("description", Box::new(git_last_commit_message)),
+    ]
 }
 
-// append description signature from the configuration
-fn build_description(description: &str, signature: &str) -> String {
-    if description.is_empty() && signature.is_empty() {
-        return "".to_string();
+/// Description templates in the repository at `root`, by name. Gitlab keeps
+/// them in .gitlab/merge_request_templates and Github in
+/// .github/PULL_REQUEST_TEMPLATE, or in a single PULL_REQUEST_TEMPLATE.md.
+fn description_templates(root: &Path) -> Vec<(String, PathBuf)> {
+    let mut templates = Vec::new();
+    for dir in [
+        ".gitlab/merge_request_templates",
+        ".github/PULL_REQUEST_TEMPLATE",
+    ] {
+        let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+            continue;
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if path.extension().is_some_and(|extension| extension == "md") {
+                let name = path.file_stem().unwrap().to_string_lossy().to_string();
+                templates.push((name, path));
+            }
+        }
     }
-    if description.is_empty() {
-        return signature.to_string();
+    for file in [
+        ".github/PULL_REQUEST_TEMPLATE.md",
+        ".github/pull_request_template.md",
+    ] {
+        let path = root.join(file);
+        if path.is_file() {
+            templates.push(("default".to_string(), path));
+            break;
+        }
     }
-    if signature.is_empty() {
+    templates.sort();
+    templates
+}
+
+/// Contents of the description template `name` in the repository at `root`.
+fn description_template(root: &Path, name: &str) -> Result<String> {
+    let templates = description_templates(root);
+    match templates.iter().find(|(template, _)| template == name) {
+        Some((_, path)) => Ok(std::fs::read_to_string(path)?.trim_end().to_string()),
+        None if templates.is_empty() => Err(GRError::PreconditionNotMet(
+            "No merge request templates found in the repository".to_string(),
+        )
+        .into()),
+        None => Err(GRError::PreconditionNotMet(format!(
+            "Merge request template {} not found. Available: {}",
+            name,
+            templates
+                .iter()
+                .map(|(template, _)| template.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .into()),
+    }
+}
+
+// append description signature from the configuration
+fn build_description(description: &str, signature: &str) -> String {
+    if description.is_empty() && signature.is_empty() {
+        return "".to_string();
+    }
+    if description.is_empty() {
+        return signature.to_string();
+    }
+    if signature.is_empty() {
         return description.to_string();
     }
     format!("{}\n\n{}", description, signature)
@@ -372,15 +1362,32 @@ struct MergeRequestBody {
     repo: Repo,
     project: Project,
     members: Vec<Member>,
+    #[builder(default)]
+    milestone: Option<Milestone>,
 }
 
-fn get_repo_project_info(cmds: Vec<Cmd<CmdInfo>>) -> Result<MergeRequestBody> {
+/// Runs the steps gathering the merge request data in parallel, showing their
+/// progress in `progress` if given, redrawn in place if it is a `terminal`.
+/// Fails on the first step that errors without waiting for the ones still
+/// running.
+fn get_repo_project_info<W: Write>(
+    steps: Vec<Step<CmdInfo>>,
+    progress: Option<W>,
+    terminal: bool,
+) -> Result<MergeRequestBody> {
     let mut project = Project::default();
     let mut members = Vec::new();
     let mut repo = git::Repo::default();
-    let cmd_results = exec::parallel_stream(cmds);
-    for cmd_result in cmd_results {
-        match cmd_result {
+    let names = steps.iter().map(|step| step.name).collect::<Vec<_>>();
+    let mut progress = StepProgress::new(&names, progress, terminal, display::ascii_output());
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let step_results =
+        exec::parallel_steps(steps, api_defaults::MAX_CONCURRENT_STEPS, cancelled.clone());
+    for (position, step_result) in step_results {
+        if step_result.is_ok() {
+            progress.done(position);
+        }
+        match step_result {
             Ok(CmdInfo::Project(project_data)) => {
                 project = project_data;
             }
@@ -392,10 +1399,16 @@ fn get_repo_project_info(cmds: Vec<Cmd<CmdInfo>>) -> Result<MergeRequestBody> {
             Ok(CmdInfo::CommitSummary(title)) => repo.with_title(&title),
             Ok(CmdInfo::CommitMessage(message)) => repo.with_last_commit_message(&message),
             // bail on first error found
-            Err(e) => return Err(e),
+            Err(e) => {
+                cancelled.store(true, Ordering::SeqCst);
+                progress.failed(position);
+                progress.finish();
+                return Err(e);
+            }
             _ => {}
         }
     }
+    progress.finish();
     Ok(MergeRequestBodyBuilder::default()
         .repo(repo)
         .project(project)
@@ -437,9 +1450,36 @@ fn list<W: Write>(
     common::list_merge_requests(remote, body_args, cli_args, &mut writer)
 }
 
-fn merge(remote: Arc<dyn MergeRequest>, merge_request_id: i64) -> Result<()> {
+fn merge<W: Write>(
+    remote: Arc<dyn MergeRequest>,
+    checks: Arc<dyn MergeRequestChecks>,
+    merge_request_id: i64,
+    mut writer: W,
+) -> Result<()> {
+    let unmet = checks.unmet_requirements(merge_request_id)?;
+    if !unmet.is_empty() {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::MergeRequirementsNotMet, &[&merge_request_id])
+        )?;
+        for requirement in unmet {
+            writer.write_all(format!("  - {}\n", requirement).as_bytes())?;
+        }
+        return Err(GRError::PreconditionNotMet(i18n::format(
+            Message::CannotMergeYet,
+            &[&merge_request_id],
+        ))
+        .into());
+    }
     let merge_request = remote.merge(merge_request_id)?;
-    println!("Merge request merged: {}", merge_request.web_url);
+    writer.write_all(
+        format!(
+            "{}\n",
+            i18n::format(Message::MergeRequestMerged, &[&merge_request.web_url])
+        )
+        .as_bytes(),
+    )?;
     Ok(())
 }
 
@@ -451,16 +1491,236 @@ fn checkout(remote: Arc<dyn MergeRequest>, id: i64) -> Result<()> {
 
 fn close(remote: Arc<dyn MergeRequest>, id: i64) -> Result<()> {
     let merge_request = remote.close(id)?;
-    println!("Merge request closed: {}", merge_request.web_url);
+    println!(
+        "{}",
+        i18n::format(Message::MergeRequestClosed, &[&merge_request.web_url])
+    );
+    Ok(())
+}
+
+/// Comments on and/or closes all the open merge requests with a label. Stops
+/// at the first error, reporting how many were done, so it can be resumed
+/// once the rate limit resets. Merge requests recorded in the checkpoint are
+/// skipped.
+fn sweep<W: Write>(
+    remote: Arc<dyn MergeRequest>,
+    comment_remote: Arc<dyn CommentMergeRequest>,
+    cli_args: MergeRequestSweepCliArgs,
+    mut checkpoint: Checkpoint,
+    mut writer: W,
+) -> Result<()> {
+    let body_args = MergeRequestListBodyArgs::builder()
+        .state(MergeRequestState::Opened)
+        .list_args(None)
+        .assignee_id(None)
+        .build()?;
+    let merge_requests = remote
+        .list(body_args)?
+        .into_iter()
+        .filter(|mr| mr.labels.contains(&cli_args.label))
+        .collect::<Vec<_>>();
+    let labeled = merge_requests.len();
+    let merge_requests = merge_requests
+        .into_iter()
+        .filter(|mr| !checkpoint.is_done(mr.id))
+        .collect::<Vec<_>>();
+    if labeled > merge_requests.len() {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::SkippingSwept, &[&(labeled - merge_requests.len())])
+        )?;
+    }
+    if merge_requests.is_empty() {
+        if labeled == 0 {
+            writeln!(
+                writer,
+                "{}",
+                i18n::format(Message::NoMergeRequestsLabeled, &[&cli_args.label])
+            )?;
+        }
+        return checkpoint.complete();
+    }
+    let mut actions = Vec::new();
+    if cli_args.comment.is_some() {
+        actions.push("comment");
+    }
+    if cli_args.close {
+        actions.push("close");
+    }
+    let actions = actions.join(" and ");
+    let total = merge_requests.len();
+    for (done, mr) in merge_requests.into_iter().enumerate() {
+        if cancel::cancelled() {
+            writeln!(
+                writer,
+                "{}",
+                i18n::format(Message::SweepCancelled, &[&done, &total])
+            )?;
+            return cancel::check("sweep");
+        }
+        if cli_args.dry_run {
+            writer.write_all(
+                format!(
+                    "Would {} merge request {}: {}\n",
+                    actions, mr.id, mr.web_url
+                )
+                .as_bytes(),
+            )?;
+            continue;
+        }
+        if done > 0 {
+            if let Some(throttle_time) = cli_args.throttle_time {
+                std::thread::sleep(std::time::Duration::from_millis(*throttle_time));
+            }
+        }
+        let result = sweep_merge_request(&remote, &comment_remote, &cli_args, mr.id);
+        if let Err(err) = result {
+            if let Some(GRError::RateLimitExceeded(_)) = err.downcast_ref::<GRError>() {
+                writeln!(
+                    writer,
+                    "{}",
+                    i18n::format(Message::SweepRateLimited, &[&done, &total])
+                )?;
+            }
+            return Err(err);
+        }
+        checkpoint.record(mr.id)?;
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::MergeRequestSwept, &[&mr.id, &mr.web_url])
+        )?;
+    }
+    checkpoint.complete()
+}
+
+fn sweep_merge_request(
+    remote: &Arc<dyn MergeRequest>,
+    comment_remote: &Arc<dyn CommentMergeRequest>,
+    cli_args: &MergeRequestSweepCliArgs,
+    id: i64,
+) -> Result<()> {
+    if let Some(comment) = &cli_args.comment {
+        comment_remote.create(
+            CommentMergeRequestBodyArgs::builder()
+                .id(id)
+                .comment(comment.clone())
+                .build()?,
+        )?;
+    }
+    if cli_args.close {
+        remote.close(id)?;
+    }
+    Ok(())
+}
+
+fn set_draft<W: Write>(
+    remote: Arc<dyn MergeRequest>,
+    id: i64,
+    draft: bool,
+    mut writer: W,
+) -> Result<()> {
+    let merge_request = remote.set_draft(id, draft)?;
+    let message = if draft {
+        Message::MergeRequestMarkedDraft
+    } else {
+        Message::MergeRequestMarkedReady
+    };
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(message, &[&merge_request.web_url])
+    )?;
     Ok(())
 }
 
-fn approve<W: Write>(remote: Arc<dyn MergeRequest>, id: i64, mut writer: W) -> Result<()> {
-    let merge_request = remote.approve(id)?;
-    writer.write_all(format!("Merge request approved: {}\n", merge_request.web_url).as_bytes())?;
+fn reopen<W: Write>(remote: Arc<dyn MergeRequest>, id: i64, mut writer: W) -> Result<()> {
+    let merge_request = remote.reopen(id)?;
+    writer.write_all(
+        format!(
+            "{}\n",
+            i18n::format(Message::MergeRequestReopened, &[&merge_request.web_url])
+        )
+        .as_bytes(),
+    )?;
+    Ok(())
+}
+
+/// Warns when the current user is the author of the merge request about to be
+/// approved or merged. With `block` set, the action is refused instead. The
+/// current user is read from the cache when available.
+fn self_review_guard<W: Write>(
+    remote: Arc<dyn MergeRequest>,
+    user: Arc<dyn UserInfo>,
+    id: i64,
+    action: &str,
+    block: bool,
+    mut writer: W,
+) -> Result<()> {
+    let merge_request = remote.get(id)?;
+    let user = user.get()?;
+    if merge_request.author != user.username {
+        return Ok(());
+    }
+    if block {
+        return Err(GRError::PreconditionNotMet(format!(
+            "Cannot {} merge request {}: you are its author and block_self_review is set",
+            action, id
+        ))
+        .into());
+    }
+    writer.write_all(
+        format!(
+            "Warning: you are about to {} your own merge request {}\n",
+            action, id
+        )
+        .as_bytes(),
+    )?;
+    Ok(())
+}
+
+fn approve<W: Write>(
+    remote: Arc<dyn MergeRequest>,
+    id: i64,
+    comment: Option<&str>,
+    mut writer: W,
+) -> Result<()> {
+    let merge_request = remote.approve(id, comment)?;
+    writer.write_all(
+        format!(
+            "{}\n",
+            i18n::format(Message::MergeRequestApproved, &[&merge_request.web_url])
+        )
+        .as_bytes(),
+    )?;
     Ok(())
 }
 
+/// The checklist is confirmed with `--checklist-ok` or item by item in a
+/// terminal. Approving without confirming it is refused.
+fn confirm_checklist(checklist: &[String], checklist_ok: bool, interactive: bool) -> Result<()> {
+    if checklist_ok {
+        return Ok(());
+    }
+    if interactive && dialog::confirm_checklist(checklist)? {
+        return Ok(());
+    }
+    Err(GRError::PreconditionNotMet(
+        "Review checklist not confirmed. Go through it in a terminal or pass --checklist-ok"
+            .to_string(),
+    )
+    .into())
+}
+
+fn checklist_comment(checklist: &[String]) -> String {
+    let mut comment = "Approved with the review checklist confirmed:\n".to_string();
+    for item in checklist {
+        comment.push_str(&format!("\n- [x] {}", item));
+    }
+    comment
+}
+
 fn create_comment<R: BufRead>(
     remote: Arc<dyn CommentMergeRequest>,
     args: CommentMergeRequestCliArgs,
@@ -484,6 +1744,45 @@ fn create_comment<R: BufRead>(
     )
 }
 
+fn list_comments<W: Write>(
+    remote: Arc<dyn CommentMergeRequest>,
+    id: i64,
+    mut writer: W,
+) -> Result<()> {
+    let threads = remote.list_comments(id)?;
+    if threads.is_empty() {
+        writeln!(writer, "{}", i18n::format(Message::NoComments, &[&id]))?;
+        return Ok(());
+    }
+    for (index, thread) in threads.iter().enumerate() {
+        if index > 0 {
+            writeln!(writer)?;
+        }
+        let mut header = format!("Thread {}", thread.id);
+        if let Some(position) = &thread.position {
+            header.push_str(&format!(" on {}", position));
+        }
+        match thread.resolved {
+            Some(true) => header.push_str(" (resolved)"),
+            Some(false) => header.push_str(" (unresolved)"),
+            None => {}
+        }
+        writeln!(writer, "{}", header)?;
+        for (index, comment) in thread.comments.iter().enumerate() {
+            let replied = if index > 0 { "reply by" } else { "by" };
+            writeln!(
+                writer,
+                "  {} {} at {}",
+                replied, comment.author, comment.created_at
+            )?;
+            for line in comment.body.lines() {
+                writeln!(writer, "    {}", line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn get_merge_request_details<W: Write>(
     remote: Arc<dyn MergeRequest>,
     args: MergeRequestGetCliArgs,
@@ -494,8 +1793,208 @@ pub fn get_merge_request_details<W: Write>(
     Ok(())
 }
 
+fn list_checks<W: Write>(
+    remote: Arc<dyn MergeRequestChecks>,
+    cli_args: MergeRequestGetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let checks = remote.list(cli_args.id)?;
+    if checks.is_empty() {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::NoChecks, &[&cli_args.id])
+        )?;
+        return Ok(());
+    }
+    display::print(&mut writer, checks, cli_args.get_args)?;
+    Ok(())
+}
+
+fn list_approvers<W: Write>(
+    remote: Arc<dyn MergeRequestApprovals>,
+    cli_args: MergeRequestGetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let status = remote.approvals(cli_args.id)?;
+    display::print(&mut writer, vec![status], cli_args.get_args)?;
+    Ok(())
+}
+
+/// Starts the rebase in the remote and waits for it to finish, polling every
+/// `poll_interval` up to `timeout`.
+fn rebase<W: Write>(
+    remote: Arc<dyn MergeRequestRebase>,
+    cli_args: MergeRequestRebaseCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let id = cli_args.id;
+    let from_sha = remote.rebase(id)?;
+    let started = std::time::Instant::now();
+    loop {
+        match remote.rebase_progress(id, &from_sha)? {
+            RebaseProgress::Done { sha, pipeline_url } => {
+                writeln!(
+                    writer,
+                    "{}",
+                    i18n::format(Message::MergeRequestRebased, &[&id, &sha])
+                )?;
+                let pipeline = match pipeline_url {
+                    Some(url) => i18n::format(Message::NewPipeline, &[&url]),
+                    None => i18n::text(Message::NoNewPipeline),
+                };
+                writeln!(writer, "{}", pipeline)?;
+                return Ok(());
+            }
+            RebaseProgress::Failed(reason) => {
+                return Err(GRError::ApplicationError(format!(
+                    "Rebase of merge request {} failed: {}",
+                    id, reason
+                ))
+                .into());
+            }
+            RebaseProgress::InProgress => {}
+        }
+        if started.elapsed().as_secs() >= cli_args.timeout {
+            return Err(GRError::ApplicationError(format!(
+                "Rebase of merge request {} did not finish in {} seconds",
+                id, cli_args.timeout
+            ))
+            .into());
+        }
+        // The rebase goes on in the remote.
+        cancel::check(&format!("waiting for the rebase of merge request {}", id))?;
+        std::thread::sleep(std::time::Duration::from_millis(*cli_args.poll_interval));
+    }
+}
+
+fn show_diff<W: Write>(remote: Arc<dyn MergeRequestDiff>, id: i64, mut writer: W) -> Result<()> {
+    let diff = remote.diff(id)?;
+    writer.write_all(diff.as_bytes())?;
+    Ok(())
+}
+
+/// Writes what changed in the merge request since the user last approved or
+/// reviewed it, so only the new changes need a read.
+fn show_changed_since_review<W: Write>(
+    remote: Arc<dyn MergeRequestReviewDiff>,
+    user: Arc<dyn UserInfo>,
+    id: i64,
+    mut writer: W,
+) -> Result<()> {
+    let username = user.get()?.username;
+    let head = remote.reviewed_head(id, &username)?;
+    let Some(reviewed_sha) = head.reviewed_sha else {
+        return Err(GRError::PreconditionNotMet(format!(
+            "{} has not reviewed merge request {} yet. Use gr mr diff {} to see all its changes",
+            username, id, id
+        ))
+        .into());
+    };
+    if reviewed_sha == head.head_sha {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(
+                Message::NoChangesSinceReview,
+                &[&id, &short_sha(&reviewed_sha)]
+            )
+        )?;
+        return Ok(());
+    }
+    let diff = remote.compare(&reviewed_sha, &head.head_sha)?;
+    writer.write_all(diff.as_bytes())?;
+    Ok(())
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(8)]
+}
+
+fn default_branch(remote: Arc<dyn RemoteProject>) -> Result<String> {
+    match remote.get_project_data(None)? {
+        CmdInfo::Project(project) => Ok(project.default_branch().to_string()),
+        _ => Err(GRError::ApplicationError(
+            "Could not retrieve the default branch of the project".to_string(),
+        )
+        .into()),
+    }
+}
+
+fn list_merge_queue<W: Write>(
+    remote: Arc<dyn MergeQueue>,
+    body_args: MergeQueueListBodyArgs,
+    cli_args: MergeQueueListCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let branch = body_args.branch.clone();
+    let entries = remote.list(body_args)?;
+    if entries.is_empty() {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::NoMergeRequestsQueued, &[&branch])
+        )?;
+        return Ok(());
+    }
+    display::print(&mut writer, entries, cli_args.get_args)?;
+    Ok(())
+}
+
+fn get_merge_queue_entry<W: Write>(
+    remote: Arc<dyn MergeQueue>,
+    cli_args: MergeQueueGetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let entry = remote.get(cli_args.id)?;
+    display::print(&mut writer, vec![entry], cli_args.get_args)?;
+    Ok(())
+}
+
+fn enqueue<W: Write>(remote: Arc<dyn MergeQueue>, id: i64, mut writer: W) -> Result<()> {
+    remote.add(id)?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::AddedToMergeQueue, &[&id])
+    )?;
+    Ok(())
+}
+
+fn dequeue<W: Write>(remote: Arc<dyn MergeQueue>, id: i64, mut writer: W) -> Result<()> {
+    remote.remove(id)?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::RemovedFromMergeQueue, &[&id])
+    )?;
+    Ok(())
+}
+
+fn update_labels<W: Write>(
+    remote: Arc<dyn MergeRequestLabels>,
+    options: MergeRequestLabelOptions,
+    mut writer: W,
+) -> Result<()> {
+    let (id, labels) = match options {
+        MergeRequestLabelOptions::Add { id, labels } => (id, remote.add(id, &labels)?),
+        MergeRequestLabelOptions::Remove { id, labels } => (id, remote.remove(id, &labels)?),
+    };
+    if labels.is_empty() {
+        writeln!(writer, "{}", i18n::format(Message::NoLabels, &[&id]))?;
+    } else {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::MergeRequestLabels, &[&id, &labels.join(", ")])
+        )?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::io::PageCount;
     use std::{
         io::{Cursor, Read},
         sync::Mutex,
@@ -504,82 +2003,291 @@ mod tests {
     use crate::{
         api_traits::CommentMergeRequest, cli::browse::BrowseOptions,
         cmds::project::ProjectListBodyArgs, error, remote::MergeRequestResponse,
+        test::utils::MockRunner, Cmd,
     };
 
     use super::*;
 
     #[test]
-    fn test_current_branch_should_not_be_the_upstream_branch() {
-        let current_branch = "current-branch";
-        let target_branch = "current-branch";
-        let result = in_feature_branch(current_branch, target_branch);
-        assert!(result.is_err());
+    fn test_push_options_from_config_and_cli() {
+        let config_options = vec!["ci.skip".to_string()];
+        let cli_options = vec!["merge_request.label=bug".to_string()];
+        assert_eq!(
+            vec!["ci.skip".to_string(), "merge_request.label=bug".to_string()],
+            push_options(&config_options, &cli_options)
+        );
     }
 
-    #[test]
-    fn test_feature_branch_not_main_master_or_develop_is_ok() {
-        let current_branch = "newfeature";
-        let target_branch = "main";
-        let result = in_feature_branch(current_branch, target_branch);
-        assert!(result.is_ok());
+    fn assignee_test_config() -> Config {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/tmp
+        gitlab.com.assignee_group_backend=tsmith, jdoe
+        "#;
+        Config::new(Cursor::new(config_data), "gitlab.com").unwrap()
     }
 
     #[test]
-    fn test_feature_branch_is_main_master_or_develop_should_err() {
-        let test_cases = [
-            ("main", "upstream-branch"),
-            ("master", "upstream-branch"),
-            ("develop", "upstream-branch"),
-        ];
+    fn test_requested_assignees_merges_cli_and_groups_without_duplicates() {
+        let cli_args = MergeRequestCliArgs::builder()
+            .title(None)
+            .title_from_commit(None)
+            .description(None)
+            .description_from_file(None)
+            .target_branch(None)
+            .auto(true)
+            .refresh_cache(false)
+            .open_browser(false)
+            .accept_summary(true)
+            .commit(None)
+            .draft(false)
+            .assignees(vec!["jdoe".to_string()])
+            .assignee_groups(vec!["backend".to_string()])
+            .build()
+            .unwrap();
+        assert_eq!(
+            vec!["jdoe".to_string(), "tsmith".to_string()],
+            requested_assignees(&assignee_test_config(), &cli_args).unwrap()
+        );
+    }
 
-        for (current_branch, upstream_branch) in test_cases {
-            let result = in_feature_branch(current_branch, upstream_branch);
-            assert!(result.is_err());
+    #[test]
+    fn test_requested_assignees_unknown_group_is_error() {
+        let cli_args = MergeRequestCliArgs::builder()
+            .title(None)
+            .title_from_commit(None)
+            .description(None)
+            .description_from_file(None)
+            .target_branch(None)
+            .auto(true)
+            .refresh_cache(false)
+            .open_browser(false)
+            .accept_summary(true)
+            .commit(None)
+            .draft(false)
+            .assignee_groups(vec!["frontend".to_string()])
+            .build()
+            .unwrap();
+        let err = requested_assignees(&assignee_test_config(), &cli_args).unwrap_err();
+        match err.downcast_ref::<error::GRError>() {
+            Some(error::GRError::PreconditionNotMet(msg)) => {
+                assert!(msg.contains("assignee_group_frontend"))
+            }
+            _ => panic!("Expected PreconditionNotMet"),
         }
     }
 
-    fn get_cmds_mock(cmd: Arc<CmdMock>) -> Vec<Cmd<CmdInfo>> {
-        let cmd_status = cmd.clone();
-        let git_status_cmd =
-            move || -> Result<CmdInfo> { Ok(CmdInfo::StatusModified(cmd_status.status_modified)) };
-        let title_cmd = cmd.clone();
-        let git_title_cmd = move || -> Result<CmdInfo> {
-            Ok(CmdInfo::CommitSummary(
-                title_cmd.last_commit_summary.clone(),
-            ))
-        };
-        let message_cmd = cmd.clone();
-        let git_message_cmd = move || -> Result<CmdInfo> {
-            Ok(CmdInfo::CommitMessage(
-                message_cmd.last_commit_message.clone(),
-            ))
-        };
-        let branch_cmd = cmd.clone();
-        let git_current_branch =
-            move || -> Result<CmdInfo> { Ok(CmdInfo::Branch(branch_cmd.current_branch.clone())) };
-        let project_cmd = cmd.clone();
-        let remote_project_cmd =
-            move || -> Result<CmdInfo> { Ok(CmdInfo::Project(project_cmd.project.clone())) };
-        let members_cmd = cmd.clone();
-        let remote_members_cmd =
-            move || -> Result<CmdInfo> { Ok(CmdInfo::Members(members_cmd.members.clone())) };
-        let mut cmds: Vec<Cmd<CmdInfo>> = vec![
-            Box::new(remote_project_cmd),
-            Box::new(remote_members_cmd),
-            Box::new(git_status_cmd),
-            Box::new(git_title_cmd),
-            Box::new(git_message_cmd),
-            Box::new(git_current_branch),
-        ];
-        if cmd.error {
-            let error_cmd =
-                move || -> Result<CmdInfo> { Err(error::gen("Failure retrieving data")) };
-            cmds.push(Box::new(error_cmd));
-        }
-        cmds
+    #[test]
+    fn test_is_draft_title() {
+        assert!(is_draft_title("Draft: Add cache"));
+        assert!(is_draft_title("DRAFT: Add cache"));
+        assert!(is_draft_title("[Draft] Add cache"));
+        assert!(is_draft_title("(draft) Add cache"));
+        assert!(!is_draft_title("Add draft mode"));
     }
 
-    #[derive(Clone, Builder)]
+    #[test]
+    fn test_apply_limits_truncates_description() {
+        let limits = MergeRequestLimits {
+            title: 10,
+            description: 5,
+        };
+        let mut args = MergeRequestBodyArgs::builder()
+            .title("Add cache".to_string())
+            .description("Añadir caché".to_string())
+            .build()
+            .unwrap();
+        assert!(apply_limits(&mut args, limits).unwrap());
+        assert_eq!("Añadi", args.description);
+        assert_eq!("Add cache", args.title);
+        assert!(!apply_limits(&mut args, limits).unwrap());
+    }
+
+    #[test]
+    fn test_apply_limits_title_too_long_is_error() {
+        let limits = MergeRequestLimits {
+            title: 5,
+            description: 100,
+        };
+        let mut args = MergeRequestBodyArgs::builder()
+            .title("Add cache".to_string())
+            .build()
+            .unwrap();
+        let err = apply_limits(&mut args, limits).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("The title is 9 characters long and the remote accepts up to 5"));
+    }
+
+    #[test]
+    fn test_resolve_usernames_to_project_members() {
+        let members = vec![
+            Member::builder()
+                .id(1)
+                .username("jdoe".to_string())
+                .name("John Doe".to_string())
+                .build()
+                .unwrap(),
+            Member::builder()
+                .id(2)
+                .username("tsmith".to_string())
+                .name("Tom Smith".to_string())
+                .build()
+                .unwrap(),
+        ];
+        let assignees = resolve_members(
+            &members,
+            &["tsmith".to_string(), "jdoe".to_string()],
+            "assignee",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![2, 1],
+            assignees.iter().map(|a| a.id).collect::<Vec<_>>()
+        );
+        let err = resolve_members(&members, &["unknown".to_string()], "reviewer").unwrap_err();
+        assert_eq!(
+            "Precondition not met error: Cannot find reviewer unknown among the project members",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_update_branch_follows_strategy() {
+        let runner = MockRunner::new(vec![Response::builder().build().unwrap()]);
+        update_branch(&runner, UpdateStrategy::Merge, "main").unwrap();
+        assert_eq!("git merge --no-edit origin/main", *runner.cmd());
+    }
+
+    #[test]
+    fn test_upstream_remote_path() {
+        let remote = |url: &str| {
+            MockRunner::new(vec![Response::builder()
+                .body(url.to_string())
+                .build()
+                .unwrap()])
+        };
+        let runner = remote("git@github.com:jordilin/gitar.git");
+        assert_eq!(
+            Some("jordilin/gitar".to_string()),
+            upstream_remote_path(&runner, "github.com", "fork/gitar")
+        );
+        // Neither upstream in another domain nor pointing at origin.
+        let runner = remote("git@gitlab.com:jordilin/gitar.git");
+        assert_eq!(
+            None,
+            upstream_remote_path(&runner, "github.com", "fork/gitar")
+        );
+        let runner = remote("git@github.com:fork/gitar.git");
+        assert_eq!(
+            None,
+            upstream_remote_path(&runner, "github.com", "fork/gitar")
+        );
+        let runner = MockRunner::new(vec![Response::builder()
+            .status(2)
+            .body("error: No such remote 'upstream'".to_string())
+            .build()
+            .unwrap()]);
+        assert_eq!(
+            None,
+            upstream_remote_path(&runner, "github.com", "fork/gitar")
+        );
+    }
+
+    #[test]
+    fn test_divergence_report() {
+        let divergence = Divergence {
+            ahead: 2,
+            behind: 1,
+        };
+        assert_eq!(
+            "Branch feature is 2 commits ahead and 1 commits behind origin/main\n",
+            divergence_report("feature", "main", &divergence)
+        );
+    }
+
+    #[test]
+    fn test_divergence_report_warns_when_far_behind() {
+        let divergence = Divergence {
+            ahead: 2,
+            behind: BEHIND_COMMITS_WARN_THRESHOLD,
+        };
+        let report = divergence_report("feature", "main", &divergence);
+        assert!(report.contains("Warning: feature is far behind origin/main"));
+    }
+
+    #[test]
+    fn test_current_branch_should_not_be_the_upstream_branch() {
+        let current_branch = "current-branch";
+        let target_branch = "current-branch";
+        let result = in_feature_branch(current_branch, target_branch);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_feature_branch_not_main_master_or_develop_is_ok() {
+        let current_branch = "newfeature";
+        let target_branch = "main";
+        let result = in_feature_branch(current_branch, target_branch);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_feature_branch_is_main_master_or_develop_should_err() {
+        let test_cases = [
+            ("main", "upstream-branch"),
+            ("master", "upstream-branch"),
+            ("develop", "upstream-branch"),
+        ];
+
+        for (current_branch, upstream_branch) in test_cases {
+            let result = in_feature_branch(current_branch, upstream_branch);
+            assert!(result.is_err());
+        }
+    }
+
+    fn get_cmds_mock(cmd: Arc<CmdMock>) -> Vec<Step<CmdInfo>> {
+        let cmd_status = cmd.clone();
+        let git_status_cmd =
+            move || -> Result<CmdInfo> { Ok(CmdInfo::StatusModified(cmd_status.status_modified)) };
+        let title_cmd = cmd.clone();
+        let git_title_cmd = move || -> Result<CmdInfo> {
+            Ok(CmdInfo::CommitSummary(
+                title_cmd.last_commit_summary.clone(),
+            ))
+        };
+        let message_cmd = cmd.clone();
+        let git_message_cmd = move || -> Result<CmdInfo> {
+            Ok(CmdInfo::CommitMessage(
+                message_cmd.last_commit_message.clone(),
+            ))
+        };
+        let branch_cmd = cmd.clone();
+        let git_current_branch =
+            move || -> Result<CmdInfo> { Ok(CmdInfo::Branch(branch_cmd.current_branch.clone())) };
+        let project_cmd = cmd.clone();
+        let remote_project_cmd =
+            move || -> Result<CmdInfo> { Ok(CmdInfo::Project(project_cmd.project.clone())) };
+        let members_cmd = cmd.clone();
+        let remote_members_cmd =
+            move || -> Result<CmdInfo> { Ok(CmdInfo::Members(members_cmd.members.clone())) };
+        let mut cmds = vec![
+            Step::new("project", Box::new(remote_project_cmd) as Cmd<CmdInfo>),
+            Step::new("members", Box::new(remote_members_cmd)),
+            Step::new("status", Box::new(git_status_cmd)),
+            Step::new("title", Box::new(git_title_cmd)),
+            Step::new("description", Box::new(git_message_cmd)),
+            Step::new("branch", Box::new(git_current_branch)),
+        ];
+        if cmd.error {
+            let error_cmd =
+                move || -> Result<CmdInfo> { Err(error::gen("Failure retrieving data")) };
+            cmds.push(Step::new("error", Box::new(error_cmd)));
+        }
+        cmds
+    }
+
+    #[derive(Clone, Builder)]
     struct CmdMock {
         #[builder(default = "false")]
         status_modified: bool,
@@ -604,7 +2312,7 @@ mod tests {
             .build()
             .unwrap();
         let cmds = get_cmds_mock(Arc::new(cmd_mock));
-        let result = get_repo_project_info(cmds);
+        let result = get_repo_project_info(cmds, None::<Vec<u8>>, false);
         assert!(result.is_ok());
         let result = result.unwrap();
         assert_eq!(result.repo.title(), "title");
@@ -626,8 +2334,52 @@ mod tests {
             .build()
             .unwrap();
         let cmds = get_cmds_mock(Arc::new(cmd_mock));
-        let result = get_repo_project_info(cmds);
+        let mut progress = Vec::new();
+        let result = get_repo_project_info(cmds, Some(&mut progress), true);
         assert!(result.is_err());
+        let progress = String::from_utf8(progress).unwrap();
+        let failed = if display::ascii_output() {
+            "error failed"
+        } else {
+            "error ✗"
+        };
+        assert!(progress.contains(failed));
+        assert!(progress.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_description_templates_of_gitlab_and_github() {
+        let dir = tempfile::tempdir().unwrap();
+        let gitlab = dir.path().join(".gitlab/merge_request_templates");
+        std::fs::create_dir_all(&gitlab).unwrap();
+        std::fs::write(gitlab.join("bugfix.md"), "## Bug\n\nCloses #\n").unwrap();
+        std::fs::write(gitlab.join("notes.txt"), "not a template").unwrap();
+        std::fs::create_dir_all(dir.path().join(".github")).unwrap();
+        std::fs::write(
+            dir.path().join(".github/PULL_REQUEST_TEMPLATE.md"),
+            "## Summary",
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["bugfix", "default"],
+            description_templates(dir.path())
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "## Bug\n\nCloses #",
+            description_template(dir.path(), "bugfix").unwrap()
+        );
+        assert_eq!(
+            "## Summary\n\n-- signed",
+            build_description(
+                &description_template(dir.path(), "default").unwrap(),
+                "-- signed"
+            )
+        );
+        let err = description_template(dir.path(), "feature").unwrap_err();
+        assert!(err.to_string().contains("Available: bugfix, default"));
     }
 
     #[test]
@@ -653,6 +2405,7 @@ mod tests {
                     .title("New feature".to_string())
                     .web_url("https://gitlab.com/owner/repo/-/merge_requests/1".to_string())
                     .author("author".to_string())
+                    .labels(vec!["bug".to_string(), "backend".to_string()])
                     .updated_at("2021-01-01".to_string())
                     .build()
                     .unwrap()])
@@ -672,8 +2425,8 @@ mod tests {
         );
         list(remote, body_args, cli_args, &mut buf).unwrap();
         assert_eq!(
-            "ID|Title|Author|URL|Updated at\n\
-             1|New feature|author|https://gitlab.com/owner/repo/-/merge_requests/1|2021-01-01\n",
+            "ID|Title|Author|Labels|URL|Updated at\n\
+             1|New feature|author|bug,backend|https://gitlab.com/owner/repo/-/merge_requests/1|2021-01-01\n",
             String::from_utf8(buf).unwrap(),
         )
     }
@@ -750,15 +2503,92 @@ mod tests {
         );
         list(remote, body_args, cli_args, &mut buf).unwrap();
         assert_eq!(
-            "1|New feature|author|https://gitlab.com/owner/repo/-/merge_requests/1|2021-01-01\n",
+            "1|New feature|author||https://gitlab.com/owner/repo/-/merge_requests/1|2021-01-01\n",
             String::from_utf8(buf).unwrap(),
         )
     }
 
+    #[test]
+    fn test_needs_my_review_filters() {
+        let user = Member::builder()
+            .id(1234)
+            .name("jordi".to_string())
+            .username("jordilin".to_string())
+            .build()
+            .unwrap();
+        let cli_args = MergeRequestListCliArgs::new(
+            MergeRequestState::Opened,
+            ListRemoteCliArgs::builder().build().unwrap(),
+        );
+        let mut body_args = MergeRequestListBodyArgs::builder();
+        body_args
+            .list_args(None)
+            .state(MergeRequestState::Opened)
+            .assignee_id(None);
+        smart_filters(
+            &mut body_args,
+            &cli_args.with_needs_my_review(true),
+            user.clone(),
+        );
+        let body_args = body_args.build().unwrap();
+        assert_eq!("jordilin", body_args.reviewer.unwrap().username);
+        assert!(body_args.in_project);
+        assert_eq!(Some(false), body_args.draft);
+        assert!(body_args.passing_ci);
+        assert_eq!(None, body_args.author);
+    }
+
+    #[test]
+    fn test_pick_merge_request_among_the_open_ones() {
+        let merge_request = |id: i64, title: &str| {
+            MergeRequestResponse::builder()
+                .id(id)
+                .title(title.to_string())
+                .author("jdoe".to_string())
+                .source_branch(format!("feature-{}", id))
+                .build()
+                .unwrap()
+        };
+        let remote = Arc::new(
+            MergeRequestRemoteMock::builder()
+                .merge_requests(vec![
+                    merge_request(7, "Add cache"),
+                    merge_request(9, "Fix login"),
+                ])
+                .build()
+                .unwrap(),
+        );
+        let id = pick_merge_request(remote.clone(), |_, items| {
+            assert_eq!(
+                vec![
+                    "7 Add cache (jdoe, feature-7)",
+                    "9 Fix login (jdoe, feature-9)"
+                ],
+                items
+            );
+            Ok(Some(1))
+        })
+        .unwrap();
+        assert_eq!(9, id);
+        assert!(pick_merge_request(remote, |_, _| Ok(None)).is_err());
+    }
+
+    #[test]
+    fn test_pick_merge_request_without_open_ones_is_err() {
+        let remote = Arc::new(MergeRequestRemoteMock::builder().build().unwrap());
+        let err = pick_merge_request(remote, |_, _| panic!("Nothing to pick")).unwrap_err();
+        match err.downcast_ref::<GRError>() {
+            Some(GRError::PreconditionNotMet(msg)) => assert_eq!("No open merge requests", msg),
+            _ => panic!("Expected PreconditionNotMet, got {:?}", err),
+        }
+    }
+
     #[derive(Clone, Builder)]
     struct MergeRequestRemoteMock {
         #[builder(default = "Vec::new()")]
         merge_requests: Vec<MergeRequestResponse>,
+        #[builder(default)]
+        approve_comment: Arc<Mutex<Option<String>>>,
     }
 
     impl MergeRequestRemoteMock {
@@ -775,7 +2605,7 @@ mod tests {
             Ok(self.merge_requests.clone())
         }
         fn merge(&self, _id: i64) -> Result<MergeRequestResponse> {
-            Ok(MergeRequestResponse::builder().build().unwrap())
+            Ok(self.merge_requests[0].clone())
         }
         fn get(&self, _id: i64) -> Result<MergeRequestResponse> {
             Ok(self.merge_requests[0].clone())
@@ -783,18 +2613,38 @@ mod tests {
         fn close(&self, _id: i64) -> Result<MergeRequestResponse> {
             Ok(MergeRequestResponse::builder().build().unwrap())
         }
-        fn num_pages(&self, _args: MergeRequestListBodyArgs) -> Result<Option<u32>> {
+        fn reopen(&self, _id: i64) -> Result<MergeRequestResponse> {
+            Ok(self.merge_requests[0].clone())
+        }
+        fn num_pages(&self, _args: MergeRequestListBodyArgs) -> Result<Option<PageCount>> {
             Ok(None)
         }
-        fn approve(&self, _id: i64) -> Result<MergeRequestResponse> {
+        fn limits(&self) -> MergeRequestLimits {
+            MergeRequestLimits {
+                title: 256,
+                description: 65_536,
+            }
+        }
+        fn approve(&self, _id: i64, comment: Option<&str>) -> Result<MergeRequestResponse> {
+            *self.approve_comment.lock().unwrap() = comment.map(|c| c.to_string());
             Ok(self.merge_requests[0].clone())
         }
+        fn set_draft(&self, _id: i64, draft: bool) -> Result<MergeRequestResponse> {
+            let mut merge_request = self.merge_requests[0].clone();
+            merge_request.title = if draft {
+                draft_title(&merge_request.title)
+            } else {
+                ready_title(&merge_request.title)
+            };
+            Ok(merge_request)
+        }
     }
 
     #[derive(Default)]
     struct MockRemoteProject {
         comment_called: Mutex<bool>,
         comment_argument: Mutex<String>,
+        comment_threads: Vec<CommentThread>,
     }
     impl RemoteProject for MockRemoteProject {
         fn get_project_data(&self, _id: Option<i64>) -> Result<CmdInfo> {
@@ -828,7 +2678,7 @@ mod tests {
             todo!()
         }
 
-        fn num_pages(&self, _args: ProjectListBodyArgs) -> Result<Option<u32>> {
+        fn num_pages(&self, _args: ProjectListBodyArgs) -> Result<Option<PageCount>> {
             todo!()
         }
     }
@@ -841,6 +2691,10 @@ mod tests {
             *argument = args.comment;
             Ok(())
         }
+
+        fn list_comments(&self, _id: i64) -> Result<Vec<CommentThread>> {
+            Ok(self.comment_threads.clone())
+        }
     }
 
     struct MockShellRunner {
@@ -915,11 +2769,17 @@ mod tests {
         let responses = gen_cmd_responses();
 
         let task_runner = Arc::new(MockShellRunner::new(responses));
-        let cmds = cmds(remote, &cli_args, task_runner, None::<Cursor<&str>>);
+        let cmds = cmds(
+            remote.clone(),
+            remote,
+            &cli_args,
+            task_runner,
+            None::<Cursor<&str>>,
+        );
         assert_eq!(cmds.len(), 7);
         let cmds = cmds
             .into_iter()
-            .map(|cmd| cmd())
+            .map(|step| (step.cmd)())
             .collect::<Result<Vec<CmdInfo>>>()
             .unwrap();
         let title_result = cmds[4].clone();
@@ -952,10 +2812,16 @@ mod tests {
 
         let task_runner = Arc::new(MockShellRunner::new(responses));
 
-        let cmds = cmds(remote, &cli_args, task_runner, None::<Cursor<&str>>);
+        let cmds = cmds(
+            remote.clone(),
+            remote,
+            &cli_args,
+            task_runner,
+            None::<Cursor<&str>>,
+        );
         let results = cmds
             .into_iter()
-            .map(|cmd| cmd())
+            .map(|step| (step.cmd)())
             .collect::<Result<Vec<CmdInfo>>>()
             .unwrap();
         let title_result = results[4].clone();
@@ -990,10 +2856,10 @@ mod tests {
 
         let description_contents = "This merge requests adds a new feature\n";
         let reader = Cursor::new(description_contents);
-        let cmds = cmds(remote, &cli_args, task_runner, Some(reader));
+        let cmds = cmds(remote.clone(), remote, &cli_args, task_runner, Some(reader));
         let results = cmds
             .into_iter()
-            .map(|cmd| cmd())
+            .map(|step| (step.cmd)())
             .collect::<Result<Vec<CmdInfo>>>()
             .unwrap();
         let description_result = results[6].clone();
@@ -1015,7 +2881,7 @@ mod tests {
             .unwrap();
         let reader = Cursor::new("comment");
         assert!(create_comment(remote.clone(), cli_args, Some(reader)).is_ok());
-        assert!(remote.comment_called.lock().unwrap().clone());
+        assert!(*remote.comment_called.lock().unwrap());
         assert_eq!(
             "All features complete, ship it",
             remote.comment_argument.lock().unwrap().clone(),
@@ -1033,30 +2899,84 @@ mod tests {
             .unwrap();
         let reader = Cursor::new("Just a long, long comment from a file");
         assert!(create_comment(remote.clone(), cli_args, Some(reader)).is_ok());
-        assert!(remote.comment_called.lock().unwrap().clone());
+        assert!(*remote.comment_called.lock().unwrap());
         assert_eq!(
             "Just a long, long comment from a file",
             remote.comment_argument.lock().unwrap().clone(),
         );
     }
 
+    fn comment(id: i64, author: &str, body: &str) -> MergeRequestComment {
+        MergeRequestComment::builder()
+            .id(id)
+            .author(author.to_string())
+            .created_at(format!("2024-03-0{}T10:00:00Z", id))
+            .body(body.to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_list_comments_shows_threads_and_replies() {
+        let remote = Arc::new(MockRemoteProject {
+            comment_threads: vec![
+                CommentThread::builder()
+                    .id("1".to_string())
+                    .comments(vec![comment(1, "jdoe", "Thanks!")])
+                    .build()
+                    .unwrap(),
+                CommentThread::builder()
+                    .id("a1b2".to_string())
+                    .position(Some("src/main.rs:12".to_string()))
+                    .resolved(Some(false))
+                    .comments(vec![
+                        comment(2, "alice", "Off by one?\nSee the loop"),
+                        comment(3, "jdoe", "Fixed"),
+                    ])
+                    .build()
+                    .unwrap(),
+            ],
+            ..Default::default()
+        });
+        let mut writer = Vec::new();
+        list_comments(remote, 7, &mut writer).unwrap();
+        assert_eq!(
+            "Thread 1\n  \
+             by jdoe at 2024-03-01T10:00:00Z\n    \
+             Thanks!\n\
+             \n\
+             Thread a1b2 on src/main.rs:12 (unresolved)\n  \
+             by alice at 2024-03-02T10:00:00Z\n    \
+             Off by one?\n    \
+             See the loop\n  \
+             reply by jdoe at 2024-03-03T10:00:00Z\n    \
+             Fixed\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list_comments_without_comments() {
+        let remote = Arc::new(MockRemoteProject::default());
+        let mut writer = Vec::new();
+        list_comments(remote, 7, &mut writer).unwrap();
+        assert_eq!(
+            "No comments on merge request 7\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
     struct ErrorReader {}
 
     impl Read for ErrorReader {
         fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Error reading from reader",
-            ))
+            Err(std::io::Error::other("Error reading from reader"))
         }
     }
 
     impl BufRead for ErrorReader {
         fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Error reading from reader",
-            ))
+            Err(std::io::Error::other("Error reading from reader"))
         }
         fn consume(&mut self, _amt: usize) {}
     }
@@ -1107,8 +3027,8 @@ mod tests {
         let mut writer = Vec::new();
         get_merge_request_details(remote, cli_args, &mut writer).unwrap();
         assert_eq!(
-            "ID|Title|Description|Author|URL|Updated at|Merged at|Pipeline ID|Pipeline URL\n\
-             1|New feature|Implement get merge request||https://gitlab.com/owner/repo/-/merge_requests/1||2024-03-03T00:00:00Z|1|https://gitlab.com/owner/repo/-/pipelines/1\n",
+            "ID|Title|Description|Author|Labels|URL|Updated at|Merged at|Pipeline ID|Pipeline URL\n\
+             1|New feature|Implement get merge request|||https://gitlab.com/owner/repo/-/merge_requests/1||2024-03-03T00:00:00Z|1|https://gitlab.com/owner/repo/-/pipelines/1\n",
             String::from_utf8(writer).unwrap(),
         )
     }
@@ -1127,10 +3047,787 @@ mod tests {
                 .unwrap(),
         );
         let mut writer = Vec::new();
-        approve(remote, 1, &mut writer).unwrap();
+        approve(remote, 1, None, &mut writer).unwrap();
         assert_eq!(
             "Merge request approved: https://gitlab.com/owner/repo/-/merge_requests/1\n",
             String::from_utf8(writer).unwrap(),
         );
     }
+
+    #[test]
+    fn test_approve_with_review_checklist_as_comment() {
+        let remote = Arc::new(
+            MergeRequestRemoteMock::builder()
+                .merge_requests(vec![MergeRequestResponse::builder().build().unwrap()])
+                .build()
+                .unwrap(),
+        );
+        let checklist = vec![
+            "Tests cover the change".to_string(),
+            "Changelog updated".to_string(),
+        ];
+        let comment = checklist_comment(&checklist);
+        approve(remote.clone(), 1, Some(&comment), Vec::new()).unwrap();
+        assert_eq!(
+            Some(
+                "Approved with the review checklist confirmed:\n\n\
+                 - [x] Tests cover the change\n\
+                 - [x] Changelog updated"
+                    .to_string()
+            ),
+            remote.approve_comment.lock().unwrap().clone(),
+        );
+    }
+
+    #[test]
+    fn test_review_checklist_requires_confirmation() {
+        let checklist = vec!["Tests cover the change".to_string()];
+        assert!(confirm_checklist(&checklist, true, false).is_ok());
+        let err = confirm_checklist(&checklist, false, false).unwrap_err();
+        match err.downcast_ref::<GRError>() {
+            Some(GRError::PreconditionNotMet(_)) => (),
+            _ => panic!("Expected PreconditionNotMet"),
+        }
+    }
+
+    #[test]
+    fn test_draft_and_ready_titles() {
+        assert_eq!("DRAFT: Add cache", draft_title("Add cache"));
+        assert_eq!("[Draft] Add cache", draft_title("[Draft] Add cache"));
+        assert_eq!("Add cache", ready_title("Draft: Add cache"));
+        assert_eq!("Add cache", ready_title("(draft)Add cache"));
+        assert_eq!("Add cache", ready_title("Add cache"));
+    }
+
+    #[test]
+    fn test_mark_merge_request_as_draft_and_ready() {
+        let merge_request = MergeRequestResponse::builder()
+            .id(1)
+            .title("Add cache".to_string())
+            .web_url("https://gitlab.com/owner/repo/-/merge_requests/1".to_string())
+            .build()
+            .unwrap();
+        let remote = Arc::new(
+            MergeRequestRemoteMock::builder()
+                .merge_requests(vec![merge_request])
+                .build()
+                .unwrap(),
+        );
+        let mut writer = Vec::new();
+        set_draft(remote.clone(), 1, true, &mut writer).unwrap();
+        set_draft(remote, 1, false, &mut writer).unwrap();
+        assert_eq!(
+            "Merge request marked as draft: https://gitlab.com/owner/repo/-/merge_requests/1\n\
+             Merge request ready for review: https://gitlab.com/owner/repo/-/merge_requests/1\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_reopen_merge_request() {
+        let reopen_response = MergeRequestResponse::builder()
+            .id(1)
+            .web_url("https://gitlab.com/owner/repo/-/merge_requests/1".to_string())
+            .build()
+            .unwrap();
+        let remote = Arc::new(
+            MergeRequestRemoteMock::builder()
+                .merge_requests(vec![reopen_response])
+                .build()
+                .unwrap(),
+        );
+        let mut writer = Vec::new();
+        reopen(remote, 1, &mut writer).unwrap();
+        assert_eq!(
+            "Merge request reopened: https://gitlab.com/owner/repo/-/merge_requests/1\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[derive(Default)]
+    struct SweepCommentMock {
+        commented: Mutex<Vec<i64>>,
+        rate_limited_at: Option<usize>,
+    }
+
+    impl CommentMergeRequest for SweepCommentMock {
+        fn create(&self, args: CommentMergeRequestBodyArgs) -> Result<()> {
+            let mut commented = self.commented.lock().unwrap();
+            if Some(commented.len()) == self.rate_limited_at {
+                return Err(GRError::RateLimitExceeded(Default::default()).into());
+            }
+            commented.push(args.id);
+            Ok(())
+        }
+
+        fn list_comments(&self, _id: i64) -> Result<Vec<CommentThread>> {
+            todo!()
+        }
+    }
+
+    fn labeled_merge_requests() -> Arc<MergeRequestRemoteMock> {
+        let mr = |id: i64, labels: Vec<&str>| {
+            MergeRequestResponse::builder()
+                .id(id)
+                .web_url(format!(
+                    "https://gitlab.com/owner/repo/-/merge_requests/{}",
+                    id
+                ))
+                .labels(labels.into_iter().map(|l| l.to_string()).collect())
+                .build()
+                .unwrap()
+        };
+        Arc::new(
+            MergeRequestRemoteMock::builder()
+                .merge_requests(vec![
+                    mr(1, vec!["stale"]),
+                    mr(2, vec!["bug"]),
+                    mr(3, vec!["bug", "stale"]),
+                ])
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn sweep_args(dry_run: bool) -> MergeRequestSweepCliArgs {
+        MergeRequestSweepCliArgs::builder()
+            .label("stale".to_string())
+            .comment(Some("Closing due to inactivity".to_string()))
+            .close(true)
+            .dry_run(dry_run)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sweep_dry_run_does_not_change_merge_requests() {
+        let comment_remote = Arc::new(SweepCommentMock::default());
+        let mut writer = Vec::new();
+        sweep(
+            labeled_merge_requests(),
+            comment_remote.clone(),
+            sweep_args(true),
+            Checkpoint::disabled(),
+            &mut writer,
+        )
+        .unwrap();
+        assert!(comment_remote.commented.lock().unwrap().is_empty());
+        assert_eq!(
+            "Would comment and close merge request 1: https://gitlab.com/owner/repo/-/merge_requests/1\n\
+             Would comment and close merge request 3: https://gitlab.com/owner/repo/-/merge_requests/3\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_sweep_comments_on_labeled_merge_requests() {
+        let comment_remote = Arc::new(SweepCommentMock::default());
+        let mut writer = Vec::new();
+        sweep(
+            labeled_merge_requests(),
+            comment_remote.clone(),
+            sweep_args(false),
+            Checkpoint::disabled(),
+            &mut writer,
+        )
+        .unwrap();
+        assert_eq!(vec![1, 3], *comment_remote.commented.lock().unwrap());
+    }
+
+    #[test]
+    fn test_sweep_stops_when_rate_limited() {
+        let comment_remote = Arc::new(SweepCommentMock {
+            rate_limited_at: Some(1),
+            ..Default::default()
+        });
+        let mut writer = Vec::new();
+        let result = sweep(
+            labeled_merge_requests(),
+            comment_remote,
+            sweep_args(false),
+            Checkpoint::disabled(),
+            &mut writer,
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            "Swept merge request 1: https://gitlab.com/owner/repo/-/merge_requests/1\n\
+             Rate limit reached after sweeping 1 of 2 merge requests. Run again with --resume once it resets\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_sweep_resume_skips_swept_merge_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().to_str().unwrap();
+        let mut checkpoint = Checkpoint::new(location, "mr sweep stale", false).unwrap();
+        checkpoint.record(1).unwrap();
+        let comment_remote = Arc::new(SweepCommentMock::default());
+        let mut writer = Vec::new();
+        sweep(
+            labeled_merge_requests(),
+            comment_remote.clone(),
+            sweep_args(false),
+            Checkpoint::new(location, "mr sweep stale", true).unwrap(),
+            &mut writer,
+        )
+        .unwrap();
+        assert_eq!(vec![3], *comment_remote.commented.lock().unwrap());
+        assert_eq!(
+            "Skipping 1 merge requests swept in a previous run\n\
+             Swept merge request 3: https://gitlab.com/owner/repo/-/merge_requests/3\n",
+            String::from_utf8(writer).unwrap(),
+        );
+        // Completed, so the next run starts from scratch.
+        let checkpoint = Checkpoint::new(location, "mr sweep stale", true).unwrap();
+        assert_eq!(0, checkpoint.num_done());
+    }
+
+    #[test]
+    fn test_sweep_stops_once_cancelled() {
+        let comment_remote = Arc::new(SweepCommentMock::default());
+        cancel::cancel();
+        let mut writer = Vec::new();
+        let result = sweep(
+            labeled_merge_requests(),
+            comment_remote.clone(),
+            sweep_args(false),
+            Checkpoint::disabled(),
+            &mut writer,
+        );
+        assert!(result.is_err());
+        assert!(comment_remote.commented.lock().unwrap().is_empty());
+        assert_eq!(
+            "Cancelled after sweeping 0 of 2 merge requests. Run again with --resume to continue\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    struct UserInfoMock {
+        username: String,
+    }
+
+    impl UserInfo for UserInfoMock {
+        fn get(&self) -> Result<Member> {
+            Ok(Member::builder()
+                .id(1)
+                .username(self.username.clone())
+                .name("John Doe".to_string())
+                .build()
+                .unwrap())
+        }
+    }
+
+    fn own_merge_request_remote() -> Arc<MergeRequestRemoteMock> {
+        Arc::new(
+            MergeRequestRemoteMock::builder()
+                .merge_requests(vec![MergeRequestResponse::builder()
+                    .id(1)
+                    .author("jdoe".to_string())
+                    .build()
+                    .unwrap()])
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_self_review_guard_warns_on_own_merge_request() {
+        let user = Arc::new(UserInfoMock {
+            username: "jdoe".to_string(),
+        });
+        let mut writer = Vec::new();
+        self_review_guard(
+            own_merge_request_remote(),
+            user,
+            1,
+            "approve",
+            false,
+            &mut writer,
+        )
+        .unwrap();
+        assert_eq!(
+            "Warning: you are about to approve your own merge request 1\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_self_review_guard_blocks_own_merge_request() {
+        let user = Arc::new(UserInfoMock {
+            username: "jdoe".to_string(),
+        });
+        let mut writer = Vec::new();
+        let result = self_review_guard(
+            own_merge_request_remote(),
+            user,
+            1,
+            "merge",
+            true,
+            &mut writer,
+        );
+        match result {
+            Err(err) => match err.downcast_ref::<error::GRError>() {
+                Some(error::GRError::PreconditionNotMet(msg)) => {
+                    assert!(msg.contains("Cannot merge merge request 1"))
+                }
+                _ => panic!("Expected PreconditionNotMet"),
+            },
+            Ok(_) => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_self_review_guard_ignores_others_merge_requests() {
+        let user = Arc::new(UserInfoMock {
+            username: "tsmith".to_string(),
+        });
+        let mut writer = Vec::new();
+        self_review_guard(
+            own_merge_request_remote(),
+            user,
+            1,
+            "merge",
+            true,
+            &mut writer,
+        )
+        .unwrap();
+        assert!(writer.is_empty());
+    }
+
+    struct MergeQueueMock {
+        entries: Vec<MergeQueueEntry>,
+    }
+
+    impl MergeQueue for MergeQueueMock {
+        fn list(&self, args: MergeQueueListBodyArgs) -> Result<Vec<MergeQueueEntry>> {
+            assert_eq!("main", args.branch);
+            Ok(self.entries.clone())
+        }
+        fn get(&self, _id: i64) -> Result<MergeQueueEntry> {
+            Ok(self.entries[0].clone())
+        }
+        fn add(&self, _id: i64) -> Result<()> {
+            Ok(())
+        }
+        fn remove(&self, _id: i64) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn merge_queue_entry() -> MergeQueueEntry {
+        MergeQueueEntry::builder()
+            .position(1)
+            .id(59)
+            .title("Add retries".to_string())
+            .target_branch("main".to_string())
+            .status("fresh".to_string())
+            .enqueued_by("jordilin".to_string())
+            .enqueued_at("2024-02-01T10:00:00Z".to_string())
+            .web_url("https://gitlab.com/owner/repo/-/merge_requests/59".to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_list_merge_queue() {
+        let remote = Arc::new(MergeQueueMock {
+            entries: vec![merge_queue_entry()],
+        });
+        let cli_args = MergeQueueListCliArgs::builder()
+            .branch(None)
+            .get_args(GetRemoteCliArgs::default())
+            .build()
+            .unwrap();
+        let body_args = MergeQueueListBodyArgs::builder()
+            .branch("main".to_string())
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        list_merge_queue(remote, body_args, cli_args, &mut writer).unwrap();
+        assert_eq!(
+            "Position|ID|Title|Branch|Status|Enqueued by|Enqueued at|URL\n\
+             1|59|Add retries|main|fresh|jordilin|2024-02-01T10:00:00Z|https://gitlab.com/owner/repo/-/merge_requests/59\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_list_empty_merge_queue() {
+        let remote = Arc::new(MergeQueueMock { entries: vec![] });
+        let cli_args = MergeQueueListCliArgs::builder()
+            .branch(None)
+            .get_args(GetRemoteCliArgs::default())
+            .build()
+            .unwrap();
+        let body_args = MergeQueueListBodyArgs::builder()
+            .branch("main".to_string())
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        list_merge_queue(remote, body_args, cli_args, &mut writer).unwrap();
+        assert_eq!(
+            "No merge requests queued for main\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_add_and_remove_from_merge_queue() {
+        let remote = Arc::new(MergeQueueMock { entries: vec![] });
+        let mut writer = Vec::new();
+        enqueue(remote.clone(), 59, &mut writer).unwrap();
+        dequeue(remote, 59, &mut writer).unwrap();
+        assert_eq!(
+            "Merge request 59 added to the merge queue\n\
+             Merge request 59 removed from the merge queue\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    struct MergeRequestLabelsMock {
+        labels: Vec<String>,
+    }
+
+    impl MergeRequestLabels for MergeRequestLabelsMock {
+        fn add(&self, _id: i64, labels: &[String]) -> Result<Vec<String>> {
+            let mut current = self.labels.clone();
+            current.extend(labels.iter().cloned());
+            Ok(current)
+        }
+        fn remove(&self, _id: i64, labels: &[String]) -> Result<Vec<String>> {
+            Ok(self
+                .labels
+                .iter()
+                .filter(|label| !labels.contains(label))
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove_merge_request_labels() {
+        let remote = Arc::new(MergeRequestLabelsMock {
+            labels: vec!["bug".to_string()],
+        });
+        let mut writer = Vec::new();
+        update_labels(
+            remote.clone(),
+            MergeRequestLabelOptions::Add {
+                id: 59,
+                labels: vec!["backend".to_string()],
+            },
+            &mut writer,
+        )
+        .unwrap();
+        update_labels(
+            remote,
+            MergeRequestLabelOptions::Remove {
+                id: 59,
+                labels: vec!["bug".to_string()],
+            },
+            &mut writer,
+        )
+        .unwrap();
+        assert_eq!(
+            "Labels of merge request 59: bug, backend\n\
+             Merge request 59 has no labels\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[derive(Default)]
+    struct MergeRequestChecksMock {
+        checks: Vec<MergeRequestCheck>,
+        unmet: Vec<String>,
+    }
+
+    impl MergeRequestChecks for MergeRequestChecksMock {
+        fn list(&self, _id: i64) -> Result<Vec<MergeRequestCheck>> {
+            Ok(self.checks.clone())
+        }
+        fn unmet_requirements(&self, _id: i64) -> Result<Vec<String>> {
+            Ok(self.unmet.clone())
+        }
+    }
+
+    #[test]
+    fn test_list_merge_request_checks() {
+        let remote = Arc::new(MergeRequestChecksMock {
+            checks: vec![MergeRequestCheck::builder()
+                .id(1)
+                .name("test".to_string())
+                .kind("job".to_string())
+                .state(CheckState::Fail)
+                .web_url("https://gitlab.com/jobs/1".to_string())
+                .build()
+                .unwrap()],
+            ..Default::default()
+        });
+        let cli_args = MergeRequestGetCliArgs::builder()
+            .id(33)
+            .get_args(GetRemoteCliArgs::default())
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        list_checks(remote, cli_args, &mut writer).unwrap();
+        assert_eq!(
+            "Name|Kind|State|URL\ntest|job|fail|https://gitlab.com/jobs/1\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    struct MergeRequestApprovalsMock {
+        status: ApprovalStatus,
+    }
+
+    impl MergeRequestApprovals for MergeRequestApprovalsMock {
+        fn approvals(&self, _id: i64) -> Result<ApprovalStatus> {
+            Ok(self.status.clone())
+        }
+    }
+
+    #[test]
+    fn test_list_merge_request_approvers() {
+        let remote = Arc::new(MergeRequestApprovalsMock {
+            status: ApprovalStatus::builder()
+                .approved(false)
+                .approvals_left(Some(1))
+                .approved_by(vec!["alice".to_string()])
+                .pending(vec!["bob".to_string(), "carol".to_string()])
+                .build()
+                .unwrap(),
+        });
+        let cli_args = MergeRequestGetCliArgs::builder()
+            .id(33)
+            .get_args(GetRemoteCliArgs::default())
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        list_approvers(remote, cli_args, &mut writer).unwrap();
+        assert_eq!(
+            "Approved|Approvals left|Approved by|Pending
+false|1|alice|bob, carol
+",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    struct MergeRequestDiffMock;
+
+    impl MergeRequestDiff for MergeRequestDiffMock {
+        fn diff(&self, id: i64) -> Result<String> {
+            Ok(format!(
+                "diff --git a/README.md b/README.md\n\
+                 --- a/README.md\n\
+                 +++ b/README.md\n\
+                 @@ -1 +1 @@\n\
+                 -Merge request\n\
+                 +Merge request {}\n",
+                id
+            ))
+        }
+    }
+
+    #[test]
+    fn test_show_merge_request_diff() {
+        let mut writer = Vec::new();
+        show_diff(Arc::new(MergeRequestDiffMock), 7, &mut writer).unwrap();
+        assert!(String::from_utf8(writer)
+            .unwrap()
+            .ends_with("-Merge request\n+Merge request 7\n"));
+    }
+
+    struct ReviewDiffMock {
+        head: ReviewedHead,
+    }
+
+    impl MergeRequestReviewDiff for ReviewDiffMock {
+        fn reviewed_head(&self, _id: i64, username: &str) -> Result<ReviewedHead> {
+            assert_eq!("alice", username);
+            Ok(self.head.clone())
+        }
+
+        fn compare(&self, from_sha: &str, to_sha: &str) -> Result<String> {
+            Ok(format!("diff {}..{}\n", from_sha, to_sha))
+        }
+    }
+
+    fn changed_since_review(reviewed_sha: Option<&str>) -> Result<String> {
+        let remote = ReviewDiffMock {
+            head: ReviewedHead::builder()
+                .head_sha("0123456789abcdef".to_string())
+                .reviewed_sha(reviewed_sha.map(String::from))
+                .build()
+                .unwrap(),
+        };
+        let user = UserInfoMock {
+            username: "alice".to_string(),
+        };
+        let mut writer = Vec::new();
+        show_changed_since_review(Arc::new(remote), Arc::new(user), 7, &mut writer)?;
+        Ok(String::from_utf8(writer).unwrap())
+    }
+
+    #[test]
+    fn test_changed_since_review_shows_incremental_diff() {
+        assert_eq!(
+            "diff fedcba9876543210..0123456789abcdef\n",
+            changed_since_review(Some("fedcba9876543210")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_changed_since_review_without_new_commits() {
+        assert_eq!(
+            "No changes in merge request 7 since your last review at 01234567\n",
+            changed_since_review(Some("0123456789abcdef")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_changed_since_review_requires_a_review() {
+        let err = changed_since_review(None).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("alice has not reviewed merge request 7 yet"));
+    }
+
+    struct RebaseMock {
+        progress: Mutex<Vec<RebaseProgress>>,
+    }
+
+    impl RebaseMock {
+        /// Progress reported on each poll, in order.
+        fn new(progress: Vec<RebaseProgress>) -> Self {
+            RebaseMock {
+                progress: Mutex::new(progress.into_iter().rev().collect()),
+            }
+        }
+    }
+
+    impl MergeRequestRebase for RebaseMock {
+        fn rebase(&self, _id: i64) -> Result<String> {
+            Ok("1111111".to_string())
+        }
+
+        fn rebase_progress(&self, _id: i64, from_sha: &str) -> Result<RebaseProgress> {
+            assert_eq!("1111111", from_sha);
+            let mut progress = self.progress.lock().unwrap();
+            Ok(progress.pop().unwrap_or(RebaseProgress::InProgress))
+        }
+    }
+
+    fn rebase_cli_args(timeout: u64) -> MergeRequestRebaseCliArgs {
+        MergeRequestRebaseCliArgs::builder()
+            .id(7)
+            .timeout(timeout)
+            .poll_interval(Milliseconds::new(0))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rebase_waits_until_done_and_reports_pipeline() {
+        let remote = Arc::new(RebaseMock::new(vec![
+            RebaseProgress::InProgress,
+            RebaseProgress::InProgress,
+            RebaseProgress::Done {
+                sha: "2222222".to_string(),
+                pipeline_url: Some("https://gitlab.com/owner/repo/-/pipelines/9".to_string()),
+            },
+        ]));
+        let mut writer = Vec::new();
+        rebase(remote, rebase_cli_args(300), &mut writer).unwrap();
+        assert_eq!(
+            "Merge request 7 rebased, new head 2222222\n\
+             Pipeline: https://gitlab.com/owner/repo/-/pipelines/9\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rebase_done_without_pipeline_yet() {
+        let remote = Arc::new(RebaseMock::new(vec![RebaseProgress::Done {
+            sha: "2222222".to_string(),
+            pipeline_url: None,
+        }]));
+        let mut writer = Vec::new();
+        rebase(remote, rebase_cli_args(300), &mut writer).unwrap();
+        assert!(String::from_utf8(writer)
+            .unwrap()
+            .ends_with("No pipeline has been created for the new head yet\n"));
+    }
+
+    #[test]
+    fn test_rebase_failure_is_error() {
+        let remote = Arc::new(RebaseMock::new(vec![RebaseProgress::Failed(
+            "Rebase failed: conflicts".to_string(),
+        )]));
+        let err = rebase(remote, rebase_cli_args(300), Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("Rebase failed: conflicts"));
+    }
+
+    #[test]
+    fn test_rebase_gives_up_after_timeout() {
+        let remote = Arc::new(RebaseMock::new(vec![]));
+        let err = rebase(remote, rebase_cli_args(0), Vec::new()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Rebase of merge request 7 did not finish in 0 seconds"));
+    }
+
+    #[test]
+    fn test_rebase_stops_waiting_once_cancelled() {
+        let remote = Arc::new(RebaseMock::new(vec![]));
+        cancel::cancel();
+        let err = rebase(remote, rebase_cli_args(300), Vec::new()).unwrap_err();
+        assert_eq!(
+            "Cancelled: waiting for the rebase of merge request 7",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_merge_blocked_by_unmet_requirements_does_not_merge() {
+        let remote = Arc::new(MergeRequestRemoteMock::builder().build().unwrap());
+        let checks = Arc::new(MergeRequestChecksMock {
+            unmet: vec![
+                "An approving review is required".to_string(),
+                "Required check test: pending".to_string(),
+            ],
+            ..Default::default()
+        });
+        let mut writer = Vec::new();
+        let result = merge(remote, checks, 33, &mut writer);
+        assert!(result.is_err());
+        assert_eq!(
+            "Merge request 33 does not meet the merge requirements:\n\
+             \x20 - An approving review is required\n\
+             \x20 - Required check test: pending\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_merge_when_requirements_are_met() {
+        let merge_response = MergeRequestResponse::builder()
+            .id(33)
+            .web_url("https://gitlab.com/owner/repo/-/merge_requests/33".to_string())
+            .build()
+            .unwrap();
+        let remote = Arc::new(
+            MergeRequestRemoteMock::builder()
+                .merge_requests(vec![merge_response])
+                .build()
+                .unwrap(),
+        );
+        let checks = Arc::new(MergeRequestChecksMock::default());
+        let mut writer = Vec::new();
+        merge(remote, checks, 33, &mut writer).unwrap();
+        assert_eq!(
+            "Merge request merged: https://gitlab.com/owner/repo/-/merge_requests/33\n",
+            String::from_utf8(writer).unwrap(),
+        );
+    }
 }
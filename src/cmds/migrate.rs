@@ -0,0 +1,417 @@
+use std::{io::Write, path::Path, sync::Arc};
+
+use crate::{
+    api_traits::MergeRequest,
+    cancel,
+    checkpoint::Checkpoint,
+    cli::migrate::MigrateOptions,
+    config::ConfigProperties,
+    display::{self, Column, DisplayBody},
+    i18n::{self, Message},
+    remote::{
+        self, GetRemoteCliArgs, MergeRequestBodyArgs, MergeRequestListBodyArgs,
+        MergeRequestResponse, MergeRequestState,
+    },
+    Result,
+};
+
+use super::common;
+
+#[derive(Builder)]
+pub struct MigrateMergeRequestCliArgs {
+    pub from: String,
+    pub to: String,
+    pub dry_run: bool,
+    pub refresh_cache: bool,
+    /// Skip the merge requests migrated by a previous interrupted run.
+    #[builder(default)]
+    pub resume: bool,
+}
+
+impl MigrateMergeRequestCliArgs {
+    pub fn builder() -> MigrateMergeRequestCliArgsBuilder {
+        MigrateMergeRequestCliArgsBuilder::default()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MigratedMergeRequest {
+    pub source_id: i64,
+    pub title: String,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub status: String,
+    pub web_url: String,
+}
+
+impl From<MigratedMergeRequest> for DisplayBody {
+    fn from(mr: MigratedMergeRequest) -> DisplayBody {
+        DisplayBody {
+            columns: vec![
+                Column::new("Source ID", mr.source_id.to_string()),
+                Column::new("Title", mr.title),
+                Column::new("Source branch", mr.source_branch),
+                Column::new("Target branch", mr.target_branch),
+                Column::new("Status", mr.status),
+                Column::new("URL", mr.web_url),
+            ],
+        }
+    }
+}
+
+/// Migrate works across two remotes, so each one gets its own configuration
+/// read from the same config file.
+pub fn execute<P: AsRef<Path>>(options: MigrateOptions, config_file: P) -> Result<()> {
+    match options {
+        MigrateOptions::MergeRequests(cli_args) => {
+            let (from_domain, from_path) = common::parse_remote(&cli_args.from)?;
+            let (to_domain, to_path) = common::parse_remote(&cli_args.to)?;
+            let from_config = common::read_config(config_file.as_ref(), &from_domain)?;
+            let to_config = common::read_config(config_file.as_ref(), &to_domain)?;
+            let checkpoint = if cli_args.dry_run {
+                Checkpoint::disabled()
+            } else {
+                Checkpoint::new(
+                    from_config.cache_location(),
+                    &format!("migrate mrs {} {}", cli_args.from, cli_args.to),
+                    cli_args.resume,
+                )?
+            };
+            let source =
+                remote::get_mr(from_domain, from_path, from_config, cli_args.refresh_cache)?;
+            let target = remote::get_mr(to_domain, to_path, to_config, false)?;
+            migrate_merge_requests(source, target, &cli_args, checkpoint, std::io::stdout())
+        }
+    }
+}
+
+/// Opens the source's open merge requests in the target. The ones migrated
+/// are recorded in the checkpoint, so a run resumed after failures or a
+/// cancellation does not open them twice.
+fn migrate_merge_requests<W: Write>(
+    source: Arc<dyn MergeRequest>,
+    target: Arc<dyn MergeRequest>,
+    cli_args: &MigrateMergeRequestCliArgs,
+    mut checkpoint: Checkpoint,
+    mut writer: W,
+) -> Result<()> {
+    let body_args = MergeRequestListBodyArgs::builder()
+        .state(MergeRequestState::Opened)
+        .list_args(None)
+        .assignee_id(None)
+        .build()?;
+    let merge_requests = source.list(body_args)?;
+    if merge_requests.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoMergeRequestsToMigrate))?;
+        return Ok(());
+    }
+    let mut migrated = Vec::new();
+    let mut all_migrated = true;
+    for mr in merge_requests {
+        let mut migrated_mr = MigratedMergeRequest {
+            source_id: mr.id,
+            title: mr.title.clone(),
+            source_branch: mr.source_branch.clone(),
+            target_branch: mr.target_branch.clone(),
+            status: "pending".to_string(),
+            web_url: String::new(),
+        };
+        if checkpoint.is_done(mr.id) {
+            migrated_mr.status = "migrated in a previous run".to_string();
+            migrated.push(migrated_mr);
+            continue;
+        }
+        if cli_args.dry_run {
+            migrated.push(migrated_mr);
+            continue;
+        }
+        if cancel::cancelled() {
+            all_migrated = false;
+            migrated_mr.status = "cancelled".to_string();
+            migrated.push(migrated_mr);
+            continue;
+        }
+        // Branches are expected to exist in the target remote already. A
+        // missing branch fails that single merge request and we carry on with
+        // the rest.
+        match target.open(migrated_body_args(mr)?) {
+            Ok(response) => {
+                checkpoint.record(migrated_mr.source_id)?;
+                migrated_mr.status = "migrated".to_string();
+                migrated_mr.web_url = response.web_url;
+            }
+            Err(err) => {
+                all_migrated = false;
+                migrated_mr.status = format!("failed: {}", err);
+            }
+        }
+        migrated.push(migrated_mr);
+    }
+    display::print(&mut writer, migrated, GetRemoteCliArgs::default())?;
+    if all_migrated {
+        checkpoint.complete()?;
+    }
+    Ok(())
+}
+
+fn migrated_body_args(mr: MergeRequestResponse) -> Result<MergeRequestBodyArgs> {
+    let description = if mr.description.is_empty() {
+        format!("Migrated from {}", mr.web_url)
+    } else {
+        format!("{}\n\nMigrated from {}", mr.description, mr.web_url)
+    };
+    Ok(MergeRequestBodyArgs::builder()
+        .title(mr.title)
+        .description(description)
+        .source_branch(mr.source_branch)
+        .target_branch(mr.target_branch)
+        .labels(mr.labels)
+        .build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::PageCount;
+    use std::sync::Mutex;
+
+    use crate::error;
+    use crate::remote::MergeRequestLimits;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MergeRequestMock {
+        merge_requests: Vec<MergeRequestResponse>,
+        opened: Mutex<Vec<MergeRequestBodyArgs>>,
+        fail_branch: Option<String>,
+    }
+
+    impl MergeRequest for MergeRequestMock {
+        fn open(&self, args: MergeRequestBodyArgs) -> Result<MergeRequestResponse> {
+            if self.fail_branch.as_deref() == Some(args.source_branch.as_str()) {
+                return Err(error::gen("branch not found"));
+            }
+            let url = format!("https://github.com/org/repo/pull/{}", args.source_branch);
+            self.opened.lock().unwrap().push(args);
+            Ok(MergeRequestResponse::builder()
+                .web_url(url)
+                .build()
+                .unwrap())
+        }
+        fn list(&self, _args: MergeRequestListBodyArgs) -> Result<Vec<MergeRequestResponse>> {
+            Ok(self.merge_requests.clone())
+        }
+        fn merge(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn get(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn close(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn reopen(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn num_pages(&self, _args: MergeRequestListBodyArgs) -> Result<Option<PageCount>> {
+            todo!()
+        }
+        fn limits(&self) -> MergeRequestLimits {
+            todo!()
+        }
+        fn set_draft(&self, _id: i64, _draft: bool) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn approve(&self, _id: i64, _comment: Option<&str>) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+    }
+
+    fn source_merge_requests() -> Vec<MergeRequestResponse> {
+        vec![
+            MergeRequestResponse::builder()
+                .id(1)
+                .title("New feature".to_string())
+                .description("Adds a feature".to_string())
+                .source_branch("feature".to_string())
+                .target_branch("main".to_string())
+                .web_url("https://gitlab.com/org/repo/-/merge_requests/1".to_string())
+                .labels(vec!["enhancement".to_string()])
+                .build()
+                .unwrap(),
+            MergeRequestResponse::builder()
+                .id(2)
+                .title("Fix bug".to_string())
+                .source_branch("bugfix".to_string())
+                .target_branch("main".to_string())
+                .web_url("https://gitlab.com/org/repo/-/merge_requests/2".to_string())
+                .build()
+                .unwrap(),
+        ]
+    }
+
+    fn cli_args(dry_run: bool) -> MigrateMergeRequestCliArgs {
+        MigrateMergeRequestCliArgs::builder()
+            .from("gitlab.com/org/repo".to_string())
+            .to("github.com/org/repo".to_string())
+            .dry_run(dry_run)
+            .refresh_cache(false)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_migrate_merge_requests_replays_title_description_labels() {
+        let source = Arc::new(MergeRequestMock {
+            merge_requests: source_merge_requests(),
+            ..Default::default()
+        });
+        let target = Arc::new(MergeRequestMock::default());
+        let mut buf = Vec::new();
+        migrate_merge_requests(
+            source,
+            target.clone(),
+            &cli_args(false),
+            Checkpoint::disabled(),
+            &mut buf,
+        )
+        .unwrap();
+        let opened = target.opened.lock().unwrap();
+        assert_eq!(2, opened.len());
+        assert_eq!("New feature", opened[0].title);
+        assert_eq!(
+            "Adds a feature\n\nMigrated from https://gitlab.com/org/repo/-/merge_requests/1",
+            opened[0].description
+        );
+        assert_eq!("feature", opened[0].source_branch);
+        assert_eq!("main", opened[0].target_branch);
+        assert_eq!(vec!["enhancement".to_string()], opened[0].labels);
+        assert_eq!(
+            "Migrated from https://gitlab.com/org/repo/-/merge_requests/2",
+            opened[1].description
+        );
+        assert_eq!(
+            "Source ID|Title|Source branch|Target branch|Status|URL\n\
+             1|New feature|feature|main|migrated|https://github.com/org/repo/pull/feature\n\
+             2|Fix bug|bugfix|main|migrated|https://github.com/org/repo/pull/bugfix\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_migrate_merge_requests_dry_run_does_not_open() {
+        let source = Arc::new(MergeRequestMock {
+            merge_requests: source_merge_requests(),
+            ..Default::default()
+        });
+        let target = Arc::new(MergeRequestMock::default());
+        let mut buf = Vec::new();
+        migrate_merge_requests(
+            source,
+            target.clone(),
+            &cli_args(true),
+            Checkpoint::disabled(),
+            &mut buf,
+        )
+        .unwrap();
+        assert!(target.opened.lock().unwrap().is_empty());
+        assert_eq!(
+            "Source ID|Title|Source branch|Target branch|Status|URL\n\
+             1|New feature|feature|main|pending|\n\
+             2|Fix bug|bugfix|main|pending|\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_migrate_merge_requests_continues_after_failure() {
+        let source = Arc::new(MergeRequestMock {
+            merge_requests: source_merge_requests(),
+            ..Default::default()
+        });
+        let target = Arc::new(MergeRequestMock {
+            fail_branch: Some("feature".to_string()),
+            ..Default::default()
+        });
+        let mut buf = Vec::new();
+        migrate_merge_requests(
+            source,
+            target.clone(),
+            &cli_args(false),
+            Checkpoint::disabled(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(1, target.opened.lock().unwrap().len());
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("1|New feature|feature|main|failed: branch not found|"));
+        assert!(output.contains("2|Fix bug|bugfix|main|migrated|"));
+    }
+
+    #[test]
+    fn test_migrate_merge_requests_resume_retries_only_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().to_str().unwrap();
+        let source = Arc::new(MergeRequestMock {
+            merge_requests: source_merge_requests(),
+            ..Default::default()
+        });
+        let target = Arc::new(MergeRequestMock {
+            fail_branch: Some("feature".to_string()),
+            ..Default::default()
+        });
+        let checkpoint = Checkpoint::new(location, "migrate", false).unwrap();
+        let mut buf = Vec::new();
+        migrate_merge_requests(
+            source.clone(),
+            target,
+            &cli_args(false),
+            checkpoint,
+            &mut buf,
+        )
+        .unwrap();
+
+        let target = Arc::new(MergeRequestMock::default());
+        let checkpoint = Checkpoint::new(location, "migrate", true).unwrap();
+        let mut buf = Vec::new();
+        migrate_merge_requests(
+            source,
+            target.clone(),
+            &cli_args(false),
+            checkpoint,
+            &mut buf,
+        )
+        .unwrap();
+        let opened = target.opened.lock().unwrap();
+        assert_eq!(1, opened.len());
+        assert_eq!("feature", opened[0].source_branch);
+        assert_eq!(
+            "Source ID|Title|Source branch|Target branch|Status|URL\n\
+             1|New feature|feature|main|migrated|https://github.com/org/repo/pull/feature\n\
+             2|Fix bug|bugfix|main|migrated in a previous run|\n",
+            String::from_utf8(buf).unwrap()
+        );
+        // All migrated, so the checkpoint is gone.
+        let checkpoint = Checkpoint::new(location, "migrate", true).unwrap();
+        assert_eq!(0, checkpoint.num_done());
+    }
+
+    #[test]
+    fn test_migrate_no_open_merge_requests() {
+        let source = Arc::new(MergeRequestMock::default());
+        let target = Arc::new(MergeRequestMock::default());
+        let mut buf = Vec::new();
+        migrate_merge_requests(
+            source,
+            target,
+            &cli_args(false),
+            Checkpoint::disabled(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            "No open merge requests found to migrate.\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+}
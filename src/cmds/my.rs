@@ -1,11 +1,18 @@
-use std::{io::Write, sync::Arc};
+use std::{fs::File, io::Write, path::Path, sync::Arc};
 
 use crate::{
-    api_traits::RemoteProject,
+    api_traits::{MergeRequest, RemoteProject, UserInfo},
     cli::my::MyOptions,
     config::Config,
-    remote::{self, ListRemoteCliArgs, Member},
-    Result,
+    display::{self, Column, DisplayBody},
+    error::{AddContext, GRError},
+    exec,
+    i18n::{self, Message},
+    remote::{
+        self, GetRemoteCliArgs, ListRemoteCliArgs, Member, MergeRequestListBodyArgs,
+        MergeRequestResponse, MergeRequestState,
+    },
+    Cmd, Result,
 };
 
 use super::{
@@ -13,6 +20,51 @@ use super::{
     project::{ProjectListBodyArgs, ProjectListCliArgs},
 };
 
+pub mod dashboard;
+
+#[derive(Builder)]
+pub struct MyAllCliArgs {
+    pub domains: Vec<String>,
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl MyAllCliArgs {
+    pub fn builder() -> MyAllCliArgsBuilder {
+        MyAllCliArgsBuilder::default()
+    }
+}
+
+/// A merge request tagged with the remote it comes from and the role the
+/// user has in it, assignee or reviewer.
+#[derive(Clone)]
+pub struct RemoteMergeRequest {
+    pub remote: String,
+    pub role: String,
+    pub merge_request: MergeRequestResponse,
+}
+
+impl From<RemoteMergeRequest> for DisplayBody {
+    fn from(mr: RemoteMergeRequest) -> DisplayBody {
+        DisplayBody {
+            columns: vec![
+                Column::new("Remote", mr.remote),
+                Column::new("Role", mr.role),
+                Column::new("ID", mr.merge_request.id.to_string()),
+                Column::new("Title", mr.merge_request.title),
+                Column::new("Author", mr.merge_request.author),
+                Column::new("URL", mr.merge_request.web_url),
+                Column::new("Updated at", mr.merge_request.updated_at),
+            ],
+        }
+    }
+}
+
+type UserMergeRequestRemote = (
+    String,
+    Arc<dyn UserInfo + Send + Sync>,
+    Arc<dyn MergeRequest + Send + Sync>,
+);
+
 pub fn execute(
     options: MyOptions,
     config: Arc<Config>,
@@ -37,13 +89,120 @@ pub fn execute(
                 .from_to_page(from_to_args)
                 .user(Some(user))
                 .stars(cli_args.stars)
+                .filters(cli_args.filters.clone())
                 .build()?;
             if cli_args.list_args.num_pages {
                 return common::num_project_pages(remote, body_args, std::io::stdout());
             }
-            list_user_projects(remote, body_args, cli_args, std::io::stdout())
+            let output = cli_args.list_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_user_projects(remote, body_args, cli_args, writer)
+            })
         }
+        // Queries several domains, each one with its own configuration.
+        // Dispatched from main before reading the current repository config.
+        MyOptions::All(_) | MyOptions::Dashboard(_) => Err(GRError::ApplicationError(
+            "gr my all and gr my dash query several domains, \
+             not the remote of the current repository"
+                .to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Lists assigned and review requested merge requests for all the given
+/// domains. If no domains are provided, all the domains found in the
+/// configuration file are queried.
+pub fn execute_all<P: AsRef<Path>>(cli_args: MyAllCliArgs, config_file: P) -> Result<()> {
+    let config_file = config_file.as_ref();
+    let mut remotes: Vec<UserMergeRequestRemote> = Vec::new();
+    for domain in domains(&cli_args.domains, config_file)? {
+        let config = common::read_config(config_file, &domain)?;
+        let refresh_cache = cli_args.get_args.refresh_cache;
+        // Listing merge requests for the auth user does not depend on a
+        // project path.
+        let user_remote = remote::get_auth_user(
+            domain.clone(),
+            "".to_string(),
+            config.clone(),
+            refresh_cache,
+        )?;
+        let mr_remote = remote::get_mr(domain.clone(), "".to_string(), config, refresh_cache)?;
+        remotes.push((domain, user_remote, mr_remote));
+    }
+    let output = cli_args.get_args.output.clone();
+    common::write_output(output.as_deref(), |writer| {
+        list_all_merge_requests(remotes, cli_args, writer)
+    })
+}
+
+/// The given domains or, if none, all the domains in the configuration file.
+fn domains(domains: &[String], config_file: &Path) -> Result<Vec<String>> {
+    if !domains.is_empty() {
+        return Ok(domains.to_vec());
     }
+    let f = File::open(config_file).err_context(GRError::ConfigurationError(format!(
+        "Cannot open config file {}",
+        config_file.display()
+    )))?;
+    Config::domains(f)
+}
+
+fn list_all_merge_requests<W: Write>(
+    remotes: Vec<UserMergeRequestRemote>,
+    cli_args: MyAllCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let cmds = remotes
+        .into_iter()
+        .enumerate()
+        .map(|(position, (domain, user_remote, mr_remote))| {
+            let cmd = move || -> Result<(usize, Vec<RemoteMergeRequest>)> {
+                let user = user_remote.get()?;
+                let assigned = MergeRequestListBodyArgs::builder()
+                    .state(MergeRequestState::Opened)
+                    .list_args(None)
+                    .assignee_id(Some(user.id))
+                    .build()?;
+                let review_requested = MergeRequestListBodyArgs::builder()
+                    .state(MergeRequestState::Opened)
+                    .list_args(None)
+                    .assignee_id(None)
+                    .reviewer(Some(user))
+                    .build()?;
+                let mut merge_requests = Vec::new();
+                for (role, body_args) in [("assignee", assigned), ("reviewer", review_requested)] {
+                    for merge_request in mr_remote.list(body_args)? {
+                        merge_requests.push(RemoteMergeRequest {
+                            remote: domain.clone(),
+                            role: role.to_string(),
+                            merge_request,
+                        });
+                    }
+                }
+                Ok((position, merge_requests))
+            };
+            Box::new(cmd) as Cmd<(usize, Vec<RemoteMergeRequest>)>
+        })
+        .collect::<Vec<_>>();
+    let mut results = Vec::new();
+    for result in exec::parallel_stream(cmds) {
+        // bail on first error found
+        results.push(result?);
+    }
+    // Results arrive in completion order. Keep the order in which domains
+    // were requested.
+    results.sort_by_key(|(position, _)| *position);
+    let merge_requests = results
+        .into_iter()
+        .flat_map(|(_, merge_requests)| merge_requests)
+        .collect::<Vec<_>>();
+    if merge_requests.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoResourcesFound))?;
+        return Ok(());
+    }
+    display::print(&mut writer, merge_requests, cli_args.get_args)?;
+    Ok(())
 }
 
 fn get_user(
@@ -74,8 +233,9 @@ fn list_user_projects<W: Write>(
 #[cfg(test)]
 mod tests {
     use crate::cmds::project::ProjectListCliArgs;
+    use crate::io::PageCount;
 
-    use self::remote::{ListRemoteCliArgs, Project};
+    use self::remote::{ListRemoteCliArgs, MergeRequestLimits, Project};
 
     use super::*;
 
@@ -106,7 +266,7 @@ mod tests {
             todo!()
         }
 
-        fn num_pages(&self, _args: ProjectListBodyArgs) -> Result<Option<u32>> {
+        fn num_pages(&self, _args: ProjectListBodyArgs) -> Result<Option<PageCount>> {
             todo!()
         }
     }
@@ -144,4 +304,126 @@ mod tests {
             String::from_utf8(buffer).unwrap()
         );
     }
+
+    struct UserInfoMock {
+        user: Member,
+    }
+
+    impl UserInfo for UserInfoMock {
+        fn get(&self) -> Result<Member> {
+            Ok(self.user.clone())
+        }
+    }
+
+    struct MergeRequestMock {
+        assigned: Vec<MergeRequestResponse>,
+        review_requested: Vec<MergeRequestResponse>,
+    }
+
+    impl MergeRequest for MergeRequestMock {
+        fn list(&self, args: MergeRequestListBodyArgs) -> Result<Vec<MergeRequestResponse>> {
+            if args.reviewer.is_some() {
+                return Ok(self.review_requested.clone());
+            }
+            Ok(self.assigned.clone())
+        }
+        fn open(&self, _args: crate::remote::MergeRequestBodyArgs) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn merge(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn get(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn close(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn reopen(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn num_pages(&self, _args: MergeRequestListBodyArgs) -> Result<Option<PageCount>> {
+            todo!()
+        }
+        fn limits(&self) -> MergeRequestLimits {
+            todo!()
+        }
+        fn set_draft(&self, _id: i64, _draft: bool) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn approve(&self, _id: i64, _comment: Option<&str>) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+    }
+
+    fn remote(
+        domain: &str,
+        assigned: Vec<MergeRequestResponse>,
+        review_requested: Vec<MergeRequestResponse>,
+    ) -> UserMergeRequestRemote {
+        let user = Member::builder()
+            .id(1)
+            .name("jordi".to_string())
+            .username("jordilin".to_string())
+            .build()
+            .unwrap();
+        (
+            domain.to_string(),
+            Arc::new(UserInfoMock { user }),
+            Arc::new(MergeRequestMock {
+                assigned,
+                review_requested,
+            }),
+        )
+    }
+
+    fn merge_request(id: i64, title: &str) -> MergeRequestResponse {
+        MergeRequestResponse::builder()
+            .id(id)
+            .title(title.to_string())
+            .author("author".to_string())
+            .web_url(format!("https://remote/mr/{}", id))
+            .updated_at("2024-01-01".to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_list_all_merge_requests_across_remotes() {
+        let remotes = vec![
+            remote(
+                "gitlab.com",
+                vec![merge_request(1, "Assigned MR")],
+                vec![merge_request(2, "Review MR")],
+            ),
+            remote("github.com", vec![], vec![merge_request(3, "Review PR")]),
+        ];
+        let cli_args = MyAllCliArgs::builder()
+            .domains(vec![])
+            .get_args(GetRemoteCliArgs::default())
+            .build()
+            .unwrap();
+        let mut buffer = Vec::new();
+        list_all_merge_requests(remotes, cli_args, &mut buffer).unwrap();
+        assert_eq!(
+            "Remote|Role|ID|Title|Author|URL|Updated at\n\
+             gitlab.com|assignee|1|Assigned MR|author|https://remote/mr/1|2024-01-01\n\
+             gitlab.com|reviewer|2|Review MR|author|https://remote/mr/2|2024-01-01\n\
+             github.com|reviewer|3|Review PR|author|https://remote/mr/3|2024-01-01\n",
+            String::from_utf8(buffer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list_all_merge_requests_none_found() {
+        let remotes = vec![remote("gitlab.com", vec![], vec![])];
+        let cli_args = MyAllCliArgs::builder()
+            .domains(vec![])
+            .get_args(GetRemoteCliArgs::default())
+            .build()
+            .unwrap();
+        let mut buffer = Vec::new();
+        list_all_merge_requests(remotes, cli_args, &mut buffer).unwrap();
+        assert_eq!("No resources found.\n", String::from_utf8(buffer).unwrap());
+    }
 }
@@ -0,0 +1,429 @@
+//! `gr my dash`. Summary of the merge requests assigned to the user, the ones
+//! awaiting their review and their failing pipelines across remotes.
+
+use std::{io::Write, path::Path, sync::Arc};
+
+use crate::{
+    api_traits::{FailingPipelines, MergeRequest, UserInfo},
+    display::{self, Column, DisplayBody},
+    exec,
+    i18n::{self, Message},
+    remote::{
+        self, GetRemoteCliArgs, Member, MergeRequestListBodyArgs, MergeRequestResponse,
+        MergeRequestState,
+    },
+    Cmd, Result,
+};
+
+use crate::cmds::common;
+
+#[derive(Builder)]
+pub struct DashboardCliArgs {
+    pub domains: Vec<String>,
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl DashboardCliArgs {
+    pub fn builder() -> DashboardCliArgsBuilder {
+        DashboardCliArgsBuilder::default()
+    }
+}
+
+/// Pipeline of the head commit of an open merge request that did not pass.
+#[derive(Builder, Clone, Debug)]
+pub struct FailingPipeline {
+    /// Path of the project the merge request belongs to.
+    pub project: String,
+    pub merge_request_id: i64,
+    pub title: String,
+    pub status: String,
+    pub web_url: String,
+}
+
+impl FailingPipeline {
+    pub fn builder() -> FailingPipelineBuilder {
+        FailingPipelineBuilder::default()
+    }
+}
+
+#[derive(Clone)]
+struct DashboardMergeRequest {
+    remote: String,
+    merge_request: MergeRequestResponse,
+}
+
+impl From<DashboardMergeRequest> for DisplayBody {
+    fn from(mr: DashboardMergeRequest) -> DisplayBody {
+        DisplayBody::new(vec![
+            Column::new("Remote", mr.remote),
+            Column::new("ID", mr.merge_request.id.to_string()),
+            Column::new("Title", mr.merge_request.title),
+            Column::new("Author", mr.merge_request.author),
+            Column::new("URL", mr.merge_request.web_url),
+            Column::new("Updated at", mr.merge_request.updated_at),
+        ])
+    }
+}
+
+#[derive(Clone)]
+struct DashboardPipeline {
+    remote: String,
+    pipeline: FailingPipeline,
+}
+
+impl From<DashboardPipeline> for DisplayBody {
+    fn from(pp: DashboardPipeline) -> DisplayBody {
+        DisplayBody::new(vec![
+            Column::new("Remote", pp.remote),
+            Column::new("Project", pp.pipeline.project),
+            Column::new("MR", pp.pipeline.merge_request_id.to_string()),
+            Column::new("Title", pp.pipeline.title),
+            Column::new("Status", pp.pipeline.status),
+            Column::new("URL", pp.pipeline.web_url),
+        ])
+    }
+}
+
+/// Remotes of a domain the dashboard is gathered from.
+struct DashboardRemote {
+    domain: String,
+    user: Arc<dyn UserInfo + Send + Sync>,
+    merge_requests: Arc<dyn MergeRequest + Send + Sync>,
+    pipelines: Arc<dyn FailingPipelines + Send + Sync>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Assigned,
+    Review,
+    Failing,
+}
+
+impl Section {
+    fn title(&self) -> &'static str {
+        match self {
+            Section::Assigned => "Assigned to me",
+            Section::Review => "Awaiting my review",
+            Section::Failing => "Failing pipelines",
+        }
+    }
+}
+
+const SECTIONS: [Section; 3] = [Section::Assigned, Section::Review, Section::Failing];
+
+enum SectionItems {
+    MergeRequests(Vec<DashboardMergeRequest>),
+    Pipelines(Vec<DashboardPipeline>),
+}
+
+/// Queries all the given domains, or the ones in the configuration file if
+/// none are given.
+pub fn execute<P: AsRef<Path>>(cli_args: DashboardCliArgs, config_file: P) -> Result<()> {
+    let config_file = config_file.as_ref();
+    let refresh_cache = cli_args.get_args.refresh_cache;
+    let mut remotes = Vec::new();
+    for domain in super::domains(&cli_args.domains, config_file)? {
+        let config = common::read_config(config_file, &domain)?;
+        // None of the queries depend on a project path.
+        let path = "".to_string();
+        remotes.push(DashboardRemote {
+            user: remote::get_auth_user(domain.clone(), path.clone(), config.clone(), false)?,
+            merge_requests: remote::get_mr(
+                domain.clone(),
+                path.clone(),
+                config.clone(),
+                refresh_cache,
+            )?,
+            pipelines: remote::get_failing_pipelines(domain.clone(), path, config, refresh_cache)?,
+            domain,
+        });
+    }
+    let output = cli_args.get_args.output.clone();
+    common::write_output(output.as_deref(), |writer| {
+        show_dashboard(remotes, cli_args, writer)
+    })
+}
+
+fn show_dashboard<W: Write>(
+    remotes: Vec<DashboardRemote>,
+    cli_args: DashboardCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    // Users first, as every query is about them.
+    let user_cmds: Vec<Cmd<(usize, Result<Member>)>> = remotes
+        .iter()
+        .enumerate()
+        .map(|(position, remote)| {
+            let user = remote.user.clone();
+            Box::new(move || Ok((position, user.get()))) as Cmd<_>
+        })
+        .collect();
+    let mut users: Vec<(usize, Result<Member>)> = exec::parallel_stream(user_cmds)
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    users.sort_by_key(|(position, _)| *position);
+    // A remote that fails is reported in its section, the others are shown
+    // anyway. One that cannot tell who the user is fails in all of them.
+    let mut results = Vec::new();
+    let mut cmds: Vec<Cmd<SectionResult>> = Vec::new();
+    for ((position, user), remote) in users.into_iter().zip(remotes) {
+        for section in SECTIONS {
+            let domain = remote.domain.clone();
+            let user = match &user {
+                Ok(user) => user.clone(),
+                Err(err) => {
+                    results.push((section, position, domain, Err(err.to_string())));
+                    continue;
+                }
+            };
+            let merge_requests = remote.merge_requests.clone();
+            let pipelines = remote.pipelines.clone();
+            cmds.push(Box::new(move || {
+                let items = fetch_section(section, &domain, user, merge_requests, pipelines)
+                    .map_err(|err| err.to_string());
+                Ok((section, position, domain, items))
+            }));
+        }
+    }
+    for result in exec::parallel_stream(cmds) {
+        results.push(result?);
+    }
+    // Results arrive in completion order. Keep the order in which domains
+    // were requested.
+    results.sort_by_key(|(section, position, _, _)| (*section as usize, *position));
+    for section in SECTIONS {
+        if section as usize > 0 {
+            writeln!(writer)?;
+        }
+        let mut errors = Vec::new();
+        let mut merge_requests = Vec::new();
+        let mut pipelines = Vec::new();
+        let (current, rest): (Vec<_>, Vec<_>) =
+            results.into_iter().partition(|(s, _, _, _)| *s == section);
+        results = rest;
+        for (_, _, domain, items) in current {
+            match items {
+                Ok(SectionItems::MergeRequests(items)) => merge_requests.extend(items),
+                Ok(SectionItems::Pipelines(items)) => pipelines.extend(items),
+                Err(err) => errors.push(format!("Could not fetch from {}: {}", domain, err)),
+            }
+        }
+        let count = merge_requests.len() + pipelines.len();
+        writeln!(writer, "{} ({})", section.title(), count)?;
+        for error in errors {
+            writeln!(writer, "{}", error)?;
+        }
+        if !merge_requests.is_empty() {
+            display::print(&mut writer, merge_requests, cli_args.get_args.clone())?;
+        } else if !pipelines.is_empty() {
+            display::print(&mut writer, pipelines, cli_args.get_args.clone())?;
+        } else {
+            writeln!(writer, "{}", i18n::text(Message::NothingHere))?;
+        }
+    }
+    Ok(())
+}
+
+type SectionResult = (
+    Section,
+    usize,
+    String,
+    std::result::Result<SectionItems, String>,
+);
+
+fn fetch_section(
+    section: Section,
+    domain: &str,
+    user: Member,
+    merge_requests: Arc<dyn MergeRequest + Send + Sync>,
+    pipelines: Arc<dyn FailingPipelines + Send + Sync>,
+) -> Result<SectionItems> {
+    let mut body_args = MergeRequestListBodyArgs::builder();
+    body_args.state(MergeRequestState::Opened).list_args(None);
+    let body_args = match section {
+        Section::Assigned => body_args.assignee_id(Some(user.id)).build()?,
+        Section::Review => body_args.assignee_id(None).reviewer(Some(user)).build()?,
+        Section::Failing => {
+            let items = pipelines
+                .failing(&user)?
+                .into_iter()
+                .map(|pipeline| DashboardPipeline {
+                    remote: domain.to_string(),
+                    pipeline,
+                })
+                .collect();
+            return Ok(SectionItems::Pipelines(items));
+        }
+    };
+    let items = merge_requests
+        .list(body_args)?
+        .into_iter()
+        .map(|merge_request| DashboardMergeRequest {
+            remote: domain.to_string(),
+            merge_request,
+        })
+        .collect();
+    Ok(SectionItems::MergeRequests(items))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::PageCount;
+    use crate::{
+        error,
+        remote::{MergeRequestBodyArgs, MergeRequestLimits},
+    };
+
+    use super::*;
+
+    struct UserInfoMock {
+        username: Option<&'static str>,
+    }
+
+    impl UserInfo for UserInfoMock {
+        fn get(&self) -> Result<Member> {
+            let Some(username) = self.username else {
+                return Err(error::gen("401 Unauthorized"));
+            };
+            Ok(Member::builder()
+                .id(1)
+                .name("Jane Doe".to_string())
+                .username(username.to_string())
+                .build()
+                .unwrap())
+        }
+    }
+
+    struct MergeRequestMock;
+
+    impl MergeRequest for MergeRequestMock {
+        fn list(&self, args: MergeRequestListBodyArgs) -> Result<Vec<MergeRequestResponse>> {
+            let (id, title) = match args.reviewer {
+                Some(reviewer) => (2, format!("Review for {}", reviewer.username)),
+                None => (1, "Assigned".to_string()),
+            };
+            Ok(vec![MergeRequestResponse::builder()
+                .id(id)
+                .title(title)
+                .author("author".to_string())
+                .web_url(format!("https://remote/mr/{}", id))
+                .updated_at("2024-01-01".to_string())
+                .build()
+                .unwrap()])
+        }
+        fn open(&self, _args: MergeRequestBodyArgs) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn merge(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn get(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn close(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn reopen(&self, _id: i64) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn approve(&self, _id: i64, _comment: Option<&str>) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn set_draft(&self, _id: i64, _draft: bool) -> Result<MergeRequestResponse> {
+            todo!()
+        }
+        fn num_pages(&self, _args: MergeRequestListBodyArgs) -> Result<Option<PageCount>> {
+            todo!()
+        }
+        fn limits(&self) -> MergeRequestLimits {
+            todo!()
+        }
+    }
+
+    struct FailingPipelinesMock {
+        pipelines: Vec<FailingPipeline>,
+    }
+
+    impl FailingPipelines for FailingPipelinesMock {
+        fn failing(&self, _user: &Member) -> Result<Vec<FailingPipeline>> {
+            Ok(self.pipelines.clone())
+        }
+    }
+
+    fn remote(
+        domain: &str,
+        username: Option<&'static str>,
+        pipelines: Vec<FailingPipeline>,
+    ) -> DashboardRemote {
+        DashboardRemote {
+            domain: domain.to_string(),
+            user: Arc::new(UserInfoMock { username }),
+            merge_requests: Arc::new(MergeRequestMock),
+            pipelines: Arc::new(FailingPipelinesMock { pipelines }),
+        }
+    }
+
+    fn cli_args() -> DashboardCliArgs {
+        DashboardCliArgs::builder()
+            .domains(vec![])
+            .get_args(GetRemoteCliArgs::default())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dashboard_groups_sections_across_remotes() {
+        let pipeline = FailingPipeline::builder()
+            .project("jdoe/gr".to_string())
+            .merge_request_id(3)
+            .title("Fix build".to_string())
+            .status("failed".to_string())
+            .web_url("https://remote/pipelines/9".to_string())
+            .build()
+            .unwrap();
+        let remotes = vec![
+            remote("gitlab.com", Some("jdoe"), vec![pipeline]),
+            remote("github.com", Some("janed"), vec![]),
+        ];
+        let mut buffer = Vec::new();
+        show_dashboard(remotes, cli_args(), &mut buffer).unwrap();
+        assert_eq!(
+            "Assigned to me (2)\n\
+             Remote|ID|Title|Author|URL|Updated at\n\
+             gitlab.com|1|Assigned|author|https://remote/mr/1|2024-01-01\n\
+             github.com|1|Assigned|author|https://remote/mr/1|2024-01-01\n\
+             \n\
+             Awaiting my review (2)\n\
+             Remote|ID|Title|Author|URL|Updated at\n\
+             gitlab.com|2|Review for jdoe|author|https://remote/mr/2|2024-01-01\n\
+             github.com|2|Review for janed|author|https://remote/mr/2|2024-01-01\n\
+             \n\
+             Failing pipelines (1)\n\
+             Remote|Project|MR|Title|Status|URL\n\
+             gitlab.com|jdoe/gr|3|Fix build|failed|https://remote/pipelines/9\n",
+            String::from_utf8(buffer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_dashboard_reports_remotes_that_fail() {
+        let remotes = vec![
+            remote("gitlab.com", None, vec![]),
+            remote("github.com", Some("janed"), vec![]),
+        ];
+        let mut buffer = Vec::new();
+        show_dashboard(remotes, cli_args(), &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with(
+            "Assigned to me (1)\n\
+             Could not fetch from gitlab.com: 401 Unauthorized\n\
+             Remote|ID|Title|Author|URL|Updated at\n\
+             github.com|1|Assigned|author|https://remote/mr/1|2024-01-01\n"
+        ));
+        assert!(output.ends_with(
+            "Failing pipelines (0)\n\
+             Could not fetch from gitlab.com: 401 Unauthorized\n\
+             Nothing here.\n"
+        ));
+    }
+}
@@ -1,22 +1,36 @@
-use crate::api_traits::RemoteProject;
-use crate::cli::project::ProjectOptions;
-use crate::config::Config;
-use crate::display;
+use crate::api_traits::{
+    CommitApi, ProjectAccessRequests, ProjectApprovals, ProjectAudit, ProjectBranchProtection,
+    ProjectFile, ProjectPushRules, ProjectRulesets, ProjectTemplate, ProjectVariables,
+    RemoteProject, Timestamp,
+};
+use crate::cli::project::{
+    AccessRequestOptions, ApprovalSettingsOptions, BranchProtectionOptions, CommitOptions,
+    MemberOptions, ProjectOptions, PushRulesOptions, RulesetOptions, VariableOptions,
+};
+use crate::config::{Config, ConfigProperties};
+use crate::dialog;
+use crate::display::{self, Column, DisplayBody};
 use crate::error;
+use crate::i18n::{self, Message};
 use crate::io::CmdInfo;
 use crate::remote::ListBodyArgs;
 use crate::remote::ListRemoteCliArgs;
 use crate::remote::Member;
 use crate::remote::{self, GetRemoteCliArgs};
-use crate::Result;
-use std::io::Write;
+use crate::{api_defaults, exec, Cmd, Result};
+use std::io::{IsTerminal, Read, Write};
+use std::path::Path;
 use std::sync::Arc;
 
+use super::common;
+
 #[derive(Builder)]
 pub struct ProjectListCliArgs {
     pub list_args: ListRemoteCliArgs,
     #[builder(default)]
     pub stars: bool,
+    #[builder(default)]
+    pub filters: ProjectListFilters,
 }
 
 impl ProjectListCliArgs {
@@ -31,39 +45,1450 @@ pub struct ProjectListBodyArgs {
     pub user: Option<Member>,
     #[builder(default)]
     pub stars: bool,
+    #[builder(default)]
+    pub filters: ProjectListFilters,
+}
+
+impl ProjectListBodyArgs {
+    pub fn builder() -> ProjectListBodyArgsBuilder {
+        ProjectListBodyArgsBuilder::default()
+    }
+}
+
+/// Narrows down the projects of the authenticated user.
+#[derive(Builder, Clone, Default)]
+pub struct ProjectListFilters {
+    /// Projects owned by the user.
+    #[builder(default)]
+    pub owned: bool,
+    /// Projects the user is a member or collaborator of.
+    #[builder(default)]
+    pub member: bool,
+    /// Archived projects only.
+    #[builder(default)]
+    pub archived: bool,
+    /// Projects whose name matches the term.
+    #[builder(default)]
+    pub search: Option<String>,
+}
+
+impl ProjectListFilters {
+    pub fn builder() -> ProjectListFiltersBuilder {
+        ProjectListFiltersBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct ProjectMetadataGetCliArgs {
+    pub id: Option<i64>,
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl ProjectMetadataGetCliArgs {
+    pub fn builder() -> ProjectMetadataGetCliArgsBuilder {
+        ProjectMetadataGetCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct ProjectTemplateBodyArgs {
+    pub name: String,
+    /// User, organization or group owning the new project. Defaults to the
+    /// authenticated user.
+    #[builder(default)]
+    pub owner: Option<String>,
+    #[builder(default)]
+    pub description: Option<String>,
+    #[builder(default)]
+    pub private: bool,
+}
+
+impl ProjectTemplateBodyArgs {
+    pub fn builder() -> ProjectTemplateBodyArgsBuilder {
+        ProjectTemplateBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct ProjectNewCliArgs {
+    /// Template as <domain>/<owner>/<repo>.
+    pub template: String,
+    pub body_args: ProjectTemplateBodyArgs,
+}
+
+impl ProjectNewCliArgs {
+    pub fn builder() -> ProjectNewCliArgsBuilder {
+        ProjectNewCliArgsBuilder::default()
+    }
+}
+
+/// Project created from a template.
+#[derive(Builder, Clone, Debug)]
+pub struct NewProject {
+    pub id: i64,
+    /// Full path, ex. owner/repo.
+    pub path: String,
+    pub web_url: String,
+    /// SSH URL to clone the project with.
+    pub clone_url: String,
+}
+
+impl NewProject {
+    pub fn builder() -> NewProjectBuilder {
+        NewProjectBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct ProjectAuditCliArgs {
+    /// Group or organization. Defaults to the namespace of the project.
+    #[builder(default)]
+    pub group: Option<String>,
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl ProjectAuditCliArgs {
+    pub fn builder() -> ProjectAuditCliArgsBuilder {
+        ProjectAuditCliArgsBuilder::default()
+    }
+}
+
+/// Visibility, license and default branch protection of a project in a
+/// group.
+#[derive(Builder, Clone, Debug)]
+pub struct AuditedProject {
+    pub id: i64,
+    /// Full path, ex. group/repo.
+    pub path: String,
+    pub visibility: String,
+    /// Empty if the project has no license.
+    #[builder(default)]
+    pub license: String,
+    /// Empty in projects with no commits.
+    #[builder(default)]
+    pub default_branch: String,
+    #[builder(default)]
+    pub default_branch_protected: bool,
+    pub created_at: String,
+}
+
+impl AuditedProject {
+    pub fn builder() -> AuditedProjectBuilder {
+        AuditedProjectBuilder::default()
+    }
+}
+
+impl Timestamp for AuditedProject {
+    fn created_at(&self) -> String {
+        self.created_at.clone()
+    }
+}
+
+impl From<AuditedProject> for DisplayBody {
+    fn from(project: AuditedProject) -> DisplayBody {
+        DisplayBody::new(vec![
+            Column::new("Path", project.path),
+            Column::new("Visibility", project.visibility),
+            Column::new("License", project.license),
+            Column::new("Default branch", project.default_branch),
+            Column::new("Protected", project.default_branch_protected.to_string()),
+        ])
+    }
+}
+
+/// A user asking to become a member of the project.
+#[derive(Builder, Clone, Debug)]
+pub struct AccessRequest {
+    pub user_id: i64,
+    pub username: String,
+    pub name: String,
+    pub requested_at: String,
+}
+
+impl AccessRequest {
+    pub fn builder() -> AccessRequestBuilder {
+        AccessRequestBuilder::default()
+    }
+}
+
+impl Timestamp for AccessRequest {
+    fn created_at(&self) -> String {
+        self.requested_at.clone()
+    }
+}
+
+impl From<AccessRequest> for DisplayBody {
+    fn from(request: AccessRequest) -> Self {
+        DisplayBody::new(vec![
+            Column::new("User ID", request.user_id.to_string()),
+            Column::new("Username", request.username),
+            Column::new("Name", request.name),
+            Column::new("Requested at", request.requested_at),
+        ])
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct MemberListBodyArgs {
+    pub list_args: Option<ListBodyArgs>,
+}
+
+impl MemberListBodyArgs {
+    pub fn builder() -> MemberListBodyArgsBuilder {
+        MemberListBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct AccessRequestListBodyArgs {
+    pub list_args: Option<ListBodyArgs>,
+}
+
+impl AccessRequestListBodyArgs {
+    pub fn builder() -> AccessRequestListBodyArgsBuilder {
+        AccessRequestListBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AccessLevel {
+    Guest,
+    Reporter,
+    #[default]
+    Developer,
+    Maintainer,
+}
+
+impl AccessLevel {
+    /// Numeric value used by Gitlab's API.
+    pub fn value(&self) -> u32 {
+        match self {
+            AccessLevel::Guest => 10,
+            AccessLevel::Reporter => 20,
+            AccessLevel::Developer => 30,
+            AccessLevel::Maintainer => 40,
+        }
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct AccessRequestApproveBodyArgs {
+    pub user_id: i64,
+    #[builder(default)]
+    pub access_level: AccessLevel,
+}
+
+impl AccessRequestApproveBodyArgs {
+    pub fn builder() -> AccessRequestApproveBodyArgsBuilder {
+        AccessRequestApproveBodyArgsBuilder::default()
+    }
+}
+
+/// Merge request approval policy. In Github, it is the required pull request
+/// reviews of the default branch protection.
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct ApprovalSettings {
+    pub required: u32,
+    pub reset_on_push: bool,
+}
+
+impl ApprovalSettings {
+    pub fn builder() -> ApprovalSettingsBuilder {
+        ApprovalSettingsBuilder::default()
+    }
+}
+
+impl From<ApprovalSettings> for DisplayBody {
+    fn from(settings: ApprovalSettings) -> Self {
+        DisplayBody::new(vec![
+            Column::new("Required approvals", settings.required.to_string()),
+            Column::new("Reset on push", settings.reset_on_push.to_string()),
+        ])
+    }
+}
+
+/// Only the settings given are updated.
+#[derive(Builder, Clone, Default)]
+pub struct ApprovalSettingsBodyArgs {
+    #[builder(default)]
+    pub required: Option<u32>,
+    #[builder(default)]
+    pub reset_on_push: Option<bool>,
+}
+
+impl ApprovalSettingsBodyArgs {
+    pub fn builder() -> ApprovalSettingsBodyArgsBuilder {
+        ApprovalSettingsBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct ApprovalSettingsSetCliArgs {
+    pub body_args: ApprovalSettingsBodyArgs,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl ApprovalSettingsSetCliArgs {
+    pub fn builder() -> ApprovalSettingsSetCliArgsBuilder {
+        ApprovalSettingsSetCliArgsBuilder::default()
+    }
+}
+
+/// Who can push to or merge into a protected branch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BranchAccess {
+    NoOne,
+    Maintainers,
+    Developers,
+}
+
+impl BranchAccess {
+    /// Numeric value used by Gitlab's API.
+    pub fn value(&self) -> u32 {
+        match self {
+            BranchAccess::NoOne => 0,
+            BranchAccess::Maintainers => 40,
+            BranchAccess::Developers => 30,
+        }
+    }
+}
+
+/// Protection rules of a branch, from Gitlab protected branches or Github
+/// branch protection.
+#[derive(Builder, Clone, Debug)]
+pub struct ProtectedBranch {
+    pub name: String,
+    #[builder(default)]
+    pub required_approvals: u32,
+    /// Roles allowed to push in Gitlab, users and teams in Github. Empty if
+    /// anyone with write access can.
+    #[builder(default)]
+    pub push: Vec<String>,
+    /// Roles allowed to merge. Gitlab only.
+    #[builder(default)]
+    pub merge: Vec<String>,
+    #[builder(default)]
+    pub allow_force_push: bool,
+}
+
+impl ProtectedBranch {
+    pub fn builder() -> ProtectedBranchBuilder {
+        ProtectedBranchBuilder::default()
+    }
+}
+
+impl From<ProtectedBranch> for DisplayBody {
+    fn from(branch: ProtectedBranch) -> Self {
+        let push = if branch.push.is_empty() {
+            "Anyone with write access".to_string()
+        } else {
+            branch.push.join(", ")
+        };
+        DisplayBody::new(vec![
+            Column::new("Branch", branch.name),
+            Column::new("Required approvals", branch.required_approvals.to_string()),
+            Column::new("Push", push),
+            Column::new("Merge", branch.merge.join(", ")),
+            Column::new("Force push", branch.allow_force_push.to_string()),
+        ])
+    }
+}
+
+/// Only the settings given are changed. The branch is protected if it is not
+/// yet.
+#[derive(Builder, Clone)]
+pub struct BranchProtectionBodyArgs {
+    pub branch: String,
+    #[builder(default)]
+    pub required_approvals: Option<u32>,
+    #[builder(default)]
+    pub push: Option<BranchAccess>,
+    #[builder(default)]
+    pub merge: Option<BranchAccess>,
+    #[builder(default)]
+    pub allow_force_push: Option<bool>,
+}
+
+impl BranchProtectionBodyArgs {
+    pub fn builder() -> BranchProtectionBodyArgsBuilder {
+        BranchProtectionBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct BranchProtectionGetCliArgs {
+    pub branch: String,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl BranchProtectionGetCliArgs {
+    pub fn builder() -> BranchProtectionGetCliArgsBuilder {
+        BranchProtectionGetCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct BranchProtectionSetCliArgs {
+    pub body_args: BranchProtectionBodyArgs,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl BranchProtectionSetCliArgs {
+    pub fn builder() -> BranchProtectionSetCliArgsBuilder {
+        BranchProtectionSetCliArgsBuilder::default()
+    }
+}
+
+/// CI/CD variable of the project. In Github, an Actions secret, whose value
+/// can never be read back.
+#[derive(Builder, Clone, Debug)]
+pub struct ProjectVariable {
+    pub key: String,
+    #[builder(default)]
+    pub value: String,
+    #[builder(default)]
+    pub masked: bool,
+    #[builder(default)]
+    pub protected: bool,
+    /// Environment the variable is scoped to. Empty if it applies to all.
+    #[builder(default)]
+    pub environment: String,
+}
+
+impl ProjectVariable {
+    pub fn builder() -> ProjectVariableBuilder {
+        ProjectVariableBuilder::default()
+    }
+}
+
+impl Timestamp for ProjectVariable {
+    fn created_at(&self) -> String {
+        // Variables are listed in the order reported by the remote. Set it to
+        // UNIX epoch.
+        "1970-01-01T00:00:00Z".to_string()
+    }
+}
+
+impl From<ProjectVariable> for DisplayBody {
+    fn from(variable: ProjectVariable) -> Self {
+        let value = if variable.masked {
+            "[masked]".to_string()
+        } else {
+            variable.value
+        };
+        DisplayBody::new(vec![
+            Column::new("Key", variable.key),
+            Column::new("Value", value),
+            Column::new("Protected", variable.protected.to_string()),
+            Column::new("Environment", variable.environment),
+        ])
+    }
+}
+
+/// Creates the variable or replaces the one with the same key and
+/// environment.
+#[derive(Builder, Clone)]
+pub struct ProjectVariableBodyArgs {
+    pub key: String,
+    pub value: String,
+    /// Hidden in job logs. Gitlab only, Github secrets always are.
+    #[builder(default)]
+    pub masked: bool,
+    /// Only exposed to protected branches and tags. Gitlab only.
+    #[builder(default)]
+    pub protected: bool,
+    #[builder(default)]
+    pub environment: Option<String>,
+}
+
+impl ProjectVariableBodyArgs {
+    pub fn builder() -> ProjectVariableBodyArgsBuilder {
+        ProjectVariableBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct VariableListCliArgs {
+    #[builder(default)]
+    pub environment: Option<String>,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl VariableListCliArgs {
+    pub fn builder() -> VariableListCliArgsBuilder {
+        VariableListCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct VariableSetCliArgs {
+    pub key: String,
+    /// Read from STDIN, or prompted for, when not given. Keeps it out of the
+    /// shell history.
+    #[builder(default)]
+    pub value: Option<String>,
+    #[builder(default)]
+    pub masked: bool,
+    #[builder(default)]
+    pub protected: bool,
+    #[builder(default)]
+    pub environment: Option<String>,
+}
+
+impl VariableSetCliArgs {
+    pub fn builder() -> VariableSetCliArgsBuilder {
+        VariableSetCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct VariableDeleteCliArgs {
+    pub key: String,
+    #[builder(default)]
+    pub environment: Option<String>,
+}
+
+impl VariableDeleteCliArgs {
+    pub fn builder() -> VariableDeleteCliArgsBuilder {
+        VariableDeleteCliArgsBuilder::default()
+    }
+}
+
+/// A Github repository ruleset. Gitlab push rules are presented as a single
+/// ruleset that applies to every branch.
+#[derive(Builder, Clone, Debug)]
+pub struct Ruleset {
+    pub id: i64,
+    pub name: String,
+    /// What the rules apply to: branch, tag or push.
+    pub target: String,
+    /// active, evaluate or disabled.
+    pub enforcement: String,
+    /// Repository or organization the ruleset is defined in.
+    #[builder(default)]
+    pub source: String,
+    /// Ref name patterns the ruleset applies to. Excluded ones are prefixed
+    /// with `!`.
+    #[builder(default)]
+    pub refs: Vec<String>,
+    #[builder(default)]
+    pub rules: Vec<String>,
+}
+
+impl Ruleset {
+    pub fn builder() -> RulesetBuilder {
+        RulesetBuilder::default()
+    }
+}
+
+impl From<Ruleset> for DisplayBody {
+    fn from(ruleset: Ruleset) -> Self {
+        DisplayBody::new(vec![
+            Column::new("ID", ruleset.id.to_string()),
+            Column::new("Name", ruleset.name),
+            Column::new("Target", ruleset.target),
+            Column::new("Enforcement", ruleset.enforcement),
+            Column::new("Source", ruleset.source),
+            Column::new("Refs", ruleset.refs.join(", ")),
+            Column::new("Rules", ruleset.rules.join(", ")),
+        ])
+    }
+}
+
+#[derive(Builder)]
+pub struct RulesetGetCliArgs {
+    pub id: i64,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl RulesetGetCliArgs {
+    pub fn builder() -> RulesetGetCliArgsBuilder {
+        RulesetGetCliArgsBuilder::default()
+    }
+}
+
+/// A commit of the repository along with the status of its CI.
+#[derive(Builder, Clone, Debug)]
+pub struct Commit {
+    pub sha: String,
+    pub title: String,
+    pub author: String,
+    pub date: String,
+    /// Status of the latest pipeline in Gitlab, of all the workflow runs in
+    /// Github. Empty if CI did not run for the commit.
+    #[builder(default)]
+    pub status: String,
+    #[builder(default)]
+    pub web_url: String,
+}
+
+impl Commit {
+    pub fn builder() -> CommitBuilder {
+        CommitBuilder::default()
+    }
+}
+
+impl Timestamp for Commit {
+    fn created_at(&self) -> String {
+        self.date.clone()
+    }
+}
+
+impl From<Commit> for DisplayBody {
+    fn from(commit: Commit) -> Self {
+        let status = if commit.status.is_empty() {
+            "-".to_string()
+        } else {
+            commit.status
+        };
+        DisplayBody::new(vec![
+            Column::new("SHA", commit.sha),
+            Column::new("Title", commit.title),
+            Column::new("Author", commit.author),
+            Column::new("Date", commit.date),
+            Column::new("Status", status),
+            Column::new("URL", commit.web_url),
+        ])
+    }
+}
+
+#[derive(Builder)]
+pub struct CommitListCliArgs {
+    /// Branch, tag or commit to list the history of. Defaults to the default
+    /// branch.
+    #[builder(default)]
+    pub ref_: Option<String>,
+    /// Only commits after this date, YYYY-MM-DD or ISO 8601.
+    #[builder(default)]
+    pub since: Option<String>,
+    pub list_args: ListRemoteCliArgs,
+}
+
+impl CommitListCliArgs {
+    pub fn builder() -> CommitListCliArgsBuilder {
+        CommitListCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct CommitListBodyArgs {
+    #[builder(default)]
+    pub ref_: Option<String>,
+    /// ISO 8601 timestamp.
+    #[builder(default)]
+    pub since: Option<String>,
+    #[builder(default)]
+    pub list_args: Option<ListBodyArgs>,
+}
+
+impl CommitListBodyArgs {
+    pub fn builder() -> CommitListBodyArgsBuilder {
+        CommitListBodyArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct CommitGetCliArgs {
+    pub sha: String,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl CommitGetCliArgs {
+    pub fn builder() -> CommitGetCliArgsBuilder {
+        CommitGetCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct CommitPickCliArgs {
+    pub sha: String,
+    /// None reverts on the default branch.
+    #[builder(default)]
+    pub branch: Option<String>,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl CommitPickCliArgs {
+    pub fn builder() -> CommitPickCliArgsBuilder {
+        CommitPickCliArgsBuilder::default()
+    }
+}
+
+/// Commit to cherry-pick or revert and the branch that gets the new commit.
+#[derive(Builder, Clone)]
+pub struct CommitPickBodyArgs {
+    pub sha: String,
+    pub branch: String,
+}
+
+impl CommitPickBodyArgs {
+    pub fn builder() -> CommitPickBodyArgsBuilder {
+        CommitPickBodyArgsBuilder::default()
+    }
+}
+
+// Gitlab rejects push rule regular expressions longer than this.
+const PUSH_RULE_REGEX_MAX_CHARS: usize = 511;
+
+/// Gitlab push rules checked on every push. Empty regular expressions and a
+/// zero max file size disable their check.
+#[derive(Builder, Clone, Debug, Default, PartialEq)]
+pub struct PushRules {
+    #[builder(default)]
+    pub commit_message_regex: String,
+    #[builder(default)]
+    pub commit_message_negative_regex: String,
+    /// In MB.
+    #[builder(default)]
+    pub max_file_size: u64,
+    /// Reject files likely to contain secrets, such as private keys.
+    #[builder(default)]
+    pub deny_secrets: bool,
+}
+
+impl PushRules {
+    pub fn builder() -> PushRulesBuilder {
+        PushRulesBuilder::default()
+    }
+}
+
+impl From<PushRules> for DisplayBody {
+    fn from(rules: PushRules) -> Self {
+        DisplayBody::new(vec![
+            Column::new("Commit message regex", rules.commit_message_regex),
+            Column::new(
+                "Commit message negative regex",
+                rules.commit_message_negative_regex,
+            ),
+            Column::new("Max file size (MB)", rules.max_file_size.to_string()),
+            Column::new("Deny secrets", rules.deny_secrets.to_string()),
+        ])
+    }
+}
+
+/// Only the rules given are updated.
+#[derive(Builder, Clone, Default)]
+pub struct PushRulesBodyArgs {
+    #[builder(default)]
+    pub commit_message_regex: Option<String>,
+    #[builder(default)]
+    pub commit_message_negative_regex: Option<String>,
+    #[builder(default)]
+    pub max_file_size: Option<u64>,
+    #[builder(default)]
+    pub deny_secrets: Option<bool>,
+}
+
+impl PushRulesBodyArgs {
+    pub fn builder() -> PushRulesBodyArgsBuilder {
+        PushRulesBodyArgsBuilder::default()
+    }
+
+    /// Gitlab evaluates the expressions with RE2, which shares the syntax of
+    /// the regex crate, so the ones it would reject fail before submitting.
+    fn validate(&self) -> Result<()> {
+        let regexes = [
+            ("commit message regex", &self.commit_message_regex),
+            (
+                "commit message negative regex",
+                &self.commit_message_negative_regex,
+            ),
+        ];
+        for (name, regex) in regexes {
+            let Some(regex) = regex else {
+                continue;
+            };
+            if regex.chars().count() > PUSH_RULE_REGEX_MAX_CHARS {
+                return Err(error::GRError::PreconditionNotMet(format!(
+                    "The {} is longer than {} characters",
+                    name, PUSH_RULE_REGEX_MAX_CHARS
+                ))
+                .into());
+            }
+            if let Err(err) = regex::Regex::new(regex) {
+                return Err(error::GRError::PreconditionNotMet(format!(
+                    "Invalid {}: {}",
+                    name, err
+                ))
+                .into());
+            }
+        }
+        // Stored as a 32 bit integer.
+        if let Some(max_file_size) = self.max_file_size {
+            if max_file_size > i32::MAX as u64 {
+                return Err(error::GRError::PreconditionNotMet(format!(
+                    "The max file size cannot be larger than {} MB",
+                    i32::MAX
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct PushRulesSetCliArgs {
+    pub body_args: PushRulesBodyArgs,
+    #[builder(default)]
+    pub get_args: GetRemoteCliArgs,
+}
+
+impl PushRulesSetCliArgs {
+    pub fn builder() -> PushRulesSetCliArgsBuilder {
+        PushRulesSetCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder)]
+pub struct FileCatCliArgs {
+    pub path: String,
+    /// Branch, tag or commit. The default branch when not given.
+    #[builder(default)]
+    pub ref_: Option<String>,
+    /// Project to read the file from, as <domain>/<owner>/<repo>, instead of
+    /// the one of the current repository.
+    #[builder(default)]
+    pub repo: Option<String>,
+    #[builder(default)]
+    pub refresh_cache: bool,
+}
+
+impl FileCatCliArgs {
+    pub fn builder() -> FileCatCliArgsBuilder {
+        FileCatCliArgsBuilder::default()
+    }
+}
+
+pub fn execute(
+    options: ProjectOptions,
+    config: Arc<Config>,
+    domain: String,
+    path: String,
+) -> Result<()> {
+    match options {
+        // The template can live in any domain, with its own configuration.
+        // Dispatched from main before reading the current repository config.
+        ProjectOptions::New(_) => unreachable!(),
+        ProjectOptions::Info(cli_args) => {
+            let remote =
+                remote::get_project(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                project_info(remote, writer, cli_args.id, cli_args.get_args)
+            })
+        }
+        ProjectOptions::Members(MemberOptions::List(cli_args)) => {
+            let remote =
+                remote::get_project_members(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let from_to_args = remote::validate_from_to_page(&cli_args)?;
+            let body_args = MemberListBodyArgs::builder()
+                .list_args(from_to_args)
+                .build()?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                common::list_members(remote, body_args, cli_args, writer)
+            })
+        }
+        ProjectOptions::AccessRequests(options) => match options {
+            AccessRequestOptions::List(cli_args) => {
+                let remote = remote::get_project_access_requests(
+                    domain,
+                    path,
+                    config,
+                    cli_args.get_args.refresh_cache,
+                )?;
+                let from_to_args = remote::validate_from_to_page(&cli_args)?;
+                let body_args = AccessRequestListBodyArgs::builder()
+                    .list_args(from_to_args)
+                    .build()?;
+                let output = cli_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    common::list_access_requests(remote, body_args, cli_args, writer)
+                })
+            }
+            AccessRequestOptions::Approve(body_args) => {
+                let remote = remote::get_project_access_requests(domain, path, config, false)?;
+                approve_access_request(remote, body_args, std::io::stdout())
+            }
+            AccessRequestOptions::Deny(user_id) => {
+                let remote = remote::get_project_access_requests(domain, path, config, false)?;
+                deny_access_request(remote, user_id, std::io::stdout())
+            }
+        },
+        ProjectOptions::AuditVisibility(cli_args) => {
+            let group = cli_args.group.clone().unwrap_or_else(|| {
+                path.rsplit_once('/')
+                    .map_or(path.as_str(), |(group, _)| group)
+                    .to_string()
+            });
+            let remote =
+                remote::get_project_audit(domain, path, config, cli_args.get_args.refresh_cache)?;
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                audit_projects(remote, &group, cli_args.get_args, writer)
+            })
+        }
+        ProjectOptions::Approvals(options) => match options {
+            ApprovalSettingsOptions::Get(get_args) => {
+                let remote =
+                    remote::get_project_approvals(domain, path, config, get_args.refresh_cache)?;
+                let output = get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    get_approval_settings(remote, get_args, writer)
+                })
+            }
+            ApprovalSettingsOptions::Set(cli_args) => {
+                let remote = remote::get_project_approvals(
+                    domain,
+                    path,
+                    config,
+                    cli_args.get_args.refresh_cache,
+                )?;
+                set_approval_settings(remote, cli_args, std::io::stdout())
+            }
+        },
+        ProjectOptions::Protect(options) => match options {
+            BranchProtectionOptions::List(get_args) => {
+                let remote = remote::get_project_branch_protection(
+                    domain,
+                    path,
+                    config,
+                    get_args.refresh_cache,
+                )?;
+                let output = get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    list_protected_branches(remote, get_args, writer)
+                })
+            }
+            BranchProtectionOptions::Get(cli_args) => {
+                let remote = remote::get_project_branch_protection(
+                    domain,
+                    path,
+                    config,
+                    cli_args.get_args.refresh_cache,
+                )?;
+                let output = cli_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    get_protected_branch(remote, cli_args, writer)
+                })
+            }
+            BranchProtectionOptions::Set(cli_args) => {
+                // The current rules are read before being changed, never from
+                // the cache.
+                let remote = remote::get_project_branch_protection(domain, path, config, true)?;
+                set_branch_protection(remote, cli_args, std::io::stdout())
+            }
+        },
+        ProjectOptions::Variables(options) => {
+            let remote = remote::get_project_variables(domain, path, config)?;
+            match options {
+                VariableOptions::List(cli_args) => {
+                    let output = cli_args.get_args.output.clone();
+                    common::write_output(output.as_deref(), |writer| {
+                        list_variables(remote, cli_args, writer)
+                    })
+                }
+                VariableOptions::Set(cli_args) => {
+                    let value = match cli_args.value.clone() {
+                        Some(value) => value,
+                        None => read_variable_value(&cli_args.key)?,
+                    };
+                    set_variable(remote, cli_args, value, std::io::stdout())
+                }
+                VariableOptions::Delete(cli_args) => {
+                    delete_variable(remote, cli_args, std::io::stdout())
+                }
+            }
+        }
+        ProjectOptions::Cat(cli_args) => {
+            let remote = remote::get_project_file(domain, path, config, cli_args.refresh_cache)?;
+            cat_file(remote, cli_args, std::io::stdout())
+        }
+        ProjectOptions::Rulesets(options) => match options {
+            RulesetOptions::List(get_args) => {
+                let remote =
+                    remote::get_project_rulesets(domain, path, config, get_args.refresh_cache)?;
+                let output = get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    list_rulesets(remote, get_args, writer)
+                })
+            }
+            RulesetOptions::Get(cli_args) => {
+                let remote = remote::get_project_rulesets(
+                    domain,
+                    path,
+                    config,
+                    cli_args.get_args.refresh_cache,
+                )?;
+                let output = cli_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    get_ruleset(remote, cli_args, writer)
+                })
+            }
+        },
+        ProjectOptions::Commits(cli_args) => {
+            let remote = remote::get_commits(
+                domain,
+                path,
+                config,
+                cli_args.list_args.get_args.refresh_cache,
+            )?;
+            let mut list_args = remote::validate_from_to_page(&cli_args.list_args)?;
+            // Commits are printed once their CI status is known.
+            if let Some(list_args) = &mut list_args {
+                list_args.flush = false;
+            }
+            let body_args = CommitListBodyArgs::builder()
+                .ref_(cli_args.ref_.clone())
+                .since(cli_args.since.as_deref().map(since_timestamp).transpose()?)
+                .list_args(list_args)
+                .build()?;
+            let output = cli_args.list_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_commits(remote, body_args, cli_args, writer)
+            })
+        }
+        ProjectOptions::Commit(options) => match options {
+            CommitOptions::Get(cli_args) => {
+                let remote =
+                    remote::get_commits(domain, path, config, cli_args.get_args.refresh_cache)?;
+                let output = cli_args.get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    get_commit(remote, cli_args, writer)
+                })
+            }
+            CommitOptions::CherryPick(cli_args) => {
+                let branch = cli_args.branch.clone().unwrap_or_default();
+                let remote = remote::get_commits(domain, path, config, true)?;
+                pick_commit(remote, cli_args, branch, false, std::io::stdout())
+            }
+            CommitOptions::Revert(cli_args) => {
+                let branch = match &cli_args.branch {
+                    Some(branch) => branch.clone(),
+                    None => {
+                        let project = remote::get_project(
+                            domain.clone(),
+                            path.clone(),
+                            config.clone(),
+                            false,
+                        )?;
+                        match project.get_project_data(None)? {
+                            CmdInfo::Project(project) => project.default_branch().to_string(),
+                            _ => {
+                                return Err(error::GRError::ApplicationError(
+                                    "Could not retrieve the default branch of the project"
+                                        .to_string(),
+                                )
+                                .into())
+                            }
+                        }
+                    }
+                };
+                let remote = remote::get_commits(domain, path, config, true)?;
+                pick_commit(remote, cli_args, branch, true, std::io::stdout())
+            }
+        },
+        ProjectOptions::PushRules(options) => match options {
+            PushRulesOptions::Get(get_args) => {
+                let remote =
+                    remote::get_project_push_rules(domain, path, config, get_args.refresh_cache)?;
+                let output = get_args.output.clone();
+                common::write_output(output.as_deref(), |writer| {
+                    get_push_rules(remote, get_args, writer)
+                })
+            }
+            PushRulesOptions::Set(cli_args) => {
+                let remote = remote::get_project_push_rules(domain, path, config, true)?;
+                set_push_rules(remote, cli_args, std::io::stdout())
+            }
+        },
+    }
+}
+
+/// Prints a file of the project given as <domain>/<owner>/<repo> in --repo. No
+/// local git repository is required.
+pub fn execute_cat<P: AsRef<Path>>(cli_args: FileCatCliArgs, config_file: P) -> Result<()> {
+    let repo = cli_args.repo.as_deref().unwrap_or_default();
+    let (domain, path) = common::parse_remote(repo)?;
+    let config = common::read_config(config_file.as_ref(), &domain)?;
+    let remote = remote::get_project_file(domain, path, config, cli_args.refresh_cache)?;
+    cat_file(remote, cli_args, std::io::stdout())
+}
+
+fn list_rulesets<W: Write>(
+    remote: Arc<dyn ProjectRulesets>,
+    get_args: GetRemoteCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let rulesets = remote.list()?;
+    if rulesets.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoRulesetsFound))?;
+        return Ok(());
+    }
+    display::print(&mut writer, rulesets, get_args)?;
+    Ok(())
+}
+
+fn get_ruleset<W: Write>(
+    remote: Arc<dyn ProjectRulesets>,
+    cli_args: RulesetGetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let ruleset = remote.get(cli_args.id)?;
+    display::print(&mut writer, vec![ruleset], cli_args.get_args)?;
+    Ok(())
+}
+
+fn list_commits<W: Write>(
+    remote: Arc<dyn CommitApi>,
+    body_args: CommitListBodyArgs,
+    cli_args: CommitListCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let commits = remote.list(body_args)?;
+    if commits.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoResourcesFound))?;
+        return Ok(());
+    }
+    display::print(&mut writer, commits, cli_args.list_args.get_args)?;
+    Ok(())
+}
+
+fn get_commit<W: Write>(
+    remote: Arc<dyn CommitApi>,
+    cli_args: CommitGetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let commit = remote.get(&cli_args.sha)?;
+    display::print(&mut writer, vec![commit], cli_args.get_args)?;
+    Ok(())
+}
+
+/// Cherry-picks, or reverts, the commit of `cli_args` onto `branch` and
+/// prints the new commit.
+fn pick_commit<W: Write>(
+    remote: Arc<dyn CommitApi>,
+    cli_args: CommitPickCliArgs,
+    branch: String,
+    revert: bool,
+    mut writer: W,
+) -> Result<()> {
+    let body_args = CommitPickBodyArgs::builder()
+        .sha(cli_args.sha)
+        .branch(branch)
+        .build()?;
+    let commit = if revert {
+        remote.revert(body_args)?
+    } else {
+        remote.cherry_pick(body_args)?
+    };
+    display::print(&mut writer, vec![commit], cli_args.get_args)?;
+    Ok(())
+}
+
+/// Timestamp for `--since`. A date alone is the start of the day in UTC.
+fn since_timestamp(since: &str) -> Result<String> {
+    if chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d").is_ok() {
+        return Ok(format!("{}T00:00:00Z", since));
+    }
+    match chrono::DateTime::parse_from_rfc3339(since) {
+        Ok(_) => Ok(since.to_string()),
+        Err(err) => Err(error::GRError::TimeConversionError(format!(
+            "Could not convert {} to date format, expected YYYY-MM-DD or ISO 8601: {}",
+            since, err
+        ))
+        .into()),
+    }
+}
+
+fn get_push_rules<W: Write>(
+    remote: Arc<dyn ProjectPushRules>,
+    get_args: GetRemoteCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let rules = remote.get()?;
+    display::print(&mut writer, vec![rules], get_args)?;
+    Ok(())
+}
+
+fn set_push_rules<W: Write>(
+    remote: Arc<dyn ProjectPushRules>,
+    cli_args: PushRulesSetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    cli_args.body_args.validate()?;
+    let rules = remote.set(cli_args.body_args)?;
+    display::print(&mut writer, vec![rules], cli_args.get_args)?;
+    Ok(())
+}
+
+fn cat_file<W: Write>(
+    remote: Arc<dyn ProjectFile>,
+    cli_args: FileCatCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let contents = remote.raw(&cli_args.path, cli_args.ref_.as_deref())?;
+    writer.write_all(&contents)?;
+    Ok(())
+}
+
+fn list_protected_branches<W: Write>(
+    remote: Arc<dyn ProjectBranchProtection>,
+    get_args: GetRemoteCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let branches = remote.list()?;
+    if branches.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoProtectedBranchesFound))?;
+        return Ok(());
+    }
+    display::print(&mut writer, branches, get_args)?;
+    Ok(())
+}
+
+fn get_protected_branch<W: Write>(
+    remote: Arc<dyn ProjectBranchProtection>,
+    cli_args: BranchProtectionGetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let branch = remote.get(&cli_args.branch)?;
+    display::print(&mut writer, vec![branch], cli_args.get_args)?;
+    Ok(())
+}
+
+fn set_branch_protection<W: Write>(
+    remote: Arc<dyn ProjectBranchProtection>,
+    cli_args: BranchProtectionSetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let branch = remote.set(cli_args.body_args)?;
+    display::print(&mut writer, vec![branch], cli_args.get_args)?;
+    Ok(())
+}
+
+/// Prompts for the value in a terminal without echoing it. Otherwise it is
+/// piped in, as in `gr pj var set KEY < file`.
+fn read_variable_value(key: &str) -> Result<String> {
+    if std::io::stdin().is_terminal() {
+        return dialog::prompt_secret(&i18n::format(Message::VariableValue, &[&key]));
+    }
+    let mut value = String::new();
+    std::io::stdin().read_to_string(&mut value)?;
+    if value.ends_with('\n') {
+        value.pop();
+        if value.ends_with('\r') {
+            value.pop();
+        }
+    }
+    Ok(value)
+}
+
+fn list_variables<W: Write>(
+    remote: Arc<dyn ProjectVariables>,
+    cli_args: VariableListCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let variables = remote.list(cli_args.environment.as_deref())?;
+    if variables.is_empty() {
+        writeln!(writer, "{}", i18n::text(Message::NoVariablesFound))?;
+        return Ok(());
+    }
+    display::print(&mut writer, variables, cli_args.get_args)?;
+    Ok(())
+}
+
+fn set_variable<W: Write>(
+    remote: Arc<dyn ProjectVariables>,
+    cli_args: VariableSetCliArgs,
+    value: String,
+    mut writer: W,
+) -> Result<()> {
+    let body_args = ProjectVariableBodyArgs::builder()
+        .key(cli_args.key.clone())
+        .value(value)
+        .masked(cli_args.masked)
+        .protected(cli_args.protected)
+        .environment(cli_args.environment)
+        .build()?;
+    remote.set(body_args)?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::VariableSet, &[&cli_args.key])
+    )?;
+    Ok(())
 }
 
-impl ProjectListBodyArgs {
-    pub fn builder() -> ProjectListBodyArgsBuilder {
-        ProjectListBodyArgsBuilder::default()
-    }
+fn delete_variable<W: Write>(
+    remote: Arc<dyn ProjectVariables>,
+    cli_args: VariableDeleteCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    remote.delete(&cli_args.key, cli_args.environment.as_deref())?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::VariableDeleted, &[&cli_args.key])
+    )?;
+    Ok(())
 }
 
-#[derive(Builder)]
-pub struct ProjectMetadataGetCliArgs {
-    pub id: Option<i64>,
-    pub get_args: GetRemoteCliArgs,
+/// Creates a project from the template given as <domain>/<owner>/<repo>. No
+/// local git repository is required.
+pub fn execute_new<P: AsRef<Path>>(cli_args: ProjectNewCliArgs, config_file: P) -> Result<()> {
+    let (domain, path) = common::parse_remote(&cli_args.template)?;
+    let config = common::read_config(config_file.as_ref(), &domain)?;
+    // The import status of the fork is polled until Gitlab finishes it, a
+    // cached answer would never change.
+    let remote = remote::get_project_template(domain, path, config.clone(), true)?;
+    new_project(
+        remote,
+        cli_args.body_args,
+        config.project_template_topics(),
+        config.project_template_labels(),
+        std::io::stdout(),
+    )
 }
 
-impl ProjectMetadataGetCliArgs {
-    pub fn builder() -> ProjectMetadataGetCliArgsBuilder {
-        ProjectMetadataGetCliArgsBuilder::default()
+/// Topics and labels that cannot be set are reported, the project is there
+/// already.
+fn new_project<W: Write>(
+    remote: Arc<dyn ProjectTemplate>,
+    body_args: ProjectTemplateBodyArgs,
+    topics: &[String],
+    labels: &[String],
+    mut writer: W,
+) -> Result<()> {
+    let project = remote.create_from_template(body_args)?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::Created, &[&project.web_url])
+    )?;
+    if !topics.is_empty() {
+        if let Err(err) = remote.set_topics(&project, topics) {
+            writeln!(writer, "{}", i18n::format(Message::TopicsNotSet, &[&err]))?;
+        }
+    }
+    for label in labels {
+        if let Err(err) = remote.create_label(&project, label) {
+            writeln!(
+                writer,
+                "{}",
+                i18n::format(Message::LabelNotCreated, &[label, &err])
+            )?;
+        }
     }
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::CloneUrl, &[&project.clone_url])
+    )?;
+    Ok(())
 }
 
-pub fn execute(
-    options: ProjectOptions,
-    config: Arc<Config>,
-    domain: String,
-    path: String,
+/// Lists the projects of the group and audits them concurrently, a few at a
+/// time. Stops at the first project that cannot be audited.
+fn audit_projects<W: Write>(
+    remote: Arc<dyn ProjectAudit + Send + Sync>,
+    group: &str,
+    get_args: GetRemoteCliArgs,
+    mut writer: W,
 ) -> Result<()> {
-    match options {
-        ProjectOptions::Info(cli_args) => {
-            let remote =
-                remote::get_project(domain, path, config, cli_args.get_args.refresh_cache)?;
-            project_info(remote, std::io::stdout(), cli_args.id, cli_args.get_args)
+    let projects = remote.list(group)?;
+    if projects.is_empty() {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::NoProjectsFoundIn, &[&group])
+        )?;
+        return Ok(());
+    }
+    let mut audited = Vec::with_capacity(projects.len());
+    for batch in projects.chunks(api_defaults::MAX_CONCURRENT_PROJECT_REQUESTS) {
+        let cmds = batch
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(position, project)| {
+                let remote = remote.clone();
+                let cmd = move || -> Result<(usize, AuditedProject)> {
+                    Ok((position, remote.audit(project)?))
+                };
+                Box::new(cmd) as Cmd<(usize, AuditedProject)>
+            })
+            .collect::<Vec<_>>();
+        let mut results = Vec::with_capacity(batch.len());
+        for result in exec::parallel_stream(cmds) {
+            results.push(result?);
         }
+        // Keep the order of the list, results arrive as they complete.
+        results.sort_by_key(|(position, _)| *position);
+        audited.extend(results.into_iter().map(|(_, project)| project));
     }
+    display::print(&mut writer, audited, get_args)?;
+    Ok(())
+}
+
+fn approve_access_request<W: Write>(
+    remote: Arc<dyn ProjectAccessRequests>,
+    body_args: AccessRequestApproveBodyArgs,
+    mut writer: W,
+) -> Result<()> {
+    let user_id = body_args.user_id;
+    let access_level = body_args.access_level;
+    remote.approve(body_args)?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(
+            Message::AccessRequestApproved,
+            &[&user_id, &format!("{:?}", access_level)]
+        )
+    )?;
+    Ok(())
+}
+
+fn deny_access_request<W: Write>(
+    remote: Arc<dyn ProjectAccessRequests>,
+    user_id: i64,
+    mut writer: W,
+) -> Result<()> {
+    remote.deny(user_id)?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::AccessRequestDenied, &[&user_id])
+    )?;
+    Ok(())
+}
+
+fn get_approval_settings<W: Write>(
+    remote: Arc<dyn ProjectApprovals>,
+    get_args: GetRemoteCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let settings = remote.get()?;
+    display::print(&mut writer, vec![settings], get_args)?;
+    Ok(())
+}
+
+fn set_approval_settings<W: Write>(
+    remote: Arc<dyn ProjectApprovals>,
+    cli_args: ApprovalSettingsSetCliArgs,
+    mut writer: W,
+) -> Result<()> {
+    let settings = remote.set(cli_args.body_args)?;
+    display::print(&mut writer, vec![settings], cli_args.get_args)?;
+    Ok(())
 }
 
 fn project_info<W: Write>(
@@ -84,8 +1509,10 @@ fn project_info<W: Write>(
 
 #[cfg(test)]
 mod test {
+    use crate::io::PageCount;
 
     use super::*;
+    use crate::api_traits::ProjectMembers;
     use crate::{cli::browse::BrowseOptions, remote::Project};
 
     #[derive(Builder)]
@@ -118,7 +1545,7 @@ mod test {
             todo!()
         }
 
-        fn num_pages(&self, _args: ProjectListBodyArgs) -> Result<Option<u32>> {
+        fn num_pages(&self, _args: ProjectListBodyArgs) -> Result<Option<PageCount>> {
             todo!()
         }
     }
@@ -133,7 +1560,7 @@ mod test {
         let mut writer = Vec::new();
         let args = GetRemoteCliArgs::default();
         project_info(remote, &mut writer, Some(1), args).unwrap();
-        assert!(writer.len() > 0);
+        assert!(!writer.is_empty());
     }
 
     #[test]
@@ -147,7 +1574,7 @@ mod test {
         let mut writer = Vec::new();
         let args = GetRemoteCliArgs::default();
         project_info(remote, &mut writer, None, args).unwrap_err();
-        assert!(writer.len() == 0);
+        assert!(writer.is_empty());
     }
 
     #[test]
@@ -168,4 +1595,674 @@ mod test {
             },
         }
     }
+
+    #[derive(Default)]
+    struct ApprovalsMock {
+        settings: std::sync::Mutex<Option<ApprovalSettings>>,
+    }
+
+    impl ProjectApprovals for ApprovalsMock {
+        fn get(&self) -> Result<ApprovalSettings> {
+            Ok(self.settings.lock().unwrap().clone().unwrap())
+        }
+
+        fn set(&self, args: ApprovalSettingsBodyArgs) -> Result<ApprovalSettings> {
+            let mut settings = self.settings.lock().unwrap();
+            let current = settings.clone().unwrap();
+            let updated = ApprovalSettings {
+                required: args.required.unwrap_or(current.required),
+                reset_on_push: args.reset_on_push.unwrap_or(current.reset_on_push),
+            };
+            *settings = Some(updated.clone());
+            Ok(updated)
+        }
+    }
+
+    fn approvals_mock() -> ApprovalsMock {
+        ApprovalsMock {
+            settings: std::sync::Mutex::new(Some(ApprovalSettings {
+                required: 1,
+                reset_on_push: false,
+            })),
+        }
+    }
+
+    #[test]
+    fn test_get_approval_settings() {
+        let remote = Arc::new(approvals_mock());
+        let mut buf = Vec::new();
+        get_approval_settings(remote, GetRemoteCliArgs::default(), &mut buf).unwrap();
+        assert_eq!(
+            "Required approvals|Reset on push\n1|false\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_approval_settings_updates_given_settings_only() {
+        let remote = Arc::new(approvals_mock());
+        let cli_args = ApprovalSettingsSetCliArgs::builder()
+            .body_args(
+                ApprovalSettingsBodyArgs::builder()
+                    .required(Some(2))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        set_approval_settings(remote, cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "Required approvals|Reset on push\n2|false\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[derive(Default)]
+    struct TemplateMock {
+        topics: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl ProjectTemplate for TemplateMock {
+        fn create_from_template(&self, args: ProjectTemplateBodyArgs) -> Result<NewProject> {
+            let path = format!("{}/{}", args.owner.unwrap(), args.name);
+            Ok(NewProject::builder()
+                .id(1)
+                .web_url(format!("https://github.com/{}", path))
+                .clone_url(format!("git@github.com:{}.git", path))
+                .path(path)
+                .build()
+                .unwrap())
+        }
+
+        fn set_topics(&self, _project: &NewProject, topics: &[String]) -> Result<()> {
+            *self.topics.lock().unwrap() = topics.to_vec();
+            Ok(())
+        }
+
+        fn create_label(&self, _project: &NewProject, name: &str) -> Result<()> {
+            if name == "bug" {
+                return Err(error::gen("Label already exists"));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_new_project_from_template_prints_clone_url() {
+        let remote = Arc::new(TemplateMock::default());
+        let body_args = ProjectTemplateBodyArgs::builder()
+            .name("my-service".to_string())
+            .owner(Some("my-org".to_string()))
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        new_project(
+            remote.clone(),
+            body_args,
+            &["rust".to_string()],
+            &["bug".to_string(), "enhancement".to_string()],
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            "Created https://github.com/my-org/my-service\n\
+             Label bug could not be created: Label already exists\n\
+             Clone URL: git@github.com:my-org/my-service.git\n",
+            String::from_utf8(buf).unwrap()
+        );
+        assert_eq!(vec!["rust"], *remote.topics.lock().unwrap());
+    }
+
+    struct AuditMock {
+        projects: usize,
+        failing: Option<i64>,
+    }
+
+    impl ProjectAudit for AuditMock {
+        fn list(&self, _group: &str) -> Result<Vec<AuditedProject>> {
+            Ok((1..=self.projects as i64)
+                .map(|id| {
+                    AuditedProject::builder()
+                        .id(id)
+                        .path(format!("tools/repo-{:02}", id))
+                        .visibility("private".to_string())
+                        .default_branch("main".to_string())
+                        .created_at(format!("2024-01-{:02}T00:00:00Z", id))
+                        .build()
+                        .unwrap()
+                })
+                .collect())
+        }
+
+        fn audit(&self, mut project: AuditedProject) -> Result<AuditedProject> {
+            if self.failing == Some(project.id) {
+                return Err(error::gen("403 Forbidden"));
+            }
+            // Later projects finish first.
+            std::thread::sleep(std::time::Duration::from_millis(
+                (20 - project.id as u64) % 8,
+            ));
+            project.license = "MIT".to_string();
+            project.default_branch_protected = project.id % 2 == 0;
+            Ok(project)
+        }
+    }
+
+    #[test]
+    fn test_audit_projects_as_csv_in_list_order() {
+        let remote = Arc::new(AuditMock {
+            projects: 10,
+            failing: None,
+        });
+        let get_args = GetRemoteCliArgs::builder()
+            .format(display::Format::CSV)
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        audit_projects(remote, "tools", get_args, &mut buf).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(11, lines.len());
+        assert_eq!("Path,Visibility,License,Default branch,Protected", lines[0]);
+        assert_eq!("tools/repo-01,private,MIT,main,false", lines[1]);
+        assert_eq!("tools/repo-10,private,MIT,main,true", lines[10]);
+    }
+
+    #[test]
+    fn test_audit_projects_errors_if_a_project_cannot_be_audited() {
+        let remote = Arc::new(AuditMock {
+            projects: 3,
+            failing: Some(2),
+        });
+        let mut buf = Vec::new();
+        let err = audit_projects(remote, "tools", GetRemoteCliArgs::default(), &mut buf);
+        assert_eq!("403 Forbidden", err.unwrap_err().to_string());
+        assert!(buf.is_empty());
+    }
+
+    struct MembersMock;
+
+    impl ProjectMembers for MembersMock {
+        fn list(&self, _args: MemberListBodyArgs) -> Result<Vec<Member>> {
+            Ok(vec![Member::builder()
+                .id(1)
+                .username("jdoe".to_string())
+                .name("John Doe".to_string())
+                .created_at("2024-03-01T10:00:00Z".to_string())
+                .role("maintainer".to_string())
+                .state("blocked".to_string())
+                .build()
+                .unwrap()])
+        }
+    }
+
+    #[test]
+    fn test_list_members_shows_role_and_state() {
+        let remote = Arc::new(MembersMock);
+        let body_args = MemberListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let cli_args = ListRemoteCliArgs::builder().build().unwrap();
+        let mut buf = Vec::new();
+        common::list_members(remote, body_args, cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "ID|Name|Username|Role|State\n1|John Doe|jdoe|maintainer|blocked\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[derive(Default)]
+    struct AccessRequestsMock {
+        approved: std::sync::Mutex<Vec<(i64, AccessLevel)>>,
+        denied: std::sync::Mutex<Vec<i64>>,
+    }
+
+    impl ProjectAccessRequests for AccessRequestsMock {
+        fn list(&self, _args: AccessRequestListBodyArgs) -> Result<Vec<AccessRequest>> {
+            Ok(vec![AccessRequest::builder()
+                .user_id(1)
+                .username("jdoe".to_string())
+                .name("John Doe".to_string())
+                .requested_at("2024-03-01T10:00:00Z".to_string())
+                .build()
+                .unwrap()])
+        }
+
+        fn approve(&self, args: AccessRequestApproveBodyArgs) -> Result<()> {
+            self.approved
+                .lock()
+                .unwrap()
+                .push((args.user_id, args.access_level));
+            Ok(())
+        }
+
+        fn deny(&self, user_id: i64) -> Result<()> {
+            self.denied.lock().unwrap().push(user_id);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_list_access_requests() {
+        let remote = Arc::new(AccessRequestsMock::default());
+        let body_args = AccessRequestListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let cli_args = ListRemoteCliArgs::builder().build().unwrap();
+        let mut buf = Vec::new();
+        common::list_access_requests(remote, body_args, cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "User ID|Username|Name|Requested at\n1|jdoe|John Doe|2024-03-01T10:00:00Z\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_approve_access_request() {
+        let remote = Arc::new(AccessRequestsMock::default());
+        let body_args = AccessRequestApproveBodyArgs::builder()
+            .user_id(1)
+            .access_level(AccessLevel::Reporter)
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        approve_access_request(remote.clone(), body_args, &mut buf).unwrap();
+        assert_eq!(
+            vec![(1, AccessLevel::Reporter)],
+            *remote.approved.lock().unwrap()
+        );
+        assert_eq!(
+            "Access request from user 1 approved as Reporter\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    struct BranchProtectionMock;
+
+    impl ProjectBranchProtection for BranchProtectionMock {
+        fn list(&self) -> Result<Vec<ProtectedBranch>> {
+            Ok(vec![
+                ProtectedBranch::builder()
+                    .name("main".to_string())
+                    .required_approvals(2)
+                    .push(vec!["No one".to_string()])
+                    .merge(vec!["Maintainers".to_string()])
+                    .build()
+                    .unwrap(),
+                ProtectedBranch::builder()
+                    .name("develop".to_string())
+                    .allow_force_push(true)
+                    .build()
+                    .unwrap(),
+            ])
+        }
+
+        fn get(&self, _branch: &str) -> Result<ProtectedBranch> {
+            todo!()
+        }
+
+        fn set(&self, _args: BranchProtectionBodyArgs) -> Result<ProtectedBranch> {
+            todo!()
+        }
+    }
+
+    #[test]
+    fn test_list_protected_branches() {
+        let remote = Arc::new(BranchProtectionMock);
+        let mut buf = Vec::new();
+        list_protected_branches(remote, GetRemoteCliArgs::default(), &mut buf).unwrap();
+        assert_eq!(
+            "Branch|Required approvals|Push|Merge|Force push\n\
+             main|2|No one|Maintainers|false\n\
+             develop|0|Anyone with write access||true\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[derive(Default)]
+    struct VariablesMock {
+        set: std::sync::Mutex<Vec<ProjectVariableBodyArgs>>,
+    }
+
+    impl ProjectVariables for VariablesMock {
+        fn list(&self, _environment: Option<&str>) -> Result<Vec<ProjectVariable>> {
+            Ok(vec![
+                ProjectVariable::builder()
+                    .key("LOG_LEVEL".to_string())
+                    .value("debug".to_string())
+                    .build()
+                    .unwrap(),
+                ProjectVariable::builder()
+                    .key("DEPLOY_TOKEN".to_string())
+                    .value("s3cr3t".to_string())
+                    .masked(true)
+                    .protected(true)
+                    .environment("production".to_string())
+                    .build()
+                    .unwrap(),
+            ])
+        }
+
+        fn set(&self, args: ProjectVariableBodyArgs) -> Result<()> {
+            self.set.lock().unwrap().push(args);
+            Ok(())
+        }
+
+        fn delete(&self, _key: &str, _environment: Option<&str>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_list_variables_redacts_masked_values() {
+        let remote = Arc::new(VariablesMock::default());
+        let mut buf = Vec::new();
+        list_variables(
+            remote,
+            VariableListCliArgs::builder().build().unwrap(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            "Key|Value|Protected|Environment\n\
+             LOG_LEVEL|debug|false|\n\
+             DEPLOY_TOKEN|[masked]|true|production\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_variable() {
+        let remote = Arc::new(VariablesMock::default());
+        let cli_args = VariableSetCliArgs::builder()
+            .key("DEPLOY_TOKEN".to_string())
+            .masked(true)
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        set_variable(remote.clone(), cli_args, "s3cr3t".to_string(), &mut buf).unwrap();
+        let set = remote.set.lock().unwrap();
+        assert_eq!("s3cr3t", set[0].value);
+        assert!(set[0].masked);
+        assert_eq!(
+            "Variable DEPLOY_TOKEN set\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_deny_access_request() {
+        let remote = Arc::new(AccessRequestsMock::default());
+        let mut buf = Vec::new();
+        deny_access_request(remote.clone(), 1, &mut buf).unwrap();
+        assert_eq!(vec![1], *remote.denied.lock().unwrap());
+        assert_eq!(
+            "Access request from user 1 denied\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    struct FileMock {
+        contents: Vec<u8>,
+        requested: std::sync::Mutex<Option<(String, Option<String>)>>,
+    }
+
+    impl ProjectFile for FileMock {
+        fn raw(&self, path: &str, ref_: Option<&str>) -> Result<Vec<u8>> {
+            *self.requested.lock().unwrap() =
+                Some((path.to_string(), ref_.map(|ref_| ref_.to_string())));
+            Ok(self.contents.clone())
+        }
+    }
+
+    #[test]
+    fn test_cat_file_writes_contents_as_is() {
+        let remote = Arc::new(FileMock {
+            contents: b"stages:\n  - test\n".to_vec(),
+            requested: std::sync::Mutex::new(None),
+        });
+        let cli_args = FileCatCliArgs::builder()
+            .path(".gitlab-ci.yml".to_string())
+            .ref_(Some("main".to_string()))
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        cat_file(remote.clone(), cli_args, &mut buf).unwrap();
+        assert_eq!("stages:\n  - test\n", String::from_utf8(buf).unwrap());
+        assert_eq!(
+            Some((".gitlab-ci.yml".to_string(), Some("main".to_string()))),
+            *remote.requested.lock().unwrap()
+        );
+    }
+
+    struct RulesetsMock {
+        rulesets: Vec<Ruleset>,
+    }
+
+    impl ProjectRulesets for RulesetsMock {
+        fn list(&self) -> Result<Vec<Ruleset>> {
+            Ok(self.rulesets.clone())
+        }
+
+        fn get(&self, id: i64) -> Result<Ruleset> {
+            Ok(self
+                .rulesets
+                .iter()
+                .find(|ruleset| ruleset.id == id)
+                .unwrap()
+                .clone())
+        }
+    }
+
+    #[test]
+    fn test_get_ruleset() {
+        let ruleset = Ruleset::builder()
+            .id(42)
+            .name("main".to_string())
+            .target("branch".to_string())
+            .enforcement("active".to_string())
+            .source("jordilin/githapi".to_string())
+            .refs(vec!["~DEFAULT_BRANCH".to_string()])
+            .rules(vec!["deletion".to_string(), "non_fast_forward".to_string()])
+            .build()
+            .unwrap();
+        let remote = Arc::new(RulesetsMock {
+            rulesets: vec![ruleset],
+        });
+        let cli_args = RulesetGetCliArgs::builder().id(42).build().unwrap();
+        let mut buf = Vec::new();
+        get_ruleset(remote, cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "ID|Name|Target|Enforcement|Source|Refs|Rules\n\
+             42|main|branch|active|jordilin/githapi|~DEFAULT_BRANCH|deletion, non_fast_forward\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list_rulesets_none() {
+        let remote = Arc::new(RulesetsMock { rulesets: vec![] });
+        let mut buf = Vec::new();
+        list_rulesets(remote, GetRemoteCliArgs::default(), &mut buf).unwrap();
+        assert_eq!("No rulesets found\n", String::from_utf8(buf).unwrap());
+    }
+
+    struct CommitsMock {
+        commits: Vec<Commit>,
+    }
+
+    impl CommitApi for CommitsMock {
+        fn list(&self, _args: CommitListBodyArgs) -> Result<Vec<Commit>> {
+            Ok(self.commits.clone())
+        }
+
+        fn get(&self, _sha: &str) -> Result<Commit> {
+            Ok(self.commits[0].clone())
+        }
+
+        fn cherry_pick(&self, args: CommitPickBodyArgs) -> Result<Commit> {
+            let mut commit = self.commits[0].clone();
+            commit.title = format!("Cherry-pick {} onto {}", args.sha, args.branch);
+            Ok(commit)
+        }
+
+        fn revert(&self, args: CommitPickBodyArgs) -> Result<Commit> {
+            let mut commit = self.commits[0].clone();
+            commit.title = format!("Revert {} on {}", args.sha, args.branch);
+            Ok(commit)
+        }
+    }
+
+    #[test]
+    fn test_get_commit() {
+        let commit = Commit::builder()
+            .sha("ed899a2f".to_string())
+            .title("Fix the release".to_string())
+            .author("Jordi".to_string())
+            .date("2024-03-02T10:00:00Z".to_string())
+            .build()
+            .unwrap();
+        let remote = Arc::new(CommitsMock {
+            commits: vec![commit],
+        });
+        let cli_args = CommitGetCliArgs::builder()
+            .sha("ed899a2f".to_string())
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        get_commit(remote, cli_args, &mut buf).unwrap();
+        assert_eq!(
+            "SHA|Title|Author|Date|Status|URL\n\
+             ed899a2f|Fix the release|Jordi|2024-03-02T10:00:00Z|-|\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_revert_commit() {
+        let commit = Commit::builder()
+            .sha("a1b2c3d4".to_string())
+            .title("".to_string())
+            .author("Jordi".to_string())
+            .date("2024-03-02T10:00:00Z".to_string())
+            .build()
+            .unwrap();
+        let remote = Arc::new(CommitsMock {
+            commits: vec![commit],
+        });
+        let cli_args = CommitPickCliArgs::builder()
+            .sha("ed899a2f".to_string())
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        pick_commit(remote, cli_args, "main".to_string(), true, &mut buf).unwrap();
+        assert_eq!(
+            "SHA|Title|Author|Date|Status|URL\n\
+             a1b2c3d4|Revert ed899a2f on main|Jordi|2024-03-02T10:00:00Z|-|\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_since_timestamp() {
+        assert_eq!(
+            "2024-03-01T00:00:00Z",
+            since_timestamp("2024-03-01").unwrap()
+        );
+        assert_eq!(
+            "2024-03-01T12:30:00+02:00",
+            since_timestamp("2024-03-01T12:30:00+02:00").unwrap()
+        );
+        assert!(since_timestamp("last week").is_err());
+    }
+
+    #[derive(Default)]
+    struct PushRulesMock {
+        rules: std::sync::Mutex<PushRules>,
+    }
+
+    impl ProjectPushRules for PushRulesMock {
+        fn get(&self) -> Result<PushRules> {
+            Ok(self.rules.lock().unwrap().clone())
+        }
+
+        fn set(&self, args: PushRulesBodyArgs) -> Result<PushRules> {
+            let mut rules = self.rules.lock().unwrap();
+            if let Some(regex) = args.commit_message_regex {
+                rules.commit_message_regex = regex;
+            }
+            if let Some(max_file_size) = args.max_file_size {
+                rules.max_file_size = max_file_size;
+            }
+            if let Some(deny_secrets) = args.deny_secrets {
+                rules.deny_secrets = deny_secrets;
+            }
+            Ok(rules.clone())
+        }
+    }
+
+    fn push_rules_set_args(body_args: PushRulesBodyArgs) -> PushRulesSetCliArgs {
+        PushRulesSetCliArgs::builder()
+            .body_args(body_args)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_set_push_rules() {
+        let remote = Arc::new(PushRulesMock::default());
+        let body_args = PushRulesBodyArgs::builder()
+            .commit_message_regex(Some("^JIRA-[0-9]+: ".to_string()))
+            .max_file_size(Some(50))
+            .deny_secrets(Some(true))
+            .build()
+            .unwrap();
+        let mut buf = Vec::new();
+        set_push_rules(remote, push_rules_set_args(body_args), &mut buf).unwrap();
+        assert_eq!(
+            "Commit message regex|Commit message negative regex|Max file size (MB)|Deny secrets\n\
+             ^JIRA-[0-9]+: ||50|true\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_push_rules_invalid_regex_is_not_submitted() {
+        let remote = Arc::new(PushRulesMock::default());
+        let body_args = PushRulesBodyArgs::builder()
+            .commit_message_regex(Some("^(feat|fix".to_string()))
+            .build()
+            .unwrap();
+        let err =
+            set_push_rules(remote.clone(), push_rules_set_args(body_args), Vec::new()).unwrap_err();
+        match err.downcast_ref::<error::GRError>() {
+            Some(error::GRError::PreconditionNotMet(msg)) => {
+                assert!(msg.starts_with("Invalid commit message regex"))
+            }
+            _ => panic!("Expected error::GRError::PreconditionNotMet"),
+        }
+        assert_eq!(PushRules::default(), remote.get().unwrap());
+    }
+
+    #[test]
+    fn test_push_rules_validation_rejects_lookarounds_and_long_regexes() {
+        let lookahead = PushRulesBodyArgs::builder()
+            .commit_message_negative_regex(Some("(?!WIP)".to_string()))
+            .build()
+            .unwrap();
+        assert!(lookahead.validate().is_err());
+        let long = PushRulesBodyArgs::builder()
+            .commit_message_regex(Some("a".repeat(512)))
+            .build()
+            .unwrap();
+        assert!(long.validate().is_err());
+        let too_large = PushRulesBodyArgs::builder()
+            .max_file_size(Some(u64::MAX))
+            .build()
+            .unwrap();
+        assert!(too_large.validate().is_err());
+    }
 }
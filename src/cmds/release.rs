@@ -1,11 +1,16 @@
-use std::io::Write;
+use std::cell::RefCell;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::api_traits::{Deploy, Timestamp};
 use crate::cli::release::ReleaseOptions;
 use crate::cmds::common::num_release_pages;
+use crate::cmds::merge_request::get_reader_file_cli;
 use crate::config::Config;
 use crate::display::{Column, DisplayBody};
+use crate::error::GRError;
+use crate::i18n::{self, Message};
 use crate::remote::{ListBodyArgs, ListRemoteCliArgs};
 use crate::Result;
 
@@ -22,10 +27,56 @@ impl ReleaseBodyArgs {
     }
 }
 
+#[derive(Builder, Clone)]
+pub struct ReleaseCreateCliArgs {
+    pub tag: String,
+    #[builder(default)]
+    pub title: Option<String>,
+    /// File with the release notes. "-" reads them from STDIN.
+    #[builder(default)]
+    pub notes_file: Option<String>,
+    #[builder(default)]
+    pub assets: Vec<String>,
+    /// Branch or commit to create the tag from when it does not exist.
+    #[builder(default)]
+    pub target: Option<String>,
+}
+
+impl ReleaseCreateCliArgs {
+    pub fn builder() -> ReleaseCreateCliArgsBuilder {
+        ReleaseCreateCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct ReleaseCreateBodyArgs {
+    pub tag: String,
+    pub title: String,
+    #[builder(default)]
+    pub notes: String,
+    #[builder(default)]
+    pub target: Option<String>,
+    #[builder(default)]
+    pub assets: Vec<PathBuf>,
+}
+
+impl ReleaseCreateBodyArgs {
+    pub fn builder() -> ReleaseCreateBodyArgsBuilder {
+        ReleaseCreateBodyArgsBuilder::default()
+    }
+}
+
+/// File name of an asset as shown in the release.
+pub fn asset_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
 #[derive(Builder, Clone)]
 pub struct Release {
     id: String,
-    url: String,
+    pub url: String,
     tag: String,
     title: String,
     description: String,
@@ -76,9 +127,117 @@ pub fn execute(
             let body_args = ReleaseBodyArgs::builder()
                 .from_to_page(from_to_args)
                 .build()?;
-            list_releases(remote, body_args, cli_args, std::io::stdout())
+            let output = cli_args.get_args.output.clone();
+            common::write_output(output.as_deref(), |writer| {
+                list_releases(remote, body_args, cli_args, writer)
+            })
+        }
+        ReleaseOptions::Create(cli_args) => {
+            let remote = crate::remote::get_deploy(domain, path, config, false)?;
+            let notes = match &cli_args.notes_file {
+                Some(notes_file) => Some(get_reader_file_cli(notes_file)?),
+                None => None,
+            };
+            create_release(
+                remote,
+                cli_args,
+                notes,
+                std::io::stdout(),
+                std::io::stderr(),
+            )
+        }
+    }
+}
+
+/// Assets from this size on report how their upload goes.
+const PROGRESS_MIN_BYTES: u64 = 1024 * 1024;
+
+/// Reports the upload of large assets every 10%.
+struct UploadProgress<W: Write> {
+    writer: RefCell<W>,
+    // Asset being uploaded and the last step reported for it.
+    reported: RefCell<(String, u64)>,
+}
+
+impl<W: Write> UploadProgress<W> {
+    fn new(writer: W) -> Self {
+        UploadProgress {
+            writer: RefCell::new(writer),
+            reported: RefCell::new((String::new(), 0)),
+        }
+    }
+
+    fn update(&self, name: &str, sent: u64, size: u64) {
+        if size < PROGRESS_MIN_BYTES {
+            return;
+        }
+        let step = sent * 10 / size * 10;
+        let mut reported = self.reported.borrow_mut();
+        if reported.0 == name && reported.1 >= step {
+            return;
+        }
+        *reported = (name.to_string(), step);
+        // Progress is informational. Failing to show it must not stop the
+        // upload.
+        let _ = writeln!(
+            self.writer.borrow_mut(),
+            "{}",
+            i18n::format(
+                Message::Uploading,
+                &[
+                    &name,
+                    &step,
+                    &common::human_size(sent),
+                    &common::human_size(size)
+                ]
+            )
+        );
+    }
+}
+
+fn create_release<R: Read, W: Write, P: Write>(
+    remote: Arc<dyn Deploy>,
+    cli_args: ReleaseCreateCliArgs,
+    notes: Option<R>,
+    mut writer: W,
+    progress_writer: P,
+) -> Result<()> {
+    // Check the assets before the release exists in the remote.
+    let assets = cli_args
+        .assets
+        .iter()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    for asset in &assets {
+        if !asset.is_file() {
+            return Err(GRError::PreconditionNotMet(format!(
+                "Asset {} not found",
+                asset.display()
+            ))
+            .into());
         }
     }
+    let mut release_notes = String::new();
+    if let Some(mut notes) = notes {
+        notes.read_to_string(&mut release_notes)?;
+    }
+    let body_args = ReleaseCreateBodyArgs::builder()
+        .title(cli_args.title.unwrap_or_else(|| cli_args.tag.clone()))
+        .tag(cli_args.tag)
+        .notes(release_notes.trim_end().to_string())
+        .target(cli_args.target)
+        .assets(assets)
+        .build()?;
+    let progress = UploadProgress::new(progress_writer);
+    let release = remote.create(body_args, &|name, sent, size| {
+        progress.update(name, sent, size)
+    })?;
+    writeln!(
+        writer,
+        "{}",
+        i18n::format(Message::ReleaseCreated, &[&release.url])
+    )?;
+    Ok(())
 }
 
 fn list_releases<W: Write>(
@@ -93,14 +252,19 @@ fn list_releases<W: Write>(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::io::PageCount;
 
     struct MockDeploy {
         empty_releases: bool,
+        created: RefCell<Vec<ReleaseCreateBodyArgs>>,
     }
 
     impl MockDeploy {
         fn new(empty_releases: bool) -> Self {
-            Self { empty_releases }
+            Self {
+                empty_releases,
+                created: RefCell::new(Vec::new()),
+            }
         }
     }
 
@@ -120,9 +284,35 @@ mod test {
             }])
         }
 
-        fn num_pages(&self) -> Result<Option<u32>> {
+        fn num_pages(&self) -> Result<Option<PageCount>> {
             todo!()
         }
+
+        fn create(
+            &self,
+            args: ReleaseCreateBodyArgs,
+            progress: &dyn Fn(&str, u64, u64),
+        ) -> Result<Release> {
+            for asset in &args.assets {
+                let size = std::fs::metadata(asset)?.len();
+                progress(&asset_name(asset), size / 2, size);
+                progress(&asset_name(asset), size, size);
+            }
+            self.created.borrow_mut().push(args.clone());
+            Ok(Release::builder()
+                .id("1".to_string())
+                .url(format!(
+                    "https://github.com/jordilin/githapi/releases/tag/{}",
+                    args.tag
+                ))
+                .tag(args.tag)
+                .title(args.title)
+                .description(args.notes)
+                .created_at("2021-01-01T00:00:00Z".to_string())
+                .updated_at("2021-01-01T00:00:00Z".to_string())
+                .build()
+                .unwrap())
+        }
     }
 
     #[test]
@@ -166,4 +356,89 @@ mod test {
         list_releases(remote, body_args, cli_args, &mut writer).unwrap();
         assert_eq!("", String::from_utf8(writer).unwrap());
     }
+
+    #[test]
+    fn test_create_release_with_notes_and_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("checksums.txt");
+        std::fs::write(&small, "abc  gr.tar.gz\n").unwrap();
+        let large = dir.path().join("gr.tar.gz");
+        std::fs::write(&large, vec![0; 2 * PROGRESS_MIN_BYTES as usize]).unwrap();
+        let remote = Arc::new(MockDeploy::new(false));
+        let cli_args = ReleaseCreateCliArgs::builder()
+            .tag("v1.2.0".to_string())
+            .assets(vec![
+                small.to_string_lossy().to_string(),
+                large.to_string_lossy().to_string(),
+            ])
+            .build()
+            .unwrap();
+        let mut writer = Vec::new();
+        let mut progress = Vec::new();
+        create_release(
+            remote.clone(),
+            cli_args,
+            Some("Bug fixes\n".as_bytes()),
+            &mut writer,
+            &mut progress,
+        )
+        .unwrap();
+        let created = remote.created.borrow();
+        assert_eq!("v1.2.0", created[0].title);
+        assert_eq!("Bug fixes", created[0].notes);
+        assert_eq!(vec![small, large], created[0].assets);
+        assert_eq!(
+            "Release created: https://github.com/jordilin/githapi/releases/tag/v1.2.0\n",
+            String::from_utf8(writer).unwrap()
+        );
+        // Small assets upload without reporting progress.
+        assert_eq!(
+            "Uploading gr.tar.gz: 50% (1.0 MB of 2.0 MB)\n\
+             Uploading gr.tar.gz: 100% (2.0 MB of 2.0 MB)\n",
+            String::from_utf8(progress).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_create_release_with_missing_asset_is_error() {
+        let remote = Arc::new(MockDeploy::new(false));
+        let cli_args = ReleaseCreateCliArgs::builder()
+            .tag("v1.2.0".to_string())
+            .assets(vec!["/nonexistent/gr.tar.gz".to_string()])
+            .build()
+            .unwrap();
+        let err = create_release(
+            remote.clone(),
+            cli_args,
+            None::<&[u8]>,
+            Vec::new(),
+            Vec::new(),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Asset /nonexistent/gr.tar.gz not found"));
+        assert!(remote.created.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_upload_progress_reports_each_step_once() {
+        let mut writer = Vec::new();
+        {
+            let progress = UploadProgress::new(&mut writer);
+            let size = 10 * PROGRESS_MIN_BYTES;
+            for sent in [
+                PROGRESS_MIN_BYTES / 2,
+                PROGRESS_MIN_BYTES,
+                PROGRESS_MIN_BYTES + 1,
+            ] {
+                progress.update("gr.tar.gz", sent, size);
+            }
+        }
+        assert_eq!(
+            "Uploading gr.tar.gz: 0% (512.0 KB of 10.0 MB)\n\
+             Uploading gr.tar.gz: 10% (1.0 MB of 10.0 MB)\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
 }
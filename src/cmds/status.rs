@@ -0,0 +1,429 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::api_traits::{RemoteStatus, Timestamp, UserInfo};
+use crate::config::Config;
+use crate::display::{self, Column, DisplayBody};
+use crate::error::{AddContext, GRError};
+use crate::i18n::{self, Message};
+use crate::io::Response;
+use crate::remote::{self, GetRemoteCliArgs};
+use crate::time::Milliseconds;
+use crate::{json_loads, log_debug, Result};
+
+use super::common;
+
+pub const HEALTH_OK: &str = "ok";
+pub const HEALTH_MAINTENANCE: &str = "maintenance";
+pub const HEALTH_ERROR: &str = "error";
+
+#[derive(Builder)]
+pub struct StatusCliArgs {
+    pub domains: Vec<String>,
+    /// Number of requests used to compute the average latency.
+    pub samples: u32,
+}
+
+impl StatusCliArgs {
+    pub fn builder() -> StatusCliArgsBuilder {
+        StatusCliArgsBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug)]
+pub struct RemoteHealth {
+    pub domain: String,
+    pub status: String,
+    #[builder(default)]
+    pub version: String,
+    #[builder(default)]
+    pub authenticated: bool,
+    #[builder(default)]
+    pub username: String,
+    #[builder(default)]
+    pub ratelimit_remaining: Option<u32>,
+    #[builder(default)]
+    pub latency: Milliseconds,
+    #[builder(default)]
+    pub message: String,
+}
+
+impl RemoteHealth {
+    pub fn builder() -> RemoteHealthBuilder {
+        RemoteHealthBuilder::default()
+    }
+
+    /// Health out of the response to a lightweight request against the API
+    /// base path. Failures are part of the report, a remote under
+    /// maintenance is reachable but blocks write operations.
+    pub fn from_response<F: Fn(&serde_json::Value) -> String>(
+        domain: &str,
+        response: Result<Response>,
+        version: F,
+    ) -> Self {
+        let mut health = RemoteHealth::builder();
+        health.domain(domain.to_string());
+        let response = response.and_then(|response| {
+            let body = json_loads(&response.body)?;
+            Ok((version(&body), response))
+        });
+        match response {
+            Ok((version, response)) => health
+                .status(HEALTH_OK.to_string())
+                .version(version)
+                .ratelimit_remaining(response.get_ratelimit_headers().map(|h| h.remaining))
+                .build()
+                .unwrap(),
+            Err(err) => match err.downcast_ref::<GRError>() {
+                Some(GRError::RemoteUnderMaintenance(message)) => health
+                    .status(HEALTH_MAINTENANCE.to_string())
+                    .message(message.to_string())
+                    .build()
+                    .unwrap(),
+                _ => health
+                    .status(HEALTH_ERROR.to_string())
+                    .message(err.to_string())
+                    .build()
+                    .unwrap(),
+            },
+        }
+    }
+}
+
+impl From<RemoteHealth> for DisplayBody {
+    fn from(health: RemoteHealth) -> Self {
+        let authenticated = if health.authenticated { "yes" } else { "no" };
+        let ratelimit_remaining = health
+            .ratelimit_remaining
+            .map(|remaining| remaining.to_string())
+            .unwrap_or("n/a".to_string());
+        DisplayBody::new(vec![
+            Column::new("Domain", health.domain),
+            Column::new("Status", health.status),
+            Column::new("Version", health.version),
+            Column::new("Authenticated", authenticated),
+            Column::new("User", health.username),
+            Column::new("Rate limit remaining", ratelimit_remaining),
+            Column::new("Avg latency (ms)", health.latency.to_string()),
+            Column::new("Message", health.message),
+        ])
+    }
+}
+
+/// Message published by the administrators of the remote instance, such as
+/// upcoming maintenance windows.
+#[derive(Builder, Clone, Debug)]
+pub struct BroadcastMessage {
+    pub id: i64,
+    pub message: String,
+    #[builder(default)]
+    pub starts_at: String,
+    #[builder(default)]
+    pub ends_at: String,
+    #[builder(default)]
+    pub active: bool,
+}
+
+impl BroadcastMessage {
+    pub fn builder() -> BroadcastMessageBuilder {
+        BroadcastMessageBuilder::default()
+    }
+}
+
+impl Timestamp for BroadcastMessage {
+    fn created_at(&self) -> String {
+        self.starts_at.clone()
+    }
+}
+
+impl From<BroadcastMessage> for DisplayBody {
+    fn from(broadcast: BroadcastMessage) -> Self {
+        DisplayBody::new(vec![
+            Column::new("ID", broadcast.id.to_string()),
+            Column::new("Message", broadcast.message),
+            Column::new("Starts at", broadcast.starts_at),
+            Column::new("Ends at", broadcast.ends_at),
+        ])
+    }
+}
+
+type StatusRemote = (
+    String,
+    Arc<dyn RemoteStatus + Send + Sync>,
+    Arc<dyn UserInfo + Send + Sync>,
+);
+
+/// Checks all the given domains. If no domains are provided, all the domains
+/// found in the configuration file are checked.
+pub fn execute<P: AsRef<Path>>(cli_args: StatusCliArgs, config_file: P) -> Result<()> {
+    let config_file = config_file.as_ref();
+    let domains = if cli_args.domains.is_empty() {
+        let f = File::open(config_file).err_context(GRError::ConfigurationError(format!(
+            "Cannot open config file {}",
+            config_file.display()
+        )))?;
+        Config::domains(f)?
+    } else {
+        cli_args.domains.clone()
+    };
+    let mut remotes: Vec<StatusRemote> = Vec::new();
+    for domain in domains {
+        let config = common::read_config(config_file, &domain)?;
+        // Always hit the remote, a cached answer says nothing about its
+        // health or latency.
+        let status_remote =
+            remote::get_remote_status(domain.clone(), "".to_string(), config.clone(), true)?;
+        let user_remote = remote::get_auth_user(domain.clone(), "".to_string(), config, true)?;
+        remotes.push((domain, status_remote, user_remote));
+    }
+    show_status(remotes, &cli_args, std::io::stdout(), std::io::stderr())
+}
+
+/// Warns about the broadcast messages active in the remote of the domain.
+/// Run after every command, errors are only logged so they never hide the
+/// command's outcome.
+pub fn warn_broadcast_messages(config: Arc<Config>, domain: String, path: String) {
+    // Broadcast messages are cached as any other project data.
+    let broadcasts = remote::get_remote_status(domain.clone(), path, config, false)
+        .and_then(|remote| remote.broadcast_messages());
+    match broadcasts {
+        Ok(broadcasts) => {
+            if let Err(err) = write_broadcast_warnings(&domain, &broadcasts, std::io::stderr()) {
+                log_debug!("Cannot write broadcast messages: {}", err);
+            }
+        }
+        Err(err) => log_debug!("Cannot get broadcast messages: {}", err),
+    }
+}
+
+fn write_broadcast_warnings<W: Write>(
+    domain: &str,
+    broadcasts: &[BroadcastMessage],
+    mut writer: W,
+) -> Result<()> {
+    for broadcast in broadcasts {
+        writeln!(
+            writer,
+            "{}",
+            i18n::format(Message::BroadcastWarning, &[&domain, &broadcast.message])
+        )?;
+    }
+    Ok(())
+}
+
+/// Warnings go to `warnings`, apart from the report, so that they do not get
+/// mixed with it when piped.
+fn show_status<W: Write, E: Write>(
+    remotes: Vec<StatusRemote>,
+    cli_args: &StatusCliArgs,
+    mut writer: W,
+    mut warnings: E,
+) -> Result<()> {
+    let mut healths = Vec::new();
+    for (domain, status_remote, user_remote) in remotes {
+        let health = check_remote(status_remote.clone(), user_remote, cli_args.samples)?;
+        if health.status != HEALTH_ERROR {
+            let broadcasts = status_remote.broadcast_messages()?;
+            write_broadcast_warnings(&domain, &broadcasts, &mut warnings)?;
+        }
+        healths.push(health);
+    }
+    display::print(&mut writer, healths, GetRemoteCliArgs::default())?;
+    Ok(())
+}
+
+fn check_remote(
+    status_remote: Arc<dyn RemoteStatus + Send + Sync>,
+    user_remote: Arc<dyn UserInfo + Send + Sync>,
+    samples: u32,
+) -> Result<RemoteHealth> {
+    let samples = samples.max(1);
+    let mut elapsed = 0;
+    let mut health = None;
+    for _ in 0..samples {
+        let start = Instant::now();
+        let sample = status_remote.health()?;
+        elapsed += start.elapsed().as_millis() as u64;
+        health = Some(sample);
+    }
+    let mut health = health.unwrap();
+    health.latency = Milliseconds::new(elapsed / samples as u64);
+    if let Ok(user) = user_remote.get() {
+        health.authenticated = true;
+        health.username = user.username;
+    }
+    Ok(health)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use crate::{error, remote::Member};
+
+    use super::*;
+
+    struct RemoteStatusMock {
+        health: RemoteHealth,
+        broadcasts: Vec<BroadcastMessage>,
+        calls: Mutex<u32>,
+    }
+
+    impl RemoteStatusMock {
+        fn new(health: RemoteHealth, broadcasts: Vec<BroadcastMessage>) -> Self {
+            RemoteStatusMock {
+                health,
+                broadcasts,
+                calls: Mutex::new(0),
+            }
+        }
+    }
+
+    impl RemoteStatus for RemoteStatusMock {
+        fn health(&self) -> Result<RemoteHealth> {
+            *self.calls.lock().unwrap() += 1;
+            Ok(self.health.clone())
+        }
+
+        fn broadcast_messages(&self) -> Result<Vec<BroadcastMessage>> {
+            Ok(self.broadcasts.clone())
+        }
+    }
+
+    struct UserInfoMock {
+        user: Option<Member>,
+    }
+
+    impl UserInfo for UserInfoMock {
+        fn get(&self) -> Result<Member> {
+            self.user
+                .clone()
+                .ok_or_else(|| error::gen("401 Unauthorized"))
+        }
+    }
+
+    fn ok_health(domain: &str) -> RemoteHealth {
+        RemoteHealth::builder()
+            .domain(domain.to_string())
+            .status(HEALTH_OK.to_string())
+            .version("16.10.0".to_string())
+            .ratelimit_remaining(Some(1999))
+            .build()
+            .unwrap()
+    }
+
+    fn user() -> Option<Member> {
+        Some(
+            Member::builder()
+                .id(1)
+                .username("jdoe".to_string())
+                .name("John Doe".to_string())
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn cli_args(samples: u32) -> StatusCliArgs {
+        StatusCliArgs::builder()
+            .domains(vec![])
+            .samples(samples)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_check_remote_samples_health_and_gets_user() {
+        let status_remote = Arc::new(RemoteStatusMock::new(ok_health("gitlab.com"), vec![]));
+        let user_remote = Arc::new(UserInfoMock { user: user() });
+        let health = check_remote(status_remote.clone(), user_remote, 3).unwrap();
+        assert_eq!(3, *status_remote.calls.lock().unwrap());
+        assert!(health.authenticated);
+        assert_eq!("jdoe", health.username);
+        assert_eq!(Some(1999), health.ratelimit_remaining);
+    }
+
+    #[test]
+    fn test_check_remote_not_authenticated() {
+        let status_remote = Arc::new(RemoteStatusMock::new(ok_health("gitlab.com"), vec![]));
+        let user_remote = Arc::new(UserInfoMock { user: None });
+        let health = check_remote(status_remote.clone(), user_remote, 0).unwrap();
+        assert_eq!(1, *status_remote.calls.lock().unwrap());
+        assert!(!health.authenticated);
+        assert_eq!("", health.username);
+    }
+
+    #[test]
+    fn test_show_status_with_broadcast_warnings() {
+        let broadcasts = vec![BroadcastMessage::builder()
+            .id(1)
+            .message("Scheduled maintenance on Saturday".to_string())
+            .active(true)
+            .build()
+            .unwrap()];
+        let remotes: Vec<StatusRemote> = vec![
+            (
+                "gitlab.com".to_string(),
+                Arc::new(RemoteStatusMock::new(ok_health("gitlab.com"), broadcasts)),
+                Arc::new(UserInfoMock { user: user() }),
+            ),
+            (
+                "github.com".to_string(),
+                Arc::new(RemoteStatusMock::new(ok_health("github.com"), vec![])),
+                Arc::new(UserInfoMock { user: None }),
+            ),
+        ];
+        let mut buf = Vec::new();
+        let mut warnings = Vec::new();
+        show_status(remotes, &cli_args(1), &mut buf, &mut warnings).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let lines = output.lines().collect::<Vec<&str>>();
+        assert_eq!(3, lines.len());
+        assert_eq!(
+            "Domain|Status|Version|Authenticated|User|Rate limit remaining|Avg latency (ms)|Message",
+            lines[0]
+        );
+        assert!(lines[1].starts_with("gitlab.com|ok|16.10.0|yes|jdoe|1999|"));
+        assert!(lines[2].starts_with("github.com|ok|16.10.0|no||1999|"));
+        assert_eq!(
+            "Warning: gitlab.com: Scheduled maintenance on Saturday\n",
+            String::from_utf8(warnings).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_health_from_response_with_ratelimit() {
+        let mut headers = crate::http::Headers::new();
+        headers.set("ratelimit-remaining", "1999");
+        let response = Response::builder()
+            .status(200)
+            .body(r#"{"version":"16.10.0"}"#.to_string())
+            .headers(headers)
+            .build()
+            .unwrap();
+        let health = RemoteHealth::from_response("gitlab.com", Ok(response), |body| {
+            body["version"].as_str().unwrap().to_string()
+        });
+        assert_eq!(HEALTH_OK, health.status);
+        assert_eq!("16.10.0", health.version);
+        assert_eq!(Some(1999), health.ratelimit_remaining);
+    }
+
+    #[test]
+    fn test_health_from_maintenance_error() {
+        let response = Err(GRError::RemoteUnderMaintenance("Upgrading".to_string()).into());
+        let health = RemoteHealth::from_response("gitlab.com", response, |_| String::new());
+        assert_eq!(HEALTH_MAINTENANCE, health.status);
+        assert_eq!("Upgrading", health.message);
+    }
+
+    #[test]
+    fn test_health_from_other_errors_is_reported() {
+        let response = Err(error::gen("connection refused"));
+        let health = RemoteHealth::from_response("gitlab.com", response, |_| String::new());
+        assert_eq!(HEALTH_ERROR, health.status);
+        assert_eq!("connection refused", health.message);
+    }
+}
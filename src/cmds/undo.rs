@@ -0,0 +1,133 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use crate::api_traits::{Timestamp, Undo};
+use crate::audit::{AuditEntry, AuditLog, FileAuditLog, Reversal};
+use crate::config::Config;
+use crate::error::GRError;
+use crate::i18n::{self, Message};
+use crate::{dialog, remote, Result};
+
+#[derive(Builder)]
+pub struct UndoCliArgs {
+    /// Undo without asking for confirmation.
+    pub accept: bool,
+}
+
+impl UndoCliArgs {
+    pub fn builder() -> UndoCliArgsBuilder {
+        UndoCliArgsBuilder::default()
+    }
+}
+
+pub fn execute(
+    cli_args: UndoCliArgs,
+    config: Arc<Config>,
+    domain: String,
+    path: String,
+) -> Result<()> {
+    let entries = FileAuditLog::new(&config).entries()?;
+    let remote = remote::get_undo(domain, path, config, false)?;
+    undo(remote, entries, cli_args.accept, std::io::stdout())
+}
+
+/// Reverses the most recent operation done in the project that can be
+/// reversed. Newer operations that cannot be are reported as skipped. Undoing
+/// is recorded in the audit log too, so a second undo reverses the first one.
+fn undo<W: Write>(
+    remote: Arc<dyn Undo>,
+    entries: Vec<AuditEntry>,
+    accept: bool,
+    mut writer: W,
+) -> Result<()> {
+    for entry in entries.into_iter().rev().filter(|entry| entry.succeeded()) {
+        match remote.reversal(&entry) {
+            Reversal::Unrelated => continue,
+            Reversal::Irreversible => writeln!(
+                writer,
+                "{}",
+                i18n::format(
+                    Message::SkippingIrreversible,
+                    &[&entry.method, &entry.url, &entry.created_at()]
+                )
+            )?,
+            Reversal::Reversible(description) => {
+                if !dialog::confirm_undo(&description, accept) {
+                    return Ok(());
+                }
+                remote.undo(&entry)?;
+                writeln!(writer, "{}", i18n::format(Message::Undone, &[&description]))?;
+                return Ok(());
+            }
+        }
+    }
+    Err(GRError::PreconditionNotMet(
+        "No operation that can be undone found for this project".to_string(),
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct UndoMock {
+        undone: Mutex<Vec<String>>,
+    }
+
+    impl Undo for UndoMock {
+        fn reversal(&self, entry: &AuditEntry) -> Reversal {
+            if entry.url.ends_with("/merge") {
+                Reversal::Irreversible
+            } else if entry.url.contains("other") {
+                Reversal::Unrelated
+            } else {
+                Reversal::Reversible(format!("Reopen {}", entry.url))
+            }
+        }
+        fn undo(&self, entry: &AuditEntry) -> Result<()> {
+            self.undone.lock().unwrap().push(entry.url.clone());
+            Ok(())
+        }
+    }
+
+    fn entry(url: &str, result: &str) -> AuditEntry {
+        AuditEntry::builder()
+            .timestamp(0)
+            .user("jdoe".to_string())
+            .method("PUT".to_string())
+            .url(url.to_string())
+            .result(result.to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_undo_most_recent_reversible_operation() {
+        let remote = Arc::new(UndoMock::default());
+        let entries = vec![
+            entry("merge_requests/1", "200"),
+            entry("merge_requests/2", "200"),
+            entry("merge_requests/3", "error: server error"),
+            entry("merge_requests/4/merge", "200"),
+            entry("other/merge_requests/5", "200"),
+        ];
+        let mut writer = Vec::new();
+        undo(remote.clone(), entries, true, &mut writer).unwrap();
+        assert_eq!(vec!["merge_requests/2"], *remote.undone.lock().unwrap());
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.starts_with("Skipping PUT merge_requests/4/merge done at "));
+        assert!(output.ends_with(": it cannot be undone\nUndone: Reopen merge_requests/2\n"));
+    }
+
+    #[test]
+    fn test_undo_nothing_to_undo_is_error() {
+        let remote = Arc::new(UndoMock::default());
+        let entries = vec![entry("other/merge_requests/5", "200")];
+        let mut writer = Vec::new();
+        assert!(undo(remote, entries, true, &mut writer).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use std::io::Write;
+use std::path::Path;
+
+use crate::api_defaults::{GITHUB_API_VERSION, GITLAB_API_PATH, USER_AGENT};
+use crate::Result;
+
+pub fn execute(verbose: bool, config_file: &Path) -> Result<()> {
+    print_version(verbose, config_file, std::io::stdout())
+}
+
+fn print_version<W: Write>(verbose: bool, config_file: &Path, mut writer: W) -> Result<()> {
+    writeln!(writer, "gr {}", env!("CARGO_PKG_VERSION"))?;
+    if !verbose {
+        return Ok(());
+    }
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    let features = if cfg!(feature = "testing") {
+        "testing"
+    } else {
+        "none"
+    };
+    let config_status = if config_file.exists() {
+        ""
+    } else {
+        " (not found)"
+    };
+    writeln!(
+        writer,
+        "Build: {} {}-{}",
+        profile,
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )?;
+    writeln!(writer, "Features: {}", features)?;
+    writeln!(
+        writer,
+        "APIs: Gitlab REST {} and GraphQL, Github REST {} and GraphQL",
+        GITLAB_API_PATH.trim_start_matches("api/"),
+        GITHUB_API_VERSION
+    )?;
+    writeln!(writer, "User-Agent: {}", USER_AGENT)?;
+    writeln!(
+        writer,
+        "Config file: {}{}",
+        config_file.display(),
+        config_status
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_print_version() {
+        let mut writer = Vec::new();
+        print_version(false, Path::new("/nonexistent/api"), &mut writer).unwrap();
+        assert_eq!(
+            format!("gr {}\n", env!("CARGO_PKG_VERSION")),
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_print_verbose_version_reports_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("api");
+        std::fs::write(&config_file, "").unwrap();
+        let mut writer = Vec::new();
+        print_version(true, &config_file, &mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output
+            .contains("APIs: Gitlab REST v4 and GraphQL, Github REST 2022-11-28 and GraphQL\n"));
+        assert!(output.contains(&format!(
+            "User-Agent: gitar/{}\n",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(output.ends_with(&format!("Config file: {}\n", config_file.display())));
+
+        let mut writer = Vec::new();
+        print_version(true, Path::new("/nonexistent/api"), &mut writer).unwrap();
+        assert!(String::from_utf8(writer)
+            .unwrap()
+            .ends_with("Config file: /nonexistent/api (not found)\n"));
+    }
+}
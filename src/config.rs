@@ -1,9 +1,18 @@
 //! Config file parsing and validation.
 
-use crate::api_defaults::{RATE_LIMIT_REMAINING_THRESHOLD, REST_API_MAX_PAGES};
+use crate::api_defaults::{
+    RATE_LIMIT_REMAINING_THRESHOLD, REST_API_MAX_PAGES, REST_API_MAX_PER_PAGE,
+    RETRY_BACKOFF_BASE_SECONDS, RETRY_MAX_RETRIES, TOKEN_EXPIRATION_WARNING_DAYS,
+};
 use crate::api_traits::ApiOperation;
-use crate::error;
+use crate::error::{self, GRError};
+use crate::git::UpdateStrategy;
+use crate::io::TaskRunner;
+use crate::paths::{self, Paths};
+use crate::shell::Shell;
+use crate::time::Seconds;
 use crate::Result;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::{collections::HashMap, io::Read};
 
@@ -26,6 +35,122 @@ pub trait ConfigProperties {
     fn rate_limit_remaining_threshold(&self) -> u32 {
         RATE_LIMIT_REMAINING_THRESHOLD
     }
+
+    /// Longest wait for the rate limit to reset once the remaining threshold
+    /// is reached. Requests fail instead if the reset is further away. 0, the
+    /// default, always fails.
+    fn rate_limit_max_wait(&self) -> Seconds {
+        Seconds::new(0)
+    }
+
+    /// Remaining requests below which requests are spread evenly until the
+    /// rate limit resets, so the threshold is not reached. 0 disables it.
+    fn rate_limit_slowdown_threshold(&self) -> u32 {
+        0
+    }
+
+    /// Times a GET request failing with a 5xx, a 429 or a network error is
+    /// retried. 0 disables retries.
+    fn retry_max_retries(&self) -> u32 {
+        RETRY_MAX_RETRIES
+    }
+
+    /// Wait before the first retry, doubled on every retry after it.
+    fn retry_backoff_base(&self) -> Seconds {
+        Seconds::new(RETRY_BACKOFF_BASE_SECONDS)
+    }
+
+    /// Randomize the wait between retries.
+    fn retry_jitter(&self) -> bool {
+        true
+    }
+
+    /// Days before the API token expires to start warning. 0 disables it.
+    fn token_expiration_warning_days(&self) -> u32 {
+        TOKEN_EXPIRATION_WARNING_DAYS
+    }
+
+    /// Number of results per page in list requests. None uses the remote's
+    /// default.
+    fn per_page(&self) -> Option<u32> {
+        None
+    }
+
+    /// How to update the feature branch with the target before opening a
+    /// merge request.
+    fn merge_request_update_strategy(&self) -> UpdateStrategy {
+        UpdateStrategy::Rebase
+    }
+
+    /// Push options always sent when pushing the branch of a new merge
+    /// request.
+    fn merge_request_push_options(&self) -> &[String] {
+        &[]
+    }
+
+    /// Topics set on projects created from a template with `gr pj new`.
+    fn project_template_topics(&self) -> &[String] {
+        &[]
+    }
+
+    /// Labels created in projects created from a template with `gr pj new`.
+    fn project_template_labels(&self) -> &[String] {
+        &[]
+    }
+
+    /// Usernames in the assignee group `name`, configured as
+    /// `assignee_group_<name>=user1,user2`.
+    fn assignee_group(&self, _name: &str) -> Option<&[String]> {
+        None
+    }
+
+    /// Refuse to approve or merge merge requests authored by the current
+    /// user instead of just warning.
+    fn block_self_review(&self) -> bool {
+        false
+    }
+
+    /// Items the reviewer confirms before `gr mr approve`.
+    fn review_checklist(&self) -> &[String] {
+        &[]
+    }
+
+    /// Additional headers sent in every request to the remote, configured as
+    /// `extra_header_<name>=value`. Underscores in the name become dashes, so
+    /// `extra_header_CF_Access_Client_Id` sends `CF-Access-Client-Id`.
+    fn extra_headers(&self) -> &[(String, String)] {
+        &[]
+    }
+
+    /// Appended to gitar's User-Agent, ex. to tell apart the requests of a CI
+    /// job in the remote's logs.
+    fn user_agent_suffix(&self) -> &str {
+        ""
+    }
+
+    /// Print plain ASCII output, same as `--ascii`.
+    fn ascii_output(&self) -> bool {
+        false
+    }
+
+    /// Expiration of the cached responses telling that a resource does not
+    /// exist or that a list is empty. None expires them as the rest of the
+    /// responses of their API operation.
+    fn cache_negative_expiration(&self) -> Option<Seconds> {
+        None
+    }
+
+    /// Passphrase the cached responses are encrypted with. Empty, the
+    /// default, keeps them in plain.
+    fn cache_passphrase(&self) -> &str {
+        ""
+    }
+
+    /// Columns printed by default when listing with `command`, configured as
+    /// `columns_<command>=url,status`, ex. `columns_pipeline`.
+    fn columns(&self, _command: &str) -> &[String] {
+        &[]
+    }
 }
 
 #[derive(Clone, Default)]
@@ -35,27 +160,92 @@ pub struct Config {
     preferred_assignee_username: String,
     merge_request_description_signature: String,
     cache_expirations: HashMap<ApiOperation, String>,
+    cache_negative_expiration: Option<Seconds>,
     max_pages: HashMap<ApiOperation, u32>,
     rate_limit_remaining_threshold: u32,
+    rate_limit_max_wait: Seconds,
+    rate_limit_slowdown_threshold: u32,
+    retry_max_retries: u32,
+    retry_backoff_base: Seconds,
+    retry_jitter: bool,
+    token_expiration_warning_days: u32,
+    per_page: Option<u32>,
+    merge_request_update_strategy: UpdateStrategy,
+    merge_request_push_options: Vec<String>,
+    project_template_topics: Vec<String>,
+    project_template_labels: Vec<String>,
+    assignee_groups: HashMap<String, Vec<String>>,
+    block_self_review: bool,
+    review_checklist: Vec<String>,
+    extra_headers: Vec<(String, String)>,
+    user_agent_suffix: String,
+    ascii_output: bool,
+    cache_passphrase: String,
+    columns: HashMap<String, Vec<String>>,
 }
 
 impl Config {
-    // TODO: make use of a BufReader instead
+    /// Configuration of `domain`, with the `GITAR_*` environment variables
+    /// overriding the config file.
     pub fn new<T: Read>(reader: T, domain: &str) -> Result<Self> {
-        let config = Config::parse(reader, domain)?;
-        let domain_config_data = config.get(domain).unwrap();
+        Config::with_env(reader, domain, std::env::vars())
+    }
+
+    // TODO: make use of a BufReader instead
+    pub fn with_env<T: Read>(
+        mut reader: T,
+        domain: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self> {
+        let mut config_data = String::new();
+        reader.read_to_string(&mut config_data)?;
+        let vars = vars.into_iter().collect::<Vec<_>>();
+        let paths = Paths::from_vars(vars.clone());
+        let home = vars
+            .iter()
+            .find(|(name, _)| name == "HOME")
+            .map(|(_, home)| PathBuf::from(home));
+        let env_config = env_overrides(vars, domain)?;
+        // Only the errors of the domain in use are fatal, along with the ones
+        // of global keys and unparseable lines, as they could be meant for any
+        // domain. Unknown keys and other domains are reported by `gr config
+        // check`. Keys set in the environment replace the file ones, invalid
+        // or missing.
+        if let Some(issue) = Config::check(config_data.as_bytes())?
+            .into_iter()
+            .find(|issue| {
+                issue.severity == Severity::Error
+                    && (issue.domain == domain || issue.domain.is_empty())
+                    && !env_config.contains_key(&issue.key)
+            })
+        {
+            return Err(GRError::ConfigurationError(issue.to_string()).into());
+        }
+        let mut config = Config::parse(config_data.as_bytes(), domain)?;
+        let domain_config_data = config.get_mut(domain).unwrap();
+        domain_config_data.extend(env_config);
+        let domain_config_data = &*domain_config_data;
         let api_token = domain_config_data.get("api_token").ok_or_else(|| {
             error::gen(format!(
-                "No api_token found for domain {} in config",
-                domain
-            ))
-        })?;
-        let cache_location = domain_config_data.get("cache_location").ok_or_else(|| {
-            error::gen(format!(
-                "No cache_location found for domain {} in config",
-                domain
+                "No api_token found for domain {} in config or {}",
+                domain,
+                env_var_name("api_token", domain)
             ))
         })?;
+        let cache_location = domain_config_data
+            .get("cache_location")
+            .map(|location| paths::expand_location(location, home.as_deref()))
+            .or_else(|| {
+                let cache_dir = paths.cache_dir.as_ref()?;
+                Some(cache_dir.to_string_lossy().to_string())
+            })
+            .ok_or_else(|| {
+                error::gen(format!(
+                    "No cache_location found for domain {} in config or {}",
+                    domain,
+                    env_var_name("cache_location", domain)
+                ))
+            })?;
         let default_assignee_username = "".to_string();
         let preferred_assignee_username = domain_config_data
             .get("preferred_assignee_username")
@@ -65,11 +255,79 @@ impl Config {
             .get("merge_request_description_signature")
             .unwrap_or(&default_merge_request_description_signature);
         let cache_expirations = Config::cache_expirations(domain_config_data);
+        let cache_negative_expiration = domain_config_data
+            .get("cache_negative_expiration")
+            .and_then(|s| Seconds::try_from(s.as_str()).ok());
         let max_pages = Config::max_pages(domain_config_data);
         let rate_limit_remaining_threshold = domain_config_data
             .get("rate_limit_remaining_threshold")
             .and_then(|s| s.parse().ok())
             .unwrap_or(RATE_LIMIT_REMAINING_THRESHOLD);
+        let rate_limit_max_wait = domain_config_data
+            .get("rate_limit_max_wait")
+            .and_then(|s| Seconds::try_from(s.as_str()).ok())
+            .unwrap_or_default();
+        let rate_limit_slowdown_threshold = domain_config_data
+            .get("rate_limit_slowdown_threshold")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        let retry_max_retries = domain_config_data
+            .get("retry_max_retries")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(RETRY_MAX_RETRIES);
+        let retry_backoff_base = domain_config_data
+            .get("retry_backoff_base")
+            .and_then(|s| Seconds::try_from(s.as_str()).ok())
+            .unwrap_or(Seconds::new(RETRY_BACKOFF_BASE_SECONDS));
+        let retry_jitter = domain_config_data
+            .get("retry_jitter")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(true);
+        let token_expiration_warning_days = domain_config_data
+            .get("token_expiration_warning_days")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(TOKEN_EXPIRATION_WARNING_DAYS);
+        let per_page = domain_config_data
+            .get("per_page")
+            .and_then(|s| s.parse().ok())
+            .filter(|per_page| (1..=REST_API_MAX_PER_PAGE).contains(per_page));
+        let merge_request_update_strategy = match domain_config_data
+            .get("merge_request_update_strategy")
+        {
+            Some(strategy) => UpdateStrategy::try_from(strategy.as_str()).map_err(error::gen)?,
+            None => UpdateStrategy::default(),
+        };
+        let merge_request_push_options =
+            Config::comma_separated(domain_config_data, "merge_request_push_options");
+        let project_template_topics =
+            Config::comma_separated(domain_config_data, "project_template_topics");
+        let project_template_labels =
+            Config::comma_separated(domain_config_data, "project_template_labels");
+        let assignee_groups = Config::prefixed_lists(domain_config_data, "assignee_group_");
+        let columns = Config::prefixed_lists(domain_config_data, "columns_");
+        let block_self_review = domain_config_data
+            .get("block_self_review")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(false);
+        let review_checklist = Config::comma_separated(domain_config_data, "review_checklist");
+        let extra_headers = Config::extra_headers(domain_config_data);
+        let user_agent_suffix = domain_config_data
+            .get("user_agent_suffix")
+            .map(|suffix| suffix.trim().to_string())
+            .unwrap_or_default();
+        let ascii_output = domain_config_data
+            .get("ascii_output")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(false);
+        // A command, ex. one reading a keyring entry, keeps the passphrase out
+        // of the config file.
+        let cache_passphrase = match domain_config_data.get("cache_passphrase_command") {
+            Some(command) => passphrase_from_command(command)?,
+            None => domain_config_data
+                .get("cache_passphrase")
+                .cloned()
+                .unwrap_or_default(),
+        };
 
         Ok(Config {
             api_token: api_token.to_string(),
@@ -77,11 +335,171 @@ impl Config {
             preferred_assignee_username: preferred_assignee_username.to_string(),
             merge_request_description_signature: merge_request_description_signature.to_string(),
             cache_expirations,
+            cache_negative_expiration,
             max_pages,
             rate_limit_remaining_threshold,
+            rate_limit_max_wait,
+            rate_limit_slowdown_threshold,
+            retry_max_retries,
+            retry_backoff_base,
+            retry_jitter,
+            token_expiration_warning_days,
+            per_page,
+            merge_request_update_strategy,
+            merge_request_push_options,
+            project_template_topics,
+            project_template_labels,
+            assignee_groups,
+            block_self_review,
+            review_checklist,
+            extra_headers,
+            user_agent_suffix,
+            ascii_output,
+            cache_passphrase,
+            columns,
         })
     }
 
+    /// Returns the domains that have an api_token configured, in the order
+    /// they appear in the configuration file.
+    pub fn domains<T: Read>(mut reader: T) -> Result<Vec<String>> {
+        let mut config_data = String::new();
+        reader.read_to_string(&mut config_data)?;
+        let regex = regex::Regex::new(r"^(?P<domain>.+)\.api_token=").unwrap();
+        let mut domains = Vec::new();
+        for line in config_data.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            if let Some(captured_names) = regex.captures(line) {
+                let domain = captured_names.name("domain").unwrap().as_str().to_string();
+                if !domains.contains(&domain) {
+                    domains.push(domain);
+                }
+            }
+        }
+        Ok(domains)
+    }
+
+    /// Validates all the domains in the config file. Reports lines that are
+    /// not `[<domain>.]<key>=<value>`, unknown keys, values of the wrong type
+    /// and missing required keys, in line order.
+    pub fn check<T: Read>(mut reader: T) -> Result<Vec<ConfigIssue>> {
+        let mut config_data = String::new();
+        reader.read_to_string(&mut config_data)?;
+        let regex = regex::Regex::new(r"^(?P<domain>[^=]+)\.(?P<key>\w+)=(?P<value>.*)$").unwrap();
+        let global_regex = regex::Regex::new(GLOBAL_KEY_REGEX).unwrap();
+        let mut issues = Vec::new();
+        // Domain, first line where it appears and keys found.
+        let mut domains: Vec<(String, usize, Vec<String>)> = Vec::new();
+        let mut global_keys = Vec::new();
+        for (index, line) in config_data.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (domain, key, value) = if let Some(captured_names) = regex.captures(line) {
+                let domain = captured_names.name("domain").unwrap().as_str();
+                let key = captured_names.name("key").unwrap().as_str();
+                match domains.iter_mut().find(|(name, _, _)| name == domain) {
+                    Some((_, _, keys)) => keys.push(key.to_string()),
+                    None => domains.push((domain.to_string(), line_number, vec![key.to_string()])),
+                }
+                (domain, key, captured_names.name("value").unwrap().as_str())
+            } else if let Some(captured_names) = global_regex.captures(line) {
+                let key = captured_names.name("key").unwrap().as_str();
+                global_keys.push(key.to_string());
+                ("", key, captured_names.name("value").unwrap().as_str())
+            } else {
+                issues.push(ConfigIssue::error(
+                    line_number,
+                    "",
+                    "",
+                    format!("expected [<domain>.]<key>=<value>, found {}", line),
+                    Some("Fix the line or comment it out with #".to_string()),
+                ));
+                continue;
+            };
+            match value_kind(key) {
+                Some(kind) => {
+                    if let Some(issue) = kind.check(line_number, domain, key, value) {
+                        issues.push(issue);
+                    }
+                }
+                None => issues.push(ConfigIssue::warning(
+                    line_number,
+                    domain,
+                    key,
+                    format!("unknown key {}, it is ignored", key),
+                    suggest_key(key)
+                        .map(|name| format!("Did you mean {}?", qualified_key(domain, name))),
+                )),
+            }
+        }
+        for (domain, line_number, keys) in domains {
+            for required in REQUIRED_KEYS {
+                if !keys.iter().chain(&global_keys).any(|key| key == required) {
+                    issues.push(ConfigIssue::error(
+                        line_number,
+                        &domain,
+                        required,
+                        format!("missing required key {} for domain {}", required, domain),
+                        Some(format!("Add {}.{}=<value>", domain, required)),
+                    ));
+                }
+            }
+        }
+        issues.sort_by_key(|issue| issue.line);
+        Ok(issues)
+    }
+
+    fn comma_separated(domain_config_data: &HashMap<String, String>, key: &str) -> Vec<String> {
+        domain_config_data
+            .get(key)
+            .map(|values| {
+                values
+                    .split(',')
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Comma separated values of the keys starting with `prefix`, by the name
+    /// that follows it.
+    fn prefixed_lists(
+        domain_config_data: &HashMap<String, String>,
+        prefix: &str,
+    ) -> HashMap<String, Vec<String>> {
+        domain_config_data
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix(prefix)?;
+                let values = value
+                    .split(',')
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .collect();
+                Some((name.to_string(), values))
+            })
+            .collect()
+    }
+
+    fn extra_headers(domain_config_data: &HashMap<String, String>) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = domain_config_data
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix("extra_header_")?;
+                Some((name.replace('_', "-"), value.trim().to_string()))
+            })
+            .collect();
+        headers.sort();
+        headers
+    }
+
     fn max_pages(domain_config_data: &HashMap<String, String>) -> HashMap<ApiOperation, u32> {
         let mut max_pages: HashMap<ApiOperation, u32> = HashMap::new();
         max_pages.insert(
@@ -119,6 +537,13 @@ impl Config {
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(REST_API_MAX_PAGES),
         );
+        max_pages.insert(
+            ApiOperation::Issue,
+            domain_config_data
+                .get("max_pages_api_issue")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(REST_API_MAX_PAGES),
+        );
         max_pages
     }
 
@@ -161,6 +586,13 @@ impl Config {
                 .unwrap_or(&"".to_string())
                 .to_string(),
         );
+        cache_expirations.insert(
+            ApiOperation::Issue,
+            domain_config_data
+                .get("cache_api_issue_expiration")
+                .unwrap_or(&"".to_string())
+                .to_string(),
+        );
         cache_expirations
     }
 
@@ -174,26 +606,33 @@ impl Config {
         let mut config = HashMap::new();
         let mut domain_config = HashMap::new();
 
-        let regex =
-            regex::Regex::new(&format!(r"^{}\.(?P<key>\w+)=(?P<value>.*)", domain)).unwrap();
+        let regex = regex::Regex::new(&format!(
+            r"^{}\.(?P<key>\w+)=(?P<value>.*)",
+            regex::escape(domain)
+        ))
+        .unwrap();
+        let global_regex = regex::Regex::new(GLOBAL_KEY_REGEX).unwrap();
+        let mut global_config = HashMap::new();
         for line in lines {
             let line = line.trim();
             if line.is_empty() || line.starts_with('#') {
                 continue;
             }
             // capture groups key and value from regex
-            let captured_names = regex.captures(line);
-            match captured_names {
-                Some(captured_names) => {
-                    let key = captured_names.name("key").unwrap().as_str();
-                    let value = captured_names.name("value").unwrap().as_str();
-                    domain_config.insert(key.to_string(), value.to_string());
-                }
-                None => {
-                    continue;
-                }
+            if let Some(captured_names) = regex.captures(line) {
+                let key = captured_names.name("key").unwrap().as_str();
+                let value = captured_names.name("value").unwrap().as_str();
+                domain_config.insert(key.to_string(), value.to_string());
+            } else if let Some(captured_names) = global_regex.captures(line) {
+                let key = captured_names.name("key").unwrap().as_str();
+                let value = captured_names.name("value").unwrap().as_str();
+                global_config.insert(key.to_string(), value.to_string());
             }
         }
+        // Global keys apply to every domain that does not set them.
+        for (key, value) in global_config {
+            domain_config.entry(key).or_insert(value);
+        }
 
         config.insert(domain.to_string(), domain_config);
         if config.is_empty() {
@@ -203,6 +642,432 @@ impl Config {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    /// The command cannot run with this configuration.
+    Error,
+    /// The configuration works, but likely not as intended.
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigIssue {
+    /// Line of the config file, starting at 1.
+    pub line: usize,
+    pub severity: Severity,
+    /// Domain the line configures. Empty for global keys and lines that
+    /// cannot be parsed.
+    pub domain: String,
+    /// Key the issue is about. Empty if the line cannot be parsed.
+    pub key: String,
+    pub message: String,
+    /// How to fix it, if known.
+    pub fix: Option<String>,
+}
+
+impl ConfigIssue {
+    fn error(line: usize, domain: &str, key: &str, message: String, fix: Option<String>) -> Self {
+        ConfigIssue {
+            line,
+            severity: Severity::Error,
+            domain: domain.to_string(),
+            key: key.to_string(),
+            message,
+            fix,
+        }
+    }
+
+    fn warning(line: usize, domain: &str, key: &str, message: String, fix: Option<String>) -> Self {
+        ConfigIssue {
+            severity: Severity::Warning,
+            ..ConfigIssue::error(line, domain, key, message, fix)
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "line {}: {}: {}", self.line, severity, self.message)?;
+        if let Some(fix) = &self.fix {
+            write!(f, ". {}", fix)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ValueKind {
+    Text,
+    Number,
+    Bool,
+    Duration,
+    PerPage,
+    UpdateStrategy,
+}
+
+impl ValueKind {
+    fn check(self, line: usize, domain: &str, key: &str, value: &str) -> Option<ConfigIssue> {
+        let value = value.trim();
+        let (valid, expected) = match self {
+            ValueKind::Text => (true, ""),
+            ValueKind::Number => (value.parse::<u32>().is_ok(), "a positive number, ex. 10"),
+            ValueKind::Bool => (value.parse::<bool>().is_ok(), "true or false"),
+            ValueKind::Duration => (
+                Seconds::try_from(value).is_ok(),
+                "a duration such as 30s, 5m, 1h or 1d",
+            ),
+            ValueKind::PerPage => {
+                return match value.parse::<u32>() {
+                    Ok(per_page) if (1..=REST_API_MAX_PER_PAGE).contains(&per_page) => None,
+                    Ok(_) => Some(ConfigIssue::warning(
+                        line,
+                        domain,
+                        key,
+                        format!("{} is out of range, the remote's default is used", key),
+                        Some(format!(
+                            "Set a number between 1 and {}",
+                            REST_API_MAX_PER_PAGE
+                        )),
+                    )),
+                    Err(_) => Some(invalid_value(
+                        line,
+                        domain,
+                        key,
+                        value,
+                        &format!("a number between 1 and {}", REST_API_MAX_PER_PAGE),
+                    )),
+                };
+            }
+            ValueKind::UpdateStrategy => (
+                UpdateStrategy::try_from(value).is_ok(),
+                "rebase, merge or none",
+            ),
+        };
+        if valid {
+            None
+        } else {
+            Some(invalid_value(line, domain, key, value, expected))
+        }
+    }
+}
+
+fn invalid_value(line: usize, domain: &str, key: &str, value: &str, expected: &str) -> ConfigIssue {
+    ConfigIssue::error(
+        line,
+        domain,
+        key,
+        format!("invalid value {} for {}", value, key),
+        Some(format!("Expected {}", expected)),
+    )
+}
+
+const REQUIRED_KEYS: &[&str] = &["api_token"];
+
+/// Keys holding a name after the prefix, ex. `assignee_group_backend`.
+const KEY_PREFIXES: &[&str] = &["assignee_group_", "extra_header_", "columns_"];
+
+const KEYS: &[(&str, ValueKind)] = &[
+    ("api_token", ValueKind::Text),
+    ("cache_location", ValueKind::Text),
+    ("preferred_assignee_username", ValueKind::Text),
+    ("merge_request_description_signature", ValueKind::Text),
+    ("cache_api_merge_request_expiration", ValueKind::Duration),
+    ("cache_api_pipeline_expiration", ValueKind::Duration),
+    ("cache_api_project_expiration", ValueKind::Duration),
+    (
+        "cache_api_container_registry_expiration",
+        ValueKind::Duration,
+    ),
+    ("cache_api_release_expiration", ValueKind::Duration),
+    ("cache_api_issue_expiration", ValueKind::Duration),
+    ("cache_negative_expiration", ValueKind::Duration),
+    ("max_pages_api_merge_request", ValueKind::Number),
+    ("max_pages_api_pipeline", ValueKind::Number),
+    ("max_pages_api_project", ValueKind::Number),
+    ("max_pages_api_container_registry", ValueKind::Number),
+    ("max_pages_api_release", ValueKind::Number),
+    ("max_pages_api_issue", ValueKind::Number),
+    ("rate_limit_remaining_threshold", ValueKind::Number),
+    ("rate_limit_max_wait", ValueKind::Duration),
+    ("rate_limit_slowdown_threshold", ValueKind::Number),
+    ("retry_max_retries", ValueKind::Number),
+    ("retry_backoff_base", ValueKind::Duration),
+    ("retry_jitter", ValueKind::Bool),
+    ("token_expiration_warning_days", ValueKind::Number),
+    ("per_page", ValueKind::PerPage),
+    ("merge_request_update_strategy", ValueKind::UpdateStrategy),
+    ("merge_request_push_options", ValueKind::Text),
+    ("project_template_topics", ValueKind::Text),
+    ("project_template_labels", ValueKind::Text),
+    ("block_self_review", ValueKind::Bool),
+    ("review_checklist", ValueKind::Text),
+    ("user_agent_suffix", ValueKind::Text),
+    ("ascii_output", ValueKind::Bool),
+    ("cache_passphrase", ValueKind::Text),
+    ("cache_passphrase_command", ValueKind::Text),
+    ("metrics_statsd", ValueKind::Text),
+    ("metrics_otlp_endpoint", ValueKind::Text),
+    ("metrics_prefix", ValueKind::Text),
+];
+
+/// Prefix of the environment variables that override the config file.
+pub const ENV_PREFIX: &str = "GITAR_";
+
+/// Value of a `GITAR_<KEY>` environment variable that applies to gitar as a
+/// whole instead of a domain, ex. `GITAR_LANG`.
+pub fn global_env_var(key: &str) -> Option<String> {
+    std::env::var(format!("{}{}", ENV_PREFIX, key.to_uppercase())).ok()
+}
+
+/// Shorter environment variable names for some keys, ex. `GITAR_CACHE_DIR`.
+const ENV_ALIASES: &[(&str, &str)] = &[("cache_dir", "cache_location")];
+
+/// Environment variable overriding `key` for `domain`, ex.
+/// `GITAR_API_TOKEN_GITLAB_COM`.
+pub fn env_var_name(key: &str, domain: &str) -> String {
+    format!(
+        "{}{}_{}",
+        ENV_PREFIX,
+        key.to_uppercase(),
+        env_domain(domain)
+    )
+}
+
+/// Domain as written in environment variable names, ex. `GITLAB_COM`.
+fn env_domain(domain: &str) -> String {
+    domain
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Known config key set by a lowercased environment variable name without
+/// the prefix.
+fn env_key(name: &str) -> Option<String> {
+    let key = ENV_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, key)| key);
+    value_kind(key).map(|_| key.to_string())
+}
+
+/// Keys set in the environment for `domain`. `GITAR_<KEY>_<DOMAIN>` sets a key
+/// for one domain and `GITAR_<KEY>` for all of them, the former winning.
+/// Values are validated as the ones in the config file. Variables that are
+/// not config keys, ex. `GITAR_FORMAT` or `GITAR_LANG`, are ignored.
+fn env_overrides(
+    vars: impl IntoIterator<Item = (String, String)>,
+    domain: &str,
+) -> Result<HashMap<String, String>> {
+    let domain_suffix = format!("_{}", env_domain(domain).to_lowercase());
+    let mut global_values = HashMap::new();
+    let mut domain_values = HashMap::new();
+    for (name, value) in vars {
+        let Some(var_key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let var_key = var_key.to_lowercase();
+        let (values, key) = match var_key.strip_suffix(&domain_suffix).and_then(env_key) {
+            Some(key) => (&mut domain_values, key),
+            None => match env_key(&var_key) {
+                Some(key) => (&mut global_values, key),
+                None => continue,
+            },
+        };
+        let kind = value_kind(&key).unwrap();
+        if let Some(issue) = kind.check(0, domain, &key, &value) {
+            if issue.severity == Severity::Error {
+                let mut message = format!("{}: {}", name, issue.message);
+                if let Some(fix) = issue.fix {
+                    message.push_str(&format!(". {}", fix));
+                }
+                return Err(GRError::ConfigurationError(message).into());
+            }
+        }
+        values.insert(key, value);
+    }
+    global_values.extend(domain_values);
+    Ok(global_values)
+}
+
+/// Whether the config file or the environment set an API token for
+/// `domain`. Invalid environment variables count as configured, so the error
+/// is reported when loading the configuration.
+pub fn is_configured(
+    config_data: &str,
+    domain: &str,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> bool {
+    get_value(config_data, Some(domain), "api_token").is_some()
+        || env_overrides(vars, domain).map_or(true, |env| env.contains_key("api_token"))
+}
+
+/// Passphrase printed by the shell `command`, its first line.
+fn passphrase_from_command(command: &str) -> Result<String> {
+    let output = Shell.run(["sh", "-c", command]).map_err(|err| {
+        GRError::ConfigurationError(format!(
+            "cache_passphrase_command {} failed: {}",
+            command, err
+        ))
+    })?;
+    let passphrase = output.body.lines().next().unwrap_or_default().to_string();
+    if passphrase.is_empty() {
+        return Err(GRError::ConfigurationError(format!(
+            "cache_passphrase_command {} printed no passphrase",
+            command
+        ))
+        .into());
+    }
+    Ok(passphrase)
+}
+
+/// Keys without a domain, ex. `per_page=50`, apply to all domains.
+const GLOBAL_KEY_REGEX: &str = r"^(?P<key>\w+)=(?P<value>.*)$";
+
+fn value_kind(key: &str) -> Option<ValueKind> {
+    KEYS.iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, kind)| *kind)
+        .or_else(|| {
+            KEY_PREFIXES
+                .iter()
+                .any(|prefix| key.starts_with(prefix) && key.len() > prefix.len())
+                .then_some(ValueKind::Text)
+        })
+}
+
+/// Key as written in the config file, `<domain>.<key>` or just `<key>` if
+/// global.
+fn qualified_key(domain: &str, key: &str) -> String {
+    if domain.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", domain, key)
+    }
+}
+
+/// Value of `key` for `domain`, falling back to the global one, or only the
+/// global one if no domain is given.
+pub fn get_value(config_data: &str, domain: Option<&str>, key: &str) -> Option<String> {
+    let find = |domain: &str| {
+        let prefix = format!("{}=", qualified_key(domain, key));
+        config_data
+            .lines()
+            .rev()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .find_map(|line| line.strip_prefix(&prefix))
+            .map(str::to_string)
+    };
+    domain.and_then(find).or_else(|| find(""))
+}
+
+/// Returns the config data with `key` set to `value` for `domain`, or
+/// globally if no domain is given. The last line setting the key is updated
+/// in place, leaving comments and the rest of the lines untouched. If the key
+/// is not set, the line is added after the last one of the domain, or at the
+/// end. Unknown keys and invalid values are rejected.
+pub fn set_value(
+    config_data: &str,
+    domain: Option<&str>,
+    key: &str,
+    value: &str,
+) -> Result<String> {
+    let domain = domain.unwrap_or_default();
+    let Some(kind) = value_kind(key) else {
+        let mut message = format!("Unknown config key {}", key);
+        if let Some(name) = suggest_key(key) {
+            message.push_str(&format!(". Did you mean {}?", name));
+        }
+        return Err(GRError::ConfigurationError(message).into());
+    };
+    if value.contains('\n') || value.contains('\r') {
+        return Err(GRError::ConfigurationError(format!(
+            "The value of {} cannot span multiple lines",
+            key
+        ))
+        .into());
+    }
+    if let Some(issue) = kind.check(0, domain, key, value) {
+        if issue.severity == Severity::Error {
+            let mut message = issue.message;
+            if let Some(fix) = issue.fix {
+                message.push_str(&format!(". {}", fix));
+            }
+            return Err(GRError::ConfigurationError(message).into());
+        }
+    }
+    let qualified = qualified_key(domain, key);
+    let prefix = format!("{}=", qualified);
+    let new_line = format!("{}{}", prefix, value);
+    let mut lines: Vec<String> = config_data.lines().map(str::to_string).collect();
+    let is_setting = |line: &str| !line.starts_with('#') && !line.is_empty();
+    let position = lines
+        .iter()
+        .rposition(|line| line.trim().starts_with(&prefix) && is_setting(line.trim()));
+    match position {
+        Some(index) => {
+            let line = &lines[index];
+            let indent = &line[..line.len() - line.trim_start().len()];
+            lines[index] = format!("{}{}", indent, new_line);
+        }
+        None => {
+            let global_regex = regex::Regex::new(GLOBAL_KEY_REGEX).unwrap();
+            let domain_prefix = format!("{}.", domain);
+            let last_of_domain = lines.iter().rposition(|line| {
+                let line = line.trim();
+                is_setting(line)
+                    && if domain.is_empty() {
+                        global_regex.is_match(line)
+                    } else {
+                        line.starts_with(&domain_prefix)
+                    }
+            });
+            match last_of_domain {
+                Some(index) => lines.insert(index + 1, new_line),
+                None => lines.push(new_line),
+            }
+        }
+    }
+    let mut config_data = lines.join("\n");
+    config_data.push('\n');
+    Ok(config_data)
+}
+
+/// Known key closest to a misspelled one, if it is close enough to be a typo.
+fn suggest_key(key: &str) -> Option<&'static str> {
+    KEYS.iter()
+        .map(|(name, _)| (*name, edit_distance(key, name)))
+        .filter(|(name, distance)| *distance <= 3.max(name.len() / 5))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(name, _)| name)
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 impl ConfigProperties for Config {
     fn api_token(&self) -> &str {
         &self.api_token
@@ -239,6 +1104,88 @@ impl ConfigProperties for Config {
     fn rate_limit_remaining_threshold(&self) -> u32 {
         self.rate_limit_remaining_threshold
     }
+
+    fn rate_limit_max_wait(&self) -> Seconds {
+        self.rate_limit_max_wait
+    }
+
+    fn rate_limit_slowdown_threshold(&self) -> u32 {
+        self.rate_limit_slowdown_threshold
+    }
+
+    fn retry_max_retries(&self) -> u32 {
+        self.retry_max_retries
+    }
+
+    fn retry_backoff_base(&self) -> Seconds {
+        self.retry_backoff_base
+    }
+
+    fn retry_jitter(&self) -> bool {
+        self.retry_jitter
+    }
+
+    fn token_expiration_warning_days(&self) -> u32 {
+        self.token_expiration_warning_days
+    }
+
+    fn per_page(&self) -> Option<u32> {
+        self.per_page
+    }
+
+    fn merge_request_update_strategy(&self) -> UpdateStrategy {
+        self.merge_request_update_strategy
+    }
+
+    fn merge_request_push_options(&self) -> &[String] {
+        &self.merge_request_push_options
+    }
+
+    fn project_template_topics(&self) -> &[String] {
+        &self.project_template_topics
+    }
+
+    fn project_template_labels(&self) -> &[String] {
+        &self.project_template_labels
+    }
+
+    fn assignee_group(&self, name: &str) -> Option<&[String]> {
+        self.assignee_groups.get(name).map(|group| group.as_slice())
+    }
+
+    fn block_self_review(&self) -> bool {
+        self.block_self_review
+    }
+
+    fn review_checklist(&self) -> &[String] {
+        &self.review_checklist
+    }
+
+    fn extra_headers(&self) -> &[(String, String)] {
+        &self.extra_headers
+    }
+
+    fn user_agent_suffix(&self) -> &str {
+        &self.user_agent_suffix
+    }
+
+    fn ascii_output(&self) -> bool {
+        self.ascii_output
+    }
+
+    fn cache_negative_expiration(&self) -> Option<Seconds> {
+        self.cache_negative_expiration
+    }
+
+    fn cache_passphrase(&self) -> &str {
+        &self.cache_passphrase
+    }
+
+    fn columns(&self, command: &str) -> &[String] {
+        self.columns
+            .get(command)
+            .map_or(&[], |columns| columns.as_slice())
+    }
 }
 
 impl ConfigProperties for Arc<Config> {
@@ -269,44 +1216,137 @@ impl ConfigProperties for Arc<Config> {
     fn rate_limit_remaining_threshold(&self) -> u32 {
         self.as_ref().rate_limit_remaining_threshold()
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    fn rate_limit_max_wait(&self) -> Seconds {
+        self.as_ref().rate_limit_max_wait()
+    }
 
-    #[test]
-    fn test_get_api_token() {
-        let config_data = r#"
-        gitlab.com.api_token=1234
-        github.com.api_token=4567
-        gitlab.com.cache_location=/home/user/.config/mr_cache
-        github.com.cache_location=/home/user/.config/mr_cache
-        "#;
-        let domain = "gitlab.com";
-        let reader = std::io::Cursor::new(config_data);
-        let config = Arc::new(Config::new(reader, domain).unwrap());
-        assert_eq!("1234", config.api_token());
+    fn rate_limit_slowdown_threshold(&self) -> u32 {
+        self.as_ref().rate_limit_slowdown_threshold()
     }
 
-    #[test]
-    fn test_ignore_commented_out_lines_and_empty_lines() {
-        let config_data = r#"
+    fn retry_max_retries(&self) -> u32 {
+        self.as_ref().retry_max_retries()
+    }
 
-        # api token
-        gitlab.com.api_token=1234
-        github.com.api_token=4567
-        gitlab.com.cache_location=/home/user/.config/mr_cache
-        github.com.cache_location=/home/user/.config/mr_cache
-        "#;
-        let domain = "gitlab.com";
-        let reader = std::io::Cursor::new(config_data);
-        let config = Arc::new(Config::new(reader, domain).unwrap());
-        assert_eq!("1234", config.api_token());
+    fn retry_backoff_base(&self) -> Seconds {
+        self.as_ref().retry_backoff_base()
     }
 
-    #[test]
-    fn test_no_api_token_is_err() {
+    fn retry_jitter(&self) -> bool {
+        self.as_ref().retry_jitter()
+    }
+
+    fn token_expiration_warning_days(&self) -> u32 {
+        self.as_ref().token_expiration_warning_days()
+    }
+
+    fn per_page(&self) -> Option<u32> {
+        self.as_ref().per_page()
+    }
+
+    fn merge_request_update_strategy(&self) -> UpdateStrategy {
+        self.as_ref().merge_request_update_strategy()
+    }
+
+    fn merge_request_push_options(&self) -> &[String] {
+        self.as_ref().merge_request_push_options()
+    }
+
+    fn project_template_topics(&self) -> &[String] {
+        self.as_ref().project_template_topics()
+    }
+
+    fn project_template_labels(&self) -> &[String] {
+        self.as_ref().project_template_labels()
+    }
+
+    fn assignee_group(&self, name: &str) -> Option<&[String]> {
+        self.as_ref().assignee_group(name)
+    }
+
+    fn block_self_review(&self) -> bool {
+        self.as_ref().block_self_review()
+    }
+
+    fn review_checklist(&self) -> &[String] {
+        self.as_ref().review_checklist()
+    }
+
+    fn extra_headers(&self) -> &[(String, String)] {
+        self.as_ref().extra_headers()
+    }
+
+    fn user_agent_suffix(&self) -> &str {
+        self.as_ref().user_agent_suffix()
+    }
+
+    fn ascii_output(&self) -> bool {
+        self.as_ref().ascii_output()
+    }
+
+    fn cache_negative_expiration(&self) -> Option<Seconds> {
+        self.as_ref().cache_negative_expiration()
+    }
+
+    fn cache_passphrase(&self) -> &str {
+        self.as_ref().cache_passphrase()
+    }
+
+    fn columns(&self, command: &str) -> &[String] {
+        self.as_ref().columns(command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_api_token() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        github.com.api_token=4567
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        github.com.cache_location=/home/user/.config/mr_cache
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!("1234", config.api_token());
+    }
+
+    #[test]
+    fn test_ignore_commented_out_lines_and_empty_lines() {
+        let config_data = r#"
+
+        # api token
+        gitlab.com.api_token=1234
+        github.com.api_token=4567
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        github.com.cache_location=/home/user/.config/mr_cache
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!("1234", config.api_token());
+    }
+
+    #[test]
+    fn test_get_configured_domains() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        # gitlab.mycompany.com.api_token=0000
+        github.com.api_token=4567
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        "#;
+        let reader = std::io::Cursor::new(config_data);
+        let domains = Config::domains(reader).unwrap();
+        assert_eq!(vec!["gitlab.com", "github.com"], domains);
+    }
+
+    #[test]
+    fn test_no_api_token_is_err() {
         let config_data = r#"
         # api token
         gitlab.com.api_token_typo=1234"#;
@@ -370,7 +1410,8 @@ mod test {
         github.com.cache_api_pipeline_expiration=1h
         github.com.cache_api_project_expiration=3h
         github.com.cache_api_container_registry_expiration=4h
-        github.com.cache_api_release_expiration=5h"#;
+        github.com.cache_api_release_expiration=5h
+        github.com.cache_api_issue_expiration=6h"#;
         let domain = "github.com";
         let reader = std::io::Cursor::new(config_data);
         let config = Arc::new(Config::new(reader, domain).unwrap());
@@ -385,6 +1426,7 @@ mod test {
             config.get_cache_expiration(&ApiOperation::ContainerRegistry)
         );
         assert_eq!("5h", config.get_cache_expiration(&ApiOperation::Release));
+        assert_eq!("6h", config.get_cache_expiration(&ApiOperation::Issue));
     }
 
     #[test]
@@ -502,6 +1544,322 @@ mod test {
         assert_eq!(15, config.rate_limit_remaining_threshold());
     }
 
+    #[test]
+    fn test_get_rate_limit_throttling() {
+        let config_data = r#"
+        gitlab.mycompany.com.api_token=1234
+        gitlab.mycompany.com.cache_location=/home/user/.config/mr_cache
+        gitlab.mycompany.com.rate_limit_max_wait=2m
+        gitlab.mycompany.com.rate_limit_slowdown_threshold=100
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        "#;
+        let config =
+            Config::new(std::io::Cursor::new(config_data), "gitlab.mycompany.com").unwrap();
+        assert_eq!(Seconds::new(120), config.rate_limit_max_wait());
+        assert_eq!(100, config.rate_limit_slowdown_threshold());
+        let config = Config::new(std::io::Cursor::new(config_data), "gitlab.com").unwrap();
+        assert_eq!(Seconds::new(0), config.rate_limit_max_wait());
+        assert_eq!(0, config.rate_limit_slowdown_threshold());
+    }
+
+    #[test]
+    fn test_get_retry_policy() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.retry_max_retries=5
+        gitlab.com.retry_backoff_base=2s
+        gitlab.com.retry_jitter=false
+        github.com.api_token=1234
+        github.com.cache_location=/home/user/.config/mr_cache
+        "#;
+        let config = Config::new(std::io::Cursor::new(config_data), "gitlab.com").unwrap();
+        assert_eq!(5, config.retry_max_retries());
+        assert_eq!(Seconds::new(2), config.retry_backoff_base());
+        assert!(!config.retry_jitter());
+        let config = Config::new(std::io::Cursor::new(config_data), "github.com").unwrap();
+        assert_eq!(RETRY_MAX_RETRIES, config.retry_max_retries());
+        assert_eq!(
+            Seconds::new(RETRY_BACKOFF_BASE_SECONDS),
+            config.retry_backoff_base()
+        );
+        assert!(config.retry_jitter());
+    }
+
+    #[test]
+    fn test_get_token_expiration_warning_days() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.token_expiration_warning_days=0
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(0, config.token_expiration_warning_days());
+    }
+
+    #[test]
+    fn test_get_token_expiration_warning_days_default() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(
+            TOKEN_EXPIRATION_WARNING_DAYS,
+            config.token_expiration_warning_days()
+        );
+    }
+
+    #[test]
+    fn test_get_per_page() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.per_page=100
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(Some(100), config.per_page());
+    }
+
+    #[test]
+    fn test_get_per_page_out_of_range_uses_remote_default() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.per_page=500
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(None, config.per_page());
+    }
+
+    #[test]
+    fn test_get_merge_request_update_strategy() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.merge_request_update_strategy=merge
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(
+            UpdateStrategy::Merge,
+            config.merge_request_update_strategy()
+        );
+    }
+
+    #[test]
+    fn test_get_merge_request_update_strategy_defaults_to_rebase() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(
+            UpdateStrategy::Rebase,
+            config.merge_request_update_strategy()
+        );
+    }
+
+    #[test]
+    fn test_invalid_merge_request_update_strategy_is_error() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.merge_request_update_strategy=squash
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        assert!(Config::new(reader, domain).is_err());
+    }
+
+    #[test]
+    fn test_get_merge_request_push_options() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.merge_request_push_options=ci.skip, merge_request.remove_source_branch
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(
+            vec![
+                "ci.skip".to_string(),
+                "merge_request.remove_source_branch".to_string()
+            ],
+            config.merge_request_push_options()
+        );
+    }
+
+    #[test]
+    fn test_get_project_template_topics_and_labels() {
+        let config_data = r#"
+        github.com.api_token=1234
+        github.com.cache_location=/home/user/.config/mr_cache
+        github.com.project_template_topics=rust, cli
+        github.com.project_template_labels=bug,enhancement,
+        "#;
+        let domain = "github.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(vec!["rust", "cli"], config.project_template_topics());
+        assert_eq!(vec!["bug", "enhancement"], config.project_template_labels());
+    }
+
+    #[test]
+    fn test_get_review_checklist() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.review_checklist=Tests cover the change, Changelog updated
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(
+            vec!["Tests cover the change", "Changelog updated"],
+            config.review_checklist()
+        );
+    }
+
+    #[test]
+    fn test_get_assignee_groups() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.assignee_group_backend=jdoe, tsmith
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(
+            Some(&["jdoe".to_string(), "tsmith".to_string()][..]),
+            config.assignee_group("backend")
+        );
+        assert_eq!(None, config.assignee_group("frontend"));
+    }
+
+    #[test]
+    fn test_get_columns_per_command() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.columns_pipeline=url, status,branch
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(
+            &[
+                "url".to_string(),
+                "status".to_string(),
+                "branch".to_string()
+            ][..],
+            config.columns("pipeline")
+        );
+        assert!(config.columns("merge_request").is_empty());
+    }
+
+    #[test]
+    fn test_block_self_review() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.block_self_review=true
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert!(config.block_self_review());
+    }
+
+    #[test]
+    fn test_get_extra_headers() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.extra_header_CF_Access_Client_Id=client.access
+        gitlab.com.extra_header_CF_Access_Client_Secret=secret
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!(
+            &[
+                (
+                    "CF-Access-Client-Id".to_string(),
+                    "client.access".to_string()
+                ),
+                ("CF-Access-Client-Secret".to_string(), "secret".to_string()),
+            ],
+            config.extra_headers()
+        );
+    }
+
+    #[test]
+    fn test_get_user_agent_suffix() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.user_agent_suffix= ci-bot
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert_eq!("ci-bot", config.user_agent_suffix());
+    }
+
+    #[test]
+    fn test_cache_passphrase() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.cache_passphrase=s3cr3t
+        github.com.api_token=4567
+        github.com.cache_location=/home/user/.config/mr_cache
+        github.com.cache_passphrase_command=printf 'from keyring\n'
+        "#;
+        let config = Config::new(std::io::Cursor::new(config_data), "gitlab.com").unwrap();
+        assert_eq!("s3cr3t", config.cache_passphrase());
+        let config = Config::new(std::io::Cursor::new(config_data), "github.com").unwrap();
+        assert_eq!("from keyring", config.cache_passphrase());
+    }
+
+    #[test]
+    fn test_cache_passphrase_command_failing_is_err() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.cache_passphrase_command=exit 1
+        "#;
+        assert!(Config::new(std::io::Cursor::new(config_data), "gitlab.com").is_err());
+    }
+
+    #[test]
+    fn test_ascii_output() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.ascii_output=true
+        "#;
+        let domain = "gitlab.com";
+        let reader = std::io::Cursor::new(config_data);
+        let config = Arc::new(Config::new(reader, domain).unwrap());
+        assert!(config.ascii_output());
+    }
+
     #[test]
     fn test_get_max_pages_for_container_registry_operations() {
         let config_data = r#"
@@ -527,4 +1885,298 @@ mod test {
         let config = Arc::new(Config::new(reader, domain).unwrap());
         assert_eq!(15, config.get_max_pages(&ApiOperation::Release));
     }
+
+    #[test]
+    fn test_invalid_value_is_error_with_line_number() {
+        let config_data = "gitlab.com.api_token=1234\n\
+                           gitlab.com.cache_location=/home/user/.config/mr_cache\n\
+                           gitlab.com.block_self_review=yes\n";
+        let reader = std::io::Cursor::new(config_data);
+        let Err(err) = Config::new(reader, "gitlab.com") else {
+            panic!("Expected an invalid config");
+        };
+        assert_eq!(
+            "Configuration error: line 3: error: invalid value yes for block_self_review. Expected true or false",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_errors_in_other_domains_are_not_fatal() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        github.com.max_pages_api_merge_request=many
+        "#;
+        let reader = std::io::Cursor::new(config_data);
+        assert!(Config::new(reader, "gitlab.com").is_ok());
+    }
+
+    #[test]
+    fn test_check_reports_all_domains() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.cache_api_pipeline_expiration=later
+        not a config line
+        github.com.api_tokn=5678
+        github.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.assignee_group_backend=jdoe,alice
+        "#;
+        let issues = Config::check(std::io::Cursor::new(config_data)).unwrap();
+        let summary = issues
+            .iter()
+            .map(|issue| (issue.line, issue.severity, issue.domain.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (4, Severity::Error, "gitlab.com"),
+                (5, Severity::Error, ""),
+                (6, Severity::Warning, "github.com"),
+                (6, Severity::Error, "github.com"),
+            ],
+            summary
+        );
+        assert_eq!(
+            Some("Did you mean github.com.api_token?"),
+            issues[2].fix.as_deref()
+        );
+        assert_eq!(
+            "missing required key api_token for domain github.com",
+            issues[3].message
+        );
+    }
+
+    #[test]
+    fn test_unknown_key_without_close_match_has_no_suggestion() {
+        let config_data = "gitlab.com.api_token=1234\n\
+                           gitlab.com.cache_location=/tmp\n\
+                           gitlab.com.favorite_color=blue\n";
+        let issues = Config::check(std::io::Cursor::new(config_data)).unwrap();
+        assert_eq!(1, issues.len());
+        assert_eq!(Severity::Warning, issues[0].severity);
+        assert_eq!(None, issues[0].fix);
+    }
+
+    #[test]
+    fn test_domain_keys_override_global_keys() {
+        let config_data = "per_page=50\n\
+                           api_token=shared\n\
+                           gitlab.com.per_page=20\n\
+                           gitlab.com.cache_location=/tmp\n\
+                           github.com.api_token=5678\n\
+                           github.com.cache_location=/tmp\n";
+        let config = Config::new(std::io::Cursor::new(config_data), "gitlab.com").unwrap();
+        assert_eq!("shared", config.api_token());
+        assert_eq!(Some(20), config.per_page());
+        let config = Config::new(std::io::Cursor::new(config_data), "github.com").unwrap();
+        assert_eq!("5678", config.api_token());
+        assert_eq!(Some(50), config.per_page());
+        assert!(Config::check(std::io::Cursor::new(config_data))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_invalid_global_value_is_fatal() {
+        let config_data = "retry_jitter=maybe\n\
+                           gitlab.com.api_token=1234\n\
+                           gitlab.com.cache_location=/tmp\n";
+        let reader = std::io::Cursor::new(config_data);
+        let Err(err) = Config::new(reader, "gitlab.com") else {
+            panic!("Expected an invalid config");
+        };
+        assert!(err
+            .to_string()
+            .contains("line 1: error: invalid value maybe"));
+    }
+
+    const SET_CONFIG: &str = "\
+# gitlab
+gitlab.com.api_token=1234
+gitlab.com.cache_location=/tmp
+  gitlab.com.per_page=20
+
+# github
+github.com.api_token=5678
+";
+
+    #[test]
+    fn test_set_value_updates_line_in_place() {
+        let updated = set_value(SET_CONFIG, Some("gitlab.com"), "per_page", "50").unwrap();
+        assert_eq!(
+            SET_CONFIG.replace("gitlab.com.per_page=20", "gitlab.com.per_page=50"),
+            updated
+        );
+    }
+
+    #[test]
+    fn test_set_value_adds_line_after_domain() {
+        let updated = set_value(SET_CONFIG, Some("github.com"), "cache_location", "/c").unwrap();
+        assert_eq!(
+            format!("{}github.com.cache_location=/c\n", SET_CONFIG),
+            updated
+        );
+        let updated = set_value(SET_CONFIG, Some("gitlab.com"), "retry_jitter", "false").unwrap();
+        assert_eq!(
+            SET_CONFIG.replace(
+                "gitlab.com.per_page=20\n",
+                "gitlab.com.per_page=20\ngitlab.com.retry_jitter=false\n"
+            ),
+            updated
+        );
+    }
+
+    #[test]
+    fn test_set_global_value() {
+        let updated = set_value(SET_CONFIG, None, "ascii_output", "true").unwrap();
+        assert_eq!(format!("{}ascii_output=true\n", SET_CONFIG), updated);
+        let updated = set_value(&updated, None, "ascii_output", "false").unwrap();
+        assert_eq!(format!("{}ascii_output=false\n", SET_CONFIG), updated);
+        assert_eq!(
+            Some("false".to_string()),
+            get_value(&updated, Some("gitlab.com"), "ascii_output")
+        );
+        assert_eq!(
+            Some("20".to_string()),
+            get_value(&updated, Some("gitlab.com"), "per_page")
+        );
+        assert_eq!(None, get_value(&updated, None, "per_page"));
+    }
+
+    #[test]
+    fn test_set_value_rejects_unknown_keys_and_invalid_values() {
+        let Err(err) = set_value(SET_CONFIG, Some("gitlab.com"), "per_pag", "50") else {
+            panic!("Expected an unknown key error");
+        };
+        assert_eq!(
+            "Configuration error: Unknown config key per_pag. Did you mean per_page?",
+            err.to_string()
+        );
+        let Err(err) = set_value(SET_CONFIG, None, "retry_backoff_base", "later") else {
+            panic!("Expected an invalid value error");
+        };
+        assert!(err
+            .to_string()
+            .contains("invalid value later for retry_backoff_base"));
+        assert!(set_value(SET_CONFIG, None, "api_token", "12\n34").is_err());
+    }
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_overrides_config_file() {
+        let config_data = "gitlab.com.api_token=1234\n\
+                           gitlab.com.cache_location=/tmp\n\
+                           gitlab.com.per_page=20\n";
+        let env = vars(&[
+            ("GITAR_API_TOKEN_GITLAB_COM", "secret"),
+            ("GITAR_API_TOKEN_GITHUB_COM", "other"),
+            ("GITAR_CACHE_DIR", "/cache"),
+            ("GITAR_PER_PAGE", "50"),
+            ("GITAR_FORMAT", "json"),
+            ("HOME", "/home/user"),
+        ]);
+        let config =
+            Config::with_env(std::io::Cursor::new(config_data), "gitlab.com", env).unwrap();
+        assert_eq!("secret", config.api_token());
+        assert_eq!("/cache", config.cache_location());
+        assert_eq!(Some(50), config.per_page());
+    }
+
+    #[test]
+    fn test_domain_env_var_wins_over_global_one() {
+        let env = vars(&[
+            ("GITAR_RETRY_MAX_RETRIES_GITLAB_EXAMPLE_COM", "5"),
+            ("GITAR_RETRY_MAX_RETRIES", "1"),
+            ("GITAR_API_TOKEN", "secret"),
+            ("GITAR_CACHE_LOCATION", "/cache"),
+        ]);
+        // No config file at all, ex. in a CI job.
+        let config = Config::with_env(std::io::Cursor::new(""), "gitlab.example.com", env).unwrap();
+        assert_eq!(5, config.retry_max_retries());
+        assert_eq!("secret", config.api_token());
+    }
+
+    #[test]
+    fn test_env_replaces_invalid_file_value() {
+        let config_data = "gitlab.com.cache_location=/tmp\n\
+                           gitlab.com.block_self_review=yes\n";
+        let env = vars(&[
+            ("GITAR_API_TOKEN_GITLAB_COM", "secret"),
+            ("GITAR_BLOCK_SELF_REVIEW", "true"),
+        ]);
+        let config =
+            Config::with_env(std::io::Cursor::new(config_data), "gitlab.com", env).unwrap();
+        assert!(config.block_self_review());
+    }
+
+    #[test]
+    fn test_invalid_env_value_is_error() {
+        let env = vars(&[("GITAR_RETRY_JITTER_GITLAB_COM", "maybe")]);
+        let Err(err) = Config::with_env(std::io::Cursor::new(""), "gitlab.com", env) else {
+            panic!("Expected an invalid config");
+        };
+        assert_eq!(
+            "Configuration error: GITAR_RETRY_JITTER_GITLAB_COM: invalid value maybe for retry_jitter. Expected true or false",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_cache_location_defaults_to_xdg_cache_dir() {
+        let config_data = "gitlab.com.api_token=1234\n";
+        let env = vars(&[("HOME", "/home/user"), ("XDG_CACHE_HOME", "/xdg/cache")]);
+        let config =
+            Config::with_env(std::io::Cursor::new(config_data), "gitlab.com", env).unwrap();
+        assert_eq!("/xdg/cache/gitar", config.cache_location());
+        let env = vars(&[("HOME", "/home/user")]);
+        let config =
+            Config::with_env(std::io::Cursor::new(config_data), "gitlab.com", env).unwrap();
+        assert_eq!("/home/user/.cache/gitar", config.cache_location());
+    }
+
+    #[test]
+    fn test_legacy_cache_location_is_expanded() {
+        let config_data = "gitlab.com.api_token=1234\n\
+                           gitlab.com.cache_location=\"~/.cache/gitar\"\n";
+        let env = vars(&[("HOME", "/home/user"), ("XDG_CACHE_HOME", "/xdg/cache")]);
+        let config =
+            Config::with_env(std::io::Cursor::new(config_data), "gitlab.com", env).unwrap();
+        assert_eq!("/home/user/.cache/gitar", config.cache_location());
+    }
+
+    #[test]
+    fn test_is_configured() {
+        let config_data = "gitlab.com.api_token=1234\n";
+        assert!(is_configured(config_data, "gitlab.com", vec![]));
+        assert!(!is_configured(config_data, "github.com", vec![]));
+        let env = vars(&[("GITAR_API_TOKEN_GITHUB_COM", "5678")]);
+        assert!(is_configured(config_data, "github.com", env));
+        assert!(is_configured("api_token=1234\n", "github.com", vec![]));
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(
+            "GITAR_API_TOKEN_GITLAB_COM",
+            env_var_name("api_token", "gitlab.com")
+        );
+        assert_eq!(
+            "GITAR_PER_PAGE_GIT_MY_ORG_IO",
+            env_var_name("per_page", "git-my.org.io")
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("per_page", "per_page"));
+        assert_eq!(1, edit_distance("api_tokn", "api_token"));
+        assert_eq!(2, edit_distance("ascii_ouptut", "ascii_output"));
+        assert_eq!(3, edit_distance("", "abc"));
+    }
 }
@@ -1,15 +1,16 @@
-use std::sync::Arc;
-
 use console::style;
 
-use dialoguer::theme::ColorfulTheme;
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
 use dialoguer::Confirm;
 use dialoguer::Editor;
 use dialoguer::FuzzySelect;
 use dialoguer::Input;
+use dialoguer::Password;
 
-use crate::config::ConfigProperties;
+use crate::display;
 use crate::error;
+use crate::git::UpdateStrategy;
+use crate::i18n::{self, Message};
 use crate::remote::Member;
 use crate::remote::MergeRequestBodyArgs;
 use crate::Result;
@@ -17,102 +18,181 @@ use crate::Result;
 pub struct MergeRequestUserInput {
     pub title: String,
     pub description: String,
-    pub user_id: i64,
-    pub username: String,
+    pub assignees: Vec<Member>,
+    pub reviewers: Vec<Member>,
 }
 
 impl MergeRequestUserInput {
-    pub fn new(title: &str, description: &str, user_id: i64, username: &str) -> Self {
+    pub fn new(title: &str, description: &str, assignees: Vec<Member>) -> Self {
         MergeRequestUserInput {
             title: title.to_string(),
             description: description.to_string(),
-            user_id,
-            username: username.to_string(),
+            assignees,
+            reviewers: Vec::new(),
         }
     }
+
+    pub fn with_reviewers(mut self, reviewers: Vec<Member>) -> Self {
+        self.reviewers = reviewers;
+        self
+    }
 }
 
-/// Given a new merge request, prompt user for assignee, title and description.
+/// Members, or other items, shown at once in the search prompts.
+const MAX_VISIBLE_MEMBERS: usize = 15;
+
+/// Given a new merge request, prompt user for title, description, assignees
+/// and reviewers. Members are offered in the given order, and the default
+/// assignees and reviewers are picked from the start.
 pub fn prompt_user_merge_request_info(
     default_title: &str,
     default_description: &str,
-    members: &[Member],
-    config: Arc<impl ConfigProperties>,
+    members: &[&Member],
+    default_assignees: &[String],
+    default_reviewers: &[String],
 ) -> Result<MergeRequestUserInput> {
-    let title: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Title: ")
+    let title: String = Input::with_theme(theme().as_ref())
+        .with_prompt(format!("{}: ", i18n::text(Message::Title)))
         .default(default_title.to_string())
         .interact_text()
         .unwrap();
 
     let description = get_description(default_description);
 
-    let mut usernames = members
+    let assignees = select_members(
+        &i18n::text(Message::SelectAssignees),
+        members,
+        default_assignees,
+    )?;
+    let reviewers = select_members(
+        &i18n::text(Message::SelectReviewers),
+        members,
+        default_reviewers,
+    )?;
+    Ok(MergeRequestUserInput::new(&title, &description, assignees).with_reviewers(reviewers))
+}
+
+/// Picks members one at a time, narrowing the list down with fuzzy search as
+/// the user types. Picking a member again unpicks it. The first entry ends
+/// the selection.
+fn select_members(prompt: &str, members: &[&Member], defaults: &[String]) -> Result<Vec<Member>> {
+    let mut picked = members
         .iter()
-        .map(|member| &member.username)
-        .collect::<Vec<&String>>();
-
-    // Set the configuration preferred assignee username at the top of the
-    // list. This way, we will just quickly enter (accept) the default value
-    // without having to type to fuzzy search the one we want.
-
-    let preferred_assignee_username = config.preferred_assignee_username();
-    let preferred_assignee_username_index = {
-        || -> usize {
-            for (index, member) in usernames.iter().enumerate() {
-                if *member == preferred_assignee_username {
-                    return index;
-                }
+        .enumerate()
+        .filter(|(_, member)| defaults.contains(&member.username))
+        .map(|(index, _)| index)
+        .collect::<Vec<usize>>();
+    loop {
+        let mut items = vec![i18n::format(
+            Message::DoneSelecting,
+            &[&picked.len().to_string()],
+        )];
+        items.extend(
+            members
+                .iter()
+                .enumerate()
+                .map(|(index, member)| member_item(member, picked.contains(&index))),
+        );
+        let choice = FuzzySelect::with_theme(theme().as_ref())
+            .with_prompt(prompt)
+            .items(&items)
+            .default(0)
+            .max_length(MAX_VISIBLE_MEMBERS)
+            .interact()?;
+        if choice == 0 {
+            break;
+        }
+        let index = choice - 1;
+        match picked.iter().position(|picked| *picked == index) {
+            Some(position) => {
+                picked.remove(position);
             }
-            0
+            None => picked.push(index),
         }
-    }();
-    let preferred_member = usernames.remove(preferred_assignee_username_index);
-    usernames.insert(0, preferred_member);
+    }
+    Ok(picked
+        .into_iter()
+        .map(|index| members[index].clone())
+        .collect())
+}
 
-    let assignee_selection_id = FuzzySelect::with_theme(&ColorfulTheme::default())
-        .with_prompt("Assignee:")
+/// Picks one of `items`, narrowing them down with fuzzy search as the user
+/// types. None if the user leaves with Esc.
+pub fn fuzzy_select(prompt: &str, items: &[String]) -> Result<Option<usize>> {
+    Ok(FuzzySelect::with_theme(theme().as_ref())
+        .with_prompt(prompt)
+        .items(items)
         .default(0)
-        .items(&usernames)
-        .interact()
-        .unwrap();
+        .max_length(MAX_VISIBLE_MEMBERS)
+        .interact_opt()?)
+}
 
-    let assignee_members_index = if assignee_selection_id != 0 {
-        // Inserted in 0 the preferred one. All shifted by 1 in usernames
-        // vec, so we need to shift back the index for members.
-        if assignee_selection_id <= preferred_assignee_username_index {
-            assignee_selection_id - 1
-        } else {
-            assignee_selection_id
-        }
+fn member_item(member: &Member, picked: bool) -> String {
+    let mark = if picked { "[x]" } else { "[ ]" };
+    if member.name.is_empty() {
+        format!("{} {}", mark, member.username)
     } else {
-        // The preferred one has been selected
-        preferred_assignee_username_index
-    };
+        format!("{} {} ({})", mark, member.username, member.name)
+    }
+}
+
+pub struct FirstRunInput {
+    pub api_token: String,
+    pub preferred_assignee_username: String,
+}
+
+/// Asks for the configuration of a domain that has none. None if the user
+/// declines to set it up.
+pub fn prompt_first_run_setup(domain: &str, token_url: &str) -> Result<Option<FirstRunInput>> {
+    if !confirm(&i18n::format(Message::SetUpDomain, &[&domain]), true) {
+        return Ok(None);
+    }
+    println!("{}", i18n::format(Message::CreateApiToken, &[&token_url]));
+    let api_token = Password::with_theme(theme().as_ref())
+        .with_prompt(i18n::text(Message::ApiToken))
+        .interact()?;
+    let preferred_assignee_username: String = Input::with_theme(theme().as_ref())
+        .with_prompt(i18n::text(Message::PreferredAssignee))
+        .allow_empty(true)
+        .interact_text()?;
+    Ok(Some(FirstRunInput {
+        api_token,
+        preferred_assignee_username,
+    }))
+}
 
-    Ok(MergeRequestUserInput::new(
-        &title,
-        &description,
-        members[assignee_members_index].id,
-        &members[assignee_members_index].username,
-    ))
+/// Reads a secret without echoing it to the terminal.
+pub fn prompt_secret(prompt: &str) -> Result<String> {
+    Ok(Password::with_theme(theme().as_ref())
+        .with_prompt(prompt)
+        .interact()?)
 }
 
 fn get_description(default_description: &str) -> String {
-    show_input("Description: ", default_description, true, Style::Bold);
+    let label = format!("{}: ", i18n::text(Message::Description));
+    show_input(&label, default_description, true, Style::Bold);
     let mut description = default_description.to_string();
-    let prompt = "Edit description";
-    while !confirm(prompt, false) {
+    let prompt = i18n::text(Message::EditDescription);
+    while !confirm(&prompt, false) {
         description = if let Some(entry_msg) = Editor::new().edit(&description).unwrap() {
             entry_msg
         } else {
             "".to_string()
         };
-        show_input("Description: ", &description, true, Style::Bold);
+        show_input(&label, &description, true, Style::Bold);
     }
     description
 }
 
+/// Colorful prompts use unicode symbols. Plain ASCII output goes without them.
+fn theme() -> Box<dyn Theme> {
+    if display::ascii_output() {
+        Box::new(SimpleTheme)
+    } else {
+        Box::new(ColorfulTheme::default())
+    }
+}
+
 pub enum Style {
     Bold,
     Light,
@@ -120,7 +200,7 @@ pub enum Style {
 
 pub fn show_input(prompt: &str, data: &str, new_line: bool, font_style: Style) {
     let mut prompt_style = style(prompt);
-    if let Style::Bold = font_style {
+    if let (Style::Bold, false) = (font_style, display::ascii_output()) {
         prompt_style = prompt_style.bold()
     }
     if new_line {
@@ -133,7 +213,7 @@ pub fn show_input(prompt: &str, data: &str, new_line: bool, font_style: Style) {
 }
 
 fn confirm(prompt: &str, default_answer: bool) -> bool {
-    if Confirm::with_theme(&ColorfulTheme::default())
+    if Confirm::with_theme(theme().as_ref())
         .with_prompt(prompt)
         .default(default_answer)
         .interact()
@@ -144,29 +224,95 @@ fn confirm(prompt: &str, default_answer: bool) -> bool {
     !default_answer
 }
 
+/// Ask before updating a source branch that is far behind its target, as it
+/// is likely to run into conflicts.
+pub fn confirm_branch_update(
+    target_branch: &str,
+    behind: u32,
+    strategy: UpdateStrategy,
+    accept: bool,
+) -> bool {
+    accept
+        || confirm(
+            &i18n::format(Message::UpdateBranch, &[&behind, &target_branch, &strategy]),
+            true,
+        )
+}
+
+/// Asks the reviewer to confirm each item of the checklist, in order. False
+/// at the first one not confirmed.
+pub fn confirm_checklist(checklist: &[String]) -> Result<bool> {
+    println!("{}", i18n::text(Message::ReviewChecklist));
+    for item in checklist {
+        let confirmed = Confirm::with_theme(theme().as_ref())
+            .with_prompt(item)
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Ask before sending the request that reverses a previous operation.
+pub fn confirm_undo(description: &str, accept: bool) -> bool {
+    accept || confirm(&format!("{}?", description), true)
+}
+
 pub fn show_summary_merge_request(
     commit_str: &str,
     args: &MergeRequestBodyArgs,
     accept: bool,
 ) -> Result<()> {
     show_input(
-        "\nSummary of outgoing changes:",
+        &format!("\n{}", i18n::text(Message::OutgoingChangesSummary)),
         commit_str,
         true,
         Style::Bold,
     );
-    show_input("Target branch", &args.target_branch, false, Style::Bold);
-    show_input("Assignee", &args.username, false, Style::Bold);
-    show_input("Title", &args.title, false, Style::Bold);
+    show_input(
+        &i18n::text(Message::TargetBranch),
+        &args.target_branch,
+        false,
+        Style::Bold,
+    );
+    let none = i18n::text(Message::None);
+    let assignees = if args.assignee_usernames.is_empty() {
+        none.clone()
+    } else {
+        args.assignee_usernames.join(", ")
+    };
+    show_input(
+        &i18n::text(Message::Assignees),
+        &assignees,
+        false,
+        Style::Bold,
+    );
+    if !args.reviewer_usernames.is_empty() {
+        show_input(
+            &i18n::text(Message::Reviewers),
+            &args.reviewer_usernames.join(", "),
+            false,
+            Style::Bold,
+        );
+    }
+    show_input(&i18n::text(Message::Title), &args.title, false, Style::Bold);
+    let description = i18n::text(Message::Description);
     if !args.description.is_empty() {
-        show_input("Description:", &args.description, true, Style::Bold);
+        show_input(
+            &format!("{}:", description),
+            &args.description,
+            true,
+            Style::Bold,
+        );
     } else {
-        show_input("Description", "None", false, Style::Bold);
+        show_input(&description, &none, false, Style::Bold);
     }
     println!();
-    if accept || confirm("Confirm summary", true) {
+    if accept || confirm(&i18n::text(Message::ConfirmSummary), true) {
         Ok(())
     } else {
-        Err(error::gen("User cancelled"))
+        Err(error::gen(i18n::text(Message::UserCancelled)))
     }
 }
@@ -1,6 +1,34 @@
+use crate::error::GRError;
 use crate::remote::GetRemoteCliArgs;
 use crate::Result;
-use std::{collections::HashMap, io::Write};
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static ASCII_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Restrict what is printed to plain ASCII, without emoji nor styling, for
+/// screen readers and dumb terminals.
+pub fn set_ascii_output(ascii: bool) {
+    ASCII_OUTPUT.store(ascii, Ordering::Relaxed);
+}
+
+/// Whether plain ASCII output was requested with `--ascii` or the
+/// configuration, or the terminal asks for it with `NO_COLOR` or `TERM=dumb`.
+pub fn ascii_output() -> bool {
+    ASCII_OUTPUT.load(Ordering::Relaxed)
+        || plain_terminal(
+            std::env::var("NO_COLOR").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+}
+
+// https://no-color.org - NO_COLOR has effect when set to a non-empty value.
+fn plain_terminal(no_color: Option<&str>, term: Option<&str>) -> bool {
+    no_color.is_some_and(|value| !value.is_empty()) || term == Some("dumb")
+}
 
 #[derive(Clone, Debug, Default)]
 pub enum Format {
@@ -51,6 +79,49 @@ impl Column {
     }
 }
 
+/// Columns of a row as per the arguments. `--columns` picks and orders them,
+/// optional ones included. Otherwise optional columns are only printed with
+/// `--more-output`.
+fn select(columns: Vec<Column>, args: &GetRemoteCliArgs) -> Result<Vec<Column>> {
+    let Some(names) = &args.columns else {
+        return Ok(columns
+            .into_iter()
+            .filter(|c| !c.optional || args.display_optional)
+            .collect());
+    };
+    let mut columns = columns.into_iter().map(Some).collect::<Vec<_>>();
+    let mut selected = Vec::with_capacity(names.len());
+    for name in names {
+        let position = columns.iter().position(|c| {
+            c.as_ref()
+                .is_some_and(|c| column_key(&c.name) == column_key(name))
+        });
+        match position.and_then(|position| columns[position].take()) {
+            Some(column) => selected.push(column),
+            None => {
+                let available = columns
+                    .iter()
+                    .flatten()
+                    .map(|c| column_key(&c.name))
+                    .collect::<Vec<_>>();
+                return Err(GRError::PreconditionNotMet(format!(
+                    "Unknown column {}. Available columns: {}",
+                    name,
+                    available.join(",")
+                ))
+                .into());
+            }
+        }
+    }
+    Ok(selected)
+}
+
+/// Name of a column as given to `--columns`, ex. `updated_at` for "Updated
+/// at".
+fn column_key(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
 pub fn print<W: Write, D: Into<DisplayBody> + Clone>(
     w: &mut W,
     data: Vec<D>,
@@ -63,37 +134,29 @@ pub fn print<W: Write, D: Into<DisplayBody> + Clone>(
         Format::JSON => {
             for d in data {
                 let d = d.into();
-                let kvs: HashMap<String, String> = d
-                    .columns
+                let kvs: HashMap<String, String> = select(d.columns, &args)?
                     .into_iter()
-                    .filter(|c| !c.optional || args.display_optional)
                     .map(|item| (item.name, item.value))
                     .collect();
                 writeln!(w, "{}", serde_json::to_string(&kvs)?)?;
             }
         }
         _ => {
+            // Columns are checked before anything is written.
+            let headers = select(data[0].clone().into().columns, &args)?
+                .into_iter()
+                .map(|c| c.name)
+                .collect::<Vec<_>>();
             let mut wtr = csv::WriterBuilder::new()
-                .delimiter(args.format.into())
+                .delimiter(args.format.clone().into())
                 .from_writer(w);
             if !args.no_headers {
-                // Get the headers from the first row of columns
-                let headers = data[0]
-                    .clone()
-                    .into()
-                    .columns
-                    .iter()
-                    .filter(|c| !c.optional || args.display_optional)
-                    .map(|c| c.name.clone())
-                    .collect::<Vec<_>>();
                 wtr.write_record(&headers)?;
             }
             for d in data {
                 let d = d.into();
-                let row = d
-                    .columns
+                let row = select(d.columns, &args)?
                     .into_iter()
-                    .filter(|c| !c.optional || args.display_optional)
                     .map(|c| c.value)
                     .collect::<Vec<_>>();
                 wtr.write_record(&row)?;
@@ -237,6 +300,47 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_columns_picked_and_ordered() {
+        let mut w = Vec::new();
+        let books = vec![BookOptionalColumns::new(
+            "The Catcher in the Rye",
+            "J.D. Salinger",
+            "0316769487",
+        )];
+        let args = GetRemoteCliArgs::builder()
+            .columns(Some(vec!["ISBN".to_string(), "title".to_string()]))
+            .build()
+            .unwrap();
+        print(&mut w, books, args).unwrap();
+        assert_eq!(
+            "isbn|title\n0316769487|The Catcher in the Rye\n",
+            String::from_utf8(w).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_column_is_error() {
+        let mut w = Vec::new();
+        let books = vec![Book::new("Faust, Part One", "Goethe")];
+        let args = GetRemoteCliArgs::builder()
+            .columns(Some(vec!["title".to_string(), "year".to_string()]))
+            .build()
+            .unwrap();
+        let err = print(&mut w, books, args).unwrap_err();
+        assert_eq!(
+            "Precondition not met error: Unknown column year. Available columns: author",
+            err.to_string()
+        );
+        assert!(w.is_empty());
+    }
+
+    #[test]
+    fn test_column_keys() {
+        assert_eq!("updated_at", column_key("Updated at"));
+        assert_eq!("pipeline_id", column_key("pipeline-id"));
+    }
+
     #[test]
     fn test_csv_display_optional_columns_on_args() {
         let mut w = Vec::new();
@@ -259,4 +363,12 @@ mod test {
             String::from_utf8(w).unwrap()
         );
     }
+
+    #[test]
+    fn test_plain_terminal_from_no_color_or_dumb_term() {
+        assert!(plain_terminal(Some("1"), Some("xterm-256color")));
+        assert!(plain_terminal(None, Some("dumb")));
+        assert!(!plain_terminal(Some(""), Some("xterm-256color")));
+        assert!(!plain_terminal(None, None));
+    }
 }
@@ -29,8 +29,14 @@ pub enum GRError {
     RemoteUnexpectedResponseContract(String),
     #[error("Remote server status error: {0}")]
     RemoteServerError(String),
+    // Gitlab answers with a 503 and a message when maintenance mode is on.
+    // Write operations are blocked until the administrators disable it.
+    #[error("Remote is under maintenance or temporarily unavailable: {0}")]
+    RemoteUnderMaintenance(String),
     #[error("HTTP Transport error/network outage: {0}")]
     HttpTransportError(String),
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
 }
 
 pub trait AddContext<T, E>: Context<T, E> {
@@ -1,7 +1,10 @@
 use crate::Cmd;
 use crate::Result;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::channel;
 use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
 
 /// Executes a sequence of commands in parallel
 pub fn parallel_stream<T>(cmds: impl IntoIterator<Item = Cmd<T>>) -> Receiver<Result<T>>
@@ -22,6 +25,148 @@ where
     receiver
 }
 
+/// A command with a short name to report its progress, ex. "fetch".
+pub struct Step<T> {
+    pub name: &'static str,
+    pub cmd: Cmd<T>,
+}
+
+impl<T> Step<T> {
+    pub fn new(name: &'static str, cmd: Cmd<T>) -> Self {
+        Step { name, cmd }
+    }
+}
+
+/// Executes named steps in parallel, `workers` at a time. Results come tagged
+/// with the position of their step. Once `cancelled` is set, steps that have
+/// not started yet are skipped and send nothing.
+pub fn parallel_steps<T>(
+    steps: Vec<Step<T>>,
+    workers: usize,
+    cancelled: Arc<AtomicBool>,
+) -> Receiver<(usize, Result<T>)>
+where
+    T: Send + 'static,
+{
+    let (sender, receiver) = channel();
+    let workers = workers.clamp(1, steps.len().max(1));
+    let steps = Arc::new(Mutex::new(steps.into_iter().enumerate()));
+    for _ in 0..workers {
+        let sender = sender.clone();
+        let steps = steps.clone();
+        let cancelled = cancelled.clone();
+        std::thread::spawn(move || loop {
+            // Checked before taking each step, so that a failure stops the
+            // steps waiting for a worker.
+            if cancelled.load(Ordering::SeqCst) {
+                return;
+            }
+            let Some((position, step)) = steps.lock().unwrap().next() else {
+                return;
+            };
+            let result = (step.cmd)();
+            sender.send((position, result)).unwrap_or_default();
+        });
+    }
+    drop(sender);
+    receiver
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum StepState {
+    Running,
+    Done,
+    Failed,
+}
+
+/// One line showing the state of each step, ex. `project ✓  members ✓  fetch
+/// …`. In a terminal it is redrawn in place as steps finish, elsewhere only
+/// the final state is written. Nothing is written without a writer.
+pub struct StepProgress<W: Write> {
+    steps: Vec<(&'static str, StepState)>,
+    writer: Option<W>,
+    terminal: bool,
+    ascii: bool,
+}
+
+impl<W: Write> StepProgress<W> {
+    /// `terminal` tells whether the writer understands the ANSI escapes used
+    /// to redraw the line. `ascii` marks the steps with plain words instead
+    /// of symbols.
+    pub fn new(names: &[&'static str], writer: Option<W>, terminal: bool, ascii: bool) -> Self {
+        let mut progress = StepProgress {
+            steps: names
+                .iter()
+                .map(|name| (*name, StepState::Running))
+                .collect(),
+            writer,
+            terminal,
+            ascii,
+        };
+        progress.render();
+        progress
+    }
+
+    pub fn done(&mut self, position: usize) {
+        self.update(position, StepState::Done);
+    }
+
+    pub fn failed(&mut self, position: usize) {
+        self.update(position, StepState::Failed);
+    }
+
+    /// Leaves the last state on screen and moves to the next line.
+    pub fn finish(&mut self) {
+        let line = self.line();
+        if let Some(writer) = &mut self.writer {
+            if !self.terminal {
+                let _ = write!(writer, "{}", line);
+            }
+            let _ = writeln!(writer);
+            let _ = writer.flush();
+        }
+        self.writer = None;
+    }
+
+    fn update(&mut self, position: usize, state: StepState) {
+        if let Some(step) = self.steps.get_mut(position) {
+            step.1 = state;
+        }
+        self.render();
+    }
+
+    fn line(&self) -> String {
+        self.steps
+            .iter()
+            .map(|(name, state)| {
+                let mark = match (state, self.ascii) {
+                    (StepState::Running, false) => "…",
+                    (StepState::Done, false) => "✓",
+                    (StepState::Failed, false) => "✗",
+                    (StepState::Running, true) => "...",
+                    (StepState::Done, true) => "ok",
+                    (StepState::Failed, true) => "failed",
+                };
+                format!("{} {}", name, mark)
+            })
+            .collect::<Vec<String>>()
+            .join("  ")
+    }
+
+    fn render(&mut self) {
+        if !self.terminal {
+            return;
+        }
+        let line = self.line();
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        // Carriage return and clear the line, so the state is redrawn in place.
+        let _ = write!(writer, "\r\x1b[2K{}", line);
+        let _ = writer.flush();
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -45,4 +190,78 @@ mod test {
         let results = repo_data_stream.iter().collect::<Vec<_>>();
         assert_eq!(2, results.len());
     }
+
+    #[test]
+    fn test_parallel_steps_tag_results_with_their_position() {
+        let steps: Vec<Step<String>> = vec![
+            Step::new("first", Box::new(|| Ok("1st op".to_string()))),
+            Step::new("second", Box::new(|| Ok("2nd op".to_string()))),
+        ];
+        let mut results = parallel_steps(steps, 1, Arc::new(AtomicBool::new(false)))
+            .iter()
+            .map(|(position, result)| (position, result.unwrap()))
+            .collect::<Vec<_>>();
+        results.sort();
+        assert_eq!(
+            vec![(0, "1st op".to_string()), (1, "2nd op".to_string())],
+            results
+        );
+    }
+
+    #[test]
+    fn test_parallel_steps_skip_when_cancelled() {
+        let steps: Vec<Step<String>> =
+            vec![Step::new("first", Box::new(|| Ok("1st op".to_string())))];
+        let results = parallel_steps(steps, 2, Arc::new(AtomicBool::new(true)));
+        assert_eq!(0, results.iter().count());
+    }
+
+    #[test]
+    fn test_step_progress_redraws_line() {
+        let mut writer = Vec::new();
+        let mut progress = StepProgress::new(&["project", "fetch"], Some(&mut writer), true, false);
+        progress.done(0);
+        progress.failed(1);
+        progress.finish();
+        assert_eq!(
+            "\r\x1b[2Kproject …  fetch …\
+             \r\x1b[2Kproject ✓  fetch …\
+             \r\x1b[2Kproject ✓  fetch ✗\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_step_progress_outside_a_terminal_writes_final_state_in_ascii() {
+        let mut writer = Vec::new();
+        let mut progress = StepProgress::new(&["project", "fetch"], Some(&mut writer), false, true);
+        progress.done(0);
+        progress.failed(1);
+        progress.finish();
+        assert_eq!(
+            "project ok  fetch failed\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parallel_steps_not_started_after_cancel() {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let on_failure = cancelled.clone();
+        let steps: Vec<Step<String>> = vec![
+            Step::new(
+                "first",
+                Box::new(move || {
+                    on_failure.store(true, Ordering::SeqCst);
+                    Err(crate::error::gen("failed"))
+                }),
+            ),
+            Step::new("second", Box::new(|| Ok("2nd op".to_string()))),
+        ];
+        let results = parallel_steps(steps, 1, cancelled)
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(1, results.len());
+        assert_eq!(0, results[0].0);
+    }
 }
@@ -10,6 +10,7 @@
 //! All public functions take a [`Runner`] as a parameter and return a
 //! [`Result<CmdInfo>`].
 
+use std::fmt::Display;
 use std::sync::Arc;
 
 use crate::error;
@@ -60,6 +61,26 @@ pub fn current_branch(runner: Arc<impl TaskRunner<Response = Response>>) -> Resu
     Ok(CmdInfo::Branch(response.body))
 }
 
+/// SHA of the commit checked out in the local git repository.
+pub fn head_sha(runner: &impl TaskRunner<Response = Response>) -> Result<String> {
+    let cmd_params = ["git", "rev-parse", "HEAD"];
+    let response = runner.run(cmd_params).err_context(format!(
+        "Failed to get the HEAD commit. Command: {}",
+        cmd_params.join(" ")
+    ))?;
+    Ok(response.body.trim().to_string())
+}
+
+/// Root directory of the local git repository.
+pub fn toplevel(runner: &impl TaskRunner<Response = Response>) -> Result<String> {
+    let cmd_params = ["git", "rev-parse", "--show-toplevel"];
+    let response = runner.run(cmd_params).err_context(format!(
+        "Failed to get the root of the repository. Command: {}",
+        cmd_params.join(" ")
+    ))?;
+    Ok(response.body.trim().to_string())
+}
+
 /// Fetch the last commits from the remote.
 ///
 /// The remote is considered to be the default remote, .i.e origin.
@@ -94,7 +115,16 @@ pub fn commit(exec: &impl TaskRunner, message: &str) -> Result<CmdInfo> {
 
 /// Get the origin remote url from the local git repository.
 pub fn remote_url(exec: &impl TaskRunner<Response = Response>) -> Result<CmdInfo> {
-    let cmd_params = ["git", "remote", "get-url", "--all", "origin"];
+    named_remote_url(exec, "origin")
+}
+
+/// Domain and project path of the git remote with the given name, such as
+/// `upstream` when working from a fork.
+pub fn named_remote_url(
+    exec: &impl TaskRunner<Response = Response>,
+    name: &str,
+) -> Result<CmdInfo> {
+    let cmd_params = ["git", "remote", "get-url", "--all", name];
     let response = exec.run(cmd_params)?;
     handle_git_remote_url(&response)
 }
@@ -176,13 +206,191 @@ pub fn outgoing_commits(
     Ok(response.body)
 }
 
-pub fn push(runner: &impl TaskRunner, remote: &str, repo: &Repo) -> Result<CmdInfo> {
-    let cmd = format!("git push {} {}", remote, repo.current_branch);
-    let cmd_params = cmd.split(' ').collect::<Vec<&str>>();
-    runner.run(cmd_params)?;
+/// Whether the git configuration asks for commits to be GPG signed.
+pub fn gpg_sign_enabled(exec: &impl TaskRunner<Response = Response>) -> bool {
+    // git config exits with an error when the key is not set.
+    exec.run(["git", "config", "--get", "--bool", "commit.gpgsign"])
+        .map(|response| response.body.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// Commit with a GPG signature. Signing failures, like a missing key or a gpg
+/// agent that cannot prompt for the passphrase, are told apart from other
+/// commit errors.
+pub fn signed_commit(exec: &impl TaskRunner, message: &str) -> Result<CmdInfo> {
+    let cmd_params = ["git", "commit", "-S", "-m", message];
+    if let Err(err) = exec.run(cmd_params) {
+        let trace = err.to_string();
+        if trace.contains("gpg") || trace.contains("signing") {
+            return Err(error::GRError::PreconditionNotMet(format!(
+                "Failed to GPG sign the commit. Check that user.signingkey is set \
+                and the gpg agent is running: {}",
+                trace.trim()
+            ))
+            .into());
+        }
+        return Err(err).err_context(format!(
+            "Failed to git commit changes. Command: {}",
+            cmd_params.join(" ")
+        ));
+    }
     Ok(CmdInfo::Ignore)
 }
 
+/// How to bring in the new commits of the target branch before pushing a
+/// feature branch.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum UpdateStrategy {
+    #[default]
+    Rebase,
+    /// Merge the target branch, for teams that do not allow rewriting the
+    /// history of shared branches.
+    Merge,
+    /// Push the branch as it is.
+    None,
+}
+
+impl TryFrom<&str> for UpdateStrategy {
+    type Error = String;
+
+    fn try_from(strategy: &str) -> std::result::Result<Self, Self::Error> {
+        match strategy {
+            "rebase" => Ok(UpdateStrategy::Rebase),
+            "merge" => Ok(UpdateStrategy::Merge),
+            "none" => Ok(UpdateStrategy::None),
+            _ => Err(format!(
+                "invalid update strategy {}, expected rebase, merge or none",
+                strategy
+            )),
+        }
+    }
+}
+
+impl Display for UpdateStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateStrategy::Rebase => write!(f, "rebase"),
+            UpdateStrategy::Merge => write!(f, "merge"),
+            UpdateStrategy::None => write!(f, "none"),
+        }
+    }
+}
+
+/// How far the current branch has moved away from a remote branch.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Divergence {
+    /// Commits in the current branch not in the remote branch.
+    pub ahead: u32,
+    /// Commits in the remote branch not in the current branch.
+    pub behind: u32,
+}
+
+/// Count the commits the current branch is ahead and behind of the remote
+/// branch. Remote refs need to be fetched beforehand.
+pub fn divergence(
+    runner: &impl TaskRunner<Response = Response>,
+    remote: &str,
+    branch: &str,
+) -> Result<Divergence> {
+    let cmd = vec![
+        "git".to_string(),
+        "rev-list".to_string(),
+        "--left-right".to_string(),
+        "--count".to_string(),
+        format!("{}/{}...HEAD", remote, branch),
+    ];
+    let response = runner.run(cmd)?;
+    // Output is: <behind>\t<ahead>
+    let counts = response
+        .body
+        .split_whitespace()
+        .map(|count| count.parse::<u32>())
+        .collect::<std::result::Result<Vec<u32>, _>>();
+    match counts.as_deref() {
+        Ok([behind, ahead]) => Ok(Divergence {
+            ahead: *ahead,
+            behind: *behind,
+        }),
+        _ => Err(error::gen(format!(
+            "Unexpected git rev-list output: {}",
+            response.body
+        ))),
+    }
+}
+
+/// Push the current branch. Push options are handed over to the remote, see
+/// https://docs.gitlab.com/ee/user/project/push_options.html
+/// A rejected push is reported with what to do about it along with the output
+/// of git.
+pub fn push(
+    runner: &impl TaskRunner,
+    remote: &str,
+    repo: &Repo,
+    push_options: &[String],
+    force_with_lease: bool,
+) -> Result<CmdInfo> {
+    let mut cmd_params = vec!["git", "push"];
+    if force_with_lease {
+        cmd_params.push("--force-with-lease");
+    }
+    for option in push_options {
+        cmd_params.push("-o");
+        cmd_params.push(option);
+    }
+    cmd_params.push(remote);
+    cmd_params.push(&repo.current_branch);
+    if let Err(err) = runner.run(cmd_params) {
+        let output = err.to_string();
+        return match push_rejection(&output, &repo.current_branch) {
+            Some(guidance) => Err(error::GRError::PreconditionNotMet(format!(
+                "{}\n\n{}",
+                guidance,
+                output.trim_end()
+            ))
+            .into()),
+            None => Err(err),
+        };
+    }
+    Ok(CmdInfo::Ignore)
+}
+
+/// What to do about a push rejected by the remote, gathered from the output
+/// of git push. None if the push failed for some other reason, ex. network.
+fn push_rejection(output: &str, branch: &str) -> Option<String> {
+    // Checked first, as Gitlab reports protected branches as declined by its
+    // pre-receive hook too.
+    if output.contains("protected branch") || output.contains("GH006") {
+        return Some(format!(
+            "Push to {} is not allowed by the protection rules of the branch. \
+             Push your changes to a different branch and open the merge request from it.",
+            branch
+        ));
+    }
+    if output.contains("(stale info)") {
+        return Some(format!(
+            "The remote {} changed since it was last fetched, so it was not overwritten. \
+             Fetch it and review the new commits before pushing again.",
+            branch
+        ));
+    }
+    if output.contains("(non-fast-forward)") || output.contains("(fetch first)") {
+        return Some(format!(
+            "The remote {} has commits that are not in your local branch. \
+             If its history was rewritten on purpose, ex. after a rebase, push again \
+             with --force-with-lease. Otherwise pull the remote changes first.",
+            branch
+        ));
+    }
+    if output.contains("hook declined") {
+        return Some(
+            "A hook in the remote declined the push. \
+             Check the messages from the remote below for the reason."
+                .to_string(),
+        );
+    }
+    None
+}
+
 pub fn rebase(runner: &impl TaskRunner, remote: &str, default_branch: &str) -> Result<CmdInfo> {
     let cmd = format!("git rebase {}/{}", remote, default_branch);
     let cmd_params = cmd.split(' ').collect::<Vec<&str>>();
@@ -190,6 +398,12 @@ pub fn rebase(runner: &impl TaskRunner, remote: &str, default_branch: &str) -> R
     Ok(CmdInfo::Ignore)
 }
 
+pub fn merge(runner: &impl TaskRunner, remote: &str, branch: &str) -> Result<CmdInfo> {
+    let target = format!("{}/{}", remote, branch);
+    runner.run(vec!["git", "merge", "--no-edit", &target])?;
+    Ok(CmdInfo::Ignore)
+}
+
 pub fn commit_message(
     runner: Arc<impl TaskRunner<Response = Response>>,
     commit: &Option<String>,
@@ -281,7 +495,7 @@ mod tests {
         let runner = Arc::new(MockRunner::new(vec![response]));
         let cmd_info = status(runner).unwrap();
         if let CmdInfo::StatusModified(dirty) = cmd_info {
-            assert_eq!(true, dirty);
+            assert!(dirty);
         } else {
             panic!("Expected CmdInfo::StatusModified");
         }
@@ -299,7 +513,7 @@ mod tests {
         let runner = Arc::new(MockRunner::new(vec![response]));
         let cmd_info = status(runner).unwrap();
         if let CmdInfo::StatusModified(dirty) = cmd_info {
-            assert_eq!(true, dirty);
+            assert!(dirty);
         } else {
             panic!("Expected CmdInfo::StatusModified");
         }
@@ -323,7 +537,7 @@ mod tests {
         let runner = Arc::new(MockRunner::new(vec![response]));
         let cmd_info = status(runner).unwrap();
         if let CmdInfo::StatusModified(dirty) = cmd_info {
-            assert_eq!(false, dirty);
+            assert!(!dirty);
         } else {
             panic!("Expected CmdInfo::StatusModified");
         }
@@ -341,7 +555,7 @@ mod tests {
         let runner = Arc::new(MockRunner::new(vec![response]));
         let cmd_info = status(runner).unwrap();
         if let CmdInfo::StatusModified(dirty) = cmd_info {
-            assert_eq!(false, dirty);
+            assert!(!dirty);
         } else {
             panic!("Expected CmdInfo::StatusModified");
         }
@@ -358,6 +572,24 @@ mod tests {
         assert_eq!("git remote get-url --all origin", *runner.cmd());
     }
 
+    #[test]
+    fn test_git_named_remote_url() {
+        let response = Response::builder()
+            .body("git@github.com:upstream/mr.git".to_string())
+            .build()
+            .unwrap();
+        let runner = MockRunner::new(vec![response]);
+        let cmdinfo = named_remote_url(&runner, "upstream").unwrap();
+        assert_eq!("git remote get-url --all upstream", *runner.cmd());
+        match cmdinfo {
+            CmdInfo::RemoteUrl { domain, path } => {
+                assert_eq!("github.com", domain);
+                assert_eq!("upstream/mr", path);
+            }
+            _ => panic!("Failed to parse remote url"),
+        }
+    }
+
     #[test]
     fn test_get_remote_git_url() {
         let response = Response::builder()
@@ -506,16 +738,52 @@ mod tests {
         assert_eq!("git log --format=%s -n1 123456", *runner.cmd());
     }
 
+    #[test]
+    fn test_head_sha_cmd_is_correct() {
+        let response = Response::builder()
+            .body("0123456789abcdef\n".to_string())
+            .build()
+            .unwrap();
+        let runner = MockRunner::new(vec![response]);
+        assert_eq!("0123456789abcdef", head_sha(&runner).unwrap());
+        assert_eq!("git rev-parse HEAD", *runner.cmd());
+    }
+
+    #[test]
+    fn test_toplevel_cmd_is_correct() {
+        let response = Response::builder()
+            .body("/home/user/gitar\n".to_string())
+            .build()
+            .unwrap();
+        let runner = MockRunner::new(vec![response]);
+        assert_eq!("/home/user/gitar", toplevel(&runner).unwrap());
+        assert_eq!("git rev-parse --show-toplevel", *runner.cmd());
+    }
+
     #[test]
     fn test_git_push_cmd_is_correct() {
         let response = Response::builder().build().unwrap();
         let runner = MockRunner::new(vec![response]);
         let mut repo = Repo::new();
         repo.with_current_branch("new_feature");
-        push(&runner, "origin", &repo).unwrap();
+        push(&runner, "origin", &repo, &[], false).unwrap();
         assert_eq!("git push origin new_feature", *runner.cmd());
     }
 
+    #[test]
+    fn test_git_push_with_push_options() {
+        let response = Response::builder().build().unwrap();
+        let runner = MockRunner::new(vec![response]);
+        let mut repo = Repo::new();
+        repo.with_current_branch("new_feature");
+        let push_options = vec!["ci.skip".to_string(), "merge_request.label=bug".to_string()];
+        push(&runner, "origin", &repo, &push_options, false).unwrap();
+        assert_eq!(
+            "git push -o ci.skip -o merge_request.label=bug origin new_feature",
+            *runner.cmd()
+        );
+    }
+
     #[test]
     fn test_git_push_cmd_fails() {
         let response = Response::builder()
@@ -526,7 +794,49 @@ mod tests {
         let runner = MockRunner::new(vec![response]);
         let mut repo = Repo::new();
         repo.with_current_branch("new_feature");
-        assert!(push(&runner, "origin", &repo).is_err());
+        let err = push(&runner, "origin", &repo, &[], false).unwrap_err();
+        // Not a rejection, git's own output is all there is to report.
+        assert!(err.to_string().starts_with("fatal: 'origin'"));
+    }
+
+    #[test]
+    fn test_git_push_force_with_lease() {
+        let response = Response::builder().build().unwrap();
+        let runner = MockRunner::new(vec![response]);
+        let mut repo = Repo::new();
+        repo.with_current_branch("new_feature");
+        push(&runner, "origin", &repo, &["ci.skip".to_string()], true).unwrap();
+        assert_eq!(
+            "git push --force-with-lease -o ci.skip origin new_feature",
+            *runner.cmd()
+        );
+    }
+
+    fn rejected_push(contract: &str) -> String {
+        let response = Response::builder()
+            .status(1)
+            .body(get_contract(ContractType::Git, contract))
+            .build()
+            .unwrap();
+        let runner = MockRunner::new(vec![response]);
+        let mut repo = Repo::new();
+        repo.with_current_branch("new_feature");
+        push(&runner, "origin", &repo, &[], false)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_git_push_rejections_come_with_guidance() {
+        let err = rejected_push("git_push_rejected_non_fast_forward.txt");
+        assert!(err.contains("push again with --force-with-lease"));
+        assert!(err.contains("(non-fast-forward)"));
+        let err = rejected_push("git_push_rejected_protected_branch.txt");
+        assert!(err.contains("Push to new_feature is not allowed"));
+        assert!(err.contains("different branch"));
+        let err = rejected_push("git_push_rejected_hook.txt");
+        assert!(err.contains("A hook in the remote declined the push"));
+        assert!(err.contains("does not reference an issue"));
     }
 
     #[test]
@@ -641,4 +951,84 @@ mod tests {
         let runner = MockRunner::new(vec![response]);
         assert!(commit(&runner, "Add README").is_err());
     }
+
+    #[test]
+    fn test_git_signed_commit() {
+        let response = Response::builder().build().unwrap();
+        let runner = MockRunner::new(vec![response]);
+        signed_commit(&runner, "Add README").unwrap();
+        assert_eq!("git commit -S -m Add README", *runner.cmd());
+    }
+
+    #[test]
+    fn test_git_signed_commit_signing_failure_is_reported() {
+        let response = Response::builder()
+            .status(1)
+            .body(
+                "error: gpg failed to sign the data\nfatal: failed to write commit object"
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let runner = MockRunner::new(vec![response]);
+        let err = signed_commit(&runner, "Add README").unwrap_err();
+        assert!(err.to_string().contains("Failed to GPG sign the commit"));
+    }
+
+    #[test]
+    fn test_git_gpg_sign_enabled_in_config() {
+        let response = Response::builder()
+            .body("true".to_string())
+            .build()
+            .unwrap();
+        let runner = MockRunner::new(vec![response]);
+        assert!(gpg_sign_enabled(&runner));
+        assert_eq!("git config --get --bool commit.gpgsign", *runner.cmd());
+    }
+
+    #[test]
+    fn test_git_gpg_sign_not_set_in_config() {
+        let response = Response::builder().status(1).build().unwrap();
+        let runner = MockRunner::new(vec![response]);
+        assert!(!gpg_sign_enabled(&runner));
+    }
+
+    #[test]
+    fn test_git_divergence_ahead_and_behind() {
+        let response = Response::builder()
+            .body("3\t5\n".to_string())
+            .build()
+            .unwrap();
+        let runner = MockRunner::new(vec![response]);
+        let divergence = divergence(&runner, "origin", "main").unwrap();
+        assert_eq!(
+            "git rev-list --left-right --count origin/main...HEAD",
+            *runner.cmd()
+        );
+        assert_eq!(
+            Divergence {
+                ahead: 5,
+                behind: 3
+            },
+            divergence
+        );
+    }
+
+    #[test]
+    fn test_git_divergence_unexpected_output_is_error() {
+        let response = Response::builder()
+            .body("fatal: ambiguous argument".to_string())
+            .build()
+            .unwrap();
+        let runner = MockRunner::new(vec![response]);
+        assert!(divergence(&runner, "origin", "main").is_err());
+    }
+
+    #[test]
+    fn test_git_merge_target_branch() {
+        let response = Response::builder().build().unwrap();
+        let runner = MockRunner::new(vec![response]);
+        merge(&runner, "origin", "main").unwrap();
+        assert_eq!("git merge --no-edit origin/main", *runner.cmd());
+    }
 }
@@ -0,0 +1,73 @@
+use crate::config::ConfigProperties;
+use crate::http::{self, Headers};
+use std::sync::Arc;
+
+pub mod cicd;
+pub mod merge_request;
+pub mod project;
+
+/// Gitea and its fork Forgejo. The REST API follows Github's closely, but it
+/// is served from the instance domain and pages are sized with `limit`
+/// instead of `per_page`, so lists come in the instance default page size.
+#[derive(Clone)]
+pub struct Gitea<R> {
+    api_token: String,
+    domain: String,
+    path: String,
+    rest_api_basepath: String,
+    runner: Arc<R>,
+    extra_headers: Vec<(String, String)>,
+    user_agent: String,
+}
+
+impl<R> Gitea<R> {
+    pub fn new(config: impl ConfigProperties, domain: &str, path: &str, runner: Arc<R>) -> Self {
+        let api_token = config.api_token().to_string();
+        let extra_headers = config.extra_headers().to_vec();
+        let user_agent = http::user_agent(config.user_agent_suffix());
+        let domain = domain.to_string();
+        let rest_api_basepath = format!("https://{}/api/v1", domain);
+
+        Gitea {
+            api_token,
+            domain,
+            path: path.to_string(),
+            rest_api_basepath,
+            runner,
+            extra_headers,
+            user_agent,
+        }
+    }
+
+    fn request_headers(&self) -> Headers {
+        let mut headers = Headers::new();
+        for (name, value) in &self.extra_headers {
+            headers.set(name, value);
+        }
+        let auth_token_value = format!("token {}", self.api_token);
+        headers.set("Authorization".to_string(), auth_token_value);
+        headers.set("Accept".to_string(), "application/json".to_string());
+        headers.set("User-Agent".to_string(), self.user_agent.clone());
+        headers
+    }
+}
+
+/// Self-hosted instances are told apart by their domain, as with Github and
+/// Gitlab. Codeberg is the largest public Forgejo instance.
+pub fn is_gitea_domain(domain: &str) -> bool {
+    domain.starts_with("gitea") || domain.starts_with("forgejo") || domain == "codeberg.org"
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gitea_domains() {
+        assert!(is_gitea_domain("gitea.mycompany.com"));
+        assert!(is_gitea_domain("forgejo.example.org"));
+        assert!(is_gitea_domain("codeberg.org"));
+        assert!(!is_gitea_domain("github.com"));
+        assert!(!is_gitea_domain("gitlab.com"));
+    }
+}
@@ -0,0 +1,196 @@
+use super::Gitea;
+use crate::{
+    api_traits::{ApiOperation, Cicd},
+    cmds::cicd::{Pipeline, PipelineBodyArgs},
+    error::GRError,
+    http,
+    io::{HttpRunner, PageCount, Response},
+    remote::query,
+    time, Result,
+};
+
+impl<R: HttpRunner<Response = Response>> Cicd for Gitea<R> {
+    fn list(&self, args: PipelineBodyArgs) -> Result<Vec<Pipeline>> {
+        // Doc: https://gitea.com/api/swagger#/repository/getWorkflowRuns
+        let url = format!(
+            "{}/repos/{}/actions/runs",
+            self.rest_api_basepath, self.path
+        );
+        query::gitea_list_pipelines(
+            &self.runner,
+            &url,
+            args.from_to_page,
+            self.request_headers(),
+            Some("workflow_runs"),
+            ApiOperation::Pipeline,
+        )
+    }
+
+    fn get_pipeline(&self, id: i64) -> Result<Pipeline> {
+        // Doc: https://gitea.com/api/swagger#/repository/GetWorkflowRun
+        let url = format!(
+            "{}/repos/{}/actions/runs/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        query::gitea_pipeline::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Pipeline,
+        )
+    }
+
+    fn num_pages(&self) -> Result<Option<PageCount>> {
+        let url = format!(
+            "{}/repos/{}/actions/runs?page=1",
+            self.rest_api_basepath, self.path
+        );
+        let headers = self.request_headers();
+        query::num_pages(&self.runner, &url, headers, ApiOperation::Pipeline)
+    }
+
+    fn retry(&self, _id: i64) -> Result<Pipeline> {
+        Err(GRError::OperationNotSupported(
+            "Re-running workflow runs is not exposed in the Gitea API".to_string(),
+        )
+        .into())
+    }
+
+    fn cancel(&self, _id: i64) -> Result<Pipeline> {
+        Err(GRError::OperationNotSupported(
+            "Cancelling workflow runs is not exposed in the Gitea API".to_string(),
+        )
+        .into())
+    }
+}
+
+pub struct GiteaPipelineFields {
+    id: i64,
+    status: String,
+    web_url: String,
+    branch: String,
+    sha: String,
+    created_at: String,
+    updated_at: String,
+}
+
+impl From<&serde_json::Value> for GiteaPipelineFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let started_at = data["started_at"].as_str().unwrap_or_default().to_string();
+        GiteaPipelineFields {
+            id: data["id"].as_i64().unwrap_or_default(),
+            // As in Github, the conclusion is the final state of the run and
+            // is only there once it finishes.
+            status: data["conclusion"]
+                .as_str()
+                .filter(|conclusion| !conclusion.is_empty())
+                .or(data["status"].as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            web_url: data["html_url"].as_str().unwrap_or_default().to_string(),
+            branch: data["head_branch"].as_str().unwrap_or_default().to_string(),
+            sha: data["head_sha"].as_str().unwrap_or_default().to_string(),
+            // Runs carry no update time, a running one has not completed yet.
+            updated_at: data["completed_at"]
+                .as_str()
+                .filter(|completed_at| !completed_at.starts_with("0001"))
+                .map(|completed_at| completed_at.to_string())
+                .unwrap_or_else(|| started_at.clone()),
+            created_at: started_at,
+        }
+    }
+}
+
+impl From<GiteaPipelineFields> for Pipeline {
+    fn from(fields: GiteaPipelineFields) -> Self {
+        Pipeline::builder()
+            .id(fields.id)
+            .status(fields.status)
+            .web_url(fields.web_url)
+            .branch(fields.branch)
+            .sha(fields.sha)
+            // Queued runs have not started.
+            .duration(if fields.created_at.is_empty() {
+                0
+            } else {
+                time::compute_duration(&fields.created_at, &fields.updated_at)
+            })
+            .created_at(fields.created_at)
+            .updated_at(fields.updated_at)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        display::DisplayBody,
+        test::utils::{config, MockRunner},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_list_workflow_runs() {
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"{"total_count": 1, "workflow_runs": [{"id": 12, "status": "completed",
+                    "conclusion": "success", "head_branch": "main", "head_sha": "abc123",
+                    "html_url": "https://gitea.com/jordilin/gitar/actions/runs/12",
+                    "started_at": "2024-03-01T10:00:00Z",
+                    "completed_at": "2024-03-01T10:01:30Z"}]}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitea: Box<dyn Cicd> = Box::new(Gitea::new(
+            config(),
+            "gitea.com",
+            "jordilin/gitar",
+            client.clone(),
+        ));
+        let args = PipelineBodyArgs::builder()
+            .from_to_page(None)
+            .build()
+            .unwrap();
+        let pipelines = gitea.list(args).unwrap();
+        assert_eq!(1, pipelines.len());
+        assert_eq!("success", pipelines[0].status);
+        let display: DisplayBody = pipelines[0].clone().into();
+        assert_eq!("90", display.columns[5].value);
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar/actions/runs",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_running_workflow_run_has_its_status() {
+        let data = serde_json::json!({
+            "id": 13, "status": "running", "conclusion": "", "head_branch": "main",
+            "head_sha": "abc123", "html_url": "https://gitea.com/jordilin/gitar/actions/runs/13",
+            "started_at": "2024-03-01T10:00:00Z", "completed_at": "0001-01-01T00:00:00Z"
+        });
+        let pipeline: Pipeline = GiteaPipelineFields::from(&data).into();
+        assert_eq!("running", pipeline.status);
+    }
+
+    #[test]
+    fn test_retry_is_not_supported() {
+        let client = Arc::new(MockRunner::new(vec![]));
+        let gitea: Box<dyn Cicd> =
+            Box::new(Gitea::new(config(), "gitea.com", "jordilin/gitar", client));
+        let err = gitea.retry(12).unwrap_err();
+        match err.downcast_ref::<GRError>() {
+            Some(GRError::OperationNotSupported(_)) => (),
+            _ => panic!("Expected OperationNotSupported, got {:?}", err),
+        }
+    }
+}
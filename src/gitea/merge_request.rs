@@ -0,0 +1,654 @@
+use super::Gitea;
+use crate::{
+    api_defaults::{GITEA_PULL_REQUEST_BODY_MAX_CHARS, GITEA_PULL_REQUEST_TITLE_MAX_CHARS},
+    api_traits::{ApiOperation, CommentMergeRequest, MergeRequest, RemoteProject},
+    cli::browse::BrowseOptions,
+    cmds::merge_request::{CommentMergeRequestBodyArgs, CommentThread, MergeRequestComment},
+    error::GRError,
+    http::{
+        Body,
+        Method::{GET, PATCH, POST},
+    },
+    io::{HttpRunner, PageCount, Response},
+    json_loads,
+    remote::{
+        query, MergeRequestBodyArgs, MergeRequestLimits, MergeRequestListBodyArgs,
+        MergeRequestResponse, MergeRequestState,
+    },
+    Result,
+};
+
+// Title prefix Gitea recognizes as a work in progress pull request in its
+// default configuration. There is no draft flag in the API.
+const DRAFT_PREFIX: &str = "WIP: ";
+const DRAFT_PREFIXES: [&str; 2] = ["WIP:", "[WIP]"];
+
+impl<R> Gitea<R> {
+    fn url_list_merge_requests(&self, args: &MergeRequestListBodyArgs) -> String {
+        let state = match args.state {
+            MergeRequestState::Opened => "open",
+            // As in Github, a merged pull request is a closed one.
+            MergeRequestState::Closed | MergeRequestState::Merged => "closed",
+        };
+        if args.reviewer.is_some() || args.assignee_id.is_some() {
+            // Only the issue search filters by review requests and assignees,
+            // and just for the authenticated user.
+            // Doc: https://gitea.com/api/swagger#/issue/issueSearchIssues
+            let filter = if args.reviewer.is_some() {
+                "review_requested=true"
+            } else {
+                "assigned=true"
+            };
+            return format!(
+                "{}/repos/issues/search?type=pulls&state={}&{}",
+                self.rest_api_basepath, state, filter
+            );
+        }
+        let mut url = format!(
+            "{}/repos/{}/pulls?state={}",
+            self.rest_api_basepath, self.path, state
+        );
+        if let Some(author) = &args.author {
+            url.push_str(&format!("&poster={}", author));
+        }
+        if let Some(milestone) = &args.milestone {
+            url.push_str(&format!("&milestone={}", milestone.id));
+        }
+        url
+    }
+
+    fn merge_request_url(&self, id: i64) -> String {
+        format!(
+            "{}/repos/{}/pulls/{}",
+            self.rest_api_basepath, self.path, id
+        )
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequest for Gitea<R> {
+    fn open(&self, args: MergeRequestBodyArgs) -> Result<MergeRequestResponse> {
+        if !args.labels.is_empty() {
+            // Labels are referenced by id when opening a pull request.
+            return Err(GRError::OperationNotSupported(
+                "Setting labels when opening a pull request is not supported in Gitea".to_string(),
+            )
+            .into());
+        }
+        let (repo, head) = match &args.target_repo {
+            Some(target_repo) => {
+                let owner = self.path.split('/').next().unwrap_or_default();
+                (
+                    target_repo.clone(),
+                    format!("{}:{}", owner, args.source_branch),
+                )
+            }
+            None => (self.path.clone(), args.source_branch.clone()),
+        };
+        let title = if args.draft {
+            format!("{}{}", DRAFT_PREFIX, args.title)
+        } else {
+            args.title
+        };
+        let mut body: Body<serde_json::Value> = Body::new();
+        body.add("head", head.clone().into());
+        body.add("base", args.target_branch.clone().into());
+        body.add("title", title.into());
+        body.add("body", args.description.into());
+        if !args.assignee_usernames.is_empty() {
+            body.add("assignees", args.assignee_usernames.into());
+        }
+        if !args.reviewer_usernames.is_empty() {
+            body.add("reviewers", args.reviewer_usernames.into());
+        }
+        if let Some(milestone_id) = args.milestone_id {
+            body.add("milestone", milestone_id.into());
+        }
+        // Doc: https://gitea.com/api/swagger#/repository/repoCreatePullRequest
+        let mr_url = format!("{}/repos/{}/pulls", self.rest_api_basepath, repo);
+        let response = query::gitea_merge_request_response(
+            &self.runner,
+            &mr_url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::MergeRequest,
+        )?;
+        match response.status {
+            201 => Ok(GiteaMergeRequestFields::from(&json_loads(&response.body)?).into()),
+            409 => {
+                // There is an open pull request for the same branches already.
+                // Doc: https://gitea.com/api/swagger#/repository/repoGetPullRequestByBaseHead
+                let existing_mr_url = format!("{}/{}/{}", mr_url, args.target_branch, head);
+                query::gitea_merge_request::<_, ()>(
+                    &self.runner,
+                    &existing_mr_url,
+                    None,
+                    self.request_headers(),
+                    GET,
+                    ApiOperation::MergeRequest,
+                )
+            }
+            _ => Err(GRError::RemoteServerError(format!(
+                "Failed to create pull request. Status code: {}, Body: {}",
+                response.status, response.body
+            ))
+            .into()),
+        }
+    }
+
+    fn list(&self, args: MergeRequestListBodyArgs) -> Result<Vec<MergeRequestResponse>> {
+        if args.draft.is_some() || args.passing_ci {
+            return Err(GRError::OperationNotSupported(
+                "Listing pull requests by draft or CI status is not supported in Gitea".to_string(),
+            )
+            .into());
+        }
+        let url = self.url_list_merge_requests(&args);
+        let merge_requests = query::gitea_list_merge_requests(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.request_headers(),
+            None,
+            ApiOperation::MergeRequest,
+        )?;
+        if args.reviewer.is_none() && args.assignee_id.is_none() {
+            return Ok(merge_requests);
+        }
+        // The issue search spans all the repositories of the user.
+        let repo_url = self.get_url(BrowseOptions::Repo);
+        Ok(merge_requests
+            .into_iter()
+            .filter(|mr| mr.web_url.starts_with(&format!("{}/", repo_url)))
+            .filter(|mr| {
+                args.author
+                    .as_ref()
+                    .is_none_or(|author| &mr.author == author)
+            })
+            .collect())
+    }
+
+    fn merge(&self, id: i64) -> Result<MergeRequestResponse> {
+        // Doc: https://gitea.com/api/swagger#/repository/repoMergePullRequest
+        let url = format!("{}/merge", self.merge_request_url(id));
+        let mut body = Body::new();
+        body.add("Do", "merge");
+        query::gitea_merge_request_response(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::MergeRequest,
+        )?;
+        // The response has no content, compute the URL of the pull request.
+        Ok(MergeRequestResponse::builder()
+            .id(id)
+            .web_url(self.get_url(BrowseOptions::MergeRequestId(id)))
+            .build()
+            .unwrap())
+    }
+
+    fn get(&self, id: i64) -> Result<MergeRequestResponse> {
+        query::gitea_merge_request::<_, ()>(
+            &self.runner,
+            &self.merge_request_url(id),
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )
+    }
+
+    fn close(&self, id: i64) -> Result<MergeRequestResponse> {
+        self.edit_merge_request(id, "state", "closed")
+    }
+
+    fn reopen(&self, id: i64) -> Result<MergeRequestResponse> {
+        self.edit_merge_request(id, "state", "open")
+    }
+
+    fn approve(&self, id: i64, comment: Option<&str>) -> Result<MergeRequestResponse> {
+        // Doc: https://gitea.com/api/swagger#/repository/repoCreatePullReview
+        let url = format!("{}/reviews", self.merge_request_url(id));
+        let mut body = Body::new();
+        body.add("event", "APPROVED");
+        if let Some(comment) = comment {
+            body.add("body", comment);
+        }
+        query::gitea_merge_request_response(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::MergeRequest,
+        )?;
+        MergeRequest::get(self, id)
+    }
+
+    fn set_draft(&self, id: i64, draft: bool) -> Result<MergeRequestResponse> {
+        let merge_request = MergeRequest::get(self, id)?;
+        let title = draft_title(&merge_request.title, draft);
+        if title == merge_request.title {
+            return Ok(merge_request);
+        }
+        self.edit_merge_request(id, "title", &title)
+    }
+
+    fn num_pages(&self, args: MergeRequestListBodyArgs) -> Result<Option<PageCount>> {
+        let url = self.url_list_merge_requests(&args) + "&page=1";
+        let headers = self.request_headers();
+        query::num_pages(&self.runner, &url, headers, ApiOperation::MergeRequest)
+    }
+
+    fn limits(&self) -> MergeRequestLimits {
+        MergeRequestLimits {
+            title: GITEA_PULL_REQUEST_TITLE_MAX_CHARS,
+            description: GITEA_PULL_REQUEST_BODY_MAX_CHARS,
+        }
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Gitea<R> {
+    fn edit_merge_request(
+        &self,
+        id: i64,
+        field: &str,
+        value: &str,
+    ) -> Result<MergeRequestResponse> {
+        // Doc: https://gitea.com/api/swagger#/repository/repoEditPullRequest
+        let mut body = Body::new();
+        body.add(field, value);
+        query::gitea_merge_request::<_, &str>(
+            &self.runner,
+            &self.merge_request_url(id),
+            Some(body),
+            self.request_headers(),
+            PATCH,
+            ApiOperation::MergeRequest,
+        )
+    }
+}
+
+/// Adds or removes the work in progress prefix of a pull request title.
+fn draft_title(title: &str, draft: bool) -> String {
+    let ready = DRAFT_PREFIXES
+        .iter()
+        .find_map(|prefix| title.strip_prefix(prefix))
+        .map(|title| title.trim_start())
+        .unwrap_or(title);
+    if draft {
+        format!("{}{}", DRAFT_PREFIX, ready)
+    } else {
+        ready.to_string()
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> CommentMergeRequest for Gitea<R> {
+    fn create(&self, args: CommentMergeRequestBodyArgs) -> Result<()> {
+        // Doc: https://gitea.com/api/swagger#/issue/issueCreateComment
+        let url = format!(
+            "{}/repos/{}/issues/{}/comments",
+            self.rest_api_basepath, self.path, args.id
+        );
+        let mut body = Body::new();
+        body.add("body", args.comment);
+        query::create_merge_request_comment(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(())
+    }
+
+    fn list_comments(&self, id: i64) -> Result<Vec<CommentThread>> {
+        // General comments only. Comments on the diff belong to reviews, which
+        // need one request each.
+        // Doc: https://gitea.com/api/swagger#/issue/issueGetComments
+        let url = format!(
+            "{}/repos/{}/issues/{}/comments",
+            self.rest_api_basepath, self.path, id
+        );
+        query::gitea_list_merge_request_comments(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            None,
+            ApiOperation::MergeRequest,
+        )
+    }
+}
+
+pub struct GiteaCommentFields {
+    thread: CommentThread,
+}
+
+impl From<&serde_json::Value> for GiteaCommentFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let comment = MergeRequestComment::builder()
+            .id(data["id"].as_i64().unwrap_or_default())
+            .author(
+                data["user"]["login"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            )
+            .created_at(data["created_at"].as_str().unwrap_or_default().to_string())
+            .body(data["body"].as_str().unwrap_or_default().to_string())
+            .build()
+            .unwrap();
+        GiteaCommentFields {
+            thread: CommentThread::builder()
+                .id(comment.id.to_string())
+                .comments(vec![comment])
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GiteaCommentFields> for CommentThread {
+    fn from(fields: GiteaCommentFields) -> Self {
+        fields.thread
+    }
+}
+
+pub struct GiteaMergeRequestFields {
+    id: i64,
+    web_url: String,
+    source_branch: String,
+    target_branch: String,
+    author: String,
+    updated_at: String,
+    created_at: String,
+    title: String,
+    description: String,
+    merged_at: String,
+    labels: Vec<String>,
+}
+
+impl From<&serde_json::Value> for GiteaMergeRequestFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let text = |value: &serde_json::Value| value.as_str().unwrap_or_default().to_string();
+        GiteaMergeRequestFields {
+            id: data["number"].as_i64().unwrap_or_default(),
+            web_url: text(&data["html_url"]),
+            // Issue search results carry no branches.
+            source_branch: text(&data["head"]["ref"]),
+            target_branch: text(&data["base"]["ref"]),
+            author: text(&data["user"]["login"]),
+            updated_at: text(&data["updated_at"]),
+            created_at: text(&data["created_at"]),
+            title: text(&data["title"]),
+            description: text(&data["body"]),
+            // Found under pull_request in issue search results.
+            merged_at: data["merged_at"]
+                .as_str()
+                .or(data["pull_request"]["merged_at"].as_str())
+                .unwrap_or_default()
+                .to_string(),
+            labels: data["labels"]
+                .as_array()
+                .map(|labels| {
+                    labels
+                        .iter()
+                        .filter_map(|label| label["name"].as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GiteaMergeRequestFields> for MergeRequestResponse {
+    fn from(fields: GiteaMergeRequestFields) -> Self {
+        MergeRequestResponse::builder()
+            .id(fields.id)
+            .web_url(fields.web_url)
+            .source_branch(fields.source_branch)
+            .target_branch(fields.target_branch)
+            .author(fields.author)
+            .updated_at(fields.updated_at)
+            .created_at(fields.created_at)
+            .title(fields.title)
+            .description(fields.description)
+            .merged_at(fields.merged_at)
+            .labels(fields.labels)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        http,
+        remote::Member,
+        test::utils::{config, MockRunner},
+    };
+
+    use super::*;
+
+    const PULL_REQUEST: &str = r#"{"number": 7, "title": "Add feature",
+        "html_url": "https://gitea.com/jordilin/gitar/pulls/7", "body": "Description",
+        "user": {"login": "jordilin"}, "head": {"ref": "feature"}, "base": {"ref": "main"},
+        "labels": [{"name": "enhancement"}], "merged_at": null,
+        "created_at": "2024-03-01T10:00:00Z", "updated_at": "2024-03-02T10:00:00Z"}"#;
+
+    fn response(status: i32, body: &str) -> Response {
+        Response::builder()
+            .status(status)
+            .body(body.to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn gitea(client: Arc<MockRunner>) -> Gitea<MockRunner> {
+        Gitea::new(config(), "gitea.com", "jordilin/gitar", client)
+    }
+
+    #[test]
+    fn test_open_pull_request() {
+        let client = Arc::new(MockRunner::new(vec![response(201, PULL_REQUEST)]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client.clone()));
+        let args = MergeRequestBodyArgs::builder()
+            .source_branch("feature".to_string())
+            .target_branch("main".to_string())
+            .title("Add feature".to_string())
+            .build()
+            .unwrap();
+        let merge_request = gitea.open(args).unwrap();
+        assert_eq!(7, merge_request.id);
+        assert_eq!("feature", merge_request.source_branch);
+        assert_eq!(vec!["enhancement"], merge_request.labels);
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar/pulls",
+            *client.url()
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!("token 1234", client.headers().get("Authorization").unwrap());
+    }
+
+    #[test]
+    fn test_open_existing_pull_request_gets_it() {
+        let client = Arc::new(MockRunner::new(vec![
+            response(200, PULL_REQUEST),
+            response(409, r#"{"message": "pull request already exists"}"#),
+        ]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client.clone()));
+        let args = MergeRequestBodyArgs::builder()
+            .source_branch("feature".to_string())
+            .target_branch("main".to_string())
+            .title("Add feature".to_string())
+            .build()
+            .unwrap();
+        let merge_request = gitea.open(args).unwrap();
+        assert_eq!(7, merge_request.id);
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar/pulls/main/feature",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_open_pull_request_with_labels_is_not_supported() {
+        let client = Arc::new(MockRunner::new(vec![]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client));
+        let args = MergeRequestBodyArgs::builder()
+            .source_branch("feature".to_string())
+            .target_branch("main".to_string())
+            .labels(vec!["bug".to_string()])
+            .build()
+            .unwrap();
+        let err = gitea.open(args).unwrap_err();
+        match err.downcast_ref::<GRError>() {
+            Some(GRError::OperationNotSupported(_)) => (),
+            _ => panic!("Expected OperationNotSupported, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_list_pull_requests_by_ci_status_is_not_supported() {
+        let client = Arc::new(MockRunner::new(vec![]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .passing_ci(true)
+            .build()
+            .unwrap();
+        let err = gitea.list(args).unwrap_err();
+        match err.downcast_ref::<GRError>() {
+            Some(GRError::OperationNotSupported(_)) => (),
+            _ => panic!("Expected OperationNotSupported, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_list_pull_requests_by_author() {
+        let client = Arc::new(MockRunner::new(vec![response(
+            200,
+            &format!("[{}]", PULL_REQUEST),
+        )]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .author(Some("jordilin".to_string()))
+            .build()
+            .unwrap();
+        let merge_requests = gitea.list(args).unwrap();
+        assert_eq!(1, merge_requests.len());
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar/pulls?state=open&poster=jordilin",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_list_pull_requests_to_review_keeps_the_repository_ones() {
+        let other_repo = PULL_REQUEST.replace("jordilin/gitar/", "jordilin/other/");
+        let client = Arc::new(MockRunner::new(vec![response(
+            200,
+            &format!("[{}, {}]", PULL_REQUEST, other_repo),
+        )]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .reviewer(Some(
+                Member::builder()
+                    .id(1)
+                    .username("jordilin".to_string())
+                    .name("".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap();
+        let merge_requests = gitea.list(args).unwrap();
+        assert_eq!(1, merge_requests.len());
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/issues/search?type=pulls&state=open&review_requested=true",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_merge_pull_request() {
+        let client = Arc::new(MockRunner::new(vec![response(200, "")]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client.clone()));
+        let merge_request = gitea.merge(7).unwrap();
+        assert_eq!(
+            "https://gitea.com/jordilin/gitar/pulls/7",
+            merge_request.web_url
+        );
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar/pulls/7/merge",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_approve_pull_request() {
+        let client = Arc::new(MockRunner::new(vec![
+            response(200, PULL_REQUEST),
+            response(200, r#"{"id": 1, "state": "APPROVED"}"#),
+        ]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client.clone()));
+        assert_eq!(7, gitea.approve(7, None).unwrap().id);
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar/pulls/7",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_set_draft_prefixes_title() {
+        let draft = PULL_REQUEST.replace("\"Add feature\"", "\"WIP: Add feature\"");
+        let client = Arc::new(MockRunner::new(vec![
+            response(200, &draft),
+            response(200, PULL_REQUEST),
+        ]));
+        let gitea: Box<dyn MergeRequest> = Box::new(gitea(client.clone()));
+        let merge_request = gitea.set_draft(7, true).unwrap();
+        assert_eq!("WIP: Add feature", merge_request.title);
+        assert_eq!(http::Method::PATCH, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_draft_title() {
+        assert_eq!("WIP: Add feature", draft_title("Add feature", true));
+        assert_eq!("WIP: Add feature", draft_title("[WIP] Add feature", true));
+        assert_eq!("Add feature", draft_title("WIP: Add feature", false));
+        assert_eq!("Add feature", draft_title("Add feature", false));
+    }
+
+    #[test]
+    fn test_list_comments() {
+        let client = Arc::new(MockRunner::new(vec![response(
+            200,
+            r#"[{"id": 3, "body": "LGTM", "user": {"login": "jdoe"},
+                "created_at": "2024-03-01T10:00:00Z"}]"#,
+        )]));
+        let gitea: Box<dyn CommentMergeRequest> = Box::new(gitea(client.clone()));
+        let threads = gitea.list_comments(7).unwrap();
+        assert_eq!(1, threads.len());
+        assert_eq!("jdoe", threads[0].comments[0].author);
+        assert_eq!("LGTM", threads[0].comments[0].body);
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar/issues/7/comments",
+            *client.url()
+        );
+    }
+}
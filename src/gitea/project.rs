@@ -0,0 +1,321 @@
+use super::Gitea;
+use crate::{
+    api_traits::{ApiOperation, RemoteProject},
+    cli::browse::BrowseOptions,
+    cmds::project::ProjectListBodyArgs,
+    http::Method::GET,
+    io::{CmdInfo, HttpRunner, PageCount, Response},
+    remote::{encode_query_param, query, Member, Project, URLQueryParamBuilder},
+    Result,
+};
+
+impl<R: HttpRunner<Response = Response>> RemoteProject for Gitea<R> {
+    fn get_project_data(&self, id: Option<i64>) -> Result<CmdInfo> {
+        // Doc: https://gitea.com/api/swagger#/repository/repoGet
+        let url = match id {
+            Some(id) => format!("{}/repositories/{}", self.rest_api_basepath, id),
+            None => format!("{}/repos/{}", self.rest_api_basepath, self.path),
+        };
+        let project = query::gitea_project_data::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        Ok(CmdInfo::Project(project))
+    }
+
+    fn get_project_members(&self) -> Result<CmdInfo> {
+        // Doc: https://gitea.com/api/swagger#/repository/repoListCollaborators
+        let url = format!(
+            "{}/repos/{}/collaborators",
+            self.rest_api_basepath, self.path
+        );
+        let members = query::gitea_list_members(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            None,
+            ApiOperation::Project,
+        )?;
+        Ok(CmdInfo::Members(members))
+    }
+
+    fn get_url(&self, option: BrowseOptions) -> String {
+        let base_url = format!("https://{}/{}", self.domain, self.path);
+        match option {
+            BrowseOptions::Repo => base_url,
+            BrowseOptions::MergeRequests => format!("{}/pulls", base_url),
+            BrowseOptions::MergeRequestId(id) => format!("{}/pulls/{}", base_url, id),
+            BrowseOptions::Pipelines => format!("{}/actions", base_url),
+            BrowseOptions::PipelineId(id) => format!("{}/actions/runs/{}", base_url, id),
+            // Without a ref type in the URL, Gitea looks the ref up among
+            // branches, tags and commits, and falls back to the default
+            // branch.
+            BrowseOptions::File { path, ref_ } => {
+                let path = path.trim_start_matches('/');
+                match ref_ {
+                    Some(ref_) => format!("{}/src/{}/{}", base_url, ref_, path),
+                    None => format!("{}/src/{}", base_url, path),
+                }
+            }
+            BrowseOptions::Releases => format!("{}/releases", base_url),
+            BrowseOptions::Settings => format!("{}/settings", base_url),
+        }
+    }
+
+    fn list(&self, args: ProjectListBodyArgs) -> Result<Vec<Project>> {
+        let url = self.list_project_url(&args, false);
+        // Search results are wrapped in an object under "data".
+        let iter_over_sub_array = is_repository_search(&args).then_some("data");
+        query::gitea_list_projects(
+            &self.runner,
+            &url,
+            args.from_to_page,
+            self.request_headers(),
+            iter_over_sub_array,
+            ApiOperation::Project,
+        )
+    }
+
+    fn num_pages(&self, args: ProjectListBodyArgs) -> Result<Option<PageCount>> {
+        let url = self.list_project_url(&args, true);
+        query::num_pages(
+            &self.runner,
+            &url,
+            self.request_headers(),
+            ApiOperation::Project,
+        )
+    }
+}
+
+impl<R> Gitea<R> {
+    fn list_project_url(&self, args: &ProjectListBodyArgs, num_pages: bool) -> String {
+        let filters = &args.filters;
+        let url = if is_repository_search(args) {
+            // Doc: https://gitea.com/api/swagger#/repository/repoSearch
+            let mut url =
+                URLQueryParamBuilder::new(&format!("{}/repos/search", self.rest_api_basepath));
+            if let Some(search) = &filters.search {
+                url.add_param("q", &encode_query_param(search));
+            }
+            if let Some(user) = &args.user {
+                url.add_param("uid", &user.id.to_string());
+            }
+            if filters.archived {
+                url.add_param("archived", "true");
+            }
+            url.build()
+        } else if args.stars {
+            format!("{}/user/starred", self.rest_api_basepath)
+        } else if filters.owned && !filters.member {
+            let username = &args.user.as_ref().unwrap().username;
+            format!("{}/users/{}/repos", self.rest_api_basepath, username)
+        } else {
+            // Owned repositories and the ones the user collaborates in.
+            format!("{}/user/repos", self.rest_api_basepath)
+        };
+        if num_pages {
+            return URLQueryParamBuilder::new(&url)
+                .add_param("page", "1")
+                .build();
+        }
+        url
+    }
+}
+
+fn is_repository_search(args: &ProjectListBodyArgs) -> bool {
+    args.filters.search.is_some() || args.filters.archived
+}
+
+pub struct GiteaProjectFields {
+    id: i64,
+    default_branch: String,
+    html_url: String,
+    created_at: String,
+}
+
+impl From<&serde_json::Value> for GiteaProjectFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GiteaProjectFields {
+            id: data["id"].as_i64().unwrap_or_default(),
+            default_branch: data["default_branch"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            html_url: data["html_url"].as_str().unwrap_or_default().to_string(),
+            created_at: data["created_at"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GiteaProjectFields> for Project {
+    fn from(fields: GiteaProjectFields) -> Self {
+        Project::new(fields.id, &fields.default_branch)
+            .with_html_url(&fields.html_url)
+            .with_created_at(&fields.created_at)
+    }
+}
+
+pub struct GiteaMemberFields {
+    id: i64,
+    login: String,
+    full_name: String,
+    created_at: String,
+}
+
+impl From<&serde_json::Value> for GiteaMemberFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GiteaMemberFields {
+            id: data["id"].as_i64().unwrap_or_default(),
+            login: data["login"].as_str().unwrap_or_default().to_string(),
+            full_name: data["full_name"].as_str().unwrap_or_default().to_string(),
+            created_at: data["created"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GiteaMemberFields> for Member {
+    fn from(fields: GiteaMemberFields) -> Self {
+        Member::builder()
+            .id(fields.id)
+            .username(fields.login)
+            .name(fields.full_name)
+            .created_at(fields.created_at)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        cmds::project::ProjectListFilters,
+        test::utils::{config, MockRunner},
+    };
+
+    use super::*;
+
+    const REPOSITORY: &str = r#"{"id": 42, "full_name": "jordilin/gitar",
+        "default_branch": "main", "html_url": "https://gitea.com/jordilin/gitar",
+        "created_at": "2024-01-01T00:00:00Z"}"#;
+
+    fn response(body: &str) -> Response {
+        Response::builder()
+            .status(200)
+            .body(body.to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_get_project_data() {
+        let client = Arc::new(MockRunner::new(vec![response(REPOSITORY)]));
+        let gitea: Box<dyn RemoteProject> = Box::new(Gitea::new(
+            config(),
+            "gitea.com",
+            "jordilin/gitar",
+            client.clone(),
+        ));
+        match gitea.get_project_data(None).unwrap() {
+            CmdInfo::Project(project) => {
+                assert_eq!(42, project.id());
+                assert_eq!("main", project.default_branch());
+            }
+            _ => panic!("Expected project"),
+        }
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_get_project_members() {
+        let client = Arc::new(MockRunner::new(vec![response(
+            r#"[{"id": 1, "login": "jdoe", "full_name": "John Doe",
+                "created": "2023-01-01T00:00:00Z"}]"#,
+        )]));
+        let gitea: Box<dyn RemoteProject> = Box::new(Gitea::new(
+            config(),
+            "gitea.com",
+            "jordilin/gitar",
+            client.clone(),
+        ));
+        match gitea.get_project_members().unwrap() {
+            CmdInfo::Members(members) => {
+                assert_eq!("jdoe", members[0].username);
+                assert_eq!("John Doe", members[0].name);
+            }
+            _ => panic!("Expected members"),
+        }
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/jordilin/gitar/collaborators",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_search_repositories() {
+        let client = Arc::new(MockRunner::new(vec![response(&format!(
+            r#"{{"ok": true, "data": [{}]}}"#,
+            REPOSITORY
+        ))]));
+        let gitea: Box<dyn RemoteProject> = Box::new(Gitea::new(
+            config(),
+            "gitea.com",
+            "jordilin/gitar",
+            client.clone(),
+        ));
+        let args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(5)
+                    .username("jordilin".to_string())
+                    .name("".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .filters(
+                ProjectListFilters::builder()
+                    .search(Some("gitar".to_string()))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let projects = gitea.list(args).unwrap();
+        assert_eq!(1, projects.len());
+        assert_eq!(
+            "https://gitea.com/api/v1/repos/search?q=gitar&uid=5",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_browse_urls() {
+        let gitea = Gitea::new(
+            config(),
+            "gitea.com",
+            "jordilin/gitar",
+            Arc::new(MockRunner::new(vec![])),
+        );
+        assert_eq!(
+            "https://gitea.com/jordilin/gitar/pulls/7",
+            gitea.get_url(BrowseOptions::MergeRequestId(7))
+        );
+        assert_eq!(
+            "https://gitea.com/jordilin/gitar/src/v1.0/src/main.rs",
+            gitea.get_url(BrowseOptions::File {
+                path: "src/main.rs".to_string(),
+                ref_: Some("v1.0".to_string()),
+            })
+        );
+    }
+}
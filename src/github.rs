@@ -1,12 +1,25 @@
+use crate::api_defaults::GITHUB_API_VERSION;
+use crate::api_traits::ApiOperation;
 use crate::config::ConfigProperties;
-use crate::http::Headers;
+use crate::http::{self, Body, Headers, Method::POST};
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::{error, Result};
 use std::sync::Arc;
 
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod auth;
 pub mod cicd;
 pub mod container_registry;
+pub mod contract;
+pub mod environment;
+pub mod issue;
 pub mod merge_request;
 pub mod project;
 pub mod release;
+pub mod status;
+pub mod undo;
 pub mod user;
 
 #[derive(Clone)]
@@ -16,11 +29,15 @@ pub struct Github<R> {
     path: String,
     rest_api_basepath: String,
     runner: Arc<R>,
+    extra_headers: Vec<(String, String)>,
+    user_agent: String,
 }
 
 impl<R> Github<R> {
     pub fn new(config: impl ConfigProperties, domain: &str, path: &str, runner: Arc<R>) -> Self {
         let api_token = config.api_token().to_string();
+        let extra_headers = config.extra_headers().to_vec();
+        let user_agent = http::user_agent(config.user_agent_suffix());
         let domain = domain.to_string();
         let rest_api_basepath = format!("https://api.{}", domain);
 
@@ -30,19 +47,86 @@ impl<R> Github<R> {
             path: path.to_string(),
             rest_api_basepath,
             runner,
+            extra_headers,
+            user_agent,
         }
     }
 
     fn request_headers(&self) -> Headers {
         let mut headers = Headers::new();
+        for (name, value) in &self.extra_headers {
+            headers.set(name, value);
+        }
         let auth_token_value = format!("bearer {}", self.api_token);
         headers.set("Authorization".to_string(), auth_token_value);
         headers.set(
             "Accept".to_string(),
             "application/vnd.github.v3+json".to_string(),
         );
-        headers.set("User-Agent".to_string(), "gitar".to_string());
-        headers.set("X-GitHub-Api-Version".to_string(), "2022-11-28".to_string());
+        headers.set("User-Agent".to_string(), self.user_agent.clone());
+        headers.set("X-GitHub-Api-Version".to_string(), GITHUB_API_VERSION);
         headers
     }
 }
+
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    /// Run a GraphQL query or mutation and return its `data` object. Some
+    /// features, like merge queues, are only exposed through the GraphQL API.
+    fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        operation: ApiOperation,
+    ) -> Result<serde_json::Value> {
+        let url = graphql_url(&self.domain);
+        let mut body = Body::new();
+        body.add("query", serde_json::Value::String(query.to_string()));
+        body.add("variables", variables);
+        let mut response = query::github_graphql(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            operation,
+        )?;
+        // GraphQL answers with a 200 status code even when the query fails.
+        if let Some(errors) = response["errors"].as_array() {
+            let messages = errors
+                .iter()
+                .filter_map(|error| error["message"].as_str())
+                .collect::<Vec<&str>>();
+            return Err(error::GRError::RemoteServerError(format!(
+                "GraphQL request to {} failed: {}",
+                url,
+                messages.join(", ")
+            ))
+            .into());
+        }
+        Ok(response["data"].take())
+    }
+}
+
+/// Github Enterprise Server serves GraphQL under the `/api` path of its own
+/// host instead of an `api.` subdomain.
+/// https://docs.github.com/en/enterprise-server@latest/graphql/guides/forming-calls-with-graphql#the-graphql-endpoint
+fn graphql_url(domain: &str) -> String {
+    if domain == "github.com" {
+        return "https://api.github.com/graphql".to_string();
+    }
+    format!("https://{}/api/graphql", domain)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_graphql_url_of_github_and_enterprise_server() {
+        assert_eq!("https://api.github.com/graphql", graphql_url("github.com"));
+        assert_eq!(
+            "https://github.mycompany.com/api/graphql",
+            graphql_url("github.mycompany.com")
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{
+    api_traits::InstanceAdmin,
+    cmds::admin::{
+        InstanceProject, InstanceProjectListBodyArgs, InstanceUser, InstanceUserListBodyArgs,
+    },
+    error::GRError,
+    io::{HttpRunner, Response},
+    Result,
+};
+
+use super::Github;
+
+// Instance administration is only available in Gitlab. Github Enterprise
+// Server has its own site admin API, not supported here.
+impl<R: HttpRunner<Response = Response>> InstanceAdmin for Github<R> {
+    fn list_users(&self, _args: InstanceUserListBodyArgs) -> Result<Vec<InstanceUser>> {
+        Err(admin_not_supported())
+    }
+
+    fn block_user(&self, _id: i64) -> Result<()> {
+        Err(admin_not_supported())
+    }
+
+    fn list_projects(&self, _args: InstanceProjectListBodyArgs) -> Result<Vec<InstanceProject>> {
+        Err(admin_not_supported())
+    }
+}
+
+fn admin_not_supported() -> anyhow::Error {
+    GRError::OperationNotSupported("Instance administration is not supported in Github".to_string())
+        .into()
+}
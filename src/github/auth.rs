@@ -0,0 +1,91 @@
+use super::Github;
+use crate::api_traits::{ApiOperation, TokenInfo};
+use crate::cmds::auth::TokenMetadata;
+use crate::http;
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::Result;
+
+// Fine-grained and classic tokens with an expiration report it in every
+// response, ex: 2024-04-01 00:00:00 UTC
+const TOKEN_EXPIRATION_HEADER: &str = "github-authentication-token-expiration";
+// Scopes of classic tokens
+const OAUTH_SCOPES_HEADER: &str = "x-oauth-scopes";
+
+impl<R: HttpRunner<Response = Response>> TokenInfo for Github<R> {
+    fn token(&self) -> Result<TokenMetadata> {
+        let url = format!("{}/user", self.rest_api_basepath);
+        let response = query::github_token_response::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )?;
+        // Github does not expose the name of the token.
+        Ok(TokenMetadata::builder()
+            .domain(self.domain.clone())
+            .scopes(
+                response
+                    .header(OAUTH_SCOPES_HEADER)
+                    .unwrap_or_default()
+                    .replace(' ', ""),
+            )
+            .expires_at(
+                response
+                    .header(TOKEN_EXPIRATION_HEADER)
+                    .and_then(|expiration| expiration.split_whitespace().next())
+                    .map(|date| date.to_string()),
+            )
+            .build()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        http::Headers,
+        test::utils::{config, MockRunner},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_token_expiration_from_headers() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let mut headers = Headers::new();
+        headers.set(TOKEN_EXPIRATION_HEADER, "2024-04-01 00:00:00 UTC");
+        headers.set(OAUTH_SCOPES_HEADER, "repo, workflow");
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn TokenInfo> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let token = github.token().unwrap();
+        assert_eq!("github.com", token.domain);
+        assert_eq!("repo,workflow", token.scopes);
+        assert_eq!(Some("2024-04-01".to_string()), token.expires_at);
+        assert_eq!("https://api.github.com/user", *client.url());
+    }
+
+    #[test]
+    fn test_token_without_expiration() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder().status(200).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn TokenInfo> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let token = github.token().unwrap();
+        assert_eq!(None, token.expires_at);
+    }
+}
@@ -1,10 +1,15 @@
 use super::Github;
-use crate::api_traits::{ApiOperation, CicdRunner};
-use crate::cmds::cicd::{Pipeline, PipelineBodyArgs, RunnerListBodyArgs, RunnerMetadata};
+use crate::api_traits::{ApiOperation, CicdArtifact, CicdGraph, CicdJob, CicdLinter, CicdRunner};
+use crate::cmds::cicd::{
+    Artifact, ArtifactListBodyArgs, Job, JobRetryBodyArgs, LintBodyArgs, LintError, LintResponse,
+    Pipeline, PipelineBodyArgs, PipelineJobNode, RunnerListBodyArgs, RunnerMetadata,
+};
+use crate::error::GRError;
+use crate::http;
 use crate::remote::query;
 use crate::{
     api_traits::Cicd,
-    io::{HttpRunner, Response},
+    io::{HttpRunner, PageCount, Response},
 };
 use crate::{time, Result};
 
@@ -26,11 +31,24 @@ impl<R: HttpRunner<Response = Response>> Cicd for Github<R> {
         )
     }
 
-    fn get_pipeline(&self, _id: i64) -> Result<Pipeline> {
-        todo!()
+    fn get_pipeline(&self, id: i64) -> Result<Pipeline> {
+        // Doc:
+        // https://docs.github.com/en/rest/actions/workflow-runs?apiVersion=2022-11-28#get-a-workflow-run
+        let url = format!(
+            "{}/repos/{}/actions/runs/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        query::github_pipeline::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Pipeline,
+        )
     }
 
-    fn num_pages(&self) -> Result<Option<u32>> {
+    fn num_pages(&self) -> Result<Option<PageCount>> {
         let url = format!(
             "{}/repos/{}/actions/runs?page=1",
             self.rest_api_basepath, self.path
@@ -38,6 +56,39 @@ impl<R: HttpRunner<Response = Response>> Cicd for Github<R> {
         let headers = self.request_headers();
         query::num_pages(&self.runner, &url, headers, ApiOperation::Pipeline)
     }
+
+    fn retry(&self, id: i64) -> Result<Pipeline> {
+        self.workflow_run_action(id, "rerun", "queued")
+    }
+
+    fn cancel(&self, id: i64) -> Result<Pipeline> {
+        self.workflow_run_action(id, "cancel", "cancelled")
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    /// Re-running and cancelling a workflow run do not return it, so it is
+    /// gathered first and returned with the status it is expected to reach.
+    fn workflow_run_action(&self, id: i64, action: &str, status: &str) -> Result<Pipeline> {
+        let mut pipeline = self.get_pipeline(id)?;
+        let url = format!(
+            "{}/repos/{}/actions/runs/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        // Doc:
+        // https://docs.github.com/en/rest/actions/workflow-runs?apiVersion=2022-11-28#re-run-a-workflow
+        // https://docs.github.com/en/rest/actions/workflow-runs?apiVersion=2022-11-28#cancel-a-workflow-run
+        query::github_pipeline_action::<_, ()>(
+            &self.runner,
+            &format!("{}/{}", url, action),
+            None,
+            self.request_headers(),
+            http::Method::POST,
+            ApiOperation::Pipeline,
+        )?;
+        pipeline.status = status.to_string();
+        Ok(pipeline)
+    }
 }
 
 impl<R: HttpRunner<Response = Response>> CicdRunner for Github<R> {
@@ -49,12 +100,350 @@ impl<R: HttpRunner<Response = Response>> CicdRunner for Github<R> {
         todo!();
     }
 
-    fn num_pages(&self, _args: RunnerListBodyArgs) -> Result<Option<u32>> {
+    fn num_pages(&self, _args: RunnerListBodyArgs) -> Result<Option<PageCount>> {
         todo!();
     }
+
+    fn set_shared_runners(&self, _enabled: bool) -> Result<()> {
+        Err(GRError::OperationNotSupported(
+            "Shared runners are a Gitlab feature. Not supported in Github".to_string(),
+        )
+        .into())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> CicdArtifact for Github<R> {
+    fn list(&self, args: ArtifactListBodyArgs) -> Result<Vec<Artifact>> {
+        // Doc:
+        // https://docs.github.com/en/rest/actions/artifacts?apiVersion=2022-11-28#list-artifacts-for-a-repository
+        let url = format!(
+            "{}/repos/{}/actions/artifacts",
+            self.rest_api_basepath, self.path
+        );
+        let artifacts = query::github_list_artifacts(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.request_headers(),
+            Some("artifacts"),
+            ApiOperation::Pipeline,
+        )?;
+        // Expired artifacts no longer take up storage.
+        Ok(artifacts
+            .into_iter()
+            .filter(|artifact| !artifact.expired)
+            .collect())
+    }
+
+    fn delete(&self, id: i64) -> Result<()> {
+        // Doc:
+        // https://docs.github.com/en/rest/actions/artifacts?apiVersion=2022-11-28#delete-an-artifact
+        let url = format!(
+            "{}/repos/{}/actions/artifacts/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        query::delete_artifact::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::DELETE,
+            ApiOperation::Pipeline,
+        )?;
+        Ok(())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> CicdJob for Github<R> {
+    fn retry(&self, args: JobRetryBodyArgs) -> Result<Job> {
+        if !args.variables.is_empty() {
+            return Err(GRError::OperationNotSupported(
+                "Github does not support overriding variables when re-running a job".to_string(),
+            )
+            .into());
+        }
+        // Doc:
+        // https://docs.github.com/en/rest/actions/workflow-jobs?apiVersion=2022-11-28#get-a-job-for-a-workflow-run
+        let url = format!(
+            "{}/repos/{}/actions/jobs/{}",
+            self.rest_api_basepath, self.path, args.id
+        );
+        let mut job = query::github_get_job::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Pipeline,
+        )?;
+        // Doc:
+        // https://docs.github.com/en/rest/actions/workflow-runs?apiVersion=2022-11-28#re-run-a-job-from-a-workflow-run
+        query::github_rerun_job::<_, ()>(
+            &self.runner,
+            &format!("{}/rerun", url),
+            None,
+            self.request_headers(),
+            http::Method::POST,
+            ApiOperation::Pipeline,
+        )?;
+        job.status = "queued".to_string();
+        Ok(job)
+    }
+
+    fn list(&self, pipeline_id: i64) -> Result<Vec<Job>> {
+        // Doc:
+        // https://docs.github.com/en/rest/actions/workflow-jobs?apiVersion=2022-11-28#list-jobs-for-a-workflow-run
+        let url = format!(
+            "{}/repos/{}/actions/runs/{}/jobs?filter=latest",
+            self.rest_api_basepath, self.path, pipeline_id
+        );
+        query::github_list_pipeline_jobs(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            Some("jobs"),
+            ApiOperation::Pipeline,
+        )
+    }
+}
+
+impl<R> CicdGraph for Github<R> {
+    fn jobs(&self, _pipeline_id: i64) -> Result<Vec<PipelineJobNode>> {
+        // The jobs of a workflow run do not tell which jobs they need.
+        Err(GRError::OperationNotSupported(
+            "Github does not expose the needs of the jobs in a workflow run".to_string(),
+        )
+        .into())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> CicdLinter for Github<R> {
+    fn lint(&self, args: LintBodyArgs) -> Result<LintResponse> {
+        // Github has no API to validate workflows, so we check them locally.
+        Ok(lint_workflow(&args.content))
+    }
+
+    fn merged_config(&self, _ref: &str) -> Result<LintResponse> {
+        Err(GRError::OperationNotSupported(
+            "Github workflows have no merged configuration. Use gr pp lint instead".to_string(),
+        )
+        .into())
+    }
+}
+
+const WORKFLOW_KEYS: [&str; 8] = [
+    "name",
+    "run-name",
+    "on",
+    "permissions",
+    "env",
+    "defaults",
+    "concurrency",
+    "jobs",
+];
+
+fn lint_workflow(content: &str) -> LintResponse {
+    let workflow: serde_yaml::Value = match serde_yaml::from_str(content) {
+        Ok(workflow) => workflow,
+        Err(err) => {
+            let line = err.location().map(|location| location.line());
+            return LintResponse {
+                valid: false,
+                errors: vec![LintError::new(line, err.to_string())],
+                ..Default::default()
+            };
+        }
+    };
+    let mut errors = Vec::new();
+    let Some(workflow) = workflow.as_mapping() else {
+        errors.push(LintError::new(Some(1), "workflow must be a mapping"));
+        return LintResponse {
+            valid: false,
+            errors,
+            ..Default::default()
+        };
+    };
+    for key in workflow.keys() {
+        let key = key.as_str().unwrap_or_default();
+        if !WORKFLOW_KEYS.contains(&key) {
+            errors.push(LintError::new(
+                key_line(content, key, 1),
+                format!("unexpected key '{}'", key),
+            ));
+        }
+    }
+    if !workflow.contains_key("on") {
+        errors.push(LintError::new(None, "missing required key 'on'"));
+    }
+    match workflow.get("jobs").and_then(|jobs| jobs.as_mapping()) {
+        Some(jobs) if !jobs.is_empty() => {
+            let jobs_line = key_line(content, "jobs", 1).unwrap_or(1);
+            for (job_id, job) in jobs {
+                let job_id = job_id.as_str().unwrap_or_default();
+                lint_job(
+                    job_id,
+                    job,
+                    key_line(content, job_id, jobs_line),
+                    &mut errors,
+                );
+            }
+        }
+        _ => errors.push(LintError::new(
+            key_line(content, "jobs", 1),
+            "'jobs' must be a non-empty mapping",
+        )),
+    }
+    LintResponse {
+        valid: errors.is_empty(),
+        errors,
+        ..Default::default()
+    }
+}
+
+fn lint_job(
+    job_id: &str,
+    job: &serde_yaml::Value,
+    line: Option<usize>,
+    errors: &mut Vec<LintError>,
+) {
+    let Some(job) = job.as_mapping() else {
+        errors.push(LintError::new(
+            line,
+            format!("job '{}' must be a mapping", job_id),
+        ));
+        return;
+    };
+    // Jobs calling a reusable workflow have no runner nor steps.
+    if job.contains_key("uses") {
+        return;
+    }
+    if !job.contains_key("runs-on") {
+        errors.push(LintError::new(
+            line,
+            format!("job '{}' is missing required key 'runs-on'", job_id),
+        ));
+    }
+    match job.get("steps").and_then(|steps| steps.as_sequence()) {
+        Some(steps) if !steps.is_empty() => {
+            for (i, step) in steps.iter().enumerate() {
+                let has_uses = step.get("uses").is_some();
+                let has_run = step.get("run").is_some();
+                if has_uses == has_run {
+                    errors.push(LintError::new(
+                        line,
+                        format!(
+                            "job '{}' step {} must define either 'uses' or 'run'",
+                            job_id,
+                            i + 1
+                        ),
+                    ));
+                }
+            }
+        }
+        _ => errors.push(LintError::new(
+            line,
+            format!("job '{}' must have a non-empty list of 'steps'", job_id),
+        )),
+    }
+}
+
+/// YAML values do not keep track of their position, so look for the line in
+/// the raw content where `key` is defined, starting at `from_line`.
+fn key_line(content: &str, key: &str, from_line: usize) -> Option<usize> {
+    let definition = format!("{}:", key);
+    content
+        .lines()
+        .enumerate()
+        .skip(from_line.saturating_sub(1))
+        .find(|(_, line)| line.trim_start().starts_with(&definition))
+        .map(|(i, _)| i + 1)
+}
+
+pub struct GithubJobFields {
+    id: i64,
+    name: String,
+    status: String,
+    web_url: String,
+    created_at: String,
+    duration: Option<u64>,
+}
+
+impl From<&serde_json::Value> for GithubJobFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubJobFields {
+            id: data["id"].as_i64().unwrap(),
+            name: data["name"].as_str().unwrap_or_default().to_string(),
+            // Same as pipelines, conclusion is the final state of the job.
+            status: data["conclusion"]
+                .as_str()
+                .or_else(|| data["status"].as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            web_url: data["html_url"].as_str().unwrap_or_default().to_string(),
+            created_at: data["created_at"].as_str().unwrap_or_default().to_string(),
+            duration: match (data["started_at"].as_str(), data["completed_at"].as_str()) {
+                (Some(started_at), Some(completed_at)) => {
+                    Some(time::compute_duration(started_at, completed_at))
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+impl From<GithubJobFields> for Job {
+    fn from(fields: GithubJobFields) -> Self {
+        Job::builder()
+            .id(fields.id)
+            .name(fields.name)
+            .status(fields.status)
+            .web_url(fields.web_url)
+            .created_at(fields.created_at)
+            .duration(fields.duration)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GithubArtifactFields {
+    id: i64,
+    name: String,
+    size: u64,
+    created_at: String,
+    web_url: String,
+    expired: bool,
+}
+
+impl From<&serde_json::Value> for GithubArtifactFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubArtifactFields {
+            id: data["id"].as_i64().unwrap(),
+            name: data["name"].as_str().unwrap_or_default().to_string(),
+            size: data["size_in_bytes"].as_u64().unwrap_or_default(),
+            created_at: data["created_at"].as_str().unwrap().to_string(),
+            web_url: data["url"].as_str().unwrap_or_default().to_string(),
+            expired: data["expired"].as_bool().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GithubArtifactFields> for Artifact {
+    fn from(fields: GithubArtifactFields) -> Self {
+        Artifact::builder()
+            .id(fields.id)
+            .name(fields.name)
+            .size(fields.size)
+            .created_at(fields.created_at)
+            .web_url(fields.web_url)
+            .expired(fields.expired)
+            .build()
+            .unwrap()
+    }
 }
 
 pub struct GithubPipelineFields {
+    id: i64,
     status: String,
     web_url: String,
     branch: String,
@@ -66,6 +455,7 @@ pub struct GithubPipelineFields {
 impl From<&serde_json::Value> for GithubPipelineFields {
     fn from(pipeline_data: &serde_json::Value) -> Self {
         GithubPipelineFields {
+            id: pipeline_data["id"].as_i64().unwrap_or_default(),
             // Github has `conclusion` as the final
             // state of the pipeline. It also has a
             // `status` field to represent the current
@@ -97,6 +487,7 @@ impl From<&serde_json::Value> for GithubPipelineFields {
 impl From<GithubPipelineFields> for Pipeline {
     fn from(fields: GithubPipelineFields) -> Self {
         Pipeline::builder()
+            .id(fields.id)
             .status(fields.status)
             .web_url(fields.web_url)
             .branch(fields.branch)
@@ -125,6 +516,70 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_get_workflow_run() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "get_pipeline.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
+        let pipeline = github.get_pipeline(7881917826).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs/7881917826",
+            *client.url()
+        );
+        assert_eq!(7881917826, pipeline.id);
+        assert_eq!("success", pipeline.status);
+    }
+
+    #[test]
+    fn test_rerun_workflow_run() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let run = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "get_pipeline.json"))
+            .build()
+            .unwrap();
+        let rerun = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![rerun, run]));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
+        let pipeline = github.retry(7881917826).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs/7881917826/rerun",
+            *client.url()
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!("queued", pipeline.status);
+    }
+
+    #[test]
+    fn test_cancel_workflow_run() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let run = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "get_pipeline.json"))
+            .build()
+            .unwrap();
+        let cancel = Response::builder().status(202).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![cancel, run]));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
+        let pipeline = github.cancel(7881917826).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs/7881917826/cancel",
+            *client.url()
+        );
+        assert_eq!("cancelled", pipeline.status);
+    }
+
     #[test]
     fn test_list_actions() {
         let config = config();
@@ -136,7 +591,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = PipelineBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -148,6 +603,7 @@ mod test {
         );
         assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
         assert_eq!(1, runs.len());
+        assert_eq!(7881917826, runs[0].id);
     }
 
     #[test]
@@ -157,7 +613,7 @@ mod test {
         let path = "jordilin/githapi";
         let response = Response::builder().status(401).build().unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = PipelineBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -172,7 +628,7 @@ mod test {
         let path = "jordilin/githapi";
         let response = Response::builder().status(302).build().unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = PipelineBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -197,7 +653,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = PipelineBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -216,7 +672,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = PipelineBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -244,8 +700,8 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
-        assert_eq!(Some(1), github.num_pages().unwrap());
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
+        assert_eq!(Some(PageCount::from(1)), github.num_pages().unwrap());
         assert_eq!(
             "https://api.github.com/repos/jordilin/githapi/actions/runs?page=1",
             *client.url(),
@@ -260,8 +716,8 @@ mod test {
         let path = "jordilin/githapi";
         let response = Response::builder().status(200).build().unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
-        assert_eq!(Some(1), github.num_pages().unwrap());
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
+        assert_eq!(Some(PageCount::from(1)), github.num_pages().unwrap());
     }
 
     #[test]
@@ -275,7 +731,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = PipelineBodyArgs::builder()
             .from_to_page(Some(
                 ListBodyArgs::builder()
@@ -311,7 +767,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = PipelineBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -337,7 +793,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Cicd> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = PipelineBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -345,4 +801,199 @@ mod test {
         let runs = github.list(args).unwrap();
         assert_eq!("unknown", runs[0].status);
     }
+
+    #[test]
+    fn test_list_artifacts_skips_expired() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "list_artifacts.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn CicdArtifact> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = ArtifactListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let artifacts = github.list(args).unwrap();
+        assert_eq!(1, artifacts.len());
+        assert_eq!(1287371466, artifacts[0].id);
+        assert_eq!("build-output", artifacts[0].name);
+        assert_eq!(5242880, artifacts[0].size);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/artifacts",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_delete_artifact() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder().status(204).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn CicdArtifact> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        github.delete(1287371466).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/artifacts/1287371466",
+            *client.url(),
+        );
+        assert_eq!(http::Method::DELETE, *client.http_method.borrow());
+        assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_rerun_job() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let get_job = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "get_job.json"))
+            .build()
+            .unwrap();
+        let rerun = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![rerun, get_job]));
+        let github: Box<dyn CicdJob> = Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = JobRetryBodyArgs::builder().id(22217842321).build().unwrap();
+        let job = github.retry(args).unwrap();
+        assert_eq!(22217842321, job.id);
+        assert_eq!("build", job.name);
+        assert_eq!("queued", job.status);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/jobs/22217842321/rerun",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_list_workflow_run_jobs() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let body = format!(
+            r#"{{"total_count": 1, "jobs": [{}]}}"#,
+            get_contract(ContractType::Github, "get_job.json")
+        );
+        let response = Response::builder().status(200).body(body).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn CicdJob> = Box::new(Github::new(config, &domain, path, client.clone()));
+        let jobs = github.list(8108594103).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs/8108594103/jobs?filter=latest",
+            *client.url(),
+        );
+        assert_eq!(1, jobs.len());
+        assert_eq!("failure", jobs[0].status);
+        assert_eq!(Some(32), jobs[0].duration);
+    }
+
+    #[test]
+    fn test_rerun_job_with_variables_not_supported() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let client = Arc::new(MockRunner::new(vec![]));
+        let github: Box<dyn CicdJob> = Box::new(Github::new(config, &domain, path, client));
+        let args = JobRetryBodyArgs::builder()
+            .id(22217842321)
+            .variables(vec![("DEBUG".to_string(), "true".to_string())])
+            .build()
+            .unwrap();
+        let err = github.retry(args).unwrap_err();
+        match err.downcast_ref::<error::GRError>() {
+            Some(error::GRError::OperationNotSupported(_)) => (),
+            _ => panic!("Expected OperationNotSupported error"),
+        }
+    }
+
+    #[test]
+    fn test_lint_valid_workflow() {
+        let workflow = "name: CI\n\
+            on: [push]\n\
+            jobs:\n  \
+              build:\n    \
+                runs-on: ubuntu-latest\n    \
+                steps:\n      \
+                  - uses: actions/checkout@v4\n      \
+                  - run: cargo test\n  \
+              release:\n    \
+                uses: org/repo/.github/workflows/release.yml@main\n";
+        let response = lint_workflow(workflow);
+        assert!(response.valid);
+        assert!(response.errors.is_empty());
+    }
+
+    #[test]
+    fn test_lint_workflow_yaml_syntax_error_has_line() {
+        let workflow = "on: [push]\njobs:\n  build:\n    runs-on: [ubuntu\n";
+        let response = lint_workflow(workflow);
+        assert!(!response.valid);
+        assert_eq!(1, response.errors.len());
+        assert!(response.errors[0].line.is_some());
+    }
+
+    #[test]
+    fn test_lint_workflow_schema_errors_point_to_job_line() {
+        let workflow = "name: CI\n\
+            trigger: push\n\
+            jobs:\n  \
+              build:\n    \
+                steps:\n      \
+                  - name: nothing to do\n";
+        let response = lint_workflow(workflow);
+        assert!(!response.valid);
+        assert_eq!(
+            vec![
+                LintError::new(Some(2), "unexpected key 'trigger'"),
+                LintError::new(None, "missing required key 'on'"),
+                LintError::new(Some(4), "job 'build' is missing required key 'runs-on'"),
+                LintError::new(
+                    Some(4),
+                    "job 'build' step 1 must define either 'uses' or 'run'"
+                ),
+            ],
+            response.errors
+        );
+    }
+
+    #[test]
+    fn test_lint_workflow_does_not_call_remote() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let client = Arc::new(MockRunner::new(vec![]));
+        let github: Box<dyn CicdLinter> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = LintBodyArgs::builder()
+            .content("on: push\njobs: {}\n".to_string())
+            .build()
+            .unwrap();
+        let response = github.lint(args).unwrap();
+        assert!(!response.valid);
+        assert_eq!(None, *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_merged_config_not_supported() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let client = Arc::new(MockRunner::new(vec![]));
+        let github: Box<dyn CicdLinter> = Box::new(Github::new(config, &domain, path, client));
+        let err = github.merged_config("main").unwrap_err();
+        match err.downcast_ref::<error::GRError>() {
+            Some(error::GRError::OperationNotSupported(_)) => (),
+            _ => panic!("Expected OperationNotSupported error"),
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{
     api_traits::ContainerRegistry,
     cmds::docker::{DockerListBodyArgs, ImageMetadata, RegistryRepository, RepositoryTag},
-    io::{HttpRunner, Response},
+    io::{HttpRunner, PageCount, Response},
     Result,
 };
 
@@ -16,11 +16,11 @@ impl<R: HttpRunner<Response = Response>> ContainerRegistry for Github<R> {
         todo!()
     }
 
-    fn num_pages_repository_tags(&self, _repository_id: i64) -> Result<Option<u32>> {
+    fn num_pages_repository_tags(&self, _repository_id: i64) -> Result<Option<PageCount>> {
         todo!()
     }
 
-    fn num_pages_repositories(&self) -> Result<Option<u32>> {
+    fn num_pages_repositories(&self) -> Result<Option<PageCount>> {
         todo!()
     }
 
@@ -0,0 +1,53 @@
+use super::Github;
+use crate::api_traits::{ApiOperation, RemoteContract};
+use crate::http;
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::Result;
+
+impl<R: HttpRunner<Response = Response>> RemoteContract for Github<R> {
+    fn fetch(&self, endpoint: &str) -> Result<serde_json::Value> {
+        let url = format!(
+            "{}/{}",
+            self.rest_api_basepath,
+            endpoint.trim_start_matches('/')
+        );
+        query::fetch_contract::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use super::*;
+
+    #[test]
+    fn test_fetch_contract_from_api_base_path() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "project.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn RemoteContract> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        github.fetch("repos/jordilin/githapi").unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi",
+            *client.url()
+        );
+    }
+}
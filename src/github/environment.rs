@@ -0,0 +1,448 @@
+use super::Github;
+use crate::api_traits::{ApiOperation, Deployment, DeploymentApproval};
+use crate::cmds::environment::{
+    DeploymentApprovalListBodyArgs, DeploymentApproveBodyArgs, Environment,
+    EnvironmentListBodyArgs, PendingDeployment,
+};
+use crate::error::GRError;
+use crate::http::{self, Body};
+use crate::io::{HttpRunner, Response};
+use crate::remote::{encode_query_param, query};
+use crate::Result;
+
+impl<R: HttpRunner<Response = Response>> Deployment for Github<R> {
+    fn list(&self, args: EnvironmentListBodyArgs) -> Result<Vec<Environment>> {
+        // Doc:
+        // https://docs.github.com/en/rest/deployments/environments?apiVersion=2022-11-28#list-environments
+        let url = format!(
+            "{}/repos/{}/environments",
+            self.rest_api_basepath, self.path
+        );
+        let environments = query::github_list_environments(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.request_headers(),
+            Some("environments"),
+            ApiOperation::Release,
+        )?;
+        environments
+            .into_iter()
+            .map(|environment| self.with_last_deployment(environment))
+            .collect()
+    }
+
+    fn get(&self, name: &str) -> Result<Environment> {
+        // Doc:
+        // https://docs.github.com/en/rest/deployments/environments?apiVersion=2022-11-28#get-an-environment
+        let url = format!(
+            "{}/repos/{}/environments/{}",
+            self.rest_api_basepath,
+            self.path,
+            encode_query_param(name)
+        );
+        let environment = query::github_environment::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Release,
+        )?;
+        self.with_last_deployment(environment)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    /// Fills in the commit, status and URL of the last deployment to the
+    /// environment, which Github keeps apart from the environment itself.
+    fn with_last_deployment(&self, mut environment: Environment) -> Result<Environment> {
+        // Doc:
+        // https://docs.github.com/en/rest/deployments/deployments?apiVersion=2022-11-28#list-deployments
+        let url = format!(
+            "{}/repos/{}/deployments?environment={}&per_page=1",
+            self.rest_api_basepath,
+            self.path,
+            encode_query_param(&environment.name)
+        );
+        let deployments = self.deployments(&url)?;
+        let deployment = &deployments[0];
+        let Some(id) = deployment["id"].as_i64() else {
+            return Ok(environment);
+        };
+        environment.sha = deployment["sha"].as_str().unwrap_or_default().to_string();
+        environment.deployed_at = deployment["created_at"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        // Doc:
+        // https://docs.github.com/en/rest/deployments/statuses?apiVersion=2022-11-28#list-deployment-statuses
+        let url = format!(
+            "{}/repos/{}/deployments/{}/statuses?per_page=1",
+            self.rest_api_basepath, self.path, id
+        );
+        let statuses = self.deployments(&url)?;
+        let status = &statuses[0];
+        environment.status = status["state"].as_str().unwrap_or_default().to_string();
+        if let Some(url) = status["environment_url"]
+            .as_str()
+            .filter(|url| !url.is_empty())
+        {
+            environment.url = url.to_string();
+        }
+        Ok(environment)
+    }
+
+    fn deployments(&self, url: &str) -> Result<serde_json::Value> {
+        query::github_deployments::<_, ()>(
+            &self.runner,
+            url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Release,
+        )
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> DeploymentApproval for Github<R> {
+    fn list(&self, args: DeploymentApprovalListBodyArgs) -> Result<Vec<PendingDeployment>> {
+        // Doc:
+        // https://docs.github.com/en/rest/actions/workflow-runs?apiVersion=2022-11-28#list-workflow-runs-for-a-repository
+        let url = format!(
+            "{}/repos/{}/actions/runs?status=waiting",
+            self.rest_api_basepath, self.path
+        );
+        let runs = query::github_list_waiting_runs(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.request_headers(),
+            Some("workflow_runs"),
+            ApiOperation::Release,
+        )?;
+        // A waiting run does not tell which environments it is waiting on.
+        runs.into_iter()
+            .map(|mut run| {
+                let environments = self.pending_deployments(run.id)?;
+                run.environment = environments
+                    .iter()
+                    .map(|(_, name)| name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ");
+                Ok(run)
+            })
+            .collect()
+    }
+
+    fn approve(&self, args: DeploymentApproveBodyArgs) -> Result<()> {
+        let environment_ids = self
+            .pending_deployments(args.id)?
+            .into_iter()
+            .map(|(id, _)| serde_json::Value::from(id))
+            .collect::<Vec<serde_json::Value>>();
+        if environment_ids.is_empty() {
+            return Err(GRError::PreconditionNotMet(format!(
+                "Workflow run {} is not waiting for any approval",
+                args.id
+            ))
+            .into());
+        }
+        // Doc:
+        // https://docs.github.com/en/rest/actions/workflow-runs?apiVersion=2022-11-28#review-pending-deployments-for-a-workflow-run
+        let url = format!(
+            "{}/repos/{}/actions/runs/{}/pending_deployments",
+            self.rest_api_basepath, self.path, args.id
+        );
+        let mut body = Body::new();
+        body.add("environment_ids", serde_json::Value::Array(environment_ids));
+        body.add("state", serde_json::Value::from("approved"));
+        body.add("comment", serde_json::Value::from(args.comment));
+        query::approve_deployment(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            http::Method::POST,
+            ApiOperation::Release,
+        )?;
+        Ok(())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    /// Environments (id, name) the workflow run is waiting on.
+    fn pending_deployments(&self, run_id: i64) -> Result<Vec<(i64, String)>> {
+        // Doc:
+        // https://docs.github.com/en/rest/actions/workflow-runs?apiVersion=2022-11-28#get-pending-deployments-for-a-workflow-run
+        let url = format!(
+            "{}/repos/{}/actions/runs/{}/pending_deployments",
+            self.rest_api_basepath, self.path, run_id
+        );
+        let pending = query::github_pending_deployments::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Release,
+        )?;
+        let pending = pending.as_array().ok_or_else(|| {
+            GRError::RemoteUnexpectedResponseContract(format!(
+                "Expected an array of pending deployments but got: {}",
+                pending
+            ))
+        })?;
+        Ok(pending
+            .iter()
+            .map(|deployment| {
+                (
+                    deployment["environment"]["id"].as_i64().unwrap_or_default(),
+                    deployment["environment"]["name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+            })
+            .collect())
+    }
+}
+
+pub struct GithubEnvironmentFields {
+    id: i64,
+    name: String,
+    html_url: String,
+    created_at: String,
+}
+
+impl From<&serde_json::Value> for GithubEnvironmentFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubEnvironmentFields {
+            id: data["id"].as_i64().unwrap(),
+            name: data["name"].as_str().unwrap().to_string(),
+            html_url: data["html_url"].as_str().unwrap_or_default().to_string(),
+            created_at: data["created_at"].as_str().unwrap().to_string(),
+        }
+    }
+}
+
+impl From<GithubEnvironmentFields> for Environment {
+    fn from(fields: GithubEnvironmentFields) -> Self {
+        // The URL of the environment page, replaced by the one set by the last
+        // deployment if any.
+        Environment::builder()
+            .id(fields.id)
+            .name(fields.name)
+            .url(fields.html_url)
+            .created_at(fields.created_at)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GithubPendingDeploymentFields {
+    id: i64,
+    branch: String,
+    created_at: String,
+    web_url: String,
+}
+
+impl From<&serde_json::Value> for GithubPendingDeploymentFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubPendingDeploymentFields {
+            id: data["id"].as_i64().unwrap(),
+            branch: data["head_branch"].as_str().unwrap_or_default().to_string(),
+            created_at: data["created_at"].as_str().unwrap().to_string(),
+            web_url: data["html_url"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GithubPendingDeploymentFields> for PendingDeployment {
+    fn from(fields: GithubPendingDeploymentFields) -> Self {
+        PendingDeployment::builder()
+            .id(fields.id)
+            .environment(String::new())
+            .branch(fields.branch)
+            .created_at(fields.created_at)
+            .web_url(fields.web_url)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use super::*;
+
+    fn contract_response(name: &str) -> Response {
+        Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, name))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_list_environments_with_last_deployment() {
+        let client = Arc::new(MockRunner::new(vec![
+            contract_response("list_deployment_statuses.json"),
+            contract_response("list_deployments.json"),
+            contract_response("list_environments.json"),
+        ]));
+        let github: Box<dyn Deployment> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let args = EnvironmentListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let environments = github.list(args).unwrap();
+        assert_eq!(1, environments.len());
+        let environment = &environments[0];
+        assert_eq!("production", environment.name);
+        assert_eq!("aa8b3b2c1f8a3d1d2e0e4a6b1c2d3e4f5a6b7c8d", environment.sha);
+        assert_eq!("success", environment.status);
+        assert_eq!("https://example.com", environment.url);
+        assert_eq!("2024-03-04T08:15:10Z", environment.deployed_at);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/deployments/1453920127/statuses?per_page=1",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_get_environment_never_deployed() {
+        let environment = Response::builder()
+            .status(200)
+            .body(
+                r#"{"id": 161088068, "name": "staging", "created_at": "2024-01-10T08:00:00Z",
+                    "html_url": "https://github.com/jordilin/githapi/deployments/activity_log?environments_filter=staging"}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let deployments = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![deployments, environment]));
+        let github: Box<dyn Deployment> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let environment = github.get("staging").unwrap();
+        assert_eq!("staging", environment.name);
+        assert_eq!("", environment.sha);
+        assert_eq!("", environment.status);
+        assert_eq!(
+            "https://github.com/jordilin/githapi/deployments/activity_log?environments_filter=staging",
+            environment.url
+        );
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/deployments?environment=staging&per_page=1",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_waiting_runs_with_environments() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let runs = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "list_pipelines.json"))
+            .build()
+            .unwrap();
+        let pending = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Github,
+                "list_pending_deployments.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![pending, runs]));
+        let github: Box<dyn DeploymentApproval> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = DeploymentApprovalListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let deployments = github.list(args).unwrap();
+        assert_eq!(1, deployments.len());
+        assert_eq!(7881917826, deployments[0].id);
+        assert_eq!("production, production-eu", deployments[0].environment);
+        assert_eq!("main", deployments[0].branch);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs/7881917826/pending_deployments",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Release), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_approve_waiting_run() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let pending = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Github,
+                "list_pending_deployments.json",
+            ))
+            .build()
+            .unwrap();
+        let approved = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![approved, pending]));
+        let github: Box<dyn DeploymentApproval> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = DeploymentApproveBodyArgs::builder()
+            .id(7881917826)
+            .build()
+            .unwrap();
+        github.approve(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs/7881917826/pending_deployments",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_approve_run_not_waiting_is_error() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let pending = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![pending]));
+        let github: Box<dyn DeploymentApproval> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = DeploymentApproveBodyArgs::builder()
+            .id(7881917826)
+            .build()
+            .unwrap();
+        assert!(github.approve(args).is_err());
+        assert_eq!(http::Method::GET, *client.http_method.borrow());
+    }
+}
@@ -0,0 +1,264 @@
+use serde_json::json;
+
+use crate::{
+    api_traits::{ApiOperation, Issue},
+    cmds::issue::{IssueBodyArgs, IssueListBodyArgs, IssueResponse, IssueState},
+    http::{self, Body},
+    io::{HttpRunner, PageCount, Response},
+    remote::query,
+    Result,
+};
+
+use super::Github;
+
+// https://docs.github.com/en/rest/issues/issues?apiVersion=2022-11-28
+
+impl<R> Github<R> {
+    fn list_issues_url(&self, args: &IssueListBodyArgs, num_pages: bool) -> String {
+        let state = match args.state {
+            IssueState::Opened => "open",
+            IssueState::Closed => "closed",
+        };
+        let mut url = format!(
+            "{}/repos/{}/issues?state={}",
+            self.rest_api_basepath, self.path, state
+        );
+        if num_pages {
+            url.push_str("&page=1");
+        }
+        url
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Issue for Github<R> {
+    fn open(&self, args: IssueBodyArgs) -> Result<IssueResponse> {
+        let url = format!("{}/repos/{}/issues", self.rest_api_basepath, self.path);
+        let mut body = Body::new();
+        body.add("title", json!(args.title));
+        body.add("body", json!(args.description));
+        if !args.labels.is_empty() {
+            body.add("labels", json!(args.labels));
+        }
+        query::github_issue(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            http::Method::POST,
+            ApiOperation::Issue,
+        )
+    }
+
+    fn list(&self, args: IssueListBodyArgs) -> Result<Vec<IssueResponse>> {
+        let url = self.list_issues_url(&args, false);
+        let issues = query::github_list_issues(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.request_headers(),
+            None,
+            ApiOperation::Issue,
+        )?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| !issue.pull_request)
+            .collect())
+    }
+
+    fn get(&self, id: i64) -> Result<IssueResponse> {
+        let url = format!(
+            "{}/repos/{}/issues/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        query::github_issue::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Issue,
+        )
+    }
+
+    fn close(&self, id: i64) -> Result<IssueResponse> {
+        let url = format!(
+            "{}/repos/{}/issues/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        let mut body = Body::new();
+        body.add("state", "closed");
+        query::github_issue(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            http::Method::PATCH,
+            ApiOperation::Issue,
+        )
+    }
+
+    fn comment(&self, id: i64, comment: &str) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/issues/{}/comments",
+            self.rest_api_basepath, self.path, id
+        );
+        let mut body = Body::new();
+        body.add("body", comment);
+        query::create_issue_comment(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            http::Method::POST,
+            ApiOperation::Issue,
+        )?;
+        Ok(())
+    }
+
+    fn num_pages(&self, args: IssueListBodyArgs) -> Result<Option<PageCount>> {
+        let url = self.list_issues_url(&args, true);
+        query::num_pages(
+            &self.runner,
+            &url,
+            self.request_headers(),
+            ApiOperation::Issue,
+        )
+    }
+}
+
+pub struct GithubIssueFields {
+    issue: IssueResponse,
+}
+
+impl From<&serde_json::Value> for GithubIssueFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubIssueFields {
+            issue: IssueResponse::builder()
+                .id(data["number"].as_i64().unwrap_or_default())
+                .title(data["title"].as_str().unwrap_or_default().to_string())
+                .description(data["body"].as_str().unwrap_or_default().to_string())
+                .author(
+                    data["user"]["login"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .state(data["state"].as_str().unwrap_or_default().to_string())
+                .web_url(data["html_url"].as_str().unwrap_or_default().to_string())
+                .labels(
+                    data["labels"]
+                        .as_array()
+                        .map(|labels| {
+                            labels
+                                .iter()
+                                .filter_map(|label| label["name"].as_str().map(String::from))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                )
+                .created_at(data["created_at"].as_str().unwrap_or_default().to_string())
+                .updated_at(data["updated_at"].as_str().unwrap_or_default().to_string())
+                .pull_request(data.get("pull_request").is_some())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GithubIssueFields> for IssueResponse {
+    fn from(fields: GithubIssueFields) -> Self {
+        fields.issue
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use super::*;
+
+    fn github(responses: Vec<Response>) -> (Arc<MockRunner>, Box<dyn Issue>) {
+        let client = Arc::new(MockRunner::new(responses));
+        let github: Box<dyn Issue> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        (client, github)
+    }
+
+    fn issue_response(status: i32) -> Response {
+        Response::builder()
+            .status(status)
+            .body(get_contract(ContractType::Github, "issue.json"))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_open_issue_sends_labels_as_array() {
+        let (client, github) = github(vec![issue_response(201)]);
+        let args = IssueBodyArgs::builder()
+            .title("Crash on startup".to_string())
+            .labels(vec!["bug".to_string(), "p1".to_string()])
+            .build()
+            .unwrap();
+        let issue = github.open(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/issues",
+            *client.url()
+        );
+        assert!(client.request_body().contains(r#""labels":["bug","p1"]"#));
+        assert_eq!(24, issue.id);
+        assert_eq!(vec!["bug".to_string()], issue.labels);
+    }
+
+    #[test]
+    fn test_list_issues_leaves_out_pull_requests() {
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "list_issues_user.json"))
+            .build()
+            .unwrap();
+        let (client, github) = github(vec![response]);
+        let args = IssueListBodyArgs::builder()
+            .state(IssueState::Closed)
+            .list_args(None)
+            .build()
+            .unwrap();
+        let issues = github.list(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/issues?state=closed",
+            *client.url()
+        );
+        assert_eq!(Some(ApiOperation::Issue), *client.api_operation.borrow());
+        assert_eq!(1, issues.len());
+        assert_eq!(24, issues[0].id);
+    }
+
+    #[test]
+    fn test_close_issue() {
+        let (client, github) = github(vec![issue_response(200)]);
+        github.close(24).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/issues/24",
+            *client.url()
+        );
+        assert_eq!(http::Method::PATCH, *client.http_method.borrow());
+        assert!(client.request_body().contains(r#""state":"closed""#));
+    }
+
+    #[test]
+    fn test_comment_issue() {
+        let response = Response::builder().status(201).build().unwrap();
+        let (client, github) = github(vec![response]);
+        github.comment(24, "Cannot reproduce").unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/issues/24/comments",
+            *client.url()
+        );
+    }
+}
@@ -1,17 +1,27 @@
 use super::Github;
 use crate::{
-    api_traits::{ApiOperation, CommentMergeRequest, MergeRequest, RemoteProject},
+    api_defaults::{GITHUB_PULL_REQUEST_BODY_MAX_CHARS, GITHUB_PULL_REQUEST_TITLE_MAX_CHARS},
+    api_traits::{
+        ApiOperation, CommentMergeRequest, FailingPipelines, MergeQueue, MergeRequest,
+        MergeRequestApprovals, MergeRequestChecks, MergeRequestDiff, MergeRequestLabels,
+        MergeRequestRebase, MergeRequestReviewDiff, RemoteProject, Timestamp,
+    },
     cli::browse::BrowseOptions,
-    cmds::merge_request::CommentMergeRequestBodyArgs,
+    cmds::merge_request::{
+        ApprovalStatus, CheckState, CommentMergeRequestBodyArgs, CommentThread, MergeQueueEntry,
+        MergeQueueListBodyArgs, MergeRequestCheck, MergeRequestComment, RebaseProgress,
+        ReviewedHead,
+    },
+    cmds::my::dashboard::FailingPipeline,
     http::{
         Body,
-        Method::{GET, PATCH, POST, PUT},
+        Method::{DELETE, GET, PATCH, POST, PUT},
     },
-    io::{HttpRunner, Response},
+    io::{HttpRunner, PageCount, Response},
     json_loads,
     remote::{
-        query, MergeRequestBodyArgs, MergeRequestListBodyArgs, MergeRequestResponse,
-        MergeRequestState,
+        encode_query_param, query, Member, MergeRequestBodyArgs, MergeRequestLimits,
+        MergeRequestListBodyArgs, MergeRequestResponse, MergeRequestState,
     },
 };
 
@@ -25,9 +35,52 @@ impl<R> Github<R> {
             // pull request is considered closed.
             MergeRequestState::Closed | MergeRequestState::Merged => "closed".to_string(),
         };
+        let mut qualifiers = args
+            .author
+            .as_ref()
+            .map(|author| format!("+author:{}", author))
+            .unwrap_or_default();
+        if let Some(milestone) = &args.milestone {
+            let milestone = format!("\"{}\"", milestone.title);
+            qualifiers.push_str(&format!("+milestone:{}", encode_query_param(&milestone)));
+        }
+        if let Some(draft) = args.draft {
+            qualifiers.push_str(&format!("+draft:{}", draft));
+        }
+        if args.passing_ci {
+            qualifiers.push_str("+status:success");
+        }
+        if let Some(reviewer) = &args.reviewer {
+            if args.in_project {
+                qualifiers.push_str(&format!("+repo:{}", self.path));
+            }
+            // https://docs.github.com/en/rest/search/search?apiVersion=2022-11-28#search-issues-and-pull-requests
+            return format!(
+                "{}/search/issues?q=is:pr+state:{}+review-requested:{}{}",
+                self.rest_api_basepath, state, reviewer.username, qualifiers
+            );
+        }
         if args.assignee_id.is_some() {
+            // The author is filtered out once the issues are retrieved. Other
+            // filters are rejected by `check_assigned_filters`.
             return format!("{}/issues?state={}", self.rest_api_basepath, state);
         }
+        if searches(args) {
+            // The pulls endpoint cannot filter by author, draft nor CI
+            // status. Search does.
+            return format!(
+                "{}/search/issues?q=is:pr+repo:{}+state:{}{}",
+                self.rest_api_basepath, self.path, state, qualifiers
+            );
+        }
+        if let Some(milestone) = &args.milestone {
+            // Neither can it filter by milestone, but the issues endpoint can.
+            // Pull requests are told apart once the issues are retrieved.
+            return format!(
+                "{}/repos/{}/issues?state={}&milestone={}",
+                self.rest_api_basepath, self.path, state, milestone.id
+            );
+        }
         format!(
             "{}/repos/{}/pulls?state={}",
             self.rest_api_basepath, self.path, state
@@ -35,10 +88,53 @@ impl<R> Github<R> {
     }
 }
 
+/// Whether pull requests are listed through the issues endpoints, which
+/// return issues too.
+fn lists_issues(args: &MergeRequestListBodyArgs) -> bool {
+    args.assignee_id.is_some() || (args.milestone.is_some() && !searches(args))
+}
+
+/// Pull requests assigned to the user come from the issues of the user, which
+/// cannot be filtered by milestone, draft nor CI status.
+fn check_assigned_filters(args: &MergeRequestListBodyArgs) -> Result<()> {
+    if args.assignee_id.is_some()
+        && args.reviewer.is_none()
+        && (args.milestone.is_some() || args.draft.is_some() || args.passing_ci)
+    {
+        return Err(error::GRError::OperationNotSupported(
+            "Github cannot filter the pull requests assigned to you by milestone, \
+             draft or CI status"
+                .to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Whether pull requests are listed through the search endpoint, which wraps
+/// them in an object under the items key.
+fn searches(args: &MergeRequestListBodyArgs) -> bool {
+    args.reviewer.is_some()
+        || (args.assignee_id.is_none()
+            && (args.author.is_some() || args.draft.is_some() || args.passing_ci))
+}
+
 impl<R: HttpRunner<Response = Response>> MergeRequest for Github<R> {
     fn open(&self, args: MergeRequestBodyArgs) -> Result<MergeRequestResponse> {
+        // Pull requests from forks are opened in the upstream repository, with
+        // the head branch qualified by the fork's owner.
+        let (repo, head) = match &args.target_repo {
+            Some(target_repo) => {
+                let owner = self.path.split('/').next().unwrap_or_default();
+                (
+                    target_repo.clone(),
+                    format!("{}:{}", owner, args.source_branch),
+                )
+            }
+            None => (self.path.clone(), args.source_branch.clone()),
+        };
         let mut body = Body::new();
-        body.add("head", args.source_branch.clone());
+        body.add("head", head);
         body.add("base", args.target_branch);
         body.add("title", args.title);
         body.add("body", args.description);
@@ -47,7 +143,7 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Github<R> {
         if args.draft {
             body.add("draft", args.draft.to_string());
         }
-        let mr_url = format!("{}/repos/{}/pulls", self.rest_api_basepath, self.path);
+        let mr_url = format!("{}/repos/{}/pulls", self.rest_api_basepath, repo);
         match query::github_merge_request_response(
             &self.runner,
             &mr_url,
@@ -72,21 +168,49 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Github<R> {
                         // https://docs.github.com/en/rest/issues/issues#update-an-issue
                         let merge_request_json = json_loads(&body)?;
                         let id = merge_request_json["number"].to_string();
-                        let issues_url = format!(
-                            "{}/repos/{}/issues/{}",
-                            self.rest_api_basepath, self.path, id
-                        );
-                        let mut body = Body::new();
-                        let assignees = vec![args.username.as_str()];
-                        body.add("assignees", &assignees);
-                        query::github_merge_request::<_, &Vec<&str>>(
+                        let issues_url =
+                            format!("{}/repos/{}/issues/{}", self.rest_api_basepath, repo, id);
+                        let mut body: Body<serde_json::Value> = Body::new();
+                        if !args.assignee_usernames.is_empty() {
+                            body.add("assignees", args.assignee_usernames.clone().into());
+                        }
+                        // Labels and milestone are also set through the issues
+                        // API.
+                        if !args.labels.is_empty() {
+                            body.add("labels", args.labels.clone().into());
+                        }
+                        if let Some(milestone_id) = args.milestone_id {
+                            body.add("milestone", milestone_id.into());
+                        }
+                        let merge_request = query::github_merge_request::<_, serde_json::Value>(
                             &self.runner,
                             &issues_url,
                             Some(body),
                             self.request_headers(),
                             PATCH,
                             ApiOperation::MergeRequest,
-                        )
+                        )?;
+                        // Reviewers are requested on the pull request itself.
+                        // https://docs.github.com/en/rest/pulls/review-requests#request-reviewers-for-a-pull-request
+                        if !args.reviewer_usernames.is_empty() {
+                            let reviewers_url = format!(
+                                "{}/repos/{}/pulls/{}/requested_reviewers",
+                                self.rest_api_basepath, repo, id
+                            );
+                            let reviewers: Vec<&str> =
+                                args.reviewer_usernames.iter().map(|r| r.as_str()).collect();
+                            let mut body = Body::new();
+                            body.add("reviewers", &reviewers);
+                            query::github_merge_request::<_, &Vec<&str>>(
+                                &self.runner,
+                                &reviewers_url,
+                                Some(body),
+                                self.request_headers(),
+                                POST,
+                                ApiOperation::MergeRequest,
+                            )?;
+                        }
+                        Ok(merge_request)
                     }
                     422 => {
                         // There is an existing pull request already.
@@ -148,17 +272,21 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Github<R> {
     }
 
     fn list(&self, args: MergeRequestListBodyArgs) -> Result<Vec<MergeRequestResponse>> {
+        check_assigned_filters(&args)?;
         let url = self.url_list_merge_requests(&args);
+        let from_issues = lists_issues(&args);
+        let iter_over_sub_array = searches(&args).then_some("items");
         let response = query::github_list_merge_requests(
             &self.runner,
             &url,
             args.list_args,
             self.request_headers(),
-            None,
+            iter_over_sub_array,
             ApiOperation::MergeRequest,
         );
-        if args.assignee_id.is_some() {
-            // Pull requests for the current authenticated user.
+        if from_issues {
+            // Pull requests for the current authenticated user or in a
+            // milestone.
             // Filter those reponses that have pull_request not empty See ref:
             // https://docs.github.com/en/rest/issues/issues?apiVersion=2022-11-28#list-issues-assigned-to-the-authenticated-user
             // Quoting Github's docs: Note: GitHub's REST API considers every
@@ -170,7 +298,11 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Github<R> {
             // request id, use the "List pull requests" endpoint.
             let mut merge_requests = vec![];
             for mr in response? {
-                if !mr.pull_request.is_empty() {
+                let by_author = args
+                    .author
+                    .as_ref()
+                    .is_none_or(|author| &mr.author == author);
+                if !mr.pull_request.is_empty() && by_author {
                     merge_requests.push(mr);
                 }
             }
@@ -242,14 +374,103 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Github<R> {
         )
     }
 
-    fn num_pages(&self, args: MergeRequestListBodyArgs) -> Result<Option<u32>> {
+    fn reopen(&self, id: i64) -> Result<MergeRequestResponse> {
+        let url = format!(
+            "{}/repos/{}/pulls/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        let mut body = Body::new();
+        body.add("state", "open");
+        query::github_merge_request::<_, &str>(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            PATCH,
+            ApiOperation::MergeRequest,
+        )
+    }
+
+    fn set_draft(&self, id: i64, draft: bool) -> Result<MergeRequestResponse> {
+        // The REST API cannot change the draft status of a pull request.
+        // https://docs.github.com/en/graphql/reference/mutations#convertpullrequesttodraft
+        let node_id = self.pull_request_node_id(id)?;
+        let mutation = if draft {
+            "mutation($id: ID!) { convertPullRequestToDraft(input: {pullRequestId: $id}) { \
+            pullRequest { isDraft } } }"
+        } else {
+            "mutation($id: ID!) { markPullRequestReadyForReview(input: {pullRequestId: $id}) { \
+            pullRequest { isDraft } } }"
+        };
+        self.graphql(
+            mutation,
+            serde_json::json!({ "id": node_id }),
+            ApiOperation::MergeRequest,
+        )?;
+        MergeRequest::get(self, id)
+    }
+
+    fn num_pages(&self, args: MergeRequestListBodyArgs) -> Result<Option<PageCount>> {
+        check_assigned_filters(&args)?;
         let url = self.url_list_merge_requests(&args) + "&page=1";
         let headers = self.request_headers();
         query::num_pages(&self.runner, &url, headers, ApiOperation::MergeRequest)
     }
 
-    fn approve(&self, _id: i64) -> Result<MergeRequestResponse> {
-        todo!()
+    fn limits(&self) -> MergeRequestLimits {
+        MergeRequestLimits {
+            title: GITHUB_PULL_REQUEST_TITLE_MAX_CHARS,
+            description: GITHUB_PULL_REQUEST_BODY_MAX_CHARS,
+        }
+    }
+
+    fn approve(&self, id: i64, comment: Option<&str>) -> Result<MergeRequestResponse> {
+        // Doc: https://docs.github.com/en/rest/pulls/reviews#create-a-review-for-a-pull-request
+        let url = format!(
+            "{}/repos/{}/pulls/{}/reviews",
+            self.rest_api_basepath, self.path, id
+        );
+        let mut body = Body::new();
+        body.add("event", "APPROVE");
+        if let Some(comment) = comment {
+            body.add("body", comment);
+        }
+        let response = query::github_merge_request_response(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::MergeRequest,
+        )?;
+        // Github refuses approving your own or a closed pull request with a
+        // 422, telling why in the errors.
+        if response.status != 200 {
+            let data = json_loads(&response.body).unwrap_or_default();
+            let reason = data["errors"]
+                .as_array()
+                .map(|errors| {
+                    errors
+                        .iter()
+                        .filter_map(|error| error.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .filter(|reason| !reason.is_empty())
+                .or_else(|| data["message"].as_str().map(|message| message.to_string()))
+                .unwrap_or(response.body);
+            return Err(error::GRError::RemoteServerError(format!(
+                "Could not approve pull request #{}: {}",
+                id, reason
+            ))
+            .into());
+        }
+        // The review in the response does not carry the pull request URL.
+        Ok(MergeRequestResponse::builder()
+            .id(id)
+            .web_url(self.get_url(BrowseOptions::MergeRequestId(id)))
+            .build()
+            .unwrap())
     }
 }
 
@@ -271,477 +492,2334 @@ impl<R: HttpRunner<Response = Response>> CommentMergeRequest for Github<R> {
         )?;
         Ok(())
     }
-}
 
-pub struct GithubMergeRequestFields {
-    id: i64,
-    web_url: String,
-    source_branch: String,
-    author: String,
-    updated_at: String,
-    created_at: String,
-    title: String,
-    pull_request: String,
-    description: String,
-    merged_at: String,
-    pipeline_id: Option<i64>,
-    pipeline_url: Option<String>,
+    fn list_comments(&self, id: i64) -> Result<Vec<CommentThread>> {
+        // General comments live in the issue of the pull request. Comments
+        // on the diff are review comments, replies pointing to the first
+        // comment of their thread.
+        // https://docs.github.com/en/rest/issues/comments#list-issue-comments
+        let url = format!(
+            "{}/repos/{}/issues/{}/comments",
+            self.rest_api_basepath, self.path, id
+        );
+        let mut threads = query::github_list_merge_request_issue_comments(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            None,
+            ApiOperation::MergeRequest,
+        )?;
+        // https://docs.github.com/en/rest/pulls/comments#list-review-comments-on-a-pull-request
+        let url = format!(
+            "{}/repos/{}/pulls/{}/comments",
+            self.rest_api_basepath, self.path, id
+        );
+        let review_comments = query::github_list_merge_request_review_comments(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            None,
+            ApiOperation::MergeRequest,
+        )?;
+        let mut review_threads: Vec<CommentThread> = Vec::new();
+        for comment in review_comments {
+            match review_threads
+                .iter_mut()
+                .find(|thread| thread.id == comment.id)
+            {
+                Some(thread) => thread.comments.extend(comment.comments),
+                None => review_threads.push(comment),
+            }
+        }
+        threads.extend(review_threads);
+        threads.sort_by_key(|thread| thread.created_at());
+        Ok(threads)
+    }
 }
 
-impl From<&serde_json::Value> for GithubMergeRequestFields {
-    fn from(merge_request_data: &serde_json::Value) -> Self {
-        GithubMergeRequestFields {
-            id: merge_request_data["number"].as_i64().unwrap(),
-            web_url: merge_request_data["html_url"].as_str().unwrap().to_string(),
-            source_branch: merge_request_data["head"]["ref"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            author: merge_request_data["user"]["login"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            updated_at: merge_request_data["updated_at"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            created_at: merge_request_data["created_at"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            title: merge_request_data["title"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            pull_request: merge_request_data["pull_request"]["html_url"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            description: merge_request_data["body"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            merged_at: merge_request_data["merged_at"]
+fn github_comment(data: &serde_json::Value) -> MergeRequestComment {
+    MergeRequestComment::builder()
+        .id(data["id"].as_i64().unwrap_or_default())
+        .author(
+            data["user"]["login"]
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
-            // Not available in the response. Set it to the same ID as the pull request
-            pipeline_id: Some(merge_request_data["number"].as_i64().unwrap()),
-            pipeline_url: merge_request_data["html_url"]
-                .as_str()
-                .map(|url| format!("{}/checks", url)),
+        )
+        .created_at(data["created_at"].as_str().unwrap_or_default().to_string())
+        .body(data["body"].as_str().unwrap_or_default().to_string())
+        .build()
+        .unwrap()
+}
+
+pub struct GithubIssueCommentFields {
+    thread: CommentThread,
+}
+
+impl From<&serde_json::Value> for GithubIssueCommentFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubIssueCommentFields {
+            thread: CommentThread::builder()
+                .id(data["id"].as_i64().unwrap_or_default().to_string())
+                .comments(vec![github_comment(data)])
+                .build()
+                .unwrap(),
         }
     }
 }
 
-impl From<GithubMergeRequestFields> for MergeRequestResponse {
-    fn from(fields: GithubMergeRequestFields) -> Self {
-        MergeRequestResponse::builder()
-            .id(fields.id)
-            .web_url(fields.web_url)
-            .source_branch(fields.source_branch)
-            .author(fields.author)
-            .updated_at(fields.updated_at)
-            .created_at(fields.created_at)
-            .title(fields.title)
-            .pull_request(fields.pull_request)
-            .description(fields.description)
-            .merged_at(fields.merged_at)
-            .pipeline_id(fields.pipeline_id)
-            .pipeline_url(fields.pipeline_url)
-            .build()
-            .unwrap()
+impl From<GithubIssueCommentFields> for CommentThread {
+    fn from(fields: GithubIssueCommentFields) -> Self {
+        fields.thread
     }
 }
 
-#[cfg(test)]
-mod test {
-
-    use std::sync::Arc;
-
-    use crate::{
-        http::{self, Headers},
-        remote::{ListBodyArgs, MergeRequestState},
-        test::utils::{config, get_contract, ContractType, MockRunner},
-    };
-
-    use super::*;
+/// A review comment as a thread of its own, identified by the first comment
+/// of the thread it belongs to, so replies can be merged into it.
+pub struct GithubReviewCommentFields {
+    thread: CommentThread,
+}
 
-    #[test]
-    fn test_open_merge_request() {
-        let config = config();
-        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
+impl From<&serde_json::Value> for GithubReviewCommentFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let thread_id = data["in_reply_to_id"]
+            .as_i64()
+            .or(data["id"].as_i64())
+            .unwrap_or_default();
+        // Comments on outdated diffs lose their line, keep the original one.
+        let line = data["line"].as_i64().or(data["original_line"].as_i64());
+        let position = data["path"].as_str().map(|path| match line {
+            Some(line) => format!("{}:{}", path, line),
+            None => path.to_string(),
+        });
+        GithubReviewCommentFields {
+            thread: CommentThread::builder()
+                .id(thread_id.to_string())
+                .position(position)
+                .comments(vec![github_comment(data)])
+                .build()
+                .unwrap(),
+        }
+    }
+}
 
-        let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let response1 = Response::builder()
-            .status(201)
-            .body(get_contract(ContractType::Github, "merge_request.json"))
-            .build()
-            .unwrap();
-        let response2 = Response::builder()
-            .status(200)
-            .body(get_contract(ContractType::Github, "merge_request.json"))
-            .build()
-            .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response2, response1]));
-        let github = Github::new(config, &domain, &path, client.clone());
+impl From<GithubReviewCommentFields> for CommentThread {
+    fn from(fields: GithubReviewCommentFields) -> Self {
+        fields.thread
+    }
+}
 
-        assert!(github.open(mr_args).is_ok());
-        assert_eq!(
-            "https://api.github.com/repos/jordilin/githapi/issues/23",
-            *client.url(),
+impl<R: HttpRunner<Response = Response>> MergeRequestChecks for Github<R> {
+    fn list(&self, id: i64) -> Result<Vec<MergeRequestCheck>> {
+        let url = format!(
+            "{}/repos/{}/pulls/{}",
+            self.rest_api_basepath, self.path, id
         );
-        assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+        let pull_request = query::github_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        let sha = pull_request["head"]["sha"].as_str().unwrap_or_default();
+        // Github Actions and other apps report check runs. Older integrations
+        // still report commit statuses.
+        // https://docs.github.com/en/rest/checks/runs#list-check-runs-for-a-git-reference
+        let url = format!(
+            "{}/repos/{}/commits/{}/check-runs",
+            self.rest_api_basepath, self.path, sha
+        );
+        let mut checks = query::github_list_check_runs(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            Some("check_runs"),
+            ApiOperation::Pipeline,
+        )?;
+        // https://docs.github.com/en/rest/commits/statuses#get-the-combined-status-for-a-specific-reference
+        let url = format!(
+            "{}/repos/{}/commits/{}/status",
+            self.rest_api_basepath, self.path, sha
         );
+        checks.extend(query::github_list_commit_statuses(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            Some("statuses"),
+            ApiOperation::Pipeline,
+        )?);
+        Ok(checks)
     }
 
-    #[test]
-    fn test_open_merge_request_error_status_code() {
-        let config = config();
-        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
-
-        let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let response1 = Response::builder().status(401).body(
-            r#"{"message":"Bad credentials","documentation_url":"https://docs.github.com/rest"}"#
-                .to_string(),
-            )
-            .build()
-            .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response1]));
-        let github = Github::new(config, &domain, &path, client.clone());
-        assert!(github.open(mr_args).is_err());
+    fn unmet_requirements(&self, id: i64) -> Result<Vec<String>> {
+        // Only GraphQL tells which checks are required by the branch
+        // protection rules without needing admin permissions.
+        let (owner, name) = self.owner_repo()?;
+        let data = self.graphql(
+            "query($owner: String!, $name: String!, $number: Int!) { \
+            repository(owner: $owner, name: $name) { pullRequest(number: $number) { \
+            state isDraft mergeable reviewDecision \
+            commits(last: 1) { nodes { commit { statusCheckRollup { \
+            contexts(first: 100) { nodes { \
+            ... on CheckRun { name status conclusion isRequired(pullRequestNumber: $number) } \
+            ... on StatusContext { context state isRequired(pullRequestNumber: $number) } \
+            } } } } } } } } }",
+            serde_json::json!({"owner": owner, "name": name, "number": id}),
+            ApiOperation::MergeRequest,
+        )?;
+        let pull_request = &data["repository"]["pullRequest"];
+        let mut unmet = Vec::new();
+        if pull_request["state"].as_str() != Some("OPEN") {
+            unmet.push("Pull request is not open".to_string());
+        }
+        if pull_request["isDraft"].as_bool().unwrap_or_default() {
+            unmet.push("Pull request is a draft".to_string());
+        }
+        if pull_request["mergeable"].as_str() == Some("CONFLICTING") {
+            unmet.push("Pull request has conflicts with the base branch".to_string());
+        }
+        match pull_request["reviewDecision"].as_str() {
+            Some("REVIEW_REQUIRED") => unmet.push("An approving review is required".to_string()),
+            Some("CHANGES_REQUESTED") => unmet.push("A reviewer requested changes".to_string()),
+            _ => {}
+        }
+        let contexts = pull_request["commits"]["nodes"][0]["commit"]["statusCheckRollup"]
+            ["contexts"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        for context in contexts {
+            if !context["isRequired"].as_bool().unwrap_or_default() {
+                continue;
+            }
+            let (name, state) = match context["name"].as_str() {
+                Some(name) => (
+                    name,
+                    check_run_state(
+                        context["status"].as_str().unwrap_or_default(),
+                        context["conclusion"].as_str().unwrap_or_default(),
+                    ),
+                ),
+                None => (
+                    context["context"].as_str().unwrap_or_default(),
+                    commit_status_state(context["state"].as_str().unwrap_or_default()),
+                ),
+            };
+            if state == CheckState::Fail || state == CheckState::Pending {
+                unmet.push(format!("Required check {}: {}", name, state));
+            }
+        }
+        Ok(unmet)
+    }
+}
+
+// The REST API returns lowercase values and GraphQL uppercase ones.
+fn check_run_state(status: &str, conclusion: &str) -> CheckState {
+    match (
+        status.to_lowercase().as_str(),
+        conclusion.to_lowercase().as_str(),
+    ) {
+        ("completed", "success") => CheckState::Pass,
+        ("completed", "neutral" | "skipped") => CheckState::Neutral,
+        ("completed", _) => CheckState::Fail,
+        _ => CheckState::Pending,
+    }
+}
+
+fn commit_status_state(state: &str) -> CheckState {
+    match state.to_lowercase().as_str() {
+        "success" => CheckState::Pass,
+        "failure" | "error" => CheckState::Fail,
+        _ => CheckState::Pending,
+    }
+}
+
+pub struct GithubCheckRunFields {
+    check: MergeRequestCheck,
+}
+
+impl From<&serde_json::Value> for GithubCheckRunFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let state = check_run_state(
+            data["status"].as_str().unwrap_or_default(),
+            data["conclusion"].as_str().unwrap_or_default(),
+        );
+        GithubCheckRunFields {
+            check: MergeRequestCheck::builder()
+                .id(data["id"].as_i64().unwrap_or_default())
+                .name(data["name"].as_str().unwrap_or_default().to_string())
+                .kind("check run".to_string())
+                .state(state)
+                .web_url(data["html_url"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GithubCheckRunFields> for MergeRequestCheck {
+    fn from(fields: GithubCheckRunFields) -> Self {
+        fields.check
+    }
+}
+
+pub struct GithubCommitStatusFields {
+    check: MergeRequestCheck,
+}
+
+impl From<&serde_json::Value> for GithubCommitStatusFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let state = commit_status_state(data["state"].as_str().unwrap_or_default());
+        GithubCommitStatusFields {
+            check: MergeRequestCheck::builder()
+                .id(data["id"].as_i64().unwrap_or_default())
+                .name(data["context"].as_str().unwrap_or_default().to_string())
+                .kind("status".to_string())
+                .state(state)
+                .web_url(data["target_url"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GithubCommitStatusFields> for MergeRequestCheck {
+    fn from(fields: GithubCommitStatusFields) -> Self {
+        fields.check
+    }
+}
+
+const MERGE_QUEUE_ENTRY_FIELDS: &str = "position state enqueuedAt enqueuer { login } \
+    pullRequest { number title url baseRefName }";
+
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    fn owner_repo(&self) -> Result<(&str, &str)> {
+        self.path.split_once('/').ok_or_else(|| {
+            error::GRError::ApplicationError(format!(
+                "Invalid path format in git config: [{}]. Expected owner/repo",
+                self.path
+            ))
+            .into()
+        })
+    }
+
+    /// GraphQL node id of the pull request, required by the queue mutations.
+    fn pull_request_node_id(&self, id: i64) -> Result<serde_json::Value> {
+        let (owner, name) = self.owner_repo()?;
+        let data = self.graphql(
+            "query($owner: String!, $name: String!, $number: Int!) { \
+            repository(owner: $owner, name: $name) { pullRequest(number: $number) { id } } }",
+            serde_json::json!({"owner": owner, "name": name, "number": id}),
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(data["repository"]["pullRequest"]["id"].clone())
+    }
+
+    fn pull_request_head_sha(&self, id: i64) -> Result<String> {
+        let url = format!(
+            "{}/repos/{}/pulls/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        let pull_request = query::github_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(pull_request["head"]["sha"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+}
+
+// Merge queues are only exposed through the GraphQL API.
+// https://docs.github.com/en/graphql/reference/objects#mergequeue
+impl<R: HttpRunner<Response = Response>> MergeRequestApprovals for Github<R> {
+    fn approvals(&self, id: i64) -> Result<ApprovalStatus> {
+        // https://docs.github.com/en/rest/pulls/reviews#list-reviews-for-a-pull-request
+        let url = format!(
+            "{}/repos/{}/pulls/{}/reviews?per_page=100",
+            self.rest_api_basepath, self.path, id
+        );
+        let reviews = query::github_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        // Reviews come in chronological order. The latest approval or change
+        // request of each reviewer is the one that counts. Comments do not
+        // change it.
+        let mut approved_by: Vec<String> = Vec::new();
+        let mut changes_requested: Vec<String> = Vec::new();
+        for review in reviews.as_array().into_iter().flatten() {
+            let login = review["user"]["login"].as_str().unwrap_or_default();
+            match review["state"].as_str().unwrap_or_default() {
+                "APPROVED" => {
+                    changes_requested.retain(|user| user != login);
+                    if !approved_by.iter().any(|user| user == login) {
+                        approved_by.push(login.to_string());
+                    }
+                }
+                "CHANGES_REQUESTED" => {
+                    approved_by.retain(|user| user != login);
+                    if !changes_requested.iter().any(|user| user == login) {
+                        changes_requested.push(login.to_string());
+                    }
+                }
+                "DISMISSED" => {
+                    approved_by.retain(|user| user != login);
+                    changes_requested.retain(|user| user != login);
+                }
+                _ => {}
+            }
+        }
+        // https://docs.github.com/en/rest/pulls/review-requests#get-all-requested-reviewers-for-a-pull-request
+        let url = format!(
+            "{}/repos/{}/pulls/{}/requested_reviewers",
+            self.rest_api_basepath, self.path, id
+        );
+        let requested = query::github_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        let (owner, _) = self.owner_repo()?;
+        let mut pending = changes_requested.clone();
+        for user in requested["users"].as_array().into_iter().flatten() {
+            let login = user["login"].as_str().unwrap_or_default().to_string();
+            // Review requested again after approving. The approval is stale.
+            approved_by.retain(|user| *user != login);
+            if !pending.contains(&login) {
+                pending.push(login);
+            }
+        }
+        for team in requested["teams"].as_array().into_iter().flatten() {
+            let slug = team["slug"].as_str().unwrap_or_default();
+            pending.push(format!("{}/{}", owner, slug));
+        }
+        Ok(ApprovalStatus::builder()
+            .approved(!approved_by.is_empty() && changes_requested.is_empty())
+            .approved_by(approved_by)
+            .pending(pending)
+            .build()
+            .unwrap())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequestLabels for Github<R> {
+    fn add(&self, id: i64, labels: &[String]) -> Result<Vec<String>> {
+        // Labels of pull requests are handled through the issues API.
+        // https://docs.github.com/en/rest/issues/labels?apiVersion=2022-11-28#add-labels-to-an-issue
+        let url = format!(
+            "{}/repos/{}/issues/{}/labels",
+            self.rest_api_basepath, self.path, id
+        );
+        let mut body = Body::new();
+        body.add("labels", labels.to_vec());
+        let labels = query::github_merge_request_json(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(label_names(&labels))
+    }
+
+    fn remove(&self, id: i64, labels: &[String]) -> Result<Vec<String>> {
+        // One label at a time. Every request returns the labels left.
+        // https://docs.github.com/en/rest/issues/labels?apiVersion=2022-11-28#remove-a-label-from-an-issue
+        let mut left = Vec::new();
+        for label in labels {
+            let url = format!(
+                "{}/repos/{}/issues/{}/labels/{}",
+                self.rest_api_basepath,
+                self.path,
+                id,
+                encode_query_param(label)
+            );
+            let labels = query::github_merge_request_json::<_, ()>(
+                &self.runner,
+                &url,
+                None,
+                self.request_headers(),
+                DELETE,
+                ApiOperation::MergeRequest,
+            )?;
+            left = label_names(&labels);
+        }
+        Ok(left)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequestDiff for Github<R> {
+    fn diff(&self, id: i64) -> Result<String> {
+        // https://docs.github.com/en/rest/pulls/pulls#get-a-pull-request
+        // The diff media type shares the URL with the JSON representation of
+        // the pull request. The query parameter, ignored by Github, keeps them
+        // apart in the cache.
+        let url = format!(
+            "{}/repos/{}/pulls/{}?media=diff",
+            self.rest_api_basepath, self.path, id
+        );
+        let mut headers = self.request_headers();
+        headers.set(
+            "Accept".to_string(),
+            "application/vnd.github.diff".to_string(),
+        );
+        let response = query::github_merge_request_response::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            headers,
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(response.body)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequestReviewDiff for Github<R> {
+    fn reviewed_head(&self, id: i64, username: &str) -> Result<ReviewedHead> {
+        // https://docs.github.com/en/rest/pulls/reviews#list-reviews-for-a-pull-request
+        let url = format!(
+            "{}/repos/{}/pulls/{}/reviews?per_page=100",
+            self.rest_api_basepath, self.path, id
+        );
+        let reviews = query::github_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        // Reviews come in chronological order and record the commit they
+        // were submitted on. Pending ones have not been submitted yet.
+        let reviewed_sha = reviews
+            .as_array()
+            .into_iter()
+            .flatten()
+            .rev()
+            .filter(|review| {
+                review["user"]["login"].as_str() == Some(username)
+                    && review["state"].as_str() != Some("PENDING")
+            })
+            .find_map(|review| review["commit_id"].as_str())
+            .map(String::from);
+        Ok(ReviewedHead::builder()
+            .head_sha(self.pull_request_head_sha(id)?)
+            .reviewed_sha(reviewed_sha)
+            .build()
+            .unwrap())
+    }
+
+    fn compare(&self, from_sha: &str, to_sha: &str) -> Result<String> {
+        // https://docs.github.com/en/rest/commits/commits#compare-two-commits
+        let url = format!(
+            "{}/repos/{}/compare/{}...{}",
+            self.rest_api_basepath, self.path, from_sha, to_sha
+        );
+        let mut headers = self.request_headers();
+        headers.set(
+            "Accept".to_string(),
+            "application/vnd.github.diff".to_string(),
+        );
+        let response = query::github_merge_request_response::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            headers,
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(response.body)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> FailingPipelines for Github<R> {
+    fn failing(&self, user: &Member) -> Result<Vec<FailingPipeline>> {
+        // The combined status of the head commit is searchable.
+        // https://docs.github.com/en/search-github/searching-on-github/searching-issues-and-pull-requests#search-by-commit-status
+        let url = format!(
+            "{}/search/issues?q=is:pr+state:open+author:{}+status:failure&per_page=100",
+            self.rest_api_basepath, user.username
+        );
+        let results = query::github_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Pipeline,
+        )?;
+        let failing = results["items"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|item| {
+                let field = |name: &str| item[name].as_str().unwrap_or_default();
+                // Ex. https://api.github.com/repos/jordilin/githapi
+                let project = field("repository_url")
+                    .split_once("/repos/")
+                    .map_or("", |(_, path)| path);
+                FailingPipeline::builder()
+                    .project(project.to_string())
+                    .merge_request_id(item["number"].as_i64().unwrap_or_default())
+                    .title(field("title").to_string())
+                    .status("failure".to_string())
+                    .web_url(format!("{}/checks", field("html_url")))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        Ok(failing)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequestRebase for Github<R> {
+    fn rebase(&self, id: i64) -> Result<String> {
+        // https://docs.github.com/en/rest/pulls/pulls#update-a-pull-request-branch
+        let sha = self.pull_request_head_sha(id)?;
+        let url = format!(
+            "{}/repos/{}/pulls/{}/update-branch",
+            self.rest_api_basepath, self.path, id
+        );
+        let mut body = Body::new();
+        // Refuse to update the branch if it got new commits in the meantime.
+        body.add("expected_head_sha", sha.clone());
+        query::github_merge_request_response(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            PUT,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(sha)
+    }
+
+    fn rebase_progress(&self, id: i64, from_sha: &str) -> Result<RebaseProgress> {
+        // Github updates the branch in the background and does not tell when
+        // it is done, other than by the head commit changing.
+        let sha = self.pull_request_head_sha(id)?;
+        if sha == from_sha {
+            return Ok(RebaseProgress::InProgress);
+        }
+        let url = format!(
+            "{}/repos/{}/actions/runs?head_sha={}",
+            self.rest_api_basepath, self.path, sha
+        );
+        let runs = query::github_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Pipeline,
+        )?;
+        let pipeline_url = runs["workflow_runs"][0]["html_url"]
+            .as_str()
+            .map(String::from);
+        Ok(RebaseProgress::Done { sha, pipeline_url })
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeQueue for Github<R> {
+    fn list(&self, args: MergeQueueListBodyArgs) -> Result<Vec<MergeQueueEntry>> {
+        let (owner, name) = self.owner_repo()?;
+        let query = format!(
+            "query($owner: String!, $name: String!, $branch: String) {{ \
+            repository(owner: $owner, name: $name) {{ mergeQueue(branch: $branch) {{ \
+            entries(first: 100) {{ nodes {{ {} }} }} }} }} }}",
+            MERGE_QUEUE_ENTRY_FIELDS
+        );
+        let data = self.graphql(
+            &query,
+            serde_json::json!({"owner": owner, "name": name, "branch": args.branch}),
+            ApiOperation::MergeRequest,
+        )?;
+        // mergeQueue is null when the branch has no merge queue configured.
+        Ok(data["repository"]["mergeQueue"]["entries"]["nodes"]
+            .as_array()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .map(|node| GithubMergeQueueEntryFields::from(node).into())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn get(&self, id: i64) -> Result<MergeQueueEntry> {
+        let (owner, name) = self.owner_repo()?;
+        let query = format!(
+            "query($owner: String!, $name: String!, $number: Int!) {{ \
+            repository(owner: $owner, name: $name) {{ pullRequest(number: $number) {{ \
+            mergeQueueEntry {{ {} }} }} }} }}",
+            MERGE_QUEUE_ENTRY_FIELDS
+        );
+        let data = self.graphql(
+            &query,
+            serde_json::json!({"owner": owner, "name": name, "number": id}),
+            ApiOperation::MergeRequest,
+        )?;
+        let entry = &data["repository"]["pullRequest"]["mergeQueueEntry"];
+        if entry.is_null() {
+            return Err(error::GRError::PreconditionNotMet(format!(
+                "Pull request {} is not in the merge queue",
+                id
+            ))
+            .into());
+        }
+        Ok(GithubMergeQueueEntryFields::from(entry).into())
+    }
+
+    fn add(&self, id: i64) -> Result<()> {
+        let node_id = self.pull_request_node_id(id)?;
+        self.graphql(
+            "mutation($id: ID!) { enqueuePullRequest(input: {pullRequestId: $id}) { \
+            mergeQueueEntry { position } } }",
+            serde_json::json!({ "id": node_id }),
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(())
+    }
+
+    fn remove(&self, id: i64) -> Result<()> {
+        let node_id = self.pull_request_node_id(id)?;
+        self.graphql(
+            "mutation($id: ID!) { dequeuePullRequest(input: {id: $id}) { \
+            mergeQueueEntry { state } } }",
+            serde_json::json!({ "id": node_id }),
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(())
+    }
+}
+
+pub struct GithubMergeQueueEntryFields {
+    entry: MergeQueueEntry,
+}
+
+impl From<&serde_json::Value> for GithubMergeQueueEntryFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubMergeQueueEntryFields {
+            entry: MergeQueueEntry::builder()
+                // Github positions start at 0 for the entry at the head.
+                .position(data["position"].as_i64().unwrap_or_default() + 1)
+                .id(data["pullRequest"]["number"].as_i64().unwrap_or_default())
+                .title(
+                    data["pullRequest"]["title"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .target_branch(
+                    data["pullRequest"]["baseRefName"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .status(data["state"].as_str().unwrap_or_default().to_lowercase())
+                .enqueued_by(
+                    data["enqueuer"]["login"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .enqueued_at(data["enqueuedAt"].as_str().unwrap_or_default().to_string())
+                .web_url(
+                    data["pullRequest"]["url"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GithubMergeQueueEntryFields> for MergeQueueEntry {
+    fn from(fields: GithubMergeQueueEntryFields) -> Self {
+        fields.entry
+    }
+}
+
+pub struct GithubMergeRequestFields {
+    id: i64,
+    web_url: String,
+    source_branch: String,
+    target_branch: String,
+    author: String,
+    updated_at: String,
+    created_at: String,
+    title: String,
+    pull_request: String,
+    description: String,
+    merged_at: String,
+    pipeline_id: Option<i64>,
+    pipeline_url: Option<String>,
+    labels: Vec<String>,
+}
+
+impl From<&serde_json::Value> for GithubMergeRequestFields {
+    fn from(merge_request_data: &serde_json::Value) -> Self {
+        GithubMergeRequestFields {
+            id: merge_request_data["number"].as_i64().unwrap(),
+            web_url: merge_request_data["html_url"].as_str().unwrap().to_string(),
+            source_branch: merge_request_data["head"]["ref"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            target_branch: merge_request_data["base"]["ref"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            author: merge_request_data["user"]["login"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            updated_at: merge_request_data["updated_at"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            created_at: merge_request_data["created_at"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            title: merge_request_data["title"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            pull_request: merge_request_data["pull_request"]["html_url"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            description: merge_request_data["body"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            merged_at: merge_request_data["merged_at"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            // Not available in the response. Set it to the same ID as the pull request
+            pipeline_id: Some(merge_request_data["number"].as_i64().unwrap()),
+            pipeline_url: merge_request_data["html_url"]
+                .as_str()
+                .map(|url| format!("{}/checks", url)),
+            labels: label_names(&merge_request_data["labels"]),
+        }
+    }
+}
+
+fn label_names(labels: &serde_json::Value) -> Vec<String> {
+    labels
+        .as_array()
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|label| label["name"].as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl From<GithubMergeRequestFields> for MergeRequestResponse {
+    fn from(fields: GithubMergeRequestFields) -> Self {
+        MergeRequestResponse::builder()
+            .id(fields.id)
+            .web_url(fields.web_url)
+            .source_branch(fields.source_branch)
+            .target_branch(fields.target_branch)
+            .author(fields.author)
+            .updated_at(fields.updated_at)
+            .created_at(fields.created_at)
+            .title(fields.title)
+            .pull_request(fields.pull_request)
+            .description(fields.description)
+            .merged_at(fields.merged_at)
+            .pipeline_id(fields.pipeline_id)
+            .pipeline_url(fields.pipeline_url)
+            .labels(fields.labels)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        http::{self, Headers},
+        remote::{ListBodyArgs, Member, MergeRequestState, Milestone},
+        test::utils::{config, get_contract, ContractType, MockRunner},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_open_merge_request() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
+
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response1 = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .build()
+            .unwrap();
+        let response2 = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response2, response1]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        assert!(github.open(mr_args).is_ok());
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/issues/23",
+            *client.url(),
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_open_merge_request_sets_all_assignees() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .assignee_ids(vec![1, 2])
+            .assignee_usernames(vec!["jdoe".to_string(), "tsmith".to_string()])
+            .build()
+            .unwrap();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response1 = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .build()
+            .unwrap();
+        let response2 = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response2, response1]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        github.open(mr_args).unwrap();
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(serde_json::json!(["jdoe", "tsmith"]), body["assignees"]);
+    }
+
+    #[test]
+    fn test_open_merge_request_requests_reviewers() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .reviewer_ids(vec![3, 4])
+            .reviewer_usernames(vec!["jdoe".to_string(), "tsmith".to_string()])
+            .build()
+            .unwrap();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let merge_request_response = |status| {
+            Response::builder()
+                .status(status)
+                .body(get_contract(ContractType::Github, "merge_request.json"))
+                .build()
+                .unwrap()
+        };
+        let client = Arc::new(MockRunner::new(vec![
+            merge_request_response(201),
+            merge_request_response(200),
+            merge_request_response(201),
+        ]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        github.open(mr_args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23/requested_reviewers",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(serde_json::json!(["jdoe", "tsmith"]), body["reviewers"]);
+    }
+
+    #[test]
+    fn test_open_merge_request_sets_milestone() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .milestone_id(Some(3))
+            .build()
+            .unwrap();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let merge_request_response = |status| {
+            Response::builder()
+                .status(status)
+                .body(get_contract(ContractType::Github, "merge_request.json"))
+                .build()
+                .unwrap()
+        };
+        let client = Arc::new(MockRunner::new(vec![
+            merge_request_response(200),
+            merge_request_response(201),
+        ]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        github.open(mr_args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/issues/23",
+            *client.url(),
+        );
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(serde_json::json!(3), body["milestone"]);
+        assert!(body.get("assignees").is_none());
+    }
+
+    #[test]
+    fn test_open_merge_request_error_status_code() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
+
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response1 = Response::builder().status(401).body(
+            r#"{"message":"Bad credentials","documentation_url":"https://docs.github.com/rest"}"#
+                .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response1]));
+        let github = Github::new(config, &domain, path, client.clone());
+        assert!(github.open(mr_args).is_err());
+    }
+
+    #[test]
+    fn test_open_merge_request_existing_one() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .source_branch("feature".to_string())
+            .build()
+            .unwrap();
+
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response1 = Response::builder()
+            .status(422)
+            .body(get_contract(
+                ContractType::Github,
+                "merge_request_conflict.json",
+            ))
+            .build()
+            .unwrap();
+        // Github returns a 422 (already exists), so the code grabs existing URL
+        // filtering by namespace and branch. The response is a list of merge
+        // requests.
+        let response2 = Response::builder()
+            .status(200)
+            .body(format!(
+                "[{}]",
+                get_contract(ContractType::Github, "merge_request.json")
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response2, response1]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        github.open(mr_args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls?head=jordilin:feature",
+            *client.url(),
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_open_merge_request_from_fork_targets_upstream() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .source_branch("feature".to_string())
+            .target_repo(Some("upstream/githapi".to_string()))
+            .build()
+            .unwrap();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let merge_request_response = |status| {
+            Response::builder()
+                .status(status)
+                .body(get_contract(ContractType::Github, "merge_request.json"))
+                .build()
+                .unwrap()
+        };
+        let client = Arc::new(MockRunner::new(vec![
+            merge_request_response(200),
+            merge_request_response(201),
+        ]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        github.open(mr_args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/upstream/githapi/issues/23",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_open_merge_request_from_fork_existing_one() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .source_branch("feature".to_string())
+            .target_repo(Some("upstream/githapi".to_string()))
+            .build()
+            .unwrap();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response1 = Response::builder()
+            .status(422)
+            .body(get_contract(
+                ContractType::Github,
+                "merge_request_conflict.json",
+            ))
+            .build()
+            .unwrap();
+        let response2 = Response::builder()
+            .status(200)
+            .body(format!(
+                "[{}]",
+                get_contract(ContractType::Github, "merge_request.json")
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response2, response1]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        github.open(mr_args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/upstream/githapi/pulls?head=jordilin:feature",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_open_merge_request_cannot_retrieve_url_existing_one_is_error() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .source_branch("feature".to_string())
+            .build()
+            .unwrap();
+
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response1 = Response::builder()
+            .status(422)
+            .body(get_contract(
+                ContractType::Github,
+                "merge_request_conflict.json",
+            ))
+            .build()
+            .unwrap();
+        let response2 = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response2, response1]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        let result = github.open(mr_args);
+        match result {
+            Ok(_) => panic!("Expected error"),
+            Err(err) => match err.downcast_ref::<error::GRError>() {
+                Some(error::GRError::RemoteUnexpectedResponseContract(_)) => (),
+                _ => panic!("Expected error::GRError::RemoteUnexpectedResponseContract"),
+            },
+        }
+    }
+
+    #[test]
+    fn test_open_merge_request_cannot_get_owner_org_namespace_in_existing_pull_request() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .source_branch("feature".to_string())
+            .build()
+            .unwrap();
+
+        let domain = "github.com".to_string();
+        // missing the repo name
+        let path = "jordilin";
+        let response1 = Response::builder()
+            .status(422)
+            .body(get_contract(
+                ContractType::Github,
+                "merge_request_conflict.json",
+            ))
+            .build()
+            .unwrap();
+        let response2 = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response2, response1]));
+        let github = Github::new(config, &domain, path, client.clone());
+
+        let result = github.open(mr_args);
+        match result {
+            Ok(_) => panic!("Expected error"),
+            Err(err) => match err.downcast_ref::<error::GRError>() {
+                Some(error::GRError::ApplicationError(_)) => (),
+                _ => panic!("Expected error::GRError::ApplicationError"),
+            },
+        }
+    }
+
+    #[test]
+    fn test_merge_request_num_pages() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let link_header = r#"<https://api.github.com/repos/jordilin/githapi/pulls?state=open&page=2>; rel="next", <https://api.github.com/repos/jordilin/githapi/pulls?state=open&page=2>; rel="last""#;
+        let mut headers = Headers::new();
+        headers.set("link".to_string(), link_header.to_string());
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .build()
+            .unwrap();
+        assert_eq!(Some(PageCount::from(2)), github.num_pages(args).unwrap());
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls?state=open&page=1",
+            *client.url(),
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_list_merge_requests_from_to_page_set_in_url() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(Some(
+                ListBodyArgs::builder()
+                    .page(2)
+                    .max_pages(3)
+                    .build()
+                    .unwrap(),
+            ))
+            .assignee_id(None)
+            .build()
+            .unwrap();
+        github.list(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls?state=open&page=2",
+            *client.url(),
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_requests_include_extra_headers_from_config() {
+        let config_data = r#"
+        github.com.api_token=1234
+        github.com.cache_location=/home/user/.config/mr_cache
+        github.com.extra_header_Proxy_Authorization=Basic dXNlcjpwYXNz
+        "#;
+        let config =
+            crate::config::Config::new(std::io::Cursor::new(config_data), "github.com").unwrap();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "list_issues_user.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> = Box::new(Github::new(
+            config,
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(Some(123456))
+            .build()
+            .unwrap();
+        github.list(args).unwrap();
+        let headers = client.headers();
+        assert_eq!(
+            Some(&"Basic dXNlcjpwYXNz".to_string()),
+            headers.get("Proxy-Authorization")
+        );
+        assert_eq!(
+            Some(&"bearer 1234".to_string()),
+            headers.get("Authorization")
+        );
+        assert_eq!(
+            Some(&format!("gitar/{}", env!("CARGO_PKG_VERSION"))),
+            headers.get("User-Agent")
+        );
+    }
+
+    #[test]
+    fn test_get_pull_requests_for_auth_user() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "list_issues_user.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(Some(123456))
+            .build()
+            .unwrap();
+        let merge_requests = github.list(args).unwrap();
+        assert_eq!("https://api.github.com/issues?state=open", *client.url());
+        assert!(merge_requests.len() == 1);
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_get_pull_requests_review_requested_for_auth_user() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let body = format!(
+            r#"{{"total_count": 2, "items": {}}}"#,
+            get_contract(ContractType::Github, "list_issues_user.json")
+        );
+        let response = Response::builder().status(200).body(body).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .reviewer(Some(
+                Member::builder()
+                    .id(123456)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap();
+        let merge_requests = github.list(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/search/issues?q=is:pr+state:open+review-requested:jordilin",
+            *client.url()
+        );
+        assert_eq!(2, merge_requests.len());
+    }
+
+    #[test]
+    fn test_list_pull_requests_needing_review_in_repo() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let body = format!(
+            r#"{{"total_count": 2, "items": {}}}"#,
+            get_contract(ContractType::Github, "list_issues_user.json")
+        );
+        let response = Response::builder().status(200).body(body).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .reviewer(Some(
+                Member::builder()
+                    .id(123456)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .in_project(true)
+            .draft(Some(false))
+            .passing_ci(true)
+            .build()
+            .unwrap();
+        let merge_requests = github.list(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/search/issues?q=is:pr+state:open+review-requested:jordilin+draft:false+status:success+repo:jordilin/githapi",
+            *client.url()
+        );
+        assert_eq!(2, merge_requests.len());
+    }
+
+    #[test]
+    fn test_list_pull_requests_by_author_searches_in_repo() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let body = format!(
+            r#"{{"total_count": 2, "items": {}}}"#,
+            get_contract(ContractType::Github, "list_issues_user.json")
+        );
+        let response = Response::builder().status(200).body(body).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .author(Some("jdoe".to_string()))
+            .build()
+            .unwrap();
+        let merge_requests = github.list(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/search/issues?q=is:pr+repo:jordilin/githapi+state:open+author:jdoe",
+            *client.url()
+        );
+        assert_eq!(2, merge_requests.len());
     }
 
     #[test]
-    fn test_open_merge_request_existing_one() {
+    fn test_list_pull_requests_by_milestone() {
         let config = config();
-        let mr_args = MergeRequestBodyArgs::builder()
-            .source_branch("feature".to_string())
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "list_issues_user.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let milestone = Milestone::builder()
+            .id(3)
+            .title("Release 1.0".to_string())
+            .build()
+            .unwrap();
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .milestone(Some(milestone))
             .build()
             .unwrap();
+        let merge_requests = github.list(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/issues?state=open&milestone=3",
+            *client.url()
+        );
+        // Issues in the milestone are left out.
+        assert_eq!(1, merge_requests.len());
+    }
+
+    #[test]
+    fn test_assigned_pull_requests_cannot_be_filtered_by_milestone_draft_nor_ci() {
+        let milestone = Milestone::builder()
+            .id(3)
+            .title("Release 1.0".to_string())
+            .build()
+            .unwrap();
+        let filters = [
+            MergeRequestListBodyArgs::builder()
+                .milestone(Some(milestone))
+                .clone(),
+            MergeRequestListBodyArgs::builder()
+                .draft(Some(false))
+                .clone(),
+            MergeRequestListBodyArgs::builder().passing_ci(true).clone(),
+        ];
+        for mut builder in filters {
+            let client = Arc::new(MockRunner::new(vec![]));
+            let github: Box<dyn MergeRequest> = Box::new(Github::new(
+                config(),
+                "github.com",
+                "jordilin/githapi",
+                client.clone(),
+            ));
+            let args = builder
+                .state(MergeRequestState::Opened)
+                .list_args(None)
+                .assignee_id(Some(123456))
+                .build()
+                .unwrap();
+            match github.list(args) {
+                Err(err) => match err.downcast_ref::<error::GRError>() {
+                    Some(error::GRError::OperationNotSupported(_)) => (),
+                    _ => panic!("Expected error::GRError::OperationNotSupported"),
+                },
+                Ok(_) => panic!("Expected an error"),
+            }
+            assert!(client.url().is_empty());
+        }
+    }
 
+    #[test]
+    fn test_search_pull_requests_by_author_and_milestone() {
+        let config = config();
         let domain = "github.com".to_string();
         let path = "jordilin/githapi";
-        let response1 = Response::builder()
-            .status(422)
-            .body(get_contract(
-                ContractType::Github,
-                "merge_request_conflict.json",
-            ))
+        let response = Response::builder()
+            .status(200)
+            .body(r#"{"total_count": 0, "items": []}"#.to_string())
             .build()
             .unwrap();
-        // Github returns a 422 (already exists), so the code grabs existing URL
-        // filtering by namespace and branch. The response is a list of merge
-        // requests.
-        let response2 = Response::builder()
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let milestone = Milestone::builder()
+            .id(3)
+            .title("Release 1.0".to_string())
+            .build()
+            .unwrap();
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .author(Some("jdoe".to_string()))
+            .milestone(Some(milestone))
+            .build()
+            .unwrap();
+        github.list(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/search/issues?q=is:pr+repo:jordilin/githapi+state:open+author:jdoe+milestone:%22Release%201.0%22",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_list_pull_requests_for_auth_user_filters_by_author() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
             .status(200)
-            .body(format!(
-                "[{}]",
-                get_contract(ContractType::Github, "merge_request.json")
-            ))
+            .body(get_contract(ContractType::Github, "list_issues_user.json"))
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response2, response1]));
-        let github = Github::new(config, &domain, &path, client.clone());
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(Some(123456))
+            .author(Some("tsmith".to_string()))
+            .build()
+            .unwrap();
+        let merge_requests = github.list(args).unwrap();
+        assert_eq!("https://api.github.com/issues?state=open", *client.url());
+        assert!(merge_requests.is_empty());
+    }
 
-        github.open(mr_args).unwrap();
+    #[test]
+    fn test_create_merge_request_comment() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn CommentMergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = CommentMergeRequestBodyArgs::builder()
+            .id(23)
+            .comment("Looks good to me".to_string())
+            .build()
+            .unwrap();
+        github.create(args).unwrap();
         assert_eq!(
-            "https://api.github.com/repos/jordilin/githapi/pulls?head=jordilin:feature",
+            "https://api.github.com/repos/jordilin/githapi/issues/23/comments",
+            *client.url(),
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_create_merge_request_comment_error_status_code() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder().status(500).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn CommentMergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = CommentMergeRequestBodyArgs::builder()
+            .id(23)
+            .comment("Looks good to me".to_string())
+            .build()
+            .unwrap();
+        assert!(github.create(args).is_err());
+    }
+
+    #[test]
+    fn test_reopen_pull_request_ok() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        github.reopen(23).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23",
+            *client.url(),
+        );
+        assert_eq!(http::Method::PATCH, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!("open", body["state"]);
+    }
+
+    #[test]
+    fn test_mark_pull_request_ready_for_review() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let node_id = r#"{"data": {"repository": {"pullRequest": {"id": "PR_kwDOKLlQxM5"}}}}"#;
+        let mutation =
+            r#"{"data": {"markPullRequestReadyForReview": {"pullRequest": {"isDraft": false}}}}"#;
+        let responses = vec![
+            Response::builder()
+                .status(200)
+                .body(get_contract(ContractType::Github, "merge_request.json"))
+                .build()
+                .unwrap(),
+            Response::builder()
+                .status(200)
+                .body(mutation.to_string())
+                .build()
+                .unwrap(),
+            Response::builder()
+                .status(200)
+                .body(node_id.to_string())
+                .build()
+                .unwrap(),
+        ];
+        let client = Arc::new(MockRunner::new(responses));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let pull_request = github.set_draft(23, false).unwrap();
+        assert_eq!("New Feature", pull_request.title);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_close_pull_request_ok() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        github.close(23).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23",
+            *client.url(),
+        );
+        assert_eq!(http::Method::PATCH, *client.http_method.borrow());
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_get_pull_request_details() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let merge_request = github.get(23).unwrap();
+        assert_eq!("main", merge_request.target_branch);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23",
+            *client.url(),
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_github_merge_pull_request() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        github.merge(23).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23/merge",
+            *client.url(),
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_github_approve_pull_request_with_comment() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(r#"{"id": 80, "state": "APPROVED"}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let merge_request = github.approve(23, Some("Checklist confirmed")).unwrap();
+        assert_eq!(
+            "https://github.com/jordilin/githapi/pull/23",
+            merge_request.web_url
+        );
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23/reviews",
             *client.url(),
         );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!("APPROVE", body["event"]);
+        assert_eq!("Checklist confirmed", body["body"]);
+    }
+
+    #[test]
+    fn test_github_approve_own_pull_request_is_an_error() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(422)
+            .body(
+                r#"{"message": "Unprocessable Entity",
+                    "errors": ["Review Can not approve your own pull request"]}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequest> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let Err(err) = github.approve(23, None) else {
+            panic!("Expected an error");
+        };
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            "Remote server status error: Could not approve pull request #23: Review Can not approve your own pull request",
+            err.to_string()
         );
     }
 
     #[test]
-    fn test_open_merge_request_cannot_retrieve_url_existing_one_is_error() {
+    fn test_list_merge_queue_entries() {
         let config = config();
-        let mr_args = MergeRequestBodyArgs::builder()
-            .source_branch("feature".to_string())
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi".to_string();
+        let body = r#"{"data": {"repository": {"mergeQueue": {"entries": {"nodes": [
+            {"position": 0, "state": "AWAITING_CHECKS", "enqueuedAt": "2024-02-01T10:00:00Z",
+             "enqueuer": {"login": "jordilin"},
+             "pullRequest": {"number": 23, "title": "Add retries", "url": "https://github.com/jordilin/githapi/pull/23", "baseRefName": "main"}}
+        ]}}}}}"#;
+        let response = Response::builder()
+            .status(200)
+            .body(body.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeQueue> =
+            Box::new(Github::new(config, &domain, &path, client.clone()));
+        let args = MergeQueueListBodyArgs::builder()
+            .branch("main".to_string())
             .build()
             .unwrap();
+        let entries = github.list(args).unwrap();
+        assert_eq!("https://api.github.com/graphql", *client.url());
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!(1, entries.len());
+        assert_eq!(1, entries[0].position);
+        assert_eq!(23, entries[0].id);
+        assert_eq!("awaiting_checks", entries[0].status);
+    }
 
+    #[test]
+    fn test_list_merge_queue_no_queue_configured_is_empty() {
+        let config = config();
         let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let response1 = Response::builder()
-            .status(422)
-            .body(get_contract(
-                ContractType::Github,
-                "merge_request_conflict.json",
-            ))
+        let path = "jordilin/githapi".to_string();
+        let body = r#"{"data": {"repository": {"mergeQueue": null}}}"#;
+        let response = Response::builder()
+            .status(200)
+            .body(body.to_string())
             .build()
             .unwrap();
-        let response2 = Response::builder()
-            .status(200)
-            .body("[]".to_string())
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeQueue> = Box::new(Github::new(config, &domain, &path, client));
+        let args = MergeQueueListBodyArgs::builder()
+            .branch("main".to_string())
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response2, response1]));
-        let github = Github::new(config, &domain, &path, client.clone());
-
-        let result = github.open(mr_args);
-        match result {
-            Ok(_) => panic!("Expected error"),
-            Err(err) => match err.downcast_ref::<error::GRError>() {
-                Some(error::GRError::RemoteUnexpectedResponseContract(_)) => (),
-                _ => panic!("Expected error::GRError::RemoteUnexpectedResponseContract"),
-            },
-        }
+        assert!(github.list(args).unwrap().is_empty());
     }
 
     #[test]
-    fn test_open_merge_request_cannot_get_owner_org_namespace_in_existing_pull_request() {
+    fn test_get_pull_request_not_in_merge_queue_is_error() {
         let config = config();
-        let mr_args = MergeRequestBodyArgs::builder()
-            .source_branch("feature".to_string())
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi".to_string();
+        let body = r#"{"data": {"repository": {"pullRequest": {"mergeQueueEntry": null}}}}"#;
+        let response = Response::builder()
+            .status(200)
+            .body(body.to_string())
             .build()
             .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeQueue> = Box::new(Github::new(config, &domain, &path, client));
+        assert!(github.get(23).is_err());
+    }
 
+    #[test]
+    fn test_graphql_errors_are_reported() {
+        let config = config();
         let domain = "github.com".to_string();
-        // missing the repo name
-        let path = "jordilin";
-        let response1 = Response::builder()
-            .status(422)
-            .body(get_contract(
-                ContractType::Github,
-                "merge_request_conflict.json",
-            ))
+        let path = "jordilin/githapi".to_string();
+        let node_response = Response::builder()
+            .status(200)
+            .body(r#"{"data": {"repository": {"pullRequest": {"id": "PR_kwDO"}}}}"#.to_string())
             .build()
             .unwrap();
-        let response2 = Response::builder()
+        let enqueue_response = Response::builder()
             .status(200)
-            .body("[]".to_string())
+            .body(
+                r#"{"data": null, "errors": [{"message": "Pull request is not mergeable"}]}"#
+                    .to_string(),
+            )
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response2, response1]));
-        let github = Github::new(config, &domain, &path, client.clone());
-
-        let result = github.open(mr_args);
-        match result {
-            Ok(_) => panic!("Expected error"),
-            Err(err) => match err.downcast_ref::<error::GRError>() {
-                Some(error::GRError::ApplicationError(_)) => (),
-                _ => panic!("Expected error::GRError::ApplicationError"),
-            },
-        }
+        let client = Arc::new(MockRunner::new(vec![enqueue_response, node_response]));
+        let github: Box<dyn MergeQueue> = Box::new(Github::new(config, &domain, &path, client));
+        let err = github.add(23).unwrap_err();
+        assert!(err.to_string().contains("Pull request is not mergeable"));
     }
 
     #[test]
-    fn test_merge_request_num_pages() {
+    fn test_list_pull_request_check_runs_and_statuses() {
         let config = config();
         let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let link_header = r#"<https://api.github.com/repos/jordilin/githapi/pulls?state=open&page=2>; rel="next", <https://api.github.com/repos/jordilin/githapi/pulls?state=open&page=2>; rel="last""#;
-        let mut headers = Headers::new();
-        headers.set("link".to_string(), link_header.to_string());
-        let response = Response::builder()
+        let path = "jordilin/githapi".to_string();
+        let pr_response = Response::builder()
             .status(200)
-            .headers(headers)
+            .body(get_contract(ContractType::Github, "merge_request.json"))
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn MergeRequest> =
-            Box::new(Github::new(config, &domain, &path, client.clone()));
-        let args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(None)
-            .assignee_id(None)
+        let check_runs_response = Response::builder()
+            .status(200)
+            .body(
+                r#"{"total_count": 2, "check_runs": [
+                {"id": 1, "name": "build", "status": "completed", "conclusion": "success", "html_url": "https://github.com/runs/1"},
+                {"id": 2, "name": "test", "status": "in_progress", "conclusion": null, "html_url": "https://github.com/runs/2"}
+            ]}"#
+                .to_string(),
+            )
+            .build()
+            .unwrap();
+        let statuses_response = Response::builder()
+            .status(200)
+            .body(
+                r#"{"state": "failure", "statuses": [
+                {"id": 7, "context": "ci/legacy", "state": "error", "target_url": "https://ci.example.com/7"}
+            ]}"#
+                .to_string(),
+            )
             .build()
             .unwrap();
-        assert_eq!(Some(2), github.num_pages(args).unwrap());
+        let client = Arc::new(MockRunner::new(vec![
+            statuses_response,
+            check_runs_response,
+            pr_response,
+        ]));
+        let github: Box<dyn MergeRequestChecks> =
+            Box::new(Github::new(config, &domain, &path, client.clone()));
+        let checks = github.list(23).unwrap();
+        assert!(client.url().ends_with("/status"));
+        let states = checks
+            .iter()
+            .map(|check| (check.name.as_str(), check.kind.as_str(), check.state))
+            .collect::<Vec<_>>();
         assert_eq!(
-            "https://api.github.com/repos/jordilin/githapi/pulls?state=open&page=1",
-            *client.url(),
+            vec![
+                ("build", "check run", CheckState::Pass),
+                ("test", "check run", CheckState::Pending),
+                ("ci/legacy", "status", CheckState::Fail),
+            ],
+            states
         );
+    }
+
+    #[test]
+    fn test_list_pull_request_issue_and_review_comments() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi".to_string();
+        let issue_comments_response = Response::builder()
+            .status(200)
+            .body(
+                r#"[
+                {"id": 10, "user": {"login": "jdoe"}, "body": "Ready for review",
+                 "created_at": "2024-03-01T10:00:00Z"},
+                {"id": 11, "user": {"login": "alice"}, "body": "LGTM",
+                 "created_at": "2024-03-05T10:00:00Z"}
+            ]"#
+                .to_string(),
+            )
+            .build()
+            .unwrap();
+        let review_comments_response = Response::builder()
+            .status(200)
+            .body(
+                r#"[
+                {"id": 20, "user": {"login": "alice"}, "body": "Off by one?",
+                 "created_at": "2024-03-02T10:00:00Z", "path": "src/main.rs", "line": 12},
+                {"id": 21, "in_reply_to_id": 20, "user": {"login": "jdoe"}, "body": "Fixed",
+                 "created_at": "2024-03-03T10:00:00Z", "path": "src/main.rs", "line": 12}
+            ]"#
+                .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![
+            review_comments_response,
+            issue_comments_response,
+        ]));
+        let github: Box<dyn CommentMergeRequest> =
+            Box::new(Github::new(config, &domain, &path, client.clone()));
+        let threads = github.list_comments(23).unwrap();
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            "https://api.github.com/repos/jordilin/githapi/pulls/23/comments",
+            *client.url()
         );
+        let summary = threads
+            .iter()
+            .map(|thread| (thread.id.as_str(), thread.comments.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![("10", 1), ("20", 2), ("11", 1)], summary);
+        assert_eq!(Some("src/main.rs:12".to_string()), threads[1].position);
+        assert_eq!(None, threads[1].resolved);
+        assert_eq!("Fixed", threads[1].comments[1].body);
     }
 
     #[test]
-    fn test_list_merge_requests_from_to_page_set_in_url() {
+    fn test_unmet_requirements_only_reports_required_checks() {
         let config = config();
         let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
+        let path = "jordilin/githapi".to_string();
+        let body = r#"{"data": {"repository": {"pullRequest": {
+            "state": "OPEN", "isDraft": false, "mergeable": "MERGEABLE",
+            "reviewDecision": "REVIEW_REQUIRED",
+            "commits": {"nodes": [{"commit": {"statusCheckRollup": {"contexts": {"nodes": [
+                {"name": "build", "status": "COMPLETED", "conclusion": "SUCCESS", "isRequired": true},
+                {"name": "test", "status": "COMPLETED", "conclusion": "FAILURE", "isRequired": true},
+                {"name": "docs", "status": "IN_PROGRESS", "conclusion": null, "isRequired": false},
+                {"context": "ci/legacy", "state": "PENDING", "isRequired": true}
+            ]}}}}]}
+        }}}}"#;
         let response = Response::builder()
             .status(200)
-            .body("[]".to_string())
+            .body(body.to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn MergeRequest> =
+        let github: Box<dyn MergeRequestChecks> =
             Box::new(Github::new(config, &domain, &path, client.clone()));
-        let args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(Some(
-                ListBodyArgs::builder()
-                    .page(2)
-                    .max_pages(3)
-                    .build()
-                    .unwrap(),
-            ))
-            .assignee_id(None)
-            .build()
-            .unwrap();
-        github.list(args).unwrap();
-        assert_eq!(
-            "https://api.github.com/repos/jordilin/githapi/pulls?state=open&page=2",
-            *client.url(),
-        );
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            vec![
+                "An approving review is required".to_string(),
+                "Required check test: fail".to_string(),
+                "Required check ci/legacy: pending".to_string(),
+            ],
+            github.unmet_requirements(23).unwrap()
         );
+        assert_eq!("https://api.github.com/graphql", *client.url());
     }
 
     #[test]
-    fn test_get_pull_requests_for_auth_user() {
+    fn test_merge_request_diff_requests_diff_media_type() {
         let config = config();
         let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
+        let path = "jordilin/githapi".to_string();
+        let patch = "diff --git a/README.md b/README.md\n\
+                     --- a/README.md\n\
+                     +++ b/README.md\n\
+                     @@ -1 +1 @@\n\
+                     -# githapi\n\
+                     +# Githapi\n";
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Github, "list_issues_user.json"))
+            .body(patch.to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn MergeRequest> =
+        let github: Box<dyn MergeRequestDiff> =
             Box::new(Github::new(config, &domain, &path, client.clone()));
-        let args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(None)
-            .assignee_id(Some(123456))
-            .build()
-            .unwrap();
-        let merge_requests = github.list(args).unwrap();
-        assert_eq!("https://api.github.com/issues?state=open", *client.url());
-        assert!(merge_requests.len() == 1);
+        let diff = github.diff(23).unwrap();
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            "https://api.github.com/repos/jordilin/githapi/pulls/23?media=diff",
+            *client.url()
+        );
+        assert_eq!(
+            "application/vnd.github.diff",
+            client.headers().get("Accept").unwrap()
         );
+        assert_eq!(patch, diff);
     }
 
     #[test]
-    fn test_create_merge_request_comment() {
+    fn test_failing_pipelines_searches_pull_requests_by_status() {
         let config = config();
         let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let response = Response::builder().status(201).build().unwrap();
+        let results = r#"{"items": [{
+            "number": 23,
+            "title": "Fix build",
+            "html_url": "https://github.com/jordilin/githapi/pull/23",
+            "repository_url": "https://api.github.com/repos/jordilin/githapi"
+        }]}"#;
+        let response = Response::builder()
+            .status(200)
+            .body(results.to_string())
+            .build()
+            .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn CommentMergeRequest> =
-            Box::new(Github::new(config, &domain, &path, client.clone()));
-        let args = CommentMergeRequestBodyArgs::builder()
-            .id(23)
-            .comment("Looks good to me".to_string())
+        let github: Box<dyn FailingPipelines> =
+            Box::new(Github::new(config, &domain, "", client.clone()));
+        let user = Member::builder()
+            .id(1)
+            .name("Jordi".to_string())
+            .username("jordilin".to_string())
             .build()
             .unwrap();
-        github.create(args).unwrap();
+        let failing = github.failing(&user).unwrap();
         assert_eq!(
-            "https://api.github.com/repos/jordilin/githapi/issues/23/comments",
-            *client.url(),
+            "https://api.github.com/search/issues?q=is:pr+state:open+author:jordilin+status:failure&per_page=100",
+            *client.url()
         );
+        assert_eq!(1, failing.len());
+        assert_eq!("jordilin/githapi", failing[0].project);
+        assert_eq!(23, failing[0].merge_request_id);
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            "https://github.com/jordilin/githapi/pull/23/checks",
+            failing[0].web_url
         );
     }
 
     #[test]
-    fn test_create_merge_request_comment_error_status_code() {
+    fn test_reviewed_head_takes_last_submitted_review_of_user() {
         let config = config();
         let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let response = Response::builder().status(500).build().unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn CommentMergeRequest> =
+        let path = "jordilin/githapi".to_string();
+        let reviews = r#"[
+            {"user": {"login": "alice"}, "state": "COMMENTED", "commit_id": "aaa"},
+            {"user": {"login": "alice"}, "state": "APPROVED", "commit_id": "bbb"},
+            {"user": {"login": "bob"}, "state": "APPROVED", "commit_id": "ccc"},
+            {"user": {"login": "alice"}, "state": "PENDING", "commit_id": "ddd"}
+        ]"#;
+        let pull_request = r#"{"head": {"sha": "eee"}}"#;
+        let responses = [pull_request, reviews]
+            .iter()
+            .map(|body| {
+                Response::builder()
+                    .status(200)
+                    .body(body.to_string())
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let client = Arc::new(MockRunner::new(responses));
+        let github: Box<dyn MergeRequestReviewDiff> =
             Box::new(Github::new(config, &domain, &path, client.clone()));
-        let args = CommentMergeRequestBodyArgs::builder()
-            .id(23)
-            .comment("Looks good to me".to_string())
-            .build()
-            .unwrap();
-        assert!(github.create(args).is_err());
+        let head = github.reviewed_head(23, "alice").unwrap();
+        assert_eq!("eee", head.head_sha);
+        assert_eq!(Some("bbb".to_string()), head.reviewed_sha);
     }
 
     #[test]
-    fn test_close_pull_request_ok() {
+    fn test_compare_commits_requests_diff_media_type() {
         let config = config();
         let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
+        let path = "jordilin/githapi".to_string();
+        let patch = "diff --git a/README.md b/README.md\n";
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .body(patch.to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn MergeRequest> =
+        let github: Box<dyn MergeRequestReviewDiff> =
             Box::new(Github::new(config, &domain, &path, client.clone()));
-        github.close(23).unwrap();
+        let diff = github.compare("bbb", "eee").unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/compare/bbb...eee",
+            *client.url()
+        );
+        assert_eq!(
+            "application/vnd.github.diff",
+            client.headers().get("Accept").unwrap()
+        );
+        assert_eq!(patch, diff);
+    }
+
+    fn github_rebase(bodies: Vec<&str>) -> (Arc<MockRunner>, Box<dyn MergeRequestRebase>) {
+        let responses = bodies
+            .into_iter()
+            .rev()
+            .map(|body| {
+                Response::builder()
+                    .status(200)
+                    .body(body.to_string())
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let client = Arc::new(MockRunner::new(responses));
+        let github: Box<dyn MergeRequestRebase> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        (client, github)
+    }
+
+    #[test]
+    fn test_rebase_updates_branch_from_expected_head() {
+        let (client, github) = github_rebase(vec![
+            r#"{"number": 23, "head": {"sha": "1111111"}}"#,
+            r#"{"message": "Updating pull request branch."}"#,
+        ]);
+        assert_eq!("1111111", github.rebase(23).unwrap());
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23/update-branch",
+            *client.url()
+        );
+        assert_eq!(PUT, *client.http_method.borrow());
+        assert!(client
+            .request_body()
+            .contains(r#""expected_head_sha":"1111111""#));
+    }
+
+    #[test]
+    fn test_rebase_in_progress_until_head_changes() {
+        let (client, github) = github_rebase(vec![r#"{"head": {"sha": "1111111"}}"#]);
+        assert_eq!(
+            RebaseProgress::InProgress,
+            github.rebase_progress(23, "1111111").unwrap()
+        );
         assert_eq!(
             "https://api.github.com/repos/jordilin/githapi/pulls/23",
-            *client.url(),
+            *client.url()
         );
-        assert_eq!(http::Method::PATCH, *client.http_method.borrow());
+
+        let (client, github) = github_rebase(vec![
+            r#"{"head": {"sha": "2222222"}}"#,
+            r#"{"total_count": 1, "workflow_runs": [{"html_url": "https://github.com/jordilin/githapi/actions/runs/9"}]}"#,
+        ]);
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            RebaseProgress::Done {
+                sha: "2222222".to_string(),
+                pipeline_url: Some(
+                    "https://github.com/jordilin/githapi/actions/runs/9".to_string()
+                ),
+            },
+            github.rebase_progress(23, "1111111").unwrap()
+        );
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs?head_sha=2222222",
+            *client.url()
         );
     }
 
     #[test]
-    fn test_get_pull_request_details() {
+    fn test_merge_request_approvals_take_latest_review() {
         let config = config();
         let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let response = Response::builder()
-            .status(200)
-            .body(get_contract(ContractType::Github, "merge_request.json"))
-            .build()
-            .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn MergeRequest> =
+        let path = "jordilin/githapi".to_string();
+        let reviews = r#"[
+            {"user": {"login": "alice"}, "state": "CHANGES_REQUESTED"},
+            {"user": {"login": "bob"}, "state": "APPROVED"},
+            {"user": {"login": "alice"}, "state": "APPROVED"},
+            {"user": {"login": "carol"}, "state": "COMMENTED"},
+            {"user": {"login": "dave"}, "state": "APPROVED"}
+        ]"#;
+        let requested = r#"{"users": [{"login": "dave"}], "teams": [{"slug": "core"}]}"#;
+        let responses = [requested, reviews]
+            .iter()
+            .map(|body| {
+                Response::builder()
+                    .status(200)
+                    .body(body.to_string())
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let client = Arc::new(MockRunner::new(responses));
+        let github: Box<dyn MergeRequestApprovals> =
             Box::new(Github::new(config, &domain, &path, client.clone()));
-        github.get(23).unwrap();
+        let status = github.approvals(23).unwrap();
         assert_eq!(
-            "https://api.github.com/repos/jordilin/githapi/pulls/23",
-            *client.url(),
+            "https://api.github.com/repos/jordilin/githapi/pulls/23/requested_reviewers",
+            *client.url()
         );
+        assert!(status.approved);
+        assert_eq!(None, status.approvals_left);
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            vec!["bob".to_string(), "alice".to_string()],
+            status.approved_by
+        );
+        assert_eq!(
+            vec!["dave".to_string(), "jordilin/core".to_string()],
+            status.pending
         );
     }
 
     #[test]
-    fn test_github_merge_pull_request() {
-        let config = config();
-        let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
+    fn test_add_pull_request_labels_through_issues_api() {
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Github, "merge_request.json"))
+            .body(r#"[{"name": "bug"}, {"name": "backend"}]"#.to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn MergeRequest> =
-            Box::new(Github::new(config, &domain, &path, client.clone()));
-        github.merge(23).unwrap();
+        let github: Box<dyn MergeRequestLabels> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let labels = github.add(23, &["backend".to_string()]).unwrap();
+        assert_eq!(vec!["bug", "backend"], labels);
         assert_eq!(
-            "https://api.github.com/repos/jordilin/githapi/pulls/23/merge",
-            *client.url(),
+            "https://api.github.com/repos/jordilin/githapi/issues/23/labels",
+            *client.url()
         );
-        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+        assert_eq!(POST, *client.http_method.borrow());
+        assert!(client.request_body().contains(r#""labels":["backend"]"#));
+    }
+
+    #[test]
+    fn test_remove_pull_request_label_encodes_name() {
+        let response = Response::builder()
+            .status(200)
+            .body(r#"[{"name": "bug"}]"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn MergeRequestLabels> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let labels = github.remove(23, &["needs review".to_string()]).unwrap();
+        assert_eq!(vec!["bug"], labels);
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            "https://api.github.com/repos/jordilin/githapi/issues/23/labels/needs%20review",
+            *client.url()
         );
+        assert_eq!(DELETE, *client.http_method.borrow());
     }
 }
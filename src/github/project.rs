@@ -1,18 +1,35 @@
 use crate::{
-    api_traits::{ApiOperation, RemoteProject},
+    api_traits::{
+        ApiOperation, CommitApi, ProjectAccessRequests, ProjectApprovals, ProjectAudit,
+        ProjectBranchProtection, ProjectFile, ProjectMembers, ProjectMilestones, ProjectPushRules,
+        ProjectRulesets, ProjectTemplate, ProjectVariables, RemoteProject,
+    },
     cli::browse::BrowseOptions,
-    cmds::project::ProjectListBodyArgs,
-    error::GRError,
-    http::Method::GET,
-    io::{CmdInfo, HttpRunner, Response},
+    cmds::project::{
+        AccessRequest, AccessRequestApproveBodyArgs, AccessRequestListBodyArgs, ApprovalSettings,
+        ApprovalSettingsBodyArgs, AuditedProject, BranchAccess, BranchProtectionBodyArgs, Commit,
+        CommitListBodyArgs, CommitPickBodyArgs, MemberListBodyArgs, NewProject,
+        ProjectListBodyArgs, ProjectTemplateBodyArgs, ProjectVariable, ProjectVariableBodyArgs,
+        ProtectedBranch, PushRules, PushRulesBodyArgs, Ruleset,
+    },
+    error::{self, GRError},
+    http::{
+        self, Body,
+        Method::{DELETE, GET, PATCH, POST, PUT},
+    },
+    io::{CmdInfo, HttpRunner, PageCount, Response},
     remote::{
+        encode_query_param,
         query::{self, github_list_members},
-        Member, Project, URLQueryParamBuilder,
+        ListBodyArgs, Member, Milestone, Project, URLQueryParamBuilder,
     },
 };
 
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crypto_box::aead::OsRng;
+
 use super::Github;
-use crate::Result;
+use crate::{json_loads, Result};
 
 impl<R: HttpRunner<Response = Response>> RemoteProject for Github<R> {
     fn get_project_data(&self, id: Option<i64>) -> Result<CmdInfo> {
@@ -58,23 +75,40 @@ impl<R: HttpRunner<Response = Response>> RemoteProject for Github<R> {
             BrowseOptions::MergeRequests => format!("{}/pulls", base_url),
             BrowseOptions::MergeRequestId(id) => format!("{}/pull/{}", base_url, id),
             BrowseOptions::Pipelines => format!("{}/actions", base_url),
+            BrowseOptions::PipelineId(id) => format!("{}/actions/runs/{}", base_url, id),
+            BrowseOptions::File { path, ref_ } => format!(
+                "{}/blob/{}/{}",
+                base_url,
+                ref_.as_deref().unwrap_or("HEAD"),
+                path.trim_start_matches('/')
+            ),
+            BrowseOptions::Releases => format!("{}/releases", base_url),
+            BrowseOptions::Settings => format!("{}/settings", base_url),
         }
     }
 
     fn list(&self, args: crate::cmds::project::ProjectListBodyArgs) -> Result<Vec<Project>> {
+        check_repository_search(&args)?;
         let url = self.list_project_url(&args, false);
+        // Search results are wrapped in an object under "items".
+        let iter_over_sub_array = if is_repository_search(&args) {
+            Some("items")
+        } else {
+            None
+        };
         let projects = query::github_list_projects(
             &self.runner,
             &url,
             args.from_to_page,
             self.request_headers(),
-            None,
+            iter_over_sub_array,
             ApiOperation::Project,
         )?;
         Ok(projects)
     }
 
-    fn num_pages(&self, args: ProjectListBodyArgs) -> Result<Option<u32>> {
+    fn num_pages(&self, args: ProjectListBodyArgs) -> Result<Option<PageCount>> {
+        check_repository_search(&args)?;
         let url = self.list_project_url(&args, true);
         query::num_pages(
             &self.runner,
@@ -85,179 +119,1963 @@ impl<R: HttpRunner<Response = Response>> RemoteProject for Github<R> {
     }
 }
 
-impl<R> Github<R> {
-    fn list_project_url(&self, args: &ProjectListBodyArgs, num_pages: bool) -> String {
-        let url = if args.stars {
-            format!("{}/user/starred", self.rest_api_basepath)
-        } else {
-            let username = args.user.as_ref().unwrap().clone().username;
-            // TODO - not needed - just /user/repos would do
-            // See: https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#list-repositories-for-the-authenticated-user
-            format!("{}/users/{}/repos", self.rest_api_basepath, username)
-        };
-        if num_pages {
-            return URLQueryParamBuilder::new(&url)
-                .add_param("page", "1")
-                .build();
+impl<R: HttpRunner<Response = Response>> ProjectAudit for Github<R> {
+    fn list(&self, group: &str) -> Result<Vec<AuditedProject>> {
+        // Doc:
+        // https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#list-organization-repositories
+        let url = format!("{}/orgs/{}/repos", self.rest_api_basepath, group);
+        query::github_list_org_projects(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            None,
+            ApiOperation::Project,
+        )
+    }
+
+    fn audit(&self, mut project: AuditedProject) -> Result<AuditedProject> {
+        if project.default_branch.is_empty() {
+            return Ok(project);
         }
-        url
+        // The branch tells whether it is protected to anyone who can read the
+        // repository, its protection rules need admin rights.
+        // Doc:
+        // https://docs.github.com/en/rest/branches/branches?apiVersion=2022-11-28#get-a-branch
+        let url = format!(
+            "{}/repos/{}/branches/{}",
+            self.rest_api_basepath,
+            project.path,
+            encode_query_param(&project.default_branch)
+        );
+        let branch = query::github_branch::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        project.default_branch_protected = branch["protected"].as_bool().unwrap_or_default();
+        Ok(project)
     }
 }
 
-pub struct GithubProjectFields {
-    id: i64,
-    default_branch: String,
-    html_url: String,
-    created_at: String,
-}
+impl<R: HttpRunner<Response = Response>> ProjectApprovals for Github<R> {
+    fn get(&self) -> Result<ApprovalSettings> {
+        // Doc:
+        // https://docs.github.com/en/rest/branches/branch-protection?apiVersion=2022-11-28#get-pull-request-review-protection
+        let url = self.required_reviews_url()?;
+        query::github_project_approvals::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )
+    }
 
-impl From<&serde_json::Value> for GithubProjectFields {
-    fn from(project_data: &serde_json::Value) -> Self {
-        GithubProjectFields {
-            id: project_data["id"].as_i64().unwrap(),
-            default_branch: project_data["default_branch"]
-                .to_string()
-                .trim_matches('"')
-                .to_string(),
-            html_url: project_data["html_url"]
-                .to_string()
-                .trim_matches('"')
-                .to_string(),
-            created_at: project_data["created_at"]
-                .to_string()
-                .trim_matches('"')
-                .to_string(),
+    fn set(&self, args: ApprovalSettingsBodyArgs) -> Result<ApprovalSettings> {
+        // Doc:
+        // https://docs.github.com/en/rest/branches/branch-protection?apiVersion=2022-11-28#update-pull-request-review-protection
+        let url = self.required_reviews_url()?;
+        let mut body = Body::new();
+        if let Some(required) = args.required {
+            body.add(
+                "required_approving_review_count",
+                serde_json::Value::from(required),
+            );
         }
+        if let Some(reset_on_push) = args.reset_on_push {
+            body.add(
+                "dismiss_stale_reviews",
+                serde_json::Value::from(reset_on_push),
+            );
+        }
+        query::github_project_approvals(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            PATCH,
+            ApiOperation::Project,
+        )
     }
 }
 
-impl From<GithubProjectFields> for Project {
-    fn from(fields: GithubProjectFields) -> Self {
-        Project::new(fields.id, &fields.default_branch)
-            .with_html_url(&fields.html_url)
-            .with_created_at(&fields.created_at)
+impl<R: HttpRunner<Response = Response>> ProjectMembers for Github<R> {
+    fn list(&self, args: MemberListBodyArgs) -> Result<Vec<Member>> {
+        // Contributors carry no permissions. Collaborators do, including
+        // those given access through organization teams.
+        let url = format!(
+            "{}/repos/{}/collaborators",
+            self.rest_api_basepath, self.path
+        );
+        query::github_list_collaborators(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.request_headers(),
+            None,
+            ApiOperation::Project,
+        )
     }
 }
 
-pub struct GithubMemberFields {
-    id: i64,
-    login: String,
-    name: String,
-    created_at: String,
+// Github has no access requests. Users are invited to repositories instead.
+impl<R: HttpRunner<Response = Response>> ProjectMilestones for Github<R> {
+    fn get(&self, title: &str) -> Result<Milestone> {
+        // Github cannot filter milestones by title. Look for it among the
+        // first hundred, which covers most repositories.
+        // https://docs.github.com/en/rest/issues/milestones?apiVersion=2022-11-28#list-milestones
+        let url = format!(
+            "{}/repos/{}/milestones?state=all&per_page=100",
+            self.rest_api_basepath, self.path
+        );
+        let milestones = query::github_milestones::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        milestones
+            .as_array()
+            .and_then(|milestones| {
+                milestones
+                    .iter()
+                    .find(|milestone| milestone["title"].as_str() == Some(title))
+            })
+            .map(|milestone| GithubMilestoneFields::from(milestone).into())
+            .ok_or_else(|| {
+                GRError::PreconditionNotMet(format!("Milestone {} not found", title)).into()
+            })
+    }
 }
 
-impl From<&serde_json::Value> for GithubMemberFields {
-    fn from(member_data: &serde_json::Value) -> Self {
-        GithubMemberFields {
-            id: member_data["id"].as_i64().unwrap(),
-            login: member_data["login"].as_str().unwrap().to_string(),
-            name: "".to_string(),
-            // Github does not provide created_at field in the response for
-            // Members (aka contributors). Set it to UNIX epoch.
-            created_at: "1970-01-01T00:00:00Z".to_string(),
+impl<R> ProjectAccessRequests for Github<R> {
+    fn list(&self, _args: AccessRequestListBodyArgs) -> Result<Vec<AccessRequest>> {
+        Err(access_requests_not_supported())
+    }
+
+    fn approve(&self, _args: AccessRequestApproveBodyArgs) -> Result<()> {
+        Err(access_requests_not_supported())
+    }
+
+    fn deny(&self, _user_id: i64) -> Result<()> {
+        Err(access_requests_not_supported())
+    }
+}
+
+fn access_requests_not_supported() -> anyhow::Error {
+    GRError::OperationNotSupported("Access requests are not supported in Github".to_string()).into()
+}
+
+// The protection rules of a branch are replaced as a whole. The current ones
+// are read first and sent back, so that only the settings given change.
+// https://docs.github.com/en/rest/branches/branch-protection?apiVersion=2022-11-28
+impl<R: HttpRunner<Response = Response>> ProjectBranchProtection for Github<R> {
+    fn list(&self) -> Result<Vec<ProtectedBranch>> {
+        let url = format!(
+            "{}/repos/{}/branches?protected=true&per_page=100",
+            self.rest_api_basepath, self.path
+        );
+        let branches = query::github_branches::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        branches
+            .as_array()
+            .map(|branches| {
+                branches
+                    .iter()
+                    .filter_map(|branch| branch["name"].as_str())
+                    .map(|name| self.branch_protection(name))
+                    .collect()
+            })
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+
+    fn get(&self, branch: &str) -> Result<ProtectedBranch> {
+        if !self.is_protected(branch)? {
+            return Err(
+                GRError::PreconditionNotMet(format!("Branch {} is not protected", branch)).into(),
+            );
+        }
+        self.branch_protection(branch)
+    }
+
+    fn set(&self, args: BranchProtectionBodyArgs) -> Result<ProtectedBranch> {
+        if args.merge.is_some() {
+            return Err(GRError::OperationNotSupported(
+                "Github has no merge access levels, pushes can be restricted instead".to_string(),
+            )
+            .into());
+        }
+        let current = if self.is_protected(&args.branch)? {
+            self.protection_json(&args.branch)?
+        } else {
+            serde_json::Value::Null
+        };
+        let restrictions = match args.push {
+            None => current_restrictions(&current["restrictions"]),
+            // Only admins can push, unless they are enforced too.
+            Some(BranchAccess::NoOne) => serde_json::json!({ "users": [], "teams": [] }),
+            Some(BranchAccess::Developers) => serde_json::Value::Null,
+            Some(BranchAccess::Maintainers) => return Err(GRError::OperationNotSupported(
+                "Github restricts pushes to users and teams, not roles. Use no-one or developers"
+                    .to_string(),
+            )
+            .into()),
+        };
+        let reviews = &current["required_pull_request_reviews"];
+        let required_approvals = args.required_approvals.or_else(|| {
+            reviews["required_approving_review_count"]
+                .as_u64()
+                .map(|count| count as u32)
+        });
+        let required_pull_request_reviews = match required_approvals {
+            Some(count) => {
+                let mut required_reviews = serde_json::json!({
+                    "dismiss_stale_reviews": reviews["dismiss_stale_reviews"]
+                        .as_bool()
+                        .unwrap_or_default(),
+                    "require_code_owner_reviews": reviews["require_code_owner_reviews"]
+                        .as_bool()
+                        .unwrap_or_default(),
+                    "required_approving_review_count": count,
+                    "require_last_push_approval": reviews["require_last_push_approval"]
+                        .as_bool()
+                        .unwrap_or_default(),
+                });
+                for allowances in ["dismissal_restrictions", "bypass_pull_request_allowances"] {
+                    if reviews[allowances].is_object() {
+                        required_reviews[allowances] = current_restrictions(&reviews[allowances]);
+                    }
+                }
+                required_reviews
+            }
+            None => serde_json::Value::Null,
+        };
+        let checks = &current["required_status_checks"];
+        let required_status_checks = if checks.is_object() {
+            // Checks keep the app that has to report them, contexts do not.
+            if let Some(items) = checks["checks"].as_array() {
+                // A null app is not accepted back, it is left out instead.
+                let items: Vec<serde_json::Value> = items
+                    .iter()
+                    .map(|check| match check["app_id"] {
+                        serde_json::Value::Null => {
+                            serde_json::json!({ "context": check["context"] })
+                        }
+                        _ => check.clone(),
+                    })
+                    .collect();
+                serde_json::json!({ "strict": checks["strict"], "checks": items })
+            } else {
+                serde_json::json!({ "strict": checks["strict"], "contexts": checks["contexts"] })
+            }
+        } else {
+            serde_json::Value::Null
+        };
+        let enabled = |setting: &str| -> serde_json::Value {
+            serde_json::Value::from(current[setting]["enabled"].as_bool().unwrap_or_default())
+        };
+        let mut body = Body::new();
+        body.add("required_status_checks", required_status_checks);
+        body.add("enforce_admins", enabled("enforce_admins"));
+        body.add(
+            "required_pull_request_reviews",
+            required_pull_request_reviews,
+        );
+        body.add("restrictions", restrictions);
+        body.add(
+            "allow_force_pushes",
+            match args.allow_force_push {
+                Some(allow_force_push) => serde_json::Value::from(allow_force_push),
+                None => enabled("allow_force_pushes"),
+            },
+        );
+        // The update replaces the whole protection, settings not handled by
+        // gitar are sent back as they are.
+        for setting in [
+            "required_linear_history",
+            "allow_deletions",
+            "block_creations",
+            "required_conversation_resolution",
+            "lock_branch",
+            "allow_fork_syncing",
+        ] {
+            body.add(setting, enabled(setting));
         }
+        let protection = query::github_branch_protection(
+            &self.runner,
+            &self.protection_url(&args.branch),
+            Some(body),
+            self.request_headers(),
+            PUT,
+            ApiOperation::Project,
+        )?;
+        Ok(github_protected_branch(&args.branch, &protection))
     }
 }
 
-impl From<GithubMemberFields> for Member {
-    fn from(fields: GithubMemberFields) -> Self {
-        Member::builder()
-            .id(fields.id)
-            .username(fields.login)
-            .name(fields.name)
-            .created_at(fields.created_at)
-            .build()
-            .unwrap()
+/// Restrictions as sent back in a protection update.
+fn current_restrictions(restrictions: &serde_json::Value) -> serde_json::Value {
+    if !restrictions.is_object() {
+        return serde_json::Value::Null;
     }
+    let names = |kind: &str, field: &str| -> Vec<serde_json::Value> {
+        restrictions[kind]
+            .as_array()
+            .map(|items| items.iter().map(|item| item[field].clone()).collect())
+            .unwrap_or_default()
+    };
+    serde_json::json!({
+        "users": names("users", "login"),
+        "teams": names("teams", "slug"),
+        "apps": names("apps", "slug"),
+    })
 }
 
-#[cfg(test)]
-mod test {
-    use std::sync::Arc;
+fn github_protected_branch(name: &str, protection: &serde_json::Value) -> ProtectedBranch {
+    let mut branch: ProtectedBranch = GithubBranchProtectionFields::from(protection).into();
+    branch.name = name.to_string();
+    branch
+}
 
-    use crate::{
-        cmds::project::ProjectListBodyArgs,
-        http::Headers,
-        test::utils::{config, get_contract, ContractType, MockRunner},
-    };
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    fn protection_url(&self, branch: &str) -> String {
+        format!(
+            "{}/repos/{}/branches/{}/protection",
+            self.rest_api_basepath,
+            self.path,
+            encode_query_param(branch)
+        )
+    }
 
-    use super::*;
+    fn protection_json(&self, branch: &str) -> Result<serde_json::Value> {
+        query::github_branch_protection::<_, ()>(
+            &self.runner,
+            &self.protection_url(branch),
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )
+    }
 
-    #[test]
-    fn test_get_project_data_no_id() {
-        let config = config();
-        let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let response = Response::builder()
-            .status(200)
-            .body(get_contract(ContractType::Github, "project.json"))
-            .build()
-            .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let github = Github::new(config, &domain, &path, client.clone());
-        github.get_project_data(None).unwrap();
-        assert_eq!(
-            "https://api.github.com/repos/jordilin/githapi",
-            *client.url(),
+    fn branch_protection(&self, branch: &str) -> Result<ProtectedBranch> {
+        Ok(github_protected_branch(
+            branch,
+            &self.protection_json(branch)?,
+        ))
+    }
+
+    /// Reading the protection of an unprotected branch is a 404, the branch
+    /// tells whether there is one.
+    fn is_protected(&self, branch: &str) -> Result<bool> {
+        let url = format!(
+            "{}/repos/{}/branches/{}",
+            self.rest_api_basepath,
+            self.path,
+            encode_query_param(branch)
         );
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+        let branch = query::github_branch::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        Ok(branch["protected"].as_bool().unwrap_or_default())
     }
+}
 
-    #[test]
-    fn test_get_project_data_with_id_not_supported() {
-        let config = config();
-        let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let client = Arc::new(MockRunner::new(vec![]));
-        let github = Github::new(config, &domain, &path, client.clone());
-        assert!(github.get_project_data(Some(1)).is_err());
+// Secret values cannot be read back, only their names are listed.
+// https://docs.github.com/en/rest/actions/secrets?apiVersion=2022-11-28
+impl<R: HttpRunner<Response = Response>> ProjectVariables for Github<R> {
+    fn list(&self, environment: Option<&str>) -> Result<Vec<ProjectVariable>> {
+        let mut secrets = query::github_list_actions_secrets(
+            &self.runner,
+            &self.secrets_url(environment),
+            None,
+            self.request_headers(),
+            Some("secrets"),
+            ApiOperation::Project,
+        )?;
+        for secret in &mut secrets {
+            secret.environment = environment.unwrap_or_default().to_string();
+        }
+        Ok(secrets)
     }
 
-    #[test]
-    fn test_list_current_user_projects() {
-        let config = config();
-        let domain = "github.com".to_string();
-        let path = "jordilin/githapi";
-        let projects = format!("[{}]", get_contract(ContractType::Github, "project.json"));
-        let response = Response::builder()
-            .status(200)
-            .body(projects)
-            .build()
-            .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let github = Github::new(config, &domain, &path, client.clone());
-        let body_args = ProjectListBodyArgs::builder()
-            .from_to_page(None)
-            .user(Some(
-                Member::builder()
-                    .id(1)
-                    .name("jdoe".to_string())
-                    .username("jdoe".to_string())
-                    .build()
-                    .unwrap(),
+    fn set(&self, args: ProjectVariableBodyArgs) -> Result<()> {
+        let secrets_url = self.secrets_url(args.environment.as_deref());
+        let public_key = query::github_actions_secrets::<_, ()>(
+            &self.runner,
+            &format!("{}/public-key", secrets_url),
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        let (Some(key_id), Some(key)) = (public_key["key_id"].as_str(), public_key["key"].as_str())
+        else {
+            return Err(GRError::RemoteUnexpectedResponseContract(format!(
+                "Expected the key and key_id of the repository public key but got: {}",
+                public_key
             ))
-            .build()
-            .unwrap();
-        let projects = github.list(body_args).unwrap();
-        assert_eq!(1, projects.len());
-        assert_eq!("https://api.github.com/users/jdoe/repos", *client.url());
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+            .into());
+        };
+        let mut body = Body::new();
+        body.add("encrypted_value", seal(key, &args.value)?);
+        body.add("key_id", key_id.to_string());
+        query::github_actions_secret_response(
+            &self.runner,
+            &format!("{}/{}", secrets_url, args.key),
+            Some(body),
+            self.request_headers(),
+            PUT,
+            ApiOperation::Project,
+        )?;
+        Ok(())
     }
 
-    #[test]
-    fn test_get_my_starred_projects() {
+    fn delete(&self, key: &str, environment: Option<&str>) -> Result<()> {
+        let url = format!("{}/{}", self.secrets_url(environment), key);
+        query::github_actions_secret_response::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            DELETE,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+}
+
+impl<R> ProjectPushRules for Github<R> {
+    fn get(&self) -> Result<PushRules> {
+        Err(push_rules_not_supported())
+    }
+
+    fn set(&self, _args: PushRulesBodyArgs) -> Result<PushRules> {
+        Err(push_rules_not_supported())
+    }
+}
+
+fn push_rules_not_supported() -> anyhow::Error {
+    GRError::OperationNotSupported(
+        "Push rules are a Gitlab feature. Use `gr pj rulesets` in Github".to_string(),
+    )
+    .into()
+}
+
+// Rulesets of the organization apply to its repositories too and are listed
+// along with the ones of the repository. The listing leaves conditions and
+// rules out, so each ruleset is fetched on its own.
+// https://docs.github.com/en/rest/repos/rules?apiVersion=2022-11-28
+impl<R: HttpRunner<Response = Response>> ProjectRulesets for Github<R> {
+    fn list(&self) -> Result<Vec<Ruleset>> {
+        let url = format!(
+            "{}/repos/{}/rulesets?includes_parents=true&per_page=100",
+            self.rest_api_basepath, self.path
+        );
+        let rulesets = query::github_rulesets::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        rulesets
+            .as_array()
+            .map(|rulesets| {
+                rulesets
+                    .iter()
+                    .filter_map(|ruleset| ruleset["id"].as_i64())
+                    .map(|id| ProjectRulesets::get(self, id))
+                    .collect()
+            })
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+
+    fn get(&self, id: i64) -> Result<Ruleset> {
+        let url = format!(
+            "{}/repos/{}/rulesets/{}",
+            self.rest_api_basepath, self.path, id
+        );
+        let ruleset = query::github_rulesets::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        Ok(GithubRulesetFields::from(&ruleset).into())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> CommitApi for Github<R> {
+    fn list(&self, args: CommitListBodyArgs) -> Result<Vec<Commit>> {
+        // https://docs.github.com/en/rest/commits/commits?apiVersion=2022-11-28#list-commits
+        let mut url = URLQueryParamBuilder::new(&format!(
+            "{}/repos/{}/commits",
+            self.rest_api_basepath, self.path
+        ));
+        if let Some(ref_) = &args.ref_ {
+            url.add_param("sha", &encode_query_param(ref_));
+        }
+        if let Some(since) = &args.since {
+            url.add_param("since", &encode_query_param(since));
+        }
+        let mut commits = query::github_list_commits(
+            &self.runner,
+            &url.build(),
+            args.list_args,
+            self.request_headers(),
+            None,
+            ApiOperation::Project,
+        )?;
+        // Runs are looked up by commit. Runs filtered by branch would miss
+        // tags and commits, and older commits out of the latest runs.
+        for commit in &mut commits {
+            commit.status = self.workflow_run_status(&commit.sha)?;
+        }
+        Ok(commits)
+    }
+
+    fn get(&self, sha: &str) -> Result<Commit> {
+        // https://docs.github.com/en/rest/commits/commits?apiVersion=2022-11-28#get-a-commit
+        let url = format!(
+            "{}/repos/{}/commits/{}",
+            self.rest_api_basepath,
+            self.path,
+            encode_query_param(sha)
+        );
+        let mut commit = query::github_commit::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        commit.status = self.workflow_run_status(&commit.sha)?;
+        Ok(commit)
+    }
+
+    fn cherry_pick(&self, args: CommitPickBodyArgs) -> Result<Commit> {
+        let commit = self.git_data(&format!("git/commits/{}", args.sha), None, GET)?;
+        let parent = single_parent(&commit, &args.sha)?;
+        let message = format!(
+            "{}\n\n(cherry picked from commit {})",
+            commit["message"].as_str().unwrap_or_default(),
+            args.sha
+        );
+        self.apply_onto(
+            &args,
+            &parent,
+            &args.sha,
+            &message,
+            Some(commit["author"].clone()),
+        )
+    }
+
+    fn revert(&self, args: CommitPickBodyArgs) -> Result<Commit> {
+        let commit = self.git_data(&format!("git/commits/{}", args.sha), None, GET)?;
+        let parent = single_parent(&commit, &args.sha)?;
+        let parent = self.git_data(&format!("git/commits/{}", parent), None, GET)?;
+        // The commit undone on its own, which is then applied onto the branch
+        // the same way as a cherry-pick.
+        let undo = self.create_git_commit(
+            &format!("Undo {}", args.sha),
+            &parent["tree"]["sha"],
+            &args.sha,
+            None,
+        )?;
+        let title = commit["message"]
+            .as_str()
+            .unwrap_or_default()
+            .lines()
+            .next()
+            .unwrap_or_default();
+        let message = format!("Revert \"{}\"\n\nThis reverts commit {}.", title, args.sha);
+        self.apply_onto(&args, &args.sha, &undo, &message, None)
+    }
+}
+
+// The Git database API has no cherry-pick nor revert. The changes of a commit
+// are applied by merging it into a temporary branch that has the contents of
+// the target branch on top of the parent of the commit. The resulting merge
+// holds the contents of the branch plus the changes, which are committed onto
+// the branch.
+// https://docs.github.com/en/rest/git?apiVersion=2022-11-28
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    /// Adds a commit with the changes from `base` to `change` on top of the
+    /// branch of `args`. The branch is fast-forwarded, so it fails if it
+    /// moved in the meantime.
+    fn apply_onto(
+        &self,
+        args: &CommitPickBodyArgs,
+        base: &str,
+        change: &str,
+        message: &str,
+        author: Option<serde_json::Value>,
+    ) -> Result<Commit> {
+        let head = self.git_data(&format!("git/ref/heads/{}", args.branch), None, GET)?;
+        let head_sha = head["object"]["sha"].as_str().unwrap_or_default();
+        let head_commit = self.git_data(&format!("git/commits/{}", head_sha), None, GET)?;
+        let temp_sha =
+            self.create_git_commit("Temporary commit", &head_commit["tree"]["sha"], base, None)?;
+        let temp_branch = format!("gr-pick-{}", temp_sha);
+        let mut body = Body::new();
+        body.add(
+            "ref",
+            serde_json::Value::from(format!("refs/heads/{}", temp_branch)),
+        );
+        body.add("sha", serde_json::Value::from(temp_sha));
+        self.git_data("git/refs", Some(body), POST)?;
+        let tree = self.merge_tree(&temp_branch, change, args);
+        let _ = self.git_data(&format!("git/refs/heads/{}", temp_branch), None, DELETE);
+        let commit = self.create_git_commit(message, &tree?, head_sha, author)?;
+        let mut body = Body::new();
+        body.add("sha", serde_json::Value::from(commit.as_str()));
+        body.add("force", serde_json::Value::from(false));
+        self.git_data(
+            &format!("git/refs/heads/{}", args.branch),
+            Some(body),
+            PATCH,
+        )?;
+        let commit = self.git_data(&format!("git/commits/{}", commit), None, GET)?;
+        Ok(GithubGitCommitFields::from(&commit).into())
+    }
+
+    /// Tree of merging `change` into `branch`.
+    fn merge_tree(
+        &self,
+        branch: &str,
+        change: &str,
+        args: &CommitPickBodyArgs,
+    ) -> Result<serde_json::Value> {
+        // https://docs.github.com/en/rest/branches/branches?apiVersion=2022-11-28#merge-a-branch
+        let url = format!("{}/repos/{}/merges", self.rest_api_basepath, self.path);
+        let mut body = Body::new();
+        body.add("base", serde_json::Value::from(branch));
+        body.add("head", serde_json::Value::from(change));
+        body.add("commit_message", serde_json::Value::from("Temporary merge"));
+        let response = query::github_git_data(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::Project,
+        )?;
+        match response.status {
+            201 => Ok(json_loads(&response.body)?["commit"]["tree"]["sha"].clone()),
+            204 => Err(GRError::PreconditionNotMet(format!(
+                "The changes of {} are already in {}",
+                args.sha, args.branch
+            ))
+            .into()),
+            409 => Err(GRError::PreconditionNotMet(format!(
+                "The changes of {} conflict with {}. Resolve them locally instead",
+                args.sha, args.branch
+            ))
+            .into()),
+            _ => Err(git_data_error(&url, &response).into()),
+        }
+    }
+
+    /// Sha of a new commit with `tree` on top of `parent`.
+    fn create_git_commit(
+        &self,
+        message: &str,
+        tree: &serde_json::Value,
+        parent: &str,
+        author: Option<serde_json::Value>,
+    ) -> Result<String> {
+        let mut body = Body::new();
+        body.add("message", serde_json::Value::from(message));
+        body.add("tree", tree.clone());
+        body.add("parents", serde_json::json!([parent]));
+        if let Some(author) = author {
+            body.add("author", author);
+        }
+        let commit = self.git_data("git/commits", Some(body), POST)?;
+        Ok(commit["sha"].as_str().unwrap_or_default().to_string())
+    }
+
+    /// Unlike elsewhere, a POST answered with a conflict or a validation
+    /// error, ex. a branch that already exists, is a failure here.
+    fn git_data(
+        &self,
+        path: &str,
+        body: Option<Body<serde_json::Value>>,
+        method: http::Method,
+    ) -> Result<serde_json::Value> {
+        let url = format!("{}/repos/{}/{}", self.rest_api_basepath, self.path, path);
+        let response = query::github_git_data(
+            &self.runner,
+            &url,
+            body,
+            self.request_headers(),
+            method,
+            ApiOperation::Project,
+        )?;
+        match response.status {
+            204 => Ok(serde_json::Value::Null),
+            200..=299 => json_loads(&response.body),
+            _ => Err(git_data_error(&url, &response).into()),
+        }
+    }
+}
+
+fn git_data_error(url: &str, response: &Response) -> GRError {
+    GRError::RemoteServerError(format!(
+        "Failed to submit request to URL: {} with status code: {} and body: {}",
+        url, response.status, response.body
+    ))
+}
+
+/// Merge commits have more than one parent and cannot be picked without
+/// choosing one of them.
+fn single_parent(commit: &serde_json::Value, sha: &str) -> Result<String> {
+    match commit["parents"]
+        .as_array()
+        .map(|parents| parents.as_slice())
+    {
+        Some([parent]) => Ok(parent["sha"].as_str().unwrap_or_default().to_string()),
+        _ => Err(GRError::OperationNotSupported(format!(
+            "Commit {} is a merge or root commit, which cannot be picked",
+            sha
+        ))
+        .into()),
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    /// Status of the commit out of its workflow runs. Empty if it has none.
+    fn workflow_run_status(&self, sha: &str) -> Result<String> {
+        // https://docs.github.com/en/rest/actions/workflow-runs?apiVersion=2022-11-28#list-workflow-runs-for-a-repository
+        let mut url = URLQueryParamBuilder::new(&format!(
+            "{}/repos/{}/actions/runs",
+            self.rest_api_basepath, self.path
+        ));
+        url.add_param("head_sha", &encode_query_param(sha));
+        let list_args = ListBodyArgs::builder()
+            .page(1)
+            .max_pages(1)
+            .per_page(Some(100))
+            .build()
+            .unwrap();
+        let runs = query::github_list_pipelines(
+            &self.runner,
+            &url.build(),
+            Some(list_args),
+            self.request_headers(),
+            Some("workflow_runs"),
+            ApiOperation::Pipeline,
+        )?;
+        if runs.is_empty() {
+            return Ok(String::new());
+        }
+        let statuses = runs
+            .iter()
+            .map(|run| run.status.as_str())
+            .collect::<Vec<_>>();
+        Ok(combined_status(&statuses))
+    }
+}
+
+/// A commit is as good as its worst workflow run: the first failed one, else
+/// the first one not done yet, else success.
+fn combined_status(statuses: &[&str]) -> String {
+    const FAILED: &[&str] = &[
+        "failure",
+        "cancelled",
+        "timed_out",
+        "action_required",
+        "startup_failure",
+        "stale",
+    ];
+    const DONE: &[&str] = &["success", "skipped", "neutral"];
+    statuses
+        .iter()
+        .find(|status| FAILED.contains(status))
+        .or_else(|| statuses.iter().find(|status| !DONE.contains(status)))
+        .map_or("success", |status| status)
+        .to_string()
+}
+
+impl<R: HttpRunner<Response = Response>> ProjectFile for Github<R> {
+    fn raw(&self, path: &str, ref_: Option<&str>) -> Result<Vec<u8>> {
+        // https://docs.github.com/en/rest/repos/contents?apiVersion=2022-11-28#get-repository-content
+        let path = path
+            .trim_start_matches('/')
+            .split('/')
+            .map(encode_query_param)
+            .collect::<Vec<_>>()
+            .join("/");
+        let base_url = format!(
+            "{}/repos/{}/contents/{}",
+            self.rest_api_basepath, self.path, path
+        );
+        let mut url = URLQueryParamBuilder::new(&base_url);
+        if let Some(ref_) = ref_ {
+            url.add_param("ref", &encode_query_param(ref_));
+        }
+        // The contents come base64 encoded so that binary files get through
+        // untouched.
+        let response = query::github_project_file::<_, ()>(
+            &self.runner,
+            &url.build(),
+            None,
+            self.request_headers(),
+            GET,
+            ApiOperation::Project,
+        )?;
+        let file = json_loads(&response.body)?;
+        if file["encoding"] == "base64" {
+            return decode_content(&file["content"]);
+        }
+        // Files over 1 MB come without contents. Their blob still has them.
+        // https://docs.github.com/en/rest/git/blobs?apiVersion=2022-11-28#get-a-blob
+        let sha = file["sha"].as_str().ok_or_else(|| {
+            GRError::RemoteUnexpectedResponseContract(format!("No contents nor blob for {}", path))
+        })?;
+        let blob = self.git_data(&format!("git/blobs/{}", sha), None, GET)?;
+        decode_content(&blob["content"])
+    }
+}
+
+/// Decodes file contents as Github sends them, base64 split in lines.
+fn decode_content(content: &serde_json::Value) -> Result<Vec<u8>> {
+    let content: String = content
+        .as_str()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect();
+    Ok(STANDARD.decode(content)?)
+}
+
+/// Encrypts the value with a libsodium sealed box for the repository public
+/// key, given in base64, as Github expects secrets to be sent.
+fn seal(public_key: &str, value: &str) -> Result<String> {
+    let public_key: [u8; crypto_box::KEY_SIZE] =
+        STANDARD.decode(public_key)?.try_into().map_err(|_| {
+            GRError::RemoteUnexpectedResponseContract(format!(
+                "Public key is not {} bytes long",
+                crypto_box::KEY_SIZE
+            ))
+        })?;
+    let sealed = crypto_box::PublicKey::from(public_key)
+        .seal(&mut OsRng, value.as_bytes())
+        .map_err(|err| error::gen(format!("Could not encrypt the secret: {}", err)))?;
+    Ok(STANDARD.encode(sealed))
+}
+
+impl<R> Github<R> {
+    fn secrets_url(&self, environment: Option<&str>) -> String {
+        match environment {
+            Some(environment) => format!(
+                "{}/repos/{}/environments/{}/secrets",
+                self.rest_api_basepath,
+                self.path,
+                encode_query_param(environment)
+            ),
+            None => format!(
+                "{}/repos/{}/actions/secrets",
+                self.rest_api_basepath, self.path
+            ),
+        }
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Github<R> {
+    /// Approval settings live in the protection rules of the default branch,
+    /// which need to exist beforehand.
+    fn required_reviews_url(&self) -> Result<String> {
+        let CmdInfo::Project(project) = self.get_project_data(None)? else {
+            return Err(GRError::ApplicationError(
+                "get_project_data expects CmdInfo::Project invariant".to_string(),
+            )
+            .into());
+        };
+        Ok(format!(
+            "{}/repos/{}/branches/{}/protection/required_pull_request_reviews",
+            self.rest_api_basepath,
+            self.path,
+            project.default_branch()
+        ))
+    }
+}
+
+impl<R> Github<R> {
+    fn list_project_url(&self, args: &ProjectListBodyArgs, num_pages: bool) -> String {
+        let filters = &args.filters;
+        let url = if is_repository_search(args) {
+            // Listing repositories cannot filter by name or archived state.
+            // The search API can, but only for repositories owned by the user.
+            // Doc:
+            // https://docs.github.com/en/rest/search/search?apiVersion=2022-11-28#search-repositories
+            let username = &args.user.as_ref().unwrap().username;
+            let mut query = Vec::new();
+            if let Some(search) = &filters.search {
+                query.push(encode_query_param(search));
+            }
+            query.push(format!("user:{}", username));
+            if filters.archived {
+                query.push("archived:true".to_string());
+            }
+            format!(
+                "{}/search/repositories?q={}",
+                self.rest_api_basepath,
+                query.join("+")
+            )
+        } else if args.stars {
+            format!("{}/user/starred", self.rest_api_basepath)
+        } else if filters.owned || filters.member {
+            // Doc:
+            // https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#list-repositories-for-the-authenticated-user
+            let mut affiliation = Vec::new();
+            if filters.owned {
+                affiliation.push("owner");
+            }
+            if filters.member {
+                affiliation.push("collaborator,organization_member");
+            }
+            format!(
+                "{}/user/repos?affiliation={}",
+                self.rest_api_basepath,
+                affiliation.join(",")
+            )
+        } else {
+            let username = args.user.as_ref().unwrap().clone().username;
+            // TODO - not needed - just /user/repos would do
+            // See: https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#list-repositories-for-the-authenticated-user
+            format!("{}/users/{}/repos", self.rest_api_basepath, username)
+        };
+        if num_pages {
+            return URLQueryParamBuilder::new(&url)
+                .add_param("page", "1")
+                .build();
+        }
+        url
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> ProjectTemplate for Github<R> {
+    fn create_from_template(&self, args: ProjectTemplateBodyArgs) -> Result<NewProject> {
+        // Doc:
+        // https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#create-a-repository-using-a-template
+        let url = format!("{}/repos/{}/generate", self.rest_api_basepath, self.path);
+        let mut body = Body::new();
+        body.add("name", serde_json::Value::from(args.name.clone()));
+        if let Some(owner) = args.owner {
+            body.add("owner", serde_json::Value::from(owner));
+        }
+        if let Some(description) = args.description {
+            body.add("description", serde_json::Value::from(description));
+        }
+        body.add("private", serde_json::Value::from(args.private));
+        let response = query::github_new_project(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::Project,
+        )?;
+        // Github answers 422 when the name is already taken by the owner.
+        if response.status != 201 {
+            return Err(GRError::RemoteServerError(format!(
+                "Could not create repository {}, the name may be taken. \
+                 Status code: {} and body: {}",
+                args.name, response.status, response.body
+            ))
+            .into());
+        }
+        Ok(GithubNewProjectFields::from(&json_loads(&response.body)?).into())
+    }
+
+    fn set_topics(&self, project: &NewProject, topics: &[String]) -> Result<()> {
+        // Doc:
+        // https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#replace-all-repository-topics
+        let url = format!("{}/repos/{}/topics", self.rest_api_basepath, project.path);
+        let mut body = Body::new();
+        body.add("names", serde_json::Value::from(topics.to_vec()));
+        query::github_update_project(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            PUT,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+
+    fn create_label(&self, project: &NewProject, name: &str) -> Result<()> {
+        // Doc:
+        // https://docs.github.com/en/rest/issues/labels?apiVersion=2022-11-28#create-a-label
+        let url = format!("{}/repos/{}/labels", self.rest_api_basepath, project.path);
+        let mut body = Body::new();
+        body.add("name", name.to_string());
+        query::github_update_project(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            POST,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+}
+
+pub struct GithubProjectFields {
+    id: i64,
+    default_branch: String,
+    html_url: String,
+    created_at: String,
+}
+
+impl From<&serde_json::Value> for GithubProjectFields {
+    fn from(project_data: &serde_json::Value) -> Self {
+        GithubProjectFields {
+            id: project_data["id"].as_i64().unwrap(),
+            default_branch: project_data["default_branch"]
+                .to_string()
+                .trim_matches('"')
+                .to_string(),
+            html_url: project_data["html_url"]
+                .to_string()
+                .trim_matches('"')
+                .to_string(),
+            created_at: project_data["created_at"]
+                .to_string()
+                .trim_matches('"')
+                .to_string(),
+        }
+    }
+}
+
+impl From<GithubProjectFields> for Project {
+    fn from(fields: GithubProjectFields) -> Self {
+        Project::new(fields.id, &fields.default_branch)
+            .with_html_url(&fields.html_url)
+            .with_created_at(&fields.created_at)
+    }
+}
+
+pub struct GithubAuditedProjectFields {
+    id: i64,
+    full_name: String,
+    visibility: String,
+    license: String,
+    default_branch: String,
+    created_at: String,
+}
+
+impl From<&serde_json::Value> for GithubAuditedProjectFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let license = &data["license"];
+        GithubAuditedProjectFields {
+            id: data["id"].as_i64().unwrap(),
+            full_name: data["full_name"].as_str().unwrap().to_string(),
+            visibility: data["visibility"].as_str().unwrap_or_default().to_string(),
+            // Licenses Github does not recognize have NOASSERTION as their
+            // SPDX id.
+            license: match license["spdx_id"].as_str() {
+                Some("NOASSERTION") | None => license["name"].as_str().unwrap_or_default(),
+                Some(spdx_id) => spdx_id,
+            }
+            .to_string(),
+            default_branch: data["default_branch"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            created_at: data["created_at"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GithubAuditedProjectFields> for AuditedProject {
+    fn from(fields: GithubAuditedProjectFields) -> Self {
+        AuditedProject::builder()
+            .id(fields.id)
+            .path(fields.full_name)
+            .visibility(fields.visibility)
+            .license(fields.license)
+            .default_branch(fields.default_branch)
+            .created_at(fields.created_at)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GithubNewProjectFields {
+    id: i64,
+    full_name: String,
+    html_url: String,
+    ssh_url: String,
+}
+
+impl From<&serde_json::Value> for GithubNewProjectFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubNewProjectFields {
+            id: data["id"].as_i64().unwrap(),
+            full_name: data["full_name"].as_str().unwrap().to_string(),
+            html_url: data["html_url"].as_str().unwrap_or_default().to_string(),
+            ssh_url: data["ssh_url"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GithubNewProjectFields> for NewProject {
+    fn from(fields: GithubNewProjectFields) -> Self {
+        NewProject::builder()
+            .id(fields.id)
+            .path(fields.full_name)
+            .web_url(fields.html_url)
+            .clone_url(fields.ssh_url)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GithubApprovalSettingsFields {
+    required_approving_review_count: u32,
+    dismiss_stale_reviews: bool,
+}
+
+impl From<&serde_json::Value> for GithubApprovalSettingsFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubApprovalSettingsFields {
+            required_approving_review_count: data["required_approving_review_count"]
+                .as_u64()
+                .unwrap_or_default() as u32,
+            dismiss_stale_reviews: data["dismiss_stale_reviews"].as_bool().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GithubApprovalSettingsFields> for ApprovalSettings {
+    fn from(fields: GithubApprovalSettingsFields) -> Self {
+        ApprovalSettings::builder()
+            .required(fields.required_approving_review_count)
+            .reset_on_push(fields.dismiss_stale_reviews)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GithubRulesetFields {
+    ruleset: Ruleset,
+}
+
+/// The rule type along with its scalar parameters, ex.
+/// `pull_request(required_approving_review_count=2)`.
+fn github_rule(rule: &serde_json::Value) -> String {
+    let rule_type = rule["type"].as_str().unwrap_or_default();
+    let parameters = rule["parameters"]
+        .as_object()
+        .map(|parameters| {
+            parameters
+                .iter()
+                .filter_map(|(name, value)| match value {
+                    serde_json::Value::Bool(_) | serde_json::Value::Number(_) => {
+                        Some(format!("{}={}", name, value))
+                    }
+                    serde_json::Value::String(value) if !value.is_empty() => {
+                        Some(format!("{}={}", name, value))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if parameters.is_empty() {
+        rule_type.to_string()
+    } else {
+        format!("{}({})", rule_type, parameters.join(" "))
+    }
+}
+
+impl From<&serde_json::Value> for GithubRulesetFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let ref_name = &data["conditions"]["ref_name"];
+        let patterns = |kind: &str| -> Vec<String> {
+            ref_name[kind]
+                .as_array()
+                .map(|patterns| {
+                    patterns
+                        .iter()
+                        .filter_map(|pattern| pattern.as_str())
+                        .map(|pattern| pattern.to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let mut refs = patterns("include");
+        refs.extend(
+            patterns("exclude")
+                .into_iter()
+                .map(|pattern| format!("!{}", pattern)),
+        );
+        let rules = data["rules"]
+            .as_array()
+            .map(|rules| rules.iter().map(github_rule).collect())
+            .unwrap_or_default();
+        GithubRulesetFields {
+            ruleset: Ruleset::builder()
+                .id(data["id"].as_i64().unwrap_or_default())
+                .name(data["name"].as_str().unwrap_or_default().to_string())
+                .target(data["target"].as_str().unwrap_or_default().to_string())
+                .enforcement(data["enforcement"].as_str().unwrap_or_default().to_string())
+                .source(data["source"].as_str().unwrap_or_default().to_string())
+                .refs(refs)
+                .rules(rules)
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GithubRulesetFields> for Ruleset {
+    fn from(fields: GithubRulesetFields) -> Self {
+        fields.ruleset
+    }
+}
+
+pub struct GithubBranchProtectionFields {
+    required_approvals: u32,
+    push: Vec<String>,
+    allow_force_push: bool,
+}
+
+impl From<&serde_json::Value> for GithubBranchProtectionFields {
+    fn from(data: &serde_json::Value) -> Self {
+        // No restrictions means anyone with write access can push.
+        let restrictions = &data["restrictions"];
+        let push = if restrictions.is_object() {
+            let mut push = Vec::new();
+            for (kind, field) in [("users", "login"), ("teams", "slug"), ("apps", "slug")] {
+                if let Some(items) = restrictions[kind].as_array() {
+                    push.extend(
+                        items
+                            .iter()
+                            .filter_map(|item| item[field].as_str())
+                            .map(|name| name.to_string()),
+                    );
+                }
+            }
+            if push.is_empty() {
+                push.push("Admins".to_string());
+            }
+            push
+        } else {
+            Vec::new()
+        };
+        GithubBranchProtectionFields {
+            required_approvals: data["required_pull_request_reviews"]
+                ["required_approving_review_count"]
+                .as_u64()
+                .unwrap_or_default() as u32,
+            push,
+            allow_force_push: data["allow_force_pushes"]["enabled"]
+                .as_bool()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GithubBranchProtectionFields> for ProtectedBranch {
+    fn from(fields: GithubBranchProtectionFields) -> Self {
+        ProtectedBranch::builder()
+            .name(String::new())
+            .required_approvals(fields.required_approvals)
+            .push(fields.push)
+            .allow_force_push(fields.allow_force_push)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GithubMemberFields {
+    id: i64,
+    login: String,
+    name: String,
+    created_at: String,
+}
+
+impl From<&serde_json::Value> for GithubMemberFields {
+    fn from(member_data: &serde_json::Value) -> Self {
+        GithubMemberFields {
+            id: member_data["id"].as_i64().unwrap(),
+            login: member_data["login"].as_str().unwrap().to_string(),
+            name: "".to_string(),
+            // Github does not provide created_at field in the response for
+            // Members (aka contributors). Set it to UNIX epoch.
+            created_at: "1970-01-01T00:00:00Z".to_string(),
+        }
+    }
+}
+
+impl From<GithubMemberFields> for Member {
+    fn from(fields: GithubMemberFields) -> Self {
+        Member::builder()
+            .id(fields.id)
+            .username(fields.login)
+            .name(fields.name)
+            .created_at(fields.created_at)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GithubMilestoneFields {
+    number: i64,
+    title: String,
+    state: String,
+}
+
+impl From<&serde_json::Value> for GithubMilestoneFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubMilestoneFields {
+            number: data["number"].as_i64().unwrap(),
+            title: data["title"].as_str().unwrap().to_string(),
+            state: data["state"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GithubMilestoneFields> for Milestone {
+    fn from(fields: GithubMilestoneFields) -> Self {
+        // Pull requests reference milestones by number, not by id.
+        Milestone::builder()
+            .id(fields.number)
+            .title(fields.title)
+            .state(fields.state)
+            .build()
+            .unwrap()
+    }
+}
+
+/// Commit of the Git database API, which comes with a shape of its own.
+pub struct GithubGitCommitFields {
+    commit: Commit,
+}
+
+impl From<&serde_json::Value> for GithubGitCommitFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let title = data["message"]
+            .as_str()
+            .unwrap_or_default()
+            .lines()
+            .next()
+            .unwrap_or_default();
+        GithubGitCommitFields {
+            commit: Commit::builder()
+                .sha(data["sha"].as_str().unwrap_or_default().to_string())
+                .title(title.to_string())
+                .author(
+                    data["author"]["name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .date(
+                    data["committer"]["date"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .web_url(data["html_url"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GithubGitCommitFields> for Commit {
+    fn from(fields: GithubGitCommitFields) -> Self {
+        fields.commit
+    }
+}
+
+pub struct GithubCommitFields {
+    commit: Commit,
+}
+
+impl From<&serde_json::Value> for GithubCommitFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let commit = &data["commit"];
+        let title = commit["message"]
+            .as_str()
+            .unwrap_or_default()
+            .lines()
+            .next()
+            .unwrap_or_default();
+        GithubCommitFields {
+            commit: Commit::builder()
+                .sha(data["sha"].as_str().unwrap_or_default().to_string())
+                .title(title.to_string())
+                .author(
+                    commit["author"]["name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .date(
+                    commit["committer"]["date"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .web_url(data["html_url"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GithubCommitFields> for Commit {
+    fn from(fields: GithubCommitFields) -> Self {
+        fields.commit
+    }
+}
+
+pub struct GithubSecretFields {
+    name: String,
+}
+
+impl From<&serde_json::Value> for GithubSecretFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubSecretFields {
+            name: data["name"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GithubSecretFields> for ProjectVariable {
+    fn from(fields: GithubSecretFields) -> Self {
+        // Secret values can never be read back.
+        ProjectVariable::builder()
+            .key(fields.name)
+            .masked(true)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GithubCollaboratorFields {
+    id: i64,
+    login: String,
+    role_name: String,
+}
+
+impl From<&serde_json::Value> for GithubCollaboratorFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GithubCollaboratorFields {
+            id: data["id"].as_i64().unwrap(),
+            login: data["login"].as_str().unwrap().to_string(),
+            role_name: data["role_name"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GithubCollaboratorFields> for Member {
+    fn from(fields: GithubCollaboratorFields) -> Self {
+        // Collaborators come with neither the date they joined nor an account
+        // state, Github does not block users at the repository level.
+        Member::builder()
+            .id(fields.id)
+            .username(fields.login)
+            .name("".to_string())
+            .role(fields.role_name)
+            .build()
+            .unwrap()
+    }
+}
+
+fn is_repository_search(args: &ProjectListBodyArgs) -> bool {
+    args.filters.search.is_some() || args.filters.archived
+}
+
+/// The search API only finds repositories owned by the user, so it cannot
+/// narrow down starred repositories nor the ones the user collaborates on.
+fn check_repository_search(args: &ProjectListBodyArgs) -> Result<()> {
+    if is_repository_search(args) && (args.stars || args.filters.member) {
+        return Err(GRError::OperationNotSupported(
+            "Github cannot search or filter archived repositories among starred ones or \
+             the ones you are a member of. Use --owned instead"
+                .to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        cmds::project::{ProjectListBodyArgs, ProjectListFilters},
+        http::Headers,
+        test::utils::{config, get_contract, ContractType, MockRunner},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_list_organization_repositories_for_audit() {
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "stars.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn ProjectAudit> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/gitar",
+            client.clone(),
+        ));
+        let projects = github.list("jordilin").unwrap();
+        assert_eq!(1, projects.len());
+        assert_eq!("jordilin/gitar", projects[0].path);
+        assert_eq!("public", projects[0].visibility);
+        assert_eq!("MIT", projects[0].license);
+        assert_eq!("https://api.github.com/orgs/jordilin/repos", *client.url());
+    }
+
+    #[test]
+    fn test_audit_repository_default_branch_protection() {
+        let response = Response::builder()
+            .status(200)
+            .body(r#"{"name":"main","protected":false}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn ProjectAudit> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/gitar",
+            client.clone(),
+        ));
+        let project = AuditedProject::builder()
+            .id(1)
+            .path("jordilin/gitar".to_string())
+            .visibility("public".to_string())
+            .license("MIT".to_string())
+            .default_branch("main".to_string())
+            .created_at("2024-01-01T00:00:00Z".to_string())
+            .build()
+            .unwrap();
+        let project = github.audit(project).unwrap();
+        assert!(!project.default_branch_protected);
+        assert_eq!("MIT", project.license);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/gitar/branches/main",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_create_repository_from_template_fails_if_the_name_is_taken() {
+        let response = Response::builder()
+            .status(422)
+            .body(
+                r#"{"message": "Repository creation failed.",
+                    "errors": [{"field": "name", "message": "name already exists on this account"}]}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn ProjectTemplate> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "my-org/service-template",
+            client.clone(),
+        ));
+        let args = ProjectTemplateBodyArgs::builder()
+            .name("my-service".to_string())
+            .build()
+            .unwrap();
+        let err = github.create_from_template(args).unwrap_err().to_string();
+        assert!(err.contains("Could not create repository my-service"));
+        assert!(err.contains("name already exists on this account"));
+    }
+
+    #[test]
+    fn test_create_repository_from_template() {
+        let response = Response::builder()
+            .status(201)
+            .body(
+                r#"{"id": 42, "full_name": "my-org/my-service",
+                    "html_url": "https://github.com/my-org/my-service",
+                    "ssh_url": "git@github.com:my-org/my-service.git"}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn ProjectTemplate> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "my-org/service-template",
+            client.clone(),
+        ));
+        let args = ProjectTemplateBodyArgs::builder()
+            .name("my-service".to_string())
+            .owner(Some("my-org".to_string()))
+            .private(true)
+            .build()
+            .unwrap();
+        let project = github.create_from_template(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/my-org/service-template/generate",
+            *client.url()
+        );
+        let body = client.request_body();
+        assert!(body.contains(r#""name":"my-service""#));
+        assert!(body.contains(r#""owner":"my-org""#));
+        assert!(body.contains(r#""private":true"#));
+        assert_eq!("my-org/my-service", project.path);
+        assert_eq!("git@github.com:my-org/my-service.git", project.clone_url);
+
+        let response = Response::builder().status(200).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn ProjectTemplate> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "my-org/service-template",
+            client.clone(),
+        ));
+        github
+            .set_topics(&project, &["rust".to_string(), "cli".to_string()])
+            .unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/my-org/my-service/topics",
+            *client.url()
+        );
+        assert!(client.request_body().contains(r#""names":["rust","cli"]"#));
+    }
+
+    #[test]
+    fn test_get_project_data_no_id() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "project.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github = Github::new(config, &domain, path, client.clone());
+        github.get_project_data(None).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_project_data_with_id_not_supported() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let client = Arc::new(MockRunner::new(vec![]));
+        let github = Github::new(config, &domain, path, client.clone());
+        assert!(github.get_project_data(Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_list_current_user_projects() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let projects = format!("[{}]", get_contract(ContractType::Github, "project.json"));
+        let response = Response::builder()
+            .status(200)
+            .body(projects)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github = Github::new(config, &domain, path, client.clone());
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jdoe".to_string())
+                    .username("jdoe".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap();
+        let projects = RemoteProject::list(&github, body_args).unwrap();
+        assert_eq!(1, projects.len());
+        assert_eq!("https://api.github.com/users/jdoe/repos", *client.url());
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_my_starred_projects() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let projects = get_contract(ContractType::Github, "stars.json").to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(projects)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github = Github::new(config, &domain, path, client.clone());
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jdoe".to_string())
+                    .username("jdoe".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .stars(true)
+            .build()
+            .unwrap();
+        let projects = RemoteProject::list(&github, body_args).unwrap();
+        assert_eq!(1, projects.len());
+        assert_eq!("https://api.github.com/user/starred", *client.url());
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_project_num_pages_url_for_user() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let link_header = "<https://api.github.com/users/jdoe/repos?page=2>; rel=\"next\", <https://api.github.com/users/jdoe/repos?page=2>; rel=\"last\"";
+        let mut headers = Headers::new();
+        headers.set("link", link_header);
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github = Github::new(config, &domain, path, client.clone());
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jdoe".to_string())
+                    .username("jdoe".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap();
+        github.num_pages(body_args).unwrap();
+        assert_eq!(
+            "https://api.github.com/users/jdoe/repos?page=1",
+            *client.url()
+        );
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_project_num_pages_url_for_starred() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let link_header = "<https://api.github.com/user/starred?page=2>; rel=\"next\", <https://api.github.com/user/starred?page=2>; rel=\"last\"";
+        let mut headers = Headers::new();
+        headers.set("link", link_header);
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github = Github::new(config, &domain, path, client.clone());
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jdoe".to_string())
+                    .username("jdoe".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .stars(true)
+            .build()
+            .unwrap();
+        github.num_pages(body_args).unwrap();
+        assert_eq!("https://api.github.com/user/starred?page=1", *client.url());
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_approval_settings_from_default_branch_protection() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let project = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "project.json"))
+            .build()
+            .unwrap();
+        let reviews = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Github,
+                "required_pull_request_reviews.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![reviews, project]));
+        let github: Box<dyn ProjectApprovals> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let settings = github.get().unwrap();
+        assert_eq!(2, settings.required);
+        assert!(settings.reset_on_push);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/branches/main/protection/required_pull_request_reviews",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_set_approval_settings_patches_required_reviews() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let project = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "project.json"))
+            .build()
+            .unwrap();
+        let reviews = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Github,
+                "required_pull_request_reviews.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![reviews, project]));
+        let github: Box<dyn ProjectApprovals> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = ApprovalSettingsBodyArgs::builder()
+            .required(Some(2))
+            .build()
+            .unwrap();
+        github.set(args).unwrap();
+        assert_eq!(PATCH, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_access_requests_not_supported() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let client = Arc::new(MockRunner::new(vec![]));
+        let github: Box<dyn ProjectAccessRequests> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        match github.deny(1) {
+            Err(err) => match err.downcast_ref::<GRError>() {
+                Some(GRError::OperationNotSupported(_)) => (),
+                _ => panic!("Expected OperationNotSupported"),
+            },
+            Ok(_) => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_get_milestone_by_title_uses_number() {
+        let config = config();
+        let domain = "github.com";
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"id": 1002, "number": 2, "title": "Release 1.1", "state": "open"},
+                    {"id": 1001, "number": 1, "title": "Release 1.0", "state": "closed"}]"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn ProjectMilestones> =
+            Box::new(Github::new(config, domain, path, client.clone()));
+        let milestone = github.get("Release 1.0").unwrap();
+        assert_eq!(1, milestone.id);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/milestones?state=all&per_page=100",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_collaborators_with_role() {
         let config = config();
         let domain = "github.com".to_string();
         let path = "jordilin/githapi";
-        let projects = format!("{}", get_contract(ContractType::Github, "stars.json"));
         let response = Response::builder()
             .status(200)
-            .body(projects)
+            .body(get_contract(
+                ContractType::Github,
+                "list_collaborators.json",
+            ))
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github = Github::new(config, &domain, &path, client.clone());
-        let body_args = ProjectListBodyArgs::builder()
+        let github: Box<dyn ProjectMembers> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let args = MemberListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let members = github.list(args).unwrap();
+        assert_eq!(2, members.len());
+        assert_eq!("jordilin", members[0].username);
+        assert_eq!("admin", members[0].role);
+        assert_eq!("write", members[1].role);
+        assert_eq!("", members[1].state);
+        assert_eq!("", members[1].created_at);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/collaborators",
+            *client.url()
+        );
+    }
+
+    fn user_projects_body_args(filters: ProjectListFilters) -> ProjectListBodyArgs {
+        ProjectListBodyArgs::builder()
             .from_to_page(None)
             .user(Some(
                 Member::builder()
@@ -267,80 +2085,576 @@ mod test {
                     .build()
                     .unwrap(),
             ))
-            .stars(true)
+            .filters(filters)
             .build()
-            .unwrap();
-        let projects = github.list(body_args).unwrap();
-        assert_eq!(1, projects.len());
-        assert_eq!("https://api.github.com/user/starred", *client.url());
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+            .unwrap()
     }
 
     #[test]
-    fn test_get_project_num_pages_url_for_user() {
+    fn test_list_projects_search_uses_search_api_items() {
         let config = config();
         let domain = "github.com".to_string();
         let path = "jordilin/githapi";
-        let link_header = "<https://api.github.com/users/jdoe/repos?page=2>; rel=\"next\", <https://api.github.com/users/jdoe/repos?page=2>; rel=\"last\"";
-        let mut headers = Headers::new();
-        headers.set("link", link_header);
+        let projects = format!(
+            "{{\"total_count\": 1, \"items\": [{}]}}",
+            get_contract(ContractType::Github, "project.json")
+        );
         let response = Response::builder()
             .status(200)
-            .headers(headers)
+            .body(projects)
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github = Github::new(config, &domain, &path, client.clone());
-        let body_args = ProjectListBodyArgs::builder()
-            .from_to_page(None)
-            .user(Some(
-                Member::builder()
-                    .id(1)
-                    .name("jdoe".to_string())
-                    .username("jdoe".to_string())
-                    .build()
-                    .unwrap(),
-            ))
+        let github = Github::new(config, &domain, path, client.clone());
+        let filters = ProjectListFilters::builder()
+            .search(Some("git tools".to_string()))
+            .archived(true)
             .build()
             .unwrap();
-        github.num_pages(body_args).unwrap();
+        let projects = RemoteProject::list(&github, user_projects_body_args(filters)).unwrap();
+        assert_eq!(1, projects.len());
         assert_eq!(
-            "https://api.github.com/users/jdoe/repos?page=1",
+            "https://api.github.com/search/repositories?q=git%20tools+user:jdoe+archived:true",
             *client.url()
         );
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
     }
 
     #[test]
-    fn test_get_project_num_pages_url_for_starred() {
+    fn test_list_projects_search_among_starred_or_member_is_not_supported() {
+        let client = Arc::new(MockRunner::new(vec![]));
+        let github = Github::new(config(), "github.com", "jordilin/githapi", client.clone());
+        let filters = ProjectListFilters::builder()
+            .search(Some("gitar".to_string()))
+            .member(true)
+            .build()
+            .unwrap();
+        let err = RemoteProject::list(&github, user_projects_body_args(filters)).unwrap_err();
+        match err.downcast_ref::<GRError>() {
+            Some(GRError::OperationNotSupported(_)) => {}
+            _ => panic!("Expected OperationNotSupported, got {}", err),
+        }
+        let mut args = user_projects_body_args(
+            ProjectListFilters::builder()
+                .archived(true)
+                .build()
+                .unwrap(),
+        );
+        args.stars = true;
+        assert!(RemoteProject::num_pages(&github, args).is_err());
+    }
+
+    #[test]
+    fn test_list_projects_owned_and_member_affiliation() {
         let config = config();
         let domain = "github.com".to_string();
         let path = "jordilin/githapi";
-        let link_header = "<https://api.github.com/user/starred?page=2>; rel=\"next\", <https://api.github.com/user/starred?page=2>; rel=\"last\"";
-        let mut headers = Headers::new();
-        headers.set("link", link_header);
         let response = Response::builder()
             .status(200)
-            .headers(headers)
+            .body("[]".to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github = Github::new(config, &domain, &path, client.clone());
-        let body_args = ProjectListBodyArgs::builder()
-            .from_to_page(None)
-            .user(Some(
-                Member::builder()
-                    .id(1)
-                    .name("jdoe".to_string())
-                    .username("jdoe".to_string())
-                    .build()
-                    .unwrap(),
+        let github = Github::new(config, &domain, path, client.clone());
+        let filters = ProjectListFilters::builder()
+            .owned(true)
+            .member(true)
+            .build()
+            .unwrap();
+        RemoteProject::list(&github, user_projects_body_args(filters)).unwrap();
+        assert_eq!(
+            "https://api.github.com/user/repos?affiliation=owner,collaborator,organization_member",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_set_secret_is_sealed_with_repository_public_key() {
+        let config = config();
+        let domain = "github.com";
+        let path = "jordilin/githapi";
+        let secret_key = crypto_box::SecretKey::generate(&mut OsRng);
+        let public_key = Response::builder()
+            .status(200)
+            .body(format!(
+                r#"{{"key_id": "568250167242549743", "key": "{}"}}"#,
+                STANDARD.encode(secret_key.public_key().as_bytes())
             ))
-            .stars(true)
             .build()
             .unwrap();
-        github.num_pages(body_args).unwrap();
-        assert_eq!("https://api.github.com/user/starred?page=1", *client.url());
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+        let created = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![created, public_key]));
+        let github: Box<dyn ProjectVariables> =
+            Box::new(Github::new(config, domain, path, client.clone()));
+        let args = ProjectVariableBodyArgs::builder()
+            .key("DEPLOY_TOKEN".to_string())
+            .value("s3cr3t".to_string())
+            .build()
+            .unwrap();
+        github.set(args).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/secrets/DEPLOY_TOKEN",
+            *client.url(),
+        );
+        assert_eq!(PUT, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!("568250167242549743", body["key_id"]);
+        let sealed = STANDARD
+            .decode(body["encrypted_value"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(b"s3cr3t".to_vec(), secret_key.unseal(&sealed).unwrap());
+    }
+
+    #[test]
+    fn test_list_environment_secrets_are_masked() {
+        let config = config();
+        let domain = "github.com";
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"{"total_count": 1, "secrets": [{"name": "DEPLOY_TOKEN", "created_at": "2024-01-10T10:00:00Z", "updated_at": "2024-01-10T10:00:00Z"}]}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn ProjectVariables> =
+            Box::new(Github::new(config, domain, path, client.clone()));
+        let secrets = github.list(Some("production")).unwrap();
+        assert_eq!("DEPLOY_TOKEN", secrets[0].key);
+        assert!(secrets[0].masked);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/environments/production/secrets",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_set_branch_protection_keeps_current_rules() {
+        let config = config();
+        let domain = "github.com";
+        let path = "jordilin/githapi";
+        let branch = Response::builder()
+            .status(200)
+            .body(r#"{"name": "main", "protected": true}"#.to_string())
+            .build()
+            .unwrap();
+        let current = Response::builder()
+            .status(200)
+            .body(
+                r#"{"required_status_checks": {"strict": true, "contexts": ["ci"],
+                        "checks": [{"context": "ci", "app_id": 15368}]},
+                    "enforce_admins": {"enabled": true},
+                    "required_pull_request_reviews": {"dismiss_stale_reviews": true, "required_approving_review_count": 1,
+                        "require_last_push_approval": true,
+                        "dismissal_restrictions": {"users": [{"login": "octocat"}], "teams": [], "apps": []}},
+                    "restrictions": {"users": [{"login": "octocat"}], "teams": [], "apps": []},
+                    "required_linear_history": {"enabled": true},
+                    "allow_force_pushes": {"enabled": false},
+                    "allow_deletions": {"enabled": false},
+                    "required_conversation_resolution": {"enabled": true},
+                    "lock_branch": {"enabled": false}}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let updated = Response::builder()
+            .status(200)
+            .body(
+                r#"{"required_pull_request_reviews": {"required_approving_review_count": 2},
+                    "restrictions": {"users": [{"login": "octocat"}], "teams": [], "apps": []},
+                    "allow_force_pushes": {"enabled": false}}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![updated, current, branch]));
+        let github: Box<dyn ProjectBranchProtection> =
+            Box::new(Github::new(config, domain, path, client.clone()));
+        let args = BranchProtectionBodyArgs::builder()
+            .branch("main".to_string())
+            .required_approvals(Some(2))
+            .build()
+            .unwrap();
+        let protected = github.set(args).unwrap();
+        assert_eq!("main", protected.name);
+        assert_eq!(2, protected.required_approvals);
+        assert_eq!(vec!["octocat"], protected.push);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/branches/main/protection",
+            *client.url(),
+        );
+        assert_eq!(PUT, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(
+            serde_json::json!([{"context": "ci", "app_id": 15368}]),
+            body["required_status_checks"]["checks"]
+        );
+        assert_eq!(serde_json::json!(true), body["enforce_admins"]);
+        assert_eq!(serde_json::json!(true), body["required_linear_history"]);
+        assert_eq!(
+            serde_json::json!(true),
+            body["required_conversation_resolution"]
+        );
+        assert_eq!(serde_json::json!(false), body["allow_deletions"]);
+        assert_eq!(
+            serde_json::json!(true),
+            body["required_pull_request_reviews"]["require_last_push_approval"]
+        );
+        assert_eq!(
+            serde_json::json!(["octocat"]),
+            body["required_pull_request_reviews"]["dismissal_restrictions"]["users"]
+        );
+        assert_eq!(
+            serde_json::json!(2),
+            body["required_pull_request_reviews"]["required_approving_review_count"]
+        );
+        assert_eq!(
+            serde_json::json!(["octocat"]),
+            body["restrictions"]["users"]
+        );
+    }
+
+    #[test]
+    fn test_get_unprotected_branch_is_error() {
+        let config = config();
+        let domain = "github.com";
+        let path = "jordilin/githapi";
+        let branch = Response::builder()
+            .status(200)
+            .body(r#"{"name": "feature", "protected": false}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![branch]));
+        let github: Box<dyn ProjectBranchProtection> =
+            Box::new(Github::new(config, domain, path, client.clone()));
+        match github.get("feature") {
+            Err(err) => match err.downcast_ref::<GRError>() {
+                Some(GRError::PreconditionNotMet(msg)) => {
+                    assert_eq!("Branch feature is not protected", msg)
+                }
+                _ => panic!("Expected PreconditionNotMet"),
+            },
+            Ok(_) => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_raw_file_on_default_branch() {
+        let config = config();
+        let domain = "github.com";
+        let path = "jordilin/githapi";
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"{"sha": "3d21ec5", "encoding": "base64", "content": "bmFtZTog\nQ0kK\n"}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn ProjectFile> =
+            Box::new(Github::new(config, domain, path, client.clone()));
+        let contents = github.raw(".github/workflows/ci.yml", None).unwrap();
+        assert_eq!(b"name: CI\n".to_vec(), contents);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/contents/.github/workflows/ci.yml",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_raw_large_binary_file_is_read_from_its_blob() {
+        let config = config();
+        let domain = "github.com";
+        let path = "jordilin/githapi";
+        let file = Response::builder()
+            .status(200)
+            .body(r#"{"sha": "3d21ec5", "encoding": "none", "content": ""}"#.to_string())
+            .build()
+            .unwrap();
+        let blob = Response::builder()
+            .status(200)
+            .body(
+                r#"{"sha": "3d21ec5", "encoding": "base64", "content": "iVBORw0KGgo="}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![blob, file]));
+        let github: Box<dyn ProjectFile> =
+            Box::new(Github::new(config, domain, path, client.clone()));
+        let contents = github.raw("docs/logo.png", Some("v1.0")).unwrap();
+        assert_eq!(
+            vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'],
+            contents
+        );
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/git/blobs/3d21ec5",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_rulesets_fetches_each_ruleset() {
+        let config = config();
+        let domain = "github.com";
+        let path = "jordilin/githapi";
+        let rulesets = Response::builder()
+            .status(200)
+            .body(r#"[{"id": 42, "name": "main", "target": "branch"}]"#.to_string())
+            .build()
+            .unwrap();
+        let ruleset = Response::builder()
+            .status(200)
+            .body(
+                r#"{"id": 42, "name": "main", "target": "branch", "source_type": "Organization",
+                    "source": "jordilin", "enforcement": "active",
+                    "conditions": {"ref_name": {"include": ["~DEFAULT_BRANCH"], "exclude": ["refs/heads/tmp"]}},
+                    "rules": [{"type": "deletion"},
+                              {"type": "pull_request", "parameters": {"required_approving_review_count": 2,
+                               "require_code_owner_review": true, "required_reviewers": []}}]}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![ruleset, rulesets]));
+        let github: Box<dyn ProjectRulesets> =
+            Box::new(Github::new(config, domain, path, client.clone()));
+        let rulesets = github.list().unwrap();
+        assert_eq!(1, rulesets.len());
+        assert_eq!("jordilin", rulesets[0].source);
+        assert_eq!(vec!["~DEFAULT_BRANCH", "!refs/heads/tmp"], rulesets[0].refs);
+        assert_eq!(
+            vec![
+                "deletion",
+                "pull_request(require_code_owner_review=true required_approving_review_count=2)"
+            ],
+            rulesets[0].rules
+        );
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/rulesets/42",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_get_commit_with_the_status_of_its_workflow_runs() {
+        let commit = Response::builder()
+            .status(200)
+            .body(
+                r#"{"sha": "ed899a2f", "html_url": "https://github.com/jordilin/githapi/commit/ed899a2f",
+                    "commit": {"message": "Fix the release\n\nLonger description",
+                               "author": {"name": "Jordi", "date": "2024-03-02T09:00:00Z"},
+                               "committer": {"name": "Jordi", "date": "2024-03-02T10:00:00Z"}}}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let runs = Response::builder()
+            .status(200)
+            .body(
+                r#"{"workflow_runs": [
+                    {"id": 1, "status": "completed", "conclusion": "success", "html_url": "",
+                     "head_branch": "main", "head_sha": "ed899a2f",
+                     "created_at": "2024-03-02T10:01:00Z", "updated_at": "2024-03-02T10:05:00Z"},
+                    {"id": 2, "status": "in_progress", "conclusion": null, "html_url": "",
+                     "head_branch": "main", "head_sha": "ed899a2f",
+                     "created_at": "2024-03-02T10:01:00Z", "updated_at": "2024-03-02T10:05:00Z"}]}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![runs, commit]));
+        let github: Box<dyn CommitApi> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let commit = github.get("ed899a2f").unwrap();
+        assert_eq!("Fix the release", commit.title);
+        assert_eq!("2024-03-02T10:00:00Z", commit.date);
+        assert_eq!("in_progress", commit.status);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs?head_sha=ed899a2f&page=1&per_page=100",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_commits_looks_up_the_runs_of_each_commit() {
+        let commits = json_response(
+            200,
+            r#"[{"sha": "ed899a2f", "html_url": "",
+                 "commit": {"message": "Fix the release",
+                            "author": {"name": "Jordi", "date": "2024-03-02T09:00:00Z"},
+                            "committer": {"name": "Jordi", "date": "2024-03-02T10:00:00Z"}}},
+                {"sha": "6104942a", "html_url": "",
+                 "commit": {"message": "Add changelog",
+                            "author": {"name": "Jordi", "date": "2024-03-01T09:00:00Z"},
+                            "committer": {"name": "Jordi", "date": "2024-03-01T10:00:00Z"}}}]"#,
+        );
+        let runs = json_response(
+            200,
+            r#"{"workflow_runs": [
+                {"id": 1, "status": "completed", "conclusion": "failure", "html_url": "",
+                 "head_branch": "main", "head_sha": "ed899a2f",
+                 "created_at": "2024-03-02T10:01:00Z", "updated_at": "2024-03-02T10:05:00Z"}]}"#,
+        );
+        let no_runs = json_response(200, r#"{"workflow_runs": []}"#);
+        let client = Arc::new(MockRunner::new(vec![runs, no_runs, commits]));
+        let github: Box<dyn CommitApi> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let args = CommitListBodyArgs::builder()
+            .ref_(Some("v1.2".to_string()))
+            .build()
+            .unwrap();
+        let commits = github.list(args).unwrap();
+        assert_eq!(2, commits.len());
+        assert_eq!("", commits[0].status);
+        assert_eq!("failure", commits[1].status);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/actions/runs?head_sha=ed899a2f&page=1&per_page=100",
+            *client.url(),
+        );
+    }
+
+    fn json_response(status: i32, body: &str) -> Response {
+        Response::builder()
+            .status(status)
+            .body(body.to_string())
+            .build()
+            .unwrap()
+    }
+
+    // Requests to cherry-pick ed899a2f onto release-1.2 up to the merge, in
+    // the order they are sent.
+    fn cherry_pick_responses(merge: Response) -> Vec<Response> {
+        vec![
+            json_response(
+                200,
+                r#"{"sha": "ed899a2f", "message": "Fix the release",
+                    "author": {"name": "Jordi", "email": "jordi@example.com",
+                               "date": "2024-03-02T09:00:00Z"},
+                    "parents": [{"sha": "6104942a"}]}"#,
+            ),
+            json_response(200, r#"{"object": {"sha": "b0a1c2d3"}}"#),
+            json_response(200, r#"{"sha": "b0a1c2d3", "tree": {"sha": "7e3e1f00"}}"#),
+            json_response(201, r#"{"sha": "c0ffee00"}"#),
+            json_response(201, r#"{"ref": "refs/heads/gr-pick-c0ffee00"}"#),
+            merge,
+            json_response(204, ""),
+        ]
+    }
+
+    #[test]
+    fn test_cherry_pick_commit_onto_branch() {
+        let mut responses = cherry_pick_responses(json_response(
+            201,
+            r#"{"sha": "d00d0000", "commit": {"tree": {"sha": "9a9a9a9a"}}}"#,
+        ));
+        responses.extend(vec![
+            json_response(201, r#"{"sha": "f1f2f3f4"}"#),
+            json_response(200, r#"{"object": {"sha": "f1f2f3f4"}}"#),
+            json_response(
+                200,
+                r#"{"sha": "f1f2f3f4", "message": "Fix the release\n\n(cherry picked from commit ed899a2f)",
+                    "author": {"name": "Jordi"}, "committer": {"date": "2024-03-03T10:00:00Z"},
+                    "html_url": "https://github.com/jordilin/githapi/commit/f1f2f3f4"}"#,
+            ),
+        ]);
+        responses.reverse();
+        let client = Arc::new(MockRunner::new(responses));
+        let github: Box<dyn CommitApi> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let args = CommitPickBodyArgs::builder()
+            .sha("ed899a2f".to_string())
+            .branch("release-1.2".to_string())
+            .build()
+            .unwrap();
+        let commit = github.cherry_pick(args).unwrap();
+        assert_eq!("f1f2f3f4", commit.sha);
+        assert_eq!("Fix the release", commit.title);
+        assert_eq!("2024-03-03T10:00:00Z", commit.date);
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/git/commits/f1f2f3f4",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_cherry_pick_conflict_removes_temporary_branch() {
+        let mut responses =
+            cherry_pick_responses(json_response(409, r#"{"message": "Merge conflict"}"#));
+        responses.reverse();
+        let client = Arc::new(MockRunner::new(responses));
+        let github: Box<dyn CommitApi> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let args = CommitPickBodyArgs::builder()
+            .sha("ed899a2f".to_string())
+            .branch("release-1.2".to_string())
+            .build()
+            .unwrap();
+        let err = github.cherry_pick(args).unwrap_err();
+        assert!(err.to_string().contains("conflict with release-1.2"));
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/git/refs/heads/gr-pick-c0ffee00",
+            *client.url(),
+        );
+        assert_eq!(http::Method::DELETE, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_cannot_pick_merge_commits() {
+        let commit = serde_json::json!({"parents": [{"sha": "a"}, {"sha": "b"}]});
+        assert!(single_parent(&commit, "ed899a2f").is_err());
+    }
+
+    #[test]
+    fn test_combined_status_of_workflow_runs() {
+        assert_eq!("success", combined_status(&["success", "skipped"]));
+        assert_eq!(
+            "failure",
+            combined_status(&["queued", "failure", "success"])
+        );
+        assert_eq!("queued", combined_status(&["success", "queued"]));
+    }
+
+    #[test]
+    fn test_browse_urls() {
+        let client = Arc::new(MockRunner::new(vec![]));
+        let github = Github::new(config(), "github.com", "jordilin/githapi", client);
+        assert_eq!(
+            "https://github.com/jordilin/githapi/actions/runs/1234",
+            github.get_url(BrowseOptions::PipelineId(1234))
+        );
+        assert_eq!(
+            "https://github.com/jordilin/githapi/blob/v1.0.0/README.md",
+            github.get_url(BrowseOptions::File {
+                path: "README.md".to_string(),
+                ref_: Some("v1.0.0".to_string()),
+            })
+        );
+        assert_eq!(
+            "https://github.com/jordilin/githapi/releases",
+            github.get_url(BrowseOptions::Releases)
+        );
+        assert_eq!(
+            "https://github.com/jordilin/githapi/settings",
+            github.get_url(BrowseOptions::Settings)
+        );
     }
 }
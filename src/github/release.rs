@@ -1,8 +1,13 @@
+use serde_json::json;
+
 use crate::{
     api_traits::{ApiOperation, Deploy},
-    cmds::release::{Release, ReleaseBodyArgs},
-    io::{HttpRunner, Response},
-    remote::query,
+    cmds::release::{asset_name, Release, ReleaseBodyArgs, ReleaseCreateBodyArgs},
+    error::GRError,
+    http::{self, Body},
+    io::{HttpRunner, PageCount, Response, Upload},
+    json_loads,
+    remote::{encode_query_param, query},
     Result,
 };
 
@@ -21,7 +26,7 @@ impl<R: HttpRunner<Response = Response>> Deploy for Github<R> {
         )
     }
 
-    fn num_pages(&self) -> Result<Option<u32>> {
+    fn num_pages(&self) -> Result<Option<PageCount>> {
         let url = format!(
             "{}/repos/{}/releases?page=1",
             self.rest_api_basepath, self.path
@@ -29,6 +34,69 @@ impl<R: HttpRunner<Response = Response>> Deploy for Github<R> {
         let headers = self.request_headers();
         query::num_pages(&self.runner, &url, headers, ApiOperation::Release)
     }
+
+    fn create(
+        &self,
+        args: ReleaseCreateBodyArgs,
+        progress: &dyn Fn(&str, u64, u64),
+    ) -> Result<Release> {
+        // Doc:
+        // https://docs.github.com/en/rest/releases/releases?apiVersion=2022-11-28#create-a-release
+        let url = format!("{}/repos/{}/releases", self.rest_api_basepath, self.path);
+        let mut body = Body::new();
+        body.add("tag_name", json!(args.tag));
+        body.add("name", json!(args.title));
+        body.add("body", json!(args.notes));
+        if let Some(target) = args.target {
+            body.add("target_commitish", json!(target));
+        }
+        let response = query::github_create_release(
+            &self.runner,
+            &url,
+            Some(body),
+            self.request_headers(),
+            http::Method::POST,
+            ApiOperation::Release,
+        )?;
+        // Github answers 422 if the tag already has a release. There is no
+        // upload URL to send the assets to then.
+        if response.status != 201 {
+            return Err(GRError::RemoteServerError(format!(
+                "Could not create release {}. Status code: {} and body: {}",
+                args.tag, response.status, response.body
+            ))
+            .into());
+        }
+        let response = json_loads(&response.body)?;
+        // Assets are uploaded to a different host, given as a URI template,
+        // ex. https://uploads.github.com/repos/o/r/releases/1/assets{?name,label}
+        let upload_url = response["upload_url"]
+            .as_str()
+            .unwrap_or_default()
+            .split('{')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        for asset in &args.assets {
+            let name = asset_name(asset);
+            // Doc:
+            // https://docs.github.com/en/rest/releases/assets?apiVersion=2022-11-28#upload-a-release-asset
+            let url = format!("{}?name={}", upload_url, encode_query_param(&name));
+            query::upload(
+                &self.runner,
+                &url,
+                self.request_headers(),
+                http::Method::POST,
+                Upload {
+                    path: asset,
+                    content_type: "application/octet-stream",
+                    progress: &|sent, size| progress(&name, sent, size),
+                },
+                ApiOperation::Release,
+            )?;
+        }
+        Ok(GithubReleaseFields::from(&response).into())
+    }
 }
 
 pub struct GithubReleaseFields {
@@ -93,7 +161,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Deploy> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Deploy> = Box::new(Github::new(config, &domain, path, client.clone()));
         let args = ReleaseBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -121,13 +189,95 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let github: Box<dyn Deploy> = Box::new(Github::new(config, &domain, &path, client.clone()));
+        let github: Box<dyn Deploy> = Box::new(Github::new(config, &domain, path, client.clone()));
         let runs = github.num_pages().unwrap();
         assert_eq!(
             "https://api.github.com/repos/jordilin/githapi/releases?page=1",
             *client.url(),
         );
         assert_eq!(Some(ApiOperation::Release), *client.api_operation.borrow());
-        assert_eq!(Some(2), runs);
+        assert_eq!(Some(PageCount::from(2)), runs);
+    }
+
+    #[test]
+    fn test_create_release_uploads_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let asset = dir.path().join("gr.tar.gz");
+        std::fs::write(&asset, "binary").unwrap();
+        let releases: serde_json::Value =
+            serde_json::from_str(&get_contract(ContractType::Github, "list_releases.json"))
+                .unwrap();
+        let created = Response::builder()
+            .status(201)
+            .body(releases[0].to_string())
+            .build()
+            .unwrap();
+        let uploaded = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![uploaded, created]));
+        let github: Box<dyn Deploy> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let args = ReleaseCreateBodyArgs::builder()
+            .tag("v0.1.20".to_string())
+            .title("Test release".to_string())
+            .target(Some("main".to_string()))
+            .assets(vec![asset])
+            .build()
+            .unwrap();
+        let release = github.create(args, &|_, _, _| {}).unwrap();
+        assert_eq!(
+            "https://github.com/jordilin/githapi/releases/tag/v0.1.20",
+            release.url
+        );
+        assert_eq!(
+            "https://uploads.github.com/repos/jordilin/githapi/releases/145605187/assets?name=gr.tar.gz",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!("binary", *client.request_body());
+        assert_eq!(
+            Some(&"application/octet-stream".to_string()),
+            client.headers().get("Content-Type")
+        );
+    }
+
+    #[test]
+    fn test_create_release_for_tag_with_release_uploads_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let asset = dir.path().join("gr.tar.gz");
+        std::fs::write(&asset, "binary").unwrap();
+        let conflict = Response::builder()
+            .status(422)
+            .body(
+                r#"{"message": "Validation Failed",
+                    "errors": [{"resource": "Release", "code": "already_exists", "field": "tag_name"}]}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![conflict]));
+        let github: Box<dyn Deploy> = Box::new(Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            client.clone(),
+        ));
+        let args = ReleaseCreateBodyArgs::builder()
+            .tag("v0.1.20".to_string())
+            .title("Test release".to_string())
+            .assets(vec![asset])
+            .build()
+            .unwrap();
+        let Err(err) = github.create(args, &|_, _, _| {}) else {
+            panic!("Expected an error");
+        };
+        assert!(err.to_string().contains("already_exists"));
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/releases",
+            *client.url(),
+        );
     }
 }
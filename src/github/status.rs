@@ -0,0 +1,73 @@
+use super::Github;
+use crate::api_traits::{ApiOperation, RemoteStatus};
+use crate::cmds::status::{BroadcastMessage, RemoteHealth};
+use crate::http;
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::Result;
+
+impl<R: HttpRunner<Response = Response>> RemoteStatus for Github<R> {
+    fn health(&self) -> Result<RemoteHealth> {
+        let url = format!("{}/meta", self.rest_api_basepath);
+        // Only Github Enterprise Server reports the installed version.
+        let response = query::github_meta::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.request_headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        );
+        Ok(RemoteHealth::from_response(
+            &self.domain,
+            response,
+            |body| {
+                body["installed_version"]
+                    .as_str()
+                    .unwrap_or("n/a")
+                    .to_string()
+            },
+        ))
+    }
+
+    fn broadcast_messages(&self) -> Result<Vec<BroadcastMessage>> {
+        // Github does not expose announcements through its REST API.
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        cmds::status::HEALTH_OK,
+        http::Headers,
+        test::utils::{config, get_contract, ContractType, MockRunner},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_health_from_meta() {
+        let config = config();
+        let domain = "github.com".to_string();
+        let path = "jordilin/githapi";
+        let mut headers = Headers::new();
+        headers.set("x-ratelimit-remaining", "4999");
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Github, "meta.json"))
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github: Box<dyn RemoteStatus> =
+            Box::new(Github::new(config, &domain, path, client.clone()));
+        let health = github.health().unwrap();
+        assert_eq!(HEALTH_OK, health.status);
+        assert_eq!("n/a", health.version);
+        assert_eq!(Some(4999), health.ratelimit_remaining);
+        assert_eq!("https://api.github.com/meta", *client.url());
+    }
+}
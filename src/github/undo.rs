@@ -0,0 +1,141 @@
+use crate::api_traits::{ApiOperation, Undo};
+use crate::audit::{AuditEntry, Reversal};
+use crate::error::GRError;
+use crate::http::{self, Body};
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::Result;
+
+use super::Github;
+
+impl<R> Github<R> {
+    fn repo_url(&self) -> String {
+        format!("{}/repos/{}", self.rest_api_basepath, self.path)
+    }
+
+    /// Pull request number and the state that reverses the operation in
+    /// `entry`.
+    fn inverse(&self, entry: &AuditEntry) -> Option<(String, &'static str)> {
+        let resource = entry.resource(&self.repo_url())?;
+        let id_regex = regex::Regex::new(r"^/pulls/(\d+)$").unwrap();
+        let id = id_regex.captures(resource)?.get(1).unwrap().as_str();
+        if entry.method != "PATCH" {
+            return None;
+        }
+        let state = match entry.body_field("state")? {
+            "closed" => "open",
+            "open" => "closed",
+            _ => return None,
+        };
+        Some((id.to_string(), state))
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Undo for Github<R> {
+    fn reversal(&self, entry: &AuditEntry) -> Reversal {
+        if entry.resource(&self.repo_url()).is_none() {
+            return Reversal::Unrelated;
+        }
+        match self.inverse(entry) {
+            Some((id, "open")) => Reversal::Reversible(format!("Reopen pull request {}", id)),
+            Some((id, _)) => Reversal::Reversible(format!("Close pull request {}", id)),
+            None => Reversal::Irreversible,
+        }
+    }
+
+    fn undo(&self, entry: &AuditEntry) -> Result<()> {
+        let (id, state) = self.inverse(entry).ok_or_else(|| {
+            GRError::PreconditionNotMet(format!("{} {} cannot be undone", entry.method, entry.url))
+        })?;
+        let mut body = Body::new();
+        body.add("state", state);
+        query::github_merge_request_response(
+            &self.runner,
+            &format!("{}/pulls/{}", self.repo_url(), id),
+            Some(body),
+            self.request_headers(),
+            http::Method::PATCH,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use crate::test::utils::{config, MockRunner};
+
+    use super::*;
+
+    fn entry(method: &str, url: &str, body: Option<serde_json::Value>) -> AuditEntry {
+        AuditEntry::builder()
+            .timestamp(0)
+            .user("jdoe".to_string())
+            .method(method.to_string())
+            .url(url.to_string())
+            .result("200".to_string())
+            .body(body)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_undo_close_reopens_pull_request() {
+        let response = Response::builder().status(200).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let github = Github::new(config(), "github.com", "jordilin/githapi", client.clone());
+        let closed = entry(
+            "PATCH",
+            "https://api.github.com/repos/jordilin/githapi/pulls/23",
+            Some(json!({"state": "closed"})),
+        );
+        assert_eq!(
+            Reversal::Reversible("Reopen pull request 23".to_string()),
+            github.reversal(&closed)
+        );
+        github.undo(&closed).unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/jordilin/githapi/pulls/23",
+            *client.url()
+        );
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!("open", body["state"]);
+    }
+
+    #[test]
+    fn test_merge_cannot_be_undone() {
+        let github = Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            Arc::new(MockRunner::new(vec![])),
+        );
+        let merged = entry(
+            "PUT",
+            "https://api.github.com/repos/jordilin/githapi/pulls/23/merge",
+            None,
+        );
+        assert_eq!(Reversal::Irreversible, github.reversal(&merged));
+        assert!(github.undo(&merged).is_err());
+    }
+
+    #[test]
+    fn test_operations_on_a_repository_with_a_longer_name_are_unrelated() {
+        let github = Github::new(
+            config(),
+            "github.com",
+            "jordilin/githapi",
+            Arc::new(MockRunner::new(vec![])),
+        );
+        let merged = entry(
+            "PUT",
+            "https://api.github.com/repos/jordilin/githapi-fork/pulls/23/merge",
+            None,
+        );
+        assert_eq!(Reversal::Unrelated, github.reversal(&merged));
+    }
+}
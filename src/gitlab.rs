@@ -1,11 +1,24 @@
+use crate::api_defaults::GITLAB_API_PATH;
+use crate::api_traits::ApiOperation;
 use crate::config::ConfigProperties;
-use crate::http::Headers;
+use crate::http::{self, Body, Headers};
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::{error, Result};
 use std::sync::Arc;
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod auth;
 pub mod cicd;
 pub mod container_registry;
+pub mod contract;
+pub mod environment;
+pub mod issue;
 pub mod merge_request;
 pub mod project;
 pub mod release;
+pub mod status;
+pub mod undo;
 pub mod user;
 
 // https://docs.gitlab.com/ee/api/rest/
@@ -17,19 +30,24 @@ pub struct Gitlab<R> {
     path: String,
     projects_base_url: String,
     runner: Arc<R>,
+    base_api_url: String,
     base_project_url: String,
     base_current_user_url: String,
     base_users_url: String,
     merge_requests_url: String,
     base_runner_url: String,
+    extra_headers: Vec<(String, String)>,
+    user_agent: String,
 }
 
 impl<R> Gitlab<R> {
     pub fn new(config: impl ConfigProperties, domain: &str, path: &str, runner: Arc<R>) -> Self {
         let api_token = config.api_token().to_string();
+        let extra_headers = config.extra_headers().to_vec();
+        let user_agent = http::user_agent(config.user_agent_suffix());
         let domain = domain.to_string();
         let encoded_path = path.replace('/', "%2F");
-        let api_path = "api/v4";
+        let api_path = GITLAB_API_PATH;
         let protocol = "https";
         let base_api_path = format!("{}://{}/{}", protocol, domain, api_path);
         let base_user_url = format!("{}/user", base_api_path);
@@ -44,11 +62,14 @@ impl<R> Gitlab<R> {
             path: path.to_string(),
             projects_base_url,
             runner,
+            base_api_url: base_api_path,
             base_project_url,
             base_current_user_url: base_user_url,
             merge_requests_url,
             base_runner_url,
             base_users_url,
+            extra_headers,
+            user_agent,
         }
     }
 
@@ -62,7 +83,49 @@ impl<R> Gitlab<R> {
 
     fn headers(&self) -> Headers {
         let mut headers = Headers::new();
+        for (name, value) in &self.extra_headers {
+            headers.set(name, value);
+        }
         headers.set("PRIVATE-TOKEN", self.api_token());
+        headers.set("User-Agent", &self.user_agent);
         headers
     }
 }
+
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    /// Run a GraphQL query and return its `data` object. Some data, like the
+    /// needs of pipeline jobs, is not exposed through the REST API.
+    fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        operation: ApiOperation,
+    ) -> Result<serde_json::Value> {
+        let url = format!("https://{}/api/graphql", self.domain);
+        let mut body = Body::new();
+        body.add("query", serde_json::Value::String(query.to_string()));
+        body.add("variables", variables);
+        let mut response = query::gitlab_graphql(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            operation,
+        )?;
+        // GraphQL answers with a 200 status code even when the query fails.
+        if let Some(errors) = response["errors"].as_array() {
+            let messages = errors
+                .iter()
+                .filter_map(|error| error["message"].as_str())
+                .collect::<Vec<&str>>();
+            return Err(error::GRError::RemoteServerError(format!(
+                "GraphQL request to {} failed: {}",
+                url,
+                messages.join(", ")
+            ))
+            .into());
+        }
+        Ok(response["data"].take())
+    }
+}
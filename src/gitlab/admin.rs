@@ -0,0 +1,216 @@
+use super::Gitlab;
+use crate::api_traits::{ApiOperation, InstanceAdmin};
+use crate::cmds::admin::{
+    InstanceProject, InstanceProjectListBodyArgs, InstanceUser, InstanceUserListBodyArgs,
+};
+use crate::http;
+use crate::io::{HttpRunner, Response};
+use crate::remote::{query, URLQueryParamBuilder};
+use crate::Result;
+
+// The users and projects endpoints return every resource of the instance
+// when the token belongs to an administrator.
+// https://docs.gitlab.com/ee/api/users.html#for-administrators
+impl<R: HttpRunner<Response = Response>> InstanceAdmin for Gitlab<R> {
+    fn list_users(&self, args: InstanceUserListBodyArgs) -> Result<Vec<InstanceUser>> {
+        let mut url = URLQueryParamBuilder::new(&self.base_users_url);
+        if args.blocked {
+            url.add_param("blocked", "true");
+        }
+        query::gitlab_list_instance_users(
+            &self.runner,
+            &url.build(),
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Project,
+        )
+    }
+
+    fn block_user(&self, id: i64) -> Result<()> {
+        // https://docs.gitlab.com/ee/api/users.html#block-user
+        let url = format!("{}/{}/block", self.base_users_url, id);
+        query::gitlab_block_user::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+
+    fn list_projects(&self, args: InstanceProjectListBodyArgs) -> Result<Vec<InstanceProject>> {
+        query::gitlab_list_instance_projects(
+            &self.runner,
+            &self.base_project_url,
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Project,
+        )
+    }
+}
+
+pub struct GitlabInstanceUserFields {
+    user: InstanceUser,
+}
+
+impl From<&serde_json::Value> for GitlabInstanceUserFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabInstanceUserFields {
+            user: InstanceUser::builder()
+                .id(data["id"].as_i64().unwrap_or_default())
+                .username(data["username"].as_str().unwrap_or_default().to_string())
+                .name(data["name"].as_str().unwrap_or_default().to_string())
+                .email(data["email"].as_str().unwrap_or_default().to_string())
+                .state(data["state"].as_str().unwrap_or_default().to_string())
+                .is_admin(data["is_admin"].as_bool().unwrap_or_default())
+                .last_activity_on(
+                    data["last_activity_on"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .created_at(data["created_at"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GitlabInstanceUserFields> for InstanceUser {
+    fn from(fields: GitlabInstanceUserFields) -> Self {
+        fields.user
+    }
+}
+
+pub struct GitlabInstanceProjectFields {
+    project: InstanceProject,
+}
+
+impl From<&serde_json::Value> for GitlabInstanceProjectFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabInstanceProjectFields {
+            project: InstanceProject::builder()
+                .id(data["id"].as_i64().unwrap_or_default())
+                .path(
+                    data["path_with_namespace"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .visibility(data["visibility"].as_str().unwrap_or_default().to_string())
+                .archived(data["archived"].as_bool().unwrap_or_default())
+                .last_activity_at(
+                    data["last_activity_at"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .created_at(data["created_at"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GitlabInstanceProjectFields> for InstanceProject {
+    fn from(fields: GitlabInstanceProjectFields) -> Self {
+        fields.project
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::test::utils::{config, MockRunner};
+
+    use super::*;
+
+    #[test]
+    fn test_list_blocked_users() {
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"id": 7, "username": "tanuki", "name": "Tanuki", "state": "blocked",
+                    "email": "tanuki@example.com", "is_admin": false,
+                    "last_activity_on": null, "created_at": "2020-01-01T00:00:00.000Z"}]"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn InstanceAdmin> =
+            Box::new(Gitlab::new(config(), "gitlab.com", "", client.clone()));
+        let args = InstanceUserListBodyArgs::builder()
+            .list_args(None)
+            .blocked(true)
+            .build()
+            .unwrap();
+        let users = gitlab.list_users(args).unwrap();
+        assert_eq!(1, users.len());
+        assert_eq!("blocked", users[0].state);
+        assert_eq!("tanuki@example.com", users[0].email);
+        assert_eq!("", users[0].last_activity_on);
+        assert_eq!(
+            "https://gitlab.com/api/v4/users?blocked=true",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_block_user() {
+        let response = Response::builder()
+            .status(201)
+            .body("true".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn InstanceAdmin> =
+            Box::new(Gitlab::new(config(), "gitlab.com", "", client.clone()));
+        gitlab.block_user(7).unwrap();
+        assert_eq!("https://gitlab.com/api/v4/users/7/block", *client.url());
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_block_user_without_admin_rights_fails() {
+        let response = Response::builder()
+            .status(403)
+            .body(r#"{"message": "403 Forbidden"}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn InstanceAdmin> =
+            Box::new(Gitlab::new(config(), "gitlab.com", "", client.clone()));
+        assert!(gitlab.block_user(7).is_err());
+    }
+
+    #[test]
+    fn test_list_instance_projects() {
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"id": 55, "path_with_namespace": "platform/api", "visibility": "internal",
+                    "archived": true, "created_at": "2021-05-01T00:00:00.000Z",
+                    "last_activity_at": "2024-02-01T10:00:00.000Z"}]"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn InstanceAdmin> =
+            Box::new(Gitlab::new(config(), "gitlab.com", "", client.clone()));
+        let args = InstanceProjectListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let projects = gitlab.list_projects(args).unwrap();
+        assert_eq!("platform/api", projects[0].path);
+        assert!(projects[0].archived);
+        assert_eq!("https://gitlab.com/api/v4/projects", *client.url());
+    }
+}
@@ -0,0 +1,96 @@
+use super::Gitlab;
+use crate::api_traits::{ApiOperation, TokenInfo};
+use crate::cmds::auth::TokenMetadata;
+use crate::http;
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::Result;
+
+impl<R: HttpRunner<Response = Response>> TokenInfo for Gitlab<R> {
+    fn token(&self) -> Result<TokenMetadata> {
+        let url = format!("{}/personal_access_tokens/self", self.base_api_url);
+        let mut token = query::gitlab_token_info::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )?;
+        token.domain = self.domain.clone();
+        Ok(token)
+    }
+}
+
+pub struct GitlabTokenFields {
+    name: String,
+    scopes: String,
+    expires_at: Option<String>,
+}
+
+impl From<&serde_json::Value> for GitlabTokenFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabTokenFields {
+            name: data["name"].as_str().unwrap_or_default().to_string(),
+            scopes: data["scopes"]
+                .as_array()
+                .map(|scopes| {
+                    scopes
+                        .iter()
+                        .filter_map(|scope| scope.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_default(),
+            expires_at: data["expires_at"].as_str().map(|s| s.to_string()),
+        }
+    }
+}
+
+impl From<GitlabTokenFields> for TokenMetadata {
+    fn from(fields: GitlabTokenFields) -> Self {
+        TokenMetadata::builder()
+            .name(fields.name)
+            .scopes(fields.scopes)
+            .expires_at(fields.expires_at)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use super::*;
+
+    #[test]
+    fn test_get_token_metadata() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "personal_access_token.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn TokenInfo> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let token = gitlab.token().unwrap();
+        assert_eq!("gitlab.com", token.domain);
+        assert_eq!("gitar", token.name);
+        assert_eq!("api,read_user", token.scopes);
+        assert_eq!(Some("2024-04-01".to_string()), token.expires_at);
+        assert_eq!(
+            "https://gitlab.com/api/v4/personal_access_tokens/self",
+            *client.url()
+        );
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+}
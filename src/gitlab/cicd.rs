@@ -1,13 +1,16 @@
 use super::Gitlab;
-use crate::api_traits::{ApiOperation, CicdRunner};
+use crate::api_traits::{ApiOperation, CicdArtifact, CicdGraph, CicdJob, CicdLinter, CicdRunner};
 use crate::cmds::cicd::{
-    Pipeline, PipelineBodyArgs, Runner, RunnerListBodyArgs, RunnerMetadata, RunnerStatus,
+    Artifact, ArtifactListBodyArgs, Job, JobRetryBodyArgs, LintBodyArgs, LintError, LintResponse,
+    Pipeline, PipelineBodyArgs, PipelineJobNode, Runner, RunnerListBodyArgs, RunnerMetadata,
+    RunnerScope, RunnerStatus,
 };
-use crate::http::{self, Headers};
-use crate::remote::{query, URLQueryParamBuilder};
+use crate::error::GRError;
+use crate::http::{self, Body};
+use crate::remote::{encode_query_param, query, URLQueryParamBuilder};
 use crate::{
     api_traits::Cicd,
-    io::{HttpRunner, Response},
+    io::{HttpRunner, PageCount, Response},
 };
 use crate::{time, Result};
 
@@ -24,15 +27,46 @@ impl<R: HttpRunner<Response = Response>> Cicd for Gitlab<R> {
         )
     }
 
-    fn get_pipeline(&self, _id: i64) -> Result<Pipeline> {
-        todo!();
+    fn get_pipeline(&self, id: i64) -> Result<Pipeline> {
+        // https://docs.gitlab.com/ee/api/pipelines.html#get-a-single-pipeline
+        let url = format!("{}/pipelines/{}", self.rest_api_basepath(), id);
+        query::gitlab_pipeline::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Pipeline,
+        )
     }
 
-    fn num_pages(&self) -> Result<Option<u32>> {
+    fn num_pages(&self) -> Result<Option<PageCount>> {
         let url = format!("{}/pipelines?page=1", self.rest_api_basepath());
-        let mut headers = Headers::new();
-        headers.set("PRIVATE-TOKEN", self.api_token());
-        query::num_pages(&self.runner, &url, headers, ApiOperation::Pipeline)
+        query::num_pages(&self.runner, &url, self.headers(), ApiOperation::Pipeline)
+    }
+
+    fn retry(&self, id: i64) -> Result<Pipeline> {
+        self.pipeline_action(id, "retry")
+    }
+
+    fn cancel(&self, id: i64) -> Result<Pipeline> {
+        self.pipeline_action(id, "cancel")
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    // https://docs.gitlab.com/ee/api/pipelines.html#retry-jobs-in-a-pipeline
+    // https://docs.gitlab.com/ee/api/pipelines.html#cancel-a-pipelines-jobs
+    fn pipeline_action(&self, id: i64, action: &str) -> Result<Pipeline> {
+        let url = format!("{}/pipelines/{}/{}", self.rest_api_basepath(), id, action);
+        query::gitlab_pipeline::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Pipeline,
+        )
     }
 }
 
@@ -49,6 +83,20 @@ impl<R: HttpRunner<Response = Response>> CicdRunner for Gitlab<R> {
         )
     }
 
+    fn set_shared_runners(&self, enabled: bool) -> Result<()> {
+        let mut body = Body::new();
+        body.add("shared_runners_enabled", serde_json::Value::from(enabled));
+        query::gitlab_update_project(
+            &self.runner,
+            self.rest_api_basepath(),
+            Some(body),
+            self.headers(),
+            http::Method::PUT,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+
     fn get(&self, id: i64) -> Result<RunnerMetadata> {
         let url = format!("{}/{}", self.base_runner_url, id);
         query::gitlab_get_runner_metadata::<_, ()>(
@@ -61,20 +109,228 @@ impl<R: HttpRunner<Response = Response>> CicdRunner for Gitlab<R> {
         )
     }
 
-    fn num_pages(&self, args: RunnerListBodyArgs) -> Result<Option<u32>> {
+    fn num_pages(&self, args: RunnerListBodyArgs) -> Result<Option<PageCount>> {
         let url = self.list_runners_url(&args, true);
         query::num_pages(&self.runner, &url, self.headers(), ApiOperation::Pipeline)
     }
 }
 
+impl<R: HttpRunner<Response = Response>> CicdArtifact for Gitlab<R> {
+    fn list(&self, args: ArtifactListBodyArgs) -> Result<Vec<Artifact>> {
+        // Artifacts are attached to jobs, so we walk the project's jobs and
+        // keep the ones that still hold something to erase. Projects can have
+        // a very large number of jobs, hence keyset pagination.
+        let url = query::gitlab_keyset_url(
+            &format!("{}/jobs", self.rest_api_basepath()),
+            &args.list_args,
+        );
+        let jobs = query::gitlab_list_job_artifacts(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Pipeline,
+        )?;
+        Ok(jobs.into_iter().filter(|job| job.size > 0).collect())
+    }
+
+    fn delete(&self, id: i64) -> Result<()> {
+        let url = format!("{}/jobs/{}/artifacts", self.rest_api_basepath(), id);
+        query::delete_artifact::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::DELETE,
+            ApiOperation::Pipeline,
+        )?;
+        Ok(())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> CicdJob for Gitlab<R> {
+    fn retry(&self, args: JobRetryBodyArgs) -> Result<Job> {
+        // Retrying a job does not take variables. Overriding them is only
+        // possible when playing a manual job.
+        let (url, body) = if args.variables.is_empty() {
+            let url = format!("{}/jobs/{}/retry", self.rest_api_basepath(), args.id);
+            (url, None)
+        } else {
+            let url = format!("{}/jobs/{}/play", self.rest_api_basepath(), args.id);
+            let variables = args
+                .variables
+                .into_iter()
+                .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+                .collect();
+            let mut body = Body::new();
+            body.add(
+                "job_variables_attributes",
+                serde_json::Value::Array(variables),
+            );
+            (url, Some(body))
+        };
+        query::gitlab_retry_job(
+            &self.runner,
+            &url,
+            body,
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Pipeline,
+        )
+    }
+
+    fn list(&self, pipeline_id: i64) -> Result<Vec<Job>> {
+        // Retried jobs are left out unless `include_retried` is set.
+        // https://docs.gitlab.com/ee/api/jobs.html#list-pipeline-jobs
+        let url = format!(
+            "{}/pipelines/{}/jobs",
+            self.rest_api_basepath(),
+            pipeline_id
+        );
+        query::gitlab_list_pipeline_jobs(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            None,
+            ApiOperation::Pipeline,
+        )
+    }
+}
+
+// Needs of the jobs are only exposed through GraphQL.
+// https://docs.gitlab.com/ee/api/graphql/reference/#cijob
+const PIPELINE_JOBS_QUERY: &str = r#"
+query($fullPath: ID!, $id: CiPipelineID!) {
+  project(fullPath: $fullPath) {
+    pipeline(id: $id) {
+      stages {
+        nodes {
+          name
+          groups {
+            nodes {
+              jobs {
+                nodes {
+                  name
+                  schedulingType
+                  needs { nodes { name } }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+impl<R: HttpRunner<Response = Response>> CicdGraph for Gitlab<R> {
+    fn jobs(&self, pipeline_id: i64) -> Result<Vec<PipelineJobNode>> {
+        let data = self.graphql(
+            PIPELINE_JOBS_QUERY,
+            serde_json::json!({
+                "fullPath": self.path,
+                "id": format!("gid://gitlab/Ci::Pipeline/{}", pipeline_id),
+            }),
+            ApiOperation::Pipeline,
+        )?;
+        let pipeline = &data["project"]["pipeline"];
+        if pipeline.is_null() {
+            return Err(GRError::PreconditionNotMet(format!(
+                "Pipeline {} not found in {}",
+                pipeline_id, self.path
+            ))
+            .into());
+        }
+        let nodes = |value: &serde_json::Value| value["nodes"].as_array().cloned();
+        let mut jobs = Vec::new();
+        for stage in nodes(&pipeline["stages"]).unwrap_or_default() {
+            let stage_name = stage["name"].as_str().unwrap_or_default();
+            for group in nodes(&stage["groups"]).unwrap_or_default() {
+                for job in nodes(&group["jobs"]).unwrap_or_default() {
+                    // Jobs scheduled by stage wait for the previous stage.
+                    // The ones with `needs`, even empty, are scheduled as a
+                    // DAG.
+                    let needs = (job["schedulingType"].as_str() == Some("dag")).then(|| {
+                        nodes(&job["needs"])
+                            .unwrap_or_default()
+                            .iter()
+                            .filter_map(|need| need["name"].as_str().map(String::from))
+                            .collect()
+                    });
+                    jobs.push(
+                        PipelineJobNode::builder()
+                            .name(job["name"].as_str().unwrap_or_default().to_string())
+                            .stage(stage_name.to_string())
+                            .needs(needs)
+                            .build()
+                            .unwrap(),
+                    );
+                }
+            }
+        }
+        Ok(jobs)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> CicdLinter for Gitlab<R> {
+    fn lint(&self, args: LintBodyArgs) -> Result<LintResponse> {
+        // Linting in the context of the project resolves local and project
+        // includes.
+        let url = format!("{}/ci/lint", self.rest_api_basepath());
+        let mut body = Body::new();
+        body.add("content", args.content);
+        query::gitlab_lint_ci(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Pipeline,
+        )
+    }
+
+    fn merged_config(&self, ref_: &str) -> Result<LintResponse> {
+        let url = format!(
+            "{}/ci/lint?content_ref={}",
+            self.rest_api_basepath(),
+            encode_query_param(ref_)
+        );
+        query::gitlab_lint_ci::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Pipeline,
+        )
+    }
+}
+
 impl<R> Gitlab<R> {
     fn list_runners_url(&self, args: &RunnerListBodyArgs, num_pages: bool) -> String {
-        let base_url = if args.all {
-            format!("{}/all", self.base_runner_url)
-        } else {
-            format!("{}/runners", self.rest_api_basepath(),)
+        let base_url = match args.scope {
+            _ if args.all => format!("{}/all", self.base_runner_url),
+            RunnerScope::Project => format!("{}/runners", self.rest_api_basepath()),
+            RunnerScope::Group => {
+                // The group is the namespace the project lives in.
+                let group = self
+                    .path
+                    .rsplit_once('/')
+                    .map_or(self.path.as_str(), |(group, _)| group);
+                format!(
+                    "{}/groups/{}/runners",
+                    self.base_api_url,
+                    group.replace('/', "%2F")
+                )
+            }
+            RunnerScope::Instance => format!("{}/all", self.base_runner_url),
         };
         let mut url = URLQueryParamBuilder::new(&base_url);
+        if !args.all && args.scope == RunnerScope::Instance {
+            url.add_param("type", "instance_type");
+        }
         match args.status {
             RunnerStatus::All => {}
             _ => {
@@ -193,6 +449,7 @@ impl From<GitlabRunnerMetadataFields> for RunnerMetadata {
 }
 
 pub struct GitlabPipelineFields {
+    id: i64,
     status: String,
     web_url: String,
     ref_: String,
@@ -204,6 +461,7 @@ pub struct GitlabPipelineFields {
 impl From<&serde_json::Value> for GitlabPipelineFields {
     fn from(data: &serde_json::Value) -> Self {
         GitlabPipelineFields {
+            id: data["id"].as_i64().unwrap_or_default(),
             status: data["status"].as_str().unwrap().to_string(),
             web_url: data["web_url"].as_str().unwrap().to_string(),
             ref_: data["ref"].as_str().unwrap().to_string(),
@@ -217,6 +475,7 @@ impl From<&serde_json::Value> for GitlabPipelineFields {
 impl From<GitlabPipelineFields> for Pipeline {
     fn from(fields: GitlabPipelineFields) -> Self {
         Pipeline::builder()
+            .id(fields.id)
             .status(fields.status.to_string())
             .web_url(fields.web_url.to_string())
             .branch(fields.ref_.to_string())
@@ -232,12 +491,143 @@ impl From<GitlabPipelineFields> for Pipeline {
     }
 }
 
+pub struct GitlabLintFields {
+    valid: bool,
+    errors: Vec<String>,
+    merged_yaml: String,
+}
+
+impl From<&serde_json::Value> for GitlabLintFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabLintFields {
+            valid: data["valid"].as_bool().unwrap_or_default(),
+            errors: data["errors"]
+                .as_array()
+                .map(|errors| {
+                    errors
+                        .iter()
+                        .filter_map(|error| error.as_str().map(|error| error.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            merged_yaml: data["merged_yaml"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GitlabLintFields> for LintResponse {
+    fn from(fields: GitlabLintFields) -> Self {
+        LintResponse {
+            valid: fields.valid,
+            errors: fields
+                .errors
+                .into_iter()
+                .map(|error| LintError::new(error_line(&error), error))
+                .collect(),
+            merged_yaml: fields.merged_yaml,
+        }
+    }
+}
+
+/// Gitlab reports the position of YAML syntax errors as part of the message,
+/// Ex: "... while parsing a block mapping at line 3 column 1"
+fn error_line(message: &str) -> Option<usize> {
+    let re = regex::Regex::new(r"at line (\d+)").unwrap();
+    re.captures(message)
+        .and_then(|captures| captures[1].parse().ok())
+}
+
+pub struct GitlabJobFields {
+    id: i64,
+    name: String,
+    stage: String,
+    status: String,
+    web_url: String,
+    created_at: String,
+    duration: Option<u64>,
+}
+
+impl From<&serde_json::Value> for GitlabJobFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabJobFields {
+            id: data["id"].as_i64().unwrap(),
+            name: data["name"].as_str().unwrap_or_default().to_string(),
+            stage: data["stage"].as_str().unwrap_or_default().to_string(),
+            status: data["status"].as_str().unwrap_or_default().to_string(),
+            web_url: data["web_url"].as_str().unwrap_or_default().to_string(),
+            created_at: data["created_at"].as_str().unwrap_or_default().to_string(),
+            duration: data["duration"].as_f64().map(|duration| duration as u64),
+        }
+    }
+}
+
+impl From<GitlabJobFields> for Job {
+    fn from(fields: GitlabJobFields) -> Self {
+        Job::builder()
+            .id(fields.id)
+            .name(fields.name)
+            .stage(fields.stage)
+            .status(fields.status)
+            .web_url(fields.web_url)
+            .created_at(fields.created_at)
+            .duration(fields.duration)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabJobArtifactFields {
+    id: i64,
+    name: String,
+    size: u64,
+    created_at: String,
+    web_url: String,
+}
+
+impl From<&serde_json::Value> for GitlabJobArtifactFields {
+    fn from(data: &serde_json::Value) -> Self {
+        // The job log (trace) is listed among the artifacts but is not
+        // removed when erasing them, so it does not count towards the size.
+        let size = data["artifacts"]
+            .as_array()
+            .map(|artifacts| {
+                artifacts
+                    .iter()
+                    .filter(|artifact| artifact["file_type"].as_str() != Some("trace"))
+                    .map(|artifact| artifact["size"].as_u64().unwrap_or_default())
+                    .sum()
+            })
+            .unwrap_or_default();
+        GitlabJobArtifactFields {
+            id: data["id"].as_i64().unwrap(),
+            name: data["name"].as_str().unwrap_or_default().to_string(),
+            size,
+            created_at: data["created_at"].as_str().unwrap().to_string(),
+            web_url: data["web_url"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GitlabJobArtifactFields> for Artifact {
+    fn from(fields: GitlabJobArtifactFields) -> Self {
+        Artifact::builder()
+            .id(fields.id)
+            .name(fields.name)
+            .size(fields.size)
+            .created_at(fields.created_at)
+            .web_url(fields.web_url)
+            .build()
+            .unwrap()
+    }
+}
+
 #[cfg(test)]
 mod test {
 
     use std::sync::Arc;
 
-    use crate::cmds::cicd::RunnerStatus;
+    use crate::cmds::cicd::{RunnerScope, RunnerStatus};
+    use crate::http::Headers;
     use crate::remote::ListBodyArgs;
     use crate::test::utils::{config, get_contract, ContractType, MockRunner};
 
@@ -259,6 +649,7 @@ mod test {
         let pipelines = gitlab.list(default_pipeline_body_args()).unwrap();
 
         assert_eq!(3, pipelines.len());
+        assert_eq!(1122075312, pipelines[0].id);
         assert_eq!(
             "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/pipelines",
             *client.url(),
@@ -331,6 +722,100 @@ mod test {
         assert_eq!(0, pipelines.len());
     }
 
+    #[test]
+    fn test_pipeline_jobs_with_needs() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "pipeline_jobs_graph.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdGraph> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let jobs = gitlab.jobs(1191917538).unwrap();
+        assert_eq!("https://gitlab.com/api/graphql", *client.url());
+        assert!(client
+            .request_body()
+            .contains("gid://gitlab/Ci::Pipeline/1191917538"));
+        assert_eq!(
+            vec!["build", "docs", "unit", "pages"],
+            jobs.iter().map(|job| job.name.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!("test", jobs[2].stage);
+        assert_eq!(Some(vec!["build".to_string()]), jobs[2].needs);
+        assert_eq!(None, jobs[3].needs);
+    }
+
+    #[test]
+    fn test_pipeline_jobs_of_unknown_pipeline_is_error() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(r#"{"data":{"project":{"pipeline":null}}}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdGraph> = Box::new(Gitlab::new(config, &domain, &path, client));
+        assert!(gitlab.jobs(1).is_err());
+    }
+
+    #[test]
+    fn test_get_pipeline() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "retry_pipeline.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn Cicd> = Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let pipeline = gitlab.get_pipeline(1191917538).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/pipelines/1191917538",
+            *client.url()
+        );
+        assert_eq!(http::Method::GET, *client.http_method.borrow());
+        assert_eq!(1191917538, pipeline.id);
+    }
+
+    #[test]
+    fn test_retry_and_cancel_pipeline() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = || {
+            Response::builder()
+                .status(201)
+                .body(get_contract(ContractType::Gitlab, "retry_pipeline.json"))
+                .build()
+                .unwrap()
+        };
+        let client = Arc::new(MockRunner::new(vec![response(), response()]));
+        let gitlab: Box<dyn Cicd> = Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let pipeline = gitlab.retry(1191917538).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/pipelines/1191917538/retry",
+            *client.url()
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!("running", pipeline.status);
+        gitlab.cancel(1191917538).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/pipelines/1191917538/cancel",
+            *client.url()
+        );
+    }
+
     #[test]
     fn test_pipeline_page_from_set_in_url() {
         let config = config();
@@ -374,7 +859,7 @@ mod test {
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
         let gitlab: Box<dyn Cicd> = Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        assert_eq!(Some(2), gitlab.num_pages().unwrap());
+        assert_eq!(Some(PageCount::from(2)), gitlab.num_pages().unwrap());
         assert_eq!(
             "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/pipelines?page=1",
             *client.url(),
@@ -466,7 +951,7 @@ mod test {
             "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/runners?status=online&page=1",
             *client.url(),
         );
-        assert_eq!(Some(1), num_pages);
+        assert_eq!(Some(PageCount::from(1)), num_pages);
     }
 
     #[test]
@@ -682,6 +1167,251 @@ mod test {
             "https://gitlab.com/api/v4/runners/all?page=1&tag_list=tag1,tag2",
             *client.url(),
         );
-        assert_eq!(Some(1), num_pages);
+        assert_eq!(Some(PageCount::from(1)), num_pages);
+    }
+
+    #[test]
+    fn test_list_job_artifacts_skips_jobs_without_artifacts() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "list_jobs.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdArtifact> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = ArtifactListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let artifacts = gitlab.list(args).unwrap();
+        assert_eq!(1, artifacts.len());
+        assert_eq!(6327855213, artifacts[0].id);
+        assert_eq!("build", artifacts[0].name);
+        assert_eq!(2098176, artifacts[0].size);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/jobs?pagination=keyset&order_by=id",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_delete_job_artifacts() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder().status(204).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdArtifact> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        gitlab.delete(6327855213).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/jobs/6327855213/artifacts",
+            *client.url(),
+        );
+        assert_eq!(http::Method::DELETE, *client.http_method.borrow());
+        assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_retry_job_without_variables() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Gitlab, "retry_job.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdJob> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = JobRetryBodyArgs::builder().id(6327855213).build().unwrap();
+        let job = gitlab.retry(args).unwrap();
+        assert_eq!(6327855300, job.id);
+        assert_eq!("pending", job.status);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/jobs/6327855213/retry",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_list_pipeline_jobs() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "list_jobs.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdJob> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let jobs = gitlab.list(1172345678).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/pipelines/1172345678/jobs",
+            *client.url(),
+        );
+        assert_eq!("build", jobs[0].name);
+        assert_eq!("build", jobs[0].stage);
+        assert_eq!(Some(90), jobs[0].duration);
+        assert_eq!("test", jobs[1].name);
+        assert_eq!("test", jobs[1].stage);
+        assert_eq!(Some(48), jobs[1].duration);
+    }
+
+    #[test]
+    fn test_retry_job_with_variables_plays_job() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "retry_job.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdJob> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = JobRetryBodyArgs::builder()
+            .id(6327855213)
+            .variables(vec![("DEPLOY_ENV".to_string(), "staging".to_string())])
+            .build()
+            .unwrap();
+        gitlab.retry(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/jobs/6327855213/play",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_lint_ci_config_with_errors() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "ci_lint.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdLinter> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = LintBodyArgs::builder()
+            .content("build:\n  script: make\n stage: build\n".to_string())
+            .build()
+            .unwrap();
+        let response = gitlab.lint(args).unwrap();
+        assert!(!response.valid);
+        assert_eq!(2, response.errors.len());
+        assert_eq!(Some(3), response.errors[0].line);
+        assert_eq!(None, response.errors[1].line);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/ci/lint",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_merged_ci_config_for_ref() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "ci_lint_merged_yaml.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdLinter> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let response = gitlab.merged_config("release/1.0").unwrap();
+        assert!(response.valid);
+        assert!(response.merged_yaml.contains("extends: \".rust\""));
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/ci/lint?content_ref=release%2F1.0",
+            *client.url(),
+        );
+        assert_eq!(http::Method::GET, *client.http_method.borrow());
+        assert_eq!(Some(ApiOperation::Pipeline), *client.api_operation.borrow());
+    }
+
+    fn list_runners_in_scope(scope: RunnerScope) -> String {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/tools/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "list_project_runners.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdRunner> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let body_args = RunnerListBodyArgs::builder()
+            .status(RunnerStatus::Online)
+            .list_args(None)
+            .scope(scope)
+            .build()
+            .unwrap();
+        gitlab.list(body_args).unwrap();
+        let url = client.url().to_string();
+        url
+    }
+
+    #[test]
+    fn test_list_group_runners() {
+        assert_eq!(
+            "https://gitlab.com/api/v4/groups/jordilin%2Ftools/runners?status=online",
+            list_runners_in_scope(RunnerScope::Group)
+        );
+    }
+
+    #[test]
+    fn test_list_instance_runners() {
+        assert_eq!(
+            "https://gitlab.com/api/v4/runners/all?type=instance_type&status=online",
+            list_runners_in_scope(RunnerScope::Instance)
+        );
+    }
+
+    #[test]
+    fn test_disable_shared_runners() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "project.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CicdRunner> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        gitlab.set_shared_runners(false).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi",
+            *client.url()
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
     }
 }
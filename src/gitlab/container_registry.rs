@@ -2,7 +2,7 @@ use crate::{
     api_traits::{ApiOperation, ContainerRegistry},
     cmds::docker::{DockerListBodyArgs, ImageMetadata, RegistryRepository, RepositoryTag},
     http,
-    io::{HttpRunner, Response},
+    io::{HttpRunner, PageCount, Response},
     remote::query,
     Result,
 };
@@ -44,7 +44,7 @@ impl<R: HttpRunner<Response = Response>> ContainerRegistry for Gitlab<R> {
         )
     }
 
-    fn num_pages_repository_tags(&self, repository_id: i64) -> Result<Option<u32>> {
+    fn num_pages_repository_tags(&self, repository_id: i64) -> Result<Option<PageCount>> {
         let url = format!(
             "{}/registry/repositories/{}/tags?page=1",
             self.rest_api_basepath(),
@@ -58,7 +58,7 @@ impl<R: HttpRunner<Response = Response>> ContainerRegistry for Gitlab<R> {
         )
     }
 
-    fn num_pages_repositories(&self) -> Result<Option<u32>> {
+    fn num_pages_repositories(&self) -> Result<Option<PageCount>> {
         let url = format!("{}/registry/repositories?page=1", self.rest_api_basepath());
         query::num_pages(
             &self.runner,
@@ -204,7 +204,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
         let args = DockerListBodyArgs::builder().repos(true).build().unwrap();
         gitlab.list_repositories(args).unwrap();
         assert_eq!(
@@ -232,7 +232,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
         let args = DockerListBodyArgs::builder()
             .repos(false)
             .tags(true)
@@ -266,8 +266,11 @@ mod test {
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
         let gitlab: Box<dyn ContainerRegistry> =
-            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        assert_eq!(Some(1), gitlab.num_pages_repository_tags(1).unwrap());
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        assert_eq!(
+            Some(PageCount::from(1)),
+            gitlab.num_pages_repository_tags(1).unwrap()
+        );
         assert_eq!(
             "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/registry/repositories/1/tags?page=1",
             client.url().to_string(),
@@ -293,8 +296,11 @@ mod test {
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
         let gitlab: Box<dyn ContainerRegistry> =
-            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        assert_eq!(Some(1), gitlab.num_pages_repositories().unwrap());
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        assert_eq!(
+            Some(PageCount::from(1)),
+            gitlab.num_pages_repositories().unwrap()
+        );
         assert_eq!(
             "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/registry/repositories?page=1",
             client.url().to_string(),
@@ -320,7 +326,7 @@ mod test {
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
         let gitlab: Box<dyn ContainerRegistry> =
-            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
         let _metadata = gitlab.get_image_metadata(1, "v0.0.1").unwrap();
         assert_eq!("https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/registry/repositories/1/tags/v0.0.1",
             client.url().to_string(),
@@ -0,0 +1,47 @@
+use super::Gitlab;
+use crate::api_traits::{ApiOperation, RemoteContract};
+use crate::http;
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::Result;
+
+impl<R: HttpRunner<Response = Response>> RemoteContract for Gitlab<R> {
+    fn fetch(&self, endpoint: &str) -> Result<serde_json::Value> {
+        let url = format!("{}/{}", self.base_api_url, endpoint.trim_start_matches('/'));
+        query::fetch_contract::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use super::*;
+
+    #[test]
+    fn test_fetch_contract_from_api_base_path() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "version.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn RemoteContract> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let data = gitlab.fetch("/version").unwrap();
+        assert_eq!("16.10.0-pre", data["version"]);
+        assert_eq!("https://gitlab.com/api/v4/version", *client.url());
+    }
+}
@@ -0,0 +1,329 @@
+use super::Gitlab;
+use crate::api_traits::{ApiOperation, Deployment, DeploymentApproval};
+use crate::cmds::environment::{
+    DeploymentApprovalListBodyArgs, DeploymentApproveBodyArgs, Environment,
+    EnvironmentListBodyArgs, PendingDeployment,
+};
+use crate::error::GRError;
+use crate::http::{self, Body};
+use crate::io::{HttpRunner, Response};
+use crate::remote::{encode_query_param, query};
+use crate::Result;
+
+impl<R: HttpRunner<Response = Response>> Deployment for Gitlab<R> {
+    fn list(&self, args: EnvironmentListBodyArgs) -> Result<Vec<Environment>> {
+        let url = format!("{}/environments", self.rest_api_basepath());
+        let environments = query::gitlab_list_environments(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Release,
+        )?;
+        // Listing environments does not include their last deployment.
+        environments
+            .into_iter()
+            .map(|environment| self.environment(environment.id))
+            .collect()
+    }
+
+    fn get(&self, name: &str) -> Result<Environment> {
+        let url = format!(
+            "{}/environments?name={}",
+            self.rest_api_basepath(),
+            encode_query_param(name)
+        );
+        let environments = query::gitlab_environments::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Release,
+        )?;
+        match environments[0]["id"].as_i64() {
+            Some(id) => self.environment(id),
+            None => {
+                Err(GRError::ApplicationError(format!("Environment {} not found", name)).into())
+            }
+        }
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    fn environment(&self, id: i64) -> Result<Environment> {
+        let url = format!("{}/environments/{}", self.rest_api_basepath(), id);
+        query::gitlab_environment::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Release,
+        )
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> DeploymentApproval for Gitlab<R> {
+    fn list(&self, args: DeploymentApprovalListBodyArgs) -> Result<Vec<PendingDeployment>> {
+        // Deployments waiting for approval are in blocked status.
+        let url = format!("{}/deployments?status=blocked", self.rest_api_basepath());
+        query::gitlab_list_pending_deployments(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Release,
+        )
+    }
+
+    fn approve(&self, args: DeploymentApproveBodyArgs) -> Result<()> {
+        let url = format!(
+            "{}/deployments/{}/approval",
+            self.rest_api_basepath(),
+            args.id
+        );
+        let mut body = Body::new();
+        body.add("status", "approved".to_string());
+        if !args.comment.is_empty() {
+            body.add("comment", args.comment);
+        }
+        query::approve_deployment(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Release,
+        )?;
+        Ok(())
+    }
+}
+
+pub struct GitlabPendingDeploymentFields {
+    id: i64,
+    environment: String,
+    branch: String,
+    created_at: String,
+    web_url: String,
+}
+
+impl From<&serde_json::Value> for GitlabPendingDeploymentFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabPendingDeploymentFields {
+            id: data["id"].as_i64().unwrap(),
+            environment: data["environment"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            branch: data["ref"].as_str().unwrap_or_default().to_string(),
+            created_at: data["created_at"].as_str().unwrap().to_string(),
+            // The job that performs the deployment.
+            web_url: data["deployable"]["web_url"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+impl From<GitlabPendingDeploymentFields> for PendingDeployment {
+    fn from(fields: GitlabPendingDeploymentFields) -> Self {
+        PendingDeployment::builder()
+            .id(fields.id)
+            .environment(fields.environment)
+            .branch(fields.branch)
+            .created_at(fields.created_at)
+            .web_url(fields.web_url)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabEnvironmentFields {
+    id: i64,
+    name: String,
+    external_url: String,
+    created_at: String,
+    last_deployment: serde_json::Value,
+}
+
+impl From<&serde_json::Value> for GitlabEnvironmentFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabEnvironmentFields {
+            id: data["id"].as_i64().unwrap(),
+            name: data["name"].as_str().unwrap().to_string(),
+            external_url: data["external_url"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            created_at: data["created_at"].as_str().unwrap().to_string(),
+            // Only present when getting a single environment.
+            last_deployment: data["last_deployment"].clone(),
+        }
+    }
+}
+
+impl From<GitlabEnvironmentFields> for Environment {
+    fn from(fields: GitlabEnvironmentFields) -> Self {
+        let deployment = &fields.last_deployment;
+        let field = |name: &str| deployment[name].as_str().unwrap_or_default().to_string();
+        Environment::builder()
+            .id(fields.id)
+            .name(fields.name)
+            .sha(field("sha"))
+            .status(field("status"))
+            .url(fields.external_url)
+            .deployed_at(field("created_at"))
+            .created_at(fields.created_at)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use super::*;
+
+    fn json_response(body: String) -> Response {
+        Response::builder().status(200).body(body).build().unwrap()
+    }
+
+    #[test]
+    fn test_list_environments_with_last_deployment() {
+        let environments = json_response(
+            r#"[{"id": 15987451, "name": "production", "external_url": "https://example.com",
+                 "created_at": "2024-01-10T08:00:00.000Z"}]"#
+                .to_string(),
+        );
+        let environment = json_response(get_contract(ContractType::Gitlab, "get_environment.json"));
+        let client = Arc::new(MockRunner::new(vec![environment, environments]));
+        let gitlab: Box<dyn Deployment> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let args = EnvironmentListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let environments = gitlab.list(args).unwrap();
+        assert_eq!(1, environments.len());
+        let environment = &environments[0];
+        assert_eq!("production", environment.name);
+        assert_eq!("aa8b3b2c1f8a3d1d2e0e4a6b1c2d3e4f5a6b7c8d", environment.sha);
+        assert_eq!("success", environment.status);
+        assert_eq!("https://example.com", environment.url);
+        assert_eq!("2024-03-04T08:15:10.120Z", environment.deployed_at);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/environments/15987451",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Release), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_environment_by_name() {
+        let environments = json_response(
+            r#"[{"id": 15987451, "name": "production", "created_at": "2024-01-10T08:00:00.000Z"}]"#
+                .to_string(),
+        );
+        let environment = json_response(get_contract(ContractType::Gitlab, "get_environment.json"));
+        let client = Arc::new(MockRunner::new(vec![environment, environments]));
+        let gitlab: Box<dyn Deployment> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let environment = gitlab.get("production").unwrap();
+        assert_eq!(15987451, environment.id);
+        assert_eq!("success", environment.status);
+    }
+
+    #[test]
+    fn test_get_unknown_environment_is_error() {
+        let client = Arc::new(MockRunner::new(vec![json_response("[]".to_string())]));
+        let gitlab: Box<dyn Deployment> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let err = gitlab.get("review/my branch").unwrap_err();
+        assert_eq!(
+            "Application error: Environment review/my branch not found",
+            err.to_string()
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/environments?name=review%2Fmy%20branch",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_blocked_deployments() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "list_blocked_deployments.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn DeploymentApproval> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = DeploymentApprovalListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let deployments = gitlab.list(args).unwrap();
+        assert_eq!(1, deployments.len());
+        assert_eq!(591345821, deployments[0].id);
+        assert_eq!("production", deployments[0].environment);
+        assert_eq!("main", deployments[0].branch);
+        assert_eq!(
+            "https://gitlab.com/jordilin/gitlapi/-/jobs/6327855400",
+            deployments[0].web_url
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/deployments?status=blocked",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Release), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_approve_deployment() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn DeploymentApproval> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = DeploymentApproveBodyArgs::builder()
+            .id(591345821)
+            .comment("LGTM".to_string())
+            .build()
+            .unwrap();
+        gitlab.approve(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/deployments/591345821/approval",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!(Some(ApiOperation::Release), *client.api_operation.borrow());
+    }
+}
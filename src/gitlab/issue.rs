@@ -0,0 +1,243 @@
+use crate::{
+    api_traits::{ApiOperation, Issue},
+    cmds::issue::{IssueBodyArgs, IssueListBodyArgs, IssueResponse},
+    http::{self, Body},
+    io::{HttpRunner, PageCount, Response},
+    remote::query,
+    Result,
+};
+
+use super::Gitlab;
+
+// https://docs.gitlab.com/ee/api/issues.html
+
+impl<R> Gitlab<R> {
+    fn list_issues_url(&self, args: &IssueListBodyArgs, num_pages: bool) -> String {
+        let mut url = format!("{}/issues?state={}", self.rest_api_basepath(), args.state);
+        if num_pages {
+            url.push_str("&page=1");
+        }
+        url
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Issue for Gitlab<R> {
+    fn open(&self, args: IssueBodyArgs) -> Result<IssueResponse> {
+        let url = format!("{}/issues", self.rest_api_basepath());
+        let mut body = Body::new();
+        body.add("title", args.title);
+        body.add("description", args.description);
+        if !args.labels.is_empty() {
+            body.add("labels", args.labels.join(","));
+        }
+        query::gitlab_issue(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Issue,
+        )
+    }
+
+    fn list(&self, args: IssueListBodyArgs) -> Result<Vec<IssueResponse>> {
+        let url = self.list_issues_url(&args, false);
+        query::gitlab_list_issues(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Issue,
+        )
+    }
+
+    fn get(&self, id: i64) -> Result<IssueResponse> {
+        let url = format!("{}/issues/{}", self.rest_api_basepath(), id);
+        query::gitlab_issue::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Issue,
+        )
+    }
+
+    fn close(&self, id: i64) -> Result<IssueResponse> {
+        let url = format!("{}/issues/{}", self.rest_api_basepath(), id);
+        let mut body = Body::new();
+        body.add("state_event", "close");
+        query::gitlab_issue(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::PUT,
+            ApiOperation::Issue,
+        )
+    }
+
+    fn comment(&self, id: i64, comment: &str) -> Result<()> {
+        let url = format!("{}/issues/{}/notes", self.rest_api_basepath(), id);
+        let mut body = Body::new();
+        body.add("body", comment);
+        query::create_issue_comment(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Issue,
+        )?;
+        Ok(())
+    }
+
+    fn num_pages(&self, args: IssueListBodyArgs) -> Result<Option<PageCount>> {
+        let url = self.list_issues_url(&args, true);
+        query::num_pages(&self.runner, &url, self.headers(), ApiOperation::Issue)
+    }
+}
+
+pub struct GitlabIssueFields {
+    issue: IssueResponse,
+}
+
+impl From<&serde_json::Value> for GitlabIssueFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabIssueFields {
+            issue: IssueResponse::builder()
+                // iid is the id within the project, the one in the web URL.
+                .id(data["iid"].as_i64().unwrap_or_default())
+                .title(data["title"].as_str().unwrap_or_default().to_string())
+                .description(data["description"].as_str().unwrap_or_default().to_string())
+                .author(
+                    data["author"]["username"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .state(data["state"].as_str().unwrap_or_default().to_string())
+                .web_url(data["web_url"].as_str().unwrap_or_default().to_string())
+                .labels(
+                    data["labels"]
+                        .as_array()
+                        .map(|labels| {
+                            labels
+                                .iter()
+                                .filter_map(|label| label.as_str().map(String::from))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                )
+                .created_at(data["created_at"].as_str().unwrap_or_default().to_string())
+                .updated_at(data["updated_at"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GitlabIssueFields> for IssueResponse {
+    fn from(fields: GitlabIssueFields) -> Self {
+        fields.issue
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::cmds::issue::IssueState;
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use super::*;
+
+    fn gitlab(responses: Vec<Response>) -> (Arc<MockRunner>, Box<dyn Issue>) {
+        let client = Arc::new(MockRunner::new(responses));
+        let gitlab: Box<dyn Issue> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        (client, gitlab)
+    }
+
+    fn issue_response(status: i32) -> Response {
+        Response::builder()
+            .status(status)
+            .body(get_contract(ContractType::Gitlab, "issue.json"))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_open_issue() {
+        let (client, gitlab) = gitlab(vec![issue_response(201)]);
+        let args = IssueBodyArgs::builder()
+            .title("Crash on startup".to_string())
+            .labels(vec!["bug".to_string(), "p1".to_string()])
+            .build()
+            .unwrap();
+        let issue = gitlab.open(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/issues",
+            *client.url()
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert!(client.request_body().contains(r#""labels":"bug,p1""#));
+        assert_eq!(1, issue.id);
+        assert_eq!("jdoe", issue.author);
+        assert_eq!(vec!["bug".to_string()], issue.labels);
+    }
+
+    #[test]
+    fn test_list_issues() {
+        let response = Response::builder()
+            .status(200)
+            .body(format!(
+                "[{}]",
+                get_contract(ContractType::Gitlab, "issue.json")
+            ))
+            .build()
+            .unwrap();
+        let (client, gitlab) = gitlab(vec![response]);
+        let args = IssueListBodyArgs::builder()
+            .state(IssueState::Opened)
+            .list_args(None)
+            .build()
+            .unwrap();
+        let issues = gitlab.list(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/issues?state=opened",
+            *client.url()
+        );
+        assert_eq!(Some(ApiOperation::Issue), *client.api_operation.borrow());
+        assert_eq!(1, issues.len());
+    }
+
+    #[test]
+    fn test_close_issue() {
+        let (client, gitlab) = gitlab(vec![issue_response(200)]);
+        gitlab.close(1).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/issues/1",
+            *client.url()
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+        assert!(client.request_body().contains(r#""state_event":"close""#));
+    }
+
+    #[test]
+    fn test_comment_issue() {
+        let response = Response::builder().status(201).build().unwrap();
+        let (client, gitlab) = gitlab(vec![response]);
+        gitlab.comment(1, "Cannot reproduce").unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/issues/1/notes",
+            *client.url()
+        );
+        assert!(client.request_body().contains("Cannot reproduce"));
+    }
+}
@@ -1,15 +1,30 @@
-use crate::api_traits::{ApiOperation, CommentMergeRequest, RemoteProject};
+use chrono::{DateTime, Utc};
+
+use crate::api_defaults::{
+    GITLAB_MERGE_REQUEST_DESCRIPTION_MAX_CHARS, GITLAB_MERGE_REQUEST_TITLE_MAX_CHARS,
+};
+use crate::api_traits::{
+    ApiOperation, CommentMergeRequest, FailingPipelines, MergeQueue, MergeRequestApprovals,
+    MergeRequestChecks, MergeRequestDiff, MergeRequestLabels, MergeRequestRebase,
+    MergeRequestReviewDiff, RemoteProject,
+};
 use crate::cli::browse::BrowseOptions;
-use crate::cmds::merge_request::CommentMergeRequestBodyArgs;
+use crate::cmds::merge_request::{
+    draft_title, ready_title, ApprovalStatus, CheckState, CommentMergeRequestBodyArgs,
+    CommentThread, MergeQueueEntry, MergeQueueListBodyArgs, MergeRequestCheck, MergeRequestComment,
+    RebaseProgress, ReviewedHead,
+};
+use crate::cmds::my::dashboard::FailingPipeline;
 use crate::error;
 use crate::http::Method::GET;
-use crate::http::{self, Body, Headers};
-use crate::remote::{query, MergeRequestListBodyArgs};
+use crate::http::{self, Body};
+use crate::log_info;
+use crate::remote::{encode_query_param, query, Member, MergeRequestListBodyArgs};
 use crate::Result;
 use crate::{
     api_traits::MergeRequest,
-    io::{HttpRunner, Response},
-    remote::{MergeRequestBodyArgs, MergeRequestResponse},
+    io::{HttpRunner, PageCount, Response},
+    remote::{MergeRequestBodyArgs, MergeRequestLimits, MergeRequestResponse},
 };
 
 use crate::json_loads;
@@ -18,13 +33,32 @@ use super::Gitlab;
 
 impl<R: HttpRunner<Response = Response>> MergeRequest for Gitlab<R> {
     fn open(&self, args: MergeRequestBodyArgs) -> Result<MergeRequestResponse> {
-        let mut body = Body::new();
-        body.add("source_branch", args.source_branch);
-        body.add("target_branch", args.target_branch);
-        body.add("title", args.title);
-        body.add("assignee_id", args.assignee_id);
-        body.add("description", args.description);
-        body.add("remove_source_branch", args.remove_source_branch);
+        let mut body: Body<serde_json::Value> = Body::new();
+        body.add("source_branch", args.source_branch.into());
+        body.add("target_branch", args.target_branch.into());
+        body.add("title", args.title.into());
+        // Multiple assignees require Gitlab premium. Free tiers keep the
+        // first one only.
+        if !args.assignee_ids.is_empty() {
+            body.add("assignee_ids", args.assignee_ids.into());
+        }
+        if !args.reviewer_ids.is_empty() {
+            body.add("reviewer_ids", args.reviewer_ids.into());
+        }
+        body.add("description", args.description.into());
+        body.add("remove_source_branch", args.remove_source_branch.into());
+        if !args.labels.is_empty() {
+            body.add("labels", args.labels.join(",").into());
+        }
+        if let Some(milestone_id) = args.milestone_id {
+            body.add("milestone_id", milestone_id.into());
+        }
+        // Merge requests from forks are opened in the fork, pointing at the
+        // upstream project.
+        if let Some(target_project_id) = args.target_project_id {
+            body.add("target_project_id", target_project_id.into());
+        }
+        let target_path = args.target_repo.unwrap_or(self.path.clone());
         let url = format!("{}/merge_requests", self.rest_api_basepath());
         let response = query::gitlab_merge_request_response(
             &self.runner,
@@ -50,7 +84,7 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Gitlab<R> {
                 .trim_matches('!');
             let merge_request_url = format!(
                 "https://{}/{}/-/merge_requests/{}",
-                self.domain, self.path, merge_request_iid
+                self.domain, target_path, merge_request_iid
             );
             return Ok(MergeRequestResponse::builder()
                 .id(merge_request_iid.parse().unwrap())
@@ -75,14 +109,39 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Gitlab<R> {
 
     fn list(&self, args: MergeRequestListBodyArgs) -> Result<Vec<MergeRequestResponse>> {
         let url = self.list_merge_request_url(&args, false);
-        query::gitlab_list_merge_requests(
+        let merge_requests = query::gitlab_list_merge_requests(
             &self.runner,
             &url,
             args.list_args,
             self.headers(),
             None,
             ApiOperation::MergeRequest,
-        )
+        )?;
+        if !args.passing_ci {
+            return Ok(merge_requests);
+        }
+        // Only single merge requests come with their head pipeline.
+        let mut passing = Vec::new();
+        for merge_request in merge_requests {
+            // https://docs.gitlab.com/ee/api/merge_requests.html#get-single-mr
+            let url = format!(
+                "{}/merge_requests/{}",
+                self.rest_api_basepath(),
+                merge_request.id
+            );
+            let details = query::gitlab_merge_request_json::<_, ()>(
+                &self.runner,
+                &url,
+                None,
+                self.headers(),
+                GET,
+                ApiOperation::Pipeline,
+            )?;
+            if details["head_pipeline"]["status"].as_str() == Some("success") {
+                passing.push(merge_request);
+            }
+        }
+        Ok(passing)
     }
 
     fn merge(&self, id: i64) -> Result<MergeRequestResponse> {
@@ -125,14 +184,74 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Gitlab<R> {
         )
     }
 
-    fn num_pages(&self, args: MergeRequestListBodyArgs) -> Result<Option<u32>> {
+    fn reopen(&self, id: i64) -> Result<MergeRequestResponse> {
+        let url = format!("{}/merge_requests/{}", self.rest_api_basepath(), id);
+        let mut body = Body::new();
+        body.add("state_event", "reopen");
+        query::gitlab_merge_request::<_, &str>(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::PUT,
+            ApiOperation::MergeRequest,
+        )
+    }
+
+    fn set_draft(&self, id: i64, draft: bool) -> Result<MergeRequestResponse> {
+        // Gitlab tracks draft status through the title prefix.
+        let merge_request = MergeRequest::get(self, id)?;
+        let title = if draft {
+            draft_title(&merge_request.title)
+        } else {
+            ready_title(&merge_request.title)
+        };
+        if title == merge_request.title {
+            return Ok(merge_request);
+        }
+        let url = format!("{}/merge_requests/{}", self.rest_api_basepath(), id);
+        let mut body = Body::new();
+        body.add("title", title);
+        query::gitlab_merge_request(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::PUT,
+            ApiOperation::MergeRequest,
+        )
+    }
+
+    fn num_pages(&self, args: MergeRequestListBodyArgs) -> Result<Option<PageCount>> {
         let url = self.list_merge_request_url(&args, true);
-        let mut headers = Headers::new();
-        headers.set("PRIVATE-TOKEN", self.api_token());
-        query::num_pages(&self.runner, &url, headers, ApiOperation::MergeRequest)
+        query::num_pages(
+            &self.runner,
+            &url,
+            self.headers(),
+            ApiOperation::MergeRequest,
+        )
+    }
+
+    fn limits(&self) -> MergeRequestLimits {
+        MergeRequestLimits {
+            title: GITLAB_MERGE_REQUEST_TITLE_MAX_CHARS,
+            description: GITLAB_MERGE_REQUEST_DESCRIPTION_MAX_CHARS,
+        }
     }
 
-    fn approve(&self, id: i64) -> Result<MergeRequestResponse> {
+    fn approve(&self, id: i64, comment: Option<&str>) -> Result<MergeRequestResponse> {
+        // Approvals carry no message in Gitlab. The note goes first, so there
+        // is no approval without it.
+        if let Some(comment) = comment {
+            CommentMergeRequest::create(
+                self,
+                CommentMergeRequestBodyArgs::builder()
+                    .id(id)
+                    .comment(comment.to_string())
+                    .build()
+                    .unwrap(),
+            )?;
+        }
         let url = format!("{}/merge_requests/{}/approve", self.rest_api_basepath(), id);
         let result = query::gitlab_merge_request::<_, ()>(
             &self.runner,
@@ -154,7 +273,17 @@ impl<R: HttpRunner<Response = Response>> MergeRequest for Gitlab<R> {
 
 impl<R> Gitlab<R> {
     fn list_merge_request_url(&self, args: &MergeRequestListBodyArgs, num_pages: bool) -> String {
-        let mut url = if let Some(assignee_id) = args.assignee_id {
+        let mut url = if let Some(reviewer) = &args.reviewer {
+            let merge_requests_url = if args.in_project {
+                format!("{}/merge_requests", self.rest_api_basepath())
+            } else {
+                self.merge_requests_url.clone()
+            };
+            format!(
+                "{}?state={}&reviewer_id={}",
+                merge_requests_url, args.state, reviewer.id
+            )
+        } else if let Some(assignee_id) = args.assignee_id {
             format!(
                 "{}?state={}&assignee_id={}",
                 self.merge_requests_url, args.state, assignee_id
@@ -166,6 +295,18 @@ impl<R> Gitlab<R> {
                 args.state
             )
         };
+        if let Some(author) = &args.author {
+            url.push_str(&format!("&author_username={}", author));
+        }
+        if let Some(milestone) = &args.milestone {
+            url.push_str(&format!(
+                "&milestone={}",
+                encode_query_param(&milestone.title)
+            ));
+        }
+        if let Some(draft) = args.draft {
+            url.push_str(if draft { "&wip=yes" } else { "&wip=no" });
+        }
         if num_pages {
             url.push_str("&page=1");
         }
@@ -192,159 +333,1380 @@ impl<R: HttpRunner<Response = Response>> CommentMergeRequest for Gitlab<R> {
         )?;
         Ok(())
     }
+
+    fn list_comments(&self, id: i64) -> Result<Vec<CommentThread>> {
+        // https://docs.gitlab.com/ee/api/discussions.html#list-project-merge-request-discussion-items
+        let url = format!(
+            "{}/merge_requests/{}/discussions",
+            self.rest_api_basepath(),
+            id
+        );
+        let threads = query::gitlab_list_merge_request_discussions(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            None,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(threads
+            .into_iter()
+            .filter(|thread| !thread.comments.is_empty())
+            .collect())
+    }
 }
 
-pub struct GitlabMergeRequestFields {
-    id: i64,
-    web_url: String,
-    source_branch: String,
-    author: String,
-    updated_at: String,
-    created_at: String,
-    title: String,
-    description: String,
-    merged_at: String,
-    pipeline_id: Option<i64>,
-    pipeline_url: Option<String>,
+pub struct GitlabDiscussionFields {
+    thread: CommentThread,
 }
 
-impl From<&serde_json::Value> for GitlabMergeRequestFields {
+impl From<&serde_json::Value> for GitlabDiscussionFields {
     fn from(data: &serde_json::Value) -> Self {
-        GitlabMergeRequestFields {
-            id: data["iid"].as_i64().unwrap_or_default(),
-            web_url: data["web_url"].as_str().unwrap_or_default().to_string(),
-            source_branch: data["source_branch"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            author: data["author"]["username"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            updated_at: data["updated_at"].as_str().unwrap_or_default().to_string(),
-            created_at: data["created_at"].as_str().unwrap_or_default().to_string(),
-            title: data["title"].as_str().unwrap_or_default().to_string(),
-            description: data["description"].as_str().unwrap_or_default().to_string(),
-            // If merge request is not merged, merged_at is an empty string.
-            merged_at: data["merged_at"].as_str().unwrap_or_default().to_string(),
-            // Documentation recommends gathering head_pipeline instead of
-            // pipeline key.
-            pipeline_id: data["head_pipeline"]["id"].as_i64(),
-            pipeline_url: data["head_pipeline"]["web_url"]
-                .as_str()
-                .map(|s| s.to_string()),
+        let notes = data["notes"].as_array().cloned().unwrap_or_default();
+        let resolvable = notes
+            .iter()
+            .filter(|note| note["resolvable"].as_bool().unwrap_or_default())
+            .collect::<Vec<_>>();
+        let resolved = (!resolvable.is_empty()).then(|| {
+            resolvable
+                .iter()
+                .all(|note| note["resolved"].as_bool().unwrap_or_default())
+        });
+        // Diff notes on removed lines only have the old line.
+        let position = notes.first().and_then(|note| {
+            let position = &note["position"];
+            let (path, line) = match position["new_line"].as_i64() {
+                Some(line) => (position["new_path"].as_str()?, line),
+                None => (
+                    position["old_path"].as_str()?,
+                    position["old_line"].as_i64()?,
+                ),
+            };
+            Some(format!("{}:{}", path, line))
+        });
+        let comments = notes
+            .iter()
+            .filter(|note| !note["system"].as_bool().unwrap_or_default())
+            .map(|note| {
+                MergeRequestComment::builder()
+                    .id(note["id"].as_i64().unwrap_or_default())
+                    .author(
+                        note["author"]["username"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                    )
+                    .created_at(note["created_at"].as_str().unwrap_or_default().to_string())
+                    .body(note["body"].as_str().unwrap_or_default().to_string())
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        GitlabDiscussionFields {
+            thread: CommentThread::builder()
+                .id(data["id"].as_str().unwrap_or_default().to_string())
+                .position(position)
+                .resolved(resolved)
+                .comments(comments)
+                .build()
+                .unwrap(),
         }
     }
 }
 
-impl From<GitlabMergeRequestFields> for MergeRequestResponse {
-    fn from(fields: GitlabMergeRequestFields) -> Self {
-        MergeRequestResponse::builder()
-            .id(fields.id)
-            .web_url(fields.web_url)
-            .source_branch(fields.source_branch)
-            .author(fields.author)
-            .updated_at(fields.updated_at)
-            .created_at(fields.created_at)
-            .title(fields.title)
-            .description(fields.description)
-            .merged_at(fields.merged_at)
-            .pipeline_id(fields.pipeline_id)
-            .pipeline_url(fields.pipeline_url)
-            .build()
-            .unwrap()
+impl From<GitlabDiscussionFields> for CommentThread {
+    fn from(fields: GitlabDiscussionFields) -> Self {
+        fields.thread
     }
 }
 
-#[cfg(test)]
-mod test {
+impl<R: HttpRunner<Response = Response>> MergeRequestChecks for Gitlab<R> {
+    fn list(&self, id: i64) -> Result<Vec<MergeRequestCheck>> {
+        let url = format!("{}/merge_requests/{}", self.rest_api_basepath(), id);
+        let merge_request = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        let mut checks = Vec::new();
+        if let Some(pipeline_id) = merge_request["head_pipeline"]["id"].as_i64() {
+            let url = format!(
+                "{}/pipelines/{}/jobs",
+                self.rest_api_basepath(),
+                pipeline_id
+            );
+            checks = query::gitlab_list_check_jobs(
+                &self.runner,
+                &url,
+                None,
+                self.headers(),
+                None,
+                ApiOperation::Pipeline,
+            )?;
+        }
+        let sha = merge_request["sha"].as_str().unwrap_or_default();
+        let url = format!(
+            "{}/repository/commits/{}/statuses",
+            self.rest_api_basepath(),
+            sha
+        );
+        let statuses = query::gitlab_list_commit_statuses(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            None,
+            ApiOperation::Pipeline,
+        )?;
+        // Jobs are commit statuses too and share their ids. Keep only the
+        // statuses set by external services.
+        for status in statuses {
+            if !checks.iter().any(|check| check.id == status.id) {
+                checks.push(status);
+            }
+        }
+        Ok(checks)
+    }
 
-    use std::sync::Arc;
+    fn unmet_requirements(&self, id: i64) -> Result<Vec<String>> {
+        // Gitlab reports a single blocking reason at a time.
+        // https://docs.gitlab.com/ee/api/merge_requests.html#merge-status
+        let url = format!("{}/merge_requests/{}", self.rest_api_basepath(), id);
+        let merge_request = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        let status = merge_request["detailed_merge_status"]
+            .as_str()
+            .unwrap_or_default();
+        let unmet = match status {
+            // Mergeability is still being computed. Let the merge decide.
+            "mergeable" | "checking" | "unchecked" | "" => vec![],
+            "not_approved" => {
+                let url = format!(
+                    "{}/merge_requests/{}/approvals",
+                    self.rest_api_basepath(),
+                    id
+                );
+                let approvals = query::gitlab_merge_request_json::<_, ()>(
+                    &self.runner,
+                    &url,
+                    None,
+                    self.headers(),
+                    GET,
+                    ApiOperation::MergeRequest,
+                )?;
+                vec![format!(
+                    "{} more approvals are required",
+                    approvals["approvals_left"].as_i64().unwrap_or(1)
+                )]
+            }
+            "ci_must_pass" | "ci_still_running" | "external_status_checks" => {
+                let blocking = MergeRequestChecks::list(self, id)?
+                    .into_iter()
+                    .filter(|check| {
+                        check.state == CheckState::Fail || check.state == CheckState::Pending
+                    })
+                    .map(|check| format!("Required check {}: {}", check.name, check.state))
+                    .collect::<Vec<String>>();
+                if blocking.is_empty() {
+                    vec!["The pipeline must succeed".to_string()]
+                } else {
+                    blocking
+                }
+            }
+            "discussions_not_resolved" => vec!["All discussions must be resolved".to_string()],
+            "draft_status" => vec!["Merge request is a draft".to_string()],
+            "conflict" => vec!["Merge request has conflicts with the target branch".to_string()],
+            "need_rebase" => vec!["Source branch must be rebased onto the target".to_string()],
+            "not_open" => vec!["Merge request is not open".to_string()],
+            "blocked_status" => vec!["Merge request is blocked by another one".to_string()],
+            "requested_changes" => vec!["A reviewer requested changes".to_string()],
+            status => vec![format!("Merge request is not mergeable: {}", status)],
+        };
+        Ok(unmet)
+    }
+}
 
-    use crate::remote::{ListBodyArgs, MergeRequestState};
-    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+fn gitlab_check_state(data: &serde_json::Value) -> CheckState {
+    match data["status"].as_str().unwrap_or_default() {
+        "success" => CheckState::Pass,
+        "failed" if data["allow_failure"].as_bool().unwrap_or_default() => CheckState::Neutral,
+        "failed" | "canceled" => CheckState::Fail,
+        "skipped" | "manual" => CheckState::Neutral,
+        _ => CheckState::Pending,
+    }
+}
 
-    use super::*;
+pub struct GitlabCheckJobFields {
+    check: MergeRequestCheck,
+}
 
-    #[test]
-    fn test_list_merge_request_with_from_page() {
-        let config = config();
-        let domain = "gitlab.com".to_string();
-        let path = "jordilin/gitlapi".to_string();
-        let response = Response::builder()
-            .status(200)
-            .body("[]".to_string())
-            .build()
-            .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
-            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(Some(
-                ListBodyArgs::builder()
-                    .page(2)
-                    .max_pages(2)
-                    .build()
-                    .unwrap(),
-            ))
-            .assignee_id(None)
-            .build()
-            .unwrap();
-        gitlab.list(args).unwrap();
-        assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=2",
-            *client.url(),
-        );
+impl From<&serde_json::Value> for GitlabCheckJobFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabCheckJobFields {
+            check: MergeRequestCheck::builder()
+                .id(data["id"].as_i64().unwrap_or_default())
+                .name(data["name"].as_str().unwrap_or_default().to_string())
+                .kind("job".to_string())
+                .state(gitlab_check_state(data))
+                .web_url(data["web_url"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
     }
+}
 
-    #[test]
-    fn test_list_all_merge_requests_assigned_for_current_user() {
-        let config = config();
-        let domain = "gitlab.com".to_string();
-        let path = "jordilin/gitlapi".to_string();
-        let response = Response::builder()
-            .status(200)
-            .body("[]".to_string())
-            .build()
-            .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
-            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(None)
-            .assignee_id(Some(1234))
-            .build()
-            .unwrap();
-        gitlab.list(args).unwrap();
-        assert_eq!(
-            "https://gitlab.com/api/v4/merge_requests?state=opened&assignee_id=1234",
-            *client.url(),
-        );
+impl From<GitlabCheckJobFields> for MergeRequestCheck {
+    fn from(fields: GitlabCheckJobFields) -> Self {
+        fields.check
     }
+}
 
-    #[test]
-    fn test_open_merge_request() {
-        let config = config();
+pub struct GitlabCommitStatusFields {
+    check: MergeRequestCheck,
+}
 
-        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
+impl From<&serde_json::Value> for GitlabCommitStatusFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabCommitStatusFields {
+            check: MergeRequestCheck::builder()
+                .id(data["id"].as_i64().unwrap_or_default())
+                .name(data["name"].as_str().unwrap_or_default().to_string())
+                .kind("status".to_string())
+                .state(gitlab_check_state(data))
+                .web_url(data["target_url"].as_str().unwrap_or_default().to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+}
 
-        let domain = "gitlab.com".to_string();
-        let path = "jordilin/gitlapi";
-        let response = Response::builder()
-            .status(201)
-            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
-            .build()
-            .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
+impl From<GitlabCommitStatusFields> for MergeRequestCheck {
+    fn from(fields: GitlabCommitStatusFields) -> Self {
+        fields.check
+    }
+}
 
-        assert!(gitlab.open(mr_args).is_ok());
-        assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests",
-            *client.url(),
-        );
+// Merge trains are only available in Gitlab Premium and Ultimate.
+// https://docs.gitlab.com/ee/api/merge_trains.html
+impl<R: HttpRunner<Response = Response>> MergeRequestApprovals for Gitlab<R> {
+    fn approvals(&self, id: i64) -> Result<ApprovalStatus> {
+        // https://docs.gitlab.com/ee/api/merge_request_approvals.html#get-configuration-1
+        let url = format!(
+            "{}/merge_requests/{}/approvals",
+            self.rest_api_basepath(),
+            id
+        );
+        let approvals = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        let usernames = |users: &serde_json::Value, pointer: &str| -> Vec<String> {
+            users
+                .as_array()
+                .map(|users| {
+                    users
+                        .iter()
+                        .filter_map(|user| user.pointer(pointer)?.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let approved_by = usernames(&approvals["approved_by"], "/user/username");
+        let pending = usernames(&approvals["suggested_approvers"], "/username")
+            .into_iter()
+            .filter(|username| !approved_by.contains(username))
+            .collect();
+        Ok(ApprovalStatus::builder()
+            .approved(approvals["approved"].as_bool().unwrap_or_default())
+            .approvals_left(approvals["approvals_left"].as_i64())
+            .approved_by(approved_by)
+            .pending(pending)
+            .build()
+            .unwrap())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequestDiff for Gitlab<R> {
+    fn diff(&self, id: i64) -> Result<String> {
+        // https://docs.gitlab.com/ee/api/merge_requests.html#get-single-merge-request-changes
+        let url = format!("{}/merge_requests/{}/changes", self.rest_api_basepath(), id);
+        let changes = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        if changes["overflow"].as_bool().unwrap_or_default() {
+            log_info!(
+                "Gitlab did not return all the changes of merge request {}",
+                id
+            );
+        }
+        let diff = changes["changes"]
+            .as_array()
+            .map(|changes| changes.iter().map(unified_diff).collect())
+            .unwrap_or_default();
+        Ok(diff)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequestReviewDiff for Gitlab<R> {
+    fn reviewed_head(&self, id: i64, username: &str) -> Result<ReviewedHead> {
+        // Approvals do not record the commit they were given on. The last
+        // approval or comment of the user tells when they reviewed it, and
+        // the versions of the merge request which commit it had back then.
+        // https://docs.gitlab.com/ee/api/notes.html#list-all-merge-request-notes
+        let url = format!(
+            "{}/merge_requests/{}/notes?sort=desc&order_by=created_at&per_page=100",
+            self.rest_api_basepath(),
+            id
+        );
+        let notes = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        let reviewed_at = notes
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|note| {
+                note["author"]["username"].as_str() == Some(username)
+                    && (!note["system"].as_bool().unwrap_or_default()
+                        || note["body"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .starts_with("approved this merge request"))
+            })
+            .and_then(|note| note["created_at"].as_str()?.parse::<DateTime<Utc>>().ok());
+        // https://docs.gitlab.com/ee/api/merge_requests.html#get-merge-request-diff-versions
+        let url = format!(
+            "{}/merge_requests/{}/versions",
+            self.rest_api_basepath(),
+            id
+        );
+        let versions = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        // Newest version first.
+        let versions = versions.as_array().cloned().unwrap_or_default();
+        let head_sha = |version: &serde_json::Value| {
+            version["head_commit_sha"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        };
+        let reviewed_sha = reviewed_at.and_then(|reviewed_at| {
+            versions
+                .iter()
+                .find(|version| {
+                    version["created_at"]
+                        .as_str()
+                        .and_then(|created_at| created_at.parse::<DateTime<Utc>>().ok())
+                        .is_some_and(|created_at| created_at <= reviewed_at)
+                })
+                .map(head_sha)
+        });
+        Ok(ReviewedHead::builder()
+            .head_sha(versions.first().map(head_sha).unwrap_or_default())
+            .reviewed_sha(reviewed_sha)
+            .build()
+            .unwrap())
+    }
+
+    fn compare(&self, from_sha: &str, to_sha: &str) -> Result<String> {
+        // https://docs.gitlab.com/ee/api/repositories.html#compare-branches-tags-or-commits
+        let url = format!(
+            "{}/repository/compare?from={}&to={}",
+            self.rest_api_basepath(),
+            from_sha,
+            to_sha
+        );
+        let comparison = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        let diff = comparison["diffs"]
+            .as_array()
+            .map(|diffs| diffs.iter().map(unified_diff).collect())
+            .unwrap_or_default();
+        Ok(diff)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> FailingPipelines for Gitlab<R> {
+    fn failing(&self, user: &Member) -> Result<Vec<FailingPipeline>> {
+        // https://docs.gitlab.com/ee/api/merge_requests.html#list-merge-requests
+        let url = format!(
+            "{}?state=opened&scope=all&author_id={}&per_page=100",
+            self.merge_requests_url, user.id
+        );
+        let merge_requests = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        // Only single merge requests come with their head pipeline.
+        let mut failing = Vec::new();
+        for merge_request in merge_requests.as_array().into_iter().flatten() {
+            let iid = merge_request["iid"].as_i64().unwrap_or_default();
+            // https://docs.gitlab.com/ee/api/merge_requests.html#get-single-mr
+            let url = format!(
+                "{}/{}/merge_requests/{}",
+                self.base_project_url,
+                merge_request["project_id"].as_i64().unwrap_or_default(),
+                iid
+            );
+            let merge_request = query::gitlab_merge_request_json::<_, ()>(
+                &self.runner,
+                &url,
+                None,
+                self.headers(),
+                GET,
+                ApiOperation::Pipeline,
+            )?;
+            let pipeline = &merge_request["head_pipeline"];
+            let status = pipeline["status"].as_str().unwrap_or_default();
+            if status != "failed" {
+                continue;
+            }
+            // Ex. group/project!12
+            let reference = merge_request["references"]["full"]
+                .as_str()
+                .unwrap_or_default();
+            let project = reference
+                .rsplit_once('!')
+                .map_or(reference, |(path, _)| path);
+            failing.push(
+                FailingPipeline::builder()
+                    .project(project.to_string())
+                    .merge_request_id(iid)
+                    .title(
+                        merge_request["title"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                    )
+                    .status(status.to_string())
+                    .web_url(pipeline["web_url"].as_str().unwrap_or_default().to_string())
+                    .build()
+                    .unwrap(),
+            );
+        }
+        Ok(failing)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequestLabels for Gitlab<R> {
+    fn add(&self, id: i64, labels: &[String]) -> Result<Vec<String>> {
+        self.update_labels(id, "add_labels", labels)
+    }
+
+    fn remove(&self, id: i64, labels: &[String]) -> Result<Vec<String>> {
+        self.update_labels(id, "remove_labels", labels)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    fn update_labels(&self, id: i64, field: &str, labels: &[String]) -> Result<Vec<String>> {
+        // https://docs.gitlab.com/ee/api/merge_requests.html#update-mr
+        let url = format!("{}/merge_requests/{}", self.rest_api_basepath(), id);
+        let mut body = Body::new();
+        body.add(field, labels.join(","));
+        let merge_request = query::gitlab_merge_request_json(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::PUT,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(label_names(&merge_request["labels"]))
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> MergeRequestRebase for Gitlab<R> {
+    fn rebase(&self, id: i64) -> Result<String> {
+        // https://docs.gitlab.com/ee/api/merge_requests.html#rebase-a-merge-request
+        let url = format!("{}/merge_requests/{}", self.rest_api_basepath(), id);
+        let merge_request = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        query::gitlab_merge_request_response::<_, ()>(
+            &self.runner,
+            &format!("{}/rebase", url),
+            None,
+            self.headers(),
+            http::Method::PUT,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(merge_request["sha"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    fn rebase_progress(&self, id: i64, _from_sha: &str) -> Result<RebaseProgress> {
+        let url = format!(
+            "{}/merge_requests/{}?include_rebase_in_progress=true",
+            self.rest_api_basepath(),
+            id
+        );
+        let merge_request = query::gitlab_merge_request_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        if merge_request["rebase_in_progress"]
+            .as_bool()
+            .unwrap_or_default()
+        {
+            return Ok(RebaseProgress::InProgress);
+        }
+        if let Some(merge_error) = merge_request["merge_error"].as_str() {
+            return Ok(RebaseProgress::Failed(merge_error.to_string()));
+        }
+        let sha = merge_request["sha"].as_str().unwrap_or_default();
+        // The head pipeline is the one of the previous head until the new
+        // one gets created.
+        let pipeline = &merge_request["head_pipeline"];
+        let pipeline_url = match pipeline["sha"].as_str() {
+            Some(pipeline_sha) if pipeline_sha == sha => {
+                pipeline["web_url"].as_str().map(String::from)
+            }
+            _ => None,
+        };
+        Ok(RebaseProgress::Done {
+            sha: sha.to_string(),
+            pipeline_url,
+        })
+    }
+}
+
+/// Git style diff of a file out of an entry of the Gitlab changes API, which
+/// only carries the hunks.
+fn unified_diff(change: &serde_json::Value) -> String {
+    let field = |name: &str| change[name].as_str().unwrap_or_default();
+    let flag = |name: &str| change[name].as_bool().unwrap_or_default();
+    let (old_path, new_path) = (field("old_path"), field("new_path"));
+    let mut diff = format!("diff --git a/{} b/{}\n", old_path, new_path);
+    if flag("new_file") {
+        diff.push_str(&format!("new file mode {}\n", field("b_mode")));
+    } else if flag("deleted_file") {
+        diff.push_str(&format!("deleted file mode {}\n", field("a_mode")));
+    } else if flag("renamed_file") {
+        diff.push_str(&format!(
+            "rename from {}\nrename to {}\n",
+            old_path, new_path
+        ));
+    }
+    let hunks = field("diff");
+    if hunks.is_empty() {
+        return diff;
+    }
+    let old_file = if flag("new_file") {
+        "/dev/null".to_string()
+    } else {
+        format!("a/{}", old_path)
+    };
+    let new_file = if flag("deleted_file") {
+        "/dev/null".to_string()
+    } else {
+        format!("b/{}", new_path)
+    };
+    diff.push_str(&format!("--- {}\n+++ {}\n{}", old_file, new_file, hunks));
+    if !hunks.ends_with('\n') {
+        diff.push('\n');
+    }
+    diff
+}
+
+impl<R: HttpRunner<Response = Response>> MergeQueue for Gitlab<R> {
+    fn list(&self, args: MergeQueueListBodyArgs) -> Result<Vec<MergeQueueEntry>> {
+        let url = format!(
+            "{}/merge_trains/{}?scope=active&sort=asc",
+            self.rest_api_basepath(),
+            encode_query_param(&args.branch)
+        );
+        let entries = query::gitlab_list_merge_train(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            None,
+            ApiOperation::MergeRequest,
+        )?;
+        // Cars are returned in train order. Gitlab does not give their
+        // position, so it is derived from it.
+        Ok(entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut entry)| {
+                entry.position = index as i64 + 1;
+                entry
+            })
+            .collect())
+    }
+
+    fn get(&self, id: i64) -> Result<MergeQueueEntry> {
+        let url = format!(
+            "{}/merge_trains/merge_requests/{}",
+            self.rest_api_basepath(),
+            id
+        );
+        let mut entry = query::gitlab_merge_train_car::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            GET,
+            ApiOperation::MergeRequest,
+        )?;
+        let train = MergeQueue::list(
+            self,
+            MergeQueueListBodyArgs::builder()
+                .branch(entry.target_branch.clone())
+                .build()?,
+        )?;
+        if let Some(car) = train.iter().find(|car| car.id == entry.id) {
+            entry.position = car.position;
+        }
+        Ok(entry)
+    }
+
+    fn add(&self, id: i64) -> Result<()> {
+        let url = format!(
+            "{}/merge_trains/merge_requests/{}",
+            self.rest_api_basepath(),
+            id
+        );
+        // Join the train once the merge request pipeline succeeds instead of
+        // failing when it is still running.
+        let mut body = Body::new();
+        body.add("auto_merge", "true");
+        query::gitlab_merge_request_response(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(())
+    }
+
+    fn remove(&self, id: i64) -> Result<()> {
+        // Cancelling the auto merge is how a merge request leaves the train.
+        let url = format!(
+            "{}/merge_requests/{}/cancel_merge_when_pipeline_succeeds",
+            self.rest_api_basepath(),
+            id
+        );
+        query::gitlab_merge_request_response::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(())
+    }
+}
+
+pub struct GitlabMergeTrainFields {
+    entry: MergeQueueEntry,
+}
+
+impl From<&serde_json::Value> for GitlabMergeTrainFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabMergeTrainFields {
+            entry: MergeQueueEntry::builder()
+                .position(0)
+                .id(data["merge_request"]["iid"].as_i64().unwrap_or_default())
+                .title(
+                    data["merge_request"]["title"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .target_branch(
+                    data["target_branch"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .status(data["status"].as_str().unwrap_or_default().to_string())
+                .enqueued_by(
+                    data["user"]["username"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .enqueued_at(data["created_at"].as_str().unwrap_or_default().to_string())
+                .web_url(
+                    data["merge_request"]["web_url"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GitlabMergeTrainFields> for MergeQueueEntry {
+    fn from(fields: GitlabMergeTrainFields) -> Self {
+        fields.entry
+    }
+}
+
+pub struct GitlabMergeRequestFields {
+    id: i64,
+    web_url: String,
+    source_branch: String,
+    target_branch: String,
+    author: String,
+    updated_at: String,
+    created_at: String,
+    title: String,
+    description: String,
+    merged_at: String,
+    pipeline_id: Option<i64>,
+    pipeline_url: Option<String>,
+    labels: Vec<String>,
+}
+
+impl From<&serde_json::Value> for GitlabMergeRequestFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabMergeRequestFields {
+            id: data["iid"].as_i64().unwrap_or_default(),
+            web_url: data["web_url"].as_str().unwrap_or_default().to_string(),
+            source_branch: data["source_branch"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            target_branch: data["target_branch"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            author: data["author"]["username"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            updated_at: data["updated_at"].as_str().unwrap_or_default().to_string(),
+            created_at: data["created_at"].as_str().unwrap_or_default().to_string(),
+            title: data["title"].as_str().unwrap_or_default().to_string(),
+            description: data["description"].as_str().unwrap_or_default().to_string(),
+            // If merge request is not merged, merged_at is an empty string.
+            merged_at: data["merged_at"].as_str().unwrap_or_default().to_string(),
+            // Documentation recommends gathering head_pipeline instead of
+            // pipeline key.
+            pipeline_id: data["head_pipeline"]["id"].as_i64(),
+            pipeline_url: data["head_pipeline"]["web_url"]
+                .as_str()
+                .map(|s| s.to_string()),
+            labels: label_names(&data["labels"]),
+        }
+    }
+}
+
+fn label_names(labels: &serde_json::Value) -> Vec<String> {
+    labels
+        .as_array()
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|label| label.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl From<GitlabMergeRequestFields> for MergeRequestResponse {
+    fn from(fields: GitlabMergeRequestFields) -> Self {
+        MergeRequestResponse::builder()
+            .id(fields.id)
+            .web_url(fields.web_url)
+            .source_branch(fields.source_branch)
+            .target_branch(fields.target_branch)
+            .author(fields.author)
+            .updated_at(fields.updated_at)
+            .created_at(fields.created_at)
+            .title(fields.title)
+            .description(fields.description)
+            .merged_at(fields.merged_at)
+            .pipeline_id(fields.pipeline_id)
+            .pipeline_url(fields.pipeline_url)
+            .labels(fields.labels)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::http::Headers;
+    use crate::remote::{ListBodyArgs, Member, MergeRequestState, Milestone};
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use super::*;
+
+    #[test]
+    fn test_list_merge_request_with_from_page() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(Some(
+                ListBodyArgs::builder()
+                    .page(2)
+                    .max_pages(2)
+                    .build()
+                    .unwrap(),
+            ))
+            .assignee_id(None)
+            .build()
+            .unwrap();
+        gitlab.list(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=2",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_merge_requests_by_author() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .author(Some("tsmith".to_string()))
+            .build()
+            .unwrap();
+        gitlab.list(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&author_username=tsmith",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_merge_requests_by_milestone() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let milestone = Milestone::builder()
+            .id(7)
+            .title("Release 1.0".to_string())
+            .build()
+            .unwrap();
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .milestone(Some(milestone))
+            .build()
+            .unwrap();
+        gitlab.list(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&milestone=Release%201.0",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_all_merge_requests_assigned_for_current_user() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(Some(1234))
+            .build()
+            .unwrap();
+        gitlab.list(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/merge_requests?state=opened&assignee_id=1234",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_all_merge_requests_review_requested_for_current_user() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .reviewer(Some(
+                Member::builder()
+                    .id(1234)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap();
+        gitlab.list(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/merge_requests?state=opened&reviewer_id=1234",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_merge_requests_needing_review_with_passing_ci() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let list = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"iid": 7, "title": "Green", "author": {"username": "jdoe"},
+                     "created_at": "2024-03-01T10:00:00Z"},
+                    {"iid": 8, "title": "Red", "author": {"username": "jdoe"},
+                     "created_at": "2024-03-02T10:00:00Z"}]"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let green = Response::builder()
+            .status(200)
+            .body(r#"{"iid": 7, "head_pipeline": {"id": 70, "status": "success"}}"#.to_string())
+            .build()
+            .unwrap();
+        let red = Response::builder()
+            .status(200)
+            .body(r#"{"iid": 8, "head_pipeline": {"id": 80, "status": "failed"}}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![red, green, list]));
+        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
+        let args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .reviewer(Some(
+                Member::builder()
+                    .id(1234)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .in_project(true)
+            .draft(Some(false))
+            .passing_ci(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&reviewer_id=1234&wip=no",
+            gitlab.list_merge_request_url(&args, false)
+        );
+        let merge_requests = MergeRequest::list(&gitlab, args).unwrap();
+        assert_eq!(1, merge_requests.len());
+        assert_eq!("Green", merge_requests[0].title);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/8",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_open_merge_request() {
+        let config = config();
+
+        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
+
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, &domain, path, client.clone());
+
+        assert!(gitlab.open(mr_args).is_ok());
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests",
+            *client.url(),
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_open_merge_request_with_multiple_assignees() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .assignee_ids(vec![1, 2])
+            .assignee_usernames(vec!["jdoe".to_string(), "tsmith".to_string()])
+            .build()
+            .unwrap();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, &domain, path, client.clone());
+
+        gitlab.open(mr_args).unwrap();
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(serde_json::json!([1, 2]), body["assignee_ids"]);
+    }
+
+    #[test]
+    fn test_open_merge_request_with_reviewers() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .reviewer_ids(vec![3, 4])
+            .reviewer_usernames(vec!["jdoe".to_string(), "tsmith".to_string()])
+            .build()
+            .unwrap();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, &domain, path, client.clone());
+
+        gitlab.open(mr_args).unwrap();
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(serde_json::json!([3, 4]), body["reviewer_ids"]);
+        assert!(body.get("assignee_ids").is_none());
+    }
+
+    #[test]
+    fn test_open_merge_request_from_fork() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .target_repo(Some("upstream/gitlapi".to_string()))
+            .target_project_id(Some(1234))
+            .build()
+            .unwrap();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, &domain, path, client.clone());
+
+        gitlab.open(mr_args).unwrap();
+        // Opened in the fork, targeting the upstream project.
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests",
+            *client.url(),
+        );
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(serde_json::json!(1234), body["target_project_id"]);
+    }
+
+    #[test]
+    fn test_open_merge_request_with_milestone() {
+        let config = config();
+        let mr_args = MergeRequestBodyArgs::builder()
+            .milestone_id(Some(7))
+            .build()
+            .unwrap();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, &domain, path, client.clone());
+
+        gitlab.open(mr_args).unwrap();
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(serde_json::json!(7), body["milestone_id"]);
+    }
+
+    #[test]
+    fn test_open_merge_request_error() {
+        let config = config();
+
+        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder().status(400).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, &domain, &path, client);
+        assert!(gitlab.open(mr_args).is_err());
+    }
+    #[test]
+    fn test_merge_request_already_exists_status_code_409_conflict() {
+        let config = config();
+
+        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
+
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(409)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "merge_request_conflict.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, &domain, &path, client);
+
+        assert!(gitlab.open(mr_args).is_ok());
+    }
+    #[test]
+    fn test_gitlab_merge_request_num_pages() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let link_header = "<https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=1>; rel=\"next\", <https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=2>; rel=\"last\"";
+        let mut headers = Headers::new();
+        headers.set("link", link_header);
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let body_args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .build()
+            .unwrap();
+        assert_eq!(
+            Some(PageCount::from(2)),
+            gitlab.num_pages(body_args).unwrap()
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=1",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_gitlab_merge_request_num_pages_current_auth_user() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let link_header = "<https://gitlab.com/api/v4/merge_requests?state=opened&assignee_id=1234&page=1>; rel=\"next\", <https://gitlab.com/api/v4/merge_requests?state=opened&assignee_id=1234&page=2>; rel=\"last\"";
+        let mut headers = Headers::new();
+        headers.set("link", link_header);
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let body_args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(Some(1234))
+            .build()
+            .unwrap();
+        assert_eq!(
+            Some(PageCount::from(2)),
+            gitlab.num_pages(body_args).unwrap()
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/merge_requests?state=opened&assignee_id=1234&page=1",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_gitlab_merge_request_num_pages_no_link_header_error() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder().status(200).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let body_args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .build()
+            .unwrap();
+        assert_eq!(
+            Some(PageCount::from(1)),
+            gitlab.num_pages(body_args).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_gitlab_merge_request_num_pages_response_error_is_error() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder().status(400).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let body_args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .build()
+            .unwrap();
+        assert!(gitlab.num_pages(body_args).is_err());
+    }
+
+    #[test]
+    fn test_gitlab_merge_request_num_pages_no_last_header_in_link() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let link_header = "<https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=1>; rel=\"next\"";
+        let mut headers = Headers::new();
+        headers.set("link", link_header);
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let body_args = MergeRequestListBodyArgs::builder()
+            .state(MergeRequestState::Opened)
+            .list_args(None)
+            .assignee_id(None)
+            .build()
+            .unwrap();
+        assert_eq!(None, gitlab.num_pages(body_args).unwrap());
+    }
+
+    #[test]
+    fn test_gitlab_create_merge_request_comment_ok() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CommentMergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let comment_args = CommentMergeRequestBodyArgs::builder()
+            .id(1456)
+            .comment("LGTM, ship it".to_string())
+            .build()
+            .unwrap();
+        gitlab.create(comment_args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/1456/notes",
+            *client.url()
+        );
         assert_eq!(
             Some(ApiOperation::MergeRequest),
             *client.api_operation.borrow()
@@ -352,315 +1714,877 @@ mod test {
     }
 
     #[test]
-    fn test_open_merge_request_error() {
+    fn test_gitlab_create_merge_request_comment_error() {
         let config = config();
-
-        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
         let response = Response::builder().status(400).build().unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client);
-        assert!(gitlab.open(mr_args).is_err());
+        let gitlab: Box<dyn CommentMergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let comment_args = CommentMergeRequestBodyArgs::builder()
+            .id(1456)
+            .comment("LGTM, ship it".to_string())
+            .build()
+            .unwrap();
+        assert!(gitlab.create(comment_args).is_err());
     }
+
     #[test]
-    fn test_merge_request_already_exists_status_code_409_conflict() {
+    fn test_get_gitlab_merge_request_details() {
         let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let merge_request_id = 123456;
+        let merge_request = gitlab.get(merge_request_id).unwrap();
+        assert_eq!("main", merge_request.target_branch);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/123456",
+            *client.url()
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
 
-        let mr_args = MergeRequestBodyArgs::builder().build().unwrap();
+    #[test]
+    fn test_requests_include_extra_headers_from_config() {
+        let config_data = r#"
+        gitlab.com.api_token=1234
+        gitlab.com.cache_location=/home/user/.config/mr_cache
+        gitlab.com.extra_header_CF_Access_Client_Id=client.access
+        gitlab.com.user_agent_suffix=ci-bot
+        "#;
+        let config =
+            crate::config::Config::new(std::io::Cursor::new(config_data), "gitlab.com").unwrap();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> = Box::new(Gitlab::new(
+            config,
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        gitlab.get(123456).unwrap();
+        let headers = client.headers();
+        assert_eq!(
+            Some(&"client.access".to_string()),
+            headers.get("CF-Access-Client-Id")
+        );
+        assert_eq!(Some(&"1234".to_string()), headers.get("PRIVATE-TOKEN"));
+        assert_eq!(
+            Some(&format!("gitar/{} ci-bot", env!("CARGO_PKG_VERSION"))),
+            headers.get("User-Agent")
+        );
+    }
 
+    #[test]
+    fn test_merge_merge_request() {
+        let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
         let response = Response::builder()
-            .status(409)
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let merge_request_id = 33;
+        gitlab.merge(merge_request_id).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/merge",
+            *client.url()
+        );
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_reopen_merge_request() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        gitlab.reopen(33).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33",
+            *client.url()
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!("reopen", body["state_event"]);
+    }
+
+    #[test]
+    fn test_mark_merge_request_as_draft_prefixes_title() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = || {
+            Response::builder()
+                .status(200)
+                .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+                .build()
+                .unwrap()
+        };
+        let client = Arc::new(MockRunner::new(vec![response(), response()]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        gitlab.set_draft(33, true).unwrap();
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!("DRAFT: New Feature", body["title"]);
+    }
+
+    #[test]
+    fn test_mark_ready_merge_request_without_draft_prefix_is_noop() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let merge_request = gitlab.set_draft(33, false).unwrap();
+        assert_eq!("New Feature", merge_request.title);
+        assert_eq!(http::Method::GET, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_close_merge_request() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let merge_request_id = 33;
+        gitlab.close(merge_request_id).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33",
+            *client.url()
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    #[test]
+    fn test_approve_merge_request_with_comment_posts_note_first() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let note_response = Response::builder().status(201).build().unwrap();
+        let approve_response = Response::builder()
+            .status(200)
             .body(get_contract(
                 ContractType::Gitlab,
-                "merge_request_conflict.json",
+                "approve_merge_request.json",
             ))
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client);
+        let client = Arc::new(MockRunner::new(vec![approve_response, note_response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        gitlab.approve(33, Some("Checklist confirmed")).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/approve",
+            *client.url()
+        );
+    }
 
-        assert!(gitlab.open(mr_args).is_ok());
+    #[test]
+    fn test_approve_merge_request_not_approved_if_note_fails() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let note_response = Response::builder().status(500).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![note_response]));
+        let gitlab: Box<dyn MergeRequest> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        assert!(gitlab.approve(33, Some("Checklist confirmed")).is_err());
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/notes",
+            *client.url()
+        );
     }
+
     #[test]
-    fn test_gitlab_merge_request_num_pages() {
+    fn test_approve_merge_request_ok() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
-        let link_header = "<https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=1>; rel=\"next\", <https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=2>; rel=\"last\"";
-        let mut headers = Headers::new();
-        headers.set("link", link_header);
         let response = Response::builder()
             .status(200)
-            .headers(headers)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "approve_merge_request.json",
+            ))
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
         let gitlab: Box<dyn MergeRequest> =
             Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let body_args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(None)
-            .assignee_id(None)
+        let merge_request_id = 33;
+        let result = gitlab.approve(merge_request_id, None);
+        match result {
+            Ok(response) => {
+                assert_eq!(
+                    "https://gitlab.com/jordilin/gitlapi/-/merge_requests/33",
+                    response.web_url
+                );
+            }
+            Err(e) => {
+                panic!(
+                    "Expected Ok merge request approval but got: {:?} instead",
+                    e
+                );
+            }
+        }
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/approve",
+            *client.url()
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert_eq!(
+            Some(ApiOperation::MergeRequest),
+            *client.api_operation.borrow()
+        );
+    }
+
+    const MERGE_TRAIN: &str = r#"[
+        {
+            "id": 110,
+            "merge_request": {"iid": 59, "title": "Add pipeline retries", "web_url": "https://gitlab.com/jordilin/gitlapi/-/merge_requests/59"},
+            "user": {"username": "jordilin"},
+            "created_at": "2024-02-01T10:00:00.000Z",
+            "target_branch": "main",
+            "status": "fresh"
+        },
+        {
+            "id": 111,
+            "merge_request": {"iid": 60, "title": "Fix cache expiration", "web_url": "https://gitlab.com/jordilin/gitlapi/-/merge_requests/60"},
+            "user": {"username": "tsmith"},
+            "created_at": "2024-02-01T10:05:00.000Z",
+            "target_branch": "main",
+            "status": "idle"
+        }
+    ]"#;
+
+    #[test]
+    fn test_list_merge_train_positions_follow_train_order() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(MERGE_TRAIN.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeQueue> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = MergeQueueListBodyArgs::builder()
+            .branch("release/1.0".to_string())
             .build()
             .unwrap();
-        assert_eq!(Some(2), gitlab.num_pages(body_args).unwrap());
+        let entries = gitlab.list(args).unwrap();
         assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=1",
-            *client.url(),
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_trains/release%2F1.0?scope=active&sort=asc",
+            *client.url()
         );
+        assert_eq!(2, entries.len());
+        assert_eq!((1, 59), (entries[0].position, entries[0].id));
+        assert_eq!((2, 60), (entries[1].position, entries[1].id));
+        assert_eq!("tsmith", entries[1].enqueued_by);
     }
 
     #[test]
-    fn test_gitlab_merge_request_num_pages_current_auth_user() {
+    fn test_get_merge_train_car_with_position() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
-        let link_header = "<https://gitlab.com/api/v4/merge_requests?state=opened&assignee_id=1234&page=1>; rel=\"next\", <https://gitlab.com/api/v4/merge_requests?state=opened&assignee_id=1234&page=2>; rel=\"last\"";
-        let mut headers = Headers::new();
-        headers.set("link", link_header);
-        let response = Response::builder()
+        let car = serde_json::from_str::<serde_json::Value>(MERGE_TRAIN).unwrap()[1].to_string();
+        let car_response = Response::builder().status(200).body(car).build().unwrap();
+        let train_response = Response::builder()
             .status(200)
-            .headers(headers)
+            .body(MERGE_TRAIN.to_string())
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
+        let client = Arc::new(MockRunner::new(vec![train_response, car_response]));
+        let gitlab: Box<dyn MergeQueue> =
             Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let body_args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(None)
-            .assignee_id(Some(1234))
-            .build()
-            .unwrap();
-        assert_eq!(Some(2), gitlab.num_pages(body_args).unwrap());
+        let entry = gitlab.get(60).unwrap();
+        assert_eq!(2, entry.position);
+        assert_eq!("idle", entry.status);
         assert_eq!(
-            "https://gitlab.com/api/v4/merge_requests?state=opened&assignee_id=1234&page=1",
-            *client.url(),
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_trains/main?scope=active&sort=asc",
+            *client.url()
         );
     }
 
     #[test]
-    fn test_gitlab_merge_request_num_pages_no_link_header_error() {
+    fn test_add_merge_request_to_merge_train() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
-        let response = Response::builder().status(200).build().unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
-            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let body_args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(None)
-            .assignee_id(None)
+        let response = Response::builder()
+            .status(201)
+            .body(MERGE_TRAIN.to_string())
             .build()
             .unwrap();
-        assert_eq!(Some(1), gitlab.num_pages(body_args).unwrap());
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeQueue> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        gitlab.add(60).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_trains/merge_requests/60",
+            *client.url()
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
     }
 
     #[test]
-    fn test_gitlab_merge_request_num_pages_response_error_is_error() {
+    fn test_remove_merge_request_from_merge_train() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
-        let response = Response::builder().status(400).build().unwrap();
+        let response = Response::builder().status(201).build().unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
+        let gitlab: Box<dyn MergeQueue> =
             Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let body_args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(None)
-            .assignee_id(None)
-            .build()
-            .unwrap();
-        assert!(gitlab.num_pages(body_args).is_err());
+        gitlab.remove(60).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/60/cancel_merge_when_pipeline_succeeds",
+            *client.url()
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
     }
 
     #[test]
-    fn test_gitlab_merge_request_num_pages_no_last_header_in_link() {
+    fn test_list_merge_request_checks_jobs_and_external_statuses() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
-        let link_header = "<https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests?state=opened&page=1>; rel=\"next\"";
-        let mut headers = Headers::new();
-        headers.set("link", link_header);
-        let response = Response::builder()
+        let mr_response = Response::builder()
             .status(200)
-            .headers(headers)
+            .body(r#"{"iid": 33, "sha": "abc123", "head_pipeline": {"id": 500}}"#.to_string())
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
-            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let body_args = MergeRequestListBodyArgs::builder()
-            .state(MergeRequestState::Opened)
-            .list_args(None)
-            .assignee_id(None)
+        let jobs_response = Response::builder()
+            .status(200)
+            .body(
+                r#"[
+                {"id": 1, "name": "build", "status": "success", "web_url": "https://gitlab.com/jobs/1"},
+                {"id": 2, "name": "lint", "status": "failed", "allow_failure": true, "web_url": "https://gitlab.com/jobs/2"},
+                {"id": 3, "name": "test", "status": "running", "web_url": "https://gitlab.com/jobs/3"}
+            ]"#
+                .to_string(),
+            )
             .build()
             .unwrap();
-        assert_eq!(None, gitlab.num_pages(body_args).unwrap());
+        let statuses_response = Response::builder()
+            .status(200)
+            .body(
+                r#"[
+                {"id": 1, "name": "build", "status": "success", "target_url": null},
+                {"id": 9, "name": "security/scan", "status": "failed", "target_url": "https://scanner.example.com/9"}
+            ]"#
+                .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![
+            statuses_response,
+            jobs_response,
+            mr_response,
+        ]));
+        let gitlab: Box<dyn MergeRequestChecks> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let checks = gitlab.list(33).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/repository/commits/abc123/statuses",
+            *client.url()
+        );
+        let states = checks
+            .iter()
+            .map(|check| (check.name.as_str(), check.kind.as_str(), check.state))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("build", "job", CheckState::Pass),
+                ("lint", "job", CheckState::Neutral),
+                ("test", "job", CheckState::Pending),
+                ("security/scan", "status", CheckState::Fail),
+            ],
+            states
+        );
     }
 
     #[test]
-    fn test_gitlab_create_merge_request_comment_ok() {
+    fn test_list_merge_request_discussions() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
-        let response = Response::builder().status(201).build().unwrap();
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"[
+                {"id": "d1", "individual_note": true, "notes": [
+                    {"id": 1, "body": "added 1 commit", "system": true,
+                     "author": {"username": "jdoe"}, "created_at": "2024-03-01T10:00:00Z"}
+                ]},
+                {"id": "d2", "individual_note": true, "notes": [
+                    {"id": 2, "body": "Nice work", "system": false, "resolvable": false,
+                     "author": {"username": "alice"}, "created_at": "2024-03-02T10:00:00Z"}
+                ]},
+                {"id": "d3", "individual_note": false, "notes": [
+                    {"id": 3, "body": "Off by one?", "system": false, "resolvable": true,
+                     "resolved": true, "author": {"username": "alice"},
+                     "created_at": "2024-03-03T10:00:00Z",
+                     "position": {"new_path": "src/main.rs", "new_line": null,
+                                  "old_path": "src/main.rs", "old_line": 12}},
+                    {"id": 4, "body": "Fixed", "system": false, "resolvable": true,
+                     "resolved": true, "author": {"username": "jdoe"},
+                     "created_at": "2024-03-04T10:00:00Z"}
+                ]}
+            ]"#
+                .to_string(),
+            )
+            .build()
+            .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
         let gitlab: Box<dyn CommentMergeRequest> =
             Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let comment_args = CommentMergeRequestBodyArgs::builder()
-            .id(1456)
-            .comment("LGTM, ship it".to_string())
-            .build()
-            .unwrap();
-        gitlab.create(comment_args).unwrap();
+        let threads = gitlab.list_comments(33).unwrap();
         assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/1456/notes",
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/discussions",
             *client.url()
         );
-        assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
-        );
+        assert_eq!(2, threads.len());
+        assert_eq!("d2", threads[0].id);
+        assert_eq!(None, threads[0].resolved);
+        assert_eq!(None, threads[0].position);
+        assert_eq!(Some(true), threads[1].resolved);
+        assert_eq!(Some("src/main.rs:12".to_string()), threads[1].position);
+        let comments = threads[1]
+            .comments
+            .iter()
+            .map(|comment| (comment.author.as_str(), comment.body.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![("alice", "Off by one?"), ("jdoe", "Fixed")], comments);
     }
 
     #[test]
-    fn test_gitlab_create_merge_request_comment_error() {
+    fn test_unmet_requirements_reports_approvals_left() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
-        let response = Response::builder().status(400).build().unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn CommentMergeRequest> =
-            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let comment_args = CommentMergeRequestBodyArgs::builder()
-            .id(1456)
-            .comment("LGTM, ship it".to_string())
+        let mr_response = Response::builder()
+            .status(200)
+            .body(r#"{"iid": 33, "detailed_merge_status": "not_approved"}"#.to_string())
             .build()
             .unwrap();
-        assert!(gitlab.create(comment_args).is_err());
+        let approvals_response = Response::builder()
+            .status(200)
+            .body(r#"{"approvals_required": 2, "approvals_left": 2}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![approvals_response, mr_response]));
+        let gitlab: Box<dyn MergeRequestChecks> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        assert_eq!(
+            vec!["2 more approvals are required".to_string()],
+            gitlab.unmet_requirements(33).unwrap()
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/approvals",
+            *client.url()
+        );
     }
 
     #[test]
-    fn test_get_gitlab_merge_request_details() {
+    fn test_merge_request_approvals() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .body(get_contract(
+                ContractType::Gitlab,
+                "merge_request_approvals.json",
+            ))
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
+        let gitlab: Box<dyn MergeRequestApprovals> =
             Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let merge_request_id = 123456;
-        gitlab.get(merge_request_id).unwrap();
+        let status = gitlab.approvals(33).unwrap();
         assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/123456",
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/approvals",
             *client.url()
         );
-        assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
-        );
+        assert!(!status.approved);
+        assert_eq!(Some(1), status.approvals_left);
+        assert_eq!(vec!["jordilin".to_string()], status.approved_by);
+        assert_eq!(vec!["octocat".to_string()], status.pending);
     }
 
     #[test]
-    fn test_merge_merge_request() {
+    fn test_merge_request_diff_from_changes() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .body(get_contract(
+                ContractType::Gitlab,
+                "merge_request_changes.json",
+            ))
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
+        let gitlab: Box<dyn MergeRequestDiff> =
             Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let merge_request_id = 33;
-        gitlab.merge(merge_request_id).unwrap();
+        let diff = gitlab.diff(33).unwrap();
         assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/merge",
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/changes",
             *client.url()
         );
         assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
+            "diff --git a/src/main.rs b/src/main.rs\n\
+             --- a/src/main.rs\n\
+             +++ b/src/main.rs\n\
+             @@ -1,3 +1,3 @@\n \
+             fn main() {\n\
+             -    println!(\"Hello\");\n\
+             +    println!(\"Hello, world!\");\n \
+             }\n\
+             diff --git a/CHANGELOG.md b/CHANGELOG.md\n\
+             new file mode 100644\n\
+             --- /dev/null\n\
+             +++ b/CHANGELOG.md\n\
+             @@ -0,0 +1 @@\n\
+             +# Changelog\n\
+             diff --git a/docs/old.md b/docs/new.md\n\
+             rename from docs/old.md\n\
+             rename to docs/new.md\n\
+             diff --git a/TODO b/TODO\n\
+             deleted file mode 100644\n\
+             --- a/TODO\n\
+             +++ /dev/null\n\
+             @@ -1 +0,0 @@\n\
+             -Write docs\n",
+            diff
+        );
+    }
+
+    fn gitlab_review_diff(bodies: Vec<&str>) -> (Arc<MockRunner>, Box<dyn MergeRequestReviewDiff>) {
+        let responses = bodies
+            .into_iter()
+            .rev()
+            .map(|body| {
+                Response::builder()
+                    .status(200)
+                    .body(body.to_string())
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let client = Arc::new(MockRunner::new(responses));
+        let gitlab = Gitlab::new(config(), "gitlab.com", "jordilin/gitlapi", client.clone());
+        (client, Box::new(gitlab))
+    }
+
+    #[test]
+    fn test_failing_pipelines_of_authored_merge_requests() {
+        let merge_requests = r#"[
+            {"iid": 12, "project_id": 7},
+            {"iid": 3, "project_id": 8}
+        ]"#;
+        let failed = r#"{
+            "title": "Fix build",
+            "references": {"full": "jordilin/gitlapi!12"},
+            "head_pipeline": {"status": "failed", "web_url": "https://gitlab.com/jordilin/gitlapi/-/pipelines/9"}
+        }"#;
+        let passed = r#"{
+            "title": "Add docs",
+            "references": {"full": "jordilin/docs!3"},
+            "head_pipeline": {"status": "success", "web_url": "https://gitlab.com/jordilin/docs/-/pipelines/8"}
+        }"#;
+        let (client, gitlab) = gitlab_failing_pipelines(vec![merge_requests, failed, passed]);
+        let user = Member::builder()
+            .id(1)
+            .name("Jordi".to_string())
+            .username("jordilin".to_string())
+            .build()
+            .unwrap();
+        let failing = gitlab.failing(&user).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/8/merge_requests/3",
+            *client.url()
+        );
+        assert_eq!(1, failing.len());
+        assert_eq!("jordilin/gitlapi", failing[0].project);
+        assert_eq!(12, failing[0].merge_request_id);
+        assert_eq!("Fix build", failing[0].title);
+        assert_eq!(
+            "https://gitlab.com/jordilin/gitlapi/-/pipelines/9",
+            failing[0].web_url
         );
     }
 
+    fn gitlab_failing_pipelines(bodies: Vec<&str>) -> (Arc<MockRunner>, Box<dyn FailingPipelines>) {
+        let responses = bodies
+            .into_iter()
+            .rev()
+            .map(|body| {
+                Response::builder()
+                    .status(200)
+                    .body(body.to_string())
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let client = Arc::new(MockRunner::new(responses));
+        let gitlab = Gitlab::new(config(), "gitlab.com", "", client.clone());
+        (client, Box::new(gitlab))
+    }
+
     #[test]
-    fn test_close_merge_request() {
+    fn test_reviewed_head_from_version_at_last_approval() {
+        let notes = r#"[
+            {"author": {"username": "alice"}, "system": true, "body": "added 1 commit", "created_at": "2024-03-03T10:00:00.000Z"},
+            {"author": {"username": "bob"}, "system": false, "body": "LGTM", "created_at": "2024-03-02T12:00:00.000Z"},
+            {"author": {"username": "alice"}, "system": true, "body": "approved this merge request", "created_at": "2024-03-02T09:00:00.000Z"},
+            {"author": {"username": "alice"}, "system": false, "body": "Typo", "created_at": "2024-03-01T09:00:00.000Z"}
+        ]"#;
+        let versions = r#"[
+            {"head_commit_sha": "ccc", "created_at": "2024-03-03T10:00:00.000Z"},
+            {"head_commit_sha": "bbb", "created_at": "2024-03-01T15:00:00.000Z"},
+            {"head_commit_sha": "aaa", "created_at": "2024-03-01T08:00:00.000Z"}
+        ]"#;
+        let (client, gitlab) = gitlab_review_diff(vec![notes, versions]);
+        let head = gitlab.reviewed_head(33, "alice").unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/versions",
+            *client.url()
+        );
+        assert_eq!("ccc", head.head_sha);
+        assert_eq!(Some("bbb".to_string()), head.reviewed_sha);
+    }
+
+    #[test]
+    fn test_reviewed_head_without_review_of_user() {
+        let notes = r#"[
+            {"author": {"username": "bob"}, "system": true, "body": "approved this merge request", "created_at": "2024-03-02T09:00:00.000Z"}
+        ]"#;
+        let versions = r#"[{"head_commit_sha": "aaa", "created_at": "2024-03-01T08:00:00.000Z"}]"#;
+        let (_, gitlab) = gitlab_review_diff(vec![notes, versions]);
+        let head = gitlab.reviewed_head(33, "alice").unwrap();
+        assert_eq!("aaa", head.head_sha);
+        assert_eq!(None, head.reviewed_sha);
+    }
+
+    #[test]
+    fn test_compare_commits_as_unified_diff() {
+        let comparison = r#"{"diffs": [{
+            "old_path": "README.md", "new_path": "README.md",
+            "diff": "@@ -1 +1 @@\n-# gitlapi\n+# Gitlapi\n"
+        }]}"#;
+        let (client, gitlab) = gitlab_review_diff(vec![comparison]);
+        let diff = gitlab.compare("bbb", "ccc").unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/repository/compare?from=bbb&to=ccc",
+            *client.url()
+        );
+        assert_eq!(
+            "diff --git a/README.md b/README.md\n\
+             --- a/README.md\n\
+             +++ b/README.md\n\
+             @@ -1 +1 @@\n\
+             -# gitlapi\n\
+             +# Gitlapi\n",
+            diff
+        );
+    }
+
+    fn gitlab_rebase(bodies: Vec<&str>) -> (Arc<MockRunner>, Box<dyn MergeRequestRebase>) {
+        let responses = bodies
+            .into_iter()
+            .rev()
+            .map(|body| {
+                Response::builder()
+                    .status(200)
+                    .body(body.to_string())
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let client = Arc::new(MockRunner::new(responses));
+        let gitlab: Box<dyn MergeRequestRebase> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        (client, gitlab)
+    }
+
+    #[test]
+    fn test_rebase_merge_request_returns_previous_head() {
+        let (client, gitlab) = gitlab_rebase(vec![
+            r#"{"iid": 33, "sha": "1111111"}"#,
+            r#"{"rebase_in_progress": true}"#,
+        ]);
+        assert_eq!("1111111", gitlab.rebase(33).unwrap());
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/rebase",
+            *client.url()
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_rebase_progress() {
+        let (client, gitlab) = gitlab_rebase(vec![r#"{"rebase_in_progress": true}"#]);
+        assert_eq!(
+            RebaseProgress::InProgress,
+            gitlab.rebase_progress(33, "1111111").unwrap()
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33?include_rebase_in_progress=true",
+            *client.url()
+        );
+
+        let (_, gitlab) = gitlab_rebase(vec![
+            r#"{"rebase_in_progress": false, "merge_error": "Rebase failed: conflicts"}"#,
+        ]);
+        assert_eq!(
+            RebaseProgress::Failed("Rebase failed: conflicts".to_string()),
+            gitlab.rebase_progress(33, "1111111").unwrap()
+        );
+
+        let (_, gitlab) = gitlab_rebase(vec![
+            r#"{"rebase_in_progress": false, "merge_error": null, "sha": "2222222",
+                "head_pipeline": {"sha": "2222222", "web_url": "https://gitlab.com/jordilin/gitlapi/-/pipelines/9"}}"#,
+        ]);
+        assert_eq!(
+            RebaseProgress::Done {
+                sha: "2222222".to_string(),
+                pipeline_url: Some("https://gitlab.com/jordilin/gitlapi/-/pipelines/9".to_string()),
+            },
+            gitlab.rebase_progress(33, "1111111").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rebase_progress_ignores_pipeline_of_previous_head() {
+        let (_, gitlab) = gitlab_rebase(vec![
+            r#"{"rebase_in_progress": false, "sha": "2222222",
+                "head_pipeline": {"sha": "1111111", "web_url": "https://gitlab.com/jordilin/gitlapi/-/pipelines/8"}}"#,
+        ]);
+        assert_eq!(
+            RebaseProgress::Done {
+                sha: "2222222".to_string(),
+                pipeline_url: None,
+            },
+            gitlab.rebase_progress(33, "1111111").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unmet_requirements_mergeable_is_empty() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Gitlab, "merge_request.json"))
+            .body(r#"{"iid": 33, "detailed_merge_status": "mergeable"}"#.to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
+        let gitlab: Box<dyn MergeRequestChecks> =
+            Box::new(Gitlab::new(config, &domain, &path, client));
+        assert!(gitlab.unmet_requirements(33).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_merge_request_labels() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(r#"{"iid": 33, "labels": ["bug", "backend"]}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn MergeRequestLabels> =
             Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let merge_request_id = 33;
-        gitlab.close(merge_request_id).unwrap();
+        let labels = gitlab
+            .add(33, &["bug".to_string(), "backend".to_string()])
+            .unwrap();
+        assert_eq!(vec!["bug", "backend"], labels);
         assert_eq!(
             "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33",
             *client.url()
         );
         assert_eq!(http::Method::PUT, *client.http_method.borrow());
-        assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
-        );
+        assert!(client
+            .request_body()
+            .contains(r#""add_labels":"bug,backend""#));
     }
 
     #[test]
-    fn test_approve_merge_request_ok() {
+    fn test_remove_merge_request_labels() {
         let config = config();
         let domain = "gitlab.com".to_string();
         let path = "jordilin/gitlapi".to_string();
         let response = Response::builder()
             .status(200)
-            .body(get_contract(
-                ContractType::Gitlab,
-                "approve_merge_request.json",
-            ))
+            .body(r#"{"iid": 33, "labels": []}"#.to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn MergeRequest> =
+        let gitlab: Box<dyn MergeRequestLabels> =
             Box::new(Gitlab::new(config, &domain, &path, client.clone()));
-        let merge_request_id = 33;
-        let result = gitlab.approve(merge_request_id);
-        match result {
-            Ok(response) => {
-                assert_eq!(
-                    "https://gitlab.com/jordilin/gitlapi/-/merge_requests/33",
-                    response.web_url
-                );
-            }
-            Err(e) => {
-                panic!(
-                    "Expected Ok merge request approval but got: {:?} instead",
-                    e
-                );
-            }
-        }
-        assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/33/approve",
-            *client.url()
-        );
-        assert_eq!(http::Method::POST, *client.http_method.borrow());
-        assert_eq!(
-            Some(ApiOperation::MergeRequest),
-            *client.api_operation.borrow()
-        );
+        assert!(gitlab.remove(33, &["bug".to_string()]).unwrap().is_empty());
+        assert!(client.request_body().contains(r#""remove_labels":"bug""#));
     }
 }
@@ -1,11 +1,28 @@
-use crate::api_traits::{ApiOperation, RemoteProject};
+use crate::api_defaults;
+use crate::api_traits::{
+    ApiOperation, CommitApi, ProjectAccessRequests, ProjectApprovals, ProjectAudit,
+    ProjectBranchProtection, ProjectFile, ProjectMembers, ProjectMilestones, ProjectPushRules,
+    ProjectRulesets, ProjectTemplate, ProjectVariables, RemoteProject, Timestamp,
+};
+use crate::cancel;
 use crate::cli::browse::BrowseOptions;
-use crate::cmds::project::ProjectListBodyArgs;
-use crate::http::{self};
-use crate::io::{CmdInfo, HttpRunner, Response};
+use crate::cmds::project::{
+    AccessRequest, AccessRequestApproveBodyArgs, AccessRequestListBodyArgs, ApprovalSettings,
+    ApprovalSettingsBodyArgs, AuditedProject, BranchAccess, BranchProtectionBodyArgs, Commit,
+    CommitListBodyArgs, CommitPickBodyArgs, MemberListBodyArgs, NewProject, ProjectListBodyArgs,
+    ProjectTemplateBodyArgs, ProjectVariable, ProjectVariableBodyArgs, ProtectedBranch, PushRules,
+    PushRulesBodyArgs, Ruleset,
+};
+use crate::error::GRError;
+use crate::http::{self, Body};
+use crate::io::{CmdInfo, HttpRunner, PageCount, Response};
 use crate::remote::query::{self, gitlab_list_members};
-use crate::remote::{Member, Project, URLQueryParamBuilder};
-use crate::Result;
+use crate::remote::{
+    encode_query_param, ListBodyArgs, Member, Milestone, Project, URLQueryParamBuilder,
+};
+use crate::{json_loads, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::time::Duration;
 
 use super::Gitlab;
 
@@ -46,11 +63,26 @@ impl<R: HttpRunner<Response = Response>> RemoteProject for Gitlab<R> {
             BrowseOptions::MergeRequests => format!("{}/merge_requests", base_url),
             BrowseOptions::MergeRequestId(id) => format!("{}/-/merge_requests/{}", base_url, id),
             BrowseOptions::Pipelines => format!("{}/pipelines", base_url),
+            BrowseOptions::PipelineId(id) => format!("{}/-/pipelines/{}", base_url, id),
+            BrowseOptions::File { path, ref_ } => format!(
+                "{}/-/blob/{}/{}",
+                base_url,
+                ref_.as_deref().unwrap_or("HEAD"),
+                path.trim_start_matches('/')
+            ),
+            BrowseOptions::Releases => format!("{}/-/releases", base_url),
+            BrowseOptions::Settings => format!("{}/edit", base_url),
         }
     }
 
     fn list(&self, args: ProjectListBodyArgs) -> Result<Vec<Project>> {
-        let url = self.list_project_url(&args, false);
+        let mut url = self.list_project_url(&args, false);
+        // Users can see a very large number of projects. Their projects can be
+        // walked with keyset pagination, starred projects cannot.
+        // https://docs.gitlab.com/ee/api/projects.html#list-a-users-projects
+        if !args.stars {
+            url = query::gitlab_keyset_url(&url, &args.from_to_page);
+        }
         let projects = query::gitlab_list_projects(
             &self.runner,
             &url,
@@ -62,314 +94,2472 @@ impl<R: HttpRunner<Response = Response>> RemoteProject for Gitlab<R> {
         Ok(projects)
     }
 
-    fn num_pages(&self, args: ProjectListBodyArgs) -> Result<Option<u32>> {
+    fn num_pages(&self, args: ProjectListBodyArgs) -> Result<Option<PageCount>> {
         let url = self.list_project_url(&args, true);
         query::num_pages(&self.runner, &url, self.headers(), ApiOperation::Project)
     }
 }
 
-impl<R> Gitlab<R> {
-    fn list_project_url(&self, args: &ProjectListBodyArgs, num_pages: bool) -> String {
-        let user = args.user.as_ref().unwrap().clone();
-        let url = if args.stars {
-            format!("{}/{}/starred_projects", self.base_users_url, user.id)
-        } else {
-            format!("{}/{}/projects", self.base_users_url, user.id)
-        };
-        if num_pages {
-            return URLQueryParamBuilder::new(&url)
-                .add_param("page", "1")
-                .build();
+impl<R: HttpRunner<Response = Response>> ProjectTemplate for Gitlab<R> {
+    fn create_from_template(&self, args: ProjectTemplateBodyArgs) -> Result<NewProject> {
+        // The template is forked and the fork relationship removed, so the
+        // new project stands on its own. The history of the template comes
+        // along.
+        // https://docs.gitlab.com/ee/api/projects.html#fork-project
+        let url = format!("{}/fork", self.rest_api_basepath());
+        let mut body = Body::new();
+        body.add("name", serde_json::Value::from(args.name.clone()));
+        body.add("path", serde_json::Value::from(args.name.clone()));
+        if let Some(owner) = args.owner {
+            body.add("namespace_path", serde_json::Value::from(owner));
+        }
+        if let Some(description) = args.description {
+            body.add("description", serde_json::Value::from(description));
+        }
+        if args.private {
+            body.add("visibility", serde_json::Value::from("private"));
+        }
+        let response = query::gitlab_new_project(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Project,
+        )?;
+        // A taken name or path comes back as 409 or 400, which carry no
+        // project.
+        if response.status != 201 && response.status != 202 {
+            return Err(GRError::RemoteServerError(format!(
+                "Could not create project {}, the name or path may be taken. \
+                 Status code: {} and body: {}",
+                args.name, response.status, response.body
+            ))
+            .into());
         }
-        url
+        let project: NewProject = GitlabNewProjectFields::from(&json_loads(&response.body)?).into();
+        self.wait_for_import(&project)?;
+        // https://docs.gitlab.com/ee/api/projects.html#delete-an-existing-forked-from-relationship
+        query::gitlab_update_project::<_, ()>(
+            &self.runner,
+            &format!("{}/{}/fork", self.base_project_url, project.id),
+            None,
+            self.headers(),
+            http::Method::DELETE,
+            ApiOperation::Project,
+        )?;
+        Ok(project)
+    }
+
+    fn set_topics(&self, project: &NewProject, topics: &[String]) -> Result<()> {
+        // https://docs.gitlab.com/ee/api/projects.html#edit-project
+        let mut body = Body::new();
+        body.add("topics", serde_json::Value::from(topics.to_vec()));
+        query::gitlab_update_project(
+            &self.runner,
+            &format!("{}/{}", self.base_project_url, project.id),
+            Some(body),
+            self.headers(),
+            http::Method::PUT,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+
+    fn create_label(&self, project: &NewProject, name: &str) -> Result<()> {
+        // https://docs.gitlab.com/ee/api/labels.html#create-a-new-label
+        let mut body = Body::new();
+        body.add("name", serde_json::Value::from(name));
+        // Color is required by Gitlab.
+        body.add("color", serde_json::Value::from("#6699cc"));
+        query::gitlab_update_project(
+            &self.runner,
+            &format!("{}/{}/labels", self.base_project_url, project.id),
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Project,
+        )?;
+        Ok(())
     }
 }
 
-pub struct GitlabProjectFields {
-    id: i64,
-    default_branch: String,
-    web_url: String,
-    created_at: String,
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    /// Forks are created in the background. Topics and labels cannot be set
+    /// until the repository is there.
+    /// https://docs.gitlab.com/ee/api/project_import_export.html#import-status
+    fn wait_for_import(&self, project: &NewProject) -> Result<()> {
+        let url = format!("{}/{}", self.base_project_url, project.id);
+        let what = format!("waiting for the fork {}", project.path);
+        for _ in 0..api_defaults::GITLAB_IMPORT_MAX_POLLS {
+            let data = query::gitlab_project_json::<_, ()>(
+                &self.runner,
+                &url,
+                None,
+                self.headers(),
+                http::Method::GET,
+                ApiOperation::Project,
+            )?;
+            match data["import_status"].as_str() {
+                Some("scheduled") | Some("started") => cancel::sleep(
+                    Duration::from_secs(api_defaults::GITLAB_IMPORT_POLL_INTERVAL_SECONDS),
+                    &what,
+                )?,
+                Some("failed") => {
+                    return Err(GRError::ApplicationError(format!(
+                        "Gitlab could not fork the template into {}: {}",
+                        project.path,
+                        data["import_error"].as_str().unwrap_or_default()
+                    ))
+                    .into())
+                }
+                _ => return Ok(()),
+            }
+        }
+        Err(GRError::ApplicationError(format!(
+            "Stopped {} after {} seconds",
+            what,
+            api_defaults::GITLAB_IMPORT_MAX_POLLS as u64
+                * api_defaults::GITLAB_IMPORT_POLL_INTERVAL_SECONDS
+        ))
+        .into())
+    }
 }
 
-impl From<&serde_json::Value> for GitlabProjectFields {
-    fn from(data: &serde_json::Value) -> Self {
-        GitlabProjectFields {
-            id: data["id"].as_i64().unwrap(),
-            default_branch: data["default_branch"].as_str().unwrap().to_string(),
-            web_url: data["web_url"].as_str().unwrap().to_string(),
-            created_at: data["created_at"].as_str().unwrap().to_string(),
+impl<R: HttpRunner<Response = Response>> ProjectAudit for Gitlab<R> {
+    fn list(&self, group: &str) -> Result<Vec<AuditedProject>> {
+        // https://docs.gitlab.com/ee/api/groups.html#list-a-groups-projects
+        let url = format!(
+            "{}/groups/{}/projects?include_subgroups=true",
+            self.base_api_url,
+            encode_query_param(group)
+        );
+        query::gitlab_list_group_projects(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            None,
+            ApiOperation::Project,
+        )
+    }
+
+    fn audit(&self, mut project: AuditedProject) -> Result<AuditedProject> {
+        // The license is only given when asking for a single project.
+        let url = format!("{}/{}?license=true", self.base_project_url, project.id);
+        let data = query::gitlab_project_json::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )?;
+        project.license = data["license"]["key"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        if project.default_branch.is_empty() {
+            return Ok(project);
         }
+        // Takes wildcard protection rules into account.
+        // https://docs.gitlab.com/ee/api/branches.html#get-single-repository-branch
+        let url = format!(
+            "{}/{}/repository/branches/{}",
+            self.base_project_url,
+            project.id,
+            encode_query_param(&project.default_branch)
+        );
+        let branch = query::gitlab_branch::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )?;
+        project.default_branch_protected = branch["protected"].as_bool().unwrap_or_default();
+        Ok(project)
     }
 }
 
-impl From<GitlabProjectFields> for Project {
-    fn from(fields: GitlabProjectFields) -> Self {
-        Project::new(fields.id, &fields.default_branch)
-            .with_html_url(&fields.web_url)
-            .with_created_at(&fields.created_at)
+impl<R: HttpRunner<Response = Response>> ProjectApprovals for Gitlab<R> {
+    fn get(&self) -> Result<ApprovalSettings> {
+        let url = format!("{}/approvals", self.rest_api_basepath());
+        query::gitlab_project_approvals::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )
+    }
+
+    fn set(&self, args: ApprovalSettingsBodyArgs) -> Result<ApprovalSettings> {
+        let url = format!("{}/approvals", self.rest_api_basepath());
+        let mut body = Body::new();
+        if let Some(required) = args.required {
+            body.add("approvals_before_merge", serde_json::Value::from(required));
+        }
+        if let Some(reset_on_push) = args.reset_on_push {
+            body.add(
+                "reset_approvals_on_push",
+                serde_json::Value::from(reset_on_push),
+            );
+        }
+        query::gitlab_project_approvals(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Project,
+        )
     }
 }
 
-pub struct GitlabMemberFields {
-    id: i64,
-    name: String,
-    username: String,
-    created_at: String,
+impl<R: HttpRunner<Response = Response>> ProjectMembers for Gitlab<R> {
+    fn list(&self, args: MemberListBodyArgs) -> Result<Vec<Member>> {
+        // Includes members inherited from parent groups.
+        let url = format!("{}/members/all", self.rest_api_basepath());
+        gitlab_list_members(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Project,
+        )
+    }
 }
 
-impl From<&serde_json::Value> for GitlabMemberFields {
-    fn from(data: &serde_json::Value) -> Self {
-        GitlabMemberFields {
-            id: data["id"].as_i64().unwrap(),
-            name: data["name"].as_str().unwrap().to_string(),
-            username: data["username"].as_str().unwrap().to_string(),
-            created_at: data["created_at"].as_str().unwrap().to_string(),
+impl<R: HttpRunner<Response = Response>> ProjectMilestones for Gitlab<R> {
+    fn get(&self, title: &str) -> Result<Milestone> {
+        // Group milestones are available to the projects in the group too.
+        // https://docs.gitlab.com/ee/api/milestones.html#list-project-milestones
+        let url = format!(
+            "{}/milestones?title={}&include_ancestors=true",
+            self.rest_api_basepath(),
+            encode_query_param(title)
+        );
+        let milestones = query::gitlab_milestones::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )?;
+        milestones
+            .as_array()
+            .and_then(|milestones| {
+                milestones
+                    .iter()
+                    .find(|milestone| milestone["title"].as_str() == Some(title))
+            })
+            .map(|milestone| GitlabMilestoneFields::from(milestone).into())
+            .ok_or_else(|| {
+                GRError::PreconditionNotMet(format!("Milestone {} not found", title)).into()
+            })
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> ProjectAccessRequests for Gitlab<R> {
+    fn list(&self, args: AccessRequestListBodyArgs) -> Result<Vec<AccessRequest>> {
+        let url = format!("{}/access_requests", self.rest_api_basepath());
+        query::gitlab_list_access_requests(
+            &self.runner,
+            &url,
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Project,
+        )
+    }
+
+    fn approve(&self, args: AccessRequestApproveBodyArgs) -> Result<()> {
+        let url = format!(
+            "{}/access_requests/{}/approve",
+            self.rest_api_basepath(),
+            args.user_id
+        );
+        let mut body = Body::new();
+        body.add(
+            "access_level",
+            serde_json::Value::from(args.access_level.value()),
+        );
+        query::gitlab_access_request_response(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::PUT,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+
+    fn deny(&self, user_id: i64) -> Result<()> {
+        let url = format!("{}/access_requests/{}", self.rest_api_basepath(), user_id);
+        query::gitlab_access_request_response::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::DELETE,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+}
+
+// Required approvals are not part of the protected branch, they come from the
+// approval rules that apply to it.
+// https://docs.gitlab.com/ee/api/protected_branches.html
+// https://docs.gitlab.com/ee/api/merge_request_approvals.html#project-approval-rules
+impl<R: HttpRunner<Response = Response>> ProjectBranchProtection for Gitlab<R> {
+    fn list(&self) -> Result<Vec<ProtectedBranch>> {
+        let branches = self.protected_branches()?;
+        let rules = self.approval_rules()?;
+        Ok(branches
+            .iter()
+            .map(|branch| {
+                let mut protected: ProtectedBranch =
+                    GitlabProtectedBranchFields::from(branch).into();
+                protected.required_approvals = required_approvals(&rules, branch);
+                protected
+            })
+            .collect())
+    }
+
+    fn get(&self, branch: &str) -> Result<ProtectedBranch> {
+        ProjectBranchProtection::list(self)?
+            .into_iter()
+            .find(|protected| protected.name == branch)
+            .ok_or_else(|| {
+                GRError::PreconditionNotMet(format!("Branch {} is not protected", branch)).into()
+            })
+    }
+
+    fn set(&self, args: BranchProtectionBodyArgs) -> Result<ProtectedBranch> {
+        let current = self
+            .protected_branches()?
+            .into_iter()
+            .find(|branch| branch["name"].as_str() == Some(args.branch.as_str()));
+        let branch = match current {
+            Some(current)
+                if args.push.is_none()
+                    && args.merge.is_none()
+                    && args.allow_force_push.is_none() =>
+            {
+                current
+            }
+            // Access levels cannot be replaced in place. The current ones
+            // are removed by id in the same request.
+            Some(current) => {
+                let mut body = Body::new();
+                if let Some(push) = args.push {
+                    body.add(
+                        "allowed_to_push",
+                        replace_access_levels(&current["push_access_levels"], push),
+                    );
+                }
+                if let Some(merge) = args.merge {
+                    body.add(
+                        "allowed_to_merge",
+                        replace_access_levels(&current["merge_access_levels"], merge),
+                    );
+                }
+                if let Some(allow_force_push) = args.allow_force_push {
+                    body.add(
+                        "allow_force_push",
+                        serde_json::Value::from(allow_force_push),
+                    );
+                }
+                let url = format!(
+                    "{}/protected_branches/{}",
+                    self.rest_api_basepath(),
+                    encode_query_param(&args.branch)
+                );
+                query::gitlab_protected_branches(
+                    &self.runner,
+                    &url,
+                    Some(body),
+                    self.headers(),
+                    http::Method::PATCH,
+                    ApiOperation::Project,
+                )?
+            }
+            // Same defaults as protecting a branch in the UI.
+            None => {
+                let mut body = Body::new();
+                body.add("name", serde_json::Value::from(args.branch.as_str()));
+                body.add(
+                    "push_access_level",
+                    serde_json::Value::from(args.push.unwrap_or(BranchAccess::Maintainers).value()),
+                );
+                body.add(
+                    "merge_access_level",
+                    serde_json::Value::from(
+                        args.merge.unwrap_or(BranchAccess::Maintainers).value(),
+                    ),
+                );
+                body.add(
+                    "allow_force_push",
+                    serde_json::Value::from(args.allow_force_push.unwrap_or_default()),
+                );
+                let url = format!("{}/protected_branches", self.rest_api_basepath());
+                query::gitlab_protected_branches(
+                    &self.runner,
+                    &url,
+                    Some(body),
+                    self.headers(),
+                    http::Method::POST,
+                    ApiOperation::Project,
+                )?
+            }
+        };
+        if let Some(required) = args.required_approvals {
+            self.set_required_approvals(&branch, required)?;
         }
+        ProjectBranchProtection::get(self, &args.branch)
     }
 }
 
-impl From<GitlabMemberFields> for Member {
-    fn from(fields: GitlabMemberFields) -> Self {
-        Member::builder()
-            .id(fields.id)
-            .name(fields.name.to_string())
-            .username(fields.username.to_string())
-            .created_at(fields.created_at.to_string())
-            .build()
-            .unwrap()
+/// Removes the current access levels and adds the new one.
+fn replace_access_levels(current: &serde_json::Value, access: BranchAccess) -> serde_json::Value {
+    let mut levels = current
+        .as_array()
+        .map(|levels| {
+            levels
+                .iter()
+                .map(|level| serde_json::json!({ "id": level["id"], "_destroy": true }))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    levels.push(serde_json::json!({ "access_level": access.value() }));
+    serde_json::Value::from(levels)
+}
+
+/// Highest number of approvals among the rules for all protected branches
+/// and the ones for this branch.
+fn required_approvals(rules: &[serde_json::Value], branch: &serde_json::Value) -> u32 {
+    rules
+        .iter()
+        .filter(|rule| {
+            rule["applies_to_all_protected_branches"]
+                .as_bool()
+                .unwrap_or_default()
+                || applies_to(rule, branch)
+        })
+        .filter_map(|rule| rule["approvals_required"].as_u64())
+        .max()
+        .unwrap_or_default() as u32
+}
+
+fn applies_to(rule: &serde_json::Value, branch: &serde_json::Value) -> bool {
+    rule["protected_branches"]
+        .as_array()
+        .map(|branches| {
+            branches
+                .iter()
+                .any(|protected| protected["id"] == branch["id"])
+        })
+        .unwrap_or_default()
+}
+
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    fn protected_branches(&self) -> Result<Vec<serde_json::Value>> {
+        let url = format!(
+            "{}/protected_branches?per_page=100",
+            self.rest_api_basepath()
+        );
+        let branches = query::gitlab_protected_branches::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )?;
+        Ok(branches.as_array().cloned().unwrap_or_default())
+    }
+
+    fn approval_rules(&self) -> Result<Vec<serde_json::Value>> {
+        let url = format!("{}/approval_rules", self.rest_api_basepath());
+        let rules = query::gitlab_approval_rules::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )?;
+        Ok(rules.as_array().cloned().unwrap_or_default())
+    }
+
+    /// Updates the rule scoped to the branch alone, or creates one.
+    fn set_required_approvals(&self, branch: &serde_json::Value, required: u32) -> Result<()> {
+        let rule = self.approval_rules()?.into_iter().find(|rule| {
+            !rule["applies_to_all_protected_branches"]
+                .as_bool()
+                .unwrap_or_default()
+                && applies_to(rule, branch)
+        });
+        let mut body = Body::new();
+        body.add("approvals_required", serde_json::Value::from(required));
+        let (url, method) = match rule {
+            Some(rule) => (
+                format!("{}/approval_rules/{}", self.rest_api_basepath(), rule["id"]),
+                http::Method::PUT,
+            ),
+            None => {
+                let name = branch["name"].as_str().unwrap_or_default();
+                body.add(
+                    "name",
+                    serde_json::Value::from(format!("{} approvals", name)),
+                );
+                body.add("protected_branch_ids", serde_json::json!([branch["id"]]));
+                (
+                    format!("{}/approval_rules", self.rest_api_basepath()),
+                    http::Method::POST,
+                )
+            }
+        };
+        query::gitlab_approval_rule_response(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            method,
+            ApiOperation::Project,
+        )?;
+        Ok(())
     }
 }
 
-#[cfg(test)]
-mod test {
-    use std::sync::Arc;
+// Variables that apply to all environments have the `*` scope.
+// https://docs.gitlab.com/ee/api/project_level_variables.html
+impl<R: HttpRunner<Response = Response>> ProjectVariables for Gitlab<R> {
+    fn list(&self, environment: Option<&str>) -> Result<Vec<ProjectVariable>> {
+        // https://docs.gitlab.com/ee/api/project_level_variables.html#list-project-variables
+        let url = format!("{}/variables", self.rest_api_basepath());
+        let mut variables = query::gitlab_list_project_variables(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            None,
+            ApiOperation::Project,
+        )?;
+        // Variables for all environments have an empty environment.
+        if let Some(environment) = environment {
+            let environment = if environment == "*" { "" } else { environment };
+            variables.retain(|variable| variable.environment == environment);
+        }
+        Ok(variables)
+    }
 
-    use crate::api_traits::ApiOperation;
-    use crate::cmds::project::ProjectListBodyArgs;
-    use crate::http::Headers;
-    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+    fn set(&self, args: ProjectVariableBodyArgs) -> Result<()> {
+        let scope = args.environment.as_deref().unwrap_or("*");
+        let exists = ProjectVariables::list(self, Some(scope))?
+            .iter()
+            .any(|variable| variable.key == args.key);
+        let mut body = Body::new();
+        body.add("value", serde_json::Value::from(args.value));
+        body.add("masked", serde_json::Value::from(args.masked));
+        body.add("protected", serde_json::Value::from(args.protected));
+        body.add("environment_scope", serde_json::Value::from(scope));
+        let (url, method) = if exists {
+            (
+                self.variable_url(&args.key, args.environment.as_deref()),
+                http::Method::PUT,
+            )
+        } else {
+            body.add("key", serde_json::Value::from(args.key));
+            (
+                format!("{}/variables", self.rest_api_basepath()),
+                http::Method::POST,
+            )
+        };
+        query::gitlab_project_variable_response(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            method,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str, environment: Option<&str>) -> Result<()> {
+        let url = self.variable_url(key, environment);
+        query::gitlab_project_variable_response::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::DELETE,
+            ApiOperation::Project,
+        )?;
+        Ok(())
+    }
+}
+
+// Push rules are a single set of checks run on every push to the project.
+// Gitlab answers with null when there are none.
+// https://docs.gitlab.com/ee/api/projects.html#get-project-push-rules
+impl<R: HttpRunner<Response = Response>> ProjectRulesets for Gitlab<R> {
+    fn list(&self) -> Result<Vec<Ruleset>> {
+        let push_rule = self.push_rule()?;
+        if push_rule.is_null() {
+            return Ok(Vec::new());
+        }
+        let mut ruleset: Ruleset = GitlabPushRuleFields::from(&push_rule).into();
+        ruleset.source = self.path.clone();
+        Ok(vec![ruleset])
+    }
+
+    fn get(&self, id: i64) -> Result<Ruleset> {
+        ProjectRulesets::list(self)?
+            .into_iter()
+            .find(|ruleset| ruleset.id == id)
+            .ok_or_else(|| {
+                GRError::PreconditionNotMet(format!(
+                    "Ruleset {} not found. Push rules are the only ruleset in Gitlab",
+                    id
+                ))
+                .into()
+            })
+    }
+}
+
+// Push rules are created with a POST the first time and edited with a PUT
+// afterwards.
+// https://docs.gitlab.com/ee/api/projects.html#push-rules
+impl<R: HttpRunner<Response = Response>> ProjectPushRules for Gitlab<R> {
+    fn get(&self) -> Result<PushRules> {
+        let push_rule = self.push_rule()?;
+        Ok(GitlabPushRulesFields::from(&push_rule).into())
+    }
+
+    fn set(&self, args: PushRulesBodyArgs) -> Result<PushRules> {
+        let method = if self.push_rule()?.is_null() {
+            http::Method::POST
+        } else {
+            http::Method::PUT
+        };
+        let mut body = Body::new();
+        if let Some(regex) = args.commit_message_regex {
+            body.add("commit_message_regex", serde_json::Value::from(regex));
+        }
+        if let Some(regex) = args.commit_message_negative_regex {
+            body.add(
+                "commit_message_negative_regex",
+                serde_json::Value::from(regex),
+            );
+        }
+        if let Some(max_file_size) = args.max_file_size {
+            body.add("max_file_size", serde_json::Value::from(max_file_size));
+        }
+        if let Some(deny_secrets) = args.deny_secrets {
+            body.add("prevent_secrets", serde_json::Value::from(deny_secrets));
+        }
+        query::gitlab_push_rules(
+            &self.runner,
+            &format!("{}/push_rule", self.rest_api_basepath()),
+            Some(body),
+            self.headers(),
+            method,
+            ApiOperation::Project,
+        )
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    /// Null when the project has no push rules.
+    fn push_rule(&self) -> Result<serde_json::Value> {
+        query::gitlab_push_rule::<_, ()>(
+            &self.runner,
+            &format!("{}/push_rule", self.rest_api_basepath()),
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> CommitApi for Gitlab<R> {
+    fn list(&self, args: CommitListBodyArgs) -> Result<Vec<Commit>> {
+        // https://docs.gitlab.com/ee/api/commits.html#list-repository-commits
+        let mut url =
+            URLQueryParamBuilder::new(&format!("{}/repository/commits", self.rest_api_basepath()));
+        if let Some(ref_) = &args.ref_ {
+            url.add_param("ref_name", &encode_query_param(ref_));
+        }
+        if let Some(since) = &args.since {
+            url.add_param("since", &encode_query_param(since));
+        }
+        let mut commits = query::gitlab_list_commits(
+            &self.runner,
+            &url.build(),
+            args.list_args,
+            self.headers(),
+            None,
+            ApiOperation::Project,
+        )?;
+        // Listed commits carry no pipeline. Look it up for each of them.
+        for commit in &mut commits {
+            commit.status = self.pipeline_status(&commit.sha)?;
+        }
+        Ok(commits)
+    }
+
+    fn get(&self, sha: &str) -> Result<Commit> {
+        // https://docs.gitlab.com/ee/api/commits.html#get-a-single-commit
+        let url = format!(
+            "{}/repository/commits/{}",
+            self.rest_api_basepath(),
+            encode_query_param(sha)
+        );
+        query::gitlab_commit::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )
+    }
+
+    fn cherry_pick(&self, args: CommitPickBodyArgs) -> Result<Commit> {
+        // https://docs.gitlab.com/ee/api/commits.html#cherry-pick-a-commit
+        self.commit_action("cherry_pick", args)
+    }
+
+    fn revert(&self, args: CommitPickBodyArgs) -> Result<Commit> {
+        // https://docs.gitlab.com/ee/api/commits.html#revert-a-commit
+        self.commit_action("revert", args)
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    /// Cherry-picks or reverts as per `action`. Conflicts and changes already
+    /// in the branch are a 400 with the reason in its message.
+    fn commit_action(&self, action: &str, args: CommitPickBodyArgs) -> Result<Commit> {
+        let url = format!(
+            "{}/repository/commits/{}/{}",
+            self.rest_api_basepath(),
+            encode_query_param(&args.sha),
+            action
+        );
+        let mut body = Body::new();
+        body.add("branch", args.branch);
+        query::gitlab_commit(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Project,
+        )
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Gitlab<R> {
+    /// Status of the latest pipeline of the commit. Empty if it has none.
+    fn pipeline_status(&self, sha: &str) -> Result<String> {
+        // https://docs.gitlab.com/ee/api/pipelines.html#list-project-pipelines
+        let mut url = URLQueryParamBuilder::new(&format!("{}/pipelines", self.rest_api_basepath()));
+        url.add_param("sha", &encode_query_param(sha));
+        let list_args = ListBodyArgs::builder()
+            .page(1)
+            .max_pages(1)
+            .per_page(Some(100))
+            .build()
+            .unwrap();
+        let pipelines = query::gitlab_list_pipelines(
+            &self.runner,
+            &url.build(),
+            Some(list_args),
+            self.headers(),
+            None,
+            ApiOperation::Pipeline,
+        )?;
+        Ok(pipelines
+            .iter()
+            .max_by(|a, b| a.created_at().cmp(&b.created_at()))
+            .map(|pipeline| pipeline.status.clone())
+            .unwrap_or_default())
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> ProjectFile for Gitlab<R> {
+    fn raw(&self, path: &str, ref_: Option<&str>) -> Result<Vec<u8>> {
+        // The raw endpoint hands out binary files mangled as text. The file
+        // endpoint sends them base64 encoded. HEAD is the default branch.
+        // https://docs.gitlab.com/ee/api/repository_files.html#get-file-from-repository
+        let base_url = format!(
+            "{}/repository/files/{}",
+            self.rest_api_basepath(),
+            encode_query_param(path)
+        );
+        let mut url = URLQueryParamBuilder::new(&base_url);
+        url.add_param("ref", &encode_query_param(ref_.unwrap_or("HEAD")));
+        let response = query::gitlab_project_file::<_, ()>(
+            &self.runner,
+            &url.build(),
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        )?;
+        let file = json_loads(&response.body)?;
+        let content = file["content"].as_str().unwrap_or_default();
+        Ok(STANDARD.decode(content)?)
+    }
+}
+
+impl<R> Gitlab<R> {
+    /// The filter tells apart variables with the same key in different
+    /// environments.
+    fn variable_url(&self, key: &str, environment: Option<&str>) -> String {
+        let url = format!("{}/variables/{}", self.rest_api_basepath(), key);
+        match environment {
+            Some(environment) => format!(
+                "{}?filter[environment_scope]={}",
+                url,
+                encode_query_param(environment)
+            ),
+            None => url,
+        }
+    }
+
+    fn list_project_url(&self, args: &ProjectListBodyArgs, num_pages: bool) -> String {
+        let user = args.user.as_ref().unwrap().clone();
+        let url = if args.stars {
+            format!("{}/{}/starred_projects", self.base_users_url, user.id)
+        } else {
+            format!("{}/{}/projects", self.base_users_url, user.id)
+        };
+        let mut url = URLQueryParamBuilder::new(&url);
+        let filters = &args.filters;
+        if filters.owned {
+            url.add_param("owned", "true");
+        }
+        if filters.member {
+            url.add_param("membership", "true");
+        }
+        if filters.archived {
+            url.add_param("archived", "true");
+        }
+        if let Some(search) = &filters.search {
+            url.add_param("search", &encode_query_param(search));
+        }
+        if num_pages {
+            url.add_param("page", "1");
+        }
+        url.build()
+    }
+}
+
+pub struct GitlabNewProjectFields {
+    id: i64,
+    path_with_namespace: String,
+    web_url: String,
+    ssh_url_to_repo: String,
+}
+
+impl From<&serde_json::Value> for GitlabNewProjectFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabNewProjectFields {
+            id: data["id"].as_i64().unwrap(),
+            path_with_namespace: data["path_with_namespace"].as_str().unwrap().to_string(),
+            web_url: data["web_url"].as_str().unwrap_or_default().to_string(),
+            ssh_url_to_repo: data["ssh_url_to_repo"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+impl From<GitlabNewProjectFields> for NewProject {
+    fn from(fields: GitlabNewProjectFields) -> Self {
+        NewProject::builder()
+            .id(fields.id)
+            .path(fields.path_with_namespace)
+            .web_url(fields.web_url)
+            .clone_url(fields.ssh_url_to_repo)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabAuditedProjectFields {
+    id: i64,
+    path_with_namespace: String,
+    visibility: String,
+    default_branch: String,
+    created_at: String,
+}
+
+impl From<&serde_json::Value> for GitlabAuditedProjectFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabAuditedProjectFields {
+            id: data["id"].as_i64().unwrap(),
+            path_with_namespace: data["path_with_namespace"].as_str().unwrap().to_string(),
+            visibility: data["visibility"].as_str().unwrap_or_default().to_string(),
+            // Null in projects with an empty repository.
+            default_branch: data["default_branch"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            created_at: data["created_at"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GitlabAuditedProjectFields> for AuditedProject {
+    fn from(fields: GitlabAuditedProjectFields) -> Self {
+        AuditedProject::builder()
+            .id(fields.id)
+            .path(fields.path_with_namespace)
+            .visibility(fields.visibility)
+            .default_branch(fields.default_branch)
+            .created_at(fields.created_at)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabProjectFields {
+    id: i64,
+    default_branch: String,
+    web_url: String,
+    created_at: String,
+}
+
+impl From<&serde_json::Value> for GitlabProjectFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabProjectFields {
+            id: data["id"].as_i64().unwrap(),
+            default_branch: data["default_branch"].as_str().unwrap().to_string(),
+            web_url: data["web_url"].as_str().unwrap().to_string(),
+            created_at: data["created_at"].as_str().unwrap().to_string(),
+        }
+    }
+}
+
+impl From<GitlabProjectFields> for Project {
+    fn from(fields: GitlabProjectFields) -> Self {
+        Project::new(fields.id, &fields.default_branch)
+            .with_html_url(&fields.web_url)
+            .with_created_at(&fields.created_at)
+    }
+}
+
+pub struct GitlabAccessRequestFields {
+    id: i64,
+    username: String,
+    name: String,
+    requested_at: String,
+}
+
+impl From<&serde_json::Value> for GitlabAccessRequestFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabAccessRequestFields {
+            id: data["id"].as_i64().unwrap(),
+            username: data["username"].as_str().unwrap_or_default().to_string(),
+            name: data["name"].as_str().unwrap_or_default().to_string(),
+            requested_at: data["requested_at"].as_str().unwrap().to_string(),
+        }
+    }
+}
+
+impl From<GitlabAccessRequestFields> for AccessRequest {
+    fn from(fields: GitlabAccessRequestFields) -> Self {
+        AccessRequest::builder()
+            .user_id(fields.id)
+            .username(fields.username)
+            .name(fields.name)
+            .requested_at(fields.requested_at)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabProtectedBranchFields {
+    name: String,
+    push: Vec<String>,
+    merge: Vec<String>,
+    allow_force_push: bool,
+}
+
+fn access_level_descriptions(levels: &serde_json::Value) -> Vec<String> {
+    levels
+        .as_array()
+        .map(|levels| {
+            levels
+                .iter()
+                .filter_map(|level| level["access_level_description"].as_str())
+                .map(|description| description.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl From<&serde_json::Value> for GitlabProtectedBranchFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabProtectedBranchFields {
+            name: data["name"].as_str().unwrap_or_default().to_string(),
+            push: access_level_descriptions(&data["push_access_levels"]),
+            merge: access_level_descriptions(&data["merge_access_levels"]),
+            allow_force_push: data["allow_force_push"].as_bool().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GitlabProtectedBranchFields> for ProtectedBranch {
+    fn from(fields: GitlabProtectedBranchFields) -> Self {
+        ProtectedBranch::builder()
+            .name(fields.name)
+            .push(fields.push)
+            .merge(fields.merge)
+            .allow_force_push(fields.allow_force_push)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabPushRuleFields {
+    id: i64,
+    rules: Vec<String>,
+}
+
+impl From<&serde_json::Value> for GitlabPushRuleFields {
+    fn from(data: &serde_json::Value) -> Self {
+        // Every other field is a check, enabled when set.
+        let rules = data
+            .as_object()
+            .map(|fields| {
+                fields
+                    .iter()
+                    .filter(|(name, _)| {
+                        !["id", "project_id", "created_at"].contains(&name.as_str())
+                    })
+                    .filter_map(|(name, value)| match value {
+                        serde_json::Value::Bool(true) => Some(name.to_string()),
+                        serde_json::Value::Number(number) if number.as_f64() != Some(0.0) => {
+                            Some(format!("{}={}", name, number))
+                        }
+                        serde_json::Value::String(value) if !value.is_empty() => {
+                            Some(format!("{}={}", name, value))
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        GitlabPushRuleFields {
+            id: data["id"].as_i64().unwrap_or_default(),
+            rules,
+        }
+    }
+}
+
+impl From<GitlabPushRuleFields> for Ruleset {
+    fn from(fields: GitlabPushRuleFields) -> Self {
+        Ruleset::builder()
+            .id(fields.id)
+            .name("Push rules".to_string())
+            .target("push".to_string())
+            .enforcement("active".to_string())
+            .refs(vec!["~ALL".to_string()])
+            .rules(fields.rules)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabPushRulesFields {
+    rules: PushRules,
+}
+
+impl From<&serde_json::Value> for GitlabPushRulesFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let regex = |name: &str| data[name].as_str().unwrap_or_default().to_string();
+        GitlabPushRulesFields {
+            rules: PushRules::builder()
+                .commit_message_regex(regex("commit_message_regex"))
+                .commit_message_negative_regex(regex("commit_message_negative_regex"))
+                .max_file_size(data["max_file_size"].as_u64().unwrap_or_default())
+                .deny_secrets(data["prevent_secrets"].as_bool().unwrap_or_default())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GitlabPushRulesFields> for PushRules {
+    fn from(fields: GitlabPushRulesFields) -> Self {
+        fields.rules
+    }
+}
+
+pub struct GitlabCommitFields {
+    commit: Commit,
+}
+
+impl From<&serde_json::Value> for GitlabCommitFields {
+    fn from(data: &serde_json::Value) -> Self {
+        let text = |name: &str| data[name].as_str().unwrap_or_default().to_string();
+        GitlabCommitFields {
+            commit: Commit::builder()
+                .sha(text("id"))
+                .title(text("title"))
+                .author(text("author_name"))
+                .date(text("committed_date"))
+                // Only set when getting a single commit.
+                .status(
+                    data["last_pipeline"]["status"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .web_url(text("web_url"))
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+impl From<GitlabCommitFields> for Commit {
+    fn from(fields: GitlabCommitFields) -> Self {
+        fields.commit
+    }
+}
+
+pub struct GitlabVariableFields {
+    key: String,
+    value: String,
+    masked: bool,
+    protected: bool,
+    environment_scope: String,
+}
+
+impl From<&serde_json::Value> for GitlabVariableFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabVariableFields {
+            key: data["key"].as_str().unwrap_or_default().to_string(),
+            // Null in hidden variables.
+            value: data["value"].as_str().unwrap_or_default().to_string(),
+            masked: data["masked"].as_bool().unwrap_or_default()
+                || data["hidden"].as_bool().unwrap_or_default(),
+            protected: data["protected"].as_bool().unwrap_or_default(),
+            environment_scope: data["environment_scope"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+impl From<GitlabVariableFields> for ProjectVariable {
+    fn from(fields: GitlabVariableFields) -> Self {
+        let environment = if fields.environment_scope == "*" {
+            String::new()
+        } else {
+            fields.environment_scope
+        };
+        ProjectVariable::builder()
+            .key(fields.key)
+            .value(fields.value)
+            .masked(fields.masked)
+            .protected(fields.protected)
+            .environment(environment)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabApprovalSettingsFields {
+    approvals_before_merge: u32,
+    reset_approvals_on_push: bool,
+}
+
+impl From<&serde_json::Value> for GitlabApprovalSettingsFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabApprovalSettingsFields {
+            approvals_before_merge: data["approvals_before_merge"].as_u64().unwrap_or_default()
+                as u32,
+            reset_approvals_on_push: data["reset_approvals_on_push"]
+                .as_bool()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GitlabApprovalSettingsFields> for ApprovalSettings {
+    fn from(fields: GitlabApprovalSettingsFields) -> Self {
+        ApprovalSettings::builder()
+            .required(fields.approvals_before_merge)
+            .reset_on_push(fields.reset_approvals_on_push)
+            .build()
+            .unwrap()
+    }
+}
+
+/// Role name of Gitlab's numeric access levels.
+fn gitlab_role(access_level: i64) -> &'static str {
+    match access_level {
+        5 => "minimal_access",
+        10 => "guest",
+        20 => "reporter",
+        30 => "developer",
+        40 => "maintainer",
+        50 => "owner",
+        _ => "no_access",
+    }
+}
+
+pub struct GitlabMemberFields {
+    id: i64,
+    name: String,
+    username: String,
+    created_at: String,
+    role: String,
+    state: String,
+}
+
+impl From<&serde_json::Value> for GitlabMemberFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabMemberFields {
+            id: data["id"].as_i64().unwrap(),
+            name: data["name"].as_str().unwrap().to_string(),
+            username: data["username"].as_str().unwrap().to_string(),
+            created_at: data["created_at"].as_str().unwrap().to_string(),
+            role: gitlab_role(data["access_level"].as_i64().unwrap_or_default()).to_string(),
+            state: data["state"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GitlabMemberFields> for Member {
+    fn from(fields: GitlabMemberFields) -> Self {
+        Member::builder()
+            .id(fields.id)
+            .name(fields.name.to_string())
+            .username(fields.username.to_string())
+            .created_at(fields.created_at.to_string())
+            .role(fields.role)
+            .state(fields.state)
+            .build()
+            .unwrap()
+    }
+}
+
+pub struct GitlabMilestoneFields {
+    id: i64,
+    title: String,
+    state: String,
+}
+
+impl From<&serde_json::Value> for GitlabMilestoneFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabMilestoneFields {
+            id: data["id"].as_i64().unwrap(),
+            title: data["title"].as_str().unwrap().to_string(),
+            state: data["state"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl From<GitlabMilestoneFields> for Milestone {
+    fn from(fields: GitlabMilestoneFields) -> Self {
+        Milestone::builder()
+            .id(fields.id)
+            .title(fields.title)
+            .state(fields.state)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::api_traits::ApiOperation;
+    use crate::cmds::project::{ProjectListBodyArgs, ProjectListFilters};
+    use crate::http::Headers;
+    use crate::test::utils::{config, get_contract, ContractType, MockRunner};
+
+    use crate::io::CmdInfo;
+
+    use super::*;
+
+    #[test]
+    fn test_get_project_data_no_id() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "project.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
+        gitlab.get_project_data(None).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi",
+            client.url().to_string(),
+        );
+        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_project_data_with_given_id() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "project.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
+        gitlab.get_project_data(Some(54345)).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/54345",
+            client.url().to_string(),
+        );
+        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_project_members() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "project_members.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
+
+        let CmdInfo::Members(members) = gitlab.get_project_members().unwrap() else {
+            panic!("Expected members");
+        };
+        assert_eq!(2, members.len());
+        assert_eq!("test_user_0", members[0].username);
+        assert_eq!("test_user_1", members[1].username);
+        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/members/all",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_list_user_projects() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let projects = format!("[{}]", get_contract(ContractType::Gitlab, "project.json"));
+        let response = Response::builder()
+            .status(200)
+            .body(projects)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
+
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap();
+        RemoteProject::list(&gitlab, body_args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/users/1/projects?pagination=keyset&order_by=id",
+            client.url().to_string(),
+        );
+        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_my_starred_projects() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let projects = get_contract(ContractType::Gitlab, "stars.json").to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(projects)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
+
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .stars(true)
+            .build()
+            .unwrap();
+        RemoteProject::list(&gitlab, body_args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/users/1/starred_projects",
+            client.url().to_string(),
+        );
+        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_get_num_pages_url_for_user_projects() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let link_headers = "<https://gitlab.com/api/v4/users/1/projects?page=2&per_page=20>; rel=\"next\", <https://gitlab.com/api/v4/users/1/projects?page=2&per_page=20>; rel=\"last\"";
+        let mut headers = Headers::new();
+        headers.set("link", link_headers);
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "project.json"))
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap();
+        gitlab.num_pages(body_args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/users/1/projects?page=1",
+            client.url().to_string(),
+        );
+        assert_eq!(
+            ApiOperation::Project,
+            *client.api_operation.borrow().as_ref().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_project_num_pages_url_for_starred() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let link_headers = "<https://gitlab.com/api/v4/users/1/starred_projects?page=2&per_page=20>; rel=\"next\", <https://gitlab.com/api/v4/users/1/starred_projects?page=2&per_page=20>; rel=\"last\"";
+        let mut headers = Headers::new();
+        headers.set("link", link_headers);
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "project.json"))
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .stars(true)
+            .build()
+            .unwrap();
+        gitlab.num_pages(body_args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/users/1/starred_projects?page=1",
+            client.url().to_string(),
+        );
+        assert_eq!(
+            ApiOperation::Project,
+            *client.api_operation.borrow().as_ref().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_project_approval_settings() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "project_approvals.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectApprovals> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let settings = gitlab.get().unwrap();
+        assert_eq!(2, settings.required);
+        assert!(settings.reset_on_push);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/approvals",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_set_project_approval_settings() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(201)
+            .body(get_contract(ContractType::Gitlab, "project_approvals.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectApprovals> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = ApprovalSettingsBodyArgs::builder()
+            .required(Some(2))
+            .reset_on_push(Some(true))
+            .build()
+            .unwrap();
+        gitlab.set(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/approvals",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_list_access_requests() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "list_access_requests.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectAccessRequests> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = AccessRequestListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let requests = gitlab.list(args).unwrap();
+        assert_eq!(2, requests.len());
+        assert_eq!(1, requests[0].user_id);
+        assert_eq!("raymond_smith", requests[0].username);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/access_requests",
+            *client.url(),
+        );
+        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+    }
+
+    #[test]
+    fn test_approve_access_request() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder().status(200).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectAccessRequests> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let args = AccessRequestApproveBodyArgs::builder()
+            .user_id(1)
+            .build()
+            .unwrap();
+        gitlab.approve(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/access_requests/1/approve",
+            *client.url(),
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_deny_access_request() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder().status(204).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectAccessRequests> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        gitlab.deny(1).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/access_requests/1",
+            *client.url(),
+        );
+        assert_eq!(http::Method::DELETE, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_list_members_with_role_and_state() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "project_members.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectMembers> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let args = MemberListBodyArgs::builder()
+            .list_args(None)
+            .build()
+            .unwrap();
+        let members = gitlab.list(args).unwrap();
+        assert_eq!("maintainer", members[0].role);
+        assert_eq!("active", members[0].state);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/members/all",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_get_milestone_by_title() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        // Title search matches substrings too.
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"id": 12, "title": "Release 1.0.1", "state": "active"},
+                    {"id": 11, "title": "Release 1.0", "state": "closed"}]"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectMilestones> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let milestone = gitlab.get("Release 1.0").unwrap();
+        assert_eq!(11, milestone.id);
+        assert_eq!("closed", milestone.state);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/milestones?title=Release%201.0&include_ancestors=true",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_create_project_from_template_forks_and_unlinks() {
+        let config = config();
+        let fork = Response::builder()
+            .status(201)
+            .body(
+                r#"{"id": 55, "path_with_namespace": "my-group/my-service",
+                    "web_url": "https://gitlab.com/my-group/my-service",
+                    "ssh_url_to_repo": "git@gitlab.com:my-group/my-service.git"}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let imported = Response::builder()
+            .status(200)
+            .body(r#"{"id": 55, "import_status": "finished"}"#.to_string())
+            .build()
+            .unwrap();
+        let unlink = Response::builder().status(204).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![unlink, imported, fork]));
+        let gitlab: Box<dyn ProjectTemplate> = Box::new(Gitlab::new(
+            config,
+            "gitlab.com",
+            "jordilin/template",
+            client.clone(),
+        ));
+        let args = ProjectTemplateBodyArgs::builder()
+            .name("my-service".to_string())
+            .owner(Some("my-group".to_string()))
+            .build()
+            .unwrap();
+        let project = gitlab.create_from_template(args).unwrap();
+        assert_eq!(55, project.id);
+        assert_eq!("my-group/my-service", project.path);
+        assert_eq!("git@gitlab.com:my-group/my-service.git", project.clone_url);
+        assert_eq!("https://gitlab.com/api/v4/projects/55/fork", *client.url());
+        assert_eq!(http::Method::DELETE, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_create_from_template_fails_if_the_fork_fails() {
+        let config = config();
+        let fork = Response::builder()
+            .status(201)
+            .body(
+                r#"{"id": 55, "path_with_namespace": "my-group/my-service",
+                    "web_url": "https://gitlab.com/my-group/my-service",
+                    "ssh_url_to_repo": "git@gitlab.com:my-group/my-service.git"}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let failed = Response::builder()
+            .status(200)
+            .body(
+                r#"{"id": 55, "import_status": "failed", "import_error": "Repository too large"}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![failed, fork]));
+        let gitlab: Box<dyn ProjectTemplate> = Box::new(Gitlab::new(
+            config,
+            "gitlab.com",
+            "jordilin/template",
+            client.clone(),
+        ));
+        let args = ProjectTemplateBodyArgs::builder()
+            .name("my-service".to_string())
+            .build()
+            .unwrap();
+        let err = gitlab.create_from_template(args).unwrap_err();
+        assert_eq!(
+            "Application error: Gitlab could not fork the template into my-group/my-service: Repository too large",
+            err.to_string()
+        );
+        assert_eq!("https://gitlab.com/api/v4/projects/55", *client.url());
+    }
+
+    #[test]
+    fn test_create_from_template_fails_if_the_path_is_taken() {
+        let config = config();
+        let fork = Response::builder()
+            .status(409)
+            .body(r#"{"message": {"path": ["has already been taken"]}}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![fork]));
+        let gitlab: Box<dyn ProjectTemplate> = Box::new(Gitlab::new(
+            config,
+            "gitlab.com",
+            "jordilin/template",
+            client.clone(),
+        ));
+        let args = ProjectTemplateBodyArgs::builder()
+            .name("my-service".to_string())
+            .build()
+            .unwrap();
+        let err = gitlab.create_from_template(args).unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("Could not create project my-service"));
+        assert!(err.contains("has already been taken"));
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Ftemplate/fork",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_create_label_in_new_project() {
+        let config = config();
+        let response = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectTemplate> = Box::new(Gitlab::new(
+            config,
+            "gitlab.com",
+            "jordilin/template",
+            client.clone(),
+        ));
+        let project = NewProject::builder()
+            .id(55)
+            .path("my-group/my-service".to_string())
+            .web_url("https://gitlab.com/my-group/my-service".to_string())
+            .clone_url("git@gitlab.com:my-group/my-service.git".to_string())
+            .build()
+            .unwrap();
+        gitlab.create_label(&project, "bug").unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/55/labels",
+            *client.url()
+        );
+        assert!(client.request_body().contains(r#""name":"bug""#));
+    }
+
+    #[test]
+    fn test_list_group_projects_includes_subgroups() {
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "stars.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectAudit> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let projects = gitlab.list("jordilin/tools").unwrap();
+        assert_eq!(1, projects.len());
+        assert_eq!("jordilin/gitlapi", projects[0].path);
+        assert_eq!("public", projects[0].visibility);
+        assert_eq!("main", projects[0].default_branch);
+        assert_eq!(
+            "https://gitlab.com/api/v4/groups/jordilin%2Ftools/projects?include_subgroups=true",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_audit_project_license_and_branch_protection() {
+        let project_response = Response::builder()
+            .status(200)
+            .body(r#"{"id":55,"license":{"key":"mit","name":"MIT License"}}"#.to_string())
+            .build()
+            .unwrap();
+        let branch_response = Response::builder()
+            .status(200)
+            .body(r#"{"name":"main","protected":true}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![branch_response, project_response]));
+        let gitlab: Box<dyn ProjectAudit> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let project = AuditedProject::builder()
+            .id(55)
+            .path("jordilin/tools".to_string())
+            .visibility("internal".to_string())
+            .default_branch("main".to_string())
+            .created_at("2024-01-01T00:00:00Z".to_string())
+            .build()
+            .unwrap();
+        let project = gitlab.audit(project).unwrap();
+        assert_eq!("mit", project.license);
+        assert!(project.default_branch_protected);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/55/repository/branches/main",
+            *client.url()
+        );
+    }
+
+    #[test]
+    fn test_audit_empty_project_has_no_branch_to_protect() {
+        let response = Response::builder()
+            .status(200)
+            .body(r#"{"id":55,"license":null}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectAudit> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let project = AuditedProject::builder()
+            .id(55)
+            .path("jordilin/empty".to_string())
+            .visibility("private".to_string())
+            .created_at("2024-01-01T00:00:00Z".to_string())
+            .build()
+            .unwrap();
+        let project = gitlab.audit(project).unwrap();
+        assert_eq!("", project.license);
+        assert!(!project.default_branch_protected);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/55?license=true",
+            *client.url()
+        );
+    }
 
-    use crate::io::CmdInfo;
+    #[test]
+    fn test_get_milestone_not_found() {
+        let config = config();
+        let response = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectMilestones> = Box::new(Gitlab::new(
+            config,
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client,
+        ));
+        let err = gitlab.get("Release 2.0").unwrap_err();
+        assert_eq!(
+            "Precondition not met error: Milestone Release 2.0 not found",
+            err.to_string()
+        );
+    }
 
-    use super::*;
+    #[test]
+    fn test_gitlab_role_names() {
+        assert_eq!("guest", gitlab_role(10));
+        assert_eq!("owner", gitlab_role(50));
+        assert_eq!("no_access", gitlab_role(0));
+    }
 
     #[test]
-    fn test_get_project_data_no_id() {
+    fn test_list_user_projects_with_filters() {
         let config = config();
         let domain = "gitlab.com";
         let path = "jordilin/gitlapi";
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Gitlab, "project.json"))
+            .body("[]".to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
-        gitlab.get_project_data(None).unwrap();
+        let gitlab = Gitlab::new(config, domain, path, client.clone());
+        let body_args = ProjectListBodyArgs::builder()
+            .from_to_page(None)
+            .user(Some(
+                Member::builder()
+                    .id(1)
+                    .name("jordi".to_string())
+                    .username("jordilin".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .filters(
+                ProjectListFilters::builder()
+                    .owned(true)
+                    .member(true)
+                    .archived(true)
+                    .search(Some("git tools".to_string()))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        RemoteProject::list(&gitlab, body_args).unwrap();
         assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi",
-            client.url().to_string(),
+            "https://gitlab.com/api/v4/users/1/projects?owned=true&membership=true&archived=true&search=git%20tools&pagination=keyset&order_by=id",
+            *client.url()
         );
-        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
     }
 
     #[test]
-    fn test_get_project_data_with_given_id() {
+    fn test_list_variables_in_environment_across_pages() {
         let config = config();
         let domain = "gitlab.com";
         let path = "jordilin/gitlapi";
-        let response = Response::builder()
+        let link_header = "<https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/variables?page=2>; rel=\"next\", <https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/variables?page=2>; rel=\"last\"";
+        let mut headers = Headers::new();
+        headers.set("link", link_header);
+        let first_page = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Gitlab, "project.json"))
+            .body(
+                r#"[{"key": "LOG_LEVEL", "value": "debug", "masked": false, "protected": false, "environment_scope": "*"}]"#
+                    .to_string(),
+            )
+            .headers(headers)
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
-        gitlab.get_project_data(Some(54345)).unwrap();
+        let second_page = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"key": "DEPLOY_TOKEN", "value": "s3cr3t", "masked": true, "protected": true, "environment_scope": "production"}]"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![second_page, first_page]));
+        let gitlab: Box<dyn ProjectVariables> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let variables = gitlab.list(Some("production")).unwrap();
+        assert_eq!(1, variables.len());
+        assert_eq!("DEPLOY_TOKEN", variables[0].key);
+        assert!(variables[0].masked);
+        assert_eq!("production", variables[0].environment);
         assert_eq!(
-            "https://gitlab.com/api/v4/projects/54345",
-            client.url().to_string(),
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/variables?page=2",
+            *client.url(),
         );
-        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
     }
 
     #[test]
-    fn test_get_project_members() {
+    fn test_set_new_variable_is_created() {
         let config = config();
         let domain = "gitlab.com";
         let path = "jordilin/gitlapi";
-        let response = Response::builder()
+        let variables = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Gitlab, "project_members.json"))
+            .body("[]".to_string())
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
-
-        let CmdInfo::Members(members) = gitlab.get_project_members().unwrap() else {
-            panic!("Expected members");
-        };
-        assert_eq!(2, members.len());
-        assert_eq!("test_user_0", members[0].username);
-        assert_eq!("test_user_1", members[1].username);
-        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
+        let created = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![created, variables]));
+        let gitlab: Box<dyn ProjectVariables> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let args = ProjectVariableBodyArgs::builder()
+            .key("DEPLOY_TOKEN".to_string())
+            .value("s3cr3t".to_string())
+            .masked(true)
+            .build()
+            .unwrap();
+        gitlab.set(args).unwrap();
         assert_eq!(
-            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/members/all",
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/variables",
             *client.url(),
         );
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert!(client.request_body().contains(r#""key":"DEPLOY_TOKEN""#));
+        assert!(client.request_body().contains(r#""environment_scope":"*""#));
     }
 
     #[test]
-    fn test_list_user_projects() {
+    fn test_set_existing_variable_in_environment_is_updated() {
         let config = config();
         let domain = "gitlab.com";
         let path = "jordilin/gitlapi";
-        let projects = format!("[{}]", get_contract(ContractType::Gitlab, "project.json"));
-        let response = Response::builder()
+        let variables = Response::builder()
             .status(200)
-            .body(projects)
+            .body(
+                r#"[{"key": "DEPLOY_TOKEN", "value": "old", "masked": true, "protected": false, "environment_scope": "production"}]"#
+                    .to_string(),
+            )
             .build()
             .unwrap();
-        let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
+        let updated = Response::builder().status(200).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![updated, variables]));
+        let gitlab: Box<dyn ProjectVariables> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let args = ProjectVariableBodyArgs::builder()
+            .key("DEPLOY_TOKEN".to_string())
+            .value("s3cr3t".to_string())
+            .environment(Some("production".to_string()))
+            .build()
+            .unwrap();
+        gitlab.set(args).unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/variables/DEPLOY_TOKEN?filter[environment_scope]=production",
+            *client.url(),
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+    }
 
-        let body_args = ProjectListBodyArgs::builder()
-            .from_to_page(None)
-            .user(Some(
-                Member::builder()
-                    .id(1)
-                    .name("jordi".to_string())
-                    .username("jordilin".to_string())
-                    .build()
-                    .unwrap(),
-            ))
+    #[test]
+    fn test_list_protected_branches_with_required_approvals() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let branches = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"id": 1, "name": "main", "allow_force_push": false,
+                     "push_access_levels": [{"id": 11, "access_level": 0, "access_level_description": "No one"}],
+                     "merge_access_levels": [{"id": 12, "access_level": 40, "access_level_description": "Maintainers"}]},
+                    {"id": 2, "name": "release/*", "allow_force_push": true,
+                     "push_access_levels": [{"id": 21, "access_level": 30, "access_level_description": "Developers + Maintainers"}],
+                     "merge_access_levels": []}]"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let rules = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"id": 5, "approvals_required": 1, "applies_to_all_protected_branches": true, "protected_branches": []},
+                    {"id": 6, "approvals_required": 2, "applies_to_all_protected_branches": false, "protected_branches": [{"id": 1, "name": "main"}]}]"#
+                    .to_string(),
+            )
             .build()
             .unwrap();
-        gitlab.list(body_args).unwrap();
+        let client = Arc::new(MockRunner::new(vec![rules, branches]));
+        let gitlab: Box<dyn ProjectBranchProtection> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let branches = gitlab.list().unwrap();
+        assert_eq!("main", branches[0].name);
+        assert_eq!(2, branches[0].required_approvals);
+        assert_eq!(vec!["No one"], branches[0].push);
+        assert_eq!(vec!["Maintainers"], branches[0].merge);
+        assert_eq!(1, branches[1].required_approvals);
+        assert!(branches[1].allow_force_push);
         assert_eq!(
-            "https://gitlab.com/api/v4/users/1/projects",
-            client.url().to_string(),
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/approval_rules",
+            *client.url(),
         );
-        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
     }
 
     #[test]
-    fn test_get_my_starred_projects() {
+    fn test_replace_access_levels_removes_current_ones() {
+        let current = serde_json::json!([{"id": 11, "access_level": 40}]);
+        assert_eq!(
+            serde_json::json!([{"id": 11, "_destroy": true}, {"access_level": 0}]),
+            replace_access_levels(&current, BranchAccess::NoOne)
+        );
+    }
+
+    #[test]
+    fn test_raw_file_at_ref() {
         let config = config();
         let domain = "gitlab.com";
         let path = "jordilin/gitlapi";
-        let projects = format!("{}", get_contract(ContractType::Gitlab, "stars.json"));
         let response = Response::builder()
             .status(200)
-            .body(projects)
+            .body(r#"{"file_path": "docs/README.md", "encoding": "base64", "content": "IyBEb2NzCg=="}"#.to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
+        let gitlab: Box<dyn ProjectFile> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let contents = gitlab.raw("docs/README.md", Some("release/1.0")).unwrap();
+        assert_eq!(b"# Docs\n".to_vec(), contents);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/repository/files/docs%2FREADME.md?ref=release%2F1.0",
+            *client.url(),
+        );
+    }
 
-        let body_args = ProjectListBodyArgs::builder()
-            .from_to_page(None)
-            .user(Some(
-                Member::builder()
-                    .id(1)
-                    .name("jordi".to_string())
-                    .username("jordilin".to_string())
-                    .build()
-                    .unwrap(),
-            ))
-            .stars(true)
+    #[test]
+    fn test_raw_binary_file_on_default_branch() {
+        let config = config();
+        let domain = "gitlab.com";
+        let path = "jordilin/gitlapi";
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"{"file_path": "logo.png", "encoding": "base64", "content": "iVBORw0KGgo="}"#
+                    .to_string(),
+            )
             .build()
             .unwrap();
-        gitlab.list(body_args).unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectFile> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let contents = gitlab.raw("logo.png", None).unwrap();
         assert_eq!(
-            "https://gitlab.com/api/v4/users/1/starred_projects",
-            client.url().to_string(),
+            vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'],
+            contents
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/repository/files/logo.png?ref=HEAD",
+            *client.url(),
         );
-        assert_eq!("1234", client.headers().get("PRIVATE-TOKEN").unwrap());
-        assert_eq!(Some(ApiOperation::Project), *client.api_operation.borrow());
     }
 
     #[test]
-    fn test_get_num_pages_url_for_user_projects() {
+    fn test_push_rules_as_ruleset() {
         let config = config();
         let domain = "gitlab.com";
         let path = "jordilin/gitlapi";
-        let link_headers = "<https://gitlab.com/api/v4/users/1/projects?page=2&per_page=20>; rel=\"next\", <https://gitlab.com/api/v4/users/1/projects?page=2&per_page=20>; rel=\"last\"";
-        let mut headers = Headers::new();
-        headers.set("link", link_headers);
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Gitlab, "project.json"))
-            .headers(headers)
+            .body(
+                r#"{"id": 3, "project_id": 55, "created_at": "2024-01-01T00:00:00Z",
+                    "commit_message_regex": "^JIRA-\\d+", "branch_name_regex": "",
+                    "deny_delete_tag": true, "prevent_secrets": false, "max_file_size": 10}"#
+                    .to_string(),
+            )
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
-        let body_args = ProjectListBodyArgs::builder()
-            .from_to_page(None)
-            .user(Some(
-                Member::builder()
-                    .id(1)
-                    .name("jordi".to_string())
-                    .username("jordilin".to_string())
-                    .build()
-                    .unwrap(),
-            ))
+        let gitlab: Box<dyn ProjectRulesets> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        let rulesets = gitlab.list().unwrap();
+        assert_eq!(1, rulesets.len());
+        assert_eq!(3, rulesets[0].id);
+        assert_eq!("jordilin/gitlapi", rulesets[0].source);
+        assert_eq!(
+            vec![
+                "commit_message_regex=^JIRA-\\d+",
+                "deny_delete_tag",
+                "max_file_size=10"
+            ],
+            rulesets[0].rules
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/push_rule",
+            *client.url(),
+        );
+    }
+
+    #[test]
+    fn test_list_commits_with_the_status_of_their_latest_pipeline() {
+        let commits = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"id": "ed899a2f", "title": "Fix the release", "author_name": "Jordi",
+                     "committed_date": "2024-03-02T10:00:00Z",
+                     "web_url": "https://gitlab.com/jordilin/gitlapi/-/commit/ed899a2f"},
+                    {"id": "6104942a", "title": "Add changelog", "author_name": "Jordi",
+                     "committed_date": "2024-03-01T10:00:00Z",
+                     "web_url": "https://gitlab.com/jordilin/gitlapi/-/commit/6104942a"}]"#
+                    .to_string(),
+            )
             .build()
             .unwrap();
-        gitlab.num_pages(body_args).unwrap();
+        let pipelines = Response::builder()
+            .status(200)
+            .body(
+                r#"[{"status": "success", "web_url": "", "ref": "main", "sha": "ed899a2f",
+                     "created_at": "2024-03-02T11:00:00Z", "updated_at": "2024-03-02T11:10:00Z"},
+                    {"status": "failed", "web_url": "", "ref": "main", "sha": "ed899a2f",
+                     "created_at": "2024-03-02T10:05:00Z", "updated_at": "2024-03-02T10:15:00Z"}]"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let no_pipelines = Response::builder()
+            .status(200)
+            .body("[]".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![pipelines, no_pipelines, commits]));
+        let gitlab: Box<dyn CommitApi> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let args = CommitListBodyArgs::builder()
+            .ref_(Some("main".to_string()))
+            .since(Some("2024-03-01T00:00:00Z".to_string()))
+            .build()
+            .unwrap();
+        let commits = gitlab.list(args).unwrap();
+        assert_eq!(2, commits.len());
+        assert_eq!("Add changelog", commits[0].title);
+        assert_eq!("", commits[0].status);
+        assert_eq!("Fix the release", commits[1].title);
+        assert_eq!("success", commits[1].status);
         assert_eq!(
-            "https://gitlab.com/api/v4/users/1/projects?page=1",
-            client.url().to_string(),
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/pipelines?sha=ed899a2f&page=1&per_page=100",
+            *client.url(),
         );
+    }
+
+    #[test]
+    fn test_get_commit_with_its_last_pipeline() {
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"{"id": "ed899a2f", "title": "Fix the release", "author_name": "Jordi",
+                    "committed_date": "2024-03-02T10:00:00Z", "web_url": "",
+                    "last_pipeline": {"id": 8, "status": "running"}}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CommitApi> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let commit = gitlab.get("ed899a2f").unwrap();
+        assert_eq!("running", commit.status);
         assert_eq!(
-            ApiOperation::Project,
-            *client.api_operation.borrow().as_ref().unwrap()
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/repository/commits/ed899a2f",
+            *client.url(),
         );
     }
 
     #[test]
-    fn test_get_project_num_pages_url_for_starred() {
+    fn test_cherry_pick_commit_onto_branch() {
+        let response = Response::builder()
+            .status(201)
+            .body(
+                r#"{"id": "f1f2f3f4", "title": "Fix the release", "author_name": "Jordi",
+                    "committed_date": "2024-03-03T10:00:00Z", "web_url": ""}"#
+                    .to_string(),
+            )
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn CommitApi> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let args = CommitPickBodyArgs::builder()
+            .sha("ed899a2f".to_string())
+            .branch("release-1.2".to_string())
+            .build()
+            .unwrap();
+        let commit = gitlab.cherry_pick(args).unwrap();
+        assert_eq!("f1f2f3f4", commit.sha);
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/repository/commits/ed899a2f/cherry_pick",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        assert!(client.request_body().contains("release-1.2"));
+    }
+
+    #[test]
+    fn test_no_push_rules_no_rulesets() {
         let config = config();
         let domain = "gitlab.com";
         let path = "jordilin/gitlapi";
-        let link_headers = "<https://gitlab.com/api/v4/users/1/starred_projects?page=2&per_page=20>; rel=\"next\", <https://gitlab.com/api/v4/users/1/starred_projects?page=2&per_page=20>; rel=\"last\"";
-        let mut headers = Headers::new();
-        headers.set("link", link_headers);
         let response = Response::builder()
             .status(200)
-            .body(get_contract(ContractType::Gitlab, "project.json"))
-            .headers(headers)
+            .body("null".to_string())
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab = Gitlab::new(config, &domain, &path, client.clone());
-        let body_args = ProjectListBodyArgs::builder()
-            .from_to_page(None)
-            .user(Some(
-                Member::builder()
-                    .id(1)
-                    .name("jordi".to_string())
-                    .username("jordilin".to_string())
-                    .build()
-                    .unwrap(),
-            ))
-            .stars(true)
+        let gitlab: Box<dyn ProjectRulesets> =
+            Box::new(Gitlab::new(config, domain, path, client.clone()));
+        assert!(gitlab.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_push_rules() {
+        let response = Response::builder()
+            .status(200)
+            .body(
+                r#"{"id": 3, "commit_message_regex": "^JIRA-\\d+",
+                    "commit_message_negative_regex": null, "prevent_secrets": true,
+                    "max_file_size": 10}"#
+                    .to_string(),
+            )
             .build()
             .unwrap();
-        gitlab.num_pages(body_args).unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn ProjectPushRules> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let rules = gitlab.get().unwrap();
+        assert_eq!("^JIRA-\\d+", rules.commit_message_regex);
+        assert_eq!("", rules.commit_message_negative_regex);
+        assert_eq!(10, rules.max_file_size);
+        assert!(rules.deny_secrets);
+    }
+
+    #[test]
+    fn test_set_push_rules_creates_them_when_there_are_none() {
+        let created = Response::builder()
+            .status(201)
+            .body(r#"{"id": 3, "prevent_secrets": true, "max_file_size": 0}"#.to_string())
+            .build()
+            .unwrap();
+        let none = Response::builder()
+            .status(200)
+            .body("null".to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![created, none]));
+        let gitlab: Box<dyn ProjectPushRules> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let args = PushRulesBodyArgs::builder()
+            .deny_secrets(Some(true))
+            .build()
+            .unwrap();
+        assert!(gitlab.set(args).unwrap().deny_secrets);
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
         assert_eq!(
-            "https://gitlab.com/api/v4/users/1/starred_projects?page=1",
-            client.url().to_string(),
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/push_rule",
+            *client.url(),
+        );
+        assert_eq!(r#"{"prevent_secrets":true}"#, *client.request_body());
+    }
+
+    #[test]
+    fn test_set_push_rules_edits_existing_ones() {
+        let edited = Response::builder()
+            .status(200)
+            .body(r#"{"id": 3, "max_file_size": 25}"#.to_string())
+            .build()
+            .unwrap();
+        let existing = Response::builder()
+            .status(200)
+            .body(r#"{"id": 3, "max_file_size": 10}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![edited, existing]));
+        let gitlab: Box<dyn ProjectPushRules> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let args = PushRulesBodyArgs::builder()
+            .max_file_size(Some(25))
+            .build()
+            .unwrap();
+        assert_eq!(25, gitlab.set(args).unwrap().max_file_size);
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+    }
+
+    #[test]
+    fn test_browse_urls() {
+        let client = Arc::new(MockRunner::new(vec![]));
+        let gitlab = Gitlab::new(config(), "gitlab.com", "jordilin/gitlapi", client);
+        assert_eq!(
+            "https://gitlab.com/jordilin/gitlapi/-/pipelines/1234",
+            gitlab.get_url(BrowseOptions::PipelineId(1234))
         );
         assert_eq!(
-            ApiOperation::Project,
-            *client.api_operation.borrow().as_ref().unwrap()
+            "https://gitlab.com/jordilin/gitlapi/-/blob/HEAD/src/main.rs",
+            gitlab.get_url(BrowseOptions::File {
+                path: "/src/main.rs".to_string(),
+                ref_: None,
+            })
+        );
+        assert_eq!(
+            "https://gitlab.com/jordilin/gitlapi/-/releases",
+            gitlab.get_url(BrowseOptions::Releases)
+        );
+        assert_eq!(
+            "https://gitlab.com/jordilin/gitlapi/edit",
+            gitlab.get_url(BrowseOptions::Settings)
         );
     }
 }
@@ -1,8 +1,13 @@
+use serde_json::json;
+
 use crate::{
     api_traits::{ApiOperation, Deploy},
-    cmds::release::{Release, ReleaseBodyArgs},
-    io::{HttpRunner, Response},
-    remote::query,
+    cmds::release::{asset_name, Release, ReleaseBodyArgs, ReleaseCreateBodyArgs},
+    error::GRError,
+    http::{self, Body},
+    io::{HttpRunner, PageCount, Response, Upload},
+    json_loads,
+    remote::{encode_query_param, query},
     Result,
 };
 
@@ -21,11 +26,87 @@ impl<R: HttpRunner<Response = Response>> Deploy for Gitlab<R> {
         )
     }
 
-    fn num_pages(&self) -> Result<Option<u32>> {
+    fn num_pages(&self) -> Result<Option<PageCount>> {
         let url = format!("{}/releases?page=1", self.rest_api_basepath());
         let headers = self.headers();
         query::num_pages(&self.runner, &url, headers, ApiOperation::Release)
     }
+
+    fn create(
+        &self,
+        args: ReleaseCreateBodyArgs,
+        progress: &dyn Fn(&str, u64, u64),
+    ) -> Result<Release> {
+        let url = format!("{}/releases", self.rest_api_basepath());
+        let mut body = Body::new();
+        body.add("tag_name", json!(args.tag));
+        body.add("name", json!(args.title));
+        body.add("description", json!(args.notes));
+        if let Some(target) = args.target {
+            body.add("ref", json!(target));
+        }
+        let response = query::gitlab_create_release(
+            &self.runner,
+            &url,
+            Some(body),
+            self.headers(),
+            http::Method::POST,
+            ApiOperation::Release,
+        )?;
+        // Gitlab answers 409 if the tag already has a release. Nothing is
+        // uploaded in that case.
+        if response.status != 201 {
+            return Err(GRError::RemoteServerError(format!(
+                "Could not create release {}. Status code: {} and body: {}",
+                args.tag, response.status, response.body
+            ))
+            .into());
+        }
+        let release = GitlabReleaseFields::from(&json_loads(&response.body)?).into();
+        // Assets go to the generic package registry, under a package named
+        // after the project and versioned by the tag, and are linked from the
+        // release.
+        let package = self.path.rsplit('/').next().unwrap_or_default();
+        for asset in &args.assets {
+            let name = asset_name(asset);
+            // Doc:
+            // https://docs.gitlab.com/ee/user/packages/generic_packages/#publish-a-package-file
+            let package_url = format!(
+                "{}/packages/generic/{}/{}/{}",
+                self.rest_api_basepath(),
+                encode_query_param(package),
+                encode_query_param(&args.tag),
+                encode_query_param(&name)
+            );
+            query::upload(
+                &self.runner,
+                &package_url,
+                self.headers(),
+                http::Method::PUT,
+                Upload {
+                    path: asset,
+                    content_type: "application/octet-stream",
+                    progress: &|sent, size| progress(&name, sent, size),
+                },
+                ApiOperation::Release,
+            )?;
+            // Doc:
+            // https://docs.gitlab.com/ee/api/releases/links.html#create-a-release-link
+            let mut body = Body::new();
+            body.add("name", json!(name));
+            body.add("url", json!(package_url));
+            body.add("link_type", json!("package"));
+            query::gitlab_create_release_link(
+                &self.runner,
+                &format!("{}/{}/assets/links", url, encode_query_param(&args.tag)),
+                Some(body),
+                self.headers(),
+                http::Method::POST,
+                ApiOperation::Release,
+            )?;
+        }
+        Ok(release)
+    }
 }
 
 pub struct GitlabReleaseFields {
@@ -91,7 +172,7 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn Deploy> = Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let gitlab: Box<dyn Deploy> = Box::new(Gitlab::new(config, &domain, path, client.clone()));
         let args = ReleaseBodyArgs::builder()
             .from_to_page(None)
             .build()
@@ -119,13 +200,103 @@ mod test {
             .build()
             .unwrap();
         let client = Arc::new(MockRunner::new(vec![response]));
-        let gitlab: Box<dyn Deploy> = Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let gitlab: Box<dyn Deploy> = Box::new(Gitlab::new(config, &domain, path, client.clone()));
         let num_pages = gitlab.num_pages().unwrap();
         assert_eq!(
             "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/releases?page=1",
             *client.url(),
         );
         assert_eq!(Some(ApiOperation::Release), *client.api_operation.borrow());
-        assert_eq!(Some(1), num_pages);
+        assert_eq!(Some(PageCount::from(1)), num_pages);
+    }
+
+    /// First release of the list contract, as returned when creating one.
+    fn created_release() -> String {
+        let releases: serde_json::Value =
+            serde_json::from_str(&get_contract(ContractType::Gitlab, "list_releases.json"))
+                .unwrap();
+        releases[0].to_string()
+    }
+
+    #[test]
+    fn test_create_release_links_assets_in_package_registry() {
+        let dir = tempfile::tempdir().unwrap();
+        let asset = dir.path().join("gr.tar.gz");
+        std::fs::write(&asset, "binary").unwrap();
+        let created = Response::builder()
+            .status(201)
+            .body(created_release())
+            .build()
+            .unwrap();
+        let uploaded = Response::builder().status(201).build().unwrap();
+        let linked = Response::builder().status(201).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![linked, uploaded, created]));
+        let gitlab: Box<dyn Deploy> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let args = ReleaseCreateBodyArgs::builder()
+            .tag("v1.2.0".to_string())
+            .title("v1.2.0".to_string())
+            .notes("Bug fixes".to_string())
+            .assets(vec![asset])
+            .build()
+            .unwrap();
+        let progress = std::cell::RefCell::new(Vec::new());
+        gitlab
+            .create(args, &|name, sent, size| {
+                progress.borrow_mut().push((name.to_string(), sent, size))
+            })
+            .unwrap();
+        assert_eq!(vec![("gr.tar.gz".to_string(), 6, 6)], *progress.borrow());
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/releases/v1.2.0/assets/links",
+            *client.url(),
+        );
+        assert_eq!(http::Method::POST, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!(
+            json!({
+                "name": "gr.tar.gz",
+                "url": "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/packages/generic/gitlapi/v1.2.0/gr.tar.gz",
+                "link_type": "package"
+            }),
+            body
+        );
+    }
+
+    #[test]
+    fn test_create_release_for_tag_with_release_uploads_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let asset = dir.path().join("gr.tar.gz");
+        std::fs::write(&asset, "binary").unwrap();
+        let conflict = Response::builder()
+            .status(409)
+            .body(r#"{"message": "Release already exists"}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![conflict]));
+        let gitlab: Box<dyn Deploy> = Box::new(Gitlab::new(
+            config(),
+            "gitlab.com",
+            "jordilin/gitlapi",
+            client.clone(),
+        ));
+        let args = ReleaseCreateBodyArgs::builder()
+            .tag("v1.2.0".to_string())
+            .title("v1.2.0".to_string())
+            .assets(vec![asset])
+            .build()
+            .unwrap();
+        let Err(err) = gitlab.create(args, &|_, _, _| {}) else {
+            panic!("Expected an error");
+        };
+        assert!(err.to_string().contains("Release already exists"));
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/releases",
+            *client.url(),
+        );
     }
 }
@@ -0,0 +1,149 @@
+use super::Gitlab;
+use crate::api_traits::{ApiOperation, RemoteStatus};
+use crate::cmds::status::{BroadcastMessage, RemoteHealth};
+use crate::http;
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::Result;
+
+impl<R: HttpRunner<Response = Response>> RemoteStatus for Gitlab<R> {
+    fn health(&self) -> Result<RemoteHealth> {
+        let url = format!("{}/version", self.base_api_url);
+        let response = query::gitlab_version::<_, ()>(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            http::Method::GET,
+            ApiOperation::Project,
+        );
+        Ok(RemoteHealth::from_response(
+            &self.domain,
+            response,
+            |body| body["version"].as_str().unwrap_or_default().to_string(),
+        ))
+    }
+
+    fn broadcast_messages(&self) -> Result<Vec<BroadcastMessage>> {
+        let url = format!("{}/broadcast_messages", self.base_api_url);
+        let messages = query::gitlab_list_broadcast_messages(
+            &self.runner,
+            &url,
+            None,
+            self.headers(),
+            None,
+            ApiOperation::Project,
+        )?;
+        Ok(messages.into_iter().filter(|m| m.active).collect())
+    }
+}
+
+pub struct GitlabBroadcastMessageFields {
+    id: i64,
+    message: String,
+    starts_at: String,
+    ends_at: String,
+    active: bool,
+}
+
+impl From<&serde_json::Value> for GitlabBroadcastMessageFields {
+    fn from(data: &serde_json::Value) -> Self {
+        GitlabBroadcastMessageFields {
+            id: data["id"].as_i64().unwrap(),
+            message: data["message"].as_str().unwrap_or_default().to_string(),
+            starts_at: data["starts_at"].as_str().unwrap_or_default().to_string(),
+            ends_at: data["ends_at"].as_str().unwrap_or_default().to_string(),
+            active: data["active"].as_bool().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GitlabBroadcastMessageFields> for BroadcastMessage {
+    fn from(fields: GitlabBroadcastMessageFields) -> Self {
+        BroadcastMessage::builder()
+            .id(fields.id)
+            .message(fields.message)
+            .starts_at(fields.starts_at)
+            .ends_at(fields.ends_at)
+            .active(fields.active)
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        cmds::status::{HEALTH_MAINTENANCE, HEALTH_OK},
+        test::utils::{config, get_contract, ContractType, MockRunner},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_health_gets_version() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(ContractType::Gitlab, "version.json"))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn RemoteStatus> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let health = gitlab.health().unwrap();
+        assert_eq!(HEALTH_OK, health.status);
+        assert_eq!("16.10.0-pre", health.version);
+        assert_eq!("https://gitlab.com/api/v4/version", *client.url());
+    }
+
+    #[test]
+    fn test_health_under_maintenance() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(503)
+            .body(r#"{"message":"GitLab Maintenance: upgrade in progress"}"#.to_string())
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn RemoteStatus> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let health = gitlab.health().unwrap();
+        assert_eq!(HEALTH_MAINTENANCE, health.status);
+        assert_eq!("GitLab Maintenance: upgrade in progress", health.message);
+    }
+
+    #[test]
+    fn test_broadcast_messages_only_active() {
+        let config = config();
+        let domain = "gitlab.com".to_string();
+        let path = "jordilin/gitlapi".to_string();
+        let response = Response::builder()
+            .status(200)
+            .body(get_contract(
+                ContractType::Gitlab,
+                "list_broadcast_messages.json",
+            ))
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab: Box<dyn RemoteStatus> =
+            Box::new(Gitlab::new(config, &domain, &path, client.clone()));
+        let messages = gitlab.broadcast_messages().unwrap();
+        assert_eq!(1, messages.len());
+        assert_eq!(
+            "Scheduled maintenance on Saturday 10:00 UTC",
+            messages[0].message
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/broadcast_messages",
+            *client.url()
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::api_traits::{ApiOperation, Undo};
+use crate::audit::{AuditEntry, Reversal};
+use crate::error::GRError;
+use crate::http::{self, Body};
+use crate::io::{HttpRunner, Response};
+use crate::remote::query;
+use crate::Result;
+
+use super::Gitlab;
+
+/// Request that reverses an operation recorded in the audit log.
+struct Inverse {
+    description: String,
+    method: http::Method,
+    url: String,
+    body: Option<Body<&'static str>>,
+}
+
+impl<R> Gitlab<R> {
+    fn inverse(&self, entry: &AuditEntry) -> Option<Inverse> {
+        let resource = entry.resource(self.rest_api_basepath())?;
+        let id_regex = regex::Regex::new(r"^/merge_requests/(\d+)(/\w+)?$").unwrap();
+        let captures = id_regex.captures(resource)?;
+        let id = captures.get(1).unwrap().as_str();
+        let mr_url = format!("{}/merge_requests/{}", self.rest_api_basepath(), id);
+        match (
+            entry.method.as_str(),
+            captures.get(2).map(|action| action.as_str()),
+        ) {
+            ("PUT", None) => {
+                let (state_event, description) = match entry.body_field("state_event")? {
+                    "close" => ("reopen", "Reopen"),
+                    "reopen" => ("close", "Close"),
+                    _ => return None,
+                };
+                let mut body = Body::new();
+                body.add("state_event", state_event);
+                Some(Inverse {
+                    description: format!("{} merge request {}", description, id),
+                    method: http::Method::PUT,
+                    url: mr_url,
+                    body: Some(body),
+                })
+            }
+            ("POST", Some(action)) => {
+                let (inverse_action, description) = match action {
+                    "/approve" => ("unapprove", "Unapprove"),
+                    "/unapprove" => ("approve", "Approve"),
+                    "/subscribe" => ("unsubscribe", "Unsubscribe from"),
+                    "/unsubscribe" => ("subscribe", "Subscribe to"),
+                    _ => return None,
+                };
+                Some(Inverse {
+                    description: format!("{} merge request {}", description, id),
+                    method: http::Method::POST,
+                    url: format!("{}/{}", mr_url, inverse_action),
+                    body: None,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<R: HttpRunner<Response = Response>> Undo for Gitlab<R> {
+    fn reversal(&self, entry: &AuditEntry) -> Reversal {
+        if entry.resource(self.rest_api_basepath()).is_none() {
+            return Reversal::Unrelated;
+        }
+        match self.inverse(entry) {
+            Some(inverse) => Reversal::Reversible(inverse.description),
+            None => Reversal::Irreversible,
+        }
+    }
+
+    fn undo(&self, entry: &AuditEntry) -> Result<()> {
+        let inverse = self.inverse(entry).ok_or_else(|| {
+            GRError::PreconditionNotMet(format!("{} {} cannot be undone", entry.method, entry.url))
+        })?;
+        query::gitlab_merge_request_response(
+            &self.runner,
+            &inverse.url,
+            inverse.body,
+            self.headers(),
+            inverse.method,
+            ApiOperation::MergeRequest,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use crate::test::utils::{config, MockRunner};
+
+    use super::*;
+
+    fn entry(method: &str, url: &str, body: Option<serde_json::Value>) -> AuditEntry {
+        AuditEntry::builder()
+            .timestamp(0)
+            .user("jdoe".to_string())
+            .method(method.to_string())
+            .url(url.to_string())
+            .result("200".to_string())
+            .body(body)
+            .build()
+            .unwrap()
+    }
+
+    fn gitlab(client: Arc<MockRunner>) -> Gitlab<MockRunner> {
+        Gitlab::new(config(), "gitlab.com", "jordilin/gitlapi", client)
+    }
+
+    #[test]
+    fn test_reversal_of_gitlab_operations() {
+        let gitlab = gitlab(Arc::new(MockRunner::new(vec![])));
+        let base = "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi";
+        assert_eq!(
+            Reversal::Reversible("Reopen merge request 2".to_string()),
+            gitlab.reversal(&entry(
+                "PUT",
+                &format!("{}/merge_requests/2", base),
+                Some(json!({"state_event": "close"}))
+            ))
+        );
+        assert_eq!(
+            Reversal::Reversible("Unapprove merge request 2".to_string()),
+            gitlab.reversal(&entry(
+                "POST",
+                &format!("{}/merge_requests/2/approve", base),
+                None
+            ))
+        );
+        assert_eq!(
+            Reversal::Irreversible,
+            gitlab.reversal(&entry(
+                "PUT",
+                &format!("{}/merge_requests/2/merge", base),
+                None
+            ))
+        );
+        assert_eq!(
+            Reversal::Unrelated,
+            gitlab.reversal(&entry(
+                "POST",
+                "https://gitlab.com/api/v4/projects/jordilin%2Fother/merge_requests/2/approve",
+                None
+            ))
+        );
+        assert_eq!(
+            Reversal::Unrelated,
+            gitlab.reversal(&entry(
+                "PUT",
+                "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi-fork/merge_requests/2/merge",
+                None
+            ))
+        );
+    }
+
+    #[test]
+    fn test_undo_close_reopens_merge_request() {
+        let response = Response::builder().status(200).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let gitlab = gitlab(client.clone());
+        gitlab
+            .undo(&entry(
+                "PUT",
+                "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/2",
+                Some(json!({"state_event": "close"})),
+            ))
+            .unwrap();
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi/merge_requests/2",
+            *client.url()
+        );
+        assert_eq!(http::Method::PUT, *client.http_method.borrow());
+        let body: serde_json::Value = serde_json::from_str(&client.request_body()).unwrap();
+        assert_eq!("reopen", body["state_event"]);
+    }
+}
@@ -1,24 +1,42 @@
 use crate::api_traits::ApiOperation;
-use crate::backoff::ExponentialBackoff;
+use crate::audit::{self, AuditEntry, AuditLog};
+use crate::backoff::{ExponentialBackoff, RetryPolicy};
 use crate::cache::{Cache, CacheState};
+use crate::cancel;
 use crate::config::ConfigProperties;
-use crate::error::GRError;
-use crate::io::{HttpRunner, RateLimitHeader, Response, ResponseField};
+use crate::error::{AddContext, GRError};
+use crate::io::{HttpRunner, Page, RateLimitHeader, Response, ResponseField, Upload};
+use crate::ratelimit::RateLimitStore;
+use crate::stats::ApiStats;
 use crate::time::{self, now_epoch_seconds, Milliseconds, Seconds};
 use crate::{api_defaults, error, log_debug, log_error};
 use crate::{log_info, Result};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::{hash_map, HashMap};
+use std::collections::hash_map::RandomState;
+use std::collections::{hash_map, HashMap, VecDeque};
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Read;
 use std::iter::Iterator;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use ureq::Error;
 
 pub struct Client<C, D> {
     cache: C,
     config: D,
     refresh_cache: bool,
+    // Answer with expired cached responses instead of revalidating them.
+    serve_stale: bool,
     time_to_ratelimit_reset: Mutex<Seconds>,
     remaining_requests: Mutex<u32>,
+    // Rate limit reported by the last response, to throttle the next
+    // requests.
+    ratelimit: Mutex<Option<RateLimitHeader>>,
+    audit_log: Option<Box<dyn AuditLog + Send + Sync>>,
+    api_stats: Option<Arc<ApiStats>>,
+    ratelimit_store: Option<Box<dyn RateLimitStore + Send + Sync>>,
 }
 
 // TODO: provide builder pattern for Client.
@@ -29,31 +47,100 @@ impl<C, D: ConfigProperties> Client<C, D> {
         Client {
             cache,
             refresh_cache,
+            serve_stale: false,
             config,
             time_to_ratelimit_reset,
             remaining_requests,
+            ratelimit: Mutex::new(None),
+            audit_log: None,
+            api_stats: None,
+            ratelimit_store: None,
+        }
+    }
+
+    /// Serves cached responses even once expired, without asking the remote.
+    /// For data that rarely changes, refreshed apart by a client without it.
+    /// Refreshing the cache still goes to the remote.
+    pub fn with_stale_responses(mut self, serve_stale: bool) -> Self {
+        self.serve_stale = serve_stale;
+        self
+    }
+
+    /// Counts the requests sent and the responses served from the cache in
+    /// `api_stats`.
+    pub fn with_api_stats(mut self, api_stats: Arc<ApiStats>) -> Self {
+        self.api_stats = Some(api_stats);
+        self
+    }
+
+    fn record_cache_hit<T>(&self, request: &Request<T>) {
+        if let Some(api_stats) = &self.api_stats {
+            api_stats.record_cache_hit(request.api_operation().as_ref());
+        }
+    }
+
+    /// Records every mutating request sent to the remote in `audit_log`.
+    pub fn with_audit_log(mut self, audit_log: impl AuditLog + Send + Sync + 'static) -> Self {
+        self.audit_log = Some(Box::new(audit_log));
+        self
+    }
+
+    /// Starts from the rate limit left by a previous invocation in
+    /// `ratelimit_store` and keeps it updated with the one reported by each
+    /// response.
+    pub fn with_ratelimit_store(
+        mut self,
+        ratelimit_store: impl RateLimitStore + Send + Sync + 'static,
+    ) -> Self {
+        *self.ratelimit.lock().unwrap() = ratelimit_store.load();
+        self.ratelimit_store = Some(Box::new(ratelimit_store));
+        self
+    }
+
+    fn store_rate_limit(&self, ratelimit: &RateLimitHeader) {
+        if let Some(ratelimit_store) = &self.ratelimit_store {
+            if let Err(err) = ratelimit_store.save(ratelimit) {
+                log_error!("Could not store the rate limit: {}", err);
+            }
+        }
+    }
+
+    fn audit<T: Serialize>(&self, request: &Request<T>, result: &Result<Response>) {
+        if let Some(audit_log) = &self.audit_log {
+            if audit::is_mutation(request) {
+                // Failing to write the trail must not fail the operation
+                // that has already been done in the remote.
+                if let Err(err) = audit_log.record(&AuditEntry::new(request, result)) {
+                    log_error!("Could not record the request in the audit log: {}", err);
+                }
+            }
         }
     }
 
     fn submit<T: Serialize>(&self, request: &Request<T>) -> Result<Response> {
-        let ureq_req = match request.method {
-            Method::GET => ureq::get(request.url()),
-            Method::HEAD => ureq::head(request.url()),
-            Method::POST => ureq::post(request.url()),
-            Method::PATCH => ureq::patch(request.url()),
-            Method::PUT => ureq::put(request.url()),
-        };
-        let ureq_req = request
-            .headers()
-            .iter()
-            .fold(ureq_req, |req, (key, value)| req.set(key, value));
-        let call = || -> std::result::Result<ureq::Response, ureq::Error> {
-            match request.method {
-                Method::GET | Method::HEAD => ureq_req.call(),
-                _ => ureq_req.send_json(serde_json::to_value(&request.body).unwrap()),
+        self.wait_for_rate_limit()?;
+        let ureq_req = ureq_request(request);
+        let started = Instant::now();
+        let mut bytes_sent = 0;
+        let result = match request.method {
+            Method::GET | Method::HEAD | Method::DELETE => ureq_req.call(),
+            _ => {
+                let body = serde_json::to_value(&request.body).unwrap();
+                bytes_sent = body.to_string().len() as u64;
+                ureq_req.send_json(body)
             }
         };
-        match call() {
+        self.response(request, result, bytes_sent, started)
+    }
+
+    fn response<T>(
+        &self,
+        request: &Request<T>,
+        result: std::result::Result<ureq::Response, Error>,
+        bytes_sent: u64,
+        started: Instant,
+    ) -> Result<Response> {
+        match result {
             Ok(response) | Err(Error::Status(_, response)) => {
                 let status = response.status().into();
                 // Grab headers for pagination and cache.
@@ -69,6 +156,10 @@ impl<C, D: ConfigProperties> Client<C, D> {
                             headers
                         });
                 let body = response.into_string().unwrap_or_default();
+                self.record_request(request, bytes_sent + body.len() as u64, started);
+                if status >= 400 {
+                    self.record_error(request);
+                }
                 let response = Response::builder()
                     .status(status)
                     .body(body)
@@ -78,18 +169,123 @@ impl<C, D: ConfigProperties> Client<C, D> {
                 self.handle_rate_limit(&response)?;
                 Ok(response)
             }
-            Err(err) => Err(GRError::HttpTransportError(err.to_string()).into()),
+            Err(err) => {
+                self.record_request(request, bytes_sent, started);
+                self.record_error(request);
+                Err(GRError::HttpTransportError(err.to_string()).into())
+            }
+        }
+    }
+
+    /// Sends an idempotent request, retrying it on transient errors as per
+    /// the retry policy of the domain.
+    fn submit_with_retries<T: Serialize>(&self, request: &Request<T>) -> Result<Response> {
+        let policy = RetryPolicy::new(&self.config);
+        let mut retry = 1;
+        loop {
+            let result = self.submit(request);
+            let Some(delay) = policy.delay(&result, retry, random()) else {
+                return result;
+            };
+            log_info!(
+                "Retrying {} in {} ms, {} out of {}",
+                request.url(),
+                delay,
+                retry,
+                policy.max_retries
+            );
+            cancel::sleep(
+                Duration::from_millis(*delay),
+                &format!("retrying {}", request.url()),
+            )?;
+            retry += 1;
+        }
+    }
+
+    fn submit_file(&self, request: &Request<()>, upload: Upload) -> Result<Response> {
+        let file = File::open(upload.path).err_context(GRError::PreconditionNotMet(format!(
+            "Cannot open file {}",
+            upload.path.display()
+        )))?;
+        let size = file.metadata()?.len();
+        self.wait_for_rate_limit()?;
+        let ureq_req = ureq_request(request)
+            .set("Content-Type", upload.content_type)
+            .set("Content-Length", &size.to_string());
+        let reader = ProgressReader {
+            inner: file,
+            sent: 0,
+            size,
+            progress: upload.progress,
+        };
+        let started = Instant::now();
+        let result = ureq_req.send(reader);
+        self.response(request, result, size, started)
+    }
+
+    fn record_request<T>(&self, request: &Request<T>, bytes: u64, started: Instant) {
+        if let Some(api_stats) = &self.api_stats {
+            api_stats.record_request(request.api_operation().as_ref(), bytes, started.elapsed());
+        }
+    }
+
+    fn record_error<T>(&self, request: &Request<T>) {
+        if let Some(api_stats) = &self.api_stats {
+            api_stats.record_error(request.api_operation().as_ref());
         }
     }
 }
 
+impl<C: Cache<Resource>, D: ConfigProperties> Client<C, D> {
+    /// Drops the cached responses telling that the resource just changed, the
+    /// collections it is in or the resources under it do not exist or are
+    /// empty. Ex. creating a project drops the 404 cached when probing for it
+    /// and every cached page of an empty list of projects.
+    fn invalidate_negative_lookups<T>(&self, request: &Request<T>) {
+        let changed = resource_path(request.url());
+        let related = |url: &str| related_paths(changed, resource_path(url));
+        if let Err(err) = self.cache.remove_negative(&related) {
+            log_error!(
+                "Could not invalidate the cached lookups of {}: {}",
+                changed,
+                err
+            );
+        }
+    }
+}
+
+/// URL of the resource without the query, ex. the page of a list.
+fn resource_path(url: &str) -> &str {
+    url.split('?')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/')
+}
+
+/// Whether one of the resources is the other one or is under it. Whole path
+/// segments are compared, `projects/12` is not under `projects/1`.
+fn related_paths(a: &str, b: &str) -> bool {
+    let under = |child: &str, parent: &str| {
+        child
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('/'))
+    };
+    a == b || under(a, b) || under(b, a)
+}
+
 impl<C, D: ConfigProperties> Client<C, D> {
     fn handle_rate_limit(&self, response: &Response) -> Result<()> {
         if let Some(headers) = response.get_ratelimit_headers() {
-            if headers.remaining <= self.config.rate_limit_remaining_threshold() {
+            self.store_rate_limit(&headers);
+            // When waiting for the reset is allowed, the next request waits
+            // instead.
+            if headers.remaining <= self.config.rate_limit_remaining_threshold()
+                && *self.config.rate_limit_max_wait() == 0
+            {
                 log_error!("Rate limit threshold reached");
                 return Err(error::GRError::RateLimitExceeded(headers).into());
             }
+            *self.ratelimit.lock().unwrap() = Some(headers);
             Ok(())
         } else {
             // The remote does not provide rate limit headers, so we apply our
@@ -107,6 +303,73 @@ impl<C, D: ConfigProperties> Client<C, D> {
     }
 }
 
+impl<C, D: ConfigProperties> Client<C, D> {
+    /// Sleeps before sending a request if the rate limit reported by the last
+    /// response is about to be reached.
+    fn wait_for_rate_limit(&self) -> Result<()> {
+        let Some(ratelimit) = self.ratelimit.lock().unwrap().clone() else {
+            return Ok(());
+        };
+        let threshold = self.config.rate_limit_remaining_threshold();
+        let wait = rate_limit_wait(
+            &ratelimit,
+            now_epoch_seconds(),
+            threshold,
+            self.config.rate_limit_slowdown_threshold(),
+            self.config.rate_limit_max_wait(),
+        )?;
+        let Some(wait) = wait else {
+            return Ok(());
+        };
+        if ratelimit.remaining <= threshold {
+            eprintln!(
+                "Rate limit threshold reached, waiting {} seconds for it to reset",
+                wait
+            );
+        } else {
+            log_info!(
+                "Rate limit remaining {}, slowing down for {} seconds",
+                ratelimit.remaining,
+                wait
+            );
+        }
+        cancel::sleep(
+            Duration::from_secs(*wait),
+            "waiting for the rate limit to reset",
+        )
+    }
+}
+
+/// Time to wait before the next request given the last rate limit reported
+/// by the remote. At the remaining `threshold` it waits for the reset, up to
+/// `max_wait`, failing if it is further away. Below `slowdown_threshold`, the
+/// requests left above the threshold are spread evenly until the reset.
+fn rate_limit_wait(
+    ratelimit: &RateLimitHeader,
+    now: Seconds,
+    threshold: u32,
+    slowdown_threshold: u32,
+    max_wait: Seconds,
+) -> Result<Option<Seconds>> {
+    // Seconds subtract to the absolute difference, so check the reset is
+    // still ahead first.
+    if ratelimit.reset <= now {
+        return Ok(None);
+    }
+    let time_to_reset = ratelimit.reset - now;
+    if ratelimit.remaining <= threshold {
+        if time_to_reset <= max_wait {
+            return Ok(Some(time_to_reset));
+        }
+        return Err(error::GRError::RateLimitExceeded(ratelimit.clone()).into());
+    }
+    if ratelimit.remaining <= slowdown_threshold {
+        let requests_left = Seconds::new((ratelimit.remaining - threshold) as u64);
+        return Ok(Some(time_to_reset / requests_left));
+    }
+    Ok(None)
+}
+
 fn default_rate_limit_handler(
     config: &impl ConfigProperties,
     time_to_ratelimit_reset: &Mutex<Seconds>,
@@ -170,6 +433,43 @@ fn default_rate_limit_handler(
     Ok(())
 }
 
+/// Random number to add jitter to retries.
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+fn ureq_request<T>(request: &Request<T>) -> ureq::Request {
+    let ureq_req = match request.method {
+        Method::GET => ureq::get(request.url()),
+        Method::HEAD => ureq::head(request.url()),
+        Method::POST => ureq::post(request.url()),
+        Method::PATCH => ureq::patch(request.url()),
+        Method::PUT => ureq::put(request.url()),
+        Method::DELETE => ureq::delete(request.url()),
+    };
+    request
+        .headers()
+        .iter()
+        .fold(ureq_req, |req, (key, value)| req.set(key, value))
+}
+
+/// Reports how much of a file has been read while it is being sent.
+struct ProgressReader<'a> {
+    inner: File,
+    sent: u64,
+    size: u64,
+    progress: &'a dyn Fn(u64, u64),
+}
+
+impl Read for ProgressReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.sent += read as u64;
+        (self.progress)(self.sent, self.size);
+        Ok(read)
+    }
+}
+
 #[derive(Default)]
 pub struct Resource {
     pub url: String,
@@ -201,6 +501,14 @@ impl<T> Body<T> {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Headers(HashMap<String, String>);
 
+/// User-Agent sent to the remotes, followed by the configured `suffix`.
+pub fn user_agent(suffix: &str) -> String {
+    if suffix.is_empty() {
+        return api_defaults::USER_AGENT.to_string();
+    }
+    format!("{} {}", api_defaults::USER_AGENT, suffix)
+}
+
 impl Headers {
     pub fn new() -> Self {
         Headers(HashMap::new())
@@ -214,7 +522,7 @@ impl Headers {
         self.0.get(key)
     }
 
-    pub fn iter(&self) -> hash_map::Iter<String, String> {
+    pub fn iter(&self) -> hash_map::Iter<'_, String, String> {
         self.0.iter()
     }
 
@@ -286,6 +594,10 @@ impl<T> Request<T> {
         &self.resource.url
     }
 
+    pub fn body(&self) -> &Option<Body<T>> {
+        &self.body
+    }
+
     pub fn headers(&self) -> &Headers {
         &self.headers
     }
@@ -299,9 +611,10 @@ pub enum Method {
     POST,
     PUT,
     PATCH,
+    DELETE,
 }
 
-impl<C: Cache<Resource>, D: ConfigProperties> HttpRunner for Client<C, D> {
+impl<C: Cache<Resource> + Sync, D: ConfigProperties + Sync> HttpRunner for Client<C, D> {
     type Response = Response;
 
     fn run<T: Serialize>(&self, cmd: &mut Request<T>) -> Result<Self::Response> {
@@ -313,12 +626,18 @@ impl<C: Cache<Resource>, D: ConfigProperties> HttpRunner for Client<C, D> {
                         log_debug!("Cache fresh for {}", cmd.resource.url);
                         if !self.refresh_cache {
                             log_debug!("Returning local cached response");
+                            self.record_cache_hit(cmd);
                             return Ok(response);
                         }
                         default_response = response;
                     }
                     Ok(CacheState::Stale(response)) => {
                         log_debug!("Cache stale for {}", cmd.resource.url);
+                        if self.serve_stale && !self.refresh_cache {
+                            log_debug!("Returning local stale cached response");
+                            self.record_cache_hit(cmd);
+                            return Ok(response);
+                        }
                         default_response = response;
                     }
                     Ok(CacheState::None) => {}
@@ -330,28 +649,89 @@ impl<C: Cache<Resource>, D: ConfigProperties> HttpRunner for Client<C, D> {
                     cmd.set_header("If-None-Match", etag);
                 }
                 // If status is 304, then we need to return the cached response.
-                let response = self.submit(cmd)?;
+                let response = self.submit_with_retries(cmd)?;
                 if response.status == 304 {
                     // Update cache with latest headers. This effectively
                     // refreshes the cache and we won't hit this until per api
                     // cache expiration as declared in the config.
                     self.cache
                         .update(&cmd.resource, &response, &ResponseField::Headers)?;
+                    self.record_cache_hit(cmd);
                     return Ok(default_response);
                 }
-                self.cache.set(&cmd.resource, &response).unwrap();
+                // Not being able to cache the response does not make the
+                // request fail.
+                if let Err(err) = self.cache.set(&cmd.resource, &response) {
+                    log_error!("Could not cache the response: {}", err);
+                }
                 Ok(response)
             }
-            _ => Ok(self.submit(cmd)?),
+            Method::HEAD => self.submit_with_retries(cmd),
+            _ => {
+                let result = self.submit(cmd);
+                self.audit(cmd, &result);
+                if matches!(&result, Ok(response) if (200..300).contains(&response.status)) {
+                    self.invalidate_negative_lookups(cmd);
+                }
+                result
+            }
         }
     }
 
+    fn upload(&self, cmd: &mut Request<()>, upload: Upload) -> Result<Self::Response> {
+        let result = self.submit_file(cmd, upload);
+        self.audit(cmd, &result);
+        result
+    }
+
     fn api_max_pages<T: Serialize>(&self, cmd: &Request<T>) -> u32 {
         let max_pages = self
             .config
             .get_max_pages(cmd.resource.api_operation.as_ref().unwrap());
         max_pages
     }
+
+    fn api_per_page(&self) -> Option<u32> {
+        self.config.per_page()
+    }
+
+    fn run_pages<T: Serialize + Send>(
+        &self,
+        requests: Vec<Request<T>>,
+        ratelimit: Option<RateLimitHeader>,
+    ) -> Vec<Result<Response>> {
+        // Leave room for the threshold, so the requests in flight do not
+        // exhaust the rate limit. Remotes without rate limit headers are
+        // bound by our default counter.
+        let remaining = match ratelimit {
+            Some(ratelimit) => ratelimit.remaining,
+            None => *self.remaining_requests.lock().unwrap(),
+        };
+        let workers = remaining
+            .saturating_sub(self.config.rate_limit_remaining_threshold())
+            .clamp(1, api_defaults::MAX_CONCURRENT_PAGE_REQUESTS) as usize;
+        log_info!("Requesting {} pages, {} at a time", requests.len(), workers);
+        let mut responses = Vec::with_capacity(requests.len());
+        let mut requests = requests.into_iter().peekable();
+        while requests.peek().is_some() {
+            let batch = requests.by_ref().take(workers).collect::<Vec<_>>();
+            std::thread::scope(|scope| {
+                let handles = batch
+                    .into_iter()
+                    .map(|mut request| scope.spawn(move || self.run(&mut request)))
+                    .collect::<Vec<_>>();
+                for handle in handles {
+                    responses.push(handle.join().unwrap_or_else(|_| {
+                        Err(
+                            GRError::ApplicationError("Page request thread panicked".to_string())
+                                .into(),
+                        )
+                    }));
+                }
+            });
+        }
+        responses
+    }
 }
 
 pub struct Paginator<'a, R, T> {
@@ -361,6 +741,8 @@ pub struct Paginator<'a, R, T> {
     iter: u32,
     throttle_time: Option<Milliseconds>,
     backoff: ExponentialBackoff<'a, R>,
+    concurrent: bool,
+    prefetched: VecDeque<Result<Response>>,
 }
 
 impl<'a, R, T> Paginator<'a, R, T> {
@@ -384,14 +766,102 @@ impl<'a, R, T> Paginator<'a, R, T> {
                 backoff_default_wait_time,
                 time::now_epoch_seconds,
             ),
+            concurrent: false,
+            prefetched: VecDeque::new(),
         }
     }
+
+    /// Once the first page tells which one is the last, request all the
+    /// remaining pages at once instead of following the next links.
+    pub fn with_concurrent_pages(mut self) -> Self {
+        self.concurrent = true;
+        self
+    }
 }
 
-impl<'a, T: Serialize, R: HttpRunner<Response = Response>> Iterator for Paginator<'a, R, T> {
+impl<'a, T: Serialize + Send, R: HttpRunner<Response = Response>> Paginator<'a, R, T> {
+    fn prefetch(&mut self, next: &Page, last: &Page, ratelimit: Option<RateLimitHeader>) {
+        // Cursor based pages can only be followed one at a time.
+        if next.cursor.is_some() || next.number == 0 || last.number < next.number {
+            return;
+        }
+        let max_pages = match self.request.max_pages {
+            Some(max_pages) => max_pages as u32,
+            None => self.runner.api_max_pages(&self.request),
+        };
+        let remaining_pages = max_pages.saturating_sub(self.iter);
+        if remaining_pages == 0 {
+            return;
+        }
+        let last_number = last.number.min(next.number + remaining_pages - 1);
+        if last_number < last.number {
+            log_info!(
+                "Listing up to page {} out of {}, as per max pages",
+                last_number,
+                last.number
+            );
+        }
+        let urls = (next.number..=last_number)
+            .map(|number| page_url(&next.url, number))
+            .collect::<Option<Vec<String>>>();
+        let Some(urls) = urls else {
+            return;
+        };
+        // The ETag belongs to the first page. Each page request sets its own.
+        let headers = self
+            .request
+            .headers()
+            .iter()
+            .filter(|(key, _)| key.as_str() != "If-None-Match")
+            .fold(Headers::new(), |mut headers, (key, value)| {
+                headers.set(key.clone(), value.clone());
+                headers
+            });
+        let requests = urls
+            .iter()
+            .map(|url| Request {
+                body: None,
+                headers: headers.clone(),
+                method: Method::GET,
+                resource: Resource::new(url, self.request.api_operation().clone()),
+                max_pages: None,
+            })
+            .collect::<Vec<Request<T>>>();
+        self.iter += requests.len() as u32;
+        self.prefetched = self.runner.run_pages(requests, ratelimit).into();
+        self.page_url = None;
+    }
+}
+
+/// Last page out of the total pages reported by the remote, for link headers
+/// without a last link.
+fn last_page(next: &Page, total_pages: Option<u32>) -> Option<Page> {
+    let total_pages = total_pages?;
+    page_url(&next.url, total_pages).map(|url| Page::new(&url, total_pages))
+}
+
+/// URL of page `number` out of the URL of another page of the same list.
+fn page_url(url: &str, number: u32) -> Option<String> {
+    lazy_static! {
+        static ref RE_PAGE: Regex = Regex::new(r"([?&])page=\d+").unwrap();
+    }
+    if !RE_PAGE.is_match(url) {
+        return None;
+    }
+    Some(
+        RE_PAGE
+            .replace(url, format!("${{1}}page={}", number).as_str())
+            .to_string(),
+    )
+}
+
+impl<'a, T: Serialize + Send, R: HttpRunner<Response = Response>> Iterator for Paginator<'a, R, T> {
     type Item = Result<Response>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(response) = self.prefetched.pop_front() {
+            return Some(response);
+        }
         if let Some(page_url) = &self.page_url {
             if let Some(max_pages) = self.request.max_pages {
                 if self.iter >= max_pages as u32 {
@@ -401,6 +871,10 @@ impl<'a, T: Serialize, R: HttpRunner<Response = Response>> Iterator for Paginato
                 return None;
             }
             if self.iter >= 1 {
+                if cancel::cancelled() {
+                    log_info!("Cancelled after {} pages", self.iter);
+                    return None;
+                }
                 self.request.set_url(page_url);
             }
             log_info!("Requesting page: {}", self.iter + 1);
@@ -412,13 +886,19 @@ impl<'a, T: Serialize, R: HttpRunner<Response = Response>> Iterator for Paginato
             match self.backoff.retry_on_error(&mut self.request) {
                 Ok(response) => {
                     if let Some(page_headers) = response.get_page_headers() {
-                        let next_page = page_headers.next;
-                        let last_page = page_headers.last;
-                        match (next_page, last_page) {
-                            (Some(next), _) => self.page_url = Some(next.url),
-                            (None, _) => self.page_url = None,
-                        }
+                        self.page_url = page_headers.next.as_ref().map(|next| next.url.clone());
                         self.iter += 1;
+                        if self.concurrent && self.iter == 1 {
+                            if let Some(next) = &page_headers.next {
+                                let last = page_headers
+                                    .last
+                                    .clone()
+                                    .or_else(|| last_page(next, response.total_pages()));
+                                if let Some(last) = last {
+                                    self.prefetch(next, &last, response.get_ratelimit_headers());
+                                }
+                            }
+                        }
                         return Some(Ok(response));
                     }
                     self.page_url = None;
@@ -510,6 +990,25 @@ mod test {
         assert_eq!(2, responses.len());
     }
 
+    #[test]
+    fn test_paginator_follows_cursor_next_page() {
+        let next_url = "http://localhost?per_page=30&after=Y3Vyc29yOjEw";
+        let mut headers = Headers::new();
+        headers.set("link".to_string(), format!("<{}>; rel=\"next\"", next_url));
+        let response1 = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let response2 = Response::builder().status(200).build().unwrap();
+        let client = Arc::new(MockRunner::new(vec![response2, response1]));
+        let request: Request<()> = Request::new("http://localhost", Method::GET);
+        let paginator = Paginator::new(&client, request, "http://localhost", None, 0, 60);
+        let responses = paginator.collect::<Vec<Result<Response>>>();
+        assert_eq!(2, responses.len());
+        assert_eq!(next_url, *client.url());
+    }
+
     #[test]
     fn test_paginator_with_link_headers_one_next_and_one_last_pages() {
         let response1 = response_with_next_page();
@@ -521,6 +1020,19 @@ mod test {
         assert_eq!(2, responses.len());
     }
 
+    #[test]
+    fn test_paginator_stops_paging_once_cancelled() {
+        let response1 = response_with_next_page();
+        let response2 = response_with_last_page();
+        let client = Arc::new(MockRunner::new(vec![response2, response1]));
+        let request: Request<()> = Request::new("http://localhost", Method::GET);
+        let mut paginator = Paginator::new(&client, request, "http://localhost", None, 0, 60);
+        assert!(paginator.next().unwrap().is_ok());
+        cancel::cancel();
+        assert!(paginator.next().is_none());
+        assert_eq!("http://localhost", *client.url());
+    }
+
     #[test]
     fn test_paginator_error_response() {
         let response = Response::builder()
@@ -604,6 +1116,145 @@ mod test {
         assert!(client.handle_rate_limit(&response).is_ok());
     }
 
+    #[test]
+    fn test_rate_limit_wait_for_reset_at_threshold() {
+        let ratelimit = RateLimitHeader::new(10, Seconds::new(1090), Seconds::new(0));
+        let now = Seconds::new(1000);
+        assert_eq!(
+            Some(Seconds::new(90)),
+            rate_limit_wait(&ratelimit, now, 10, 0, Seconds::new(120)).unwrap()
+        );
+        // Reset too far away to wait for it.
+        assert!(rate_limit_wait(&ratelimit, now, 10, 0, Seconds::new(60)).is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_wait_spreads_requests_below_slowdown_threshold() {
+        let now = Seconds::new(1000);
+        let ratelimit = RateLimitHeader::new(40, Seconds::new(1060), Seconds::new(0));
+        // 30 requests left above the threshold in 60 seconds.
+        assert_eq!(
+            Some(Seconds::new(2)),
+            rate_limit_wait(&ratelimit, now, 10, 50, Seconds::new(0)).unwrap()
+        );
+        assert_eq!(
+            None,
+            rate_limit_wait(&ratelimit, now, 10, 0, Seconds::new(0)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_wait_after_reset_does_not_wait() {
+        let ratelimit = RateLimitHeader::new(0, Seconds::new(900), Seconds::new(0));
+        assert_eq!(
+            None,
+            rate_limit_wait(&ratelimit, Seconds::new(1000), 10, 50, Seconds::new(0)).unwrap()
+        );
+    }
+
+    struct WaitConfigMock;
+
+    impl ConfigProperties for WaitConfigMock {
+        fn api_token(&self) -> &str {
+            "1234"
+        }
+        fn cache_location(&self) -> &str {
+            ""
+        }
+        fn rate_limit_max_wait(&self) -> Seconds {
+            Seconds::new(60)
+        }
+    }
+
+    #[test]
+    fn test_ratelimit_threshold_reached_with_max_wait_is_ok() {
+        let mut headers = Headers::new();
+        headers.set("x-ratelimit-remaining".to_string(), "10".to_string());
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Client::new(cache::NoCache, WaitConfigMock, false);
+        assert!(client.handle_rate_limit(&response).is_ok());
+        assert_eq!(
+            10,
+            client.ratelimit.lock().unwrap().as_ref().unwrap().remaining
+        );
+    }
+
+    #[test]
+    fn test_related_paths_compare_whole_segments() {
+        let merge_request =
+            resource_path("https://gitlab.com/api/v4/projects/1/merge_requests/2?state=opened");
+        for related in [
+            "https://gitlab.com/api/v4/projects/1/merge_requests/2",
+            "https://gitlab.com/api/v4/projects/1/merge_requests?state=opened&page=1",
+            "https://gitlab.com/api/v4/projects/1/merge_requests/2/notes",
+        ] {
+            assert!(
+                related_paths(merge_request, resource_path(related)),
+                "{}",
+                related
+            );
+        }
+        for unrelated in [
+            "https://gitlab.com/api/v4/projects/1/merge_requests/21",
+            "https://gitlab.com/api/v4/projects/12",
+            "https://gitlab.com/api/v4/projects/1/issues",
+        ] {
+            assert!(
+                !related_paths(merge_request, resource_path(unrelated)),
+                "{}",
+                unrelated
+            );
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct RateLimitStoreMock {
+        ratelimit: Arc<Mutex<Option<RateLimitHeader>>>,
+    }
+
+    impl RateLimitStore for RateLimitStoreMock {
+        fn load(&self) -> Option<RateLimitHeader> {
+            self.ratelimit.lock().unwrap().clone()
+        }
+        fn save(&self, ratelimit: &RateLimitHeader) -> Result<()> {
+            *self.ratelimit.lock().unwrap() = Some(ratelimit.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_left_by_previous_invocation_is_honored() {
+        let store = RateLimitStoreMock::default();
+        let reset = now_epoch_seconds() + Seconds::new(600);
+        store
+            .save(&RateLimitHeader::new(10, reset, Seconds::new(0)))
+            .unwrap();
+        let client =
+            Client::new(cache::NoCache, ConfigMock::new(1), false).with_ratelimit_store(store);
+        assert!(client.wait_for_rate_limit().is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_reported_is_stored() {
+        let store = RateLimitStoreMock::default();
+        let client = Client::new(cache::NoCache, ConfigMock::new(1), false)
+            .with_ratelimit_store(store.clone());
+        let mut headers = Headers::new();
+        headers.set("x-ratelimit-remaining".to_string(), "10".to_string());
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        // Stored even when failing, so the next invocation does not go on.
+        assert!(client.handle_rate_limit(&response).is_err());
+        assert_eq!(10, store.load().unwrap().remaining);
+    }
+
     fn epoch_seconds_now_mock(secs: u64) -> Seconds {
         Seconds::new(secs)
     }
@@ -734,4 +1385,111 @@ mod test {
         let responses = paginator.collect::<Vec<Result<Response>>>();
         assert_eq!(5, responses.len());
     }
+    fn header_processor_next_and_last_page(_header: &str) -> PageHeader {
+        let mut page_header = PageHeader::new();
+        page_header.set_next_page(Page::new("http://localhost?page=2&per_page=30", 2));
+        page_header.set_last_page(Page::new("http://localhost?page=4&per_page=30", 4));
+        page_header
+    }
+
+    fn response_with_next_and_last_page() -> Response {
+        let mut headers = Headers::new();
+        headers.set("link".to_string(), "http://localhost?page=2".to_string());
+        Response::builder()
+            .status(200)
+            .headers(headers)
+            .link_header_processor(header_processor_next_and_last_page)
+            .build()
+            .unwrap()
+    }
+
+    fn response_with_body(body: &str) -> Response {
+        Response::builder()
+            .status(200)
+            .body(body.to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_page_url_replaces_page_number() {
+        assert_eq!(
+            Some("http://localhost?page=3&per_page=30".to_string()),
+            page_url("http://localhost?page=2&per_page=30", 3)
+        );
+        assert_eq!(
+            Some("http://localhost?state=opened&page=10".to_string()),
+            page_url("http://localhost?state=opened&page=2", 10)
+        );
+        assert_eq!(None, page_url("http://localhost?after=Y3Vyc29y", 3));
+    }
+
+    #[test]
+    fn test_concurrent_paginator_requests_up_to_last_page_in_order() {
+        let client = Arc::new(MockRunner::new(vec![
+            response_with_body("page 4"),
+            response_with_body("page 3"),
+            response_with_body("page 2"),
+            response_with_next_and_last_page(),
+        ]));
+        let request: Request<()> = Request::new("http://localhost", Method::GET);
+        let paginator = Paginator::new(&client, request, "http://localhost", None, 0, 60)
+            .with_concurrent_pages();
+        let responses = paginator
+            .map(|response| response.unwrap().body)
+            .collect::<Vec<String>>();
+        assert_eq!(vec!["", "page 2", "page 3", "page 4"], responses);
+        assert_eq!("http://localhost?page=4&per_page=30", *client.url());
+    }
+
+    #[test]
+    fn test_concurrent_paginator_plans_pages_out_of_total_pages() {
+        let mut headers = Headers::new();
+        headers.set("link".to_string(), "http://localhost?page=2".to_string());
+        headers.set("x-total-pages".to_string(), "3".to_string());
+        let first_page = Response::builder()
+            .status(200)
+            .headers(headers)
+            .link_header_processor(|_header| {
+                let mut page_header = PageHeader::new();
+                page_header.set_next_page(Page::new("http://localhost?page=2", 2));
+                page_header
+            })
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![
+            response_with_body("page 3"),
+            response_with_body("page 2"),
+            first_page,
+        ]));
+        let request: Request<()> = Request::new("http://localhost", Method::GET);
+        let paginator = Paginator::new(&client, request, "http://localhost", None, 0, 60)
+            .with_concurrent_pages();
+        let responses = paginator
+            .map(|response| response.unwrap().body)
+            .collect::<Vec<String>>();
+        assert_eq!(vec!["", "page 2", "page 3"], responses);
+        assert_eq!("http://localhost?page=3", *client.url());
+    }
+
+    #[test]
+    fn test_concurrent_paginator_honors_max_pages() {
+        let client = Arc::new(MockRunner::new(vec![
+            response_with_body("page 4"),
+            response_with_body("page 3"),
+            response_with_body("page 2"),
+            response_with_next_and_last_page(),
+        ]));
+        let request: Request<()> = Request::builder()
+            .method(Method::GET)
+            .resource(Resource::new("http://localhost", None))
+            .max_pages(2)
+            .build()
+            .unwrap();
+        let paginator = Paginator::new(&client, request, "http://localhost", None, 0, 60)
+            .with_concurrent_pages();
+        let responses = paginator.collect::<Vec<Result<Response>>>();
+        assert_eq!(2, responses.len());
+        assert_eq!("http://localhost?page=2&per_page=30", *client.url());
+    }
 }
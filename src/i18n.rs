@@ -0,0 +1,452 @@
+//! Catalog of the messages shown to the user, so prompts, summaries and
+//! command results can be localized. The language is taken from `GITAR_LANG`,
+//! falling back to `LANG`, ex. `GITAR_LANG=es`. English is used when the
+//! language is not supported.
+
+use std::fmt::Display;
+
+use crate::config;
+
+lazy_static! {
+    static ref LANGUAGE: Language = Language::from_env();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+impl Language {
+    fn from_env() -> Self {
+        // Tests assert on the English messages regardless of the locale of the
+        // machine running them.
+        if cfg!(test) {
+            return Language::English;
+        }
+        let locale = config::global_env_var("lang")
+            .or_else(|| std::env::var("LANG").ok())
+            .unwrap_or_default();
+        Language::from_locale(&locale)
+    }
+
+    /// Language of a locale such as `es_ES.UTF-8`.
+    pub fn from_locale(locale: &str) -> Self {
+        match locale.get(..2) {
+            Some("es") => Language::Spanish,
+            _ => Language::English,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Message {
+    Title,
+    Description,
+    EditDescription,
+    SelectAssignees,
+    SelectReviewers,
+    DoneSelecting,
+    PickMergeRequest,
+    Assignees,
+    Reviewers,
+    TargetBranch,
+    None,
+    OutgoingChangesSummary,
+    ConfirmSummary,
+    UserCancelled,
+    UpdateBranch,
+    SkipBranchUpdate,
+    TakingOff,
+    MergeRequestOpened,
+    MergeRequestClosed,
+    MergeRequestReopened,
+    MergeRequestMarkedDraft,
+    MergeRequestMarkedReady,
+    MergeRequestRebased,
+    NewPipeline,
+    NoNewPipeline,
+    MergeRequestMerged,
+    MergeRequestApproved,
+    IssueOpened,
+    IssueClosed,
+    NoResourcesFound,
+    SetUpDomain,
+    CreateApiToken,
+    ApiToken,
+    PreferredAssignee,
+    ConfigSaved,
+    VariableValue,
+    ReviewChecklist,
+    WaitingForPipeline,
+    PipelineStatus,
+    NoFlakyJobs,
+    SkippingErasedArtifacts,
+    NoArtifactsToPrune,
+    PruneCancelled,
+    SharedRunnersEnabled,
+    SharedRunnersDisabled,
+    CannotReadCiFile,
+    CiConfigValid,
+    CiConfigInvalid,
+    AlreadyScheduledCron,
+    ScheduledCron,
+    AlreadyScheduled,
+    Created,
+    ContractStored,
+    NoOperationsRecorded,
+    NoRulesetsFound,
+    NoProtectedBranchesFound,
+    NoVariablesFound,
+    VariableSet,
+    VariableDeleted,
+    TopicsNotSet,
+    LabelNotCreated,
+    CloneUrl,
+    NoProjectsFoundIn,
+    AccessRequestApproved,
+    AccessRequestDenied,
+    SkippingIrreversible,
+    Undone,
+    DeploymentApproved,
+    NoMergeRequestsToMigrate,
+    NothingHere,
+    Uploading,
+    ReleaseCreated,
+    DescriptionTruncated,
+    MergeRequirementsNotMet,
+    CannotMergeYet,
+    SkippingSwept,
+    NoMergeRequestsLabeled,
+    SweepCancelled,
+    SweepRateLimited,
+    MergeRequestSwept,
+    NoComments,
+    NoChecks,
+    NoChangesSinceReview,
+    NoMergeRequestsQueued,
+    AddedToMergeQueue,
+    RemovedFromMergeQueue,
+    NoLabels,
+    MergeRequestLabels,
+    Total,
+    NumPagesNotAvailable,
+    BroadcastWarning,
+    UserBlocked,
+}
+
+impl Message {
+    /// Template of the message. Each `{}` is replaced in order by the
+    /// arguments given to [`format`].
+    pub fn template(self, language: Language) -> &'static str {
+        match language {
+            Language::English => self.english(),
+            Language::Spanish => self.spanish(),
+        }
+    }
+
+    fn english(self) -> &'static str {
+        match self {
+            Message::Title => "Title",
+            Message::Description => "Description",
+            Message::EditDescription => "Edit description",
+            Message::SelectAssignees => "Assignees (type to search, enter to pick):",
+            Message::SelectReviewers => "Reviewers (type to search, enter to pick):",
+            Message::DoneSelecting => "Done ({} selected)",
+            Message::PickMergeRequest => "Merge request (type to search, enter to pick):",
+            Message::Assignees => "Assignees",
+            Message::Reviewers => "Reviewers",
+            Message::TargetBranch => "Target branch",
+            Message::None => "None",
+            Message::OutgoingChangesSummary => "Summary of outgoing changes:",
+            Message::ConfirmSummary => "Confirm summary",
+            Message::UserCancelled => "User cancelled",
+            Message::UpdateBranch => "Update branch with {} new commits from origin/{} ({})?",
+            Message::SkipBranchUpdate => {
+                "Skipping branch update. The merge request may have conflicts."
+            }
+            Message::TakingOff => "Taking off...",
+            Message::MergeRequestOpened => "Merge request opened: {}",
+            Message::MergeRequestClosed => "Merge request closed: {}",
+            Message::MergeRequestReopened => "Merge request reopened: {}",
+            Message::MergeRequestMarkedDraft => "Merge request marked as draft: {}",
+            Message::MergeRequestMarkedReady => "Merge request ready for review: {}",
+            Message::MergeRequestRebased => "Merge request {} rebased, new head {}",
+            Message::NewPipeline => "Pipeline: {}",
+            Message::NoNewPipeline => "No pipeline has been created for the new head yet",
+            Message::MergeRequestMerged => "Merge request merged: {}",
+            Message::MergeRequestApproved => "Merge request approved: {}",
+            Message::IssueOpened => "Issue opened: {}",
+            Message::IssueClosed => "Issue closed: {}",
+            Message::NoResourcesFound => "No resources found.",
+            Message::SetUpDomain => "No configuration found for {}. Set it up now?",
+            Message::CreateApiToken => "Create an API token with read/write access at {}",
+            Message::ApiToken => "API token",
+            Message::PreferredAssignee => "Preferred assignee username (optional)",
+            Message::ConfigSaved => "Configuration saved to {}",
+            Message::VariableValue => "Value of {}",
+            Message::ReviewChecklist => "Review checklist:",
+            Message::WaitingForPipeline => "Waiting for a pipeline of commit {}",
+            Message::PipelineStatus => "Pipeline {} is {}",
+            Message::NoFlakyJobs => "No flaky jobs found in the last {} pipelines",
+            Message::SkippingErasedArtifacts => "Skipping {} artifacts erased in a previous run",
+            Message::NoArtifactsToPrune => "No artifacts found to prune.",
+            Message::PruneCancelled => {
+                "Cancelled after erasing {} of {} artifacts. Run again with --resume to continue"
+            }
+            Message::SharedRunnersEnabled => "Shared runners enabled for the project",
+            Message::SharedRunnersDisabled => "Shared runners disabled for the project",
+            Message::CannotReadCiFile => "Cannot read CI configuration file {}",
+            Message::CiConfigValid => "{}: configuration is valid",
+            Message::CiConfigInvalid => "{} of {} CI configuration files are invalid",
+            Message::AlreadyScheduledCron => "Already scheduled with cron: {}",
+            Message::ScheduledCron => "Scheduled with cron: {}",
+            Message::AlreadyScheduled => "Already scheduled: {}",
+            Message::Created => "Created {}",
+            Message::ContractStored => "Contract stored in {}",
+            Message::NoOperationsRecorded => "No operations recorded",
+            Message::NoRulesetsFound => "No rulesets found",
+            Message::NoProtectedBranchesFound => "No protected branches found",
+            Message::NoVariablesFound => "No variables found",
+            Message::VariableSet => "Variable {} set",
+            Message::VariableDeleted => "Variable {} deleted",
+            Message::TopicsNotSet => "Topics could not be set: {}",
+            Message::LabelNotCreated => "Label {} could not be created: {}",
+            Message::CloneUrl => "Clone URL: {}",
+            Message::NoProjectsFoundIn => "No projects found in {}",
+            Message::AccessRequestApproved => "Access request from user {} approved as {}",
+            Message::AccessRequestDenied => "Access request from user {} denied",
+            Message::SkippingIrreversible => "Skipping {} {} done at {}: it cannot be undone",
+            Message::Undone => "Undone: {}",
+            Message::DeploymentApproved => "Deployment {} approved",
+            Message::NoMergeRequestsToMigrate => "No open merge requests found to migrate.",
+            Message::NothingHere => "Nothing here.",
+            Message::Uploading => "Uploading {}: {}% ({} of {})",
+            Message::ReleaseCreated => "Release created: {}",
+            Message::DescriptionTruncated => {
+                "Description truncated to {} characters, the most the remote accepts"
+            }
+            Message::MergeRequirementsNotMet => {
+                "Merge request {} does not meet the merge requirements:"
+            }
+            Message::CannotMergeYet => "Merge request {} cannot be merged yet",
+            Message::SkippingSwept => "Skipping {} merge requests swept in a previous run",
+            Message::NoMergeRequestsLabeled => "No open merge requests labeled {}",
+            Message::SweepCancelled => {
+                "Cancelled after sweeping {} of {} merge requests. Run again with --resume to continue"
+            }
+            Message::SweepRateLimited => {
+                "Rate limit reached after sweeping {} of {} merge requests. Run again with --resume once it resets"
+            }
+            Message::MergeRequestSwept => "Swept merge request {}: {}",
+            Message::NoComments => "No comments on merge request {}",
+            Message::NoChecks => "No checks reported for merge request {}",
+            Message::NoChangesSinceReview => {
+                "No changes in merge request {} since your last review at {}"
+            }
+            Message::NoMergeRequestsQueued => "No merge requests queued for {}",
+            Message::AddedToMergeQueue => "Merge request {} added to the merge queue",
+            Message::RemovedFromMergeQueue => "Merge request {} removed from the merge queue",
+            Message::NoLabels => "Merge request {} has no labels",
+            Message::MergeRequestLabels => "Labels of merge request {}: {}",
+            Message::Total => "Total: {}",
+            Message::NumPagesNotAvailable => "Number of pages not available.",
+            Message::BroadcastWarning => "Warning: {}: {}",
+            Message::UserBlocked => "User {} blocked",
+        }
+    }
+
+    fn spanish(self) -> &'static str {
+        match self {
+            Message::Title => "Título",
+            Message::Description => "Descripción",
+            Message::EditDescription => "Editar descripción",
+            Message::SelectAssignees => "Asignados (escribe para buscar, intro para elegir):",
+            Message::SelectReviewers => "Revisores (escribe para buscar, intro para elegir):",
+            Message::DoneSelecting => "Hecho ({} seleccionados)",
+            Message::PickMergeRequest => "Merge request (escribe para buscar, intro para elegir):",
+            Message::Assignees => "Asignados",
+            Message::Reviewers => "Revisores",
+            Message::TargetBranch => "Rama de destino",
+            Message::None => "Ninguno",
+            Message::OutgoingChangesSummary => "Resumen de los cambios a enviar:",
+            Message::ConfirmSummary => "Confirmar resumen",
+            Message::UserCancelled => "Cancelado por el usuario",
+            Message::UpdateBranch => "¿Actualizar la rama con {} commits nuevos de origin/{} ({})?",
+            Message::SkipBranchUpdate => {
+                "Se omite la actualización de la rama. La merge request puede tener conflictos."
+            }
+            Message::TakingOff => "Despegando...",
+            Message::MergeRequestOpened => "Merge request abierta: {}",
+            Message::MergeRequestClosed => "Merge request cerrada: {}",
+            Message::MergeRequestReopened => "Merge request reabierta: {}",
+            Message::MergeRequestMarkedDraft => "Merge request marcada como borrador: {}",
+            Message::MergeRequestMarkedReady => "Merge request lista para revisión: {}",
+            Message::MergeRequestRebased => "Merge request {} rebasada, nuevo head {}",
+            Message::NewPipeline => "Pipeline: {}",
+            Message::NoNewPipeline => "Todavía no se ha creado ningún pipeline para el nuevo head",
+            Message::MergeRequestMerged => "Merge request fusionada: {}",
+            Message::MergeRequestApproved => "Merge request aprobada: {}",
+            Message::IssueOpened => "Issue abierta: {}",
+            Message::IssueClosed => "Issue cerrada: {}",
+            Message::NoResourcesFound => "No se encontraron recursos.",
+            Message::SetUpDomain => "No hay configuración para {}. ¿Configurarlo ahora?",
+            Message::CreateApiToken => {
+                "Crea un token de API con acceso de lectura y escritura en {}"
+            }
+            Message::ApiToken => "Token de API",
+            Message::PreferredAssignee => "Usuario asignado por defecto (opcional)",
+            Message::ConfigSaved => "Configuración guardada en {}",
+            Message::VariableValue => "Valor de {}",
+            Message::ReviewChecklist => "Lista de comprobación de la revisión:",
+            Message::WaitingForPipeline => "Esperando un pipeline del commit {}",
+            Message::PipelineStatus => "El pipeline {} está {}",
+            Message::NoFlakyJobs => "No se encontraron jobs inestables en los últimos {} pipelines",
+            Message::SkippingErasedArtifacts => {
+                "Se omiten {} artefactos borrados en una ejecución anterior"
+            }
+            Message::NoArtifactsToPrune => "No se encontraron artefactos que borrar.",
+            Message::PruneCancelled => {
+                "Cancelado tras borrar {} de {} artefactos. Vuelve a ejecutarlo con --resume para continuar"
+            }
+            Message::SharedRunnersEnabled => "Runners compartidos activados en el proyecto",
+            Message::SharedRunnersDisabled => "Runners compartidos desactivados en el proyecto",
+            Message::CannotReadCiFile => "No se puede leer el fichero de configuración de CI {}",
+            Message::CiConfigValid => "{}: la configuración es válida",
+            Message::CiConfigInvalid => "{} de {} ficheros de configuración de CI no son válidos",
+            Message::AlreadyScheduledCron => "Ya programado con cron: {}",
+            Message::ScheduledCron => "Programado con cron: {}",
+            Message::AlreadyScheduled => "Ya programado: {}",
+            Message::Created => "Creado {}",
+            Message::ContractStored => "Contrato guardado en {}",
+            Message::NoOperationsRecorded => "No hay operaciones registradas",
+            Message::NoRulesetsFound => "No se encontraron rulesets",
+            Message::NoProtectedBranchesFound => "No se encontraron ramas protegidas",
+            Message::NoVariablesFound => "No se encontraron variables",
+            Message::VariableSet => "Variable {} guardada",
+            Message::VariableDeleted => "Variable {} borrada",
+            Message::TopicsNotSet => "No se pudieron poner los temas: {}",
+            Message::LabelNotCreated => "No se pudo crear la etiqueta {}: {}",
+            Message::CloneUrl => "URL para clonar: {}",
+            Message::NoProjectsFoundIn => "No se encontraron proyectos en {}",
+            Message::AccessRequestApproved => "Solicitud de acceso del usuario {} aprobada como {}",
+            Message::AccessRequestDenied => "Solicitud de acceso del usuario {} denegada",
+            Message::SkippingIrreversible => "Se omite {} {} hecho el {}: no se puede deshacer",
+            Message::Undone => "Deshecho: {}",
+            Message::DeploymentApproved => "Despliegue {} aprobado",
+            Message::NoMergeRequestsToMigrate => {
+                "No se encontraron merge requests abiertas que migrar."
+            }
+            Message::NothingHere => "Aquí no hay nada.",
+            Message::Uploading => "Subiendo {}: {}% ({} de {})",
+            Message::ReleaseCreated => "Release creada: {}",
+            Message::DescriptionTruncated => {
+                "Descripción recortada a {} caracteres, lo máximo que acepta el remoto"
+            }
+            Message::MergeRequirementsNotMet => {
+                "La merge request {} no cumple los requisitos para fusionarla:"
+            }
+            Message::CannotMergeYet => "La merge request {} todavía no se puede fusionar",
+            Message::SkippingSwept => {
+                "Se omiten {} merge requests barridas en una ejecución anterior"
+            }
+            Message::NoMergeRequestsLabeled => "No hay merge requests abiertas con la etiqueta {}",
+            Message::SweepCancelled => {
+                "Cancelado tras barrer {} de {} merge requests. Vuelve a ejecutarlo con --resume para continuar"
+            }
+            Message::SweepRateLimited => {
+                "Límite de peticiones alcanzado tras barrer {} de {} merge requests. Vuelve a ejecutarlo con --resume cuando se restablezca"
+            }
+            Message::MergeRequestSwept => "Merge request {} barrida: {}",
+            Message::NoComments => "No hay comentarios en la merge request {}",
+            Message::NoChecks => "No hay checks para la merge request {}",
+            Message::NoChangesSinceReview => {
+                "No hay cambios en la merge request {} desde tu última revisión en {}"
+            }
+            Message::NoMergeRequestsQueued => "No hay merge requests en cola para {}",
+            Message::AddedToMergeQueue => "Merge request {} añadida a la cola de fusión",
+            Message::RemovedFromMergeQueue => "Merge request {} quitada de la cola de fusión",
+            Message::NoLabels => "La merge request {} no tiene etiquetas",
+            Message::MergeRequestLabels => "Etiquetas de la merge request {}: {}",
+            Message::Total => "Total: {}",
+            Message::NumPagesNotAvailable => "Número de páginas no disponible.",
+            Message::BroadcastWarning => "Aviso: {}: {}",
+            Message::UserBlocked => "Usuario {} bloqueado",
+        }
+    }
+}
+
+/// Message in the user's language.
+pub fn text(message: Message) -> String {
+    message.template(*LANGUAGE).to_string()
+}
+
+/// Message in the user's language with its placeholders replaced by `args`.
+pub fn format(message: Message, args: &[&dyn Display]) -> String {
+    fill(message.template(*LANGUAGE), args)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut parts = template.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_language_from_locale() {
+        assert_eq!(Language::Spanish, Language::from_locale("es_ES.UTF-8"));
+        assert_eq!(Language::Spanish, Language::from_locale("es"));
+        assert_eq!(Language::English, Language::from_locale("en_US.UTF-8"));
+        assert_eq!(Language::English, Language::from_locale("C"));
+        assert_eq!(Language::English, Language::from_locale(""));
+    }
+
+    #[test]
+    fn test_fill_placeholders_in_order() {
+        let template = Message::UpdateBranch.template(Language::Spanish);
+        assert_eq!(
+            "¿Actualizar la rama con 3 commits nuevos de origin/main (rebase)?",
+            fill(template, &[&3, &"main", &"rebase"])
+        );
+    }
+
+    #[test]
+    fn test_command_results_are_translated() {
+        assert_eq!(
+            "Variable TOKEN guardada",
+            fill(
+                Message::VariableSet.template(Language::Spanish),
+                &[&"TOKEN"]
+            )
+        );
+        assert_eq!(
+            "2 de 3 ficheros de configuración de CI no son válidos",
+            fill(
+                Message::CiConfigInvalid.template(Language::Spanish),
+                &[&2, &3]
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_uses_english_in_tests() {
+        assert_eq!(
+            "Merge request opened: https://gitlab.com/a/b/-/merge_requests/1",
+            format(
+                Message::MergeRequestOpened,
+                &[&"https://gitlab.com/a/b/-/merge_requests/1"]
+            )
+        );
+    }
+}
@@ -1,17 +1,19 @@
-use std::fs::OpenOptions;
-use std::io::{ErrorKind, Write};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, IsTerminal, Write};
 use std::path::Path;
 
 use crate::cli::init::InitCommandOptions;
 use crate::error::{AddContext, GRError};
-use crate::Result;
+use crate::i18n::{self, Message};
+use crate::{cmds, dialog, gitea, Result};
 
 const CONFIG_TEMPLATE: &str = r#"
 # Fill in the <VALUE> below with your own values
 # and tweak accordingly.
 
 <DOMAIN>.api_token=<VALUE>
-<DOMAIN>.cache_location="~/.cache/gitar"
+# Cache directory. Defaults to $XDG_CACHE_HOME/gitar, ~/.cache/gitar if unset
+# <DOMAIN>.cache_location=~/.cache/gitar
 <DOMAIN>.preferred_assignee_username=<VALUE>
 <DOMAIN>.merge_request_description_signature=""
 
@@ -51,6 +53,12 @@ const CONFIG_TEMPLATE: &str = r#"
 "#;
 
 pub fn execute<P: AsRef<Path>>(options: InitCommandOptions, config_path: P) -> Result<()> {
+    if let Some(parent) = config_path.as_ref().parent() {
+        fs::create_dir_all(parent).err_context(format!(
+            "Unable to create config directory {}",
+            parent.display()
+        ))?;
+    }
     let file = OpenOptions::new()
         .write(true)
         .create_new(true)
@@ -78,6 +86,40 @@ pub fn execute<P: AsRef<Path>>(options: InitCommandOptions, config_path: P) -> R
     ))
 }
 
+/// Offers to configure `domain` when running a command in a repository whose
+/// remote has no configuration yet, so new users do not have to go through
+/// `gr init` first. Only on a terminal. Returns whether it was configured.
+pub fn first_run_setup(config_file: &Path, domain: &str) -> Result<bool> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Ok(false);
+    }
+    let Some(input) = dialog::prompt_first_run_setup(domain, &token_url(domain))? else {
+        return Ok(false);
+    };
+    let mut values = vec![("api_token", input.api_token.trim())];
+    let username = input.preferred_assignee_username.trim();
+    if !username.is_empty() {
+        values.push(("preferred_assignee_username", username));
+    }
+    cmds::config::set_values(config_file, Some(domain), &values)?;
+    println!(
+        "{}",
+        i18n::format(Message::ConfigSaved, &[&config_file.display()])
+    );
+    Ok(true)
+}
+
+/// Where to create an API token for the remote.
+fn token_url(domain: &str) -> String {
+    if domain.starts_with("github") {
+        format!("https://{}/settings/tokens", domain)
+    } else if gitea::is_gitea_domain(domain) {
+        format!("https://{}/user/settings/applications", domain)
+    } else {
+        format!("https://{}/-/user_settings/personal_access_tokens", domain)
+    }
+}
+
 fn generate_and_persist<W: Write>(options: InitCommandOptions, writer: &mut W) -> Result<()> {
     let data = change_placeholders(&options.domain);
     persist_config(data, writer)
@@ -99,6 +141,22 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_token_url() {
+        assert_eq!(
+            "https://github.com/settings/tokens",
+            token_url("github.com")
+        );
+        assert_eq!(
+            "https://gitlab.example.com/-/user_settings/personal_access_tokens",
+            token_url("gitlab.example.com")
+        );
+        assert_eq!(
+            "https://codeberg.org/user/settings/applications",
+            token_url("codeberg.org")
+        );
+    }
+
     #[test]
     fn test_persist_config() {
         let options = InitCommandOptions {
@@ -107,7 +165,7 @@ mod test {
         let mut writer = Vec::new();
         let result = generate_and_persist(options, &mut writer);
         assert!(result.is_ok());
-        assert!(writer.len() > 0);
+        assert!(!writer.is_empty());
         let content = String::from_utf8(writer).unwrap();
         assert!(content.contains("gitlab.com"));
     }
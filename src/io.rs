@@ -10,6 +10,7 @@ use serde::Serialize;
 use std::{
     ffi::OsStr,
     fmt::{self, Display, Formatter},
+    path::Path,
     thread,
 };
 
@@ -34,10 +35,38 @@ pub trait HttpRunner {
     fn run<T: Serialize>(&self, cmd: &mut Request<T>) -> Result<Self::Response>;
     /// Return the number of API MAX PAGES allowed for the given Request.
     fn api_max_pages<T: Serialize>(&self, cmd: &Request<T>) -> u32;
+    /// Configured number of results per page for list requests, if any.
+    fn api_per_page(&self) -> Option<u32> {
+        None
+    }
     /// Milliseconds to wait before executing the next request
     fn throttle(&self, milliseconds: Milliseconds) {
         thread::sleep(std::time::Duration::from_millis(*milliseconds));
     }
+    /// Runs the requests for several pages of a list and returns their
+    /// responses in the same order. `ratelimit` is the rate limit reported
+    /// along with the first page, if any. Runs them one after another unless
+    /// the implementor can send them concurrently.
+    fn run_pages<T: Serialize + Send>(
+        &self,
+        requests: Vec<Request<T>>,
+        _ratelimit: Option<RateLimitHeader>,
+    ) -> Vec<Result<Self::Response>> {
+        requests
+            .into_iter()
+            .map(|mut request| self.run(&mut request))
+            .collect()
+    }
+    /// Sends a file as the raw body of the request, ex. a release asset.
+    fn upload(&self, cmd: &mut Request<()>, upload: Upload) -> Result<Self::Response>;
+}
+
+/// A file sent as is in the body of a request.
+pub struct Upload<'a> {
+    pub path: &'a Path,
+    pub content_type: &'a str,
+    /// Called with the bytes sent so far and the size of the file.
+    pub progress: &'a dyn Fn(u64, u64),
 }
 
 #[derive(Clone, Debug)]
@@ -49,7 +78,7 @@ pub enum CmdInfo {
     CommitMessage(String),
     Project(Project),
     Members(Vec<Member>),
-    MergeRequest(MergeRequestResponse),
+    MergeRequest(Box<MergeRequestResponse>),
     MergeRequestsList(Vec<MergeRequestResponse>),
     OutgoingCommits(String),
     Ignore,
@@ -91,6 +120,21 @@ impl Response {
             .map(|s| s.as_str())
     }
 
+    /// Resources in the whole list, reported by Gitlab in `x-total` and by
+    /// Gitea in `x-total-count`. Gitlab leaves it out of lists over 10,000
+    /// resources.
+    pub fn total(&self) -> Option<u32> {
+        self.header(TOTAL_HEADER)
+            .or_else(|| self.header(TOTAL_COUNT_HEADER))
+            .and_then(|total| total.parse().ok())
+    }
+
+    /// Pages in the whole list, Gitlab only.
+    pub fn total_pages(&self) -> Option<u32> {
+        self.header(TOTAL_PAGES_HEADER)
+            .and_then(|pages| pages.parse().ok())
+    }
+
     pub fn get_page_headers(&self) -> Option<PageHeader> {
         if let Some(headers) = &self.headers {
             match headers.get(LINK_HEADER) {
@@ -138,6 +182,20 @@ impl Response {
         None
     }
 
+    /// Message explaining why the remote is unavailable when it answers with
+    /// a 503. Gitlab in maintenance mode returns a JSON body with the
+    /// message set by the administrators.
+    pub fn maintenance_message(&self) -> Option<String> {
+        if self.status != 503 {
+            return None;
+        }
+        let message = serde_json::from_str::<serde_json::Value>(&self.body)
+            .ok()
+            .and_then(|body| body["message"].as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| "service unavailable".to_string());
+        Some(message)
+    }
+
     pub fn get_etag(&self) -> Option<&str> {
         self.header("etag")
     }
@@ -149,7 +207,9 @@ impl Response {
             http::Method::POST => {
                 self.status >= 200 && self.status < 300 || self.status == 409 || self.status == 422
             }
-            http::Method::PATCH | http::Method::PUT => self.status >= 200 && self.status < 300,
+            http::Method::PATCH | http::Method::PUT | http::Method::DELETE => {
+                self.status >= 200 && self.status < 300
+            }
         }
     }
 }
@@ -157,42 +217,49 @@ impl Response {
 const NEXT: &str = "next";
 const LAST: &str = "last";
 pub const LINK_HEADER: &str = "link";
+// Docs: https://docs.gitlab.com/ee/api/rest/#pagination-link-header
+pub const TOTAL_HEADER: &str = "x-total";
+pub const TOTAL_PAGES_HEADER: &str = "x-total-pages";
+// Gitea
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
 
 pub fn parse_link_headers(link: &str) -> PageHeader {
     lazy_static! {
         static ref RE_URL: Regex = Regex::new(r#"<([^>]+)>;\s*rel="([^"]+)""#).unwrap();
-        static ref RE_PAGE_NUMBER: Regex = Regex::new(r"[^(per_)]page=(\d+)").unwrap();
     }
     let mut page_header = PageHeader::new();
     for cap in RE_URL.captures_iter(link) {
-        if cap.len() > 2 && &cap[2] == NEXT {
-            let url = cap[1].to_string();
-            for page_cap in RE_PAGE_NUMBER.captures_iter(&url) {
-                if page_cap.len() == 2 {
-                    let page_number = page_cap[1].to_string();
-                    let page_number: u32 = page_number.parse().unwrap_or(0);
-                    let page = Page::new(&url, page_number);
-                    page_header.set_next_page(page);
-                    continue;
-                }
-            }
+        if cap.len() < 3 {
+            continue;
         }
-        // TODO pull code out - return a page and its type next or last.
-        if cap.len() > 2 && &cap[2] == LAST {
-            let url = cap[1].to_string();
-            for page_cap in RE_PAGE_NUMBER.captures_iter(&url) {
-                if page_cap.len() == 2 {
-                    let page_number = page_cap[1].to_string();
-                    let page_number: u32 = page_number.parse().unwrap_or(0);
-                    let page = Page::new(&url, page_number);
-                    page_header.set_last_page(page);
-                }
-            }
+        match &cap[2] {
+            NEXT => page_header.set_next_page(parse_page(&cap[1])),
+            LAST => page_header.set_last_page(parse_page(&cap[1])),
+            _ => {}
         }
     }
     page_header
 }
 
+/// Builds a page out of a link URL. Numbered pages carry `page=N`. Some Github
+/// endpoints paginate with an opaque cursor instead (`after=`, `before=` or
+/// `cursor=`), as does Gitlab keyset pagination (`id_after=`, `id_before=` or
+/// `cursor=`). In that case the page number is 0 and the URL is followed as is.
+fn parse_page(url: &str) -> Page {
+    lazy_static! {
+        static ref RE_PAGE_NUMBER: Regex = Regex::new(r"[^(per_)]page=(\d+)").unwrap();
+        static ref RE_CURSOR: Regex =
+            Regex::new(r"[?&](?:id_after|id_before|after|before|cursor)=([^&]+)").unwrap();
+    }
+    let number = RE_PAGE_NUMBER
+        .captures(url)
+        .and_then(|cap| cap[1].parse().ok())
+        .unwrap_or(0);
+    let mut page = Page::new(url, number);
+    page.cursor = RE_CURSOR.captures(url).map(|cap| cap[1].to_string());
+    page
+}
+
 #[derive(Default)]
 pub struct PageHeader {
     pub next: Option<Page>,
@@ -212,10 +279,12 @@ impl PageHeader {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Page {
     pub url: String,
     pub number: u32,
+    /// Opaque cursor for endpoints that do not paginate by page number.
+    pub cursor: Option<String>,
 }
 
 impl Page {
@@ -223,10 +292,25 @@ impl Page {
         Page {
             url: url.to_string(),
             number,
+            cursor: None,
         }
     }
 }
 
+/// Size of a list, as queried with `--num-pages`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageCount {
+    pub pages: u32,
+    /// Resources in the whole list, if the remote tells.
+    pub total: Option<u32>,
+}
+
+impl From<u32> for PageCount {
+    fn from(pages: u32) -> Self {
+        PageCount { pages, total: None }
+    }
+}
+
 // https://docs.github.com/en/rest/using-the-rest-api/rate-limits-for-the-rest-api?apiVersion=2022-11-28#exceeding-the-rate-limit
 
 pub const GITHUB_RATELIMIT_REMAINING: &str = "x-ratelimit-remaining";
@@ -369,6 +453,81 @@ mod test {
         assert_eq!(2, page_headers.next.unwrap().number);
     }
 
+    #[test]
+    fn test_link_header_follows_cursor_next_page() {
+        let link = r#"<https://api.github.com/repos/org/repo/rulesets/rule-suites?per_page=30&after=Y3Vyc29yOjEw>; rel="next""#;
+        let page_headers = parse_link_headers(link);
+        let next = page_headers.next.unwrap();
+        assert_eq!(
+            "https://api.github.com/repos/org/repo/rulesets/rule-suites?per_page=30&after=Y3Vyc29yOjEw",
+            next.url
+        );
+        assert_eq!(0, next.number);
+        assert_eq!(Some("Y3Vyc29yOjEw".to_string()), next.cursor);
+        assert_eq!(None, page_headers.last);
+    }
+
+    #[test]
+    fn test_link_header_follows_gitlab_keyset_next_page() {
+        let link = r#"<https://gitlab.com/api/v4/projects/1/jobs?order_by=id&pagination=keyset&per_page=20&id_before=6327855213>; rel="next""#;
+        let page_headers = parse_link_headers(link);
+        let next = page_headers.next.unwrap();
+        assert_eq!(0, next.number);
+        assert_eq!(Some("6327855213".to_string()), next.cursor);
+    }
+
+    #[test]
+    fn test_link_header_numbered_page_has_no_cursor() {
+        let link = r#"<https://api.github.com/repos/org/repo/pulls?page=2>; rel="next""#;
+        let page_headers = parse_link_headers(link);
+        assert_eq!(None, page_headers.next.unwrap().cursor);
+    }
+
+    #[test]
+    fn test_total_headers() {
+        let mut headers = Headers::new();
+        headers.set("x-total".to_string(), "1234".to_string());
+        headers.set("x-total-pages".to_string(), "13".to_string());
+        let response = Response::builder().headers(headers).build().unwrap();
+        assert_eq!(Some(1234), response.total());
+        assert_eq!(Some(13), response.total_pages());
+        let mut headers = Headers::new();
+        headers.set("x-total-count".to_string(), "42".to_string());
+        let response = Response::builder().headers(headers).build().unwrap();
+        assert_eq!(Some(42), response.total());
+        assert_eq!(None, response.total_pages());
+        let response = Response::builder().build().unwrap();
+        assert_eq!(None, response.total());
+    }
+
+    #[test]
+    fn test_maintenance_message_from_gitlab_body() {
+        let response = Response::builder()
+            .status(503)
+            .body(r#"{"message":"GitLab Maintenance: upgrade in progress"}"#.to_string())
+            .build()
+            .unwrap();
+        assert_eq!(
+            Some("GitLab Maintenance: upgrade in progress".to_string()),
+            response.maintenance_message()
+        );
+    }
+
+    #[test]
+    fn test_maintenance_message_non_json_body() {
+        let response = Response::builder()
+            .status(503)
+            .body("<html>Service Unavailable</html>".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(
+            Some("service unavailable".to_string()),
+            response.maintenance_message()
+        );
+        let response = Response::builder().status(500).build().unwrap();
+        assert_eq!(None, response.maintenance_message());
+    }
+
     #[test]
     fn test_response_ok_status_get_request_200() {
         assert!(Response::builder()
@@ -396,6 +555,15 @@ mod test {
             .is_ok(&http::Method::POST));
     }
 
+    #[test]
+    fn test_response_ok_status_delete_request_204() {
+        assert!(Response::builder()
+            .status(204)
+            .build()
+            .unwrap()
+            .is_ok(&http::Method::DELETE));
+    }
+
     #[test]
     fn test_response_ok_if_post_request_409_422() {
         // special case handled by the caller (merge_request)
@@ -413,6 +581,7 @@ mod test {
             http::Method::POST,
             http::Method::PATCH,
             http::Method::PUT,
+            http::Method::DELETE,
         ];
         let not_ok_status = 500..=599;
         for status in not_ok_status {
@@ -1,19 +1,33 @@
+// Test doubles such as MockRunner record requests in RefCells and are shared
+// through Arcs in unit tests.
+#![cfg_attr(test, allow(clippy::arc_with_non_send_sync))]
+
 pub mod api_defaults;
 pub mod api_traits;
+pub mod audit;
 pub mod cache;
+pub mod cancel;
+pub mod checkpoint;
 pub mod cli;
 pub mod config;
 pub mod dialog;
 pub mod error;
 pub mod exec;
 pub mod git;
+pub mod gitea;
 pub mod github;
 pub mod gitlab;
 pub mod http;
+pub mod i18n;
 pub mod init;
 pub mod io;
+pub mod metrics;
+pub mod paths;
+pub mod ratelimit;
+pub mod recent;
 pub mod remote;
 pub mod shell;
+pub mod stats;
 pub mod test;
 pub mod time;
 pub type Result<T> = anyhow::Result<T>;
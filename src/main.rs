@@ -1,55 +1,159 @@
-use std::{fs::File, path::Path, sync::Arc};
+use std::{fs, io::ErrorKind, path::PathBuf, sync::Arc, time::Instant};
 
 use env_logger::Env;
 use gr::{
-    cli::{parse_cli, CliOptions},
+    cancel,
+    cli::{my::MyOptions, parse_cli, project::ProjectOptions, CliOptions},
     cmds::{self, browse, cicd, docker, merge_request, project},
-    error, git, init,
+    config::ConfigProperties,
+    display, error, git, init,
     io::CmdInfo,
+    metrics::{self, CommandRun, MetricsConfig},
+    paths::Paths,
     shell::Shell,
-    Result,
+    stats, Result,
 };
 
-const CONFIG_PATH: &str = ".config/gitar/api";
-
 fn main() -> Result<()> {
-    let home_dir = std::env::var("HOME").unwrap();
-    let config_file = Path::new(&home_dir).join(CONFIG_PATH);
+    let paths = Paths::from_env();
+    if let Some(legacy_config_file) = paths.migrate_config()? {
+        eprintln!(
+            "Moved config file from {} to {}",
+            legacy_config_file.display(),
+            paths.config_file.display()
+        );
+    }
+    let config_file = paths.config_file;
+    let started = Instant::now();
     let option_args = parse_cli();
     let cli_options = option_args.cli_options.unwrap_or_else(|| {
         eprintln!("Please specify a subcommand");
         std::process::exit(1);
     });
     let cli_args = option_args.cli_args;
+    display::set_ascii_output(cli_args.ascii);
     if cli_args.verbose {
         let env = Env::default().default_filter_or("info");
         env_logger::init_from_env(env);
     }
-    if let CliOptions::Init(options) = cli_options {
-        init::execute(options, config_file)
-    } else {
-        let f = File::open(config_file).expect("Unable to open file");
-        let CmdInfo::RemoteUrl { domain, path } = git::remote_url(&Shell)? else {
-            return Err(error::gen("No remote url found. Please set a remote url."));
+    cancel::install_handler()?;
+    // Read once the command is done, as it can change the config file, ex.
+    // gr init.
+    let metrics_config_file = config_file.clone();
+    let result = match cli_options {
+        CliOptions::Init(options) => init::execute(options, config_file),
+        CliOptions::Version => cmds::version::execute(cli_args.verbose, &config_file),
+        // Source and target remotes are given explicitly. No local git
+        // repository is required.
+        CliOptions::Migrate(options) => cmds::migrate::execute(options, config_file),
+        CliOptions::My(MyOptions::All(cli_args)) => cmds::my::execute_all(cli_args, config_file),
+        CliOptions::My(MyOptions::Dashboard(cli_args)) => {
+            cmds::my::dashboard::execute(cli_args, config_file)
+        }
+        CliOptions::Project(ProjectOptions::New(cli_args)) => {
+            cmds::project::execute_new(cli_args, config_file)
+        }
+        CliOptions::Project(ProjectOptions::Cat(cli_args)) if cli_args.repo.is_some() => {
+            cmds::project::execute_cat(cli_args, config_file)
+        }
+        CliOptions::Status(cli_args) => cmds::status::execute(cli_args, config_file),
+        CliOptions::Auth(options) => cmds::auth::execute(options, config_file),
+        CliOptions::Contracts(options) => cmds::contracts::execute(options, config_file),
+        CliOptions::Config(options) => cmds::config::execute(options, config_file),
+        CliOptions::Cron(cli_args) => cmds::cron::execute(cli_args, config_file),
+        #[cfg(feature = "admin")]
+        CliOptions::Admin(options) => cmds::admin::execute(options, config_file),
+        cli_options => execute(cli_options, config_file),
+    };
+    let api_stats = stats::global();
+    if cli_args.api_stats || (cli_args.verbose && !api_stats.operations().is_empty()) {
+        api_stats.report(std::io::stderr())?;
+    }
+    let config_data = fs::read_to_string(&metrics_config_file).unwrap_or_default();
+    if let Some(metrics_config) = MetricsConfig::new(&config_data, std::env::vars()) {
+        let run = CommandRun {
+            command: option_args.command_name,
+            elapsed: started.elapsed(),
+            failed: result.is_err(),
+            operations: api_stats.operations(),
         };
-        let config = Arc::new(gr::config::Config::new(f, &domain).expect("Unable to read config"));
-        match cli_options {
-            CliOptions::MergeRequest(options) => {
-                merge_request::execute(options, config, domain, path)
-            }
-            CliOptions::Browse(options) => {
-                // Use default config for browsing - does not require auth.
-                let config = Arc::new(gr::config::Config::default());
-                browse::execute(options, config, domain, path)
-            }
-            CliOptions::Pipeline(options) => cicd::execute(options, config, domain, path),
-            CliOptions::Project(options) => project::execute(options, config, domain, path),
-            CliOptions::Docker(options) => docker::execute(options, config, domain, path),
-            CliOptions::Release(options) => cmds::release::execute(options, config, domain, path),
-            CliOptions::My(options) => cmds::my::execute(options, config, domain, path),
-            // Init command is handled above when user creates a new
-            // configuration - this is unreachable
-            CliOptions::Init(_) => unreachable!(),
+        metrics::emit(&metrics_config, &run);
+    }
+    if cancel::cancelled() {
+        if let Err(err) = result {
+            eprintln!("{}", err);
+        }
+        std::process::exit(cancel::EXIT_CODE);
+    }
+    result
+}
+
+fn execute(cli_options: CliOptions, config_file: PathBuf) -> Result<()> {
+    // The config file is optional when the environment provides the
+    // configuration, ex. GITAR_API_TOKEN_GITLAB_COM in a CI job.
+    let config_data = match fs::read_to_string(&config_file) {
+        Ok(config_data) => config_data,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let CmdInfo::RemoteUrl { domain, path } = git::remote_url(&Shell)? else {
+        return Err(error::gen("No remote url found. Please set a remote url."));
+    };
+    // Browsing does not authenticate, no need to ask for a token.
+    let config_data = if !matches!(cli_options, CliOptions::Browse(_))
+        && !gr::config::is_configured(&config_data, &domain, std::env::vars())
+        && init::first_run_setup(&config_file, &domain)?
+    {
+        fs::read_to_string(&config_file)?
+    } else {
+        config_data
+    };
+    let config = Arc::new(gr::config::Config::new(config_data.as_bytes(), &domain)?);
+    if config.ascii_output() {
+        display::set_ascii_output(true);
+    }
+    let (warn_config, warn_domain, warn_path) = (config.clone(), domain.clone(), path.clone());
+    let result = match cli_options {
+        CliOptions::MergeRequest(options) => merge_request::execute(options, config, domain, path),
+        CliOptions::Browse(options) => {
+            // Use default config for browsing - does not require auth.
+            let config = Arc::new(gr::config::Config::default());
+            return browse::execute(options, config, domain, path);
+        }
+        CliOptions::Pipeline(options) => cicd::execute(options, config, domain, path),
+        CliOptions::Project(options) => project::execute(options, config, domain, path),
+        CliOptions::Docker(options) => docker::execute(options, config, domain, path),
+        CliOptions::Release(options) => cmds::release::execute(options, config, domain, path),
+        CliOptions::Issue(options) => cmds::issue::execute(options, config, domain, path),
+        CliOptions::Environment(options) => {
+            cmds::environment::execute(options, config, domain, path)
         }
+        CliOptions::My(options) => cmds::my::execute(options, config, domain, path),
+        CliOptions::Cache(options) => cmds::cache::execute(options, config, domain, path),
+        CliOptions::History(cli_args) => cmds::history::execute(cli_args, config),
+        CliOptions::Undo(cli_args) => cmds::undo::execute(cli_args, config, domain, path),
+        // Init, Migrate, Status, Auth, Contracts, Config, Admin and Version are
+        // handled in main as they do not require the current repository
+        // configuration - this is unreachable
+        CliOptions::Init(_)
+        | CliOptions::Version
+        | CliOptions::Migrate(_)
+        | CliOptions::Status(_)
+        | CliOptions::Auth(_)
+        | CliOptions::Contracts(_)
+        | CliOptions::Config(_)
+        | CliOptions::Cron(_) => unreachable!(),
+        #[cfg(feature = "admin")]
+        CliOptions::Admin(_) => unreachable!(),
+    };
+    // Broadcast messages, ex. an upcoming maintenance, can explain a failure.
+    cmds::status::warn_broadcast_messages(
+        warn_config.clone(),
+        warn_domain.clone(),
+        warn_path.clone(),
+    );
+    if result.is_ok() {
+        cmds::auth::warn_token_expiration(warn_config, warn_domain, warn_path);
     }
+    result
 }
@@ -0,0 +1,324 @@
+//! Metrics of each command run, sent to StatsD or to an OpenTelemetry
+//! collector once the command is done, for teams running gitar at scale, ex.
+//! in CI pipelines. A run reports its duration, whether it failed and the
+//! API requests, cache hits and errors per operation, so dashboards can
+//! aggregate durations and error rates across runs.
+//!
+//! The sink is configured with global keys, as a run can involve several
+//! domains or none:
+//!
+//! ```text
+//! metrics_statsd=127.0.0.1:8125
+//! metrics_otlp_endpoint=http://localhost:4318/v1/metrics
+//! metrics_prefix=gitar
+//! ```
+//!
+//! Sending is best effort. A sink that is down never fails the command.
+
+use std::net::UdpSocket;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::config::{self, ENV_PREFIX};
+use crate::stats::OperationStats;
+use crate::{log_error, Result};
+
+const DEFAULT_PREFIX: &str = "gitar";
+const OTLP_TIMEOUT: Duration = Duration::from_secs(5);
+/// OTLP aggregation temporality. Each run reports its own counts.
+const DELTA: u8 = 1;
+
+#[derive(Debug, PartialEq)]
+pub struct MetricsConfig {
+    statsd: Option<String>,
+    otlp_endpoint: Option<String>,
+    prefix: String,
+}
+
+impl MetricsConfig {
+    /// Sink as per the global keys of the config file, overridden by the
+    /// `GITAR_METRICS_*` environment variables. None if metrics are not
+    /// enabled.
+    pub fn new(
+        config_data: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Option<Self> {
+        let vars = vars.into_iter().collect::<Vec<_>>();
+        let value = |key: &str| {
+            let env_name = format!("{}{}", ENV_PREFIX, key.to_uppercase());
+            vars.iter()
+                .find(|(name, _)| *name == env_name)
+                .map(|(_, value)| value.clone())
+                .or_else(|| config::get_value(config_data, None, key))
+                .filter(|value| !value.is_empty())
+        };
+        let statsd = value("metrics_statsd");
+        let otlp_endpoint = value("metrics_otlp_endpoint");
+        if statsd.is_none() && otlp_endpoint.is_none() {
+            return None;
+        }
+        Some(MetricsConfig {
+            statsd,
+            otlp_endpoint,
+            prefix: value("metrics_prefix").unwrap_or(DEFAULT_PREFIX.to_string()),
+        })
+    }
+}
+
+pub struct CommandRun {
+    /// Ex. `mr list`.
+    pub command: String,
+    pub elapsed: Duration,
+    pub failed: bool,
+    /// API usage per operation, as gathered by [`crate::stats::ApiStats`].
+    pub operations: Vec<(String, OperationStats)>,
+}
+
+impl CommandRun {
+    fn status(&self) -> &'static str {
+        if self.failed {
+            "error"
+        } else {
+            "ok"
+        }
+    }
+}
+
+/// Sends the metrics of `run` to the configured sinks. Errors are logged.
+pub fn emit(config: &MetricsConfig, run: &CommandRun) {
+    if let Some(address) = &config.statsd {
+        if let Err(err) = send_statsd(address, &statsd_lines(&config.prefix, run)) {
+            log_error!("Could not send metrics to StatsD {}: {}", address, err);
+        }
+    }
+    if let Some(endpoint) = &config.otlp_endpoint {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let payload = otlp_payload(&config.prefix, run, now);
+        if let Err(err) = ureq::post(endpoint)
+            .timeout(OTLP_TIMEOUT)
+            .send_json(payload)
+        {
+            log_error!("Could not send metrics to {}: {}", endpoint, err);
+        }
+    }
+}
+
+fn send_statsd(address: &str, lines: &[String]) -> Result<()> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.send_to(lines.join("\n").as_bytes(), address)?;
+    Ok(())
+}
+
+/// Metrics in the StatsD line protocol, with DogStatsD tags.
+fn statsd_lines(prefix: &str, run: &CommandRun) -> Vec<String> {
+    let command = run.command.replace(' ', "_");
+    let tags = format!("#command:{},status:{}", command, run.status());
+    let mut lines = vec![
+        format!(
+            "{}.command.duration:{}|ms|{}",
+            prefix,
+            run.elapsed.as_millis(),
+            tags
+        ),
+        format!("{}.command.runs:1|c|{}", prefix, tags),
+        format!(
+            "{}.command.errors:{}|c|{}",
+            prefix,
+            u8::from(run.failed),
+            tags
+        ),
+    ];
+    for (operation, stats) in &run.operations {
+        let tags = format!("#command:{},operation:{}", command, operation);
+        for (name, value) in API_COUNTERS.iter().zip(api_counts(stats)) {
+            lines.push(format!("{}.api.{}:{}|c|{}", prefix, name, value, tags));
+        }
+    }
+    lines
+}
+
+const API_COUNTERS: [&str; 3] = ["requests", "cache_hits", "errors"];
+
+fn api_counts(stats: &OperationStats) -> [u32; 3] {
+    [stats.requests, stats.cache_hits, stats.errors]
+}
+
+/// Metrics as an OTLP/HTTP JSON export request.
+fn otlp_payload(prefix: &str, run: &CommandRun, time_unix_nano: u128) -> Value {
+    let time = time_unix_nano.to_string();
+    let command_attributes = json!([
+        attribute("command", &run.command),
+        attribute("status", run.status()),
+    ]);
+    let data_point = |value: u64, attributes: &Value| {
+        json!({
+            "asInt": value.to_string(),
+            "timeUnixNano": time,
+            "attributes": attributes,
+        })
+    };
+    let sum = |name: &str, data_points: Vec<Value>| {
+        json!({
+            "name": format!("{}.{}", prefix, name),
+            "sum": {
+                "aggregationTemporality": DELTA,
+                "isMonotonic": true,
+                "dataPoints": data_points,
+            },
+        })
+    };
+    let mut metrics = vec![
+        json!({
+            "name": format!("{}.command.duration", prefix),
+            "unit": "ms",
+            "gauge": {
+                "dataPoints": [data_point(run.elapsed.as_millis() as u64, &command_attributes)],
+            },
+        }),
+        sum("command.runs", vec![data_point(1, &command_attributes)]),
+        sum(
+            "command.errors",
+            vec![data_point(u64::from(run.failed), &command_attributes)],
+        ),
+    ];
+    for (index, name) in API_COUNTERS.iter().enumerate() {
+        let data_points = run
+            .operations
+            .iter()
+            .map(|(operation, stats)| {
+                let attributes = json!([
+                    attribute("command", &run.command),
+                    attribute("operation", operation),
+                ]);
+                data_point(u64::from(api_counts(stats)[index]), &attributes)
+            })
+            .collect::<Vec<_>>();
+        if !data_points.is_empty() {
+            metrics.push(sum(&format!("api.{}", name), data_points));
+        }
+    }
+    json!({
+        "resourceMetrics": [{
+            "resource": {
+                "attributes": [attribute("service.name", "gitar")],
+            },
+            "scopeMetrics": [{
+                "scope": {"name": "gitar", "version": env!("CARGO_PKG_VERSION")},
+                "metrics": metrics,
+            }],
+        }],
+    })
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({"key": key, "value": {"stringValue": value}})
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(failed: bool) -> CommandRun {
+        CommandRun {
+            command: "mr list".to_string(),
+            elapsed: Duration::from_millis(1250),
+            failed,
+            operations: vec![(
+                "merge_request".to_string(),
+                OperationStats {
+                    requests: 3,
+                    cache_hits: 1,
+                    errors: 1,
+                    bytes: 2048,
+                    elapsed: Duration::from_millis(900),
+                },
+            )],
+        }
+    }
+
+    #[test]
+    fn test_metrics_config() {
+        assert_eq!(
+            None,
+            MetricsConfig::new("gitlab.com.api_token=1234", vec![])
+        );
+        let config_data = "metrics_statsd=127.0.0.1:8125\n";
+        assert_eq!(
+            Some(MetricsConfig {
+                statsd: Some("127.0.0.1:8125".to_string()),
+                otlp_endpoint: None,
+                prefix: "gitar".to_string(),
+            }),
+            MetricsConfig::new(config_data, vec![])
+        );
+        let vars = vec![
+            (
+                "GITAR_METRICS_OTLP_ENDPOINT".to_string(),
+                "http://localhost:4318/v1/metrics".to_string(),
+            ),
+            ("GITAR_METRICS_PREFIX".to_string(), "ci.gitar".to_string()),
+        ];
+        assert_eq!(
+            Some(MetricsConfig {
+                statsd: Some("127.0.0.1:8125".to_string()),
+                otlp_endpoint: Some("http://localhost:4318/v1/metrics".to_string()),
+                prefix: "ci.gitar".to_string(),
+            }),
+            MetricsConfig::new(config_data, vars)
+        );
+    }
+
+    #[test]
+    fn test_statsd_lines() {
+        assert_eq!(
+            vec![
+                "gitar.command.duration:1250|ms|#command:mr_list,status:error",
+                "gitar.command.runs:1|c|#command:mr_list,status:error",
+                "gitar.command.errors:1|c|#command:mr_list,status:error",
+                "gitar.api.requests:3|c|#command:mr_list,operation:merge_request",
+                "gitar.api.cache_hits:1|c|#command:mr_list,operation:merge_request",
+                "gitar.api.errors:1|c|#command:mr_list,operation:merge_request",
+            ],
+            statsd_lines("gitar", &run(true))
+        );
+    }
+
+    #[test]
+    fn test_otlp_payload() {
+        let payload = otlp_payload("gitar", &run(false), 1700000000000000000);
+        let metrics = &payload["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+        let names = metrics
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|metric| metric["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "gitar.command.duration",
+                "gitar.command.runs",
+                "gitar.command.errors",
+                "gitar.api.requests",
+                "gitar.api.cache_hits",
+                "gitar.api.errors",
+            ],
+            names
+        );
+        let duration = &metrics[0]["gauge"]["dataPoints"][0];
+        assert_eq!("1250", duration["asInt"]);
+        assert_eq!("1700000000000000000", duration["timeUnixNano"]);
+        assert_eq!("mr list", duration["attributes"][0]["value"]["stringValue"]);
+        assert_eq!("ok", duration["attributes"][1]["value"]["stringValue"]);
+        assert_eq!("0", metrics[2]["sum"]["dataPoints"][0]["asInt"]);
+        let requests = &metrics[3]["sum"]["dataPoints"][0];
+        assert_eq!("3", requests["asInt"]);
+        assert_eq!(
+            "merge_request",
+            requests["attributes"][1]["value"]["stringValue"]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Locations of the config file and the cache, following the XDG base
+//! directory specification. The config file lives in
+//! `$XDG_CONFIG_HOME/gitar/api` and the cache in `$XDG_CACHE_HOME/gitar`,
+//! defaulting to `~/.config` and `~/.cache`. A `cache_location` in the config
+//! file overrides the cache directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+const APP_DIR: &str = "gitar";
+const CONFIG_FILE: &str = "api";
+/// Where the config file lived before honouring `XDG_CONFIG_HOME`.
+const LEGACY_CONFIG_PATH: &str = ".config/gitar/api";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Paths {
+    pub config_file: PathBuf,
+    pub legacy_config_file: Option<PathBuf>,
+    /// None if neither `XDG_CACHE_HOME` nor `HOME` are set.
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Paths {
+    pub fn from_env() -> Self {
+        Paths::from_vars(std::env::vars())
+    }
+
+    /// Paths resolved from the given environment variables. Relative XDG
+    /// directories are ignored, as the specification asks.
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        let (mut home, mut config_home, mut cache_home) = (None, None, None);
+        for (name, value) in vars {
+            match name.as_str() {
+                "HOME" if !value.is_empty() => home = Some(PathBuf::from(value)),
+                "XDG_CONFIG_HOME" => config_home = absolute(value),
+                "XDG_CACHE_HOME" => cache_home = absolute(value),
+                _ => {}
+            }
+        }
+        let config_home = config_home.or_else(|| home.as_ref().map(|home| home.join(".config")));
+        let cache_home = cache_home.or_else(|| home.as_ref().map(|home| home.join(".cache")));
+        Paths {
+            config_file: config_home
+                .unwrap_or_default()
+                .join(APP_DIR)
+                .join(CONFIG_FILE),
+            legacy_config_file: home.map(|home| home.join(LEGACY_CONFIG_PATH)),
+            cache_dir: cache_home.map(|cache_home| cache_home.join(APP_DIR)),
+        }
+    }
+
+    /// Moves the config file from its legacy location to the XDG one, unless
+    /// there is already a config file there. Returns where it was moved from.
+    pub fn migrate_config(&self) -> Result<Option<PathBuf>> {
+        let Some(legacy) = &self.legacy_config_file else {
+            return Ok(None);
+        };
+        if legacy == &self.config_file || self.config_file.exists() || !legacy.exists() {
+            return Ok(None);
+        }
+        if let Some(parent) = self.config_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Renaming fails across file systems, copy it over instead.
+        if fs::rename(legacy, &self.config_file).is_err() {
+            fs::copy(legacy, &self.config_file)?;
+            fs::remove_file(legacy)?;
+        }
+        Ok(Some(legacy.clone()))
+    }
+}
+
+fn absolute(value: String) -> Option<PathBuf> {
+    let path = PathBuf::from(value);
+    path.is_absolute().then_some(path)
+}
+
+/// Cache location as written in the config file, expanded. Older versions of
+/// `gr init` wrote `"~/.cache/gitar"`, quotes included, which was taken as a
+/// relative directory.
+pub fn expand_location(location: &str, home: Option<&Path>) -> String {
+    let location = location.trim().trim_matches('"');
+    match (location.strip_prefix("~/"), home) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ if location == "~" => home
+            .map(|home| home.to_string_lossy().to_string())
+            .unwrap_or_else(|| location.to_string()),
+        _ => location.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_paths_under_home() {
+        let paths = Paths::from_vars(vars(&[("HOME", "/home/user")]));
+        assert_eq!(
+            PathBuf::from("/home/user/.config/gitar/api"),
+            paths.config_file
+        );
+        assert_eq!(
+            Some(PathBuf::from("/home/user/.cache/gitar")),
+            paths.cache_dir
+        );
+    }
+
+    #[test]
+    fn test_xdg_paths() {
+        let paths = Paths::from_vars(vars(&[
+            ("HOME", "/home/user"),
+            ("XDG_CONFIG_HOME", "/xdg/config"),
+            ("XDG_CACHE_HOME", "/xdg/cache"),
+        ]));
+        assert_eq!(PathBuf::from("/xdg/config/gitar/api"), paths.config_file);
+        assert_eq!(Some(PathBuf::from("/xdg/cache/gitar")), paths.cache_dir);
+        assert_eq!(
+            Some(PathBuf::from("/home/user/.config/gitar/api")),
+            paths.legacy_config_file
+        );
+    }
+
+    #[test]
+    fn test_relative_xdg_paths_are_ignored() {
+        let paths = Paths::from_vars(vars(&[("HOME", "/home/user"), ("XDG_CACHE_HOME", "cache")]));
+        assert_eq!(
+            Some(PathBuf::from("/home/user/.cache/gitar")),
+            paths.cache_dir
+        );
+    }
+
+    #[test]
+    fn test_migrate_legacy_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        let legacy = home.join(LEGACY_CONFIG_PATH);
+        fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+        fs::write(&legacy, "gitlab.com.api_token=1234\n").unwrap();
+        let paths = Paths::from_vars(vec![
+            ("HOME".to_string(), home.to_string_lossy().to_string()),
+            (
+                "XDG_CONFIG_HOME".to_string(),
+                dir.path().join("xdg").to_string_lossy().to_string(),
+            ),
+        ]);
+        assert_eq!(Some(legacy.clone()), paths.migrate_config().unwrap());
+        assert!(!legacy.exists());
+        assert_eq!(
+            "gitlab.com.api_token=1234\n",
+            fs::read_to_string(&paths.config_file).unwrap()
+        );
+        // Nothing left to migrate.
+        assert_eq!(None, paths.migrate_config().unwrap());
+    }
+
+    #[test]
+    fn test_migration_keeps_existing_xdg_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        let legacy = home.join(LEGACY_CONFIG_PATH);
+        fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+        fs::write(&legacy, "legacy").unwrap();
+        let xdg = dir.path().join("xdg");
+        let paths = Paths::from_vars(vec![
+            ("HOME".to_string(), home.to_string_lossy().to_string()),
+            (
+                "XDG_CONFIG_HOME".to_string(),
+                xdg.to_string_lossy().to_string(),
+            ),
+        ]);
+        fs::create_dir_all(paths.config_file.parent().unwrap()).unwrap();
+        fs::write(&paths.config_file, "current").unwrap();
+        assert_eq!(None, paths.migrate_config().unwrap());
+        assert!(legacy.exists());
+        assert_eq!("current", fs::read_to_string(&paths.config_file).unwrap());
+    }
+
+    #[test]
+    fn test_expand_location() {
+        let home = Path::new("/home/user");
+        assert_eq!(
+            "/home/user/.cache/gitar",
+            expand_location("\"~/.cache/gitar\"", Some(home))
+        );
+        assert_eq!("/home/user", expand_location("~", Some(home)));
+        assert_eq!("/tmp/cache", expand_location("/tmp/cache", Some(home)));
+        assert_eq!("~/.cache", expand_location("~/.cache", None));
+    }
+}
@@ -0,0 +1,141 @@
+//! Last rate limit reported by each remote, kept under the cache location.
+//! Each gitar invocation is a new process, so without it back to back runs,
+//! ex. in a shell loop, would not see how much of the quota is left until
+//! their first response.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ConfigProperties;
+use crate::io::RateLimitHeader;
+use crate::time::{self, Seconds};
+use crate::Result;
+
+pub trait RateLimitStore {
+    /// Rate limit of the current window, if it has not been reset yet.
+    fn load(&self) -> Option<RateLimitHeader>;
+    fn save(&self, ratelimit: &RateLimitHeader) -> Result<()>;
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredRateLimit {
+    remaining: u32,
+    /// Epoch seconds at which the quota is reset.
+    reset: u64,
+}
+
+pub struct FileRateLimitStore {
+    path: PathBuf,
+}
+
+impl FileRateLimitStore {
+    pub fn new(config: &impl ConfigProperties, domain: &str) -> Self {
+        FileRateLimitStore {
+            path: PathBuf::from(config.cache_location()).join(format!("ratelimit-{}.json", domain)),
+        }
+    }
+}
+
+impl RateLimitStore for FileRateLimitStore {
+    fn load(&self) -> Option<RateLimitHeader> {
+        // A missing or partially written file, ex. two invocations saving at
+        // the same time, is the same as not knowing the rate limit.
+        let contents = fs::read_to_string(&self.path).ok()?;
+        let stored: StoredRateLimit = serde_json::from_str(&contents).ok()?;
+        let reset = Seconds::new(stored.reset);
+        if reset <= time::now_epoch_seconds() {
+            return None;
+        }
+        Some(RateLimitHeader::new(
+            stored.remaining,
+            reset,
+            Seconds::new(0),
+        ))
+    }
+
+    fn save(&self, ratelimit: &RateLimitHeader) -> Result<()> {
+        let stored = StoredRateLimit {
+            remaining: ratelimit.remaining,
+            reset: *ratelimit.reset,
+        };
+        if let Some(cache_location) = self.path.parent() {
+            fs::create_dir_all(cache_location)?;
+        }
+        fs::write(&self.path, serde_json::to_string(&stored)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ConfigMock {
+        cache_location: String,
+    }
+
+    impl ConfigProperties for ConfigMock {
+        fn api_token(&self) -> &str {
+            "1234"
+        }
+        fn cache_location(&self) -> &str {
+            &self.cache_location
+        }
+    }
+
+    fn store(dir: &tempfile::TempDir, domain: &str) -> FileRateLimitStore {
+        let config = ConfigMock {
+            cache_location: dir.path().to_str().unwrap().to_string(),
+        };
+        FileRateLimitStore::new(&config, domain)
+    }
+
+    #[test]
+    fn test_save_and_load_per_domain() {
+        let dir = tempfile::tempdir().unwrap();
+        let github = store(&dir, "github.com");
+        assert!(github.load().is_none());
+        let reset = time::now_epoch_seconds() + Seconds::new(600);
+        github
+            .save(&RateLimitHeader::new(42, reset, Seconds::new(0)))
+            .unwrap();
+        let ratelimit = github.load().unwrap();
+        assert_eq!(42, ratelimit.remaining);
+        assert_eq!(reset, ratelimit.reset);
+        assert!(store(&dir, "gitlab.com").load().is_none());
+    }
+
+    #[test]
+    fn test_save_creates_the_cache_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ConfigMock {
+            cache_location: dir.path().join("gitar").to_str().unwrap().to_string(),
+        };
+        let github = FileRateLimitStore::new(&config, "github.com");
+        let reset = time::now_epoch_seconds() + Seconds::new(600);
+        github
+            .save(&RateLimitHeader::new(42, reset, Seconds::new(0)))
+            .unwrap();
+        assert_eq!(42, github.load().unwrap().remaining);
+    }
+
+    #[test]
+    fn test_load_ignores_reset_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let github = store(&dir, "github.com");
+        let reset = time::now_epoch_seconds() - Seconds::new(1);
+        github
+            .save(&RateLimitHeader::new(0, reset, Seconds::new(0)))
+            .unwrap();
+        assert!(github.load().is_none());
+    }
+
+    #[test]
+    fn test_load_ignores_corrupted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ratelimit-github.com.json"), "{\"remain").unwrap();
+        assert!(store(&dir, "github.com").load().is_none());
+    }
+}
@@ -0,0 +1,150 @@
+//! Members recently picked as assignees or reviewers, so prompts offer them
+//! first. They are kept per project under the cache location, most recent
+//! first.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+
+use crate::remote::Member;
+use crate::Result;
+
+const RECENT_DIR: &str = "recent_members";
+const MAX_RECENT: usize = 20;
+
+pub struct RecentMembers {
+    // None when nothing is persisted, ex. the cache location is not writable.
+    path: Option<PathBuf>,
+    usernames: Vec<String>,
+}
+
+impl RecentMembers {
+    /// Recent members of the project identified by `key` under the cache
+    /// `location`. Failing to read them is not an error, prompts just lose
+    /// the ranking.
+    pub fn new(location: &str, key: &str) -> Self {
+        let dir = PathBuf::from(location).join(RECENT_DIR);
+        if fs::create_dir_all(&dir).is_err() {
+            return RecentMembers::disabled();
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        let path = dir.join(format!("{:x}", hasher.finalize()));
+        let usernames = match fs::read_to_string(&path) {
+            Ok(data) => data
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.to_string())
+                .collect(),
+            Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(_) => return RecentMembers::disabled(),
+        };
+        RecentMembers {
+            path: Some(path),
+            usernames,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        RecentMembers {
+            path: None,
+            usernames: Vec::new(),
+        }
+    }
+
+    /// Members in the order they are offered: recently picked ones first,
+    /// most recent at the top, then the rest by username.
+    pub fn rank<'a>(&self, members: &'a [Member]) -> Vec<&'a Member> {
+        let mut ranked = members.iter().collect::<Vec<&Member>>();
+        ranked.sort_by_key(|member| {
+            let position = self
+                .usernames
+                .iter()
+                .position(|username| username == &member.username)
+                .unwrap_or(usize::MAX);
+            (position, member.username.to_lowercase())
+        });
+        ranked
+    }
+
+    /// Moves the given usernames to the top of the recent ones.
+    pub fn record(&mut self, usernames: &[String]) -> Result<()> {
+        if usernames.is_empty() {
+            return Ok(());
+        }
+        self.usernames
+            .retain(|username| !usernames.contains(username));
+        let mut recent = usernames.to_vec();
+        recent.append(&mut self.usernames);
+        recent.truncate(MAX_RECENT);
+        self.usernames = recent;
+        if let Some(path) = &self.path {
+            let mut data = self.usernames.join("\n");
+            data.push('\n');
+            fs::write(path, data)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn member(username: &str) -> Member {
+        Member::builder()
+            .id(0)
+            .name("".to_string())
+            .username(username.to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn usernames(members: Vec<&Member>) -> Vec<&str> {
+        members
+            .into_iter()
+            .map(|member| member.username.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_recent_members_are_ranked_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().to_str().unwrap();
+        let members = vec![member("tsmith"), member("adoe"), member("jdoe")];
+
+        let mut recent = RecentMembers::new(location, "gitlab.com/a/b");
+        assert_eq!(
+            vec!["adoe", "jdoe", "tsmith"],
+            usernames(recent.rank(&members))
+        );
+        recent.record(&["tsmith".to_string()]).unwrap();
+        recent.record(&["jdoe".to_string()]).unwrap();
+
+        let recent = RecentMembers::new(location, "gitlab.com/a/b");
+        assert_eq!(
+            vec!["jdoe", "tsmith", "adoe"],
+            usernames(recent.rank(&members))
+        );
+        // Other projects keep their own.
+        let recent = RecentMembers::new(location, "gitlab.com/a/c");
+        assert_eq!(
+            vec!["adoe", "jdoe", "tsmith"],
+            usernames(recent.rank(&members))
+        );
+    }
+
+    #[test]
+    fn test_record_keeps_most_recent_only() {
+        let mut recent = RecentMembers::disabled();
+        for i in 0..MAX_RECENT + 5 {
+            recent.record(&[format!("user{}", i)]).unwrap();
+        }
+        recent.record(&["user0".to_string()]).unwrap();
+        assert_eq!(MAX_RECENT, recent.usernames.len());
+        assert_eq!("user0", recent.usernames[0]);
+        assert_eq!("user24", recent.usernames[1]);
+    }
+}
@@ -1,15 +1,26 @@
 use std::fmt::{self, Display, Formatter};
 
+#[cfg(feature = "admin")]
+use crate::api_traits::InstanceAdmin;
 use crate::api_traits::{
-    Cicd, CicdRunner, CommentMergeRequest, ContainerRegistry, Deploy, MergeRequest, RemoteProject,
-    Timestamp, UserInfo,
+    Cicd, CicdArtifact, CicdGraph, CicdJob, CicdLinter, CicdRunner, CommentMergeRequest, CommitApi,
+    ContainerRegistry, Deploy, Deployment, DeploymentApproval, FailingPipelines, Issue, MergeQueue,
+    MergeRequest, MergeRequestApprovals, MergeRequestChecks, MergeRequestDiff, MergeRequestLabels,
+    MergeRequestRebase, MergeRequestReviewDiff, ProjectAccessRequests, ProjectApprovals,
+    ProjectAudit, ProjectBranchProtection, ProjectFile, ProjectMembers, ProjectMilestones,
+    ProjectPushRules, ProjectRulesets, ProjectTemplate, ProjectVariables, RemoteContract,
+    RemoteProject, RemoteStatus, Timestamp, TokenInfo, Undo, UserInfo,
 };
-use crate::cache::filesystem::FileCache;
+use crate::audit::FileAuditLog;
+use crate::cache::{filesystem::FileCache, NoCache};
 use crate::config::Config;
 use crate::display::{Column, DisplayBody, Format};
 use crate::error::GRError;
+use crate::gitea::{self, Gitea};
 use crate::github::Github;
 use crate::gitlab::Gitlab;
+use crate::ratelimit::FileRateLimitStore;
+use crate::stats;
 use crate::time::Milliseconds;
 use crate::Result;
 use crate::{error, http};
@@ -49,6 +60,10 @@ impl Project {
         self
     }
 
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
     pub fn default_branch(&self) -> &str {
         &self.default_branch
     }
@@ -80,6 +95,12 @@ pub struct Member {
     pub username: String,
     #[builder(default)]
     pub created_at: String,
+    /// Role in the project. Ex: maintainer in Gitlab, admin in Github
+    #[builder(default)]
+    pub role: String,
+    /// Account state, such as active or blocked
+    #[builder(default)]
+    pub state: String,
 }
 
 impl Member {
@@ -101,11 +122,29 @@ impl From<Member> for DisplayBody {
                 Column::new("ID", m.id.to_string()),
                 Column::new("Name", m.name),
                 Column::new("Username", m.username),
+                Column::new("Role", m.role),
+                Column::new("State", m.state),
             ],
         }
     }
 }
 
+/// Milestone of a project. The id is the one used to reference it from merge
+/// requests, which in Github is the milestone number.
+#[derive(Builder, Clone, Debug, PartialEq, Default)]
+pub struct Milestone {
+    pub id: i64,
+    pub title: String,
+    #[builder(default)]
+    pub state: String,
+}
+
+impl Milestone {
+    pub fn builder() -> MilestoneBuilder {
+        MilestoneBuilder::default()
+    }
+}
+
 #[derive(Builder, Clone, Debug, Default)]
 #[builder(default)]
 pub struct MergeRequestResponse {
@@ -114,6 +153,7 @@ pub struct MergeRequestResponse {
     pub author: String,
     pub updated_at: String,
     pub source_branch: String,
+    pub target_branch: String,
     pub created_at: String,
     pub title: String,
     // For Github to filter pull requests from issues.
@@ -123,6 +163,7 @@ pub struct MergeRequestResponse {
     pub merged_at: String,
     pub pipeline_id: Option<i64>,
     pub pipeline_url: Option<String>,
+    pub labels: Vec<String>,
 }
 
 impl MergeRequestResponse {
@@ -144,6 +185,7 @@ impl From<MergeRequestResponse> for DisplayBody {
                     .build()
                     .unwrap(),
                 Column::new("Author", mr.author),
+                Column::new("Labels", mr.labels.join(",")),
                 Column::new("URL", mr.web_url),
                 Column::new("Updated at", mr.updated_at),
                 Column::builder()
@@ -216,13 +258,30 @@ pub struct MergeRequestBodyArgs {
     #[builder(default)]
     pub target_branch: String,
     #[builder(default)]
-    pub assignee_id: String,
+    pub assignee_ids: Vec<i64>,
+    /// Usernames of the assignees, in the same order as `assignee_ids`.
     #[builder(default)]
-    pub username: String,
+    pub assignee_usernames: Vec<String>,
+    #[builder(default)]
+    pub reviewer_ids: Vec<i64>,
+    /// Usernames of the reviewers, in the same order as `reviewer_ids`.
+    #[builder(default)]
+    pub reviewer_usernames: Vec<String>,
     #[builder(default = "String::from(\"true\")")]
     pub remove_source_branch: String,
     #[builder(default)]
     pub draft: bool,
+    #[builder(default)]
+    pub labels: Vec<String>,
+    /// Project to open the merge request against when the source branch
+    /// lives in a fork, as owner/repo. None opens it in the same project.
+    #[builder(default)]
+    pub target_repo: Option<String>,
+    /// Id of `target_repo`. Gitlab references the target project by id.
+    #[builder(default)]
+    pub target_project_id: Option<i64>,
+    #[builder(default)]
+    pub milestone_id: Option<i64>,
 }
 
 impl MergeRequestBodyArgs {
@@ -231,11 +290,38 @@ impl MergeRequestBodyArgs {
     }
 }
 
+/// Longest title and description, in characters, the remote accepts when
+/// opening a merge request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MergeRequestLimits {
+    pub title: usize,
+    pub description: usize,
+}
+
 #[derive(Builder, Clone)]
 pub struct MergeRequestListBodyArgs {
     pub state: MergeRequestState,
     pub list_args: Option<ListBodyArgs>,
     pub assignee_id: Option<i64>,
+    // Merge requests where the user has been requested for review.
+    #[builder(default)]
+    pub reviewer: Option<Member>,
+    /// Username of the author of the merge requests.
+    #[builder(default)]
+    pub author: Option<String>,
+    #[builder(default)]
+    pub milestone: Option<Milestone>,
+    /// Keeps the reviewer filter, which otherwise spans all the projects of
+    /// the user, to the current project.
+    #[builder(default)]
+    pub in_project: bool,
+    /// Some(false) leaves drafts out, Some(true) lists drafts only.
+    #[builder(default)]
+    pub draft: Option<bool>,
+    /// Only merge requests whose head pipeline succeeded. Gitlab checks the
+    /// pipeline of each merge request in the current project.
+    #[builder(default)]
+    pub passing_ci: bool,
 }
 
 impl MergeRequestListBodyArgs {
@@ -256,6 +342,8 @@ pub struct ListRemoteCliArgs {
     #[builder(default)]
     pub page_number: Option<i64>,
     #[builder(default)]
+    pub per_page: Option<u32>,
+    #[builder(default)]
     pub created_after: Option<String>,
     #[builder(default)]
     pub created_before: Option<String>,
@@ -289,12 +377,28 @@ pub struct GetRemoteCliArgs {
     pub backoff_max_retries: u32,
     #[builder(default)]
     pub backoff_retry_after: u64,
+    /// File the formatted output is written to. `None` or "-" is STDOUT.
+    #[builder(default)]
+    pub output: Option<String>,
+    /// Columns to print, in that order. `None` prints all but the optional
+    /// ones.
+    #[builder(default)]
+    pub columns: Option<Vec<String>>,
 }
 
 impl GetRemoteCliArgs {
     pub fn builder() -> GetRemoteCliArgsBuilder {
         GetRemoteCliArgsBuilder::default()
     }
+
+    /// Uses the columns configured for the command unless `--columns` was
+    /// given.
+    pub fn with_default_columns(mut self, columns: &[String]) -> Self {
+        if self.columns.is_none() && !columns.is_empty() {
+            self.columns = Some(columns.to_vec());
+        }
+        self
+    }
 }
 
 /// List body args is a common structure that can be used across multiple APIs
@@ -308,6 +412,9 @@ pub struct ListBodyArgs {
     pub page: Option<i64>,
     #[builder(setter(strip_option), default)]
     pub max_pages: Option<i64>,
+    /// Results per page. Overrides the configured default.
+    #[builder(default)]
+    pub per_page: Option<u32>,
     #[builder(default)]
     pub created_after: Option<String>,
     #[builder(default)]
@@ -330,12 +437,13 @@ impl ListBodyArgs {
 }
 
 pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Option<ListBodyArgs>> {
-    if remote_cli_args.page_number.is_some() {
+    if let Some(page_number) = remote_cli_args.page_number {
         return Ok(Some(
             ListBodyArgs::builder()
-                .page(remote_cli_args.page_number.unwrap())
+                .page(page_number)
                 .max_pages(1)
                 .sort_mode(remote_cli_args.sort.clone())
+                .per_page(remote_cli_args.per_page)
                 .created_after(remote_cli_args.created_after.clone())
                 .created_before(remote_cli_args.created_before.clone())
                 .build()
@@ -364,6 +472,7 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                     .page(from_page)
                     .max_pages(max_pages)
                     .sort_mode(remote_cli_args.sort.clone())
+                    .per_page(remote_cli_args.per_page)
                     .flush(remote_cli_args.flush)
                     .throttle_time(remote_cli_args.throttle_time)
                     .get_args(remote_cli_args.get_args.clone())
@@ -388,6 +497,7 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                     .page(1)
                     .max_pages(to_page)
                     .sort_mode(remote_cli_args.sort.clone())
+                    .per_page(remote_cli_args.per_page)
                     .flush(remote_cli_args.flush)
                     .throttle_time(remote_cli_args.throttle_time)
                     .get_args(remote_cli_args.get_args.clone())
@@ -410,6 +520,7 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                         .created_after(Some(created_after.to_string()))
                         .created_before(Some(created_before.to_string()))
                         .sort_mode(remote_cli_args.sort.clone())
+                        .per_page(remote_cli_args.per_page)
                         .flush(remote_cli_args.flush)
                         .throttle_time(remote_cli_args.throttle_time)
                         .get_args(remote_cli_args.get_args.clone())
@@ -417,17 +528,18 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                         .unwrap(),
                 ));
             }
-            return Ok(Some(
+            Ok(Some(
                 ListBodyArgs::builder()
                     .created_after(Some(created_after.to_string()))
                     .created_before(Some(created_before.to_string()))
                     .sort_mode(remote_cli_args.sort.clone())
+                    .per_page(remote_cli_args.per_page)
                     .flush(remote_cli_args.flush)
                     .throttle_time(remote_cli_args.throttle_time)
                     .get_args(remote_cli_args.get_args.clone())
                     .build()
                     .unwrap(),
-            ));
+            ))
         }
         (Some(created_after), None) => {
             if let Some(body_args) = &body_args {
@@ -437,6 +549,7 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                         .max_pages(body_args.max_pages.unwrap())
                         .created_after(Some(created_after.to_string()))
                         .sort_mode(remote_cli_args.sort.clone())
+                        .per_page(remote_cli_args.per_page)
                         .flush(remote_cli_args.flush)
                         .throttle_time(remote_cli_args.throttle_time)
                         .get_args(remote_cli_args.get_args.clone())
@@ -444,16 +557,17 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                         .unwrap(),
                 ));
             }
-            return Ok(Some(
+            Ok(Some(
                 ListBodyArgs::builder()
                     .created_after(Some(created_after.to_string()))
                     .sort_mode(remote_cli_args.sort.clone())
+                    .per_page(remote_cli_args.per_page)
                     .flush(remote_cli_args.flush)
                     .throttle_time(remote_cli_args.throttle_time)
                     .get_args(remote_cli_args.get_args.clone())
                     .build()
                     .unwrap(),
-            ));
+            ))
         }
         (None, Some(created_before)) => {
             if let Some(body_args) = &body_args {
@@ -463,6 +577,7 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                         .max_pages(body_args.max_pages.unwrap())
                         .created_before(Some(created_before.to_string()))
                         .sort_mode(remote_cli_args.sort.clone())
+                        .per_page(remote_cli_args.per_page)
                         .flush(remote_cli_args.flush)
                         .throttle_time(remote_cli_args.throttle_time)
                         .get_args(remote_cli_args.get_args.clone())
@@ -470,16 +585,17 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                         .unwrap(),
                 ));
             }
-            return Ok(Some(
+            Ok(Some(
                 ListBodyArgs::builder()
                     .created_before(Some(created_before.to_string()))
                     .sort_mode(remote_cli_args.sort.clone())
+                    .per_page(remote_cli_args.per_page)
                     .flush(remote_cli_args.flush)
                     .throttle_time(remote_cli_args.throttle_time)
                     .get_args(remote_cli_args.get_args.clone())
                     .build()
                     .unwrap(),
-            ));
+            ))
         }
         (None, None) => {
             if let Some(body_args) = &body_args {
@@ -488,6 +604,7 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                         .page(body_args.page.unwrap())
                         .max_pages(body_args.max_pages.unwrap())
                         .sort_mode(remote_cli_args.sort.clone())
+                        .per_page(remote_cli_args.per_page)
                         .flush(remote_cli_args.flush)
                         .throttle_time(remote_cli_args.throttle_time)
                         .get_args(remote_cli_args.get_args.clone())
@@ -495,15 +612,16 @@ pub fn validate_from_to_page(remote_cli_args: &ListRemoteCliArgs) -> Result<Opti
                         .unwrap(),
                 ));
             }
-            return Ok(Some(
+            Ok(Some(
                 ListBodyArgs::builder()
                     .sort_mode(remote_cli_args.sort.clone())
+                    .per_page(remote_cli_args.per_page)
                     .flush(remote_cli_args.flush)
                     .throttle_time(remote_cli_args.throttle_time)
                     .get_args(remote_cli_args.get_args.clone())
                     .build()
                     .unwrap(),
-            ));
+            ))
         }
     }
 }
@@ -533,6 +651,19 @@ impl URLQueryParamBuilder {
     }
 }
 
+/// Percent-encodes a value to be used in a URL query parameter.
+pub fn encode_query_param(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub enum ListSortMode {
     #[default]
@@ -540,19 +671,49 @@ pub enum ListSortMode {
     Desc,
 }
 
+// Traits implemented by the Gitea backend are marked with `gitea`. Other
+// traits are not available in Gitea and Forgejo domains.
 macro_rules! get {
     ($func_name:ident, $trait_name:ident) => {
+        get!(@build $func_name, $trait_name, false, unsupported);
+    };
+    ($func_name:ident, $trait_name:ident, gitea) => {
+        get!(@build $func_name, $trait_name, false, gitea);
+    };
+    ($func_name:ident, $trait_name:ident, $serve_stale:expr) => {
+        get!(@build $func_name, $trait_name, $serve_stale, unsupported);
+    };
+    ($func_name:ident, $trait_name:ident, $serve_stale:expr, gitea) => {
+        get!(@build $func_name, $trait_name, $serve_stale, gitea);
+    };
+    (@gitea gitea, $config:ident, $domain:ident, $path:ident, $runner:ident) => {
+        Arc::new(Gitea::new($config, &$domain, &$path, $runner))
+    };
+    (@gitea unsupported, $config:ident, $domain:ident, $path:ident, $runner:ident) => {
+        return Err(GRError::OperationNotSupported(format!(
+            "Operation not supported in Gitea domain: {}",
+            &$domain
+        ))
+        .into())
+    };
+    (@build $func_name:ident, $trait_name:ident, $serve_stale:expr, $gitea:ident) => {
         pub fn $func_name(
             domain: String,
             path: String,
             config: Arc<Config>,
             refresh_cache: bool,
         ) -> Result<Arc<dyn $trait_name + Send + Sync + 'static>> {
-            let runner = Arc::new(http::Client::new(
-                FileCache::new(config.clone()),
-                config.clone(),
-                refresh_cache,
-            ));
+            let runner = Arc::new(
+                http::Client::new(
+                    FileCache::new(config.clone()),
+                    config.clone(),
+                    refresh_cache,
+                )
+                .with_audit_log(FileAuditLog::new(&config))
+                .with_api_stats(stats::global())
+                .with_stale_responses($serve_stale)
+                .with_ratelimit_store(FileRateLimitStore::new(&config, &domain)),
+            );
 
             let github_domain_regex = regex::Regex::new(r"^github").unwrap();
             let gitlab_domain_regex = regex::Regex::new(r"^gitlab").unwrap();
@@ -561,6 +722,8 @@ macro_rules! get {
                     Arc::new(Github::new(config, &domain, &path, runner))
                 } else if gitlab_domain_regex.is_match(&domain) {
                     Arc::new(Gitlab::new(config, &domain, &path, runner))
+                } else if gitea::is_gitea_domain(&domain) {
+                    get!(@gitea $gitea, config, domain, path, runner)
                 } else {
                     return Err(error::gen(format!("Unsupported domain: {}", &domain)));
                 };
@@ -569,14 +732,73 @@ macro_rules! get {
     };
 }
 
-get!(get_mr, MergeRequest);
-get!(get_cicd, Cicd);
-get!(get_project, RemoteProject);
+get!(get_mr, MergeRequest, gitea);
+get!(get_merge_queue, MergeQueue);
+get!(get_mr_checks, MergeRequestChecks);
+get!(get_mr_approvals, MergeRequestApprovals);
+get!(get_mr_diff, MergeRequestDiff);
+get!(get_mr_review_diff, MergeRequestReviewDiff);
+get!(get_failing_pipelines, FailingPipelines);
+get!(get_mr_labels, MergeRequestLabels);
+get!(get_mr_rebase, MergeRequestRebase);
+get!(get_cicd, Cicd, gitea);
+get!(get_project, RemoteProject, gitea);
+// Answers from the cache even once expired. Used for data that rarely changes,
+// such as members, while `get_project` refreshes it in the background.
+get!(get_cached_project, RemoteProject, true, gitea);
+get!(get_project_approvals, ProjectApprovals);
+get!(get_project_access_requests, ProjectAccessRequests);
+get!(get_project_members, ProjectMembers);
+get!(get_project_milestones, ProjectMilestones);
+get!(get_project_template, ProjectTemplate);
+get!(get_project_audit, ProjectAudit);
+get!(get_project_branch_protection, ProjectBranchProtection);
+get!(get_project_file, ProjectFile);
+get!(get_project_rulesets, ProjectRulesets);
+get!(get_project_push_rules, ProjectPushRules);
+get!(get_commits, CommitApi);
 get!(get_registry, ContainerRegistry);
+
+/// Variable values are secrets, their responses are never written to the
+/// cache.
+pub fn get_project_variables(
+    domain: String,
+    path: String,
+    config: Arc<Config>,
+) -> Result<Arc<dyn ProjectVariables + Send + Sync + 'static>> {
+    let runner = Arc::new(
+        http::Client::new(NoCache, config.clone(), true)
+            .with_audit_log(FileAuditLog::new(&config))
+            .with_api_stats(stats::global())
+            .with_ratelimit_store(FileRateLimitStore::new(&config, &domain)),
+    );
+    let remote: Arc<dyn ProjectVariables + Send + Sync + 'static> = if domain.starts_with("github")
+    {
+        Arc::new(Github::new(config, &domain, &path, runner))
+    } else if domain.starts_with("gitlab") {
+        Arc::new(Gitlab::new(config, &domain, &path, runner))
+    } else {
+        return Err(error::gen(format!("Unsupported domain: {}", &domain)));
+    };
+    Ok(remote)
+}
 get!(get_deploy, Deploy);
+get!(get_issue, Issue);
 get!(get_auth_user, UserInfo);
 get!(get_cicd_runner, CicdRunner);
-get!(get_comment_mr, CommentMergeRequest);
+get!(get_cicd_artifact, CicdArtifact);
+get!(get_cicd_job, CicdJob);
+get!(get_cicd_linter, CicdLinter);
+get!(get_cicd_graph, CicdGraph);
+get!(get_deployment, Deployment);
+get!(get_deployment_approval, DeploymentApproval);
+get!(get_comment_mr, CommentMergeRequest, gitea);
+get!(get_remote_status, RemoteStatus);
+get!(get_token_info, TokenInfo);
+get!(get_remote_contract, RemoteContract);
+get!(get_undo, Undo);
+#[cfg(feature = "admin")]
+get!(get_instance_admin, InstanceAdmin);
 
 #[cfg(test)]
 mod test {
@@ -605,8 +827,8 @@ mod test {
             .target_branch("target".to_string())
             .title("title".to_string())
             .description("description".to_string())
-            .assignee_id("assignee_id".to_string())
-            .username("username".to_string())
+            .assignee_ids(vec![1, 2])
+            .assignee_usernames(vec!["jdoe".to_string(), "tsmith".to_string()])
             .remove_source_branch("false".to_string())
             .build()
             .unwrap();
@@ -615,8 +837,8 @@ mod test {
         assert_eq!(args.target_branch, "target");
         assert_eq!(args.title, "title");
         assert_eq!(args.description, "description");
-        assert_eq!(args.assignee_id, "assignee_id");
-        assert_eq!(args.username, "username");
+        assert_eq!(args.assignee_ids, vec![1, 2]);
+        assert_eq!(args.assignee_usernames, vec!["jdoe", "tsmith"]);
         assert_eq!(args.remove_source_branch, "false");
     }
 
@@ -806,6 +1028,24 @@ mod test {
         assert_eq!(args.sort_mode, ListSortMode::Desc);
     }
 
+    #[test]
+    fn test_includes_per_page_in_list_body_args() {
+        let args = ListRemoteCliArgs::builder()
+            .from_page(Some(1))
+            .to_page(Some(3))
+            .per_page(Some(100))
+            .build()
+            .unwrap();
+        let args = validate_from_to_page(&args).unwrap().unwrap();
+        assert_eq!(Some(100), args.per_page);
+        let args = ListRemoteCliArgs::builder()
+            .per_page(Some(50))
+            .build()
+            .unwrap();
+        let args = validate_from_to_page(&args).unwrap().unwrap();
+        assert_eq!(Some(50), args.per_page);
+    }
+
     #[test]
     fn test_add_created_after_with_page_number() {
         let page_number = Some(1);
@@ -968,4 +1208,10 @@ mod test {
             .build();
         assert_eq!(url, "https://example.com?key=value&key2=value2");
     }
+
+    #[test]
+    fn test_encode_query_param() {
+        assert_eq!("gitar", encode_query_param("gitar"));
+        assert_eq!("my%20repo%26more", encode_query_param("my repo&more"));
+    }
 }
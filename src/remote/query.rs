@@ -6,44 +6,85 @@ use serde::Serialize;
 use crate::{
     api_traits::ApiOperation,
     cmds::{
-        cicd::{Pipeline, Runner, RunnerMetadata},
+        auth::TokenMetadata,
+        cicd::{Artifact, Job, LintResponse, Pipeline, Runner, RunnerMetadata},
         docker::{ImageMetadata, RegistryRepository, RepositoryTag},
+        environment::{Environment, PendingDeployment},
+        issue::IssueResponse,
+        merge_request::{CommentThread, MergeQueueEntry, MergeRequestCheck},
+        project::{
+            AccessRequest, ApprovalSettings, AuditedProject, Commit, ProjectVariable, PushRules,
+        },
         release::Release,
+        status::BroadcastMessage,
     },
     display, error,
+    gitea::{
+        cicd::GiteaPipelineFields,
+        merge_request::{GiteaCommentFields, GiteaMergeRequestFields},
+        project::{GiteaMemberFields, GiteaProjectFields},
+    },
     github::{
-        cicd::GithubPipelineFields,
-        merge_request::GithubMergeRequestFields,
-        project::{GithubMemberFields, GithubProjectFields},
+        cicd::{GithubArtifactFields, GithubJobFields, GithubPipelineFields},
+        environment::{GithubEnvironmentFields, GithubPendingDeploymentFields},
+        issue::GithubIssueFields,
+        merge_request::{
+            GithubCheckRunFields, GithubCommitStatusFields, GithubIssueCommentFields,
+            GithubMergeRequestFields, GithubReviewCommentFields,
+        },
+        project::{
+            GithubApprovalSettingsFields, GithubAuditedProjectFields, GithubCollaboratorFields,
+            GithubCommitFields, GithubMemberFields, GithubProjectFields, GithubSecretFields,
+        },
         release::GithubReleaseFields,
         user::GithubUserFields,
     },
     gitlab::{
-        cicd::{GitlabPipelineFields, GitlabRunnerFields, GitlabRunnerMetadataFields},
+        auth::GitlabTokenFields,
+        cicd::{
+            GitlabJobArtifactFields, GitlabJobFields, GitlabLintFields, GitlabPipelineFields,
+            GitlabRunnerFields, GitlabRunnerMetadataFields,
+        },
         container_registry::{
             GitlabImageMetadataFields, GitlabRegistryRepositoryFields, GitlabRepositoryTagFields,
         },
-        merge_request::GitlabMergeRequestFields,
-        project::{GitlabMemberFields, GitlabProjectFields},
+        environment::{GitlabEnvironmentFields, GitlabPendingDeploymentFields},
+        issue::GitlabIssueFields,
+        merge_request::{
+            GitlabCheckJobFields, GitlabCommitStatusFields, GitlabDiscussionFields,
+            GitlabMergeRequestFields, GitlabMergeTrainFields,
+        },
+        project::{
+            GitlabAccessRequestFields, GitlabApprovalSettingsFields, GitlabAuditedProjectFields,
+            GitlabCommitFields, GitlabMemberFields, GitlabProjectFields, GitlabPushRulesFields,
+            GitlabVariableFields,
+        },
         release::GitlabReleaseFields,
+        status::GitlabBroadcastMessageFields,
         user::GitlabUserFields,
     },
     http::{self, Body, Headers, Paginator, Request, Resource},
-    io::{HttpRunner, Response},
+    io::{HttpRunner, PageCount, Response, Upload},
     json_load_page, json_loads,
     remote::ListBodyArgs,
     time::sort_filter_by_date,
     Result,
 };
 
-use super::{Member, MergeRequestResponse, Project};
+#[cfg(feature = "admin")]
+use crate::{
+    cmds::admin::{InstanceProject, InstanceUser},
+    gitlab::admin::{GitlabInstanceProjectFields, GitlabInstanceUserFields},
+};
+
+use super::{Member, MergeRequestResponse, Project, URLQueryParamBuilder};
 
 pub fn num_pages<R: HttpRunner<Response = Response>>(
     runner: &Arc<R>,
     url: &str,
     request_headers: Headers,
     api_operation: ApiOperation,
-) -> Result<Option<u32>> {
+) -> Result<Option<PageCount>> {
     let mut request: Request<()> = http::Request::builder()
         .method(http::Method::HEAD)
         .resource(Resource::new(url, Some(api_operation)))
@@ -51,21 +92,32 @@ pub fn num_pages<R: HttpRunner<Response = Response>>(
         .build()
         .unwrap();
     let response = runner.run(&mut request)?;
+    let total = response.total();
+    // Exact count, unlike the last link that some remotes leave out.
+    if let Some(pages) = response.total_pages() {
+        return Ok(Some(PageCount { pages, total }));
+    }
     let page_header = response.get_page_headers();
     match page_header {
         Some(page_header) => {
             if let Some(last_page) = page_header.last {
-                return Ok(Some(last_page.number));
+                return Ok(Some(PageCount {
+                    pages: last_page.number,
+                    total,
+                }));
             }
             Ok(None)
         }
         // Github does not return page headers when there is only one page, so
         // we assume 1 page in this case.
-        None => Ok(Some(1)),
+        None => Ok(Some(PageCount { pages: 1, total })),
     }
 }
 
 fn query_error(url: &str, response: &Response) -> error::GRError {
+    if let Some(message) = response.maintenance_message() {
+        return error::GRError::RemoteUnderMaintenance(message);
+    }
     error::GRError::RemoteServerError(format!(
         "Failed to submit request to URL: {} with status code: {} and body: {}",
         url, response.status, response.body
@@ -145,6 +197,28 @@ fn send_request<R: HttpRunner<Response = Response>, T: Serialize>(
     Ok(response)
 }
 
+/// Sends a file as the body of the request.
+pub fn upload<R: HttpRunner<Response = Response>>(
+    runner: &Arc<R>,
+    url: &str,
+    request_headers: Headers,
+    method: http::Method,
+    upload: Upload,
+    operation: ApiOperation,
+) -> Result<Response> {
+    let mut request: Request<()> = http::Request::builder()
+        .method(method.clone())
+        .resource(Resource::new(url, Some(operation)))
+        .headers(request_headers)
+        .build()
+        .unwrap();
+    let response = runner.upload(&mut request, upload)?;
+    if !response.is_ok(&method) {
+        return Err(query_error(url, &response).into());
+    }
+    Ok(response)
+}
+
 macro_rules! paged {
     ($func_name:ident, $map_type:ident, $return_type:ident) => {
         pub fn $func_name<R: HttpRunner<Response = Response>>(
@@ -155,16 +229,22 @@ macro_rules! paged {
             iter_over_sub_array: Option<&str>,
             operation: ApiOperation,
         ) -> Result<Vec<$return_type>> {
-            let request = build_list_request(url, &list_args, request_headers, operation);
+            let per_page = list_args
+                .as_ref()
+                .and_then(|list_args| list_args.per_page)
+                .or_else(|| runner.api_per_page());
+            let request = build_list_request(url, &list_args, per_page, request_headers, operation);
             let mut throttle_time = None;
             let mut backoff_max_retries = 0;
             let mut backoff_wait_time = 60;
+            let mut flush = false;
             if let Some(list_args) = &list_args {
                 throttle_time = list_args.throttle_time;
                 backoff_max_retries = list_args.get_args.backoff_max_retries;
                 backoff_wait_time = list_args.get_args.backoff_retry_after;
+                flush = list_args.flush;
             }
-            let paginator = Paginator::new(
+            let mut paginator = Paginator::new(
                 &runner,
                 request,
                 url,
@@ -172,6 +252,11 @@ macro_rules! paged {
                 backoff_max_retries,
                 backoff_wait_time,
             );
+            // Throttling, retrying with backoff and flushing as pages arrive
+            // all expect one request after the other.
+            if throttle_time.is_none() && backoff_max_retries == 0 && !flush {
+                paginator = paginator.with_concurrent_pages();
+            }
             let all_data = paginator
                 .map(|response| {
                     let response = response?;
@@ -240,31 +325,62 @@ macro_rules! paged {
 fn build_list_request(
     url: &str,
     list_args: &Option<ListBodyArgs>,
+    per_page: Option<u32>,
     request_headers: Headers,
     operation: ApiOperation,
 ) -> Request<()> {
     let mut request: http::Request<()> =
         http::Request::new(url, http::Method::GET).with_api_operation(operation);
     request.set_headers(request_headers);
+    let mut url = URLQueryParamBuilder::new(url);
     if let Some(list_args) = list_args {
         if let Some(from_page) = list_args.page {
-            let url = if url.contains('?') {
-                format!("{}&page={}", url, &from_page)
-            } else {
-                format!("{}?page={}", url, &from_page)
-            };
+            url.add_param("page", &from_page.to_string());
             request.set_max_pages(list_args.max_pages.unwrap());
-            request.set_url(&url);
         }
     }
+    // Next page links returned by both remotes keep the per_page parameter,
+    // so setting it in the first request is enough.
+    if let Some(per_page) = per_page {
+        url.add_param("per_page", &per_page.to_string());
+    }
+    request.set_url(&url.build());
     request
 }
 
+/// Switches a Gitlab list URL to keyset pagination, which walks large result
+/// sets by id instead of by page offset. Keyset pages can only be followed
+/// forward, so when a page range is requested offset pagination is kept. Only
+/// use it on endpoints that support keyset ordered by id.
+pub fn gitlab_keyset_url(url: &str, list_args: &Option<ListBodyArgs>) -> String {
+    if list_args.as_ref().and_then(|args| args.page).is_some() {
+        return url.to_string();
+    }
+    URLQueryParamBuilder::new(url)
+        .add_param("pagination", "keyset")
+        .add_param("order_by", "id")
+        .build()
+}
+
 // Paged HTTP requests
 
 paged!(github_list_members, GithubMemberFields, Member);
 paged!(gitlab_list_members, GitlabMemberFields, Member);
+paged!(github_list_collaborators, GithubCollaboratorFields, Member);
 paged!(github_list_pipelines, GithubPipelineFields, Pipeline);
+paged!(gitea_list_pipelines, GiteaPipelineFields, Pipeline);
+paged!(gitea_list_members, GiteaMemberFields, Member);
+paged!(gitea_list_projects, GiteaProjectFields, Project);
+paged!(
+    gitea_list_merge_requests,
+    GiteaMergeRequestFields,
+    MergeRequestResponse
+);
+paged!(
+    gitea_list_merge_request_comments,
+    GiteaCommentFields,
+    CommentThread
+);
 paged!(gitlab_list_pipelines, GitlabPipelineFields, Pipeline);
 paged!(
     github_list_merge_requests,
@@ -276,6 +392,47 @@ paged!(
     GitlabMergeRequestFields,
     MergeRequestResponse
 );
+paged!(
+    gitlab_list_merge_train,
+    GitlabMergeTrainFields,
+    MergeQueueEntry
+);
+
+paged!(
+    gitlab_list_check_jobs,
+    GitlabCheckJobFields,
+    MergeRequestCheck
+);
+paged!(
+    gitlab_list_merge_request_discussions,
+    GitlabDiscussionFields,
+    CommentThread
+);
+paged!(
+    github_list_merge_request_issue_comments,
+    GithubIssueCommentFields,
+    CommentThread
+);
+paged!(
+    github_list_merge_request_review_comments,
+    GithubReviewCommentFields,
+    CommentThread
+);
+paged!(
+    gitlab_list_commit_statuses,
+    GitlabCommitStatusFields,
+    MergeRequestCheck
+);
+paged!(
+    github_list_check_runs,
+    GithubCheckRunFields,
+    MergeRequestCheck
+);
+paged!(
+    github_list_commit_statuses,
+    GithubCommitStatusFields,
+    MergeRequestCheck
+);
 
 paged!(
     gitlab_project_registry_repositories,
@@ -290,17 +447,79 @@ paged!(
 );
 
 paged!(github_releases, GithubReleaseFields, Release);
+send!(github_create_release, Response);
+send!(gitlab_create_release, Response);
+send!(gitlab_create_release_link, Response);
 paged!(gitlab_releases, GitlabReleaseFields, Release);
 
+paged!(gitlab_list_issues, GitlabIssueFields, IssueResponse);
+paged!(github_list_issues, GithubIssueFields, IssueResponse);
+
 paged!(gitlab_list_project_runners, GitlabRunnerFields, Runner);
 
+paged!(gitlab_list_job_artifacts, GitlabJobArtifactFields, Artifact);
+paged!(github_list_artifacts, GithubArtifactFields, Artifact);
+
+paged!(
+    gitlab_list_environments,
+    GitlabEnvironmentFields,
+    Environment
+);
+paged!(
+    github_list_environments,
+    GithubEnvironmentFields,
+    Environment
+);
+paged!(
+    gitlab_list_pending_deployments,
+    GitlabPendingDeploymentFields,
+    PendingDeployment
+);
+paged!(
+    github_list_waiting_runs,
+    GithubPendingDeploymentFields,
+    PendingDeployment
+);
+
+paged!(
+    gitlab_list_access_requests,
+    GitlabAccessRequestFields,
+    AccessRequest
+);
+
+paged!(
+    gitlab_list_broadcast_messages,
+    GitlabBroadcastMessageFields,
+    BroadcastMessage
+);
+
 paged!(gitlab_list_projects, GitlabProjectFields, Project);
 paged!(github_list_projects, GithubProjectFields, Project);
+paged!(
+    gitlab_list_group_projects,
+    GitlabAuditedProjectFields,
+    AuditedProject
+);
+paged!(
+    github_list_org_projects,
+    GithubAuditedProjectFields,
+    AuditedProject
+);
 
 // Single HTTP requests
 
 send!(gitlab_project_data, GitlabProjectFields, Project);
 send!(github_project_data, GithubProjectFields, Project);
+send!(
+    gitlab_project_approvals,
+    GitlabApprovalSettingsFields,
+    ApprovalSettings
+);
+send!(
+    github_project_approvals,
+    GithubApprovalSettingsFields,
+    ApprovalSettings
+);
 send!(
     github_merge_request,
     GithubMergeRequestFields,
@@ -316,6 +535,14 @@ send!(
 );
 
 send!(gitlab_merge_request_response, Response);
+send!(gitlab_merge_request_json, serde_json::Value);
+send!(
+    gitlab_merge_train_car,
+    GitlabMergeTrainFields,
+    MergeQueueEntry
+);
+send!(github_graphql, serde_json::Value);
+send!(gitlab_graphql, serde_json::Value);
 send!(
     gitlab_registry_image_tag_metadata,
     GitlabImageMetadataFields,
@@ -332,6 +559,108 @@ send!(
 );
 
 send!(create_merge_request_comment, Response);
+send!(create_issue_comment, Response);
+
+send!(gitlab_issue, GitlabIssueFields, IssueResponse);
+send!(github_issue, GithubIssueFields, IssueResponse);
+
+send!(delete_artifact, Response);
+
+send!(gitlab_retry_job, GitlabJobFields, Job);
+paged!(gitlab_list_pipeline_jobs, GitlabJobFields, Job);
+paged!(github_list_pipeline_jobs, GithubJobFields, Job);
+send!(github_get_job, GithubJobFields, Job);
+send!(github_rerun_job, Response);
+
+send!(gitlab_pipeline, GitlabPipelineFields, Pipeline);
+send!(github_pipeline, GithubPipelineFields, Pipeline);
+send!(github_pipeline_action, Response);
+send!(gitea_pipeline, GiteaPipelineFields, Pipeline);
+send!(gitea_project_data, GiteaProjectFields, Project);
+send!(
+    gitea_merge_request,
+    GiteaMergeRequestFields,
+    MergeRequestResponse
+);
+send!(gitea_merge_request_response, Response);
+
+send!(gitlab_lint_ci, GitlabLintFields, LintResponse);
+
+send!(gitlab_environment, GitlabEnvironmentFields, Environment);
+send!(gitlab_environments, serde_json::Value);
+send!(github_environment, GithubEnvironmentFields, Environment);
+send!(github_deployments, serde_json::Value);
+send!(github_pending_deployments, serde_json::Value);
+send!(approve_deployment, Response);
+
+send!(gitlab_access_request_response, Response);
+
+send!(gitlab_milestones, serde_json::Value);
+send!(github_milestones, serde_json::Value);
+
+send!(gitlab_protected_branches, serde_json::Value);
+send!(gitlab_approval_rules, serde_json::Value);
+send!(gitlab_approval_rule_response, Response);
+send!(github_branches, serde_json::Value);
+send!(github_branch_protection, serde_json::Value);
+
+paged!(
+    gitlab_list_project_variables,
+    GitlabVariableFields,
+    ProjectVariable
+);
+send!(gitlab_project_variable_response, Response);
+paged!(
+    github_list_actions_secrets,
+    GithubSecretFields,
+    ProjectVariable
+);
+send!(github_actions_secrets, serde_json::Value);
+send!(github_actions_secret_response, Response);
+
+send!(gitlab_project_file, Response);
+send!(github_project_file, Response);
+
+send!(github_rulesets, serde_json::Value);
+send!(gitlab_push_rule, serde_json::Value);
+send!(gitlab_push_rules, GitlabPushRulesFields, PushRules);
+paged!(gitlab_list_commits, GitlabCommitFields, Commit);
+send!(gitlab_commit, GitlabCommitFields, Commit);
+paged!(github_list_commits, GithubCommitFields, Commit);
+send!(github_commit, GithubCommitFields, Commit);
+send!(github_git_data, Response);
+
+#[cfg(feature = "admin")]
+paged!(
+    gitlab_list_instance_users,
+    GitlabInstanceUserFields,
+    InstanceUser
+);
+#[cfg(feature = "admin")]
+paged!(
+    gitlab_list_instance_projects,
+    GitlabInstanceProjectFields,
+    InstanceProject
+);
+#[cfg(feature = "admin")]
+send!(gitlab_block_user, Response);
+
+send!(gitlab_update_project, Response);
+send!(github_update_project, Response);
+
+send!(gitlab_new_project, Response);
+send!(gitlab_project_json, serde_json::Value);
+send!(gitlab_branch, serde_json::Value);
+send!(github_branch, serde_json::Value);
+send!(github_new_project, Response);
+
+send!(gitlab_version, Response);
+send!(github_meta, Response);
+
+send!(gitlab_token_info, GitlabTokenFields, TokenMetadata);
+send!(github_token_response, Response);
+
+send!(fetch_contract, serde_json::Value);
 
 #[cfg(test)]
 mod test {
@@ -347,6 +676,80 @@ mod test {
         let headers = Headers::new();
         let operation = ApiOperation::Pipeline;
         let num_pages = num_pages(&client, url, headers, operation).unwrap();
-        assert_eq!(Some(1), num_pages);
+        assert_eq!(Some(PageCount::from(1)), num_pages);
+    }
+
+    #[test]
+    fn test_numpages_from_total_headers() {
+        let mut headers = Headers::new();
+        headers.set("x-total".to_string(), "1234".to_string());
+        headers.set("x-total-pages".to_string(), "62".to_string());
+        let response = Response::builder()
+            .status(200)
+            .headers(headers)
+            .build()
+            .unwrap();
+        let client = Arc::new(MockRunner::new(vec![response]));
+        let url = "https://gitlab.com/api/v4/projects/1/pipelines";
+        let num_pages = num_pages(&client, url, Headers::new(), ApiOperation::Pipeline).unwrap();
+        assert_eq!(
+            Some(PageCount {
+                pages: 62,
+                total: Some(1234)
+            }),
+            num_pages
+        );
+    }
+
+    #[test]
+    fn test_list_request_appends_page_and_per_page() {
+        let list_args = ListBodyArgs::builder()
+            .page(2)
+            .max_pages(1)
+            .build()
+            .unwrap();
+        let request = build_list_request(
+            "https://gitlab.com/api/v4/projects/1/pipelines?ref=main",
+            &Some(list_args),
+            Some(100),
+            Headers::new(),
+            ApiOperation::Pipeline,
+        );
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/1/pipelines?ref=main&page=2&per_page=100",
+            request.url()
+        );
+    }
+
+    #[test]
+    fn test_list_request_without_per_page_keeps_url() {
+        let request = build_list_request(
+            "https://api.github.com/repos/org/repo/pulls",
+            &None,
+            None,
+            Headers::new(),
+            ApiOperation::MergeRequest,
+        );
+        assert_eq!("https://api.github.com/repos/org/repo/pulls", request.url());
+    }
+
+    #[test]
+    fn test_gitlab_keyset_url_when_fetching_from_start() {
+        let url = "https://gitlab.com/api/v4/projects/1/jobs";
+        assert_eq!(
+            "https://gitlab.com/api/v4/projects/1/jobs?pagination=keyset&order_by=id",
+            gitlab_keyset_url(url, &None)
+        );
+    }
+
+    #[test]
+    fn test_gitlab_keyset_url_keeps_offset_for_page_ranges() {
+        let url = "https://gitlab.com/api/v4/projects/1/jobs";
+        let list_args = ListBodyArgs::builder()
+            .page(3)
+            .max_pages(2)
+            .build()
+            .unwrap();
+        assert_eq!(url, gitlab_keyset_url(url, &Some(list_args)));
     }
 }
@@ -3,7 +3,8 @@ use crate::io::Response;
 use crate::io::TaskRunner;
 use crate::Result;
 use std::ffi::OsStr;
-use std::process;
+use std::io::Write;
+use std::process::{self, Stdio};
 use std::str;
 
 pub struct Shell;
@@ -51,3 +52,80 @@ where
         Err(val) => Err(error::gen(val.to_string())),
     }
 }
+
+/// Runs `args` with `input` written to its STDIN. Used to hand content to
+/// commands without going through a temporary file.
+pub fn run_with_input(args: &[&str], input: &str) -> Result<Response> {
+    let mut child = process::Command::new(args[0])
+        .args(&args[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(error::gen(str::from_utf8(&output.stderr)?));
+    }
+    Ok(Response::builder()
+        .status(output.status.code().unwrap_or(0))
+        .body(str::from_utf8(&output.stdout)?.to_string())
+        .build()?)
+}
+
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Shows `content` through the user's pager, taken from `PAGER`. Prints it to
+/// STDOUT instead if the pager cannot be started.
+pub fn page(content: &str) -> Result<()> {
+    let (program, args) = pager_command(std::env::var("PAGER").ok());
+    match process::Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                // The pager can be quit before it reads everything.
+                let _ = stdin.write_all(content.as_bytes());
+            }
+            child.wait()?;
+            Ok(())
+        }
+        Err(_) => {
+            std::io::stdout().write_all(content.as_bytes())?;
+            Ok(())
+        }
+    }
+}
+
+fn pager_command(pager: Option<String>) -> (String, Vec<String>) {
+    let pager = pager
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let mut args = pager.split_whitespace().map(String::from);
+    let program = args.next().unwrap();
+    (program, args.collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pager_command_from_env() {
+        assert_eq!(
+            ("bat".to_string(), vec!["--paging=always".to_string()]),
+            pager_command(Some("bat --paging=always".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_pager_command_defaults_to_less() {
+        let less = ("less".to_string(), vec!["-FRX".to_string()]);
+        assert_eq!(less, pager_command(None));
+        assert_eq!(less, pager_command(Some(" ".to_string())));
+    }
+}
@@ -0,0 +1,169 @@
+//! Usage of the remote APIs during a command run. The HTTP client records
+//! every request it sends and every response served from the cache, grouped
+//! by [`ApiOperation`], so the totals can be reported once the command is
+//! done with `--api-stats` or `--verbose`.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::api_traits::ApiOperation;
+use crate::Result;
+
+lazy_static! {
+    static ref API_STATS: Arc<ApiStats> = Arc::new(ApiStats::default());
+}
+
+/// Stats shared by all the HTTP clients created during the run.
+pub fn global() -> Arc<ApiStats> {
+    API_STATS.clone()
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OperationStats {
+    /// HTTP calls sent to the remote.
+    pub requests: u32,
+    /// Responses served from the cache, either fresh or revalidated with a
+    /// 304 from the remote.
+    pub cache_hits: u32,
+    /// Requests answered with an error status or not answered at all.
+    pub errors: u32,
+    /// Request and response bodies.
+    pub bytes: u64,
+    /// Time spent waiting for the remote.
+    pub elapsed: Duration,
+}
+
+impl OperationStats {
+    fn add(&mut self, other: &OperationStats) {
+        self.requests += other.requests;
+        self.cache_hits += other.cache_hits;
+        self.errors += other.errors;
+        self.bytes += other.bytes;
+        self.elapsed += other.elapsed;
+    }
+}
+
+#[derive(Default)]
+pub struct ApiStats {
+    operations: Mutex<BTreeMap<String, OperationStats>>,
+}
+
+impl ApiStats {
+    pub fn record_request(&self, operation: Option<&ApiOperation>, bytes: u64, elapsed: Duration) {
+        self.update(operation, |stats| {
+            stats.requests += 1;
+            stats.bytes += bytes;
+            stats.elapsed += elapsed;
+        });
+    }
+
+    pub fn record_cache_hit(&self, operation: Option<&ApiOperation>) {
+        self.update(operation, |stats| stats.cache_hits += 1);
+    }
+
+    pub fn record_error(&self, operation: Option<&ApiOperation>) {
+        self.update(operation, |stats| stats.errors += 1);
+    }
+
+    fn update(&self, operation: Option<&ApiOperation>, f: impl FnOnce(&mut OperationStats)) {
+        // Requests without an operation are mostly the ones that change data
+        // in the remote.
+        let name = operation.map_or_else(|| "other".to_string(), |op| op.to_string());
+        if let Ok(mut operations) = self.operations.lock() {
+            f(operations.entry(name).or_default());
+        }
+    }
+
+    /// Stats per operation, sorted by operation name.
+    pub fn operations(&self) -> Vec<(String, OperationStats)> {
+        self.operations
+            .lock()
+            .map(|operations| {
+                operations
+                    .iter()
+                    .map(|(name, stats)| (name.clone(), stats.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn report<W: Write>(&self, mut writer: W) -> Result<()> {
+        let operations = self.operations();
+        if operations.is_empty() {
+            writeln!(writer, "API usage: no requests")?;
+            return Ok(());
+        }
+        let mut total = OperationStats::default();
+        writeln!(writer, "API usage:")?;
+        writeln!(writer, "Operation|Requests|Cache hits|Bytes|Time (ms)")?;
+        for (name, stats) in &operations {
+            write_row(&mut writer, name, stats)?;
+            total.add(stats);
+        }
+        write_row(&mut writer, "total", &total)?;
+        Ok(())
+    }
+}
+
+fn write_row<W: Write>(writer: &mut W, name: &str, stats: &OperationStats) -> Result<()> {
+    writeln!(
+        writer,
+        "{}|{}|{}|{}|{}",
+        name,
+        stats.requests,
+        stats.cache_hits,
+        stats.bytes,
+        stats.elapsed.as_millis()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_report_groups_by_operation() {
+        let stats = ApiStats::default();
+        stats.record_request(
+            Some(&ApiOperation::Pipeline),
+            1024,
+            Duration::from_millis(120),
+        );
+        stats.record_request(
+            Some(&ApiOperation::MergeRequest),
+            300,
+            Duration::from_millis(80),
+        );
+        stats.record_cache_hit(Some(&ApiOperation::MergeRequest));
+        stats.record_request(None, 50, Duration::from_millis(200));
+        stats.record_request(
+            Some(&ApiOperation::Pipeline),
+            2048,
+            Duration::from_millis(100),
+        );
+        let mut writer = Vec::new();
+        stats.report(&mut writer).unwrap();
+        assert_eq!(
+            "API usage:\n\
+             Operation|Requests|Cache hits|Bytes|Time (ms)\n\
+             merge_request|1|1|300|80\n\
+             other|1|0|50|200\n\
+             pipeline|2|0|3072|220\n\
+             total|4|1|3422|500\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_report_without_requests() {
+        let mut writer = Vec::new();
+        ApiStats::default().report(&mut writer).unwrap();
+        assert_eq!(
+            "API usage: no requests\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+}
@@ -1,4 +1,7 @@
-#[cfg(test)]
+/// Test doubles and helpers shared by the unit tests. Crates embedding gitar as
+/// a library can enable the `testing` feature to unit-test their integration
+/// against the same mocked runners and response contracts.
+#[cfg(any(test, feature = "testing"))]
 pub mod utils {
     use crate::{
         api_defaults::REST_API_MAX_PAGES,
@@ -6,7 +9,7 @@ pub mod utils {
         config::ConfigProperties,
         error,
         http::{self, Headers, Request},
-        io::{HttpRunner, Response, TaskRunner},
+        io::{HttpRunner, Response, TaskRunner, Upload},
         time::Milliseconds,
         Result,
     };
@@ -37,8 +40,16 @@ pub mod utils {
         }
     }
 
+    /// Loads a JSON response recorded from the remote. Contracts are resolved
+    /// from the gitar crate root, so they are found when used from other
+    /// crates too.
     pub fn get_contract(contract_type: ContractType, filename: &str) -> String {
-        let contracts_path = format!("contracts/{}/{}", contract_type.as_str(), filename);
+        let contracts_path = format!(
+            "{}/contracts/{}/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            contract_type.as_str(),
+            filename
+        );
         let mut file = File::open(contracts_path).unwrap();
         let mut contents = String::new();
         file.read_to_string(&mut contents).unwrap();
@@ -50,6 +61,7 @@ pub mod utils {
         cmd: RefCell<String>,
         headers: RefCell<Headers>,
         url: RefCell<String>,
+        request_body: RefCell<String>,
         pub api_operation: RefCell<Option<ApiOperation>>,
         pub config: ConfigMock,
         pub http_method: RefCell<http::Method>,
@@ -64,6 +76,7 @@ pub mod utils {
                 cmd: RefCell::new(String::new()),
                 headers: RefCell::new(Headers::new()),
                 url: RefCell::new(String::new()),
+                request_body: RefCell::new(String::new()),
                 api_operation: RefCell::new(None),
                 config: ConfigMock::default(),
                 http_method: RefCell::new(http::Method::GET),
@@ -76,23 +89,28 @@ pub mod utils {
             Self { config, ..self }
         }
 
-        pub fn cmd(&self) -> Ref<String> {
+        pub fn cmd(&self) -> Ref<'_, String> {
             self.cmd.borrow()
         }
 
-        pub fn url(&self) -> Ref<String> {
+        pub fn url(&self) -> Ref<'_, String> {
             self.url.borrow()
         }
 
-        pub fn headers(&self) -> Ref<Headers> {
+        /// JSON body of the last request sent.
+        pub fn request_body(&self) -> Ref<'_, String> {
+            self.request_body.borrow()
+        }
+
+        pub fn headers(&self) -> Ref<'_, Headers> {
             self.headers.borrow()
         }
 
-        pub fn throttled(&self) -> Ref<u32> {
+        pub fn throttled(&self) -> Ref<'_, u32> {
             self.throttled.borrow()
         }
 
-        pub fn milliseconds_throttled(&self) -> Ref<Milliseconds> {
+        pub fn milliseconds_throttled(&self) -> Ref<'_, Milliseconds> {
             self.milliseconds_throttled.borrow()
         }
     }
@@ -113,8 +131,8 @@ pub mod utils {
             );
             let response = self.responses.borrow_mut().pop().unwrap();
             match response.status {
-                0 => return Ok(response),
-                _ => return Err(error::gen(&response.body)),
+                0 => Ok(response),
+                _ => Err(error::gen(&response.body)),
             }
         }
     }
@@ -125,27 +143,30 @@ pub mod utils {
         fn run<T: Serialize>(&self, cmd: &mut Request<T>) -> Result<Self::Response> {
             self.url.replace(cmd.url().to_string());
             self.headers.replace(cmd.headers().clone());
+            self.request_body
+                .replace(serde_json::to_string(cmd.body()).unwrap());
             self.api_operation.replace(cmd.api_operation().clone());
             let response = self.responses.borrow_mut().pop().unwrap();
             self.http_method.replace(cmd.method.clone());
             match response.status {
                 // 409 Conflict - Merge request already exists. - Gitlab
                 // 422 Conflict - Merge request already exists. - Github
-                200 | 201 | 302 | 409 | 422 => return Ok(response),
+                // 503 Service unavailable - Gitlab in maintenance mode.
+                // 202 Accepted - Github cancelling a workflow run.
+                200 | 201 | 202 | 204 | 302 | 409 | 422 | 503 => Ok(response),
                 // RateLimit error code. 403 secondary rate limit, 429 primary
                 // rate limit.
                 403 | 429 => {
                     let headers = response.get_ratelimit_headers().unwrap_or_default();
-                    return Err(error::GRError::RateLimitExceeded(headers).into());
+                    Err(error::GRError::RateLimitExceeded(headers).into())
                 }
-                _ => return Err(error::gen(&response.body)),
+                _ => Err(error::gen(&response.body)),
             }
         }
 
         fn api_max_pages<T: Serialize>(&self, _cmd: &Request<T>) -> u32 {
             self.config.get_max_pages(
-                &self
-                    .api_operation
+                self.api_operation
                     .borrow()
                     .as_ref()
                     // We set it to Project by default in cases where it does
@@ -160,6 +181,18 @@ pub mod utils {
             let mut milliseconds_throttled = self.milliseconds_throttled.borrow_mut();
             *milliseconds_throttled += milliseconds;
         }
+
+        fn upload(&self, cmd: &mut Request<()>, upload: Upload) -> Result<Self::Response> {
+            // The file contents are recorded as the request body.
+            let contents = std::fs::read(upload.path)?;
+            let size = contents.len() as u64;
+            (upload.progress)(size, size);
+            cmd.set_header("Content-Type", upload.content_type);
+            let response = HttpRunner::run(self, cmd)?;
+            self.request_body
+                .replace(String::from_utf8_lossy(&contents).to_string());
+            Ok(response)
+        }
     }
 
     pub struct ConfigMock {
@@ -62,6 +62,13 @@ pub fn epoch_to_minutes_relative(epoch_seconds: Seconds) -> String {
     minutes.to_string()
 }
 
+/// Formats epoch seconds as an RFC 3339 date in local time, so it can be used
+/// as a created_after/created_before filter.
+pub fn epoch_to_rfc3339(epoch_seconds: Seconds) -> String {
+    let date = DateTime::from_timestamp(*epoch_seconds as i64, 0).unwrap_or_default();
+    date.with_timezone(&Local).to_rfc3339()
+}
+
 pub fn epoch_to_seconds_relative(epoch_seconds: Seconds) -> String {
     let now = now_epoch_seconds();
     let diff = now - epoch_seconds;
@@ -317,15 +324,19 @@ fn sort_by_date<T: Timestamp>(
         (None, None) => data
             .into_iter()
             .map(|item| {
-                let item_date = item.created_at().parse::<DateTime<Local>>().unwrap();
+                // Items without a date, ex. Github collaborators, go first.
+                let item_date = item
+                    .created_at()
+                    .parse::<DateTime<Local>>()
+                    .unwrap_or_default();
                 (item, item_date)
             })
             .collect::<Vec<(T, DateTime<Local>)>>(),
     };
     if let Some(sort_mode) = sort_mode {
         match sort_mode {
-            ListSortMode::Asc => data_dates.sort_by(|a, b| a.1.cmp(&b.1)),
-            ListSortMode::Desc => data_dates.sort_by(|a, b| b.1.cmp(&a.1)),
+            ListSortMode::Asc => data_dates.sort_by_key(|a| a.1),
+            ListSortMode::Desc => data_dates.sort_by_key(|a| std::cmp::Reverse(a.1)),
         }
     }
     data_dates.into_iter().map(|(item, _)| item).collect()
@@ -1,10 +1,17 @@
+use std::sync::{Arc, Mutex};
+
+use gr::api_traits::ApiOperation;
+use gr::audit::{AuditEntry, AuditLog};
 use gr::cache::{Cache, InMemoryCache, NoCache};
 use gr::config::ConfigProperties;
 use gr::error::GRError;
-use gr::http::{Client, Headers, Method, Request};
-use gr::io::{HttpRunner, Response, ResponseField};
+use gr::http::{Body, Client, Headers, Method, Request, Resource};
+use gr::io::{HttpRunner, Response, ResponseField, Upload};
+use gr::stats::ApiStats;
+use gr::time::Seconds;
+use gr::Result;
 use httpmock::prelude::*;
-use httpmock::Method::{GET, HEAD, PATCH, POST};
+use httpmock::Method::{GET, HEAD, PATCH, POST, PUT};
 
 struct ConfigMock {}
 
@@ -21,6 +28,10 @@ impl ConfigProperties for ConfigMock {
     fn cache_location(&self) -> &str {
         ""
     }
+    // Retry right away, so requests to a server that is down fail fast.
+    fn retry_backoff_base(&self) -> Seconds {
+        Seconds::new(0)
+    }
 }
 
 #[test]
@@ -109,6 +120,46 @@ fn test_http_runner_post_request() {
     server_mock.assert();
 }
 
+#[derive(Clone, Default)]
+struct AuditLogMock {
+    entries: Arc<Mutex<Vec<AuditEntry>>>,
+}
+
+impl AuditLog for AuditLogMock {
+    fn record(&self, entry: &AuditEntry) -> Result<()> {
+        self.entries.lock().unwrap().push(entry.clone());
+        Ok(())
+    }
+    fn entries(&self) -> Result<Vec<AuditEntry>> {
+        Ok(self.entries.lock().unwrap().clone())
+    }
+}
+
+#[test]
+fn test_http_runner_records_mutating_requests_in_audit_log() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(POST).path("/repos/jordilin/mr");
+        then.status(201).body("{}");
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/repos/jordilin/mr");
+        then.status(200).body("{}");
+    });
+    let audit_log = AuditLogMock::default();
+    let runner = Client::new(NoCache, ConfigMock::new(), false).with_audit_log(audit_log.clone());
+    let mut request = Request::<()>::new(&server.url("/repos/jordilin/mr"), Method::POST);
+    runner.run(&mut request).unwrap();
+    let mut request = Request::<()>::new(&server.url("/repos/jordilin/mr"), Method::GET);
+    runner.run(&mut request).unwrap();
+
+    let entries = audit_log.entries().unwrap();
+    assert_eq!(1, entries.len());
+    assert_eq!("POST", entries[0].method);
+    assert_eq!(server.url("/repos/jordilin/mr"), entries[0].url);
+    assert_eq!("201", entries[0].result);
+}
+
 #[test]
 fn test_http_gathers_from_inmemory_fresh_cache() {
     let server = MockServer::start();
@@ -201,8 +252,39 @@ fn test_http_gathers_from_inmemory_stale_cache_server_304() {
     // request.
     server_mock.assert_hits(1);
     // 304 - cache has been updated with the new upstream headers
-    assert!(*cache.updated.borrow());
-    assert_eq!(ResponseField::Headers, *cache.updated_field.borrow(),);
+    assert!(*cache.updated.lock().unwrap());
+    assert_eq!(ResponseField::Headers, *cache.updated_field.lock().unwrap(),);
+}
+
+#[test]
+fn test_http_serves_stale_cache_without_hitting_server() {
+    let server = MockServer::start();
+    let server_mock = server.mock(|when, then| {
+        when.method(GET).path("/repos/jordilin/mr/members");
+        then.status(200).body("[]");
+    });
+    let response = Response::builder()
+        .status(200)
+        .body(r#"[{"id": 4}]"#.to_string())
+        .build()
+        .unwrap();
+    let url = format!("http://{}/repos/jordilin/mr/members", server.address());
+    let mut cache = InMemoryCache::default();
+    cache.set(&url, &response).unwrap();
+    cache.expire();
+
+    let runner = Client::new(&cache, ConfigMock::new(), false).with_stale_responses(true);
+    let mut request = Request::<()>::new(&url, Method::GET);
+    let response = runner.run(&mut request).unwrap();
+    assert!(response.body.contains("id"));
+    server_mock.assert_hits(0);
+
+    // Refreshing the cache asks the remote regardless.
+    let runner = Client::new(&cache, ConfigMock::new(), true).with_stale_responses(true);
+    let mut request = Request::<()>::new(&url, Method::GET);
+    let response = runner.run(&mut request).unwrap();
+    assert_eq!("[]", response.body);
+    server_mock.assert_hits(1);
 }
 
 #[test]
@@ -438,3 +520,253 @@ fn test_ratelimit_remaining_above_threshold_is_ok() {
     assert!(runner.run(&mut request).is_ok());
     server_mock.assert_hits(1);
 }
+
+#[test]
+fn test_run_pages_returns_responses_in_request_order() {
+    let server = MockServer::start();
+    let mocks = (1..=5)
+        .map(|page| {
+            server.mock(|when, then| {
+                when.method(GET)
+                    .path("/repos/jordilin/mr/issues")
+                    .query_param("page", page.to_string());
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .header("x-ratelimit-remaining", "100")
+                    .body(format!("[{{\"page\": {}}}]", page));
+            })
+        })
+        .collect::<Vec<_>>();
+    let runner = Client::new(NoCache, ConfigMock::new(), false);
+    let requests = (1..=5)
+        .map(|page| {
+            let url = format!(
+                "http://{}/repos/jordilin/mr/issues?page={}",
+                server.address(),
+                page
+            );
+            Request::<()>::new(&url, Method::GET)
+        })
+        .collect::<Vec<_>>();
+
+    let bodies = runner
+        .run_pages(requests, None)
+        .into_iter()
+        .map(|response| response.unwrap().body)
+        .collect::<Vec<_>>();
+
+    let expected = (1..=5)
+        .map(|page| format!("[{{\"page\": {}}}]", page))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, bodies);
+    for mock in mocks {
+        mock.assert_hits(1);
+    }
+}
+
+#[test]
+fn test_api_stats_count_requests_and_cache_hits() {
+    let server = MockServer::start();
+    let body_str = r#"{"id": 4, "default_branch": "main"}"#;
+    let server_mock = server.mock(|when, then| {
+        when.method(GET).path("/repos/jordilin/mr");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(body_str);
+    });
+    let post_mock = server.mock(|when, then| {
+        when.method(POST).path("/repos/jordilin/mr/comments");
+        then.status(201).body("{}");
+    });
+    let url = format!("http://{}/repos/jordilin/mr", server.address());
+    let cache = &InMemoryCache::default();
+    let api_stats = Arc::new(ApiStats::default());
+    let runner = Client::new(cache, ConfigMock::new(), false).with_api_stats(api_stats.clone());
+
+    for _ in 0..2 {
+        let mut request =
+            Request::<()>::new(&url, Method::GET).with_api_operation(ApiOperation::MergeRequest);
+        runner.run(&mut request).unwrap();
+    }
+    let mut body = Body::new();
+    body.add("body", "LGTM".to_string());
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .resource(Resource::new(&format!("{}/comments", url), None))
+        .body(body)
+        .build()
+        .unwrap();
+    runner.run(&mut request).unwrap();
+
+    server_mock.assert_hits(1);
+    post_mock.assert_hits(1);
+    let operations = api_stats.operations();
+    assert_eq!(2, operations.len());
+    let (name, merge_request) = &operations[0];
+    assert_eq!("merge_request", name);
+    assert_eq!(1, merge_request.requests);
+    assert_eq!(1, merge_request.cache_hits);
+    assert_eq!(body_str.len() as u64, merge_request.bytes);
+    let (name, other) = &operations[1];
+    assert_eq!("other", name);
+    assert_eq!(1, other.requests);
+    assert_eq!(0, other.cache_hits);
+    assert_eq!((r#"{"body":"LGTM"}"#.len() + 2) as u64, other.bytes);
+}
+
+#[test]
+fn test_http_runner_upload_sends_file_with_progress() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("gr.tar.gz");
+    std::fs::write(&path, vec![7u8; 20_000]).unwrap();
+    let server = MockServer::start();
+    let server_mock = server.mock(|when, then| {
+        when.method(PUT)
+            .path("/packages/generic/gr/v1.2.0/gr.tar.gz")
+            .header("content-type", "application/octet-stream")
+            .header("content-length", "20000")
+            .body(String::from_utf8(vec![7u8; 20_000]).unwrap());
+        then.status(201);
+    });
+    let runner = Client::new(NoCache, ConfigMock::new(), false);
+    let mut request = Request::<()>::new(
+        &server.url("/packages/generic/gr/v1.2.0/gr.tar.gz"),
+        Method::PUT,
+    );
+    let progress = Mutex::new(Vec::new());
+    let response = runner
+        .upload(
+            &mut request,
+            Upload {
+                path: &path,
+                content_type: "application/octet-stream",
+                progress: &|sent, size| progress.lock().unwrap().push((sent, size)),
+            },
+        )
+        .unwrap();
+    assert_eq!(201, response.status);
+    server_mock.assert();
+    let progress = progress.lock().unwrap();
+    assert_eq!(Some(&(20_000, 20_000)), progress.last());
+}
+
+struct RetryConfigMock {}
+
+impl ConfigProperties for RetryConfigMock {
+    fn api_token(&self) -> &str {
+        "1234"
+    }
+    fn cache_location(&self) -> &str {
+        ""
+    }
+    fn retry_max_retries(&self) -> u32 {
+        2
+    }
+    fn retry_backoff_base(&self) -> Seconds {
+        Seconds::new(0)
+    }
+}
+
+#[test]
+fn test_http_runner_retries_get_on_server_error() {
+    let server = MockServer::start();
+    let server_mock = server.mock(|when, then| {
+        when.method(GET).path("/repos/jordilin/mr");
+        then.status(502);
+    });
+    let runner = Client::new(NoCache, RetryConfigMock {}, false);
+    let mut request = Request::<()>::new(&server.url("/repos/jordilin/mr"), Method::GET);
+    let response = runner.run(&mut request).unwrap();
+    assert_eq!(502, response.status);
+    server_mock.assert_hits(3);
+}
+
+#[test]
+fn test_http_runner_does_not_retry_post_on_server_error() {
+    let server = MockServer::start();
+    let server_mock = server.mock(|when, then| {
+        when.method(POST).path("/repos/jordilin/mr");
+        then.status(502);
+    });
+    let runner = Client::new(NoCache, RetryConfigMock {}, false);
+    let mut request = Request::<()>::new(&server.url("/repos/jordilin/mr"), Method::POST);
+    let response = runner.run(&mut request).unwrap();
+    assert_eq!(502, response.status);
+    server_mock.assert_hits(1);
+}
+
+#[test]
+fn test_mutation_invalidates_negative_lookups_of_the_resource() {
+    let server = MockServer::start();
+    let server_mock = server.mock(|when, then| {
+        when.method(POST).path("/repos/jordilin/mr/pulls");
+        then.status(201).body(r#"{"number": 1}"#);
+    });
+    let pulls_url = server.url("/repos/jordilin/mr/pulls");
+    let repo_url = server.url("/repos/jordilin/mr");
+    let cache = &InMemoryCache::default();
+    let no_pulls = Response::builder()
+        .status(200)
+        .body("[]".to_string())
+        .build()
+        .unwrap();
+    let repo = Response::builder()
+        .status(200)
+        .body(r#"{"id": 4}"#.to_string())
+        .build()
+        .unwrap();
+    let open_pulls_url = format!("{}?state=open&page=1", pulls_url);
+    cache.set(&pulls_url, &no_pulls).unwrap();
+    cache.set(&open_pulls_url, &no_pulls).unwrap();
+    cache.set(&repo_url, &repo).unwrap();
+
+    let runner = Client::new(cache, ConfigMock::new(), false);
+    let mut request =
+        Request::<()>::new(&pulls_url, Method::POST).with_api_operation(ApiOperation::MergeRequest);
+    runner.run(&mut request).unwrap();
+
+    server_mock.assert();
+    assert!(matches!(
+        Cache::<String>::get(cache, &pulls_url).unwrap(),
+        gr::cache::CacheState::None
+    ));
+    assert!(matches!(
+        Cache::<String>::get(cache, &open_pulls_url).unwrap(),
+        gr::cache::CacheState::None
+    ));
+    assert!(matches!(
+        Cache::<String>::get(cache, &repo_url).unwrap(),
+        gr::cache::CacheState::Fresh(_)
+    ));
+}
+
+#[test]
+fn test_creating_a_resource_invalidates_its_cached_not_found() {
+    let server = MockServer::start();
+    let server_mock = server.mock(|when, then| {
+        when.method(POST).path("/api/v4/projects");
+        then.status(201).body(r#"{"id": 4}"#);
+    });
+    let projects_url = server.url("/api/v4/projects");
+    let new_project_url = server.url("/api/v4/projects/group%2Fnew");
+    let other_group_url = server.url("/api/v4/projects2/group%2Fnew");
+    let cache = &InMemoryCache::default();
+    let not_found = Response::builder().status(404).build().unwrap();
+    cache.set(&new_project_url, &not_found).unwrap();
+    cache.set(&other_group_url, &not_found).unwrap();
+
+    let runner = Client::new(cache, ConfigMock::new(), false);
+    let mut request =
+        Request::<()>::new(&projects_url, Method::POST).with_api_operation(ApiOperation::Project);
+    runner.run(&mut request).unwrap();
+
+    server_mock.assert();
+    assert!(matches!(
+        Cache::<String>::get(cache, &new_project_url).unwrap(),
+        gr::cache::CacheState::None
+    ));
+    assert!(matches!(
+        Cache::<String>::get(cache, &other_group_url).unwrap(),
+        gr::cache::CacheState::Fresh(_)
+    ));
+}
@@ -0,0 +1,25 @@
+// MockRunner records requests in RefCells and is shared through an Arc.
+#![allow(clippy::arc_with_non_send_sync)]
+
+use std::sync::Arc;
+
+use gr::api_traits::RemoteProject;
+use gr::gitlab::Gitlab;
+use gr::io::Response;
+use gr::test::utils::{config, get_contract, ContractType, MockRunner};
+
+#[test]
+fn test_mock_runner_and_contracts_available_with_testing_feature() {
+    let response = Response::builder()
+        .status(200)
+        .body(get_contract(ContractType::Gitlab, "project.json"))
+        .build()
+        .unwrap();
+    let client = Arc::new(MockRunner::new(vec![response]));
+    let gitlab = Gitlab::new(config(), "gitlab.com", "jordilin/gitlapi", client.clone());
+    gitlab.get_project_data(None).unwrap();
+    assert_eq!(
+        "https://gitlab.com/api/v4/projects/jordilin%2Fgitlapi",
+        *client.url()
+    );
+}